@@ -0,0 +1,112 @@
+use alloc::boxed::Box;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::base::Symbol;
+use super::base::Value;
+use super::context::Context;
+
+/// Policy of accesses through a context, all accesses are allowed by default
+///
+/// Values checked are the slots whose properties, prototypes, traps or
+/// internal slots are accessed, not the receivers of the accesses.
+pub trait AccessPolicy {
+
+    fn allow_property_read(&self, _subject: Value, _symbol: Symbol) -> bool {
+        true
+    }
+
+    /// Check setting, defining or deleting a property
+    fn allow_property_write(&self, _subject: Value, _symbol: Symbol) -> bool {
+        true
+    }
+
+    fn allow_prototype_mutation(&self, _subject: Value) -> bool {
+        true
+    }
+
+    /// Check setting or clearing a slot trap
+    fn allow_trap_installation(&self, _subject: Value) -> bool {
+        true
+    }
+
+    fn allow_internal_slot_access(&self, _subject: Value, _index: u64) -> bool {
+        true
+    }
+
+}
+
+/// Check access with the policy of the context, `AccessDenied` will be
+/// returned if denied
+pub fn check_access<F>(context: &Box<dyn Context>, allow: F, message: &str) -> Result<(), Error> where F: FnOnce(&dyn AccessPolicy) -> bool {
+    match context.get_access_policy() {
+        Some(access_policy) if !allow(access_policy.as_ref()) => Err(Error::new(AccessDenied, message)),
+        _ => Ok(())
+    }
+}
+
+#[cfg(test)] use alloc::sync::Arc;
+#[cfg(test)] use super::base::PrimitiveType;
+#[cfg(test)] use super::context_builder::ContextBuilder;
+#[cfg(test)] use super::isolate::Isolate;
+#[cfg(test)] use super::test::TestSlotTrap;
+
+#[cfg(test)]
+struct ReadOnlyPolicy {
+    secret: Symbol
+}
+
+#[cfg(test)]
+impl AccessPolicy for ReadOnlyPolicy {
+
+    fn allow_property_read(&self, _subject: Value, symbol: Symbol) -> bool {
+        symbol != self.secret
+    }
+
+    fn allow_property_write(&self, _subject: Value, _symbol: Symbol) -> bool {
+        false
+    }
+
+    fn allow_prototype_mutation(&self, _subject: Value) -> bool {
+        false
+    }
+
+    fn allow_trap_installation(&self, _subject: Value) -> bool {
+        false
+    }
+
+}
+
+#[test]
+fn test_access_policy() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context = ContextBuilder::new(isolate.clone()).build();
+
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let symbol = context.get_text_symbol("test", "foo");
+    let secret = context.get_text_symbol("test", "secret");
+    context.set_own_property(object, symbol, Value::make_integer(1), &context)?;
+    context.set_own_property(object, secret, Value::make_integer(2), &context)?;
+
+    let sandbox = ContextBuilder::new(isolate.clone())
+        .access_policy(Arc::new(ReadOnlyPolicy { secret: secret }))
+        .build();
+
+    assert_eq!(sandbox.get_own_property(object, symbol, None, &sandbox)?.get_value(), Value::make_integer(1));
+    assert!(sandbox.get_own_property(object, secret, None, &sandbox).is_err());
+    assert!(isolate.get_property(object, secret, None, &sandbox).is_err());
+    assert!(sandbox.set_own_property(object, symbol, Value::make_null(), &sandbox).is_err());
+    assert!(sandbox.delete_own_property(object, symbol, &sandbox).is_err());
+    assert!(sandbox.set_prototype(object, Value::make_null(), &sandbox).is_err());
+    assert!(sandbox.set_slot_trap(object, Arc::new(TestSlotTrap::new(object)), &sandbox).is_err());
+
+    let child = sandbox.create_child();
+    assert!(child.set_own_property(object, symbol, Value::make_null(), &child).is_err());
+
+    assert_eq!(context.get_own_property(object, symbol, None, &context)?.get_value(), Value::make_integer(1));
+
+    Ok(())
+
+}
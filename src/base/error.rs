@@ -1,4 +1,13 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::cell::RefCell;
+use core::fmt;
+
 use super::super::storage::Pinned;
+use super::super::util::HashMap;
+use super::super::util::RwLock;
+use super::value::Value;
 
 /// Type of errors
 #[derive(Debug)]
@@ -43,6 +52,9 @@ pub enum ErrorType {
     /// Mutating read-only properties of some values
     MutatingReadOnlyProperty,
 
+    /// Mutating constant properties of some values assigned already
+    MutatingConstantProperty,
+
     /// Prototype of some values not found
     PrototypeNotFound,
 
@@ -61,27 +73,360 @@ pub enum ErrorType {
     /// Slot moved
     SlotMoved,
 
+    /// Encoded data could not be decoded
+    MalformedData,
+
+    /// Access denied by the access policy of the context
+    AccessDenied,
+
+    /// Property path could not be resolved through undefined or null, with
+    /// the index of the segment failed
+    PropertyPathBroken(usize),
+
+    /// Prototype chain contains a cycle, or is deeper than the limit of the
+    /// isolate
+    PrototypeCycle,
+
+    /// Budget of the quota a region assigned to is exceeded
+    QuotaExceeded,
+
+    /// Operation interrupted by the cancellation token of the context
+    Interrupted,
+
+    /// Traps nested deeper than the limit of the context, such as a slot
+    /// trap reentering itself
+    TrapDepthExceeded,
+
+    /// Operations nested deeper than the recursion limit of the isolate,
+    /// instead of overflowing the stack
+    RecursionLimitExceeded,
+
     /// Rogic runtime error
     RogicRuntimeError,
     
     /// Rogic script error
-    RogicError(Pinned)
+    RogicError(Pinned),
+
+    /// Error defined by embedders, with a code registered in the custom
+    /// error registry of the isolate
+    Custom(u32)
+
+}
+
+/// Stable codes of error types, which are kept unchanged among versions,
+/// and could be passed through the C API
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u32)]
+pub enum ErrorCode {
+    FatalError = 1,
+    OutOfSpace = 2,
+    VisitingUndefinedPrototype = 3,
+    VisitingUndefinedProperty = 4,
+    VisitingNullPrototype = 5,
+    VisitingNullProperty = 6,
+    MutatingUndefinedPrototype = 7,
+    MutatingUndefinedProperty = 8,
+    MutatingNullPrototype = 9,
+    MutatingNullProperty = 10,
+    MutatingSealedPrototype = 11,
+    MutatingSealedProperty = 12,
+    MutatingReadOnlyProperty = 13,
+    PrototypeNotFound = 14,
+    PropertyNotFound = 15,
+    TypeNotMatch = 16,
+    IntegerOutOfRange = 17,
+    InternalSlotNotFound = 18,
+    SlotMoved = 19,
+    MalformedData = 20,
+    AccessDenied = 21,
+    PropertyPathBroken = 22,
+    RogicRuntimeError = 23,
+    RogicError = 24,
+    Custom = 25,
+    PrototypeCycle = 26,
+    QuotaExceeded = 27,
+    Interrupted = 28,
+    TrapDepthExceeded = 29,
+    RecursionLimitExceeded = 30,
+    MutatingConstantProperty = 31
+}
+
+impl ErrorType {
+
+    pub fn get_code(&self) -> ErrorCode {
+        match self {
+            ErrorType::FatalError => ErrorCode::FatalError,
+            ErrorType::OutOfSpace => ErrorCode::OutOfSpace,
+            ErrorType::VisitingUndefinedPrototype => ErrorCode::VisitingUndefinedPrototype,
+            ErrorType::VisitingUndefinedProperty => ErrorCode::VisitingUndefinedProperty,
+            ErrorType::VisitingNullPrototype => ErrorCode::VisitingNullPrototype,
+            ErrorType::VisitingNullProperty => ErrorCode::VisitingNullProperty,
+            ErrorType::MutatingUndefinedPrototype => ErrorCode::MutatingUndefinedPrototype,
+            ErrorType::MutatingUndefinedProperty => ErrorCode::MutatingUndefinedProperty,
+            ErrorType::MutatingNullPrototype => ErrorCode::MutatingNullPrototype,
+            ErrorType::MutatingNullProperty => ErrorCode::MutatingNullProperty,
+            ErrorType::MutatingSealedPrototype => ErrorCode::MutatingSealedPrototype,
+            ErrorType::MutatingSealedProperty => ErrorCode::MutatingSealedProperty,
+            ErrorType::MutatingReadOnlyProperty => ErrorCode::MutatingReadOnlyProperty,
+            ErrorType::MutatingConstantProperty => ErrorCode::MutatingConstantProperty,
+            ErrorType::PrototypeNotFound => ErrorCode::PrototypeNotFound,
+            ErrorType::PropertyNotFound => ErrorCode::PropertyNotFound,
+            ErrorType::TypeNotMatch => ErrorCode::TypeNotMatch,
+            ErrorType::IntegerOutOfRange => ErrorCode::IntegerOutOfRange,
+            ErrorType::InternalSlotNotFound => ErrorCode::InternalSlotNotFound,
+            ErrorType::SlotMoved => ErrorCode::SlotMoved,
+            ErrorType::MalformedData => ErrorCode::MalformedData,
+            ErrorType::AccessDenied => ErrorCode::AccessDenied,
+            ErrorType::PropertyPathBroken(_) => ErrorCode::PropertyPathBroken,
+            ErrorType::PrototypeCycle => ErrorCode::PrototypeCycle,
+            ErrorType::QuotaExceeded => ErrorCode::QuotaExceeded,
+            ErrorType::Interrupted => ErrorCode::Interrupted,
+            ErrorType::TrapDepthExceeded => ErrorCode::TrapDepthExceeded,
+            ErrorType::RecursionLimitExceeded => ErrorCode::RecursionLimitExceeded,
+            ErrorType::RogicRuntimeError => ErrorCode::RogicRuntimeError,
+            ErrorType::RogicError(_) => ErrorCode::RogicError,
+            ErrorType::Custom(_) => ErrorCode::Custom
+        }
+    }
 
 }
 
 /// Error record with type and message
+///
+/// Errors could be chained with the errors causing them, and tagged with the
+/// region and the slot value involved. Backtraces are captured on creation
+/// with the `backtrace` feature enabled
 #[derive(Debug)]
 pub struct Error {
     error_type: ErrorType,
-    message: String
+    message: String,
+    source: Option<Box<Error>>,
+    region_id: Option<u32>,
+    value: Option<Value>,
+    #[cfg(feature = "backtrace")]
+    backtrace: Box<std::backtrace::Backtrace>
 }
 
 impl Error {
+
     /// Create error with error type and message
     pub fn new(error_type: ErrorType, message: &str) -> Error {
         Error {
             error_type: error_type,
-            message: message.to_owned()
+            message: message.to_owned(),
+            source: None,
+            region_id: None,
+            value: None,
+            #[cfg(feature = "backtrace")]
+            backtrace: Box::new(std::backtrace::Backtrace::capture())
+        }
+    }
+
+    /// Chain the error causing this error
+    pub fn with_source(mut self, source: Error) -> Error {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Tag the region involved
+    pub fn with_region_id(mut self, region_id: u32) -> Error {
+        self.region_id = Some(region_id);
+        self
+    }
+
+    /// Tag the slot value involved
+    pub fn with_value(mut self, value: Value) -> Error {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn get_error_type(&self) -> &ErrorType {
+        &self.error_type
+    }
+
+    pub fn get_code(&self) -> ErrorCode {
+        self.error_type.get_code()
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_source(&self) -> Option<&Error> {
+        self.source.as_deref()
+    }
+
+    pub fn get_region_id(&self) -> Option<u32> {
+        self.region_id
+    }
+
+    pub fn get_value(&self) -> Option<Value> {
+        self.value
+    }
+
+    #[cfg(feature = "backtrace")]
+    pub fn get_backtrace(&self) -> &std::backtrace::Backtrace {
+        &self.backtrace
+    }
+
+}
+
+impl fmt::Display for Error {
+
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(formatter, "[E{:04}] {}", self.get_code() as u32, self.message)?;
+
+        match self.error_type {
+            ErrorType::PropertyPathBroken(index) => write!(formatter, " at segment {}", index)?,
+            ErrorType::Custom(code) => write!(formatter, " (custom error {})", code)?,
+            _ => {}
+        }
+        if let Some(region_id) = self.region_id {
+            write!(formatter, " (region {})", region_id)?;
+        }
+        if let Some(value) = self.value {
+            write!(formatter, " (value {:?})", value)?;
+        }
+        if let Some(source) = &self.source {
+            write!(formatter, ", caused by: {}", source)?;
+        }
+
+        Ok(())
+
+    }
+
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            Some(source) => Some(source.as_ref()),
+            None => None
+        }
+    }
+
+}
+
+/// Name and default message of a custom error type
+#[derive(Clone, Debug)]
+pub struct CustomErrorInfo {
+    name: String,
+    default_message: String
+}
+
+impl CustomErrorInfo {
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_default_message(&self) -> &str {
+        &self.default_message
+    }
+
+}
+
+/// Registry of error types defined by embedders, keyed by custom codes
+///
+/// Traps could surface domain errors by returning errors made from the
+/// registry, which are passed through to the callers as they are
+pub struct CustomErrorRegistry {
+    rw_lock: RwLock,
+    errors: RefCell<HashMap<u32, CustomErrorInfo>>
+}
+
+impl CustomErrorRegistry {
+
+    pub fn new() -> CustomErrorRegistry {
+        CustomErrorRegistry {
+            rw_lock: RwLock::new(),
+            errors: RefCell::new(HashMap::new())
         }
     }
+
+    /// Register a custom error type, codes could not be registered twice
+    pub fn register(&self, code: u32, name: &str, default_message: &str) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut errors = self.errors.borrow_mut();
+        if errors.contains_key(&code) {
+            return Err(Error::new(ErrorType::FatalError, "Custom error type already registered"));
+        }
+
+        errors.insert(code, CustomErrorInfo {
+            name: name.to_owned(),
+            default_message: default_message.to_owned()
+        });
+
+        Ok(())
+
+    }
+
+    pub fn get_info(&self, code: u32) -> Option<CustomErrorInfo> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.errors.borrow().get(&code).cloned()
+
+    }
+
+    /// Make an error of a custom error type registered, the default message
+    /// is used if no message provided
+    pub fn make_error(&self, code: u32, message: Option<&str>) -> Result<Error, Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        match self.errors.borrow().get(&code) {
+            Some(info) => Ok(Error::new(ErrorType::Custom(code), message.unwrap_or(&info.default_message))),
+            None => Err(Error::new(ErrorType::FatalError, "Custom error type not registered"))
+        }
+
+    }
+
+}
+
+#[test]
+fn test_error() {
+
+    let source = Error::new(ErrorType::OutOfSpace, "Out of slots").with_region_id(3);
+    let error = Error::new(ErrorType::FatalError, "Slot not moved").with_value(Value::make_integer(1)).with_source(source);
+
+    assert_eq!(error.get_code(), ErrorCode::FatalError);
+    assert_eq!(error.get_message(), "Slot not moved");
+    assert_eq!(error.get_value(), Some(Value::make_integer(1)));
+    assert_eq!(error.get_source().unwrap().get_code(), ErrorCode::OutOfSpace);
+    assert_eq!(error.get_source().unwrap().get_region_id(), Some(3));
+    assert_eq!(ErrorType::PropertyPathBroken(2).get_code() as u32, 22);
+
+    let text = std::format!("{}", error);
+    assert!(text.starts_with("[E0001] Slot not moved (value "));
+    assert!(text.ends_with(", caused by: [E0002] Out of slots (region 3)"));
+
+    assert!(std::error::Error::source(&error).is_some());
+
+}
+
+#[test]
+fn test_custom_error_registry() -> Result<(), Error> {
+
+    let registry = CustomErrorRegistry::new();
+
+    registry.register(7, "QuotaExceeded", "Quota exceeded")?;
+    assert!(registry.register(7, "QuotaExceeded", "Quota exceeded").is_err());
+    assert_eq!(registry.get_info(7).unwrap().get_name(), "QuotaExceeded");
+    assert!(registry.get_info(8).is_none());
+
+    let error = registry.make_error(7, None)?;
+    assert_eq!(error.get_code(), ErrorCode::Custom);
+    assert!(matches!(error.get_error_type(), ErrorType::Custom(7)));
+    assert_eq!(error.get_message(), "Quota exceeded");
+    assert_eq!(registry.make_error(7, Some("Too many slots"))?.get_message(), "Too many slots");
+    assert!(registry.make_error(8, None).is_err());
+
+    Ok(())
+
 }
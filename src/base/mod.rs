@@ -3,12 +3,19 @@ mod primitive_type;
 mod symbol;
 mod value;
 
+pub use error::CustomErrorInfo;
+pub use error::CustomErrorRegistry;
 pub use error::Error;
+pub use error::ErrorCode;
 pub use error::ErrorType;
 pub use primitive_type::PrimitiveType;
 pub use symbol::Symbol;
+pub use symbol::SymbolCacheEntry;
 pub use symbol::SymbolIdGenerator;
 pub use symbol::SymbolRecord;
+pub use symbol::SymbolRegistry;
 pub use symbol::SymbolInfo;
 pub use symbol::SymbolScope;
+pub use symbol::TextSymbolCache;
+pub use symbol::WellKnownSymbols;
 pub use value::Value;
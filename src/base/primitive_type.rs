@@ -1,6 +1,6 @@
 
 /// Pritimive types supported in rogic memory
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum PrimitiveType {
 
     /// Undefined data type, which means the data is not initialized
@@ -32,6 +32,9 @@ pub enum PrimitiveType {
     /// Tuple data type
     Tuple,
 
+    /// Arbitrary precision integer data type stored in slots of memory
+    BigInteger,
+
     /// Complex object data type stored in slots of memory
     Object
 
@@ -1,13 +1,20 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::sync::Arc;
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use core::cell::Cell;
+use core::cell::RefCell;
+use super::super::util::HashMap;
+use super::super::util::HashSet;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
 
 use super::error::Error;
 use super::error::ErrorType::*;
+use super::super::util::RcuCell;
 use super::super::util::RwLock;
+use super::super::util::SpinLock;
 use super::value::Value;
 
 /// Symbol info for a specified symbol
@@ -90,6 +97,218 @@ impl SymbolIdGenerator {
         self.next_id.fetch_add(1, Ordering::SeqCst)
     }
 
+    /// Duplicate the generator to generate IDs from the same next ID
+    pub fn duplicate(&self) -> SymbolIdGenerator {
+        SymbolIdGenerator {
+            next_id: AtomicU32::new(self.next_id.load(Ordering::SeqCst))
+        }
+    }
+
+}
+
+/// Registry of text symbols shared between isolates
+///
+/// Isolates created with the same registry resolve text symbols with the
+/// same scope and text into identical symbols. Registered symbols are never
+/// forgotten, so a text symbol recycled in an isolate will get the same
+/// symbol again when recreated
+pub struct SymbolRegistry {
+    rw_lock: RwLock,
+    generator: Arc<SymbolIdGenerator>,
+    text_symbols: RefCell<HashMap<String, HashMap<String, Symbol>>>
+}
+
+impl SymbolRegistry {
+
+    pub fn new() -> SymbolRegistry {
+        SymbolRegistry {
+            rw_lock: RwLock::new(),
+            generator: Arc::new(SymbolIdGenerator::new()),
+            text_symbols: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Get the symbol ID generator shared by all participating isolates
+    pub fn get_symbol_id_generator(&self) -> Arc<SymbolIdGenerator> {
+        self.generator.clone()
+    }
+
+    /// Get a text symbol registered, the symbol will be registered if not
+    /// exists
+    pub fn get_text_symbol(&self, scope: &str, text: &str) -> Symbol {
+
+        {
+            let _guard = self.rw_lock.lock_read();
+            if let Some(text_symbols) = self.text_symbols.borrow().get(scope) {
+                if let Some(result) = text_symbols.get(text) {
+                    return *result;
+                }
+            }
+        }
+
+        {
+            let _guard = self.rw_lock.lock_write();
+            let mut scopes = self.text_symbols.borrow_mut();
+            let text_symbols = match scopes.get_mut(scope) {
+                Some(text_symbols) => text_symbols,
+                None => {
+                    scopes.insert(scope.to_owned(), HashMap::new());
+                    scopes.get_mut(scope).unwrap()
+                }
+            };
+            if let Some(result) = text_symbols.get(text) {
+                return *result;
+            }
+            let result = Symbol::new(self.generator.generate());
+            text_symbols.insert(text.to_owned(), result);
+            result
+        }
+
+    }
+
+}
+
+/// Handle of a text symbol held by an embedder, such as identifiers used by
+/// an interpreter loop, the symbol is resolved once with the generation of
+/// symbols of the isolate, and resolved again only if the generation changed
+/// after symbols recycled, or the handle is used with another isolate
+pub struct SymbolCacheEntry {
+    scope: String,
+    text: String,
+    cached: AtomicU64
+}
+
+impl SymbolCacheEntry {
+
+    pub fn new(scope: &str, text: &str) -> SymbolCacheEntry {
+        SymbolCacheEntry {
+            scope: scope.to_owned(),
+            text: text.to_owned(),
+            cached: AtomicU64::new(0)
+        }
+    }
+
+    pub fn get_scope(&self) -> &str {
+        &self.scope
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the symbol cached if it is resolved with the generation
+    #[inline]
+    pub fn get_symbol(&self, generation: u32) -> Option<Symbol> {
+        let cached = self.cached.load(Ordering::Acquire);
+        if (cached >> 32) as u32 != generation {
+            return None;
+        }
+        Some(Symbol::new(cached as u32))
+    }
+
+    /// Cache the symbol resolved with the generation
+    pub fn set_symbol(&self, symbol: Symbol, generation: u32) {
+        self.cached.store(((generation as u64) << 32) | (symbol.get_id() as u64), Ordering::Release);
+    }
+
+    /// Forget the symbol cached
+    pub fn reset(&self) {
+        self.cached.store(0, Ordering::Release);
+    }
+
+}
+
+/// Cache of text symbols resolved by an isolate, looked up without locks
+///
+/// Symbols cached are published in an immutable map replaced on refreshes.
+/// Symbols inserted are kept pending until misses paid for copying the map,
+/// so a symbol just inserted may still miss for a while, but interning many
+/// symbols never copies the map for each of them
+pub struct TextSymbolCache {
+    spin_lock: SpinLock,
+    published: RcuCell<HashMap<String, HashMap<String, Symbol>>>,
+    published_count: Cell<usize>,
+    pending: RefCell<Vec<(String, String, Symbol)>>,
+    misses: Cell<usize>
+}
+
+impl TextSymbolCache {
+
+    pub fn new() -> TextSymbolCache {
+        TextSymbolCache {
+            spin_lock: SpinLock::new(),
+            published: RcuCell::new(HashMap::new()),
+            published_count: Cell::new(0),
+            pending: RefCell::new(Vec::new()),
+            misses: Cell::new(0)
+        }
+    }
+
+    /// Get a text symbol published without locks
+    #[inline]
+    pub fn get(&self, scope: &str, text: &str) -> Option<Symbol> {
+        self.published.read().get(scope)?.get(text).copied()
+    }
+
+    /// Insert a text symbol resolved after a miss, pending symbols are
+    /// published once misses since the last refresh reach a quarter of the
+    /// symbols published
+    pub fn insert(&self, scope: &str, text: &str, symbol: Symbol) {
+
+        let _guard = self.spin_lock.lock();
+
+        {
+            let mut pending = self.pending.borrow_mut();
+            if !pending.iter().any(|(pending_scope, pending_text, _)| (pending_scope == scope) && (pending_text == text)) {
+                pending.push((scope.to_owned(), text.to_owned(), symbol));
+            }
+        }
+
+        self.misses.set(self.misses.get() + 1);
+        if self.misses.get() * 4 >= self.published_count.get() {
+            self.refresh(|_symbol| true);
+        }
+
+    }
+
+    /// Forget cached symbols not kept by the filter, such as symbols
+    /// recycled
+    pub fn retain<F>(&self, filter: F) where F: Fn(Symbol) -> bool {
+
+        let _guard = self.spin_lock.lock();
+
+        self.refresh(filter);
+
+    }
+
+    /// Publish pending symbols with the symbols published kept by the filter
+    fn refresh<F>(&self, filter: F) where F: Fn(Symbol) -> bool {
+
+        let mut published = HashMap::new();
+        let mut count = 0;
+        for (scope, text_symbols) in self.published.read().iter() {
+            let text_symbols: HashMap<String, Symbol> = text_symbols.iter()
+                .filter(|(_text, symbol)| filter(**symbol))
+                .map(|(text, symbol)| (text.clone(), *symbol))
+                .collect();
+            if !text_symbols.is_empty() {
+                count += text_symbols.len();
+                published.insert(scope.clone(), text_symbols);
+            }
+        }
+
+        for (scope, text, symbol) in self.pending.borrow_mut().drain(..) {
+            if filter(symbol) && published.entry(scope).or_insert_with(HashMap::new).insert(text, symbol).is_none() {
+                count += 1;
+            }
+        }
+
+        self.published.replace(published);
+        self.published_count.set(count);
+        self.misses.set(0);
+
+    }
+
 }
 
 #[derive(Clone)]
@@ -103,11 +322,14 @@ pub struct SymbolScope {
     id: Arc<String>,
     rw_lock: RwLock,
     generator: Arc<SymbolIdGenerator>,
+    registry: Option<Arc<SymbolRegistry>>,
     text_symbols: RefCell<HashMap<String, Symbol>>,
     value_symbols: RefCell<HashMap<Value, Symbol>>,
     symbol_records: RefCell<HashMap<Symbol, SymbolRecord>>,
     symbol_references: RefCell<HashMap<Symbol, u32>>,
-    symbol_nursery: RefCell<HashSet<Symbol>>
+    symbol_nursery: RefCell<HashSet<Symbol>>,
+    pinned_symbols: RefCell<HashSet<Symbol>>,
+    collectable: Cell<bool>
 }
 
 impl SymbolScope {
@@ -118,12 +340,43 @@ impl SymbolScope {
             id: Arc::new(id.to_owned()),
             rw_lock: RwLock::new(),
             generator: generator,
+            registry: None,
             text_symbols: RefCell::new(HashMap::new()),
             value_symbols: RefCell::new(HashMap::new()),
             symbol_records: RefCell::new(HashMap::new()),
             symbol_references: RefCell::new(HashMap::new()),
-            symbol_nursery: RefCell::new(HashSet::new())
+            symbol_nursery: RefCell::new(HashSet::new()),
+            pinned_symbols: RefCell::new(HashSet::new()),
+            collectable: Cell::new(true)
+        }
+    }
+
+    /// Create a new symbol scope with text symbols resolved by the registry
+    pub fn with_registry(registry: Arc<SymbolRegistry>, id: &str) -> SymbolScope {
+        let mut symbol_scope = SymbolScope::new(registry.get_symbol_id_generator(), id);
+        symbol_scope.registry = Some(registry);
+        symbol_scope
+    }
+
+    /// Duplicate the symbol scope with all symbols for a forked isolate
+    pub fn duplicate(&self, generator: Arc<SymbolIdGenerator>) -> SymbolScope {
+
+        let _guard = self.rw_lock.lock_read();
+
+        SymbolScope {
+            id: self.id.clone(),
+            rw_lock: RwLock::new(),
+            generator: generator,
+            registry: self.registry.clone(),
+            text_symbols: RefCell::new(self.text_symbols.borrow().clone()),
+            value_symbols: RefCell::new(self.value_symbols.borrow().clone()),
+            symbol_records: RefCell::new(self.symbol_records.borrow().clone()),
+            symbol_references: RefCell::new(self.symbol_references.borrow().clone()),
+            symbol_nursery: RefCell::new(self.symbol_nursery.borrow().clone()),
+            pinned_symbols: RefCell::new(self.pinned_symbols.borrow().clone()),
+            collectable: Cell::new(self.collectable.get())
         }
+
     }
 
     /// Get the id of the symbol scope
@@ -155,7 +408,10 @@ impl SymbolScope {
             if let Some(result) = self.text_symbols.borrow().get(text) {
                 return *result;
             }
-            let result = Symbol::new(self.generator.generate());
+            let result = match &self.registry {
+                Some(registry) => registry.get_text_symbol(&self.id, text),
+                None => Symbol::new(self.generator.generate())
+            };
             self.text_symbols.borrow_mut().insert(text.to_owned(), result);
             self.symbol_records.borrow_mut().insert(result, SymbolRecord::TextSymbol(Arc::new(text.to_owned())));
             self.symbol_nursery.borrow_mut().insert(result);
@@ -188,6 +444,19 @@ impl SymbolScope {
 
     }
 
+    /// Redirect the value symbol of the old value to the new value
+    pub fn refresh_value_symbol(&self, old_value: Value, new_value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut value_symbols = self.value_symbols.borrow_mut();
+        if let Some(symbol) = value_symbols.remove(&old_value) {
+            value_symbols.insert(new_value, symbol);
+            self.symbol_records.borrow_mut().insert(symbol, SymbolRecord::ValueSymbol(new_value));
+        }
+
+    }
+
     pub fn add_symbol_reference(&self, symbol: Symbol) -> Result<(), Error> {
 
         let _guard = self.rw_lock.lock_write();
@@ -233,6 +502,10 @@ impl SymbolScope {
         if self.symbol_nursery.borrow().get(&symbol).is_some() {
             return Err(Error::new(FatalError, "Symbol in nursery"));
         }
+
+        if self.pinned_symbols.borrow().get(&symbol).is_some() {
+            return Err(Error::new(FatalError, "Symbol pinned"));
+        }
         
         let references = self.symbol_references.borrow_mut();
         match references.get(&symbol) {
@@ -263,8 +536,82 @@ impl SymbolScope {
 
     }
 
-}
+    /// Keep the symbol from automatic recycling even if not referenced
+    pub fn pin_symbol(&self, symbol: Symbol) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if self.symbol_records.borrow().get(&symbol).is_none() {
+            return Err(Error::new(FatalError, "Symbol not found"));
+        }
+
+        self.pinned_symbols.borrow_mut().insert(symbol);
+
+        Ok(())
+
+    }
+
+    pub fn unpin_symbol(&self, symbol: Symbol) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if !self.pinned_symbols.borrow_mut().remove(&symbol) {
+            return Err(Error::new(FatalError, "Symbol not pinned"));
+        }
+
+        Ok(())
+
+    }
+
+    pub fn is_collectable(&self) -> bool {
+        let _guard = self.rw_lock.lock_read();
+        self.collectable.get()
+    }
 
+    /// Set whether symbols of the scope could be recycled automatically,
+    /// scopes used as stable external identifiers should not be collectable
+    pub fn set_collectable(&self, collectable: bool) {
+        let _guard = self.rw_lock.lock_write();
+        self.collectable.set(collectable);
+    }
+
+    /// Recycle all symbols not referenced, symbols in nursery or pinned are
+    /// kept. Symbols recycled will be returned
+    pub fn sweep_symbols(&self) -> Vec<Symbol> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if !self.collectable.get() {
+            return Vec::new();
+        }
+
+        let references = self.symbol_references.borrow();
+        let nursery = self.symbol_nursery.borrow();
+        let pinned_symbols = self.pinned_symbols.borrow();
+
+        let mut records = self.symbol_records.borrow_mut();
+
+        let symbols: Vec<Symbol> = records.keys().filter(|symbol| {
+            references.get(*symbol).is_none() && nursery.get(*symbol).is_none() && pinned_symbols.get(*symbol).is_none()
+        }).copied().collect();
+
+        for symbol in symbols.iter() {
+            match records.remove(symbol) {
+                Some(SymbolRecord::TextSymbol(text)) => {
+                    self.text_symbols.borrow_mut().remove(text.as_ref());
+                },
+                Some(SymbolRecord::ValueSymbol(value)) => {
+                    self.value_symbols.borrow_mut().remove(&value);
+                },
+                None => {}
+            }
+        }
+
+        symbols
+
+    }
+
+}
 
 /// Property symbol for objects
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -290,6 +637,75 @@ impl Symbol {
 
 }
 
+/// Symbols predefined by isolates, shared by traps and host codes
+#[derive(Copy, Clone, Debug)]
+pub struct WellKnownSymbols {
+    prototype: Symbol,
+    iterator: Symbol,
+    to_text: Symbol,
+    to_primitive: Symbol,
+    call: Symbol,
+    equals: Symbol,
+    hash: Symbol
+}
+
+impl WellKnownSymbols {
+
+    pub fn new(prototype: Symbol, iterator: Symbol, to_text: Symbol, to_primitive: Symbol, call: Symbol, equals: Symbol, hash: Symbol) -> WellKnownSymbols {
+        WellKnownSymbols {
+            prototype: prototype,
+            iterator: iterator,
+            to_text: to_text,
+            to_primitive: to_primitive,
+            call: call,
+            equals: equals,
+            hash: hash
+        }
+    }
+
+    #[inline]
+    pub fn get_prototype(&self) -> Symbol {
+        self.prototype
+    }
+
+    /// Get the symbol for iterating over values
+    #[inline]
+    pub fn get_iterator(&self) -> Symbol {
+        self.iterator
+    }
+
+    /// Get the symbol for converting values into texts
+    #[inline]
+    pub fn get_to_text(&self) -> Symbol {
+        self.to_text
+    }
+
+    /// Get the symbol for converting values into primitives for coercions
+    #[inline]
+    pub fn get_to_primitive(&self) -> Symbol {
+        self.to_primitive
+    }
+
+    /// Get the symbol for calling values as functions
+    #[inline]
+    pub fn get_call(&self) -> Symbol {
+        self.call
+    }
+
+    /// Get the symbol for comparing equality of values
+    #[inline]
+    pub fn get_equals(&self) -> Symbol {
+        self.equals
+    }
+
+    /// Get the symbol for hashing values
+    #[inline]
+    pub fn get_hash(&self) -> Symbol {
+        self.hash
+    }
+
+}
+
 #[test]
 fn test_symbol_id_generator() {
 
@@ -372,3 +788,83 @@ fn test_symbol_recycle() -> Result<(), Error> {
     Ok(())
 
 }
+
+#[test]
+fn test_symbol_sweep() -> Result<(), Error> {
+
+    let generator = Arc::new(SymbolIdGenerator::new());
+    let scope = SymbolScope::new(generator.clone(), "test");
+
+    let test = scope.get_text_symbol("test");
+    let test_2 = scope.get_text_symbol("test2");
+    let test_3 = scope.get_text_symbol("test3");
+    let test_4 = scope.get_text_symbol("test4");
+
+    for symbol in [test, test_2, test_3].iter() {
+        scope.add_symbol_reference(*symbol)?;
+        scope.remove_symbol_reference(*symbol)?;
+    }
+    scope.add_symbol_reference(test_2)?;
+    scope.pin_symbol(test_3)?;
+
+    scope.set_collectable(false);
+    assert!(scope.sweep_symbols().is_empty());
+
+    scope.set_collectable(true);
+    assert_eq!(scope.sweep_symbols(), [test].to_vec());
+    assert!(scope.get_symbol_record(test).is_none());
+    assert!(scope.get_symbol_record(test_2).is_some());
+    assert!(scope.get_symbol_record(test_3).is_some());
+    assert!(scope.get_symbol_record(test_4).is_some());
+    assert!(scope.recycle_symbol(test_3).is_err());
+
+    scope.unpin_symbol(test_3)?;
+    assert_eq!(scope.sweep_symbols(), [test_3].to_vec());
+    assert_ne!(scope.get_text_symbol("test"), test);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_symbol_registry() {
+
+    let registry = Arc::new(SymbolRegistry::new());
+
+    let scope = SymbolScope::with_registry(registry.clone(), "test");
+    let scope_2 = SymbolScope::with_registry(registry.clone(), "test");
+    let scope_3 = SymbolScope::with_registry(registry.clone(), "test2");
+
+    let test = scope.get_text_symbol("test");
+    assert_eq!(scope_2.get_text_symbol("test"), test);
+    assert_eq!(registry.get_text_symbol("test", "test"), test);
+    assert_ne!(scope_3.get_text_symbol("test"), test);
+
+    assert_ne!(scope.get_value_symbol(Value::make_null()), scope_2.get_value_symbol(Value::make_null()));
+
+}
+
+#[test]
+fn test_text_symbol_cache() {
+
+    let cache = TextSymbolCache::new();
+    assert!(cache.get("test", "foo").is_none());
+
+    cache.insert("test", "foo", Symbol::new(1));
+    assert_eq!(cache.get("test", "foo"), Some(Symbol::new(1)));
+    assert!(cache.get("other", "foo").is_none());
+
+    for id in 2 .. 10 {
+        cache.insert("test", &format!("bar{}", id), Symbol::new(id));
+    }
+    cache.insert("other", "foo", Symbol::new(10));
+    cache.insert("other", "foo", Symbol::new(10));
+    cache.insert("other", "foo", Symbol::new(10));
+    assert_eq!(cache.get("other", "foo"), Some(Symbol::new(10)));
+    assert_eq!(cache.get("test", "bar9"), Some(Symbol::new(9)));
+
+    cache.retain(|symbol| symbol != Symbol::new(1));
+    assert!(cache.get("test", "foo").is_none());
+    assert_eq!(cache.get("test", "bar2"), Some(Symbol::new(2)));
+
+}
@@ -1,6 +1,6 @@
-use std::fmt;
-use std::hash::Hash; 
-use std::hash::Hasher;
+use core::fmt;
+use core::hash::Hash; 
+use core::hash::Hasher;
 
 use super::error::Error;
 use super::error::ErrorType::*;
@@ -19,6 +19,10 @@ const TUPLE_PREFIX: u64 = NAN_PREFIX | 0b101;
 const LIST_PREFIX: u64 = NAN_PREFIX | 0b110;
 const OBJECT_PREFIX: u64 = NAN_PREFIX | 0b111;
 
+// Positive NaN prefixes are all occupied, extended types use the sign bit
+const EXTENDED_NAN_PREFIX: u64 = 0x8000 | NAN_PREFIX;
+const BIG_INTEGER_PREFIX: u64 = EXTENDED_NAN_PREFIX | 0b001;
+
 const UNDEFINED_SUFFIX: u64 = 0x0;
 const NULL_SUFFIX: u64 = 0x1;
 const NO_SUFFIX: u64 = 0x2;
@@ -77,6 +81,15 @@ impl fmt::Debug for Value {
                 } else {
                     formatter.debug_tuple("Value:object").finish()
                 }
+            },
+            BigInteger => {
+                let region = self.get_region_id();
+                let slot = self.get_region_slot();
+                if region.is_ok() && slot.is_ok() {
+                    formatter.debug_tuple("Value::big_integer").field(&region.unwrap()).field(&slot.unwrap()).finish()
+                } else {
+                    formatter.debug_tuple("Value:big_integer").finish()
+                }
             }
         }
     }
@@ -86,8 +99,8 @@ impl PartialEq for Value {
 
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        let data = unsafe { std::mem::transmute::<f64, u64>(self.data) };
-        let other_data = unsafe { std::mem::transmute::<f64, u64>(other.data) };
+        let data = unsafe { core::mem::transmute::<f64, u64>(self.data) };
+        let other_data = unsafe { core::mem::transmute::<f64, u64>(other.data) };
         data == other_data 
     }
 
@@ -98,7 +111,7 @@ impl Eq for Value {}
 impl Hash for Value {
 
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let data = unsafe { std::mem::transmute::<f64, u64>(self.data) };
+        let data = unsafe { core::mem::transmute::<f64, u64>(self.data) };
         data.hash(state);
     }
     
@@ -111,7 +124,29 @@ impl Value {
     #[inline]
     fn get_data(&self) -> u64 {
         unsafe {
-            std::mem::transmute::<f64, u64>(self.data)
+            core::mem::transmute::<f64, u64>(self.data)
+        }
+    }
+
+    /// Get the raw 64-bit data of the value, slotted values are only valid
+    /// within the isolate they belong to
+    #[inline]
+    pub fn to_bits(&self) -> u64 {
+        self.get_data()
+    }
+
+    /// Get the identity hash of the value, slotted values get different
+    /// hashes once moved into other slots
+    #[inline]
+    pub fn get_identity_hash(&self) -> u64 {
+        self.get_data().wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    }
+
+    /// Make a value from raw 64-bit data got by `to_bits`
+    #[inline]
+    pub fn from_bits(data: u64) -> Value {
+        Value {
+            data: f64::from_bits(data)
         }
     }
 
@@ -141,6 +176,7 @@ impl Value {
             LIST_PREFIX => List,
             TUPLE_PREFIX => Tuple,
             OBJECT_PREFIX => Object,
+            BIG_INTEGER_PREFIX => BigInteger,
             _ => Float
         }
 
@@ -168,7 +204,7 @@ impl Value {
     #[inline]
     pub fn make_null() -> Value {
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | NULL_SUFFIX) }
+            data: unsafe { core::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | NULL_SUFFIX) }
         }
     }
 
@@ -176,7 +212,7 @@ impl Value {
     #[inline]
     pub fn make_undefined() -> Value {
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | UNDEFINED_SUFFIX) }
+            data: unsafe { core::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | UNDEFINED_SUFFIX) }
         }
     }
 
@@ -185,11 +221,11 @@ impl Value {
     pub fn make_boolean(value: bool) -> Value {
         if value {
             Value { 
-                data: unsafe { std::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | YES_SUFFIX) }
+                data: unsafe { core::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | YES_SUFFIX) }
             }
         } else {
             Value { 
-                data: unsafe { std::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | NO_SUFFIX) }
+                data: unsafe { core::mem::transmute::<u64, f64>(NIL_OR_BOOLEAN_PREFIX << 48 | NO_SUFFIX) }
             }
         }
     }
@@ -197,7 +233,7 @@ impl Value {
     /// Make a symbol value
     pub fn make_symbol(symbol: super::symbol::Symbol) -> Value {
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(SYMBOL_PREFIX << 48 | (symbol.get_id() as u64)) }
+            data: unsafe { core::mem::transmute::<u64, f64>(SYMBOL_PREFIX << 48 | (symbol.get_id() as u64)) }
         }
     }
 
@@ -205,7 +241,7 @@ impl Value {
     #[inline]
     pub fn make_cardinal(value: u32) -> Value {
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>((INTEGER_PREFIX << 48) | (value as u64)) }
+            data: unsafe { core::mem::transmute::<u64, f64>((INTEGER_PREFIX << 48) | (value as u64)) }
         }
     }
 
@@ -214,14 +250,14 @@ impl Value {
     pub fn make_integer(value: i32) -> Value {
 
         let mut uvalue = unsafe { 
-            std::mem::transmute::<i32, u32>(value) 
+            core::mem::transmute::<i32, u32>(value) 
         } as u64;
         if value < 0 {
             uvalue |= 1 << 32;
         }
 
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>((INTEGER_PREFIX << 48) | uvalue) }
+            data: unsafe { core::mem::transmute::<u64, f64>((INTEGER_PREFIX << 48) | uvalue) }
         }
 
     }
@@ -231,7 +267,7 @@ impl Value {
     pub fn make_float(value: f64) -> Value {
         if value.is_nan() {
             Value { 
-                data: unsafe { std::mem::transmute::<u64, f64>(NAN_PREFIX << 48) }
+                data: unsafe { core::mem::transmute::<u64, f64>(NAN_PREFIX << 48) }
             }
         } else {
             Value { data: value }
@@ -247,7 +283,7 @@ impl Value {
         data |= slot as u64;
 
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(data) }
+            data: unsafe { core::mem::transmute::<u64, f64>(data) }
         }
 
     }
@@ -261,7 +297,7 @@ impl Value {
         data |= slot as u64;
 
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(data) }
+            data: unsafe { core::mem::transmute::<u64, f64>(data) }
         }
 
     }
@@ -275,7 +311,7 @@ impl Value {
         data |= slot as u64;
 
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(data) }
+            data: unsafe { core::mem::transmute::<u64, f64>(data) }
         }
 
     }
@@ -289,7 +325,21 @@ impl Value {
         data |= slot as u64;
 
         Value { 
-            data: unsafe { std::mem::transmute::<u64, f64>(data) }
+            data: unsafe { core::mem::transmute::<u64, f64>(data) }
+        }
+
+    }
+
+    /// Make a big integer value
+    #[inline]
+    pub fn make_big_integer(region: u32, slot: u32) -> Value {
+
+        let mut data = BIG_INTEGER_PREFIX << 48;
+        data |= (region as u64) << 16;
+        data |= slot as u64;
+
+        Value { 
+            data: unsafe { core::mem::transmute::<u64, f64>(data) }
         }
 
     }
@@ -477,6 +527,15 @@ impl Value {
         }
     }
 
+    /// Check whether a value is a big integer
+    #[inline]
+    pub fn is_big_integer(&self) -> bool {
+        match self.get_primitive_type() {
+            BigInteger => true,
+            _ => false
+        }
+    }
+
     /// Check whether a value is a slot
     #[inline]
     pub fn is_slotted(&self) -> bool {
@@ -485,6 +544,7 @@ impl Value {
             List => true,
             Tuple => true,
             Object => true,
+            BigInteger => true,
             _ => false
         }
     }
@@ -515,7 +575,8 @@ impl Value {
             List => true,
             Text => self.get_data() & 0xffff_ffff_ffff != 0,
             Tuple => true,
-            Object => true
+            Object => true,
+            BigInteger => true
         }
     }
 
@@ -532,38 +593,7 @@ impl Value {
     /// * Otherwise will output `default`
     #[inline]
     pub fn extract_integer(&self, default: i32) -> i32 {
-        match self.get_primitive_type() {
-            Null => default,
-            Undefined => default,
-            Boolean => match self.get_data() & 0xff == YES_SUFFIX {
-                false => 0,
-                _ => 1
-            },
-            Integer => {
-                let data = self.get_data();
-                if ((data >> 32) & 0b1 == 1) || ((data >> 31) & 0b1 == 0) {
-                    unsafe {
-                        std::mem::transmute::<u32, i32>((data & 0xffff_ffff) as u32)
-                    }
-                } else {
-                    default
-                }
-            },
-            Float => {
-                if self.data.is_nan() || self.data.is_infinite() || 
-                   (self.data > (0x7fff_ffff as f64)) || 
-                   (self.data < (-0x8000_0000 as f64)) {
-                    default
-                } else {
-                    self.data as i32
-                }
-            },
-            Symbol => default,
-            List => default,
-            Text => default,
-            Tuple => default,
-            Object => default
-        }
+        self.try_extract_integer().unwrap_or(default)
     }
 
     /// Extract 32-bit cardinal from the value
@@ -579,36 +609,7 @@ impl Value {
     /// * Otherwise will output `default`
     #[inline]
     pub fn extract_cardinal(&self, default: u32) -> u32 {
-        match self.get_primitive_type() {
-            Null => default,
-            Undefined => default,
-            Boolean => match self.get_data() & 0xff == YES_SUFFIX {
-                false => 0,
-                _ => 1
-            },
-            Integer => {
-                let data = self.get_data();
-                if ((data >> 32) & 0b1 == 1) && ((data >> 31) & 0b1 == 1) {
-                    default
-                } else {
-                    (data & 0xffff_ffff) as u32
-                }
-            },
-            Float => {
-                if self.data.is_nan() || self.data.is_infinite() || 
-                   (self.data > ((0xffff_ffff as u32) as f64)) || 
-                   (self.data < 0.0) {
-                    default
-                } else {
-                    self.data as u32
-                }
-            },
-            Symbol => default,
-            List => default,
-            Text => default,
-            Tuple => default,
-            Object => default
-        }
+        self.try_extract_cardinal().unwrap_or(default)
     }
 
     /// Extract 64-bit float from the value
@@ -633,7 +634,7 @@ impl Value {
                 let data = self.get_data();
                 if ((data >> 32) & 0b1 == 1) && ((data >> 31) & 0b1 == 1) {
                     unsafe {
-                        std::mem::transmute::<u32, i32>((data & 0xffff_ffff) as u32) as f64
+                        core::mem::transmute::<u32, i32>((data & 0xffff_ffff) as u32) as f64
                     }
                 } else {
                     (data & 0xffff_ffff) as f64
@@ -644,7 +645,8 @@ impl Value {
             List => default,
             Text => default,
             Tuple => default,
-            Object => default
+            Object => default,
+            BigInteger => default
         }
     }
 
@@ -663,7 +665,112 @@ impl Value {
             List => default,
             Text => default,
             Tuple => default,
-            Object => default
+            Object => default,
+            BigInteger => default
+        }
+    }
+
+}
+
+/// Extract primitive values, errors are returned instead of defaults
+impl Value {
+
+    /// Cast the value into a boolean strictly, only booleans and numbers are
+    /// supported, following the conversions of `as_boolean`
+    #[inline]
+    pub fn try_as_boolean(&self) -> Result<bool, Error> {
+        match self.get_primitive_type() {
+            Boolean | Integer | Float => Ok(self.as_boolean()),
+            _ => Err(Error::new(TypeNotMatch, "Value could not be cast into boolean"))
+        }
+    }
+
+    /// Extract 32-bit integer from the value, following the conversions of
+    /// `extract_integer`
+    ///
+    /// `IntegerOutOfRange` is returned for numbers out of range, and
+    /// `TypeNotMatch` for values other than booleans and numbers
+    #[inline]
+    pub fn try_extract_integer(&self) -> Result<i32, Error> {
+        match self.get_primitive_type() {
+            Boolean => Ok(match self.get_data() & 0xff == YES_SUFFIX {
+                false => 0,
+                _ => 1
+            }),
+            Integer => {
+                let data = self.get_data();
+                if ((data >> 32) & 0b1 == 1) || ((data >> 31) & 0b1 == 0) {
+                    Ok(unsafe {
+                        core::mem::transmute::<u32, i32>((data & 0xffff_ffff) as u32)
+                    })
+                } else {
+                    Err(Error::new(IntegerOutOfRange, "Integer out of range"))
+                }
+            },
+            Float => {
+                if self.data.is_nan() || self.data.is_infinite() || 
+                   (self.data > (0x7fff_ffff as f64)) || 
+                   (self.data < (-0x8000_0000 as f64)) {
+                    Err(Error::new(IntegerOutOfRange, "Float out of integer range"))
+                } else {
+                    Ok(self.data as i32)
+                }
+            },
+            _ => Err(Error::new(TypeNotMatch, "Value could not be extracted as integer"))
+        }
+    }
+
+    /// Extract 32-bit cardinal from the value, following the conversions of
+    /// `extract_cardinal`
+    ///
+    /// `IntegerOutOfRange` is returned for numbers out of range, and
+    /// `TypeNotMatch` for values other than booleans and numbers
+    #[inline]
+    pub fn try_extract_cardinal(&self) -> Result<u32, Error> {
+        match self.get_primitive_type() {
+            Boolean => Ok(match self.get_data() & 0xff == YES_SUFFIX {
+                false => 0,
+                _ => 1
+            }),
+            Integer => {
+                let data = self.get_data();
+                if ((data >> 32) & 0b1 == 1) && ((data >> 31) & 0b1 == 1) {
+                    Err(Error::new(IntegerOutOfRange, "Cardinal out of range"))
+                } else {
+                    Ok((data & 0xffff_ffff) as u32)
+                }
+            },
+            Float => {
+                if self.data.is_nan() || self.data.is_infinite() || 
+                   (self.data > ((0xffff_ffff as u32) as f64)) || 
+                   (self.data < 0.0) {
+                    Err(Error::new(IntegerOutOfRange, "Float out of cardinal range"))
+                } else {
+                    Ok(self.data as u32)
+                }
+            },
+            _ => Err(Error::new(TypeNotMatch, "Value could not be extracted as cardinal"))
+        }
+    }
+
+    /// Extract 64-bit float from the value, following the conversions of
+    /// `extract_float`
+    ///
+    /// `TypeNotMatch` is returned for values other than booleans and numbers
+    #[inline]
+    pub fn try_extract_float(&self) -> Result<f64, Error> {
+        match self.get_primitive_type() {
+            Boolean | Integer | Float => Ok(self.extract_float(0.0)),
+            _ => Err(Error::new(TypeNotMatch, "Value could not be extracted as float"))
+        }
+    }
+
+    /// Extract symbol from the value
+    #[inline]
+    pub fn try_extract_symbol(&self) -> Result<super::symbol::Symbol, Error> {
+        match self.get_primitive_type() {
+            Symbol => Ok(self.extract_symbol(super::symbol::Symbol::new(0))),
+            _ => Err(Error::new(TypeNotMatch, "Value could not be extracted as symbol"))
         }
     }
 
@@ -690,7 +797,7 @@ impl Value {
             Integer => {
                 let data = self.get_data();
                 let value = unsafe {
-                    std::mem::transmute::<u32, i32>((data & 0xffff_ffff) as u32) 
+                    core::mem::transmute::<u32, i32>((data & 0xffff_ffff) as u32) 
                 };
                 if ((data >> 32) & 0b1 == 1) || (value >= 0) {
                     Ok(value)
@@ -1231,6 +1338,28 @@ fn test_float_negative_infinity() {
     }
 }
 
+#[test]
+fn test_big_integer() {
+    let value = Value::make_big_integer(3, 7);
+    assert!(!value.is_null());
+    assert!(!value.is_nil());
+    assert!(!value.is_integer());
+    assert!(!value.is_float());
+    assert!(!value.is_number());
+    assert!(value.is_big_integer());
+    assert!(value.is_slotted());
+    assert!(!value.is_tuple());
+    assert!(!value.is_object());
+    assert!(!value.is_text());
+    assert!(value.as_boolean());
+    assert_eq!(value.extract_integer(1), 1);
+    assert_eq!(value.extract_float(1.0), 1.0);
+    assert_eq!(value.get_region_id().unwrap(), 3);
+    assert_eq!(value.get_region_slot().unwrap(), 7);
+    assert_ne!(value, Value::make_object(3, 7));
+    assert!(!Value::make_float(-f64::NAN).is_big_integer());
+}
+
 #[test]
 fn test_equal() {
     let null_value = Value::make_null();
@@ -1275,4 +1404,30 @@ fn test_equal() {
     assert_eq!(negative_float_value, negative_float_value_2);
     assert!(zero_value.number_eq(&zero_float_value));
     assert!(negative_value.number_eq(&negative_float_value));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_fallible_extraction() {
+
+    assert_eq!(Value::make_boolean(true).try_extract_integer().unwrap(), 1);
+    assert_eq!(Value::make_integer(-3).try_extract_integer().unwrap(), -3);
+    assert_eq!(Value::make_float(3.7).try_extract_integer().unwrap(), 3);
+    assert!(matches!(Value::make_float(1e20).try_extract_integer().unwrap_err().get_error_type(), IntegerOutOfRange));
+    assert!(matches!(Value::make_cardinal(0xffff_ffff).try_extract_integer().unwrap_err().get_error_type(), IntegerOutOfRange));
+    assert!(matches!(Value::make_null().try_extract_integer().unwrap_err().get_error_type(), TypeNotMatch));
+
+    assert_eq!(Value::make_cardinal(0xffff_ffff).try_extract_cardinal().unwrap(), 0xffff_ffff);
+    assert!(matches!(Value::make_integer(-1).try_extract_cardinal().unwrap_err().get_error_type(), IntegerOutOfRange));
+    assert!(matches!(Value::make_float(-1.0).try_extract_cardinal().unwrap_err().get_error_type(), IntegerOutOfRange));
+
+    assert_eq!(Value::make_integer(2).try_extract_float().unwrap(), 2.0);
+    assert!(matches!(Value::make_undefined().try_extract_float().unwrap_err().get_error_type(), TypeNotMatch));
+
+    assert!(!Value::make_float(0.0).try_as_boolean().unwrap());
+    assert!(Value::make_integer(3).try_as_boolean().unwrap());
+    assert!(Value::make_null().try_as_boolean().is_err());
+
+    assert_eq!(Value::make_symbol(super::symbol::Symbol::new(7)).try_extract_symbol().unwrap().get_id(), 7);
+    assert!(Value::make_integer(7).try_extract_symbol().is_err());
+
+}
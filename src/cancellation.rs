@@ -0,0 +1,67 @@
+use alloc::sync::Arc;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+
+/// Token cancelling long operations of contexts holding it, such as listing
+/// properties through long prototype chains and converting deep value
+/// graphs, which fail with `Interrupted` once the token cancelled
+///
+/// Clones of a token share the same cancellation state, so a token could be
+/// cancelled from another thread or a watchdog. Collections are not
+/// interrupted, since an unfinished marking or sweeping leaves colors of
+/// slots inconsistent
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>
+}
+
+impl CancellationToken {
+
+    pub fn new() -> CancellationToken {
+        CancellationToken {
+            cancelled: Arc::new(AtomicBool::new(false))
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Reset the token to be used by later operations
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    /// Fail with `Interrupted` if the token is cancelled
+    #[inline]
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            return Err(Error::new(Interrupted, "Operation cancelled"));
+        }
+        Ok(())
+    }
+
+}
+
+#[test]
+fn test_cancellation_token() {
+
+    let token = CancellationToken::new();
+    assert!(token.check().is_ok());
+
+    let cloned = token.clone();
+    cloned.cancel();
+    assert!(token.is_cancelled());
+    assert!(matches!(token.check().unwrap_err().get_error_type(), Interrupted));
+
+    token.reset();
+    assert!(!cloned.is_cancelled());
+
+}
@@ -0,0 +1,407 @@
+//! C API for embedding the isolate from other languages
+//!
+//! All objects are opaque handles owned by the caller. Values are pinned by
+//! their handles, and must be released by `rogiso_value_release` before the
+//! isolate is disposed. Property names are UTF-8 texts of symbols in the
+//! JSON symbol scope, same as keys of objects parsed from JSON.
+//!
+//! Functions returning handles return null if failed, and functions
+//! returning status return `ROGISO_OK` if succeeded.
+
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::str;
+use std::sync::Arc;
+
+use super::base::PrimitiveType;
+use super::base::Value;
+use super::context::Context;
+use super::isolate::Isolate;
+use super::json::JSON_SYMBOL_SCOPE;
+use super::standard_context::StandardContext;
+use super::storage::Pinned;
+
+pub const ROGISO_OK: i32 = 0;
+pub const ROGISO_ERROR: i32 = -1;
+pub const ROGISO_INVALID_ARGUMENT: i32 = -2;
+
+pub const ROGISO_TYPE_UNDEFINED: i32 = 0;
+pub const ROGISO_TYPE_NULL: i32 = 1;
+pub const ROGISO_TYPE_BOOLEAN: i32 = 2;
+pub const ROGISO_TYPE_INTEGER: i32 = 3;
+pub const ROGISO_TYPE_FLOAT: i32 = 4;
+pub const ROGISO_TYPE_SYMBOL: i32 = 5;
+pub const ROGISO_TYPE_TEXT: i32 = 6;
+pub const ROGISO_TYPE_LIST: i32 = 7;
+pub const ROGISO_TYPE_TUPLE: i32 = 8;
+pub const ROGISO_TYPE_BIG_INTEGER: i32 = 9;
+pub const ROGISO_TYPE_OBJECT: i32 = 10;
+
+/// Opaque handle of an isolate with its context
+pub struct RogisoIsolate {
+    context: Box<dyn Context>
+}
+
+/// Opaque handle of a pinned value
+pub struct RogisoValue {
+    pinned: Pinned
+}
+
+fn make_value_handle(context: &Box<dyn Context>, value: Value) -> *mut RogisoValue {
+    match Pinned::new(context, value) {
+        Ok(pinned) => Box::into_raw(Box::new(RogisoValue { pinned: pinned })),
+        Err(_) => ptr::null_mut()
+    }
+}
+
+unsafe fn read_utf8<'a>(text: *const c_char, length: usize) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    str::from_utf8(slice::from_raw_parts(text as *const u8, length)).ok()
+}
+
+/// Create an isolate, null will be returned if failed
+#[no_mangle]
+pub extern "C" fn rogiso_isolate_create() -> *mut RogisoIsolate {
+
+    let isolate = match Isolate::create() {
+        Ok(isolate) => Arc::new(isolate),
+        Err(_) => { return ptr::null_mut(); }
+    };
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate));
+
+    Box::into_raw(Box::new(RogisoIsolate { context: context }))
+
+}
+
+/// Dispose an isolate, all values of the isolate should be released before
+///
+/// # Safety
+///
+/// The isolate should be created by `rogiso_isolate_create` and not disposed.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_isolate_dispose(isolate: *mut RogisoIsolate) {
+    if !isolate.is_null() {
+        drop(Box::from_raw(isolate));
+    }
+}
+
+/// Release a value handle
+///
+/// # Safety
+///
+/// The value should be created by the C API and not released.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_value_release(value: *mut RogisoValue) {
+    if !value.is_null() {
+        drop(Box::from_raw(value));
+    }
+}
+
+/// Create another handle of the same value
+///
+/// # Safety
+///
+/// Handles should be alive and of the same isolate.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_value_clone(isolate: *mut RogisoIsolate, value: *const RogisoValue) -> *mut RogisoValue {
+    match (isolate.as_ref(), value.as_ref()) {
+        (Some(isolate), Some(value)) => make_value_handle(&isolate.context, value.pinned.get_value()),
+        _ => ptr::null_mut()
+    }
+}
+
+/// Get the type of a value as one of `ROGISO_TYPE_*`
+///
+/// # Safety
+///
+/// The value should be alive.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_value_get_type(value: *const RogisoValue) -> i32 {
+
+    let value = match value.as_ref() {
+        Some(value) => value.pinned.get_value(),
+        None => { return ROGISO_INVALID_ARGUMENT; }
+    };
+
+    match value.get_primitive_type() {
+        PrimitiveType::Undefined => ROGISO_TYPE_UNDEFINED,
+        PrimitiveType::Null => ROGISO_TYPE_NULL,
+        PrimitiveType::Boolean => ROGISO_TYPE_BOOLEAN,
+        PrimitiveType::Integer => ROGISO_TYPE_INTEGER,
+        PrimitiveType::Float => ROGISO_TYPE_FLOAT,
+        PrimitiveType::Symbol => ROGISO_TYPE_SYMBOL,
+        PrimitiveType::Text => ROGISO_TYPE_TEXT,
+        PrimitiveType::List => ROGISO_TYPE_LIST,
+        PrimitiveType::Tuple => ROGISO_TYPE_TUPLE,
+        PrimitiveType::BigInteger => ROGISO_TYPE_BIG_INTEGER,
+        PrimitiveType::Object => ROGISO_TYPE_OBJECT
+    }
+
+}
+
+/// Make a null value
+///
+/// # Safety
+///
+/// The isolate should be alive.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_make_null(isolate: *mut RogisoIsolate) -> *mut RogisoValue {
+    match isolate.as_ref() {
+        Some(isolate) => make_value_handle(&isolate.context, Value::make_null()),
+        None => ptr::null_mut()
+    }
+}
+
+/// Make a boolean value
+///
+/// # Safety
+///
+/// The isolate should be alive.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_make_boolean(isolate: *mut RogisoIsolate, boolean: bool) -> *mut RogisoValue {
+    match isolate.as_ref() {
+        Some(isolate) => make_value_handle(&isolate.context, Value::make_boolean(boolean)),
+        None => ptr::null_mut()
+    }
+}
+
+/// Make an integer value
+///
+/// # Safety
+///
+/// The isolate should be alive.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_make_integer(isolate: *mut RogisoIsolate, integer: i32) -> *mut RogisoValue {
+    match isolate.as_ref() {
+        Some(isolate) => make_value_handle(&isolate.context, Value::make_integer(integer)),
+        None => ptr::null_mut()
+    }
+}
+
+/// Make a float value
+///
+/// # Safety
+///
+/// The isolate should be alive.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_make_float(isolate: *mut RogisoIsolate, float: f64) -> *mut RogisoValue {
+    match isolate.as_ref() {
+        Some(isolate) => make_value_handle(&isolate.context, Value::make_float(float)),
+        None => ptr::null_mut()
+    }
+}
+
+/// Make a text value from UTF-8 bytes
+///
+/// # Safety
+///
+/// The isolate should be alive, and the text should have `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_make_text(isolate: *mut RogisoIsolate, text: *const c_char, length: usize) -> *mut RogisoValue {
+
+    let (isolate, text) = match (isolate.as_ref(), read_utf8(text, length)) {
+        (Some(isolate), Some(text)) => (isolate, text),
+        _ => { return ptr::null_mut(); }
+    };
+
+    match isolate.context.make_text(text, &isolate.context) {
+        Ok(pinned) => Box::into_raw(Box::new(RogisoValue { pinned: pinned })),
+        Err(_) => ptr::null_mut()
+    }
+
+}
+
+/// Gain an object slot with prototype, the object prototype will be used if
+/// the prototype is null
+///
+/// # Safety
+///
+/// The isolate and the prototype should be alive.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_gain_object(isolate: *mut RogisoIsolate, prototype: *const RogisoValue) -> *mut RogisoValue {
+
+    let isolate = match isolate.as_ref() {
+        Some(isolate) => isolate,
+        None => { return ptr::null_mut(); }
+    };
+
+    let prototype = match prototype.as_ref() {
+        Some(prototype) => prototype.pinned.get_value(),
+        None => isolate.context.get_isolate().get_object_prototype()
+    };
+
+    match isolate.context.gain_slot(PrimitiveType::Object, prototype) {
+        Ok(value) => make_value_handle(&isolate.context, value),
+        Err(_) => ptr::null_mut()
+    }
+
+}
+
+/// Get integer data of a value
+///
+/// # Safety
+///
+/// The value should be alive, and the result should be writable.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_value_get_integer(value: *const RogisoValue, result: *mut i32) -> i32 {
+
+    if result.is_null() {
+        return ROGISO_INVALID_ARGUMENT;
+    }
+
+    match value.as_ref().map(|value| value.pinned.get_value().get_integer_data()) {
+        Some(Ok(integer)) => {
+            *result = integer;
+            ROGISO_OK
+        },
+        Some(Err(_)) => ROGISO_ERROR,
+        None => ROGISO_INVALID_ARGUMENT
+    }
+
+}
+
+/// Get float data of a value
+///
+/// # Safety
+///
+/// The value should be alive, and the result should be writable.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_value_get_float(value: *const RogisoValue, result: *mut f64) -> i32 {
+
+    if result.is_null() {
+        return ROGISO_INVALID_ARGUMENT;
+    }
+
+    match value.as_ref().map(|value| value.pinned.get_value().get_float_data()) {
+        Some(Ok(float)) => {
+            *result = float;
+            ROGISO_OK
+        },
+        Some(Err(_)) => ROGISO_ERROR,
+        None => ROGISO_INVALID_ARGUMENT
+    }
+
+}
+
+/// Copy UTF-8 bytes of a text value into the buffer
+///
+/// The byte length of the whole text will be returned, which may be larger
+/// than the capacity, and a negative status will be returned if failed. No
+/// null terminator will be written.
+///
+/// # Safety
+///
+/// The isolate and the value should be alive, and the buffer should be
+/// writable with `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_extract_text(isolate: *mut RogisoIsolate, value: *const RogisoValue, buffer: *mut c_char, capacity: usize) -> isize {
+
+    let (isolate, value) = match (isolate.as_ref(), value.as_ref()) {
+        (Some(isolate), Some(value)) => (isolate, value.pinned.get_value()),
+        _ => { return ROGISO_INVALID_ARGUMENT as isize; }
+    };
+
+    let text = match isolate.context.get_isolate().extract_text_slot(value, &isolate.context) {
+        Ok(text) => text.to_string(),
+        Err(_) => { return ROGISO_ERROR as isize; }
+    };
+
+    if !buffer.is_null() {
+        let length = std::cmp::min(capacity, text.len());
+        ptr::copy_nonoverlapping(text.as_ptr(), buffer as *mut u8, length);
+    }
+
+    text.len() as isize
+
+}
+
+/// Get property of a value by UTF-8 symbol name
+///
+/// # Safety
+///
+/// The isolate and the subject should be alive, and the name should have
+/// `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_get_property(isolate: *mut RogisoIsolate, subject: *const RogisoValue, name: *const c_char, length: usize) -> *mut RogisoValue {
+
+    let (isolate, subject, name) = match (isolate.as_ref(), subject.as_ref(), read_utf8(name, length)) {
+        (Some(isolate), Some(subject), Some(name)) => (isolate, subject.pinned.get_value(), name),
+        _ => { return ptr::null_mut(); }
+    };
+
+    let symbol = isolate.context.get_text_symbol(JSON_SYMBOL_SCOPE, name);
+
+    match isolate.context.get_property(subject, symbol, None, &isolate.context) {
+        Ok(pinned) => Box::into_raw(Box::new(RogisoValue { pinned: pinned })),
+        Err(_) => ptr::null_mut()
+    }
+
+}
+
+/// Set own property of a value by UTF-8 symbol name
+///
+/// # Safety
+///
+/// The isolate, the subject and the value should be alive, and the name
+/// should have `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rogiso_set_property(isolate: *mut RogisoIsolate, subject: *const RogisoValue, name: *const c_char, length: usize, value: *const RogisoValue) -> i32 {
+
+    let (isolate, subject, name, value) = match (isolate.as_ref(), subject.as_ref(), read_utf8(name, length), value.as_ref()) {
+        (Some(isolate), Some(subject), Some(name), Some(value)) => (isolate, subject.pinned.get_value(), name, value.pinned.get_value()),
+        _ => { return ROGISO_INVALID_ARGUMENT; }
+    };
+
+    let symbol = isolate.context.get_text_symbol(JSON_SYMBOL_SCOPE, name);
+
+    match isolate.context.set_own_property(subject, symbol, value, &isolate.context) {
+        Ok(()) => ROGISO_OK,
+        Err(_) => ROGISO_ERROR
+    }
+
+}
+
+#[test]
+fn test_capi() {
+
+    unsafe {
+
+        let isolate = rogiso_isolate_create();
+        assert!(!isolate.is_null());
+
+        let object = rogiso_gain_object(isolate, ptr::null());
+        assert_eq!(rogiso_value_get_type(object), ROGISO_TYPE_OBJECT);
+
+        let text = rogiso_make_text(isolate, "foo".as_ptr() as *const c_char, 3);
+        assert_eq!(rogiso_set_property(isolate, object, "name".as_ptr() as *const c_char, 4, text), ROGISO_OK);
+        rogiso_value_release(text);
+
+        let integer = rogiso_make_integer(isolate, 42);
+        assert_eq!(rogiso_set_property(isolate, object, "count".as_ptr() as *const c_char, 5, integer), ROGISO_OK);
+        rogiso_value_release(integer);
+
+        let name = rogiso_get_property(isolate, object, "name".as_ptr() as *const c_char, 4);
+        let mut buffer = [0 as c_char; 2];
+        assert_eq!(rogiso_extract_text(isolate, name, buffer.as_mut_ptr(), buffer.len()), 3);
+        assert_eq!(buffer[1] as u8, b'o');
+        rogiso_value_release(name);
+
+        let count = rogiso_get_property(isolate, object, "count".as_ptr() as *const c_char, 5);
+        let mut result = 0;
+        assert_eq!(rogiso_value_get_integer(count, &mut result), ROGISO_OK);
+        assert_eq!(result, 42);
+        assert!(rogiso_extract_text(isolate, count, ptr::null_mut(), 0) < 0);
+        rogiso_value_release(count);
+
+        assert!(rogiso_make_text(isolate, [0xffu8].as_ptr() as *const c_char, 1).is_null());
+
+        rogiso_value_release(object);
+        rogiso_isolate_dispose(isolate);
+
+    }
+
+}
@@ -0,0 +1,21 @@
+use alloc::boxed::Box;
+
+use super::base::Error;
+use super::base::Value;
+use super::context::Context;
+
+/// Hook coercing values into numbers and booleans, registered on the isolate
+/// and used when values define no `to_primitive` conversion
+///
+/// `None` could be returned to fall back to the default coercions
+pub trait ValueCoercer {
+
+    fn coerce_to_float(&self, _value: Value, _context: &Box<dyn Context>) -> Result<Option<f64>, Error> {
+        Ok(None)
+    }
+
+    fn coerce_to_boolean(&self, _value: Value, _context: &Box<dyn Context>) -> Result<Option<bool>, Error> {
+        Ok(None)
+    }
+
+}
@@ -0,0 +1,322 @@
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::base::PrimitiveType;
+use super::base::Symbol;
+use super::base::Value;
+use super::internal_slot::BigInt;
+use super::internal_slot::Bytes;
+use super::internal_slot::InternalSlot;
+use super::internal_slot::List;
+use super::internal_slot::Text;
+use super::internal_slot::Tuple;
+use super::util::HashMap;
+use super::util::SpinLock;
+
+/// Storage of regions paged out from an isolate
+///
+/// Data stored for a region is loaded back only once, when the region is
+/// rehydrated, so the storage could release it after loading
+pub trait ColdRegionStorage {
+
+    fn store(&self, region_id: u32, data: Vec<u8>) -> Result<(), Error>;
+
+    fn load(&self, region_id: u32) -> Result<Vec<u8>, Error>;
+
+}
+
+/// Storage keeping paged out regions as byte buffers in memory
+pub struct MemoryColdRegionStorage {
+    spin_lock: SpinLock,
+    regions: RefCell<HashMap<u32, Vec<u8>>>
+}
+
+impl MemoryColdRegionStorage {
+
+    pub fn new() -> MemoryColdRegionStorage {
+        MemoryColdRegionStorage {
+            spin_lock: SpinLock::new(),
+            regions: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Get total bytes of regions stored
+    pub fn get_stored_size(&self) -> usize {
+
+        let _guard = self.spin_lock.lock();
+
+        self.regions.borrow().values().map(|data| data.len()).sum()
+
+    }
+
+}
+
+impl ColdRegionStorage for MemoryColdRegionStorage {
+
+    fn store(&self, region_id: u32, data: Vec<u8>) -> Result<(), Error> {
+
+        let _guard = self.spin_lock.lock();
+
+        if self.regions.borrow().contains_key(&region_id) {
+            return Err(Error::new(FatalError, "Region already stored"));
+        }
+
+        self.regions.borrow_mut().insert(region_id, data);
+
+        Ok(())
+
+    }
+
+    fn load(&self, region_id: u32) -> Result<Vec<u8>, Error> {
+
+        let _guard = self.spin_lock.lock();
+
+        match self.regions.borrow_mut().remove(&region_id) {
+            Some(data) => Ok(data),
+            None => Err(Error::new(FatalError, "Region not stored"))
+        }
+
+    }
+
+}
+
+const INTERNAL_SLOT_TEXT: u8 = 0;
+const INTERNAL_SLOT_LIST: u8 = 1;
+const INTERNAL_SLOT_TUPLE: u8 = 2;
+const INTERNAL_SLOT_BYTES: u8 = 3;
+const INTERNAL_SLOT_BIG_INTEGER: u8 = 4;
+
+/// Writer encoding a cold region as little-endian bytes
+///
+/// Only texts, lists, tuples without field templates, bytes and big integers
+/// are supported as internal slots, regions with other internal slots could
+/// not be paged out
+pub struct ColdRegionWriter {
+    data: Vec<u8>
+}
+
+impl ColdRegionWriter {
+
+    pub fn new() -> ColdRegionWriter {
+        ColdRegionWriter {
+            data: Vec::new()
+        }
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u32(bytes.len() as u32);
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub fn write_value(&mut self, value: Value) {
+        self.write_u64(value.to_bits());
+    }
+
+    pub fn write_symbol(&mut self, symbol: Symbol) {
+        self.write_u32(symbol.get_id());
+    }
+
+    pub fn write_values(&mut self, values: &[Value]) {
+        self.write_u32(values.len() as u32);
+        for value in values.iter() {
+            self.write_value(*value);
+        }
+    }
+
+    pub fn write_primitive_type(&mut self, primitive_type: PrimitiveType) {
+        self.write_u8(match primitive_type {
+            PrimitiveType::Undefined => 0,
+            PrimitiveType::Null => 1,
+            PrimitiveType::Boolean => 2,
+            PrimitiveType::Integer => 3,
+            PrimitiveType::Float => 4,
+            PrimitiveType::Symbol => 5,
+            PrimitiveType::Text => 6,
+            PrimitiveType::List => 7,
+            PrimitiveType::Tuple => 8,
+            PrimitiveType::BigInteger => 9,
+            PrimitiveType::Object => 10
+        });
+    }
+
+    pub fn write_internal_slot(&mut self, internal_slot: &Arc<dyn InternalSlot>) -> Result<(), Error> {
+
+        let any = internal_slot.as_any();
+
+        if let Some(text) = any.downcast_ref::<Text>() {
+            self.write_u8(INTERNAL_SLOT_TEXT);
+            self.write_bytes(&text.as_utf8());
+        } else if let Some(list) = any.downcast_ref::<List>() {
+            self.write_u8(INTERNAL_SLOT_LIST);
+            self.write_values(&list.get_value_list());
+        } else if let Some(tuple) = any.downcast_ref::<Tuple>() {
+            if tuple.get_field_template().is_some() {
+                return Err(Error::new(FatalError, "Tuple with field template could not be paged out"));
+            }
+            self.write_u8(INTERNAL_SLOT_TUPLE);
+            self.write_u32(tuple.get_id());
+            self.write_values(&tuple.get_value_list());
+        } else if let Some(bytes) = any.downcast_ref::<Bytes>() {
+            self.write_u8(INTERNAL_SLOT_BYTES);
+            self.write_bytes(&bytes.as_vec());
+        } else if let Some(big_int) = any.downcast_ref::<BigInt>() {
+            self.write_u8(INTERNAL_SLOT_BIG_INTEGER);
+            self.write_bytes(big_int.to_string().as_bytes());
+        } else {
+            return Err(Error::new(FatalError, "Internal slot could not be paged out"));
+        }
+
+        Ok(())
+
+    }
+
+}
+
+/// Reader decoding a cold region from little-endian bytes
+pub struct ColdRegionReader<'a> {
+    data: &'a [u8],
+    offset: usize
+}
+
+impl<'a> ColdRegionReader<'a> {
+
+    pub fn new(data: &'a [u8]) -> ColdRegionReader<'a> {
+        ColdRegionReader {
+            data: data,
+            offset: 0
+        }
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+
+        if self.offset + N > self.data.len() {
+            return Err(Error::new(MalformedData, "Unexpected end of cold region"));
+        }
+
+        let mut array = [0u8; N];
+        array.copy_from_slice(&self.data[self.offset .. self.offset + N]);
+        self.offset += N;
+
+        Ok(array)
+
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, Error> {
+
+        let length = self.read_u32()? as usize;
+        if self.offset + length > self.data.len() {
+            return Err(Error::new(MalformedData, "Unexpected end of cold region"));
+        }
+
+        let bytes = self.data[self.offset .. self.offset + length].to_vec();
+        self.offset += length;
+
+        Ok(bytes)
+
+    }
+
+    pub fn read_value(&mut self) -> Result<Value, Error> {
+        Ok(Value::from_bits(self.read_u64()?))
+    }
+
+    pub fn read_symbol(&mut self) -> Result<Symbol, Error> {
+        Ok(Symbol::new(self.read_u32()?))
+    }
+
+    pub fn read_values(&mut self) -> Result<Vec<Value>, Error> {
+
+        let length = self.read_u32()? as usize;
+
+        let mut values = Vec::new();
+        for _ in 0 .. length {
+            values.push(self.read_value()?);
+        }
+
+        Ok(values)
+
+    }
+
+    pub fn read_primitive_type(&mut self) -> Result<PrimitiveType, Error> {
+        match self.read_u8()? {
+            0 => Ok(PrimitiveType::Undefined),
+            1 => Ok(PrimitiveType::Null),
+            2 => Ok(PrimitiveType::Boolean),
+            3 => Ok(PrimitiveType::Integer),
+            4 => Ok(PrimitiveType::Float),
+            5 => Ok(PrimitiveType::Symbol),
+            6 => Ok(PrimitiveType::Text),
+            7 => Ok(PrimitiveType::List),
+            8 => Ok(PrimitiveType::Tuple),
+            9 => Ok(PrimitiveType::BigInteger),
+            10 => Ok(PrimitiveType::Object),
+            _ => Err(Error::new(MalformedData, "Invalid primitive type in cold region"))
+        }
+    }
+
+    pub fn read_internal_slot(&mut self, subject: Value) -> Result<Arc<dyn InternalSlot>, Error> {
+        match self.read_u8()? {
+            INTERNAL_SLOT_TEXT => Ok(Arc::new(Text::new(&self.read_text()?))),
+            INTERNAL_SLOT_LIST => Ok(Arc::new(List::new(subject, self.read_values()?))),
+            INTERNAL_SLOT_TUPLE => {
+                let id = self.read_u32()?;
+                Ok(Arc::new(Tuple::new(subject, id, self.read_values()?)))
+            },
+            INTERNAL_SLOT_BYTES => Ok(Arc::new(Bytes::from_vec(self.read_bytes()?))),
+            INTERNAL_SLOT_BIG_INTEGER => Ok(Arc::new(BigInt::parse(&self.read_text()?)?)),
+            _ => Err(Error::new(MalformedData, "Invalid internal slot in cold region"))
+        }
+    }
+
+    fn read_text(&mut self) -> Result<String, Error> {
+        match String::from_utf8(self.read_bytes()?) {
+            Ok(text) => Ok(text),
+            Err(_) => Err(Error::new(MalformedData, "Invalid UTF-8 in cold region"))
+        }
+    }
+
+}
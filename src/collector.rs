@@ -1,7 +1,11 @@
-use std::cell::RefCell;
-use std::collections::HashSet;
-use std::ptr::NonNull;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cell::RefCell;
+use super::util::HashSet;
+use core::ptr::NonNull;
+use alloc::sync::Arc;
 
 use super::base::Error;
 use super::base::Symbol;
@@ -10,7 +14,12 @@ use super::base::PrimitiveType;
 use super::base::Value;
 use super::barrier::Barrier;
 use super::context::Context;
+use super::field_shortcuts::FieldTemplate;
 use super::field_shortcuts::FieldToken;
+use super::gc_listener::GcPhase;
+use super::gc_listener::GcPhaseStats;
+use super::gc_listener::GcStats;
+use super::gc_listener::GcStopwatch;
 use super::internal_slot::InternalSlot;
 use super::internal_slot::ProtectedInternalSlot;
 use super::isolate::Isolate;
@@ -27,6 +36,24 @@ use super::util::SpinLock;
 
 const MAX_SLICE_SIZE: usize = 128;
 
+const DEFAULT_REFRAGMENT_RATIO: f32 = 0.4;
+
+/// Mode to collect garbages on every slot gained through standard contexts,
+/// so that values not rooted or referenced properly are dropped at once
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GcStressMode {
+
+    /// Collect only when requested
+    Disabled,
+
+    /// Mark and sweep before every slot gained
+    Collect,
+
+    /// Mark, sweep and compact before every slot gained
+    CollectAndCompact
+
+}
+
 enum CollectorState {
     Free,
     Pending,
@@ -71,7 +98,7 @@ impl Barrier for CollectorBarrier {
 
 struct CollectorContext {
     isolate: Arc<Isolate>,
-    slot_layout_token: ReentrantToken
+    slot_layout_token: Arc<ReentrantToken>
 }
 
 impl Context for CollectorContext {
@@ -141,6 +168,14 @@ impl Context for CollectorContext {
         panic!("Collector context only support reference operations");
     }
 
+    fn call(&self, _function: Value, _this: Value, _arguments: Vec<Value>, _context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        panic!("Collector context only support reference operations");
+    }
+
+    fn create_child(&self) -> Box<dyn Context> {
+        panic!("Collector context only support reference operations");
+    }
+
     fn has_own_property(&self, _subject: Value, _symbol: Symbol, _context: &Box<dyn Context>) -> Result<bool, Error> {
         panic!("Collector context only support reference operations");
     }
@@ -149,7 +184,11 @@ impl Context for CollectorContext {
         panic!("Collector context only support reference operations");
     }
 
-    fn delete_own_property(&self, _subject: Value, _symbol: Symbol, _context: &Box<dyn Context>) -> Result<(), Error> {
+    fn get_own_property_entry(&self, _subject: Value, _symbol: Symbol, _field_token: Option<&FieldToken>, _context: &Box<dyn Context>) -> Result<Option<Pinned>, Error> {
+        panic!("Collector context only support reference operations");
+    }
+
+    fn delete_own_property(&self, _subject: Value, _symbol: Symbol, _context: &Box<dyn Context>) -> Result<bool, Error> {
         panic!("Collector context only support reference operations");
     }
 
@@ -157,11 +196,11 @@ impl Context for CollectorContext {
         panic!("Collector context only support reference operations");
     }
 
-    fn define_own_property(&self, _subject: Value, _symbol: Symbol, _property_trap: Arc<dyn PropertyTrap>, _context: &Box<dyn Context>) -> Result<(), Error> {
+    fn define_own_property(&self, _subject: Value, _symbol: Symbol, _property_trap: Arc<dyn PropertyTrap>, _context: &Box<dyn Context>) -> Result<bool, Error> {
         panic!("Collector context only support reference operations");
     }
 
-    fn list_own_property_symbols(&self, _subject: Value, _context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    fn list_own_property_symbols(&self, _subject: Value, _context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
         panic!("Collector context only support reference operations");
     }
 
@@ -173,15 +212,15 @@ impl Context for CollectorContext {
         panic!("Collector context only support reference operations");
     }
 
-    fn delete_own_property_ignore_slot_trap(&self, _id: Value, _subject: Value, _symbol: Symbol, _context: &Box<dyn Context>) -> Result<(), Error> {
+    fn delete_own_property_ignore_slot_trap(&self, _id: Value, _subject: Value, _symbol: Symbol, _context: &Box<dyn Context>) -> Result<bool, Error> {
         panic!("Collector context only support reference operations");
     }
 
-    fn define_own_property_ignore_slot_trap(&self, _id: Value, _subject: Value, _symbol: Symbol, _property_trap: Arc<dyn PropertyTrap>, _context: &Box<dyn Context>) -> Result<(), Error> {
+    fn define_own_property_ignore_slot_trap(&self, _id: Value, _subject: Value, _symbol: Symbol, _property_trap: Arc<dyn PropertyTrap>, _context: &Box<dyn Context>) -> Result<bool, Error> {
         panic!("Collector context only support reference operations");
     }
 
-    fn list_own_property_symbols_ignore_slot_trap(&self, _id: Value, _subject: Value, _context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    fn list_own_property_symbols_ignore_slot_trap(&self, _id: Value, _subject: Value, _context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
         panic!("Collector context only support reference operations");
     }
 
@@ -197,7 +236,7 @@ impl Context for CollectorContext {
         panic!("Collector context only support reference operations");
     }
 
-    fn list_property_symbols(&self, _subject: Value, _context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    fn list_property_symbols(&self, _subject: Value, _context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
         panic!("Collector context only support reference operations");
     }
 
@@ -221,7 +260,11 @@ impl Context for CollectorContext {
         panic!("Collector context only support reference operations");
     }
 
-    fn extract_text(&self, _value: Value, _context: &Box<dyn Context>) -> String {
+    fn make_named_tuple(&self, _prototype: Value, _id: u32, _field_template: &Arc<FieldTemplate>, _values: Vec<Value>, _context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        panic!("Collector context only support reference operations");
+    }
+
+    fn extract_text(&self, _value: Value, _context: &Box<dyn Context>) -> Result<String, Error> {
         panic!("Collector context only support reference operations");
     }
 
@@ -258,6 +301,7 @@ impl Context for CollectorContext {
 pub struct Collector {
 
     context: Box<dyn Context>,
+    slot_layout_token: Arc<ReentrantToken>,
 
     barrier_remarking_lock: SpinLock,
     barrier_remarking_slice: ValueSlice,
@@ -265,31 +309,58 @@ pub struct Collector {
     state: CollectorState,
     requested_to_collect: bool,
 
+    deterministic: bool,
+    random_state: Cell<u64>,
+
     gray_slices: Arc<RefCell<Vec<Vec<Value>>>>,
 
     symbol_rw_lock: RwLock,
-    symbol_marks: RefCell<HashSet<Symbol>>
+    symbol_marks: RefCell<HashSet<Symbol>>,
+
+    cold_region_rw_lock: RwLock,
+    cold_region_marks: RefCell<HashSet<u32>>
 
 }
 
 impl Collector {
 
     pub fn new(isolate: &Arc<Isolate>) -> Collector {
+        Collector::create(isolate, false, 0)
+    }
+
+    /// Create a collector for reproducible tests, collections requested are
+    /// only run by `poll`, and values listed from hash tables are visited
+    /// in an order decided by the seed
+    pub fn new_deterministic(isolate: &Arc<Isolate>, seed: u64) -> Collector {
+        Collector::create(isolate, true, seed)
+    }
+
+    fn create(isolate: &Arc<Isolate>, deterministic: bool, seed: u64) -> Collector {
+
+        let slot_layout_token = Arc::new(isolate.create_slot_layout_token());
 
         Collector {
             context: Box::new(CollectorContext {
                 isolate: isolate.clone(),
-                slot_layout_token: isolate.create_slot_layout_token()
+                slot_layout_token: slot_layout_token.clone()
             }),
+            slot_layout_token: slot_layout_token,
             barrier_remarking_lock: SpinLock::new(),
             barrier_remarking_slice: ValueSlice {
                 values: RefCell::new(Vec::new())
             },
             state: CollectorState::Free,
             requested_to_collect: false,
+            deterministic: deterministic,
+            random_state: Cell::new(match seed.wrapping_add(0x9e37_79b9_7f4a_7c15) {
+                0 => 0x9e37_79b9_7f4a_7c15,
+                state => state
+            }),
             gray_slices: Arc::new(RefCell::new(Vec::new())),
             symbol_rw_lock: RwLock::new(),
-            symbol_marks: RefCell::new(HashSet::new())
+            symbol_marks: RefCell::new(HashSet::new()),
+            cold_region_rw_lock: RwLock::new(),
+            cold_region_marks: RefCell::new(HashSet::new())
         }
 
     }
@@ -337,7 +408,10 @@ impl Collector {
         match self.state {
             CollectorState::Free => {
                 self.state = CollectorState::Pending;
-                if self.full_collect_garbages(0.4, context).is_err() {
+                if self.deterministic {
+                    return;
+                }
+                if self.full_collect_garbages(Some(DEFAULT_REFRAGMENT_RATIO), context).is_err() {
                     panic!("Failed to collect garbages");
                 }
             },
@@ -346,24 +420,119 @@ impl Collector {
 
     }
 
-    fn full_collect_garbages(&mut self, refragment_ratio: f32, context: &Box<dyn Context>) -> Result<(), Error> {
+    /// Run the collection requested before, false will be returned if no
+    /// collection is pending
+    pub fn poll(&mut self, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        match self.state {
+            CollectorState::Pending => {
+                self.full_collect_garbages(Some(DEFAULT_REFRAGMENT_RATIO), context)?;
+                Ok(true)
+            },
+            _ => Ok(false)
+        }
+
+    }
+
+    /// Collect garbages at once for GC stress, slots are compacted only if
+    /// specified, and the collection is skipped if the slot layout is
+    /// locked or another collection is running
+    pub fn collect_for_stress(&mut self, compact: bool) -> Result<bool, Error> {
+
+        match self.state {
+            CollectorState::Free => {},
+            _ => {
+                return Ok(false);
+            }
+        }
+
+        // The context of the collection shares the token of the collector,
+        // so the slot layout stays locked for the whole collection
+        let isolate = self.context.get_isolate().clone();
+        let context: Box<dyn Context> = Box::new(CollectorContext {
+            slot_layout_token: self.slot_layout_token.clone(),
+            isolate: isolate
+        });
+
+        let guard = context.get_slot_layout_token().try_lock_write();
+        if !guard.is_locked() {
+            return Ok(false);
+        }
+
+        self.state = CollectorState::Pending;
+        self.full_collect_garbages(if compact { Some(DEFAULT_REFRAGMENT_RATIO) } else { None }, &context)?;
+
+        drop(guard);
+
+        Ok(true)
+
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    pub fn is_collection_pending(&self) -> bool {
+        self.requested_to_collect
+    }
+
+    fn full_collect_garbages(&mut self, refragment_ratio: Option<f32>, context: &Box<dyn Context>) -> Result<(), Error> {
 
         self.requested_to_collect = false;
 
+        self.cold_region_marks.borrow_mut().clear();
+
+        let isolate = self.context.get_isolate().clone();
+
+        let stopwatch = GcStopwatch::start();
+        let live_slots_before = isolate.count_live_slots();
+        isolate.get_gc_listeners().notify(|listener| listener.notify_gc_started());
+
+        let phase_stopwatch = self.notify_gc_phase_started(GcPhase::Mark);
         self.mark_roots()?;
-        self.full_mark_grays()?;
-        self.remark_grays()?;
-        self.full_sweep_values(context)?;
-        self.full_refragment_slots(refragment_ratio, context)?;
+        let marked = self.full_mark_grays()? + self.remark_grays()?;
+        self.notify_gc_phase_finished(GcPhase::Mark, marked, &phase_stopwatch);
+
+        let phase_stopwatch = self.notify_gc_phase_started(GcPhase::Sweep);
+        let swept = self.full_sweep_values(context)?;
+        self.notify_gc_phase_finished(GcPhase::Sweep, swept, &phase_stopwatch);
+
+        if let Some(refragment_ratio) = refragment_ratio {
+            let phase_stopwatch = self.notify_gc_phase_started(GcPhase::Compact);
+            let recycled = self.full_refragment_slots(refragment_ratio, context)?;
+            self.notify_gc_phase_finished(GcPhase::Compact, recycled, &phase_stopwatch);
+        }
 
-        self.context.get_isolate().flip_base_color();
+        self.full_sweep_symbols()?;
+
+        isolate.flip_base_color();
+        isolate.get_metrics_counters().increase_gc_runs();
 
         self.state = CollectorState::Free;
 
+        let stats = GcStats::new(live_slots_before, isolate.count_live_slots(), stopwatch.elapsed());
+        isolate.get_gc_listeners().notify(|listener| listener.notify_gc_finished(&stats));
+
         Ok(())
 
     }
 
+    fn notify_gc_phase_started(&self, phase: GcPhase) -> GcStopwatch {
+
+        self.context.get_isolate().get_gc_listeners().notify(|listener| listener.notify_gc_phase_started(phase));
+
+        GcStopwatch::start()
+
+    }
+
+    fn notify_gc_phase_finished(&self, phase: GcPhase, count: usize, stopwatch: &GcStopwatch) {
+
+        let stats = GcPhaseStats::new(phase, count, stopwatch.elapsed());
+
+        self.context.get_isolate().get_gc_listeners().notify(|listener| listener.notify_gc_phase_finished(&stats));
+
+    }
+
 }
 
 impl Collector {
@@ -377,15 +546,24 @@ impl Collector {
         let slice = self.create_value_slice();
 
         let isolate = self.context.get_isolate();
+
+        isolate.drain_persistents()?;
+        isolate.flush_handle_blocks(self.context.get_slot_layout_token())?;
+        isolate.release_retired_field_shortcuts(self.context.get_slot_layout_token());
+
         for value in isolate.list_buitins() {
             self.mark_as_gray(value, &slice)?;
         }
 
-        for value in isolate.list_roots() {
+        let mut roots = isolate.list_roots();
+        self.order_values(&mut roots);
+        for value in roots {
             self.mark_as_gray(value, &slice)?;
         }
 
-        for value in isolate.list_values_in_nursery() {
+        let mut values_in_nursery = isolate.list_values_in_nursery();
+        self.order_values(&mut values_in_nursery);
+        for value in values_in_nursery {
             self.mark_as_gray(value, &slice)?;
         }
 
@@ -395,13 +573,13 @@ impl Collector {
             collector: NonNull::from(&*self)
         });
 
-        isolate.overwrite_barrier(barrier)?;
+        isolate.overwrite_barrier(barrier, self.context.get_slot_layout_token())?;
 
         Ok(())
 
     }
 
-    fn full_mark_grays(&mut self) -> Result<(), Error> {
+    fn full_mark_grays(&mut self) -> Result<usize, Error> {
 
         self.state = CollectorState::MarkingGrays;
 
@@ -411,6 +589,8 @@ impl Collector {
 
         let isolate = self.context.get_isolate();
 
+        let mut count = 0;
+
         loop {
             let values = self.list_grays(MAX_SLICE_SIZE);
             if values.len() == 0 {
@@ -418,7 +598,10 @@ impl Collector {
             }
             for value in values {
                 self.mark_as_black(value)?;
-                let (values, _symbols) = isolate.list_and_autorefresh_referenced_values(value, &self.context)?;
+                count += 1;
+                self.mark_object_group_as_gray(value, &slice)?;
+                let (mut values, _symbols) = isolate.list_and_autorefresh_referenced_values(value, &self.context)?;
+                self.order_values(&mut values);
                 for value in values {
                     self.mark_as_gray(value, &slice)?;
                 }
@@ -426,11 +609,11 @@ impl Collector {
             self.flush_slice(&slice)?;
         }
 
-        Ok(())
+        Ok(count)
 
     }
 
-    fn remark_grays(&mut self) -> Result<(), Error> {
+    fn remark_grays(&mut self) -> Result<usize, Error> {
 
         self.state = CollectorState::RemarkingGrays;
 
@@ -438,11 +621,13 @@ impl Collector {
 
         let isolate = self.context.get_isolate();
 
-        isolate.clear_barrier()?;
+        isolate.clear_barrier(self.context.get_slot_layout_token())?;
 
         self.flush_slice(&self.barrier_remarking_slice)?;
 
         let slice = self.create_value_slice();
+
+        let mut count = 0;
         loop {
             let values = self.list_grays(MAX_SLICE_SIZE);
             if values.len() == 0 {
@@ -450,7 +635,10 @@ impl Collector {
             }
             for value in values {
                 self.mark_as_black(value)?;
-                let (values, _symbols) = isolate.list_and_autorefresh_referenced_values(value, &self.context)?;
+                count += 1;
+                self.mark_object_group_as_gray(value, &slice)?;
+                let (mut values, _symbols) = isolate.list_and_autorefresh_referenced_values(value, &self.context)?;
+                self.order_values(&mut values);
                 for value in values {
                     self.mark_as_gray(value, &slice)?;
                 }
@@ -458,11 +646,11 @@ impl Collector {
             self.flush_slice(&slice)?;
         }
 
-        Ok(())
+        Ok(count)
 
     }
 
-    fn full_sweep_values(&mut self, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn full_sweep_values(&mut self, context: &Box<dyn Context>) -> Result<usize, Error> {
 
         self.state = CollectorState::Sweeping;
 
@@ -470,15 +658,29 @@ impl Collector {
 
         let isolate = self.context.get_isolate();
 
+        let live_slots = isolate.count_live_slots();
+
         for id in isolate.list_region_ids()? {
-            isolate.sweep_region(id, context)?;
+            if isolate.get_region_space_policy(id)?.is_sweepable() {
+                isolate.sweep_region(id, context)?;
+            }
         }
 
+        Ok(live_slots.saturating_sub(isolate.count_live_slots()))
+
+    }
+
+    fn full_sweep_symbols(&mut self) -> Result<(), Error> {
+
+        self.state = CollectorState::Sweeping;
+
+        self.context.get_isolate().sweep_symbols()?;
+
         Ok(())
 
     }
 
-    fn full_refragment_slots(&mut self, refragment_ratio: f32, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn full_refragment_slots(&mut self, refragment_ratio: f32, context: &Box<dyn Context>) -> Result<usize, Error> {
 
         self.state = CollectorState::Refragmenting;
 
@@ -488,6 +690,7 @@ impl Collector {
         let ids = isolate.list_region_ids()?;
 
         let mut max_alive_region_id = 0;
+        let mut recycled = 0;
 
         let mut target_id: u32 = 0;
         let mut source_id: u32 = ids.len() as u32 - 1;
@@ -495,34 +698,44 @@ impl Collector {
         let next_region_id = isolate.peek_next_region_id();
 
         while target_id <= source_id {
-            if isolate.need_region_refragment(source_id)? > refragment_ratio {
+            let policy = isolate.get_region_space_policy(source_id)?;
+            if policy.is_refragmentable() && (isolate.need_region_refragment(source_id)? > refragment_ratio) {
+                while (target_id < source_id) && !isolate.get_region_space_policy(target_id)?.is_refragmentable() {
+                    target_id += 1;
+                }
                 loop {
                     let all_finished = isolate.refragment_region(source_id, target_id, context)?;
                     if all_finished {
                         break;
                     }
-                    while (target_id < source_id) && isolate.is_region_full(target_id)? {
+                    while (target_id < source_id) && (isolate.is_region_full(target_id)? ||
+                        !isolate.get_region_space_policy(target_id)?.is_refragmentable()) {
                         target_id += 1;
                     }
                     if target_id > source_id {
                         break;
                     }
                 }
-                let protected = isolate.is_region_protected(source_id)?;
-                if (!protected) && isolate.is_region_empty(source_id)? {
-                    isolate.recycle_region(source_id)?;
-                } else {
-                    if source_id > max_alive_region_id {
-                        max_alive_region_id = source_id;
-                    }
+            }
+            let protected = isolate.is_region_protected(source_id)?;
+            if (!protected) && policy.is_recyclable() && isolate.is_region_empty(source_id)? {
+                isolate.recycle_region(source_id)?;
+                recycled += 1;
+            } else {
+                if source_id > max_alive_region_id {
+                    max_alive_region_id = source_id;
                 }
             }
+            if source_id == 0 {
+                break;
+            }
             source_id -= 1;
         }
 
         isolate.shrink_next_region_id(next_region_id, max_alive_region_id + 1);
+        isolate.shrink_regions();
 
-        Ok(())
+        Ok(recycled)
 
     }
 
@@ -530,6 +743,37 @@ impl Collector {
 
 impl Collector {
 
+    /// Order values listed from hash tables, values are sorted and then
+    /// shuffled by the seed in deterministic mode, otherwise they are kept
+    /// as listed
+    fn order_values(&self, values: &mut [Value]) {
+
+        if !self.deterministic {
+            return;
+        }
+
+        values.sort_by_key(|value| value.to_bits());
+
+        for index in (1..values.len()).rev() {
+            let other = (self.next_random() % (index as u64 + 1)) as usize;
+            values.swap(index, other);
+        }
+
+    }
+
+    /// Generate the next pseudorandom number by xorshift
+    fn next_random(&self) -> u64 {
+
+        let mut state = self.random_state.get();
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        self.random_state.set(state);
+
+        state
+
+    }
+
     fn create_value_slice(&self) -> ValueSlice {
         ValueSlice {
             values: RefCell::new(Vec::new())
@@ -559,6 +803,60 @@ impl Collector {
             if need_flush {
                 self.flush_slice(slice)?;
             }
+        } else if value.is_slotted() {
+            self.mark_cold_region_references(value, slice)?;
+        }
+
+        Ok(())
+
+    }
+
+    /// Mark values referenced by the region of a value paged out, once in a
+    /// collection, so that the region stays paged out
+    fn mark_cold_region_references(&self, value: Value, slice: &ValueSlice) -> Result<(), Error> {
+
+        let region_id = value.get_region_id()?;
+
+        {
+            let _guard = self.cold_region_rw_lock.lock_read();
+            if self.cold_region_marks.borrow().contains(&region_id) {
+                return Ok(());
+            }
+        }
+
+        let isolate = self.context.get_isolate();
+
+        let references = match isolate.list_cold_region_references(region_id) {
+            Some(references) => references,
+            None => {
+                return Ok(());
+            }
+        };
+
+        {
+            let _guard = self.cold_region_rw_lock.lock_write();
+            if !self.cold_region_marks.borrow_mut().insert(region_id) {
+                return Ok(());
+            }
+        }
+
+        for value in references {
+            let value = isolate.resolve_real_value(value, self.context.get_slot_layout_token())?;
+            self.mark_as_gray(value, slice)?;
+        }
+
+        Ok(())
+
+    }
+
+    /// Mark values grouped with a reachable value, so that values in an
+    /// object group are retained together
+    fn mark_object_group_as_gray(&self, value: Value, slice: &ValueSlice) -> Result<(), Error> {
+
+        for member in self.context.get_isolate().list_object_group_members(value) {
+            if member != value {
+                self.mark_as_gray(member, slice)?;
+            }
         }
 
         Ok(())
@@ -609,4 +907,216 @@ impl Collector {
 
     }
 
-}
\ No newline at end of file
+}
+#[cfg(test)] use core::cell::Cell as TestCell;
+#[cfg(test)] use super::test::TestContext2;
+#[cfg(test)] use super::test::TestDropListener;
+#[cfg(test)] use super::gc_listener::GcListener;
+
+/// Listener counting phases of collections started while the slot layout
+/// could be locked by other tokens
+#[cfg(test)]
+struct SlotLayoutProbe {
+    slot_layout_token: ReentrantToken,
+    unlocked_phases: TestCell<usize>
+}
+
+#[cfg(test)]
+impl GcListener for SlotLayoutProbe {
+    fn notify_gc_phase_started(&self, _phase: GcPhase) {
+        if self.slot_layout_token.try_lock_read().is_locked() {
+            self.unlocked_phases.set(self.unlocked_phases.get() + 1);
+        }
+    }
+}
+
+#[test]
+fn test_collector_deterministic() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_4 = isolate.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    for value in [value, value_2, value_3, value_4].iter() {
+        isolate.move_value_out_from_nursery(*value, &layout_token)?;
+    }
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(value, value, symbol, value_2, &context)?;
+    isolate.set_own_property(value_3, value_3, symbol, value_4, &context)?;
+    isolate.set_own_property(value_4, value_4, symbol, value_3, &context)?;
+    let _root = isolate.add_root(value, &layout_token)?;
+
+    let dropped_2 = Arc::new(TestCell::new(Value::make_undefined()));
+    let dropped_3 = Arc::new(TestCell::new(Value::make_undefined()));
+    let weak_root_2 = isolate.add_weak_root(value_2, Some(Box::new(TestDropListener::new(dropped_2.clone()))), &layout_token)?;
+    let weak_root_3 = isolate.add_weak_root(value_3, Some(Box::new(TestDropListener::new(dropped_3.clone()))), &layout_token)?;
+
+    let mut collector = Collector::new_deterministic(&isolate, 7);
+    assert!(collector.is_deterministic());
+    assert!(!collector.poll(&context)?);
+
+    collector.request_to_collect(&context);
+    assert!(collector.is_collection_pending());
+    assert!(dropped_3.get().is_undefined());
+
+    assert!(collector.poll(&context)?);
+    assert!(!collector.is_collection_pending());
+    assert!(!collector.poll(&context)?);
+
+    assert!(dropped_2.get().is_undefined());
+    assert!(dropped_3.get().is_null());
+    let value = isolate.resolve_real_value(value, &layout_token)?;
+    let value_2 = isolate.get_own_property(value, value, symbol, None, &context)?.get_value();
+    assert!(isolate.is_direct_value_alive(value, &context)?);
+    assert!(isolate.is_direct_value_alive(value_2, &context)?);
+    assert_eq!(weak_root_2.get_value(), Some(value_2));
+    assert!(weak_root_3.is_dropped());
+
+    collector.request_to_collect(&context);
+    assert!(collector.poll(&context)?);
+    let value_2 = isolate.get_own_property(value, value, symbol, None, &context)?.get_value();
+    assert!(isolate.is_direct_value_alive(value_2, &context)?);
+    assert_eq!(weak_root_2.get_value(), Some(value_2));
+
+    let mut values = vec![value_4, value_3, value_2, value];
+    let mut values_2 = values.clone();
+    Collector::new_deterministic(&isolate, 7).order_values(&mut values);
+    Collector::new_deterministic(&isolate, 7).order_values(&mut values_2);
+    assert_eq!(values, values_2);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_collector_fork() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_3 = isolate.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    for value in [value, value_2, value_3].iter() {
+        isolate.move_value_out_from_nursery(*value, &layout_token)?;
+    }
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(value, value, symbol, value_2, &context)?;
+    let _root = isolate.add_root(value, &layout_token)?;
+
+    let isolate_2 = Arc::new(isolate.fork()?);
+    let context_2: Box<dyn Context> = Box::new(TestContext2::new(isolate_2.clone()));
+    let _root_2 = isolate_2.add_root(value, context_2.get_slot_layout_token())?;
+
+    let mut collector = Collector::new(&isolate_2);
+    assert!(collector.collect_for_stress(false)?);
+
+    assert!(isolate.is_region_copy_on_write(region_id));
+    assert!(isolate_2.is_region_copy_on_write(region_id));
+    assert!(!isolate_2.is_region_copy_on_write(region_id_2));
+    assert!(isolate_2.is_direct_value_alive(value, &context_2)?);
+    assert!(isolate_2.is_direct_value_alive(value_2, &context_2)?);
+    assert!(!isolate_2.is_direct_value_alive(value_3, &context_2)?);
+    assert!(isolate.is_direct_value_alive(value_3, &context)?);
+
+    isolate_2.set_own_property(value, value, symbol, Value::make_null(), &context_2)?;
+    assert!(!isolate_2.is_region_copy_on_write(region_id));
+    assert!(isolate_2.is_white(value_2)?);
+
+    assert!(collector.collect_for_stress(false)?);
+    assert!(isolate_2.is_direct_value_alive(value, &context_2)?);
+    assert!(!isolate_2.is_direct_value_alive(value_2, &context_2)?);
+    assert!(isolate.is_direct_value_alive(value_2, &context)?);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_collector_cold_regions() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+    isolate.unprotect_region(region_id_2)?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_4 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    for value in [value, value_2, value_3, value_4].iter() {
+        isolate.move_value_out_from_nursery(*value, &layout_token)?;
+    }
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(value, value, symbol, value_2, &context)?;
+    isolate.set_own_property(value_2, value_2, symbol, value_3, &context)?;
+    let _root = isolate.add_root(value, &layout_token)?;
+
+    isolate.page_out_region(region_id_2)?;
+    assert_eq!(isolate.list_cold_region_references(region_id_2), Some([value_3].to_vec()));
+
+    let mut collector = Collector::new(&isolate);
+    assert!(collector.collect_for_stress(false)?);
+
+    assert!(isolate.is_region_cold(region_id_2));
+    assert!(isolate.is_direct_value_alive(value_3, &context)?);
+    assert!(!isolate.is_direct_value_alive(value_4, &context)?);
+
+    assert!(collector.collect_for_stress(false)?);
+    assert!(isolate.is_region_cold(region_id_2));
+    assert!(isolate.is_direct_value_alive(value_3, &context)?);
+
+    assert_eq!(isolate.get_own_property(value_2, value_2, symbol, None, &context)?.get_value(), value_3);
+    assert!(!isolate.is_region_cold(region_id_2));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_collector_stress_slot_layout() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let probe = Arc::new(SlotLayoutProbe {
+        slot_layout_token: isolate.create_slot_layout_token(),
+        unlocked_phases: TestCell::new(0)
+    });
+    isolate.add_gc_listener(probe.clone());
+
+    let mut collector = Collector::new(&isolate);
+    assert!(collector.collect_for_stress(true)?);
+    assert_eq!(probe.unlocked_phases.get(), 0);
+
+    let layout_token = isolate.create_slot_layout_token();
+    let guard = layout_token.lock_read();
+    assert!(!collector.collect_for_stress(true)?);
+    drop(guard);
+    assert!(collector.collect_for_stress(false)?);
+
+    Ok(())
+
+}
@@ -1,12 +1,17 @@
-use std::collections::HashSet;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
 
+use super::access_policy::AccessPolicy;
+use super::cancellation::CancellationToken;
 use super::base::Error;
 use super::base::PrimitiveType;
 use super::base::PrimitiveType::*;
 use super::base::Symbol;
 use super::base::SymbolInfo;
 use super::base::Value;
+use super::field_shortcuts::FieldTemplate;
 use super::field_shortcuts::FieldToken;
 use super::internal_slot::InternalSlot;
 use super::internal_slot::ProtectedInternalSlot;
@@ -14,6 +19,7 @@ use super::internal_slot::List;
 use super::internal_slot::Text;
 use super::internal_slot::Tuple;
 use super::isolate::Isolate;
+use super::region_cache::RegionCache;
 use super::root::DropListener;
 use super::root::Root;
 use super::root::WeakRoot;
@@ -29,10 +35,23 @@ pub trait Context {
     /// Get isolate of the context
     fn get_isolate<'a>(&'a self) -> &'a Arc<Isolate>;
 
+    /// Get the access policy of the context, `None` means all accesses are
+    /// allowed
+    fn get_access_policy(&self) -> Option<&Arc<dyn AccessPolicy>> {
+        None
+    }
+
     /// Get the slot layout token to lock slot layouts.
     /// The token could be used to keep your slot got from slot refragmentation
     fn get_slot_layout_token<'a>(&'a self) -> &'a ReentrantToken;
 
+    /// Get the cache of recently used regions consulted before the isolate
+    /// resolves regions, `None` means regions are always resolved by the
+    /// isolate
+    fn get_region_cache(&self) -> Option<&RegionCache> {
+        None
+    }
+
     fn protect_property_trap(&self, property_trap: &Arc<dyn PropertyTrap>) -> Result<(u64, Arc<dyn PropertyTrap>), Error> {
         self.get_isolate().protect_property_trap(property_trap)
     }
@@ -57,6 +76,26 @@ pub trait Context {
         self.get_isolate().unprotect_internal_slot(protected_id)
     }
 
+    /// Enter a trap called by the context, `TrapDepthExceeded` should be
+    /// returned once traps nested deeper than the limit of the context
+    fn enter_trap(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Leave a trap entered before
+    fn leave_trap(&self) {}
+
+    /// Enter a nested operation of the context, such as a trap dispatch, a
+    /// prototype step or a nested value converted, `RecursionLimitExceeded`
+    /// should be returned once operations nested deeper than the recursion
+    /// limit of the isolate
+    fn enter_recursion(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Leave a nested operation entered before
+    fn leave_recursion(&self) {}
+
     /// Resolve the value, to get the final value usable.
     /// The API helps you to keep the value from slot refragmention redirection
     fn resolve_real_value(&self, value: Value) -> Result<Value, Error> {
@@ -77,6 +116,12 @@ pub trait Context {
         Ok(())
     }
     
+    /// Add and remove references from a value in batch, references are
+    /// grouped by regions of the referenced values to lock each region once
+    fn update_value_references(&self, from: Value, added: &[Value], removed: &[Value]) -> Result<(), Error> {
+        self.get_isolate().update_value_references(from, added, removed, self.get_slot_layout_token())
+    }
+
     /// Add symbol reference, to inform the isolate that keeps the symbol from recycle
     fn add_symbol_reference(&self, symbol: Symbol) -> Result<(), Error> {
         self.get_isolate().add_symbol_reference(symbol)
@@ -88,13 +133,60 @@ pub trait Context {
     }
 
 
+    /// Make an error of a custom error type registered in the isolate, traps
+    /// could return it to surface domain errors to the callers
+    fn make_custom_error(&self, code: u32, message: Option<&str>) -> Result<Error, Error> {
+        self.get_isolate().make_custom_error(code, message)
+    }
+
+
     /// Create a new trap info
     fn create_trap_info(&self, subject: Value, parameters: Vec<Value>, context: &Box<dyn Context>) -> Box<dyn TrapInfo>;
 
+    /// Create a trap info with parameters copied, contexts could keep the
+    /// parameter buffers in a pool to reuse them after the trap info dropped
+    fn acquire_trap_info(&self, subject: Value, parameters: &[Value], context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        self.create_trap_info(subject, parameters.to_vec(), context)
+    }
+
 
     /// Gain a new slot with prototype preset
+    #[cfg_attr(feature = "provenance", track_caller)]
     fn gain_slot(&self, primitive_type: PrimitiveType, prototype: Value) -> Result<Value, Error>;
 
+    /// Get the tag recorded with slots gained by the context when the
+    /// isolate samples allocations
+    fn get_allocation_tag(&self) -> Option<&str> {
+        None
+    }
+
+    /// Get the quota slots gained by the context count against, regions
+    /// created by the context are assigned to the quota
+    fn get_quota_id(&self) -> Option<u64> {
+        None
+    }
+
+    /// Get the token cancelling long operations of the context
+    fn get_cancellation_token(&self) -> Option<&CancellationToken> {
+        None
+    }
+
+    /// Fail with `Interrupted` if the cancellation token of the context is
+    /// cancelled, checked periodically by long operations
+    #[inline]
+    fn check_interrupted(&self) -> Result<(), Error> {
+        match self.get_cancellation_token() {
+            Some(token) => token.check(),
+            None => Ok(())
+        }
+    }
+
+    /// Allocate a new slot with prototype preset, the region of the slot is
+    /// decided by the context
+    fn allocate(&self, primitive_type: PrimitiveType, prototype: Value) -> Result<Value, Error> {
+        self.gain_slot(primitive_type, prototype)
+    }
+
 
     /// Get a symbol with specified scope and text
     fn get_text_symbol(&self, scope: &str, text: &str) -> Symbol {
@@ -131,6 +223,11 @@ pub trait Context {
         self.get_isolate().set_slot_trap(value, slot_trap, context)
     }
 
+    /// Call a value as function with `this` value and arguments
+    fn call(&self, function: Value, this: Value, arguments: Vec<Value>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        self.get_isolate().call(function, this, arguments, context)
+    }
+
 
     /// Test whether a slot has own some properties
     fn has_own_property(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
@@ -142,8 +239,14 @@ pub trait Context {
         self.get_isolate().get_own_property(subject, subject, symbol, field_token, context)
     }
 
+    /// Get own property of a value, `None` will be returned if the property
+    /// is absent
+    fn get_own_property_entry(&self, subject: Value, symbol: Symbol, field_token: Option<&FieldToken>, context: &Box<dyn Context>) -> Result<Option<Pinned>, Error> {
+        self.get_isolate().get_own_property_entry(subject, subject, symbol, field_token, context)
+    }
+
     /// Delete own property of a value
-    fn delete_own_property(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn delete_own_property(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
         self.get_isolate().delete_own_property(subject, subject, symbol, context)
     }
 
@@ -153,12 +256,12 @@ pub trait Context {
     }
 
     /// Define own property of a value
-    fn define_own_property(&self, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn define_own_property(&self, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<bool, Error> {
         self.get_isolate().define_own_property(subject, subject, symbol, property_trap, context)
     }
 
     /// List own property symbols in a value
-    fn list_own_property_symbols(&self, subject: Value, context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    fn list_own_property_symbols(&self, subject: Value, context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
         self.get_isolate().list_own_property_symbols(subject, subject, context)
     }
 
@@ -174,17 +277,17 @@ pub trait Context {
     }
 
     /// Delete own property of a value
-    fn delete_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn delete_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
         self.get_isolate().delete_own_property_ignore_slot_trap(id, subject, symbol, context)
     }
 
     /// Define own property of a value
-    fn define_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn define_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<bool, Error> {
         self.get_isolate().define_own_property_ignore_slot_trap(id, subject, symbol, property_trap, context)
     }
 
     /// List own property symbols in a value
-    fn list_own_property_symbols_ignore_slot_trap(&self, id: Value, subject: Value, context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    fn list_own_property_symbols_ignore_slot_trap(&self, id: Value, subject: Value, context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
         self.get_isolate().list_own_property_symbols_ignore_slot_trap(id, subject, context)
     }
 
@@ -205,7 +308,7 @@ pub trait Context {
 
 
     /// List property symbols in a value
-    fn list_property_symbols(&self, subject: Value, context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    fn list_property_symbols(&self, subject: Value, context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
         self.get_isolate().list_property_symbols(subject, context)
     }
 
@@ -259,9 +362,22 @@ pub trait Context {
 
     }
 
+    /// Make a tuple value with named fields from values
+    fn make_named_tuple(&self, prototype: Value, id: u32, field_template: &Arc<FieldTemplate>, values: Vec<Value>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let value = self.gain_slot(Tuple, prototype)?;
+
+        let tuple: Arc<dyn InternalSlot> = Arc::new(Tuple::new_with_field_template(value, id, field_template.clone(), values));
+
+        self.set_internal_slot(value, 0, tuple, context)?;
+
+        Pinned::new(context, value)
+
+    }
+
 
     /// Extract text from a value 
-    fn extract_text(&self, value: Value, context: &Box<dyn Context>) -> String {
+    fn extract_text(&self, value: Value, context: &Box<dyn Context>) -> Result<String, Error> {
         self.get_isolate().extract_text(value, context)
     }
 
@@ -277,9 +393,10 @@ pub trait Context {
     fn extract_property_trap(&self, value: Value, context: &Box<dyn Context>) -> Result<Arc<dyn PropertyTrap>, Error>;
 
 
-    /// Add root value for garbage collection
+    /// Add root value for garbage collection, the root is added through the
+    /// handle block of the current thread
     fn add_root(&self, value: Value) -> Result<Arc<Root>, Error> {
-        self.get_isolate().add_root(value, self.get_slot_layout_token())
+        self.get_isolate().add_local_root(value, self.get_slot_layout_token())
     }
 
     /// Remove value from roots for garbage collection
@@ -297,9 +414,39 @@ pub trait Context {
         self.get_isolate().remove_weak_root(root)
     }
 
+    /// Create a child context with the configuration of the context, roots
+    /// added by the child context will be released once the child context
+    /// dropped
+    fn create_child(&self) -> Box<dyn Context>;
+
     /// Notify while a value is dropped
     fn notify_slot_drop(&self, value: Value) -> Result<(), Error> {
         self.get_isolate().notify_slot_drop(value)
     }
 
 }
+
+/// Guard of a nested operation entered in a context, the operation is left
+/// once the guard dropped, even if the operation fails
+pub struct RecursionGuard<'a> {
+    context: &'a Box<dyn Context>
+}
+
+impl<'a> RecursionGuard<'a> {
+
+    pub fn enter(context: &'a Box<dyn Context>) -> Result<RecursionGuard<'a>, Error> {
+        context.enter_recursion()?;
+        Ok(RecursionGuard {
+            context: context
+        })
+    }
+
+}
+
+impl<'a> Drop for RecursionGuard<'a> {
+
+    fn drop(&mut self) {
+        self.context.leave_recursion();
+    }
+
+}
@@ -0,0 +1,134 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+
+use super::access_policy::AccessPolicy;
+use super::context::Context;
+use super::isolate::Isolate;
+use super::standard_context::ContextListener;
+use super::standard_context::StandardContext;
+use super::trap::StandardTrapInfoFactory;
+use super::trap::TrapInfoFactory;
+
+/// Builder of standard contexts
+pub struct ContextBuilder {
+    isolate: Arc<Isolate>,
+    allocation_region_id: Option<u32>,
+    trap_info_factory: Arc<dyn TrapInfoFactory>,
+    listeners: Vec<Arc<dyn ContextListener>>,
+    access_policy: Option<Arc<dyn AccessPolicy>>
+}
+
+impl ContextBuilder {
+
+    pub fn new(isolate: Arc<Isolate>) -> ContextBuilder {
+        ContextBuilder {
+            isolate: isolate,
+            allocation_region_id: None,
+            trap_info_factory: Arc::new(StandardTrapInfoFactory::new()),
+            listeners: Vec::new(),
+            access_policy: None
+        }
+    }
+
+    /// Prefer the region to allocate new slots
+    pub fn allocation_region(mut self, region_id: u32) -> ContextBuilder {
+        self.allocation_region_id = Some(region_id);
+        self
+    }
+
+    pub fn trap_info_factory(mut self, trap_info_factory: Arc<dyn TrapInfoFactory>) -> ContextBuilder {
+        self.trap_info_factory = trap_info_factory;
+        self
+    }
+
+    /// Add a listener to instrument operations of the context
+    pub fn listener(mut self, listener: Arc<dyn ContextListener>) -> ContextBuilder {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// Restrict accesses through the context
+    pub fn access_policy(mut self, access_policy: Arc<dyn AccessPolicy>) -> ContextBuilder {
+        self.access_policy = Some(access_policy);
+        self
+    }
+
+    pub fn build_standard_context(self) -> StandardContext {
+
+        let mut context = StandardContext::with_trap_info_factory(self.isolate, self.trap_info_factory);
+
+        context.set_allocation_region_id(self.allocation_region_id);
+        context.set_access_policy(self.access_policy);
+        for listener in self.listeners {
+            context.add_listener(listener);
+        }
+
+        context
+
+    }
+
+    pub fn build(self) -> Box<dyn Context> {
+        Box::new(self.build_standard_context())
+    }
+
+}
+
+#[cfg(test)] use core::sync::atomic::AtomicUsize;
+#[cfg(test)] use core::sync::atomic::Ordering;
+#[cfg(test)] use super::base::Error;
+#[cfg(test)] use super::base::PrimitiveType;
+#[cfg(test)] use super::base::Value;
+
+#[cfg(test)]
+struct CountingContextListener {
+    slots_gained: AtomicUsize,
+    references_added: AtomicUsize
+}
+
+#[cfg(test)]
+impl ContextListener for CountingContextListener {
+
+    fn notify_slot_gained(&self, _value: Value) {
+        self.slots_gained.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn notify_value_reference_added(&self, _from: Value, _to: Value) {
+        self.references_added.fetch_add(1, Ordering::SeqCst);
+    }
+
+}
+
+#[test]
+fn test_context_builder() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let region_id = isolate.create_region()?;
+
+    let listener = Arc::new(CountingContextListener {
+        slots_gained: AtomicUsize::new(0),
+        references_added: AtomicUsize::new(0)
+    });
+
+    let context = ContextBuilder::new(isolate.clone())
+        .allocation_region(region_id)
+        .listener(listener.clone())
+        .build();
+
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    assert_eq!(object.get_region_id()?, region_id);
+    assert_eq!(listener.slots_gained.load(Ordering::SeqCst), 1);
+
+    let object_2 = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let symbol = context.get_text_symbol("test", "foo");
+    context.set_own_property(object, symbol, object_2, &context)?;
+    assert!(listener.references_added.load(Ordering::SeqCst) > 0);
+
+    let context = ContextBuilder::new(isolate.clone()).build();
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    assert_ne!(object.get_region_id()?, region_id);
+
+    Ok(())
+
+}
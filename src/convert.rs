@@ -0,0 +1,294 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::base::Value;
+use super::context::Context;
+use super::storage::Pinned;
+
+/// Conversion from rust data into the slot heap
+pub trait ToValue {
+
+    /// Convert into a value, slotted values are pinned until dropped
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error>;
+
+}
+
+/// Conversion from the slot heap into rust data
+pub trait FromValue: Sized {
+
+    /// Convert from a value, error will be returned if the type not match
+    fn from_value(value: Value, context: &Box<dyn Context>) -> Result<Self, Error>;
+
+}
+
+impl ToValue for Value {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, *self)
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value, _context: &Box<dyn Context>) -> Result<Self, Error> {
+        Ok(value)
+    }
+}
+
+impl ToValue for () {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, Value::make_undefined())
+    }
+}
+
+impl FromValue for () {
+    fn from_value(value: Value, _context: &Box<dyn Context>) -> Result<Self, Error> {
+        if !value.is_undefined() {
+            return Err(Error::new(TypeNotMatch, "Value is not undefined"));
+        }
+        Ok(())
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, Value::make_boolean(*self))
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value, _context: &Box<dyn Context>) -> Result<Self, Error> {
+        value.get_boolean_data()
+    }
+}
+
+impl ToValue for i32 {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, Value::make_integer(*self))
+    }
+}
+
+impl FromValue for i32 {
+    fn from_value(value: Value, _context: &Box<dyn Context>) -> Result<Self, Error> {
+        value.get_integer_data()
+    }
+}
+
+impl ToValue for u32 {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, Value::make_cardinal(*self))
+    }
+}
+
+impl FromValue for u32 {
+    fn from_value(value: Value, _context: &Box<dyn Context>) -> Result<Self, Error> {
+        value.get_cardinal_data()
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, Value::make_float(*self))
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value, _context: &Box<dyn Context>) -> Result<Self, Error> {
+        if value.is_integer() {
+            if value.is_cardinal() {
+                return Ok(value.get_cardinal_data()? as f64);
+            }
+            return Ok(value.get_integer_data()? as f64);
+        }
+        value.get_float_data()
+    }
+}
+
+impl ToValue for f32 {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        Pinned::new(context, Value::make_float(*self as f64))
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: Value, context: &Box<dyn Context>) -> Result<Self, Error> {
+        Ok(f64::from_value(value, context)? as f32)
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        context.make_text(self, context)
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        context.make_text(self, context)
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value, context: &Box<dyn Context>) -> Result<Self, Error> {
+        Ok(context.get_isolate().extract_text_slot(value, context)?.to_string())
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        match self {
+            Some(value) => value.to_value(context),
+            None => Pinned::new(context, Value::make_null())
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value, context: &Box<dyn Context>) -> Result<Self, Error> {
+        if value.is_nil() {
+            return Ok(None);
+        }
+        Ok(Some(T::from_value(value, context)?))
+    }
+}
+
+impl<T: ToValue> ToValue for [T] {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let mut pinneds = Vec::new();
+        for element in self.iter() {
+            pinneds.push(element.to_value(context)?);
+        }
+
+        context.make_list(pinneds.iter().map(|pinned| pinned.get_value()).collect(), context)
+
+    }
+}
+
+impl<T: ToValue> ToValue for Vec<T> {
+    fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        self.as_slice().to_value(context)
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value, context: &Box<dyn Context>) -> Result<Self, Error> {
+
+        if !value.is_list() {
+            return Err(Error::new(TypeNotMatch, "Value is not list"));
+        }
+
+        let mut result = Vec::new();
+        for element in context.extract_list(value, context)? {
+            let element = Pinned::new(context, element)?;
+            result.push(T::from_value(element.get_value(), context)?);
+        }
+
+        Ok(result)
+
+    }
+}
+
+macro_rules! impl_tuple_conversions {
+    ($length:expr, $($name:ident : $index:tt),+) => {
+
+        impl<$($name: ToValue),+> ToValue for ($($name,)+) {
+            fn to_value(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+                let pinneds = vec!($(self.$index.to_value(context)?),+);
+
+                let prototype = context.get_isolate().get_tuple_prototype();
+
+                context.make_tuple(prototype, 0, pinneds.iter().map(|pinned| pinned.get_value()).collect(), context)
+
+            }
+        }
+
+        impl<$($name: FromValue),+> FromValue for ($($name,)+) {
+            fn from_value(value: Value, context: &Box<dyn Context>) -> Result<Self, Error> {
+
+                let values = context.get_isolate().extract_tuple(value, context)?;
+                if values.len() != $length {
+                    return Err(Error::new(TypeNotMatch, "Tuple length not match"));
+                }
+
+                let pinneds = values.into_iter().map(|value| Pinned::new(context, value)).collect::<Result<Vec<Pinned>, Error>>()?;
+
+                Ok(($($name::from_value(pinneds[$index].get_value(), context)?,)+))
+
+            }
+        }
+
+    };
+}
+
+impl_tuple_conversions!(1, A: 0);
+impl_tuple_conversions!(2, A: 0, B: 1);
+impl_tuple_conversions!(3, A: 0, B: 1, C: 2);
+impl_tuple_conversions!(4, A: 0, B: 1, C: 2, D: 3);
+impl_tuple_conversions!(5, A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_tuple_conversions!(6, A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+
+#[cfg(test)] use std::sync::Arc;
+
+#[cfg(test)] use super::isolate::Isolate;
+#[cfg(test)] use super::test::TestContext2;
+
+#[test]
+fn test_convert_primitives() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let value = true.to_value(&context)?;
+    assert!(bool::from_value(value.get_value(), &context)?);
+
+    let value = (-42).to_value(&context)?;
+    assert_eq!(i32::from_value(value.get_value(), &context)?, -42);
+    assert_eq!(f64::from_value(value.get_value(), &context)?, -42.0);
+    assert!(bool::from_value(value.get_value(), &context).is_err());
+
+    let value = 1.5f64.to_value(&context)?;
+    assert_eq!(f64::from_value(value.get_value(), &context)?, 1.5);
+    assert!(i32::from_value(value.get_value(), &context).is_err());
+
+    let value = "foo".to_value(&context)?;
+    assert_eq!(&String::from_value(value.get_value(), &context)?, "foo");
+
+    let value = None::<i32>.to_value(&context)?;
+    assert_eq!(Option::<i32>::from_value(value.get_value(), &context)?, None);
+
+    let value = Some(3).to_value(&context)?;
+    assert_eq!(Option::<i32>::from_value(value.get_value(), &context)?, Some(3));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_convert_structures() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let data = vec!(
+        (String::from("foo"), 1, Some(vec!(true, false))),
+        (String::from("bar"), 2, None)
+    );
+
+    let value = isolate.convert_to_value(&data, &context)?;
+    assert!(value.get_value().is_list());
+
+    let result: Vec<(String, i32, Option<Vec<bool>>)> = isolate.convert_from_value(value.get_value(), &context)?;
+    assert_eq!(result, data);
+
+    assert!(isolate.convert_from_value::<Vec<(String, i32)>>(value.get_value(), &context).is_err());
+    assert!(isolate.convert_from_value::<(String, i32)>(value.get_value(), &context).is_err());
+
+    Ok(())
+
+}
@@ -0,0 +1,267 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::base::Error;
+use super::base::PrimitiveType;
+use super::base::Value;
+use super::context::Context;
+use super::isolate::Isolate;
+use super::storage::Pinned;
+
+/// Options to describe values for debugging
+#[derive(Clone)]
+pub struct DescribeOptions {
+    max_depth: usize,
+    max_items: usize
+}
+
+impl DescribeOptions {
+
+    /// Create options describing at most 4 levels of nested values, and at
+    /// most 32 items of each list, tuple or object
+    pub fn new() -> DescribeOptions {
+        DescribeOptions {
+            max_depth: 4,
+            max_items: 32
+        }
+    }
+
+    /// Limit levels of nested values described, deeper values are written
+    /// as `[...]`, `(...)` or `{...}`
+    pub fn max_depth(mut self, max_depth: usize) -> DescribeOptions {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Limit items described for each list, tuple or object, the count of
+    /// the others is written instead
+    pub fn max_items(mut self, max_items: usize) -> DescribeOptions {
+        self.max_items = max_items;
+        self
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn get_max_items(&self) -> usize {
+        self.max_items
+    }
+
+}
+
+/// Hook formatting values of a primitive type into texts, registered on the
+/// isolate and used when values define no `to_text` conversion
+pub trait TextFormatter {
+
+    fn format_text(&self, value: Value, context: &Box<dyn Context>) -> Result<String, Error>;
+
+}
+
+/// Describer rendering values into readable text, errors and cycles are
+/// written inline instead of failing the whole description
+pub struct ValueDescriber<'a> {
+    isolate: &'a Isolate,
+    context: &'a Box<dyn Context>,
+    options: &'a DescribeOptions,
+    visiting: Vec<Value>,
+    output: String
+}
+
+impl<'a> ValueDescriber<'a> {
+
+    pub fn new(isolate: &'a Isolate, context: &'a Box<dyn Context>, options: &'a DescribeOptions) -> ValueDescriber<'a> {
+        ValueDescriber {
+            isolate: isolate,
+            context: context,
+            options: options,
+            visiting: Vec::new(),
+            output: String::new()
+        }
+    }
+
+    /// Describe the value and get the text
+    pub fn describe(mut self, value: Value) -> String {
+        self.describe_value(value, 0);
+        self.output
+    }
+
+    fn describe_value(&mut self, value: Value, depth: usize) {
+        if let Err(error) = self.write_value(value, depth) {
+            self.output.push_str(&format!("<error: {}>", error.get_message()));
+        }
+    }
+
+    fn write_value(&mut self, value: Value, depth: usize) -> Result<(), Error> {
+
+        match value.get_primitive_type() {
+            PrimitiveType::Text => {
+                let text = self.isolate.extract_text_slot(value, self.context)?;
+                self.output.push_str(&format!("{:?}", text.to_string()));
+            },
+            PrimitiveType::List => {
+                if self.enter(value, depth, "[", "]") {
+                    let elements = self.isolate.extract_list(value, self.context);
+                    self.write_elements(elements, depth, "[", "]")?;
+                }
+            },
+            PrimitiveType::Tuple => {
+                if self.enter(value, depth, "(", ")") {
+                    let elements = self.isolate.extract_tuple(value, self.context);
+                    self.write_elements(elements, depth, "(", ")")?;
+                }
+            },
+            PrimitiveType::Object => {
+                if self.enter(value, depth, "{", "}") {
+                    let result = self.write_object(value, depth);
+                    self.leave();
+                    result?;
+                }
+            },
+            _ => {
+                self.output.push_str(&self.isolate.extract_text(value, self.context)?);
+            }
+        }
+
+        Ok(())
+
+    }
+
+    /// Enter a nested value, false will be returned with the value written
+    /// if it is a cycle, too deep, or over the recursion limit of the context
+    fn enter(&mut self, value: Value, depth: usize, open: &str, close: &str) -> bool {
+
+        if self.visiting.contains(&value) {
+            self.output.push_str("<cycle>");
+            return false;
+        }
+
+        if (depth >= self.options.max_depth) || self.context.enter_recursion().is_err() {
+            self.output.push_str(open);
+            self.output.push_str("...");
+            self.output.push_str(close);
+            return false;
+        }
+
+        self.visiting.push(value);
+
+        true
+
+    }
+
+    fn leave(&mut self) {
+        self.visiting.pop();
+        self.context.leave_recursion();
+    }
+
+    fn write_elements(&mut self, elements: Result<Vec<Value>, Error>, depth: usize, open: &str, close: &str) -> Result<(), Error> {
+
+        let elements = match elements {
+            Ok(elements) => elements,
+            Err(error) => {
+                self.leave();
+                return Err(error);
+            }
+        };
+
+        let mut pinneds = Vec::new();
+        for element in elements.iter().take(self.options.max_items) {
+            match Pinned::new(self.context, *element) {
+                Ok(pinned) => pinneds.push(pinned),
+                Err(error) => {
+                    self.leave();
+                    return Err(error);
+                }
+            }
+        }
+
+        self.output.push_str(open);
+        for (index, element) in pinneds.iter().enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+            self.describe_value(element.get_value(), depth + 1);
+        }
+        self.write_omitted(elements.len());
+        self.output.push_str(close);
+
+        self.leave();
+
+        Ok(())
+
+    }
+
+    fn write_object(&mut self, value: Value, depth: usize) -> Result<(), Error> {
+
+        let prototype_symbol = self.isolate.get_prototype_symbol();
+
+        let mut symbols = self.isolate.list_own_property_symbols(value, value, self.context)?;
+        symbols.retain(|symbol| *symbol != prototype_symbol);
+
+        self.output.push('{');
+        for (index, symbol) in symbols.iter().take(self.options.max_items).enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+            match self.isolate.resolve_symbol_info(*symbol)?.get_text() {
+                Some(text) => self.output.push_str(text),
+                None => self.output.push_str("<symbol>")
+            }
+            self.output.push_str(": ");
+            match self.isolate.get_own_property(value, value, *symbol, None, self.context) {
+                Ok(property) => self.describe_value(property.get_value(), depth + 1),
+                Err(error) => self.output.push_str(&format!("<error: {}>", error.get_message()))
+            }
+        }
+        self.write_omitted(symbols.len());
+        self.output.push('}');
+
+        Ok(())
+
+    }
+
+    fn write_omitted(&mut self, count: usize) {
+        if count > self.options.max_items {
+            if self.options.max_items > 0 {
+                self.output.push_str(", ");
+            }
+            self.output.push_str(&format!("<{} more>", count - self.options.max_items));
+        }
+    }
+
+}
+
+/// Adapter displaying a value through `fmt::Display` with a description
+///
+/// The value should be kept alive while displayed
+pub struct DisplayValue<'a> {
+    isolate: &'a Isolate,
+    value: Value,
+    context: &'a Box<dyn Context>,
+    options: DescribeOptions
+}
+
+impl<'a> DisplayValue<'a> {
+
+    pub fn new(isolate: &'a Isolate, value: Value, context: &'a Box<dyn Context>, options: DescribeOptions) -> DisplayValue<'a> {
+        DisplayValue {
+            isolate: isolate,
+            value: value,
+            context: context,
+            options: options
+        }
+    }
+
+}
+
+impl<'a> fmt::Display for DisplayValue<'a> {
+
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&ValueDescriber::new(self.isolate, self.context, &self.options).describe(self.value))
+    }
+
+}
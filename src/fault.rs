@@ -0,0 +1,33 @@
+use super::base::PrimitiveType;
+use super::base::Value;
+
+/// Access of a lock acquired
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum LockAccess {
+    Read,
+    Write
+}
+
+/// Hook registered on an isolate to inject faults, for testing error
+/// handlings of embedders around heap exhaustion and lock contention
+///
+/// The injector is asked while slot layouts are possibly locked, so it
+/// should not access values of the isolate
+pub trait FaultInjector: Send + Sync {
+
+    /// Fail gaining a slot in the region with `OutOfSpace` if true returned
+    fn fail_gain_slot(&self, _region_id: u32, _primitive_type: PrimitiveType) -> bool {
+        false
+    }
+
+    /// Fail restoring the slot moved into the region if true returned, the
+    /// slot is kept untouched in its region
+    fn fail_restore_slot(&self, _from: Value, _to_region_id: u32) -> bool {
+        false
+    }
+
+    /// Called before the slot layout lock is acquired blocking, and the
+    /// acquisition is delayed until the call returns
+    fn delay_lock(&self, _access: LockAccess) {}
+
+}
@@ -1,7 +1,12 @@
-use std::cell::Cell;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::sync::Arc;
+use core::cell::Cell;
+use core::cell::RefCell;
+use core::sync::atomic::AtomicU16;
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+use core::sync::atomic::fence;
+use super::util::HashMap;
+use alloc::sync::Arc;
 
 use super::base::Error;
 use super::base::ErrorType::*;
@@ -11,16 +16,25 @@ use super::util::RwLock;
 
 const MAX_SHORTCUTS_SIZE: usize = 26; // make the field shortcuts 256 byte size
 
+/// Field token caching the index of a symbol in a field template, the
+/// version and the index are packed in one atomic, so that they are always
+/// read in pair without locking
 pub struct FieldToken {
-    rw_lock: RwLock,
     template: u32,
-    version: Cell<u16>,
-    index: Cell<u8>,
+    state: AtomicU32,
     symbol: Symbol
 }
 
 impl FieldToken {
 
+    fn new(template: u32, version: u16, index: u8, symbol: Symbol) -> FieldToken {
+        FieldToken {
+            template: template,
+            state: AtomicU32::new(pack_field_state(version, index)),
+            symbol: symbol
+        }
+    }
+
     pub fn get_template(&self) -> u32 {
         self.template
     }
@@ -30,21 +44,22 @@ impl FieldToken {
     }
 
     pub fn get_version(&self) -> u16 {
-        let _guard = self.rw_lock.lock_read();
-        self.version.get()
+        (self.state.load(Ordering::Acquire) >> 8) as u16
     }
 
     pub fn get_index(&self) -> u8 {
-        let _guard = self.rw_lock.lock_read();
-        self.index.get()
+        self.state.load(Ordering::Acquire) as u8
+    }
+
+    fn refresh(&self, version: u16, index: u8) {
+        self.state.store(pack_field_state(version, index), Ordering::Release);
     }
 
     pub fn get_field(&self, field_shortcuts: &Arc<FieldShortcuts>) -> Option<Value> {
 
-        let (result, need_update) = {
-            let _guard = self.rw_lock.lock_read();
-            field_shortcuts.get_field(self.template, self.version.get(), self.index.get())
-        };
+        let state = self.state.load(Ordering::Acquire);
+
+        let (result, need_update) = field_shortcuts.get_field(self.template, (state >> 8) as u16, state as u8);
 
         if need_update {
             field_shortcuts.refresh_field_token(self);
@@ -54,12 +69,22 @@ impl FieldToken {
 
     }
 
+    /// Read the field without locking the field shortcuts, `None` will be
+    /// returned if the field is not cached or the shortcuts are modified
+    /// meanwhile, and the read should fall back to `get_field`
+    pub fn get_field_optimistically(&self, field_shortcuts: &FieldShortcuts) -> Option<Value> {
+
+        let state = self.state.load(Ordering::Acquire);
+
+        field_shortcuts.get_field_optimistically(self.template, (state >> 8) as u16, state as u8)
+
+    }
+
     pub fn set_field(&self, field_shortcuts: &Arc<FieldShortcuts>, value: Value) {
 
-        let need_update = {
-            let _guard = self.rw_lock.lock_read();
-            field_shortcuts.set_field(self.template, self.version.get(), self.index.get(), value)
-        };
+        let state = self.state.load(Ordering::Acquire);
+
+        let need_update = field_shortcuts.set_field(self.template, (state >> 8) as u16, state as u8, value);
 
         if need_update {
             field_shortcuts.refresh_field_token(self);
@@ -69,6 +94,11 @@ impl FieldToken {
 
 }
 
+#[inline]
+fn pack_field_state(version: u16, index: u8) -> u32 {
+    ((version as u32) << 8) | (index as u32)
+}
+
 pub struct FieldTemplate {
     rw_lock: RwLock,
     id: u32, 
@@ -103,13 +133,7 @@ impl FieldTemplate {
 
         match self.fields.borrow().get(&symbol) {
             None => None,
-            Some(index) => Some(FieldToken {
-                rw_lock: RwLock::new(),
-                template: self.id,
-                version: Cell::new(self.version.get()),
-                index: Cell::new(*index),
-                symbol: symbol
-            })
+            Some(index) => Some(FieldToken::new(self.id, self.version.get(), *index, symbol))
         }
         
     }
@@ -120,9 +144,7 @@ impl FieldTemplate {
 
         match self.fields.borrow().get(&field_token.symbol) {
             Some(index) => {
-                let _guard = field_token.rw_lock.lock_write();
-                field_token.version.set(self.version.get());
-                field_token.index.set(*index);
+                field_token.refresh(self.version.get(), *index);
             },
             None => {}
         }
@@ -208,12 +230,42 @@ impl FieldTemplate {
 
 }
 
+/// Fields cached for a slot, the fields are stored in atomics and the
+/// sequence stays odd while they are modified under the lock, so that they
+/// could be read optimistically without the lock
 pub struct FieldShortcuts {
     rw_lock: RwLock,
-    version: Cell<u16>,
+    sequence: AtomicU32,
+    template_id: AtomicU32,
+    version: AtomicU16,
     template: RefCell<Arc<FieldTemplate>>,
-    bitmap: Cell<u64>,
-    fields: RefCell<[Value; MAX_SHORTCUTS_SIZE]>
+    bitmap: AtomicU64,
+    fields: [AtomicU64; MAX_SHORTCUTS_SIZE]
+}
+
+/// Modification of field shortcuts, the sequence stays odd until the guard
+/// dropped
+struct FieldShortcutsWriteGuard<'a> {
+    sequence: &'a AtomicU32
+}
+
+impl<'a> FieldShortcutsWriteGuard<'a> {
+
+    fn new(sequence: &'a AtomicU32) -> FieldShortcutsWriteGuard<'a> {
+        sequence.fetch_add(1, Ordering::SeqCst);
+        FieldShortcutsWriteGuard {
+            sequence: sequence
+        }
+    }
+
+}
+
+impl<'a> Drop for FieldShortcutsWriteGuard<'a> {
+
+    fn drop(&mut self) {
+        self.sequence.fetch_add(1, Ordering::SeqCst);
+    }
+
 }
 
 impl FieldShortcuts {
@@ -222,10 +274,12 @@ impl FieldShortcuts {
 
         FieldShortcuts {
             rw_lock: RwLock::new(),
-            version: Cell::new(template.get_version()),
+            sequence: AtomicU32::new(0),
+            template_id: AtomicU32::new(template.get_id()),
+            version: AtomicU16::new(template.get_version()),
             template: RefCell::new(template),
-            bitmap: Cell::new(0u64),
-            fields: RefCell::new([Value::make_undefined(); MAX_SHORTCUTS_SIZE])
+            bitmap: AtomicU64::new(0u64),
+            fields: core::array::from_fn(|_| AtomicU64::new(Value::make_undefined().to_bits()))
         }
 
     }
@@ -234,7 +288,26 @@ impl FieldShortcuts {
 
         let _guard = self.rw_lock.lock_write();
 
-        self.bitmap.set(0u64);
+        let _write_guard = FieldShortcutsWriteGuard::new(&self.sequence);
+
+        self.bitmap.store(0u64, Ordering::Relaxed);
+
+    }
+
+    /// Duplicate the field shortcuts for a forked isolate
+    pub fn duplicate(&self) -> FieldShortcuts {
+
+        let _guard = self.rw_lock.lock_read();
+
+        FieldShortcuts {
+            rw_lock: RwLock::new(),
+            sequence: AtomicU32::new(0),
+            template_id: AtomicU32::new(self.template_id.load(Ordering::Relaxed)),
+            version: AtomicU16::new(self.version.load(Ordering::Relaxed)),
+            template: RefCell::new(self.template.borrow().clone()),
+            bitmap: AtomicU64::new(self.bitmap.load(Ordering::Relaxed)),
+            fields: core::array::from_fn(|index| AtomicU64::new(self.fields[index].load(Ordering::Relaxed)))
+        }
 
     }
 
@@ -264,9 +337,7 @@ impl FieldShortcuts {
 
     pub fn get_field_template_id(&self) -> u32 {
 
-        let _guard = self.rw_lock.lock_read();
-
-        self.template.borrow().get_id()
+        self.template_id.load(Ordering::Acquire)
 
     }
 
@@ -274,15 +345,19 @@ impl FieldShortcuts {
 
         let _guard = self.rw_lock.lock_write();
 
+        let _write_guard = FieldShortcutsWriteGuard::new(&self.sequence);
+
         {
             let _guard_2 = template.rw_lock.lock_read();
             let version = template.version.get();
-            self.version.set(version);
+            self.version.store(version, Ordering::Relaxed);
         }
 
+        self.template_id.store(template.get_id(), Ordering::Relaxed);
+
         *self.template.borrow_mut() = template;
 
-        self.bitmap.set(0u64);
+        self.bitmap.store(0u64, Ordering::Relaxed);
 
     }
 
@@ -305,40 +380,75 @@ impl FieldShortcuts {
             }
             let _guard_2 = self_template.rw_lock.lock_read();
             let template_version = self_template.version.get();
-            if self.version.get() != template_version {
-                self.bitmap.set(0u64);
-                self.version.set(template_version);
+            if self.version.load(Ordering::Relaxed) != template_version {
+                self.bitmap.store(0u64, Ordering::Relaxed);
+                self.version.store(template_version, Ordering::Relaxed);
                 return (None, true);
             }
             template_version
         };
 
         if (template_version == version) &&
-           ((self.bitmap.get() >> index) & 0b1 == 1) {
-            (Some(self.fields.borrow()[index as usize]), false)
+           ((self.bitmap.load(Ordering::Relaxed) >> index) & 0b1 == 1) {
+            (Some(Value::from_bits(self.fields[index as usize].load(Ordering::Relaxed))), false)
         } else {
             (None, false)
         }
 
     }
 
+    /// Read the field without the lock, `None` will be returned if the field
+    /// is not cached, or the shortcuts are modified meanwhile
+    ///
+    /// The version of the template is not checked, fields cached for an
+    /// older version stay valid until the shortcuts modified, since every
+    /// modification synchronizes the version first
+    pub fn get_field_optimistically(&self, template: u32, version: u16, index: u8) -> Option<Value> {
+
+        let sequence = self.sequence.load(Ordering::Acquire);
+        if sequence & 1 != 0 {
+            return None;
+        }
+
+        let field = self.fields.get(index as usize)?;
+
+        let result = if (self.template_id.load(Ordering::Relaxed) == template) &&
+           (self.version.load(Ordering::Relaxed) == version) &&
+           ((self.bitmap.load(Ordering::Relaxed) >> index) & 0b1 == 1) {
+            Some(Value::from_bits(field.load(Ordering::Relaxed)))
+        } else {
+            None
+        };
+
+        fence(Ordering::Acquire);
+
+        if self.sequence.load(Ordering::Relaxed) != sequence {
+            return None;
+        }
+
+        result
+
+    }
+
     pub fn set_symbol_field(&self, symbol: Symbol, value: Value) {
 
         let _guard = self.rw_lock.lock_write();
 
+        let _write_guard = FieldShortcutsWriteGuard::new(&self.sequence);
+
         let self_template = self.template.borrow();
 
         let _guard_2 = self_template.rw_lock.lock_read();
 
         let template_version = self_template.version.get();
-        if self.version.get() != template_version {
-            self.bitmap.set(0u64);
-            self.version.set(template_version);
+        if self.version.load(Ordering::Relaxed) != template_version {
+            self.bitmap.store(0u64, Ordering::Relaxed);
+            self.version.store(template_version, Ordering::Relaxed);
         }
 
         if let Some(index) = self_template.get_symbol_index(symbol) {
-            self.bitmap.set(self.bitmap.get() | (1 << index));
-            self.fields.borrow_mut()[index as usize] = value;
+            self.fields[index as usize].store(value.to_bits(), Ordering::Relaxed);
+            self.bitmap.fetch_or(1 << index, Ordering::Relaxed);
         }
 
     }
@@ -347,25 +457,28 @@ impl FieldShortcuts {
 
         let _guard = self.rw_lock.lock_write();
 
+        let self_template = self.template.borrow();
+        if self_template.get_id() != template {
+            return false;
+        }
+
+        let _write_guard = FieldShortcutsWriteGuard::new(&self.sequence);
+
         let mut need_update = false;
         let template_version = {
-            let self_template = self.template.borrow();
-            if self_template.get_id() != template {
-                return false;
-            }
             let _guard_2 = self_template.rw_lock.lock_read();
             let template_version = self_template.version.get();
-            if self.version.get() != template_version {
+            if self.version.load(Ordering::Relaxed) != template_version {
                 need_update = true;
-                self.bitmap.set(0u64);
-                self.version.set(template_version);
+                self.bitmap.store(0u64, Ordering::Relaxed);
+                self.version.store(template_version, Ordering::Relaxed);
             }
             template_version
         };
 
         if version == template_version {
-            self.bitmap.set(self.bitmap.get() | (1 << index));
-            self.fields.borrow_mut()[index as usize] = value;
+            self.fields[index as usize].store(value.to_bits(), Ordering::Relaxed);
+            self.bitmap.fetch_or(1 << index, Ordering::Relaxed);
         }
 
         need_update
@@ -375,20 +488,20 @@ impl FieldShortcuts {
 
         let _guard = self.rw_lock.lock_write();
 
-        let index = self.template.borrow().get_symbol_index(symbol);
-        match index {
-            Some(index) => {
-                let self_template = self.template.borrow();
-                let _guard_2 = self_template.rw_lock.lock_read();
-                let template_version = self_template.version.get();
-                if self.version.get() != template_version {
-                    self.bitmap.set(0u64);
-                    self.version.set(template_version);
-                } else {
-                    self.bitmap.set(self.bitmap.get() & (!(1 << index)));
-                }
-            },
-            None => {}
+        let _write_guard = FieldShortcutsWriteGuard::new(&self.sequence);
+
+        let self_template = self.template.borrow();
+        let index = self_template.get_symbol_index(symbol);
+
+        let _guard_2 = self_template.rw_lock.lock_read();
+        let template_version = self_template.version.get();
+        if self.version.load(Ordering::Relaxed) != template_version {
+            // symbols removed from the template are cleared too, since
+            // fields of an older version could still be read optimistically
+            self.bitmap.store(0u64, Ordering::Relaxed);
+            self.version.store(template_version, Ordering::Relaxed);
+        } else if let Some(index) = index {
+            self.bitmap.fetch_and(!(1 << index), Ordering::Relaxed);
         }
 
     }
@@ -435,10 +548,11 @@ fn test_field_template_symbol() -> Result<(), Error> {
 
 }
 
+#[cfg(not(any(feature = "lock-diagnostics", feature = "lock-stats")))]
 #[test]
 fn test_field_shortcuts_size() {
 
-    assert_eq!(std::mem::size_of::<FieldShortcuts>(), 256);
+    assert_eq!(std::mem::size_of::<FieldShortcuts>(), 264);
 
 }
 
@@ -532,4 +646,40 @@ fn test_field_token() -> Result<(), Error> {
     assert!(field_token.get_field(&field_shortcuts).is_none());
 
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_field_token_optimistic_read() -> Result<(), Error> {
+
+    let template = Arc::new(FieldTemplate::new(1));
+    template.add_symbol(Symbol::new(1))?;
+
+    let field_shortcuts = Arc::new(FieldShortcuts::new(template.clone()));
+
+    let field_token = template.get_field_token(Symbol::new(1)).unwrap();
+
+    assert!(field_token.get_field_optimistically(&field_shortcuts).is_none());
+
+    field_token.set_field(&field_shortcuts, Value::make_float(23.4));
+    assert_eq!(field_token.get_field_optimistically(&field_shortcuts), Some(Value::make_float(23.4)));
+
+    {
+        let _write_guard = FieldShortcutsWriteGuard::new(&field_shortcuts.sequence);
+        assert!(field_token.get_field_optimistically(&field_shortcuts).is_none());
+    }
+    assert_eq!(field_token.get_field_optimistically(&field_shortcuts), Some(Value::make_float(23.4)));
+
+    template.add_symbol(Symbol::new(2))?;
+    template.remove_symbol(Symbol::new(2))?;
+    field_shortcuts.clear_field(Symbol::new(2));
+    assert!(field_token.get_field_optimistically(&field_shortcuts).is_none());
+
+    field_shortcuts.refresh_field_token(&field_token);
+    field_token.set_field(&field_shortcuts, Value::make_float(32.0));
+    assert_eq!(field_token.get_field_optimistically(&field_shortcuts), Some(Value::make_float(32.0)));
+
+    field_shortcuts.update_field_template(Arc::new(FieldTemplate::new(2)));
+    assert!(field_token.get_field_optimistically(&field_shortcuts).is_none());
+
+    Ok(())
+
+}
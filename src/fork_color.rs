@@ -0,0 +1,159 @@
+//! Colors of slots in regions shared copy-on-write after forking, kept by
+//! each isolate so that marking never copies a shared region
+
+use core::cell::RefCell;
+
+use super::base::Value;
+use super::util::HashMap;
+use super::util::hash_map::Entry;
+use super::util::HashSet;
+use super::util::RwLock;
+
+/// Color of a slot marked in the current collection, slots not marked yet
+/// are white
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForkColor {
+    Gray,
+    Black
+}
+
+/// Colors of slots in regions forked copy-on-write, the colors are written
+/// back into the region once it is copied for a mutation
+pub struct ForkColors {
+    rw_lock: RwLock,
+    region_ids: RefCell<HashSet<u32>>,
+    colors: RefCell<HashMap<Value, ForkColor>>
+}
+
+impl ForkColors {
+
+    pub fn new() -> ForkColors {
+        ForkColors {
+            rw_lock: RwLock::new(),
+            region_ids: RefCell::new(HashSet::new()),
+            colors: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Keep colors of the regions outside them from now on
+    pub fn track_regions(&self, region_ids: &[u32]) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.region_ids.borrow_mut().extend(region_ids.iter().copied());
+
+    }
+
+    pub fn is_region_tracked(&self, region_id: u32) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.region_ids.borrow().contains(&region_id)
+
+    }
+
+    /// Stop tracking the region, colors of its slots are returned to be
+    /// written back into the region
+    pub fn untrack_region(&self, region_id: u32) -> Option<HashMap<Value, ForkColor>> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if !self.region_ids.borrow_mut().remove(&region_id) {
+            return None;
+        }
+
+        let mut colors = self.colors.borrow_mut();
+
+        let values: alloc::vec::Vec<Value> = colors.keys().filter(|value| {
+            value.get_region_id().ok() == Some(region_id)
+        }).copied().collect();
+
+        Some(values.into_iter().filter_map(|value| {
+            colors.remove(&value).map(|color| (value, color))
+        }).collect())
+
+    }
+
+    pub fn get_color(&self, value: Value) -> Option<ForkColor> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.colors.borrow().get(&value).copied()
+
+    }
+
+    pub fn mark_as_white(&self, value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.colors.borrow_mut().remove(&value);
+
+    }
+
+    pub fn mark_as_black(&self, value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.colors.borrow_mut().insert(value, ForkColor::Black);
+
+    }
+
+    /// Mark the slot as gray if it is white, whether it was marked is
+    /// returned
+    pub fn mark_as_gray(&self, value: Value) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        match self.colors.borrow_mut().entry(value) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(ForkColor::Gray);
+                true
+            }
+        }
+
+    }
+
+    /// Forget colors once the collection finished, all slots become white
+    /// for the next collection
+    pub fn clear_colors(&self) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.colors.borrow_mut().clear();
+
+    }
+
+}
+
+#[test]
+fn test_fork_colors() {
+
+    let colors = ForkColors::new();
+
+    let value = Value::make_object(1, 1);
+    let value_2 = Value::make_object(2, 1);
+
+    colors.track_regions(&[1, 2]);
+    assert!(colors.is_region_tracked(1));
+    assert!(!colors.is_region_tracked(3));
+
+    assert!(colors.mark_as_gray(value));
+    assert!(!colors.mark_as_gray(value));
+    assert_eq!(colors.get_color(value), Some(ForkColor::Gray));
+    colors.mark_as_black(value);
+    colors.mark_as_black(value_2);
+    assert_eq!(colors.get_color(value), Some(ForkColor::Black));
+
+    let region_colors = colors.untrack_region(1).unwrap();
+    assert_eq!(region_colors.len(), 1);
+    assert_eq!(region_colors.get(&value), Some(&ForkColor::Black));
+    assert!(!colors.is_region_tracked(1));
+    assert!(colors.untrack_region(1).is_none());
+    assert_eq!(colors.get_color(value), None);
+
+    colors.clear_colors();
+    assert_eq!(colors.get_color(value_2), None);
+    assert!(colors.is_region_tracked(2));
+
+}
@@ -0,0 +1,459 @@
+//! Random object graphs for fuzzing garbage collections and traps, available
+//! with the `fuzzing` feature
+//!
+//! Graphs are built by logs of `GraphOperation` generated by `arbitrary`,
+//! node and symbol indices wrap around the nodes and symbols created, and
+//! operations not applicable are skipped, so any operation could be removed
+//! from a log while shrinking without invalidating the others
+
+use std::format;
+use std::sync::Arc;
+use std::vec::Vec;
+
+use arbitrary::Arbitrary;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::base::PrimitiveType;
+use super::base::Symbol;
+use super::base::Value;
+use super::collector::Collector;
+use super::context::Context;
+use super::isolate::Isolate;
+use super::root::Root;
+use super::root::WeakRoot;
+use super::standard_context::StandardContext;
+use super::test::RecordingPropertyTrap;
+use super::test::TestInternalSlot;
+use super::util::HashMap;
+
+/// Count of symbols properties are set with
+const GRAPH_SYMBOL_COUNT: usize = 8;
+
+/// Count of nodes a graph holds at most, so indices fit in `u8`
+const GRAPH_NODE_LIMIT: usize = 256;
+
+/// Count of internal slots set on nodes, index 0 is left for lists
+const GRAPH_INTERNAL_SLOT_COUNT: u8 = 3;
+
+/// Value used by graph operations, nodes are referred by indices
+#[derive(Arbitrary, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GraphValue {
+    Node(u8),
+    Integer(i32),
+    Boolean(bool),
+    Null,
+    Undefined
+}
+
+/// Operation building or mutating a graph
+#[derive(Arbitrary, Clone, Eq, PartialEq, Debug)]
+pub enum GraphOperation {
+    CreateObject { prototype: Option<u8> },
+    CreateList { elements: Vec<GraphValue> },
+    SetProperty { node: u8, symbol: u8, value: GraphValue },
+    DefineTrap { node: u8, symbol: u8, value: GraphValue },
+    DeleteProperty { node: u8, symbol: u8 },
+    SetPrototype { node: u8, prototype: Option<u8> },
+    SetInternalSlot { node: u8, index: u8, value: GraphValue },
+    PushElement { node: u8, value: GraphValue },
+    Root { node: u8 },
+    Unroot { node: u8 },
+    MoveOutFromNursery { node: u8 },
+    Collect { compact: bool }
+}
+
+/// Graph of nodes built by operations on an isolate
+///
+/// Nodes are tracked by weak roots, so nodes collected are detected, and
+/// operations on them are skipped
+pub struct ValueGraph {
+    isolate: Arc<Isolate>,
+    context: Box<dyn Context>,
+    symbols: Vec<Symbol>,
+    nodes: Vec<Arc<WeakRoot>>,
+    roots: HashMap<usize, Arc<Root>>,
+    applied_operations: Vec<GraphOperation>
+}
+
+impl ValueGraph {
+
+    pub fn new(isolate: Arc<Isolate>) -> ValueGraph {
+
+        let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+        let symbols = (0 .. GRAPH_SYMBOL_COUNT).map(|index| {
+            isolate.get_text_symbol("fuzzing", &format!("property{}", index))
+        }).collect();
+
+        ValueGraph {
+            isolate: isolate,
+            context: context,
+            symbols: symbols,
+            nodes: Vec::new(),
+            roots: HashMap::new(),
+            applied_operations: Vec::new()
+        }
+
+    }
+
+    pub fn get_context(&self) -> &Box<dyn Context> {
+        &self.context
+    }
+
+    pub fn count_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Count nodes not collected yet
+    pub fn count_alive_nodes(&self) -> usize {
+        self.nodes.iter().filter(|node| !node.is_dropped()).count()
+    }
+
+    /// Get the current value of a node, `None` will be returned if the node
+    /// is collected
+    pub fn get_node(&self, index: usize) -> Option<Value> {
+        self.nodes.get(index).and_then(|node| node.get_value())
+    }
+
+    /// List operations applied, with node indices wrapped, which replays
+    /// the same graph without skipped operations
+    pub fn list_applied_operations(&self) -> &[GraphOperation] {
+        &self.applied_operations
+    }
+
+    /// Apply operations in order, and verify the graph at the end
+    pub fn run(&mut self, operations: &[GraphOperation]) -> Result<(), Error> {
+
+        for operation in operations.iter() {
+            self.apply(operation)?;
+        }
+
+        self.verify()
+
+    }
+
+    /// Apply an operation, false will be returned if the operation is
+    /// skipped, errors rejecting the operation are ignored except fatal
+    /// errors
+    pub fn apply(&mut self, operation: &GraphOperation) -> Result<bool, Error> {
+
+        let applied = match self.apply_without_log(operation) {
+            Ok(Some(operation)) => Some(operation),
+            Ok(None) => None,
+            Err(error) => match error.get_error_type() {
+                FatalError => {
+                    return Err(error);
+                },
+                _ => None
+            }
+        };
+
+        match applied {
+            Some(operation) => {
+                self.applied_operations.push(operation);
+                Ok(true)
+            },
+            None => Ok(false)
+        }
+
+    }
+
+    fn apply_without_log(&mut self, operation: &GraphOperation) -> Result<Option<GraphOperation>, Error> {
+
+        let isolate = self.isolate.clone();
+        let context = &self.context;
+
+        match operation {
+            GraphOperation::CreateObject { prototype } => {
+                if self.nodes.len() >= GRAPH_NODE_LIMIT {
+                    return Ok(None);
+                }
+                let (prototype, prototype_value) = match prototype {
+                    None => (None, isolate.get_object_prototype()),
+                    Some(prototype) => match self.resolve_node(*prototype) {
+                        Some((index, value)) => (Some(index), value),
+                        None => {
+                            return Ok(None);
+                        }
+                    }
+                };
+                let value = context.gain_slot(PrimitiveType::Object, prototype_value)?;
+                self.add_node(value)?;
+                Ok(Some(GraphOperation::CreateObject { prototype: prototype }))
+            },
+            GraphOperation::CreateList { elements } => {
+                if self.nodes.len() >= GRAPH_NODE_LIMIT {
+                    return Ok(None);
+                }
+                let (elements, element_values) = self.resolve_values(elements);
+                let value = context.make_list(element_values, context)?;
+                self.add_node(value.get_value())?;
+                Ok(Some(GraphOperation::CreateList { elements: elements }))
+            },
+            GraphOperation::SetProperty { node, symbol, value } => {
+                let ((node, node_value), (value, property_value)) = match (self.resolve_node(*node), self.resolve_value(*value)) {
+                    (Some(node), Some(value)) => (node, value),
+                    _ => {
+                        return Ok(None);
+                    }
+                };
+                let symbol = *symbol as usize % GRAPH_SYMBOL_COUNT;
+                isolate.set_own_property(node_value, node_value, self.symbols[symbol], property_value, context)?;
+                Ok(Some(GraphOperation::SetProperty { node: node, symbol: symbol as u8, value: value }))
+            },
+            GraphOperation::DefineTrap { node, symbol, value } => {
+                let ((node, node_value), (value, trapped_value)) = match (self.resolve_node(*node), self.resolve_value(*value)) {
+                    (Some(node), Some(value)) => (node, value),
+                    _ => {
+                        return Ok(None);
+                    }
+                };
+                let symbol = *symbol as usize % GRAPH_SYMBOL_COUNT;
+                let property_trap = Arc::new(RecordingPropertyTrap::new(trapped_value));
+                isolate.define_own_property(node_value, node_value, self.symbols[symbol], property_trap, context)?;
+                Ok(Some(GraphOperation::DefineTrap { node: node, symbol: symbol as u8, value: value }))
+            },
+            GraphOperation::DeleteProperty { node, symbol } => {
+                let (node, node_value) = match self.resolve_node(*node) {
+                    Some(node) => node,
+                    None => {
+                        return Ok(None);
+                    }
+                };
+                let symbol = *symbol as usize % GRAPH_SYMBOL_COUNT;
+                isolate.delete_own_property(node_value, node_value, self.symbols[symbol], context)?;
+                Ok(Some(GraphOperation::DeleteProperty { node: node, symbol: symbol as u8 }))
+            },
+            GraphOperation::SetPrototype { node, prototype } => {
+                let (node, node_value) = match self.resolve_node(*node) {
+                    Some(node) => node,
+                    None => {
+                        return Ok(None);
+                    }
+                };
+                let (prototype, prototype_value) = match prototype {
+                    None => (None, Value::make_null()),
+                    Some(prototype) => match self.resolve_node(*prototype) {
+                        Some((index, value)) => (Some(index), value),
+                        None => {
+                            return Ok(None);
+                        }
+                    }
+                };
+                isolate.set_prototype(node_value, prototype_value, context)?;
+                Ok(Some(GraphOperation::SetPrototype { node: node, prototype: prototype }))
+            },
+            GraphOperation::SetInternalSlot { node, index, value } => {
+                let ((node, node_value), (value, referenced_value)) = match (self.resolve_node(*node), self.resolve_value(*value)) {
+                    (Some(node), Some(value)) => (node, value),
+                    _ => {
+                        return Ok(None);
+                    }
+                };
+                let index = index % GRAPH_INTERNAL_SLOT_COUNT;
+                let internal_slot = Arc::new(TestInternalSlot::new(referenced_value));
+                isolate.set_internal_slot(node_value, 1 + index as u64, internal_slot, context)?;
+                Ok(Some(GraphOperation::SetInternalSlot { node: node, index: index, value: value }))
+            },
+            GraphOperation::PushElement { node, value } => {
+                let ((node, node_value), (value, element_value)) = match (self.resolve_node(*node), self.resolve_value(*value)) {
+                    (Some(node), Some(value)) => (node, value),
+                    _ => {
+                        return Ok(None);
+                    }
+                };
+                isolate.list_push(node_value, element_value, context)?;
+                Ok(Some(GraphOperation::PushElement { node: node, value: value }))
+            },
+            GraphOperation::Root { node } => {
+                let (node, node_value) = match self.resolve_node(*node) {
+                    Some(node) => node,
+                    None => {
+                        return Ok(None);
+                    }
+                };
+                if self.roots.contains_key(&(node as usize)) {
+                    return Ok(None);
+                }
+                let root = isolate.add_root(node_value, context.get_slot_layout_token())?;
+                self.roots.insert(node as usize, root);
+                Ok(Some(GraphOperation::Root { node: node }))
+            },
+            GraphOperation::Unroot { node } => {
+                let (node, _) = match self.resolve_node(*node) {
+                    Some(node) => node,
+                    None => {
+                        return Ok(None);
+                    }
+                };
+                match self.roots.remove(&(node as usize)) {
+                    Some(root) => {
+                        isolate.remove_root(&root)?;
+                        Ok(Some(GraphOperation::Unroot { node: node }))
+                    },
+                    None => Ok(None)
+                }
+            },
+            GraphOperation::MoveOutFromNursery { node } => {
+                let (node, node_value) = match self.resolve_node(*node) {
+                    Some(node) => node,
+                    None => {
+                        return Ok(None);
+                    }
+                };
+                isolate.move_value_out_from_nursery(node_value, context.get_slot_layout_token())?;
+                Ok(Some(GraphOperation::MoveOutFromNursery { node: node }))
+            },
+            GraphOperation::Collect { compact } => {
+                if !Collector::new(&isolate).collect_for_stress(*compact)? {
+                    return Ok(None);
+                }
+                self.verify()?;
+                Ok(Some(GraphOperation::Collect { compact: *compact }))
+            }
+        }
+
+    }
+
+    /// Verify rooted nodes are not collected, and values referenced by
+    /// nodes alive through properties, prototypes and list elements are
+    /// alive too, and regions are consistent
+    pub fn verify(&self) -> Result<(), Error> {
+
+        self.isolate.verify_regions()?;
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let value = match node.get_value() {
+                Some(value) => value,
+                None => {
+                    if self.roots.contains_key(&index) {
+                        return Err(Error::new(FatalError, "Rooted node collected"));
+                    }
+                    continue;
+                }
+            };
+            for symbol in self.isolate.list_own_property_symbols(value, value, &self.context)? {
+                let property = self.isolate.get_own_property(value, value, symbol, None, &self.context)?;
+                self.verify_referenced_value(property.get_value())?;
+            }
+            self.verify_referenced_value(self.isolate.get_prototype(value, &self.context)?.get_value())?;
+            if value.get_primitive_type() == PrimitiveType::List {
+                for element in self.isolate.extract_list(value, &self.context)? {
+                    self.verify_referenced_value(element)?;
+                }
+            }
+        }
+
+        Ok(())
+
+    }
+
+    fn verify_referenced_value(&self, value: Value) -> Result<(), Error> {
+
+        if !value.is_slotted() {
+            return Ok(());
+        }
+
+        let value = self.isolate.resolve_real_value(value, self.context.get_slot_layout_token())?;
+        if !self.isolate.is_direct_value_alive(value, &self.context)? {
+            return Err(Error::new(FatalError, "Referenced value collected").with_value(value));
+        }
+
+        Ok(())
+
+    }
+
+    fn add_node(&mut self, value: Value) -> Result<(), Error> {
+
+        let node = self.isolate.add_weak_root(value, None, self.context.get_slot_layout_token())?;
+        self.nodes.push(node);
+
+        Ok(())
+
+    }
+
+    /// Resolve a node index wrapped into the nodes created, `None` will be
+    /// returned if no node created or the node is collected
+    fn resolve_node(&self, index: u8) -> Option<(u8, Value)> {
+
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let index = index as usize % self.nodes.len();
+
+        self.nodes[index].get_value().map(|value| (index as u8, value))
+
+    }
+
+    fn resolve_value(&self, value: GraphValue) -> Option<(GraphValue, Value)> {
+        match value {
+            GraphValue::Node(index) => self.resolve_node(index).map(|(index, value)| (GraphValue::Node(index), value)),
+            GraphValue::Integer(integer) => Some((value, Value::make_integer(integer))),
+            GraphValue::Boolean(boolean) => Some((value, Value::make_boolean(boolean))),
+            GraphValue::Null => Some((value, Value::make_null())),
+            GraphValue::Undefined => Some((value, Value::make_undefined()))
+        }
+    }
+
+    /// Resolve values skipping nodes not available
+    fn resolve_values(&self, values: &[GraphValue]) -> (Vec<GraphValue>, Vec<Value>) {
+        values.iter().filter_map(|value| self.resolve_value(*value)).unzip()
+    }
+
+}
+
+#[test]
+fn test_value_graph() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let mut graph = ValueGraph::new(isolate.clone());
+
+    assert!(!graph.apply(&GraphOperation::SetProperty { node: 0, symbol: 0, value: GraphValue::Null })?);
+
+    graph.run(&[
+        GraphOperation::CreateObject { prototype: None },
+        GraphOperation::CreateObject { prototype: Some(0) },
+        GraphOperation::CreateList { elements: vec![GraphValue::Node(1), GraphValue::Integer(3)] },
+        GraphOperation::CreateObject { prototype: None },
+        GraphOperation::SetProperty { node: 0, symbol: 9, value: GraphValue::Node(2) },
+        GraphOperation::DefineTrap { node: 1, symbol: 2, value: GraphValue::Node(3) },
+        GraphOperation::SetInternalSlot { node: 4, index: 5, value: GraphValue::Node(1) },
+        GraphOperation::PushElement { node: 2, value: GraphValue::Boolean(true) },
+        GraphOperation::PushElement { node: 0, value: GraphValue::Null },
+        GraphOperation::SetPrototype { node: 0, prototype: Some(1) },
+        GraphOperation::Root { node: 0 },
+        GraphOperation::MoveOutFromNursery { node: 0 },
+        GraphOperation::MoveOutFromNursery { node: 1 },
+        GraphOperation::MoveOutFromNursery { node: 2 },
+        GraphOperation::MoveOutFromNursery { node: 3 },
+        GraphOperation::Collect { compact: true }
+    ])?;
+
+    assert_eq!(graph.count_nodes(), 4);
+    assert_eq!(graph.count_alive_nodes(), 4);
+    assert_eq!(graph.list_applied_operations()[4], GraphOperation::SetProperty { node: 0, symbol: 1, value: GraphValue::Node(2) });
+    assert_eq!(graph.list_applied_operations()[6], GraphOperation::SetInternalSlot { node: 0, index: 2, value: GraphValue::Node(1) });
+    assert_eq!(graph.list_applied_operations().len(), 14);
+
+    graph.run(&[
+        GraphOperation::Unroot { node: 0 },
+        GraphOperation::Collect { compact: false }
+    ])?;
+    assert_eq!(graph.count_alive_nodes(), 0);
+    assert!(graph.get_node(0).is_none());
+
+    let bytes: Vec<u8> = (0 .. 4096u32).map(|index| (index.wrapping_mul(2654435761) >> 13) as u8).collect();
+    let mut unstructured = arbitrary::Unstructured::new(&bytes);
+    let operations: Vec<GraphOperation> = unstructured.arbitrary().unwrap();
+    let mut graph = ValueGraph::new(isolate.clone());
+    graph.run(&operations)?;
+    let mut replayed_graph = ValueGraph::new(isolate);
+    replayed_graph.run(graph.list_applied_operations())?;
+    assert_eq!(replayed_graph.list_applied_operations(), graph.list_applied_operations());
+
+    Ok(())
+
+}
@@ -0,0 +1,212 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+#[cfg(feature = "std")] use std::time::Instant;
+
+use super::base::Value;
+use super::util::RwLock;
+
+/// Phases of a garbage collection
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GcPhase {
+
+    /// Values reachable from roots are marked
+    Mark,
+
+    /// Values not marked are recycled
+    Sweep,
+
+    /// Slots are moved out of fragmented regions, and empty regions are
+    /// recycled
+    Compact
+
+}
+
+/// Statistics of a finished phase of a garbage collection
+///
+/// The count is the values marked for `Mark`, the slots recycled for
+/// `Sweep`, and the regions recycled for `Compact`
+#[derive(Copy, Clone, Debug)]
+pub struct GcPhaseStats {
+    phase: GcPhase,
+    count: usize,
+    elapsed: Option<Duration>
+}
+
+impl GcPhaseStats {
+
+    pub fn new(phase: GcPhase, count: usize, elapsed: Option<Duration>) -> GcPhaseStats {
+        GcPhaseStats {
+            phase: phase,
+            count: count,
+            elapsed: elapsed
+        }
+    }
+
+    pub fn get_phase(&self) -> GcPhase {
+        self.phase
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    /// Get time spent in the phase, only measured with the `std` feature
+    pub fn get_elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+}
+
+/// Statistics of a finished garbage collection
+#[derive(Copy, Clone, Debug)]
+pub struct GcStats {
+    live_slots_before: usize,
+    live_slots_after: usize,
+    elapsed: Option<Duration>
+}
+
+impl GcStats {
+
+    pub fn new(live_slots_before: usize, live_slots_after: usize, elapsed: Option<Duration>) -> GcStats {
+        GcStats {
+            live_slots_before: live_slots_before,
+            live_slots_after: live_slots_after,
+            elapsed: elapsed
+        }
+    }
+
+    pub fn get_live_slots_before(&self) -> usize {
+        self.live_slots_before
+    }
+
+    pub fn get_live_slots_after(&self) -> usize {
+        self.live_slots_after
+    }
+
+    /// Count slots recycled by the collection
+    pub fn count_recycled_slots(&self) -> usize {
+        self.live_slots_before.saturating_sub(self.live_slots_after)
+    }
+
+    /// Get time spent in the collection, only measured with the `std`
+    /// feature
+    pub fn get_elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+}
+
+/// Listener of garbage collections of an isolate, for logging and exporting
+///
+/// Listeners are notified inside the collection with slot layouts possibly
+/// locked, so they should not access values of the isolate
+pub trait GcListener {
+
+    fn notify_gc_started(&self) {}
+
+    fn notify_gc_finished(&self, _stats: &GcStats) {}
+
+    fn notify_gc_phase_started(&self, _phase: GcPhase) {}
+
+    fn notify_gc_phase_finished(&self, _stats: &GcPhaseStats) {}
+
+    /// Notified once a value is moved out from the nursery
+    fn notify_value_promoted(&self, _value: Value) {}
+
+}
+
+/// Listeners of garbage collections registered on an isolate
+pub struct GcListeners {
+    rw_lock: RwLock,
+    next_id: AtomicU64,
+    listeners: RefCell<Vec<(u64, Arc<dyn GcListener>)>>
+}
+
+impl GcListeners {
+
+    pub fn new() -> GcListeners {
+        GcListeners {
+            rw_lock: RwLock::new(),
+            next_id: AtomicU64::new(0),
+            listeners: RefCell::new(Vec::new())
+        }
+    }
+
+    pub fn add(&self, listener: Arc<dyn GcListener>) -> u64 {
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.listeners.borrow_mut().push((id, listener));
+
+        id
+
+    }
+
+    pub fn remove(&self, id: u64) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut listeners = self.listeners.borrow_mut();
+        let count = listeners.len();
+        listeners.retain(|(listener_id, _)| *listener_id != id);
+
+        listeners.len() != count
+
+    }
+
+    pub fn is_empty(&self) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.listeners.borrow().is_empty()
+
+    }
+
+    /// Notify all listeners in the order they were added, listeners are
+    /// copied before notified so they could be added or removed meanwhile
+    pub fn notify<F>(&self, notify: F) where F: Fn(&Arc<dyn GcListener>) {
+
+        let listeners: Vec<Arc<dyn GcListener>> = {
+            let _guard = self.rw_lock.lock_read();
+            self.listeners.borrow().iter().map(|(_, listener)| listener.clone()).collect()
+        };
+
+        for listener in listeners.iter() {
+            notify(listener);
+        }
+
+    }
+
+}
+
+/// Stopwatch measuring time of collections, nothing is measured without the
+/// `std` feature
+pub struct GcStopwatch {
+    #[cfg(feature = "std")] started: Instant
+}
+
+impl GcStopwatch {
+
+    pub fn start() -> GcStopwatch {
+        GcStopwatch {
+            #[cfg(feature = "std")] started: Instant::now()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn elapsed(&self) -> Option<Duration> {
+        Some(self.started.elapsed())
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn elapsed(&self) -> Option<Duration> {
+        None
+    }
+
+}
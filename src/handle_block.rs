@@ -0,0 +1,269 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::cell::RefMut;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+use super::base::Value;
+use super::root::Root;
+use super::util::LockClass;
+use super::util::RwLock;
+use super::util::RwLockWriteGuard;
+
+/// Count of roots a handle block holds before flushed into the shared root
+/// table
+pub const HANDLE_BLOCK_CAPACITY: usize = 256;
+
+static NEXT_HANDLE_BLOCKS_ID: AtomicU64 = AtomicU64::new(1);
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static THREAD_HANDLE_BLOCKS: RefCell<Vec<(u64, Arc<HandleBlock>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Block of roots added by a thread, only locked by the thread itself except
+/// at safepoints where roots of all blocks are flushed
+pub struct HandleBlock {
+    rw_lock: RwLock,
+    roots: RefCell<Vec<Arc<Root>>>
+}
+
+impl HandleBlock {
+
+    fn new() -> HandleBlock {
+        HandleBlock {
+            rw_lock: RwLock::with_class(LockClass::Root),
+            roots: RefCell::new(Vec::new())
+        }
+    }
+
+    pub fn lock_write(&self) -> RwLockWriteGuard<'_> {
+        self.rw_lock.lock_write()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, Vec<Arc<Root>>> {
+        self.roots.borrow_mut()
+    }
+
+    /// Add a root into the block, count of roots in the block is returned
+    pub fn push(&self, root: Arc<Root>) -> usize {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut roots = self.roots.borrow_mut();
+        roots.push(root);
+
+        roots.len()
+
+    }
+
+    /// Find a root in the block, roots added later are found first as they
+    /// are usually removed first
+    pub fn position(roots: &[Arc<Root>], root: &Arc<Root>) -> Option<usize> {
+        roots.iter().rposition(|block_root| Arc::ptr_eq(block_root, root))
+    }
+
+    fn count_references(&self, value: Value) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.roots.borrow().iter().filter(|root| root.get_value() == value).map(|root| root.get_references() as usize).sum()
+
+    }
+
+    fn list_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.roots.borrow().iter().filter(|root| !root.is_released() && !root.is_alone()).map(|root| root.get_value()).collect()
+
+    }
+
+    fn is_empty(&self) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.roots.borrow().is_empty()
+
+    }
+
+}
+
+/// Handle blocks of threads adding roots into an isolate, a thread registers
+/// its block once, and roots added later only lock the block of the thread
+///
+/// Blocks are held by both the thread and the isolate, so roots left in the
+/// block of an exited thread are kept until flushed, and the block is pruned
+/// once it is empty and only held by the isolate
+pub struct HandleBlocks {
+    id: u64,
+    rw_lock: RwLock,
+    blocks: RefCell<Vec<Arc<HandleBlock>>>
+}
+
+impl HandleBlocks {
+
+    pub fn new() -> HandleBlocks {
+        HandleBlocks {
+            id: NEXT_HANDLE_BLOCKS_ID.fetch_add(1, Ordering::SeqCst),
+            rw_lock: RwLock::with_class(LockClass::Root),
+            blocks: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Get the block of the current thread, the block is registered at the
+    /// first time
+    #[cfg(feature = "std")]
+    pub fn get_thread_block(&self) -> Arc<HandleBlock> {
+
+        if let Some(block) = self.find_thread_block() {
+            return block;
+        }
+
+        let block = Arc::new(HandleBlock::new());
+
+        {
+            let _guard = self.rw_lock.lock_write();
+            self.blocks.borrow_mut().push(block.clone());
+        }
+
+        THREAD_HANDLE_BLOCKS.with(|blocks| {
+            let mut blocks = blocks.borrow_mut();
+            // blocks only held by the thread are of isolates dropped
+            blocks.retain(|(_id, block)| Arc::strong_count(block) > 1);
+            blocks.push((self.id, block.clone()));
+        });
+
+        block
+
+    }
+
+    /// Find the block of the current thread without registering it
+    #[cfg(feature = "std")]
+    pub fn find_thread_block(&self) -> Option<Arc<HandleBlock>> {
+
+        THREAD_HANDLE_BLOCKS.with(|blocks| {
+            blocks.borrow().iter().find(|(id, _block)| *id == self.id).map(|(_id, block)| block.clone())
+        })
+
+    }
+
+    /// List blocks of all threads, the block of the current thread comes
+    /// first if registered
+    pub fn list_blocks(&self) -> Vec<Arc<HandleBlock>> {
+
+        let blocks = {
+            let _guard = self.rw_lock.lock_read();
+            self.blocks.borrow().clone()
+        };
+
+        #[cfg(feature = "std")]
+        let blocks = {
+            let mut blocks = blocks;
+            if let Some(block) = self.find_thread_block() {
+                if let Some(index) = blocks.iter().position(|another_block| Arc::ptr_eq(another_block, &block)) {
+                    blocks.swap(0, index);
+                }
+            }
+            blocks
+        };
+
+        blocks
+
+    }
+
+    /// Prune blocks of exited threads after flushed, count of blocks pruned
+    /// is returned
+    pub fn prune_blocks(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut blocks = self.blocks.borrow_mut();
+
+        let count = blocks.len();
+
+        blocks.retain(|block| Arc::strong_count(block) > 1 || !block.is_empty());
+
+        count - blocks.len()
+
+    }
+
+    /// List values rooted by all blocks
+    pub fn list_values(&self) -> Vec<Value> {
+        self.list_blocks().iter().flat_map(|block| block.list_values()).collect()
+    }
+
+    /// Count references of roots of a value held by all blocks
+    pub fn count_references(&self, value: Value) -> usize {
+        self.list_blocks().iter().map(|block| block.count_references(value)).sum()
+    }
+
+    pub fn contains(&self, value: Value) -> bool {
+        self.list_blocks().iter().any(|block| block.list_values().contains(&value))
+    }
+
+}
+
+impl Drop for HandleBlocks {
+
+    fn drop(&mut self) {
+        // blocks could outlive the isolate in threads never registering
+        // blocks again, so their roots are released here
+        for block in self.blocks.get_mut().iter() {
+            block.borrow_mut().clear();
+        }
+    }
+
+}
+
+#[test]
+fn test_handle_blocks() {
+
+    let handle_blocks = HandleBlocks::new();
+    let handle_blocks_2 = HandleBlocks::new();
+
+    let block = handle_blocks.get_thread_block();
+    assert!(Arc::ptr_eq(&handle_blocks.get_thread_block(), &block));
+    assert!(handle_blocks_2.find_thread_block().is_none());
+
+    let root = Arc::new(Root::new(Value::make_object(1, 1)));
+    root.increase_reference().unwrap();
+    assert_eq!(block.push(root.clone()), 1);
+
+    let root_2 = Arc::new(Root::new(Value::make_object(1, 2)));
+    assert_eq!(block.push(root_2.clone()), 2);
+
+    assert_eq!(handle_blocks.list_values(), vec!(Value::make_object(1, 1)));
+    assert!(handle_blocks.contains(Value::make_object(1, 1)));
+    assert!(!handle_blocks.contains(Value::make_object(1, 2)));
+    assert_eq!(handle_blocks.count_references(Value::make_object(1, 1)), 1);
+
+    assert_eq!(HandleBlock::position(&block.borrow_mut(), &root_2), Some(1));
+
+    let block_2 = handle_blocks_2.get_thread_block();
+    assert!(!Arc::ptr_eq(&block, &block_2));
+    assert_eq!(handle_blocks_2.list_blocks().len(), 1);
+
+}
+
+#[test]
+fn test_handle_blocks_prune() {
+
+    let handle_blocks = HandleBlocks::new();
+
+    let block = handle_blocks.get_thread_block();
+    block.push(Arc::new(Root::new(Value::make_object(1, 1))));
+    drop(block);
+    assert_eq!(handle_blocks.prune_blocks(), 0);
+
+    // forget the block in the thread as if the thread exited
+    THREAD_HANDLE_BLOCKS.with(|blocks| blocks.borrow_mut().retain(|(id, _block)| *id != handle_blocks.id));
+    assert!(handle_blocks.find_thread_block().is_none());
+    assert_eq!(handle_blocks.prune_blocks(), 0);
+
+    handle_blocks.list_blocks()[0].borrow_mut().clear();
+    assert_eq!(handle_blocks.prune_blocks(), 1);
+    assert!(handle_blocks.list_blocks().is_empty());
+
+}
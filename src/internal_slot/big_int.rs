@@ -0,0 +1,573 @@
+use alloc::format;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cmp::Ordering;
+
+use super::internal_slot::InternalSlot;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+/// Arbitrary precision integer
+///
+/// The integer is stored as sign and magnitude, the magnitude is a list of
+/// 32-bit limbs in little endian without leading zeros. Zero is always
+/// positive with no limbs.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>
+}
+
+impl core::fmt::Debug for BigInt {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.debug_tuple("BigInt").field(&self.to_string()).finish()
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.limbs, &other.limbs),
+            (true, true) => compare_magnitudes(&other.limbs, &self.limbs)
+        }
+    }
+}
+
+impl ToString for BigInt {
+
+    fn to_string(&self) -> String {
+
+        if self.is_zero() {
+            return "0".to_owned();
+        }
+
+        let mut chunks = Vec::new();
+        let mut limbs = self.limbs.clone();
+        while !limbs.is_empty() {
+            let (quotient, remainder) = divide_magnitude_by_small(&limbs, DECIMAL_CHUNK);
+            chunks.push(remainder);
+            limbs = quotient;
+        }
+
+        let mut string = String::new();
+        if self.negative {
+            string.push('-');
+        }
+
+        let mut chunks = chunks.iter().rev();
+        if let Some(chunk) = chunks.next() {
+            string.push_str(&chunk.to_string());
+        }
+        for chunk in chunks {
+            string.push_str(&format!("{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS));
+        }
+
+        string
+
+    }
+
+}
+
+impl InternalSlot for BigInt {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+}
+
+// Big integer constructors
+impl BigInt {
+
+    pub fn zero() -> BigInt {
+        BigInt {
+            negative: false,
+            limbs: Vec::new()
+        }
+    }
+
+    pub fn from_i64(value: i64) -> BigInt {
+        BigInt::new_with_limbs(value < 0, split_u64(value.unsigned_abs()))
+    }
+
+    pub fn from_u64(value: u64) -> BigInt {
+        BigInt::new_with_limbs(false, split_u64(value))
+    }
+
+    /// Parse a decimal text with optional sign
+    pub fn parse(text: &str) -> Result<BigInt, Error> {
+
+        let (negative, digits) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text)
+        };
+
+        if digits.is_empty() {
+            return Err(Error::new(TypeNotMatch, "Big integer text is empty"));
+        }
+
+        let mut limbs = Vec::new();
+        for digit in digits.chars() {
+            match digit.to_digit(10) {
+                Some(digit) => {
+                    limbs = multiply_magnitude_by_small(&limbs, 10, digit);
+                },
+                None => {
+                    return Err(Error::new(TypeNotMatch, "Invalid digit in big integer text"));
+                }
+            }
+        }
+
+        Ok(BigInt::new_with_limbs(negative, limbs))
+
+    }
+
+    fn new_with_limbs(negative: bool, mut limbs: Vec<u32>) -> BigInt {
+
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        BigInt {
+            negative: negative && !limbs.is_empty(),
+            limbs: limbs
+        }
+
+    }
+
+}
+
+// Big integer basic properties
+impl BigInt {
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Convert to 64-bit integer, `None` will be returned if out of range
+    pub fn to_i64(&self) -> Option<i64> {
+
+        if self.limbs.len() > 2 {
+            return None;
+        }
+
+        let mut magnitude = 0u64;
+        for limb in self.limbs.iter().rev() {
+            magnitude = (magnitude << 32) | (*limb as u64);
+        }
+
+        if self.negative {
+            if magnitude <= (i64::MAX as u64) + 1 {
+                Some((magnitude as i64).wrapping_neg())
+            } else {
+                None
+            }
+        } else if magnitude <= i64::MAX as u64 {
+            Some(magnitude as i64)
+        } else {
+            None
+        }
+
+    }
+
+}
+
+// Big integer arithmetic
+impl BigInt {
+
+    pub fn negate(&self) -> BigInt {
+        BigInt::new_with_limbs(!self.negative, self.limbs.clone())
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::new_with_limbs(false, self.limbs.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+
+        if self.negative == other.negative {
+            return BigInt::new_with_limbs(self.negative, add_magnitudes(&self.limbs, &other.limbs));
+        }
+
+        match compare_magnitudes(&self.limbs, &other.limbs) {
+            Ordering::Equal => BigInt::zero(),
+            Ordering::Greater => BigInt::new_with_limbs(self.negative, subtract_magnitudes(&self.limbs, &other.limbs)),
+            Ordering::Less => BigInt::new_with_limbs(other.negative, subtract_magnitudes(&other.limbs, &self.limbs))
+        }
+
+    }
+
+    pub fn subtract(&self, other: &BigInt) -> BigInt {
+        self.add(&other.negate())
+    }
+
+    pub fn multiply(&self, other: &BigInt) -> BigInt {
+        BigInt::new_with_limbs(self.negative != other.negative, multiply_magnitudes(&self.limbs, &other.limbs))
+    }
+
+    /// Divide and truncate toward zero
+    pub fn divide(&self, other: &BigInt) -> Result<BigInt, Error> {
+        Ok(self.divide_with_remainder(other)?.0)
+    }
+
+    /// Remainder of truncated division, the sign follows the dividend
+    pub fn remainder(&self, other: &BigInt) -> Result<BigInt, Error> {
+        Ok(self.divide_with_remainder(other)?.1)
+    }
+
+    pub fn divide_with_remainder(&self, other: &BigInt) -> Result<(BigInt, BigInt), Error> {
+
+        if other.is_zero() {
+            return Err(Error::new(IntegerOutOfRange, "Big integer divided by zero"));
+        }
+
+        let (quotient, remainder) = divide_magnitudes(&self.limbs, &other.limbs);
+
+        Ok((BigInt::new_with_limbs(self.negative != other.negative, quotient),
+            BigInt::new_with_limbs(self.negative, remainder)))
+
+    }
+
+}
+
+fn split_u64(value: u64) -> Vec<u32> {
+    [value as u32, (value >> 32) as u32].to_vec()
+}
+
+fn trim_magnitude(mut limbs: Vec<u32>) -> Vec<u32> {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+fn compare_magnitudes(limbs: &[u32], limbs_2: &[u32]) -> Ordering {
+
+    if limbs.len() != limbs_2.len() {
+        return limbs.len().cmp(&limbs_2.len());
+    }
+
+    for (limb, limb_2) in limbs.iter().rev().zip(limbs_2.iter().rev()) {
+        if limb != limb_2 {
+            return limb.cmp(limb_2);
+        }
+    }
+
+    Ordering::Equal
+
+}
+
+fn add_magnitudes(limbs: &[u32], limbs_2: &[u32]) -> Vec<u32> {
+
+    let length = core::cmp::max(limbs.len(), limbs_2.len());
+
+    let mut result = Vec::with_capacity(length + 1);
+    let mut carry = 0u64;
+    for index in 0 .. length {
+        let sum = (*limbs.get(index).unwrap_or(&0) as u64) + (*limbs_2.get(index).unwrap_or(&0) as u64) + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    result
+
+}
+
+/// Subtract magnitudes, the first one should not be less than the second one
+fn subtract_magnitudes(limbs: &[u32], limbs_2: &[u32]) -> Vec<u32> {
+
+    let mut result = Vec::with_capacity(limbs.len());
+    let mut borrow = 0i64;
+    for (index, limb) in limbs.iter().enumerate() {
+        let mut difference = (*limb as i64) - (*limbs_2.get(index).unwrap_or(&0) as i64) - borrow;
+        if difference < 0 {
+            difference += 1 << 32;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        result.push(difference as u32);
+    }
+
+    trim_magnitude(result)
+
+}
+
+fn multiply_magnitudes(limbs: &[u32], limbs_2: &[u32]) -> Vec<u32> {
+
+    if limbs.is_empty() || limbs_2.is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = vec![0u32; limbs.len() + limbs_2.len()];
+    for (index, limb) in limbs.iter().enumerate() {
+        let mut carry = 0u64;
+        for (index_2, limb_2) in limbs_2.iter().enumerate() {
+            let product = (*limb as u64) * (*limb_2 as u64) + (result[index + index_2] as u64) + carry;
+            result[index + index_2] = product as u32;
+            carry = product >> 32;
+        }
+        result[index + limbs_2.len()] = carry as u32;
+    }
+
+    trim_magnitude(result)
+
+}
+
+fn multiply_magnitude_by_small(limbs: &[u32], factor: u32, addend: u32) -> Vec<u32> {
+
+    let mut result = Vec::with_capacity(limbs.len() + 1);
+    let mut carry = addend as u64;
+    for limb in limbs.iter() {
+        let product = (*limb as u64) * (factor as u64) + carry;
+        result.push(product as u32);
+        carry = product >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    trim_magnitude(result)
+
+}
+
+fn divide_magnitude_by_small(limbs: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+
+    let mut quotient = vec![0u32; limbs.len()];
+    let mut remainder = 0u64;
+    for (index, limb) in limbs.iter().enumerate().rev() {
+        let dividend = (remainder << 32) | (*limb as u64);
+        quotient[index] = (dividend / (divisor as u64)) as u32;
+        remainder = dividend % (divisor as u64);
+    }
+
+    (trim_magnitude(quotient), remainder as u32)
+
+}
+
+/// Schoolbook long division of magnitudes (Knuth's algorithm D), the
+/// divisor should not be zero
+fn divide_magnitudes(limbs: &[u32], limbs_2: &[u32]) -> (Vec<u32>, Vec<u32>) {
+
+    if compare_magnitudes(limbs, limbs_2) == Ordering::Less {
+        return (Vec::new(), limbs.to_vec());
+    }
+
+    if limbs_2.len() == 1 {
+        let (quotient, remainder) = divide_magnitude_by_small(limbs, limbs_2[0]);
+        return (quotient, trim_magnitude([remainder].to_vec()));
+    }
+
+    // Normalize to get the top limb of the divisor with the highest bit set,
+    // so the estimated quotient limbs are at most 2 more than the real ones
+    let shift = limbs_2[limbs_2.len() - 1].leading_zeros();
+    let divisor = shift_magnitude_left(limbs_2, shift);
+    let mut dividend = shift_magnitude_left(limbs, shift);
+    dividend.resize(limbs.len() + 1, 0);
+
+    let length = divisor.len();
+    let divisor_top = divisor[length - 1] as u64;
+    let divisor_next = divisor[length - 2] as u64;
+
+    let mut quotient = vec![0u32; limbs.len() - length + 1];
+    for index in (0 .. quotient.len()).rev() {
+
+        let numerator = ((dividend[index + length] as u64) << 32) | (dividend[index + length - 1] as u64);
+        let mut estimate = numerator / divisor_top;
+        let mut estimate_remainder = numerator % divisor_top;
+        while (estimate >> 32 != 0) ||
+              (estimate * divisor_next > ((estimate_remainder << 32) | (dividend[index + length - 2] as u64))) {
+            estimate -= 1;
+            estimate_remainder += divisor_top;
+            if estimate_remainder >> 32 != 0 {
+                break;
+            }
+        }
+
+        // Multiply and subtract in place
+        let mut borrow = 0i64;
+        for (offset, limb) in divisor.iter().enumerate() {
+            let product = estimate * (*limb as u64);
+            let difference = (dividend[index + offset] as i64) - borrow - ((product & 0xFFFF_FFFF) as i64);
+            dividend[index + offset] = difference as u32;
+            borrow = ((product >> 32) as i64) - (difference >> 32);
+        }
+        let difference = (dividend[index + length] as i64) - borrow;
+        dividend[index + length] = difference as u32;
+
+        // The estimate was 1 too large, add the divisor back
+        if difference < 0 {
+            estimate -= 1;
+            let mut carry = 0u64;
+            for (offset, limb) in divisor.iter().enumerate() {
+                let sum = (dividend[index + offset] as u64) + (*limb as u64) + carry;
+                dividend[index + offset] = sum as u32;
+                carry = sum >> 32;
+            }
+            dividend[index + length] = dividend[index + length].wrapping_add(carry as u32);
+        }
+
+        quotient[index] = estimate as u32;
+
+    }
+
+    dividend.truncate(length);
+
+    (trim_magnitude(quotient), trim_magnitude(shift_magnitude_right(&dividend, shift)))
+
+}
+
+fn shift_magnitude_left(limbs: &[u32], shift: u32) -> Vec<u32> {
+
+    if shift == 0 {
+        return limbs.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(limbs.len() + 1);
+    let mut carry = 0u32;
+    for limb in limbs.iter() {
+        result.push((*limb << shift) | carry);
+        carry = *limb >> (32 - shift);
+    }
+    if carry > 0 {
+        result.push(carry);
+    }
+
+    result
+
+}
+
+fn shift_magnitude_right(limbs: &[u32], shift: u32) -> Vec<u32> {
+
+    if shift == 0 {
+        return limbs.to_vec();
+    }
+
+    let mut result = vec![0u32; limbs.len()];
+    for (index, limb) in limbs.iter().enumerate() {
+        result[index] = *limb >> shift;
+        if let Some(next) = limbs.get(index + 1) {
+            result[index] |= *next << (32 - shift);
+        }
+    }
+
+    result
+
+}
+
+#[test]
+fn test_big_int_text() -> Result<(), Error> {
+
+    assert_eq!(&BigInt::parse("0")?.to_string(), "0");
+    assert_eq!(&BigInt::parse("-0")?.to_string(), "0");
+    assert_eq!(&BigInt::parse("+42")?.to_string(), "42");
+    assert_eq!(&BigInt::parse("-123456789012345678901234567890")?.to_string(), "-123456789012345678901234567890");
+    assert_eq!(&BigInt::parse("1000000000")?.to_string(), "1000000000");
+
+    assert!(BigInt::parse("").is_err());
+    assert!(BigInt::parse("-").is_err());
+    assert!(BigInt::parse("12a").is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_big_int_conversions() {
+
+    assert_eq!(BigInt::from_i64(i64::MIN).to_i64(), Some(i64::MIN));
+    assert_eq!(BigInt::from_i64(i64::MAX).to_i64(), Some(i64::MAX));
+    assert_eq!(BigInt::from_u64(u64::MAX).to_i64(), None);
+    assert_eq!(&BigInt::from_u64(u64::MAX).to_string(), "18446744073709551615");
+    assert!(BigInt::from_i64(0).is_zero());
+    assert!(!BigInt::from_i64(0).is_negative());
+
+}
+
+#[test]
+fn test_big_int_arithmetic() -> Result<(), Error> {
+
+    let a = BigInt::parse("123456789012345678901234567890")?;
+    let b = BigInt::parse("-987654321098765432109876543210")?;
+
+    assert_eq!(&a.add(&b).to_string(), "-864197532086419753208641975320");
+    assert_eq!(&a.subtract(&b).to_string(), "1111111110111111111011111111100");
+    assert_eq!(&a.multiply(&b).to_string(), "-121932631137021795226185032733622923332237463801111263526900");
+    assert_eq!(&b.divide(&a)?.to_string(), "-8");
+    assert_eq!(&b.remainder(&a)?.to_string(), "-9000000000900000000090");
+    assert_eq!(&a.divide(&BigInt::from_i64(-7))?.to_string(), "-17636684144620811271604938270");
+
+    assert!(a.divide(&BigInt::zero()).is_err());
+    assert!(a.add(&a.negate()).is_zero());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_big_int_long_division() -> Result<(), Error> {
+
+    // The first estimated quotient limb is 1 too large, and the divisor is
+    // added back
+    let (quotient, remainder) = BigInt::parse("79228162514264337593543950336")?
+        .divide_with_remainder(&BigInt::parse("18446744073709551617")?)?;
+    assert_eq!(&quotient.to_string(), "4294967295");
+    assert_eq!(&remainder.to_string(), "18446744069414584321");
+
+    let mut seed = 0x2545_F491_4F6C_DD1Du64;
+    let mut next_limbs = |count: usize| {
+        let mut limbs = Vec::with_capacity(count);
+        for _ in 0 .. count {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            limbs.push((seed >> 32) as u32);
+        }
+        limbs
+    };
+
+    for (count, count_2) in [(4, 2), (5, 3), (8, 3), (8, 7), (12, 5)].iter() {
+        let dividend = BigInt::new_with_limbs(false, next_limbs(*count));
+        let divisor = BigInt::new_with_limbs(true, next_limbs(*count_2));
+        let (quotient, remainder) = dividend.divide_with_remainder(&divisor)?;
+        assert!(remainder.abs() < divisor.abs());
+        assert_eq!(quotient.multiply(&divisor).add(&remainder), dividend);
+    }
+
+    Ok(())
+
+}
+
+#[test]
+fn test_big_int_ordering() -> Result<(), Error> {
+
+    assert!(BigInt::parse("-10")? < BigInt::parse("-9")?);
+    assert!(BigInt::parse("-1")? < BigInt::zero());
+    assert!(BigInt::parse("18446744073709551616")? > BigInt::from_u64(u64::MAX));
+    assert_eq!(BigInt::parse("-0")?, BigInt::zero());
+
+    Ok(())
+
+}
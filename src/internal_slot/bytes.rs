@@ -0,0 +1,238 @@
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::RefCell;
+use alloc::sync::Arc;
+
+use super::internal_slot::InternalSlot;
+
+use super::super::util::RwLock;
+
+
+pub struct Bytes {
+    rw_lock: RwLock,
+    data: RefCell<Arc<Vec<u8>>>
+}
+
+impl Clone for Bytes {
+    fn clone(&self) -> Self {
+        Bytes::new_with_data(self.share_data())
+    }
+}
+
+impl core::fmt::Debug for Bytes {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.debug_tuple("Bytes").field(&self.share_data()).finish()
+    }
+}
+
+impl PartialEq for Bytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.share_data() == other.share_data()
+    }
+}
+
+impl Eq for Bytes {}
+
+impl InternalSlot for Bytes {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+        Some(Arc::new(self.clone()))
+    }
+
+}
+
+// Bytes constructors
+impl Bytes {
+
+    pub fn new(bytes: &[u8]) -> Bytes {
+        Bytes::new_with_data(Arc::new(bytes.to_vec()))
+    }
+
+    pub fn from_vec(bytes: Vec<u8>) -> Bytes {
+        Bytes::new_with_data(Arc::new(bytes))
+    }
+
+    fn new_with_data(data: Arc<Vec<u8>>) -> Bytes {
+        Bytes {
+            rw_lock: RwLock::new(),
+            data: RefCell::new(data)
+        }
+    }
+
+}
+
+// Bytes basic properties
+impl Bytes {
+
+    pub fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+
+    pub fn get_length(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.data.borrow().len()
+
+    }
+
+    /// Check whether the data is shared with other bytes
+    pub fn is_shared(&self) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Arc::strong_count(&self.data.borrow()) > 1
+
+    }
+
+}
+
+// Bytes data
+impl Bytes {
+
+    /// Share the underlying data without copying
+    pub fn share_data(&self) -> Arc<Vec<u8>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.data.borrow().clone()
+
+    }
+
+    pub fn as_vec(&self) -> Vec<u8> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.data.borrow().to_vec()
+
+    }
+
+    pub fn get_byte_at(&self, index: usize) -> Option<u8> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.data.borrow().get(index).copied()
+
+    }
+
+    pub fn get_slice(&self, from: usize, to: usize) -> Option<Vec<u8>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.data.borrow().get(from .. to).map(|slice| slice.to_vec())
+
+    }
+
+}
+
+// Bytes operations
+impl Bytes {
+
+    pub fn slice(&self, from: usize, to: usize) -> Option<Bytes> {
+        self.get_slice(from, to).map(Bytes::from_vec)
+    }
+
+    /// Set a byte, the data will be copied first if it is shared
+    pub fn set_byte_at(&self, index: usize, byte: u8) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut data = self.data.borrow_mut();
+        if index >= data.len() {
+            return false;
+        }
+
+        Arc::make_mut(&mut data)[index] = byte;
+
+        true
+
+    }
+
+    /// Append bytes, the data will be copied first if it is shared
+    pub fn append(&self, bytes: &[u8]) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut data = self.data.borrow_mut();
+
+        Arc::make_mut(&mut data).extend_from_slice(bytes);
+
+    }
+
+    pub fn truncate(&self, length: usize) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut data = self.data.borrow_mut();
+        if length >= data.len() {
+            return;
+        }
+
+        Arc::make_mut(&mut data).truncate(length);
+
+    }
+
+}
+
+#[test]
+fn test_bytes_creation() {
+
+    let bytes = Bytes::new(&[1, 2, 3]);
+
+    assert_eq!(bytes.get_length(), 3);
+    assert!(!bytes.is_empty());
+    assert!(Bytes::new(&[]).is_empty());
+    assert!(bytes.list_referenced_values().is_empty());
+
+}
+
+#[test]
+fn test_bytes_access() {
+
+    let bytes = Bytes::new(&[1, 2, 3, 4]);
+
+    assert_eq!(bytes.get_byte_at(0), Some(1));
+    assert_eq!(bytes.get_byte_at(4), None);
+    assert_eq!(bytes.get_slice(1, 3), Some([2, 3].to_vec()));
+    assert_eq!(bytes.get_slice(3, 5), None);
+    assert_eq!(bytes.slice(2, 4), Some(Bytes::new(&[3, 4])));
+
+}
+
+#[test]
+fn test_bytes_append() {
+
+    let bytes = Bytes::new(&[1, 2]);
+
+    bytes.append(&[3, 4]);
+    assert_eq!(bytes.as_vec(), [1, 2, 3, 4].to_vec());
+
+    assert!(bytes.set_byte_at(0, 5));
+    assert!(!bytes.set_byte_at(4, 5));
+    assert_eq!(bytes.as_vec(), [5, 2, 3, 4].to_vec());
+
+    bytes.truncate(1);
+    assert_eq!(bytes.as_vec(), [5].to_vec());
+
+}
+
+#[test]
+fn test_bytes_copy_on_write() {
+
+    let bytes = Bytes::new(&[1, 2]);
+    let bytes_2 = bytes.clone();
+
+    assert!(bytes.is_shared());
+    assert!(Arc::ptr_eq(&bytes.share_data(), &bytes_2.share_data()));
+
+    bytes_2.append(&[3]);
+
+    assert!(!bytes.is_shared());
+    assert_eq!(bytes.as_vec(), [1, 2].to_vec());
+    assert_eq!(bytes_2.as_vec(), [1, 2, 3].to_vec());
+
+}
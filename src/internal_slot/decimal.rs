@@ -0,0 +1,305 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::string::ToString;
+use core::any::Any;
+use core::cmp::Ordering;
+
+use super::big_int::BigInt;
+use super::internal_slot::InternalSlot;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+
+/// Exact base-10 decimal
+///
+/// The decimal is stored as an arbitrary precision coefficient and a scale,
+/// the value is `coefficient * 10 ^ -scale`. Addition, subtraction and
+/// multiplication are exact, division and rescaling round half to even.
+#[derive(Clone)]
+pub struct Decimal {
+    coefficient: BigInt,
+    scale: u32
+}
+
+impl core::fmt::Debug for Decimal {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.debug_tuple("Decimal").field(&self.to_string()).finish()
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = core::cmp::max(self.scale, other.scale);
+        self.get_scaled_coefficient(scale).cmp(&other.get_scaled_coefficient(scale))
+    }
+}
+
+impl ToString for Decimal {
+
+    fn to_string(&self) -> String {
+
+        let digits = self.coefficient.abs().to_string();
+
+        let mut string = String::new();
+        if self.coefficient.is_negative() {
+            string.push('-');
+        }
+
+        let scale = self.scale as usize;
+        if scale == 0 {
+            string.push_str(&digits);
+        } else if digits.len() > scale {
+            string.push_str(&digits[.. digits.len() - scale]);
+            string.push('.');
+            string.push_str(&digits[digits.len() - scale ..]);
+        } else {
+            string.push_str("0.");
+            string.push_str(&"0".repeat(scale - digits.len()));
+            string.push_str(&digits);
+        }
+
+        string
+
+    }
+
+}
+
+impl InternalSlot for Decimal {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+}
+
+// Decimal constructors
+impl Decimal {
+
+    pub fn new(coefficient: BigInt, scale: u32) -> Decimal {
+        Decimal {
+            coefficient: coefficient,
+            scale: scale
+        }
+    }
+
+    pub fn from_i64(value: i64) -> Decimal {
+        Decimal::new(BigInt::from_i64(value), 0)
+    }
+
+    /// Parse a decimal text with optional sign and fraction, e.g. `-12.50`
+    pub fn parse(text: &str) -> Result<Decimal, Error> {
+
+        match text.find('.') {
+            None => Ok(Decimal::new(BigInt::parse(text)?, 0)),
+            Some(index) => {
+                let fraction = &text[index + 1 ..];
+                if fraction.is_empty() || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return Err(Error::new(TypeNotMatch, "Invalid fraction in decimal text"));
+                }
+                let mut digits = text[.. index].to_owned();
+                if digits.is_empty() || (digits == "-") || (digits == "+") {
+                    digits.push('0');
+                }
+                digits.push_str(fraction);
+                Ok(Decimal::new(BigInt::parse(&digits)?, fraction.len() as u32))
+            }
+        }
+
+    }
+
+}
+
+// Decimal basic properties
+impl Decimal {
+
+    pub fn get_coefficient(&self) -> BigInt {
+        self.coefficient.clone()
+    }
+
+    pub fn get_scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.coefficient.is_negative()
+    }
+
+}
+
+// Decimal arithmetic
+impl Decimal {
+
+    pub fn negate(&self) -> Decimal {
+        Decimal::new(self.coefficient.negate(), self.scale)
+    }
+
+    pub fn add(&self, other: &Decimal) -> Decimal {
+        let scale = core::cmp::max(self.scale, other.scale);
+        Decimal::new(self.get_scaled_coefficient(scale).add(&other.get_scaled_coefficient(scale)), scale)
+    }
+
+    pub fn subtract(&self, other: &Decimal) -> Decimal {
+        self.add(&other.negate())
+    }
+
+    pub fn multiply(&self, other: &Decimal) -> Decimal {
+        Decimal::new(self.coefficient.multiply(&other.coefficient), self.scale + other.scale)
+    }
+
+    /// Divide with specified scale of the result, rounding half to even
+    pub fn divide(&self, other: &Decimal, scale: u32) -> Result<Decimal, Error> {
+
+        if other.is_zero() {
+            return Err(Error::new(IntegerOutOfRange, "Decimal divided by zero"));
+        }
+
+        // self / other * 10 ^ scale = self.coefficient * 10 ^ (scale + other.scale - self.scale) / other.coefficient
+        let exponent = (scale as i64) + (other.scale as i64) - (self.scale as i64);
+        let (dividend, divisor) = if exponent >= 0 {
+            (multiply_by_power_of_ten(&self.coefficient, exponent as u32), other.coefficient.clone())
+        } else {
+            (self.coefficient.clone(), multiply_by_power_of_ten(&other.coefficient, (-exponent) as u32))
+        };
+
+        Ok(Decimal::new(divide_rounding_half_even(&dividend, &divisor)?, scale))
+
+    }
+
+    /// Change the scale, rounding half to even if digits are dropped
+    pub fn rescale(&self, scale: u32) -> Decimal {
+
+        if scale >= self.scale {
+            return Decimal::new(self.get_scaled_coefficient(scale), scale);
+        }
+
+        let divisor = multiply_by_power_of_ten(&BigInt::from_i64(1), self.scale - scale);
+        match divide_rounding_half_even(&self.coefficient, &divisor) {
+            Ok(coefficient) => Decimal::new(coefficient, scale),
+            Err(_) => panic!("Power of ten should not be zero")
+        }
+
+    }
+
+    fn get_scaled_coefficient(&self, scale: u32) -> BigInt {
+        multiply_by_power_of_ten(&self.coefficient, scale - self.scale)
+    }
+
+}
+
+fn multiply_by_power_of_ten(value: &BigInt, exponent: u32) -> BigInt {
+
+    let ten = BigInt::from_i64(10);
+
+    let mut result = value.clone();
+    for _ in 0 .. exponent {
+        result = result.multiply(&ten);
+    }
+
+    result
+
+}
+
+fn divide_rounding_half_even(dividend: &BigInt, divisor: &BigInt) -> Result<BigInt, Error> {
+
+    let (quotient, remainder) = dividend.divide_with_remainder(divisor)?;
+
+    if remainder.is_zero() {
+        return Ok(quotient);
+    }
+
+    let ordering = remainder.abs().multiply(&BigInt::from_i64(2)).cmp(&divisor.abs());
+    let round_away = match ordering {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => !quotient.remainder(&BigInt::from_i64(2))?.is_zero()
+    };
+
+    if !round_away {
+        return Ok(quotient);
+    }
+
+    if dividend.is_negative() != divisor.is_negative() {
+        Ok(quotient.subtract(&BigInt::from_i64(1)))
+    } else {
+        Ok(quotient.add(&BigInt::from_i64(1)))
+    }
+
+}
+
+#[test]
+fn test_decimal_text() -> Result<(), Error> {
+
+    assert_eq!(&Decimal::parse("12.50")?.to_string(), "12.50");
+    assert_eq!(&Decimal::parse("-0.005")?.to_string(), "-0.005");
+    assert_eq!(&Decimal::parse(".5")?.to_string(), "0.5");
+    assert_eq!(&Decimal::parse("42")?.to_string(), "42");
+    assert_eq!(Decimal::parse("-0.005")?.get_scale(), 3);
+
+    assert!(Decimal::parse("1.").is_err());
+    assert!(Decimal::parse("1.2.3").is_err());
+    assert!(Decimal::parse("a.5").is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_decimal_arithmetic() -> Result<(), Error> {
+
+    let a = Decimal::parse("0.1")?;
+    let b = Decimal::parse("0.2")?;
+
+    assert_eq!(a.add(&b), Decimal::parse("0.3")?);
+    assert_eq!(&a.subtract(&b).to_string(), "-0.1");
+    assert_eq!(&Decimal::parse("1.25")?.multiply(&Decimal::parse("-0.4")?).to_string(), "-0.500");
+
+    assert_eq!(&Decimal::from_i64(1).divide(&Decimal::from_i64(3), 4)?.to_string(), "0.3333");
+    assert_eq!(&Decimal::from_i64(2).divide(&Decimal::from_i64(3), 2)?.to_string(), "0.67");
+    assert_eq!(&Decimal::parse("-1")?.divide(&Decimal::parse("0.8")?, 0)?.to_string(), "-1");
+    assert!(a.divide(&Decimal::parse("0.00")?, 2).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_decimal_rounding() -> Result<(), Error> {
+
+    assert_eq!(&Decimal::parse("2.345")?.rescale(2).to_string(), "2.34");
+    assert_eq!(&Decimal::parse("2.355")?.rescale(2).to_string(), "2.36");
+    assert_eq!(&Decimal::parse("-2.355")?.rescale(2).to_string(), "-2.36");
+    assert_eq!(&Decimal::parse("2.3451")?.rescale(2).to_string(), "2.35");
+    assert_eq!(&Decimal::parse("2.5")?.rescale(3).to_string(), "2.500");
+
+    Ok(())
+
+}
+
+#[test]
+fn test_decimal_ordering() -> Result<(), Error> {
+
+    assert_eq!(Decimal::parse("1.0")?, Decimal::parse("1.00")?);
+    assert!(Decimal::parse("1.01")? > Decimal::parse("1.009")?);
+    assert!(Decimal::parse("-1.01")? < Decimal::parse("-1")?);
+
+    Ok(())
+
+}
@@ -0,0 +1,129 @@
+use alloc::boxed::Box;
+use core::any::Any;
+use alloc::sync::Arc;
+
+use super::internal_slot::InternalSlot;
+
+use super::super::root::DropListener;
+
+/// Internal slot attaching a host object to a value
+///
+/// The host object is released after the slot is recycled, and the drop
+/// listener will be notified at the same time. Transferable host objects
+/// are shared with other isolates when the value is transferred.
+pub struct Foreign {
+    data: Arc<dyn Any + Send + Sync>,
+    drop_listener: Option<Box<dyn DropListener>>,
+    transferable: bool
+}
+
+impl Drop for Foreign {
+    fn drop(&mut self) {
+        if let Some(drop_listener) = &self.drop_listener {
+            drop_listener.notify_drop();
+        }
+    }
+}
+
+impl InternalSlot for Foreign {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+}
+
+// Foreign constructors
+impl Foreign {
+
+    pub fn new(data: Arc<dyn Any + Send + Sync>) -> Foreign {
+        Foreign {
+            data: data,
+            drop_listener: None,
+            transferable: false
+        }
+    }
+
+    pub fn with_drop_listener(data: Arc<dyn Any + Send + Sync>, drop_listener: Box<dyn DropListener>) -> Foreign {
+        Foreign {
+            data: data,
+            drop_listener: Some(drop_listener),
+            transferable: false
+        }
+    }
+
+    pub fn new_transferable(data: Arc<dyn Any + Send + Sync>) -> Foreign {
+        Foreign {
+            data: data,
+            drop_listener: None,
+            transferable: true
+        }
+    }
+
+}
+
+// Foreign data
+impl Foreign {
+
+    pub fn get_data(&self) -> Arc<dyn Any + Send + Sync> {
+        self.data.clone()
+    }
+
+    pub fn is_transferable(&self) -> bool {
+        self.transferable
+    }
+
+    pub fn is<T: Any + Send + Sync>(&self) -> bool {
+        self.data.is::<T>()
+    }
+
+    /// Get the host object if its type matches
+    pub fn downcast<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.data.clone().downcast::<T>().ok()
+    }
+
+}
+
+#[cfg(test)] use std::sync::atomic::AtomicBool;
+#[cfg(test)] use std::sync::atomic::Ordering;
+
+#[cfg(test)]
+struct TestDropListener {
+    dropped: Arc<AtomicBool>
+}
+
+#[cfg(test)]
+impl DropListener for TestDropListener {
+    fn notify_drop(&self) {
+        self.dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_foreign_downcast() {
+
+    let foreign = Foreign::new(Arc::new(String::from("foo")));
+
+    assert!(foreign.is::<String>());
+    assert!(!foreign.is::<u32>());
+    assert_eq!(foreign.downcast::<String>().unwrap().as_str(), "foo");
+    assert!(foreign.downcast::<u32>().is_none());
+
+}
+
+#[test]
+fn test_foreign_drop() {
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let data = Arc::new(42u32);
+
+    let foreign = Foreign::with_drop_listener(data.clone(), Box::new(TestDropListener {
+        dropped: dropped.clone()
+    }));
+    assert_eq!(Arc::strong_count(&data), 2);
+
+    drop(foreign);
+    assert!(dropped.load(Ordering::SeqCst));
+    assert_eq!(Arc::strong_count(&data), 1);
+
+}
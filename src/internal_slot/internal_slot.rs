@@ -1,6 +1,9 @@
-use std::any::Any;
-use std::ops::Deref;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::ops::Deref;
+use alloc::sync::Arc;
 
 use super::super::base::Error;
 use super::super::base::Symbol;
@@ -43,6 +46,14 @@ pub trait InternalSlot: Any {
         // Do nothing
     }
 
+    /// Duplicate the internal slot for a forked isolate
+    ///
+    /// **Default** return `None`, that means the internal slot is shared
+    /// between the isolates
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+        None
+    }
+
 }
 
 pub struct ProtectedInternalSlot<'a> {
@@ -1,10 +1,15 @@
-use std::any::Any;
-use std::cell::Cell;
-use std::cell::RefCell;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::cell::RefCell;
+use core::cmp::Ordering;
+use alloc::sync::Arc;
 
 use super::internal_slot::InternalSlot;
 
 use super::super::base::Error;
+use super::super::base::ErrorType::*;
 use super::super::base::Value;
 use super::super::context::Context;
 use super::super::util::RwLock;
@@ -39,6 +44,16 @@ impl InternalSlot for List {
         self
     }
 
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let values = self.values.borrow().iter().map(|value| value.get()).collect();
+
+        Some(Arc::new(List::new(self.subject.get(), values)))
+
+    }
+
     fn refresh_subject(&self, subject: Value) {
 
         let _guard = self.rw_lock.lock_write();
@@ -150,12 +165,168 @@ impl List {
 
     }
 
-    // TODO: rest apis
-    // push
-    // pop
-    // shift
-    // unshift
+}
+
+// List operations
+impl List {
+
+    /// Append a value, returns the removed and added references
+    pub fn push(&self, value: Value) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.values.borrow_mut().push(Cell::new(value));
+
+        (Vec::new(), [value].to_vec())
+
+    }
+
+    /// Remove the last value, returns the removed and added references
+    pub fn pop(&self) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        match self.values.borrow_mut().pop() {
+            Some(value) => ([value.get()].to_vec(), Vec::new()),
+            None => (Vec::new(), Vec::new())
+        }
+
+    }
+
+    /// Insert a value before the index, the list will be padded with
+    /// undefined if the index is out of range
+    pub fn insert(&self, index: usize, value: Value) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut values = self.values.borrow_mut();
+        while index > values.len() {
+            values.push(Cell::new(Value::make_undefined()));
+        }
+
+        values.insert(index, Cell::new(value));
+
+        (Vec::new(), [value].to_vec())
+
+    }
+
+    /// Remove the value at the index, returns the removed and added references
+    pub fn remove(&self, index: usize) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut values = self.values.borrow_mut();
+        if index >= values.len() {
+            return (Vec::new(), Vec::new());
+        }
+
+        ([values.remove(index).get()].to_vec(), Vec::new())
+
+    }
+
+    /// Replace `delete_count` values started from `from` with new values,
+    /// returns the removed and added references
+    pub fn splice(&self, from: usize, delete_count: usize, new_values: Vec<Value>) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut values = self.values.borrow_mut();
+
+        let from = core::cmp::min(from, values.len());
+        let to = core::cmp::min(from.saturating_add(delete_count), values.len());
+
+        let mut removes = Vec::with_capacity(to - from);
+        for value in values.splice(from .. to, new_values.iter().map(|value| Cell::new(*value))) {
+            removes.push(value.get());
+        }
+
+        (removes, new_values)
+
+    }
+
+}
+
+// List sorting
+//
+// Comparators are called without the list locked, so they could visit the
+// list or other values through the context
+impl List {
+
+    /// Stable sort values with comparator
+    pub fn sort_by<F>(&self, mut comparator: F) -> Result<(), Error> where F: FnMut(Value, Value) -> Result<Ordering, Error> {
+
+        let values = self.get_value_list();
+
+        let mut error = None;
+        let mut sorted_values = values.clone();
+        sorted_values.sort_by(|value, value_2| {
+            if error.is_some() {
+                return Ordering::Equal;
+            }
+            match comparator(*value, *value_2) {
+                Ok(ordering) => ordering,
+                Err(comparator_error) => {
+                    error = Some(comparator_error);
+                    Ordering::Equal
+                }
+            }
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let _guard = self.rw_lock.lock_write();
+
+        let current_values = self.values.borrow();
+        if (current_values.len() != values.len()) ||
+           current_values.iter().zip(values.iter()).any(|(current_value, value)| current_value.get() != *value) {
+            return Err(Error::new(FatalError, "List mutated while sorting"));
+        }
+
+        for (current_value, value) in current_values.iter().zip(sorted_values) {
+            current_value.set(value);
+        }
+
+        Ok(())
+
+    }
+
+    /// Binary search a sorted list, the comparator compares an element with
+    /// the target
+    ///
+    /// Returns `Ok(index)` if found, or `Err(index)` where the target could be
+    /// inserted to keep the list sorted
+    pub fn binary_search_by<F>(&self, mut comparator: F) -> Result<Result<usize, usize>, Error> where F: FnMut(Value) -> Result<Ordering, Error> {
+
+        let values = self.get_value_list();
+
+        let mut from = 0;
+        let mut to = values.len();
+        let mut result = Err(from);
+        while from < to {
+            let middle = from + (to - from) / 2;
+            match comparator(values[middle])? {
+                Ordering::Less => { from = middle + 1; },
+                Ordering::Greater => { to = middle; },
+                Ordering::Equal => {
+                    result = Ok(middle);
+                    break;
+                }
+            }
+            result = Err(from);
+        }
+
+        let _guard = self.rw_lock.lock_read();
 
+        let current_values = self.values.borrow();
+        if (current_values.len() != values.len()) ||
+           current_values.iter().zip(values.iter()).any(|(current_value, value)| current_value.get() != *value) {
+            return Err(Error::new(FatalError, "List mutated while searching"));
+        }
+
+        Ok(result)
+
+    }
 
 }
 
@@ -226,3 +397,113 @@ fn test_elements() {
     assert_eq!(list.get_element(5), Value::make_undefined());
 
 }
+
+#[test]
+fn test_push_and_pop() {
+
+    let list = List::new(Value::make_null(), [Value::make_cardinal(1)].to_vec());
+
+    let (removes, adds) = list.push(Value::make_cardinal(2));
+    assert!(removes.is_empty());
+    assert_eq!(adds, [Value::make_cardinal(2)].to_vec());
+    assert_eq!(list.get_length(), 2);
+
+    let (removes, adds) = list.pop();
+    assert_eq!(removes, [Value::make_cardinal(2)].to_vec());
+    assert!(adds.is_empty());
+
+    list.pop();
+    let (removes, _adds) = list.pop();
+    assert!(removes.is_empty());
+    assert_eq!(list.get_length(), 0);
+
+}
+
+#[test]
+fn test_insert_and_remove() {
+
+    let list = List::new(Value::make_null(), [Value::make_cardinal(1), Value::make_cardinal(3)].to_vec());
+
+    list.insert(1, Value::make_cardinal(2));
+    assert_eq!(list.get_value_list(), [Value::make_cardinal(1), Value::make_cardinal(2), Value::make_cardinal(3)].to_vec());
+
+    list.insert(4, Value::make_cardinal(5));
+    assert_eq!(list.get_element(3), Value::make_undefined());
+    assert_eq!(list.get_element(4), Value::make_cardinal(5));
+
+    let (removes, _adds) = list.remove(0);
+    assert_eq!(removes, [Value::make_cardinal(1)].to_vec());
+    assert_eq!(list.get_element(0), Value::make_cardinal(2));
+
+    let (removes, _adds) = list.remove(10);
+    assert!(removes.is_empty());
+
+}
+
+#[test]
+fn test_splice() {
+
+    let list = List::new(Value::make_null(), [
+        Value::make_cardinal(1), Value::make_cardinal(2), Value::make_cardinal(3)
+    ].to_vec());
+
+    let (removes, adds) = list.splice(1, 1, [Value::make_cardinal(4), Value::make_cardinal(5)].to_vec());
+    assert_eq!(removes, [Value::make_cardinal(2)].to_vec());
+    assert_eq!(adds, [Value::make_cardinal(4), Value::make_cardinal(5)].to_vec());
+    assert_eq!(list.get_value_list(), [
+        Value::make_cardinal(1), Value::make_cardinal(4), Value::make_cardinal(5), Value::make_cardinal(3)
+    ].to_vec());
+
+    let (removes, _adds) = list.splice(2, 10, Vec::new());
+    assert_eq!(removes, [Value::make_cardinal(5), Value::make_cardinal(3)].to_vec());
+
+    let (removes, _adds) = list.splice(5, 1, [Value::make_cardinal(6)].to_vec());
+    assert!(removes.is_empty());
+    assert_eq!(list.get_value_list(), [Value::make_cardinal(1), Value::make_cardinal(4), Value::make_cardinal(6)].to_vec());
+
+    let (removes, _adds) = list.splice(1, usize::MAX, Vec::new());
+    assert_eq!(removes, [Value::make_cardinal(4), Value::make_cardinal(6)].to_vec());
+    assert_eq!(list.get_value_list(), [Value::make_cardinal(1)].to_vec());
+
+}
+
+#[test]
+fn test_sort() -> Result<(), Error> {
+
+    let list = List::new(Value::make_null(), [
+        Value::make_cardinal(3), Value::make_cardinal(1), Value::make_cardinal(2)
+    ].to_vec());
+
+    list.sort_by(|value, value_2| Ok(value.extract_cardinal(0).cmp(&value_2.extract_cardinal(0))))?;
+    assert_eq!(list.get_value_list(), [
+        Value::make_cardinal(1), Value::make_cardinal(2), Value::make_cardinal(3)
+    ].to_vec());
+
+    assert!(list.sort_by(|_value, _value_2| Err(Error::new(FatalError, "Failed to compare"))).is_err());
+
+    assert!(list.sort_by(|value, value_2| {
+        list.push(Value::make_null());
+        Ok(value.extract_cardinal(0).cmp(&value_2.extract_cardinal(0)))
+    }).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_binary_search() -> Result<(), Error> {
+
+    let list = List::new(Value::make_null(), [
+        Value::make_cardinal(1), Value::make_cardinal(3), Value::make_cardinal(5)
+    ].to_vec());
+
+    let search = |target: u32| list.binary_search_by(|value| Ok(value.extract_cardinal(0).cmp(&target)));
+
+    assert_eq!(search(3)?, Ok(1));
+    assert_eq!(search(4)?, Err(2));
+    assert_eq!(search(0)?, Err(0));
+    assert_eq!(search(6)?, Err(3));
+
+    Ok(())
+
+}
@@ -0,0 +1,320 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::cell::RefCell;
+use alloc::sync::Arc;
+use super::super::util::HashMap;
+
+use super::internal_slot::InternalSlot;
+
+use super::super::base::Error;
+use super::super::base::Value;
+use super::super::context::Context;
+use super::super::util::RwLock;
+
+/// Hash map from values to values
+///
+/// Slotted keys are hashed by identity, and they will be rehashed if the
+/// slots are moved by refragment
+pub struct Map {
+    subject: Cell<Value>,
+    rw_lock: RwLock,
+    entries: RefCell<HashMap<Value, Value>>
+}
+
+// Map constructor
+impl Map {
+
+    pub fn new(subject: Value, entries: Vec<(Value, Value)>) -> Map {
+        let mut new_entries = HashMap::new();
+        for (key, value) in entries {
+            new_entries.insert(key, value);
+        }
+        Map {
+            subject: Cell::new(subject),
+            rw_lock: RwLock::new(),
+            entries: RefCell::new(new_entries)
+        }
+    }
+
+}
+
+impl InternalSlot for Map {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Some(Arc::new(Map {
+            subject: Cell::new(self.subject.get()),
+            rw_lock: RwLock::new(),
+            entries: RefCell::new(self.entries.borrow().clone())
+        }))
+
+    }
+
+    fn get_subject(&self) -> Value {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.subject.get()
+
+    }
+
+    fn refresh_subject(&self, subject: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.subject.set(subject);
+
+    }
+
+    fn list_and_autorefresh_referenced_values(&self, self_id: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut entries = self.entries.borrow_mut();
+        let mut result = Vec::with_capacity(entries.len() * 2);
+        let mut rehashed = Vec::new();
+        for (key, value) in entries.iter_mut() {
+            let old_value = *value;
+            let new_value = context.resolve_real_value(old_value)?;
+            if old_value != new_value {
+                context.add_value_reference(self_id, new_value)?;
+                *value = new_value;
+                context.remove_value_reference(self_id, old_value)?;
+            }
+            result.push(new_value);
+            let new_key = context.resolve_real_value(*key)?;
+            if *key != new_key {
+                context.add_value_reference(self_id, new_key)?;
+                rehashed.push((*key, new_key));
+            }
+            result.push(new_key);
+        }
+
+        for (old_key, new_key) in rehashed {
+            if let Some(value) = entries.remove(&old_key) {
+                entries.insert(new_key, value);
+            }
+            context.remove_value_reference(self_id, old_key)?;
+        }
+
+        Ok(result)
+
+    }
+
+    fn list_referenced_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let entries = self.entries.borrow();
+
+        let mut result = Vec::with_capacity(entries.len() * 2);
+        for (key, value) in entries.iter() {
+            result.push(*key);
+            result.push(*value);
+        }
+
+        result
+
+    }
+
+    fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut entries = self.entries.borrow_mut();
+
+        for value in entries.values_mut() {
+            if *value == old_value {
+                *value = new_value;
+            }
+        }
+
+        if let Some(value) = entries.remove(&old_value) {
+            entries.insert(new_value, value);
+        }
+
+    }
+
+}
+
+// Map basic properties
+impl Map {
+
+    pub fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+
+    pub fn get_length(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.entries.borrow().len()
+
+    }
+
+    pub fn has_key(&self, key: Value) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.entries.borrow().contains_key(&key)
+
+    }
+
+    pub fn get_value(&self, key: Value) -> Option<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.entries.borrow().get(&key).copied()
+
+    }
+
+}
+
+// Map operations
+impl Map {
+
+    /// Insert an entry, returns the removed and added references
+    pub fn insert(&self, key: Value, value: Value) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        match self.entries.borrow_mut().insert(key, value) {
+            Some(old_value) => ([old_value].to_vec(), [value].to_vec()),
+            None => (Vec::new(), [key, value].to_vec())
+        }
+
+    }
+
+    /// Delete an entry, returns the removed and added references
+    pub fn delete(&self, key: Value) -> (Vec<Value>, Vec<Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        match self.entries.borrow_mut().remove_entry(&key) {
+            Some((key, value)) => ([key, value].to_vec(), Vec::new()),
+            None => (Vec::new(), Vec::new())
+        }
+
+    }
+
+    /// Remove all entries, returns the removed references
+    pub fn clear(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut result = Vec::new();
+        for (key, value) in self.entries.borrow_mut().drain() {
+            result.push(key);
+            result.push(value);
+        }
+
+        result
+
+    }
+
+    pub fn list_keys(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.entries.borrow().keys().copied().collect()
+
+    }
+
+    pub fn list_entries(&self) -> Vec<(Value, Value)> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.entries.borrow().iter().map(|(key, value)| (*key, *value)).collect()
+
+    }
+
+}
+
+
+#[cfg(test)] use super::super::base::PrimitiveType::Object;
+#[cfg(test)] use super::super::isolate::Isolate;
+#[cfg(test)] use super::super::test::TestContext2;
+
+#[test]
+fn test_map_entries() {
+
+    let map = Map::new(Value::make_null(), [(Value::make_cardinal(1), Value::make_cardinal(2))].to_vec());
+
+    assert_eq!(map.get_length(), 1);
+    assert_eq!(map.get_value(Value::make_cardinal(1)), Some(Value::make_cardinal(2)));
+    assert_eq!(map.get_value(Value::make_cardinal(2)), None);
+
+    let (removes, adds) = map.insert(Value::make_cardinal(1), Value::make_cardinal(3));
+    assert_eq!(removes, [Value::make_cardinal(2)].to_vec());
+    assert_eq!(adds, [Value::make_cardinal(3)].to_vec());
+
+    let (removes, adds) = map.insert(Value::make_null(), Value::make_cardinal(4));
+    assert!(removes.is_empty());
+    assert_eq!(adds, [Value::make_null(), Value::make_cardinal(4)].to_vec());
+    assert_eq!(map.get_length(), 2);
+    assert_eq!(map.list_referenced_values().len(), 4);
+
+    let (removes, adds) = map.delete(Value::make_null());
+    assert_eq!(removes, [Value::make_null(), Value::make_cardinal(4)].to_vec());
+    assert!(adds.is_empty());
+    assert!(!map.has_key(Value::make_null()));
+
+    assert_eq!(map.clear().len(), 2);
+    assert!(map.is_empty());
+
+}
+
+#[test]
+fn test_map_refresh_reference() {
+
+    let map = Map::new(Value::make_null(), [
+        (Value::make_cardinal(1), Value::make_cardinal(2)),
+        (Value::make_cardinal(2), Value::make_cardinal(3))
+    ].to_vec());
+
+    map.refresh_referenced_value(Value::make_cardinal(2), Value::make_cardinal(5));
+
+    assert_eq!(map.get_value(Value::make_cardinal(1)), Some(Value::make_cardinal(5)));
+    assert_eq!(map.get_value(Value::make_cardinal(5)), Some(Value::make_cardinal(3)));
+    assert!(!map.has_key(Value::make_cardinal(2)));
+
+}
+
+#[test]
+fn test_map_autorefresh_moved_key() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let layout_token = isolate.create_slot_layout_token();
+
+    let subject = isolate.gain_slot(region_id, Object, Value::make_null(), &layout_token)?;
+    let key = isolate.gain_slot(region_id, Object, Value::make_null(), &layout_token)?;
+
+    let map = Map::new(subject, Vec::new());
+    let (_removes, adds) = map.insert(key, Value::make_cardinal(1));
+    for value in adds {
+        context.add_value_reference(subject, value)?;
+    }
+
+    let new_key = isolate.move_slot(key, region_id, &context)?;
+    assert_ne!(key, new_key);
+
+    let values = map.list_and_autorefresh_referenced_values(subject, &context)?;
+    assert!(values.contains(&new_key));
+    assert!(!map.has_key(key));
+    assert_eq!(map.get_value(new_key), Some(Value::make_cardinal(1)));
+
+    Ok(())
+
+}
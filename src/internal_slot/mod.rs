@@ -1,10 +1,33 @@
+mod big_int;
+mod bytes;
+mod decimal;
+mod foreign;
 mod internal_slot;
 mod list;
+mod map;
+mod set;
 mod text;
+mod timestamp;
 mod tuple;
+mod typed_list;
+mod weak_value_map;
 
+pub use big_int::BigInt;
+pub use bytes::Bytes;
+pub use decimal::Decimal;
+pub use foreign::Foreign;
 pub use internal_slot::InternalSlot;
 pub use internal_slot::ProtectedInternalSlot;
 pub use list::List;
+pub use map::Map;
+pub use set::Set;
 pub use text::Text;
+pub use timestamp::Timestamp;
 pub use tuple::Tuple;
+pub use typed_list::Float32List;
+pub use typed_list::Float64List;
+pub use typed_list::Int32List;
+pub use typed_list::Int64List;
+pub use typed_list::TypedElement;
+pub use typed_list::TypedList;
+pub use weak_value_map::WeakValueMap;
@@ -0,0 +1,346 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::cell::RefCell;
+use alloc::sync::Arc;
+use super::super::util::HashMap;
+use super::super::util::HashSet;
+
+use super::internal_slot::InternalSlot;
+use super::text::Text;
+
+use super::super::base::Error;
+use super::super::base::Value;
+use super::super::context::Context;
+use super::super::util::RwLock;
+
+/// Set of unique values
+///
+/// Values are compared by identity, texts could be compared by content if
+/// the set is created with `compare_texts_by_content`
+pub struct Set {
+    subject: Cell<Value>,
+    rw_lock: RwLock,
+    compare_texts_by_content: bool,
+    values: RefCell<HashSet<Value>>,
+    texts: RefCell<HashMap<Text, Value>>
+}
+
+// Set constructor
+impl Set {
+
+    pub fn new(subject: Value, compare_texts_by_content: bool) -> Set {
+        Set {
+            subject: Cell::new(subject),
+            rw_lock: RwLock::new(),
+            compare_texts_by_content: compare_texts_by_content,
+            values: RefCell::new(HashSet::new()),
+            texts: RefCell::new(HashMap::new())
+        }
+    }
+
+    pub fn is_comparing_texts_by_content(&self) -> bool {
+        self.compare_texts_by_content
+    }
+
+}
+
+impl InternalSlot for Set {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Some(Arc::new(Set {
+            subject: Cell::new(self.subject.get()),
+            rw_lock: RwLock::new(),
+            compare_texts_by_content: self.compare_texts_by_content,
+            values: RefCell::new(self.values.borrow().clone()),
+            texts: RefCell::new(self.texts.borrow().clone())
+        }))
+
+    }
+
+    fn get_subject(&self) -> Value {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.subject.get()
+
+    }
+
+    fn refresh_subject(&self, subject: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.subject.set(subject);
+
+    }
+
+    fn list_and_autorefresh_referenced_values(&self, self_id: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut values = self.values.borrow_mut();
+        let mut result = Vec::with_capacity(values.len());
+        let mut rehashed = Vec::new();
+        for value in values.iter() {
+            let new_value = context.resolve_real_value(*value)?;
+            if *value != new_value {
+                context.add_value_reference(self_id, new_value)?;
+                rehashed.push((*value, new_value));
+            }
+            result.push(new_value);
+        }
+
+        for (old_value, new_value) in rehashed {
+            values.remove(&old_value);
+            values.insert(new_value);
+            self.refresh_text_value(old_value, new_value);
+            context.remove_value_reference(self_id, old_value)?;
+        }
+
+        Ok(result)
+
+    }
+
+    fn list_referenced_values(&self) -> Vec<Value> {
+
+        self.list_values()
+
+    }
+
+    fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut values = self.values.borrow_mut();
+        if values.remove(&old_value) {
+            values.insert(new_value);
+            self.refresh_text_value(old_value, new_value);
+        }
+
+    }
+
+}
+
+// Set basic properties
+impl Set {
+
+    pub fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+
+    pub fn get_length(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.values.borrow().len()
+
+    }
+
+    pub fn list_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.values.borrow().iter().copied().collect()
+
+    }
+
+}
+
+// Set operations
+impl Set {
+
+    /// Test whether an equivalent value is in the set
+    pub fn has(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let text = self.resolve_text(value, context)?;
+
+        let _guard = self.rw_lock.lock_read();
+
+        match text {
+            Some(text) => Ok(self.texts.borrow().contains_key(&text)),
+            None => Ok(self.values.borrow().contains(&value))
+        }
+
+    }
+
+    /// Add a value, returns the removed and added references
+    pub fn add(&self, value: Value, context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Value>), Error> {
+
+        let text = self.resolve_text(value, context)?;
+
+        let _guard = self.rw_lock.lock_write();
+
+        if let Some(text) = text {
+            let mut texts = self.texts.borrow_mut();
+            if texts.contains_key(&text) {
+                return Ok((Vec::new(), Vec::new()));
+            }
+            texts.insert(text, value);
+        }
+
+        if self.values.borrow_mut().insert(value) {
+            Ok((Vec::new(), [value].to_vec()))
+        } else {
+            Ok((Vec::new(), Vec::new()))
+        }
+
+    }
+
+    /// Delete an equivalent value, returns the removed and added references
+    pub fn delete(&self, value: Value, context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Value>), Error> {
+
+        let text = self.resolve_text(value, context)?;
+
+        let _guard = self.rw_lock.lock_write();
+
+        let value = match text {
+            Some(text) => {
+                match self.texts.borrow_mut().remove(&text) {
+                    Some(value) => value,
+                    None => { return Ok((Vec::new(), Vec::new())); }
+                }
+            },
+            None => value
+        };
+
+        if self.values.borrow_mut().remove(&value) {
+            Ok(([value].to_vec(), Vec::new()))
+        } else {
+            Ok((Vec::new(), Vec::new()))
+        }
+
+    }
+
+    /// Remove all values, returns the removed references
+    pub fn clear(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.texts.borrow_mut().clear();
+
+        self.values.borrow_mut().drain().collect()
+
+    }
+
+}
+
+impl Set {
+
+    fn resolve_text(&self, value: Value, context: &Box<dyn Context>) -> Result<Option<Text>, Error> {
+
+        if (!self.compare_texts_by_content) || (!value.is_text()) {
+            return Ok(None);
+        }
+
+        match context.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Text>() {
+                    Some(text) => Ok(Some(text.clone())),
+                    None => Ok(None)
+                }
+            },
+            None => Ok(None)
+        }
+
+    }
+
+    fn refresh_text_value(&self, old_value: Value, new_value: Value) {
+
+        for value in self.texts.borrow_mut().values_mut() {
+            if *value == old_value {
+                *value = new_value;
+            }
+        }
+
+    }
+
+}
+
+
+#[cfg(test)] use super::super::isolate::Isolate;
+#[cfg(test)] use super::super::test::TestContext2;
+
+#[test]
+fn test_set_values() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let set = Set::new(Value::make_null(), false);
+
+    let (removes, adds) = set.add(Value::make_cardinal(1), &context)?;
+    assert!(removes.is_empty());
+    assert_eq!(adds, [Value::make_cardinal(1)].to_vec());
+
+    let (_removes, adds) = set.add(Value::make_cardinal(1), &context)?;
+    assert!(adds.is_empty());
+
+    assert!(set.has(Value::make_cardinal(1), &context)?);
+    assert!(!set.has(Value::make_cardinal(2), &context)?);
+    assert_eq!(set.get_length(), 1);
+
+    let (removes, _adds) = set.delete(Value::make_cardinal(1), &context)?;
+    assert_eq!(removes, [Value::make_cardinal(1)].to_vec());
+    assert!(set.is_empty());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_set_texts() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let foo = context.make_text("foo", &context)?;
+    let foo_2 = context.make_text("foo", &context)?;
+
+    let set = Set::new(Value::make_null(), false);
+    set.add(*foo, &context)?;
+    assert!(set.has(*foo, &context)?);
+    assert!(!set.has(*foo_2, &context)?);
+
+    let set = Set::new(Value::make_null(), true);
+    set.add(*foo, &context)?;
+    let (_removes, adds) = set.add(*foo_2, &context)?;
+    assert!(adds.is_empty());
+    assert!(set.has(*foo_2, &context)?);
+    assert_eq!(set.get_length(), 1);
+
+    let (removes, _adds) = set.delete(*foo_2, &context)?;
+    assert_eq!(removes, [*foo].to_vec());
+    assert!(set.is_empty());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_set_refresh_reference() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let set = Set::new(Value::make_null(), false);
+    set.add(Value::make_cardinal(1), &context)?;
+
+    set.refresh_referenced_value(Value::make_cardinal(1), Value::make_cardinal(2));
+
+    assert!(set.has(Value::make_cardinal(2), &context)?);
+    assert!(!set.has(Value::make_cardinal(1), &context)?);
+
+    Ok(())
+
+}
@@ -1,22 +1,26 @@
-use std::any::Any;
-use std::cmp::Ordering;
-use std::hash::{Hash, Hasher};
-use std::sync::Arc;
-use std::slice::Iter;
-use std::str::Chars;
-use std::string::FromUtf8Error;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use alloc::sync::Arc;
+use core::slice::Iter;
+use core::str::Chars;
+use alloc::string::FromUtf8Error;
 
 use super::internal_slot::InternalSlot;
 
 const AUTOSHRINK_LENGTH: usize = 64;
+const MAX_ROPE_DEPTH: usize = 32;
 
 pub struct TextCharIterator<'a> {
-    slices_iterator: Iter<'a, TextSlice>,
+    slices_iterator: TextSliceIterator<'a>,
     char_iterator: Option<Chars<'a>>
 }
 
 impl<'a> TextCharIterator<'a> {
-    fn new(slices_iterator: Iter<'a, TextSlice>) -> TextCharIterator<'a> {
+    fn new(slices_iterator: TextSliceIterator<'a>) -> TextCharIterator<'a> {
         TextCharIterator {
             slices_iterator: slices_iterator,
             char_iterator: None
@@ -65,8 +69,59 @@ impl<'a> Iterator for TextCharIterator<'a> {
 
 }
 
+pub struct TextCodePointIterator<'a> {
+    char_iterator: TextCharIterator<'a>
+}
+
+impl<'a> Iterator for TextCodePointIterator<'a> {
+
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.char_iterator.next().map(|value| value as u32)
+    }
+
+}
+
+pub struct TextUtf16Iterator<'a> {
+    char_iterator: TextCharIterator<'a>,
+    pending_unit: Option<u16>
+}
+
+impl<'a> Iterator for TextUtf16Iterator<'a> {
+
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        if let Some(unit) = self.pending_unit.take() {
+            return Some(unit);
+        }
+
+        let value = self.char_iterator.next()?;
+
+        let mut units = [0; 2];
+        let units = value.encode_utf16(&mut units);
+        if units.len() > 1 {
+            self.pending_unit = Some(units[1]);
+        }
+
+        Some(units[0])
+
+    }
+
+}
+
+/// Offsets of a position in text measured in different units
+#[derive(Clone, Copy)]
+struct TextIndex {
+    utf8: usize,
+    utf16: usize,
+    code_point: usize
+}
+
 struct TextSlice {
-    string: Arc<String>,
+    string: Arc<str>,
     utf8_from: usize,
     utf8_to: usize
 }
@@ -97,19 +152,81 @@ impl Clone for TextSlice {
     }
 }
 
+struct TextSliceIterator<'a> {
+    texts: Vec<&'a Text>,
+    slices_iterator: Option<Iter<'a, TextSlice>>
+}
+
+impl<'a> TextSliceIterator<'a> {
+    fn new(text: &'a Text) -> TextSliceIterator<'a> {
+        TextSliceIterator {
+            texts: [text].to_vec(),
+            slices_iterator: None
+        }
+    }
+}
+
+impl<'a> Iterator for TextSliceIterator<'a> {
+
+    type Item = &'a TextSlice;
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        loop {
+            if let Some(slices_iterator) = &mut self.slices_iterator {
+                if let Some(slice) = slices_iterator.next() {
+                    return Some(slice);
+                }
+                self.slices_iterator = None;
+            }
+            match self.texts.pop() {
+                None => { return None; },
+                Some(text) => {
+                    match text.node.as_ref() {
+                        TextNode::Slices(slices) => {
+                            self.slices_iterator = Some(slices.iter());
+                        },
+                        TextNode::Concatenation(left, right) => {
+                            self.texts.push(right);
+                            self.texts.push(left);
+                        }
+                    }
+                }
+            }
+        }
+
+    }
+
+}
+
+enum TextNode {
+    Slices(Vec<TextSlice>),
+    Concatenation(Text, Text)
+}
+
+/// Immutable text stored as a rope
+///
+/// Concatenations and slices share the underlying strings without copying,
+/// the rope will be flattened into a list of slices lazily if it grows too
+/// deep.
 pub struct Text {
-    slices: Vec<TextSlice>,
+    node: Arc<TextNode>,
+    depth: usize,
     cached_utf8_length: usize
 }
 
 impl Clone for Text {
     fn clone(&self) -> Self {
-        Text::new_with_slices(self.slices.clone())
+        Text {
+            node: self.node.clone(),
+            depth: self.depth,
+            cached_utf8_length: self.cached_utf8_length
+        }
     }
 }
 
-impl std::fmt::Debug for Text {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Text {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.debug_tuple("Text").field(&self.to_string()).finish()
     }
 }
@@ -184,7 +301,7 @@ impl ToString for Text {
 
         let mut string = String::new();
 
-        for slice in self.slices.iter() {
+        for slice in self.iterate_slices() {
             string.push_str(slice.string.get(slice.utf8_from .. slice.utf8_to).unwrap());
         }
 
@@ -213,7 +330,7 @@ impl Text {
 
         let mut slices = Vec::new();
         slices.push(TextSlice {
-            string: Arc::new(string.to_owned()),
+            string: Arc::from(string),
             utf8_from: 0,
             utf8_to: string.len()
         });
@@ -222,6 +339,27 @@ impl Text {
         
     }
 
+    /// Wrap a host owned string without copying, the string is kept alive
+    /// until all texts sharing it are dropped
+    pub fn from_external(string: Arc<str>) -> Text {
+
+        if string.is_empty() {
+            return Text::new_with_slices(Vec::new());
+        }
+
+        let utf8_length = string.len();
+
+        let mut slices = Vec::new();
+        slices.push(TextSlice {
+            string: string,
+            utf8_from: 0,
+            utf8_to: utf8_length
+        });
+
+        Text::new_with_slices(slices)
+
+    }
+
     pub fn from_utf8(utf8: Vec<u8>) -> Result<Text, FromUtf8Error> {
         Ok(Text::new(&String::from_utf8(utf8)?))
     }
@@ -243,7 +381,7 @@ impl Text {
 
         let mut i = 0;
         while i < repeat {
-            for slice in pattern.slices.iter() {
+            for slice in pattern.iterate_slices() {
                 slices.push(slice.clone());
             }
             i += 1;
@@ -256,22 +394,52 @@ impl Text {
     fn new_with_slices(slices: Vec<TextSlice>) -> Text {
 
         (Text {
-            slices: slices,
+            node: Arc::new(TextNode::Slices(slices)),
+            depth: 0,
             cached_utf8_length: 0
         }).autoshrink()
 
     }
 
+    /// Concatenate two texts without copying the underlying strings
+    pub fn concat(left: &Text, right: &Text) -> Text {
+
+        if left.is_empty() {
+            return right.clone();
+        }
+        if right.is_empty() {
+            return left.clone();
+        }
+
+        let utf8_length = left.get_utf8_length() + right.get_utf8_length();
+        if utf8_length < AUTOSHRINK_LENGTH {
+            let mut string = left.to_string();
+            string.push_str(&right.to_string());
+            return Text::new(&string);
+        }
+
+        let text = Text {
+            node: Arc::new(TextNode::Concatenation(left.clone(), right.clone())),
+            depth: core::cmp::max(left.depth, right.depth) + 1,
+            cached_utf8_length: utf8_length
+        };
+
+        if text.depth > MAX_ROPE_DEPTH {
+            text.flatten()
+        } else {
+            text
+        }
+
+    }
+
     pub fn concatentate(slices: Vec<Text>) -> Text {
 
-        let mut new_slices = Vec::new();
+        let mut result = Text::new("");
         for slice in slices.iter() {
-            for value in slice.slices.iter() {
-                new_slices.push(value.clone());
-            }
+            result = Text::concat(&result, slice);
         }
 
-        Text::new_with_slices(new_slices)
+        result
 
     }
 
@@ -293,27 +461,55 @@ impl Text {
 
     fn shrink(&mut self) {
 
-        if self.slices.len() == 1 {
-            return;
+        if let TextNode::Slices(slices) = self.node.as_ref() {
+            if slices.len() == 1 {
+                return;
+            }
         }
 
         let string = self.to_string();
 
         let utf8_length = string.len();
 
-        self.slices = Vec::new();
-
-        self.slices.push(TextSlice {
-            string: Arc::new(string),
+        let mut slices = Vec::new();
+        slices.push(TextSlice {
+            string: Arc::from(string),
             utf8_from: 0,
             utf8_to: utf8_length
         });
 
+        self.node = Arc::new(TextNode::Slices(slices));
+        self.depth = 0;
+
+    }
+
+    /// Collapse the rope into a flat list of slices, the underlying strings
+    /// are still shared
+    pub fn flatten(&self) -> Text {
+
+        if self.depth == 0 {
+            return self.clone();
+        }
+
+        Text {
+            node: Arc::new(TextNode::Slices(self.iterate_slices().cloned().collect())),
+            depth: 0,
+            cached_utf8_length: self.cached_utf8_length
+        }
+
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.depth == 0
+    }
+
+    fn iterate_slices(&self) -> TextSliceIterator<'_> {
+        TextSliceIterator::new(self)
     }
 
     fn calculate_utf8_length(&self) -> usize {
         let mut utf8_length = 0;
-        for slice in self.slices.iter() {
+        for slice in self.iterate_slices() {
             utf8_length += slice.get_utf8_length();
         }
         utf8_length
@@ -327,7 +523,7 @@ impl Text {
     pub fn as_utf8(&self) -> Vec<u8> {
 
         let mut utf8 = Vec::new();
-        for slice in &self.slices {
+        for slice in self.iterate_slices() {
             for value in slice.string[slice.utf8_from .. slice.utf8_to].as_bytes() {
                 utf8.push(*value);
             }
@@ -348,13 +544,84 @@ impl Text {
     }
 
     pub fn iterate_chars(&self) -> TextCharIterator {
-        TextCharIterator::new(self.slices.iter())
+        TextCharIterator::new(self.iterate_slices())
     }
 
     pub fn get_char_at(&self, index: usize) -> Option<char> {
         self.iterate_chars().skip(index).next()
     }
 
+    pub fn iter_code_points(&self) -> TextCodePointIterator<'_> {
+        TextCodePointIterator {
+            char_iterator: self.iterate_chars()
+        }
+    }
+
+    /// Iterate UTF-16 code units, chars outside BMP are split into surrogate pairs
+    pub fn iter_utf16_units(&self) -> TextUtf16Iterator<'_> {
+        TextUtf16Iterator {
+            char_iterator: self.iterate_chars(),
+            pending_unit: None
+        }
+    }
+
+}
+
+// Text index conversions
+//
+// Indices should be at boundaries of chars, or equal to the length of the
+// text. `None` will be returned for indices inside a char or out of range.
+impl Text {
+
+    pub fn utf8_index_to_utf16_index(&self, utf8_index: usize) -> Option<usize> {
+        self.convert_index(utf8_index, |index| index.utf8).map(|index| index.utf16)
+    }
+
+    pub fn utf16_index_to_utf8_index(&self, utf16_index: usize) -> Option<usize> {
+        self.convert_index(utf16_index, |index| index.utf16).map(|index| index.utf8)
+    }
+
+    pub fn utf8_index_to_code_point_index(&self, utf8_index: usize) -> Option<usize> {
+        self.convert_index(utf8_index, |index| index.utf8).map(|index| index.code_point)
+    }
+
+    pub fn code_point_index_to_utf8_index(&self, code_point_index: usize) -> Option<usize> {
+        self.convert_index(code_point_index, |index| index.code_point).map(|index| index.utf8)
+    }
+
+    pub fn utf16_index_to_code_point_index(&self, utf16_index: usize) -> Option<usize> {
+        self.convert_index(utf16_index, |index| index.utf16).map(|index| index.code_point)
+    }
+
+    pub fn code_point_index_to_utf16_index(&self, code_point_index: usize) -> Option<usize> {
+        self.convert_index(code_point_index, |index| index.code_point).map(|index| index.utf16)
+    }
+
+    fn convert_index(&self, index: usize, measure: fn(&TextIndex) -> usize) -> Option<TextIndex> {
+
+        let mut position = TextIndex {
+            utf8: 0,
+            utf16: 0,
+            code_point: 0
+        };
+
+        for value in self.iterate_chars() {
+            if measure(&position) >= index {
+                break;
+            }
+            position.utf8 += value.len_utf8();
+            position.utf16 += value.len_utf16();
+            position.code_point += 1;
+        }
+
+        if measure(&position) == index {
+            Some(position)
+        } else {
+            None
+        }
+
+    }
+
 }
 
 // Text basic properties
@@ -372,6 +639,10 @@ impl Text {
         self.iterate_chars().count()
     }
 
+    pub fn get_utf16_length(&self) -> usize {
+        self.iterate_chars().map(|value| value.len_utf16()).sum()
+    }
+
 }
 
 // Text operations
@@ -382,7 +653,7 @@ impl Text {
         let mut new_slices = Vec::new();
 
         let mut index = 0;
-        let mut slices_iterator = self.slices.iter();
+        let mut slices_iterator = self.iterate_slices();
         loop {
 
             let next = slices_iterator.next();
@@ -614,6 +885,97 @@ fn test_shrink() {
     let abc = Text::concatentate([Text::new("a"), Text::new("bc")].to_vec());
 
     assert_eq!(&abc.to_string(), "abc");
-    assert_eq!(abc.slices.len(), 1);
+    assert_eq!(abc.iterate_slices().count(), 1);
+
+}
+#[test]
+fn test_rope_concat() {
+
+    let foo = Text::new(&"foo ".repeat(16));
+    let bar = Text::new(&"bar ".repeat(16));
+
+    let foo_bar = Text::concat(&foo, &bar);
+
+    assert!(!foo_bar.is_flat());
+    assert_eq!(foo_bar.get_utf8_length(), 128);
+    assert_eq!(foo_bar.iterate_slices().count(), 2);
+    assert_eq!(foo_bar.to_string(), format!("{}{}", "foo ".repeat(16), "bar ".repeat(16)));
+    assert_eq!(&foo_bar.slice(60, 68).to_string(), "foo bar ");
+
+    assert!(Text::concat(&foo, &Text::new("")).is_flat());
+
+}
+
+#[test]
+fn test_rope_flatten() {
+
+    let foo = Text::new(&"foo ".repeat(16));
+
+    let mut text = foo.clone();
+    for _ in 0 .. MAX_ROPE_DEPTH + 1 {
+        text = Text::concat(&text, &foo);
+    }
+
+    assert!(text.is_flat());
+    assert_eq!(text.get_utf8_length(), 64 * (MAX_ROPE_DEPTH + 2));
+    assert_eq!(text.iterate_slices().count(), MAX_ROPE_DEPTH + 2);
+    assert_eq!(text.flatten(), text);
+
+}
+
+#[test]
+fn test_from_external() {
 
-}
\ No newline at end of file
+    let string: Arc<str> = Arc::from("foo bar");
+
+    let text = Text::from_external(string.clone());
+    assert_eq!(Arc::strong_count(&string), 2);
+    assert_eq!(&text.to_string(), "foo bar");
+
+    let foo = text.slice(0, 3);
+    assert_eq!(Arc::strong_count(&string), 3);
+    assert_eq!(&foo.to_string(), "foo");
+
+    drop(text);
+    drop(foo);
+    assert_eq!(Arc::strong_count(&string), 1);
+
+    assert!(Text::from_external(Arc::from("")).is_empty());
+
+}
+
+#[test]
+fn test_code_points() {
+
+    let text = Text::concatentate([Text::new("a\u{e9}"), Text::new("\u{1f600}")].to_vec());
+
+    assert_eq!(text.iter_code_points().collect::<Vec<u32>>(), [0x61, 0xe9, 0x1f600].to_vec());
+    assert_eq!(text.iter_utf16_units().collect::<Vec<u16>>(), [0x61, 0xe9, 0xd83d, 0xde00].to_vec());
+    assert_eq!(text.get_utf16_length(), 4);
+
+}
+
+#[test]
+fn test_index_conversions() {
+
+    let text = Text::new("a\u{e9}\u{1f600}b");
+
+    assert_eq!(text.utf8_index_to_utf16_index(3), Some(2));
+    assert_eq!(text.utf8_index_to_utf16_index(7), Some(4));
+    assert_eq!(text.utf8_index_to_utf16_index(8), Some(5));
+    assert_eq!(text.utf8_index_to_utf16_index(2), None);
+    assert_eq!(text.utf8_index_to_utf16_index(9), None);
+
+    assert_eq!(text.utf16_index_to_utf8_index(4), Some(7));
+    assert_eq!(text.utf16_index_to_utf8_index(3), None);
+
+    assert_eq!(text.utf8_index_to_code_point_index(7), Some(3));
+    assert_eq!(text.code_point_index_to_utf8_index(2), Some(3));
+
+    assert_eq!(text.utf16_index_to_code_point_index(4), Some(3));
+    assert_eq!(text.code_point_index_to_utf16_index(3), Some(4));
+    assert_eq!(text.code_point_index_to_utf16_index(5), None);
+
+    assert_eq!(Text::new("").utf16_index_to_utf8_index(0), Some(0));
+
+}
@@ -0,0 +1,161 @@
+use core::any::Any;
+#[cfg(feature = "std")] use std::time::Duration;
+#[cfg(feature = "std")] use std::time::SystemTime;
+#[cfg(feature = "std")] use std::time::UNIX_EPOCH;
+
+use super::internal_slot::InternalSlot;
+
+const NANOSECONDS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Point in time with a timezone offset
+///
+/// The instant is stored as nanoseconds since the unix epoch in UTC, the
+/// offset only affects the local time, two timestamps with the same instant
+/// but different offsets are still equal.
+#[derive(Clone, Copy, Debug)]
+pub struct Timestamp {
+    epoch_nanoseconds: i128,
+    offset_seconds: i32
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.epoch_nanoseconds == other.epoch_nanoseconds
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.epoch_nanoseconds.cmp(&other.epoch_nanoseconds)
+    }
+}
+
+impl InternalSlot for Timestamp {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+}
+
+// Timestamp constructors
+impl Timestamp {
+
+    pub fn new(epoch_nanoseconds: i128, offset_seconds: i32) -> Timestamp {
+        Timestamp {
+            epoch_nanoseconds: epoch_nanoseconds,
+            offset_seconds: offset_seconds
+        }
+    }
+
+    /// Current time, only available with the `std` feature
+    #[cfg(feature = "std")]
+    pub fn now(offset_seconds: i32) -> Timestamp {
+        Timestamp::from_system_time(SystemTime::now(), offset_seconds)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_system_time(time: SystemTime, offset_seconds: i32) -> Timestamp {
+        let epoch_nanoseconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as i128,
+            Err(error) => -(error.duration().as_nanos() as i128)
+        };
+        Timestamp::new(epoch_nanoseconds, offset_seconds)
+    }
+
+}
+
+// Timestamp basic properties
+impl Timestamp {
+
+    pub fn get_epoch_nanoseconds(&self) -> i128 {
+        self.epoch_nanoseconds
+    }
+
+    pub fn get_epoch_milliseconds(&self) -> i128 {
+        self.epoch_nanoseconds.div_euclid(1_000_000)
+    }
+
+    /// Get the offset from UTC in seconds, positive for east of Greenwich
+    pub fn get_offset_seconds(&self) -> i32 {
+        self.offset_seconds
+    }
+
+    /// Get nanoseconds since the unix epoch in local time of the offset
+    pub fn get_local_nanoseconds(&self) -> i128 {
+        self.epoch_nanoseconds + (self.offset_seconds as i128) * NANOSECONDS_PER_SECOND
+    }
+
+}
+
+// Timestamp conversions
+impl Timestamp {
+
+    /// Get the same instant with another offset
+    pub fn with_offset(&self, offset_seconds: i32) -> Timestamp {
+        Timestamp::new(self.epoch_nanoseconds, offset_seconds)
+    }
+
+    /// Convert to system time, `None` will be returned if out of range
+    #[cfg(feature = "std")]
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+
+        let seconds = self.epoch_nanoseconds.div_euclid(NANOSECONDS_PER_SECOND);
+        let nanoseconds = self.epoch_nanoseconds.rem_euclid(NANOSECONDS_PER_SECOND) as u32;
+
+        if seconds >= 0 {
+            if seconds > (u64::MAX as i128) {
+                return None;
+            }
+            UNIX_EPOCH.checked_add(Duration::new(seconds as u64, nanoseconds))
+        } else {
+            if -seconds > (u64::MAX as i128) {
+                return None;
+            }
+            UNIX_EPOCH.checked_sub(Duration::new((-seconds) as u64, 0))?
+                      .checked_add(Duration::new(0, nanoseconds))
+        }
+
+    }
+
+}
+
+#[test]
+fn test_timestamp_system_time() {
+
+    let time = UNIX_EPOCH + Duration::new(1_600_000_000, 123);
+    let timestamp = Timestamp::from_system_time(time, 8 * 3600);
+
+    assert_eq!(timestamp.get_epoch_nanoseconds(), 1_600_000_000_000_000_123);
+    assert_eq!(timestamp.get_epoch_milliseconds(), 1_600_000_000_000);
+    assert_eq!(timestamp.to_system_time(), Some(time));
+
+    let time = UNIX_EPOCH - Duration::new(10, 500);
+    let timestamp = Timestamp::from_system_time(time, 0);
+
+    assert_eq!(timestamp.get_epoch_nanoseconds(), -10_000_000_500);
+    assert_eq!(timestamp.get_epoch_milliseconds(), -10_001);
+    assert_eq!(timestamp.to_system_time(), Some(time));
+
+}
+
+#[test]
+fn test_timestamp_offset() {
+
+    let timestamp = Timestamp::new(0, 3600);
+    let timestamp_2 = timestamp.with_offset(-1800);
+
+    assert_eq!(timestamp.get_local_nanoseconds(), 3600 * NANOSECONDS_PER_SECOND);
+    assert_eq!(timestamp_2.get_local_nanoseconds(), -1800 * NANOSECONDS_PER_SECOND);
+    assert_eq!(timestamp, timestamp_2);
+    assert!(Timestamp::new(1, 0) > timestamp);
+
+}
@@ -1,11 +1,16 @@
-use std::any::Any;
-use std::cell::Cell;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use alloc::sync::Arc;
 
 use super::internal_slot::InternalSlot;
 
 use super::super::base::Error;
+use super::super::base::Symbol;
 use super::super::base::Value;
 use super::super::context::Context;
+use super::super::field_shortcuts::FieldTemplate;
 use super::super::util::RwLock;
 
 
@@ -13,6 +18,7 @@ pub struct Tuple {
     rw_lock: RwLock,
     subject: Cell<Value>,
     id: u32,
+    field_template: Option<Arc<FieldTemplate>>,
     values: Vec<Cell<Value>>
 }
 
@@ -28,14 +34,27 @@ impl Tuple {
             rw_lock: RwLock::new(),
             subject: Cell::new(subject),
             id: id,
+            field_template: None,
             values: new_values
         }
     }
 
+    /// Create a tuple with named fields, the field template maps symbols to
+    /// the indices of values, and could be shared across tuples
+    pub fn new_with_field_template(subject: Value, id: u32, field_template: Arc<FieldTemplate>, values: Vec<Value>) -> Tuple {
+        let mut tuple = Tuple::new(subject, id, values);
+        tuple.field_template = Some(field_template);
+        tuple
+    }
+
     pub fn get_id(&self) -> u32 {
         self.id
     }
 
+    pub fn get_field_template(&self) -> Option<Arc<FieldTemplate>> {
+        self.field_template.clone()
+    }
+
 }
 
 impl InternalSlot for Tuple {
@@ -44,6 +63,20 @@ impl InternalSlot for Tuple {
         self
     }
 
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Some(Arc::new(Tuple {
+            rw_lock: RwLock::new(),
+            subject: Cell::new(self.subject.get()),
+            id: self.id,
+            field_template: self.field_template.clone(),
+            values: self.values.clone()
+        }))
+
+    }
+
     fn refresh_subject(&self, subject: Value) {
 
         let _guard = self.rw_lock.lock_write();
@@ -115,6 +148,18 @@ impl Tuple {
 
     }
 
+    /// Get value of a named field, `None` will be returned if the field is
+    /// not defined in the field template
+    pub fn get_field(&self, symbol: Symbol) -> Option<Value> {
+
+        let index = self.field_template.as_ref()?.get_symbol_index(symbol)? as usize;
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.values.get(index).map(|value| value.get())
+
+    }
+
 }
 
 impl Tuple {
@@ -184,3 +229,25 @@ fn test_get_element() {
     assert_eq!(tuple.get_element(2), Value::make_undefined());
 
 }
+
+#[test]
+fn test_named_fields() -> Result<(), Error> {
+
+    let field_template = Arc::new(FieldTemplate::new(1));
+    field_template.add_symbol(Symbol::new(1))?;
+    field_template.add_symbol(Symbol::new(2))?;
+
+    let tuple = Tuple::new_with_field_template(Value::make_null(), 0, field_template.clone(), [Value::make_cardinal(23), Value::make_cardinal(34)].to_vec());
+    let tuple_2 = Tuple::new_with_field_template(Value::make_null(), 0, field_template.clone(), [Value::make_cardinal(45)].to_vec());
+
+    assert_eq!(tuple.get_field(Symbol::new(1)), Some(Value::make_cardinal(23)));
+    assert_eq!(tuple.get_field(Symbol::new(2)), Some(Value::make_cardinal(34)));
+    assert_eq!(tuple.get_field(Symbol::new(3)), None);
+    assert_eq!(tuple_2.get_field(Symbol::new(2)), None);
+    assert!(Arc::ptr_eq(&tuple.get_field_template().unwrap(), &tuple_2.get_field_template().unwrap()));
+
+    assert_eq!(Tuple::new(Value::make_null(), 0, Vec::new()).get_field(Symbol::new(1)), None);
+
+    Ok(())
+
+}
@@ -0,0 +1,217 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::RefCell;
+use alloc::sync::Arc;
+
+use super::internal_slot::InternalSlot;
+
+use super::super::util::RwLock;
+
+/// Element types could be stored in typed lists
+pub trait TypedElement: Copy + Default + PartialEq + 'static {}
+
+impl TypedElement for i32 {}
+impl TypedElement for i64 {}
+impl TypedElement for f32 {}
+impl TypedElement for f64 {}
+
+/// Contiguously stored numeric list without value boxing
+pub struct TypedList<T: TypedElement> {
+    rw_lock: RwLock,
+    elements: RefCell<Vec<T>>
+}
+
+pub type Int32List = TypedList<i32>;
+pub type Int64List = TypedList<i64>;
+pub type Float32List = TypedList<f32>;
+pub type Float64List = TypedList<f64>;
+
+// Typed list constructors
+impl<T: TypedElement> TypedList<T> {
+
+    pub fn new(elements: Vec<T>) -> TypedList<T> {
+        TypedList {
+            rw_lock: RwLock::new(),
+            elements: RefCell::new(elements)
+        }
+    }
+
+    /// Create a typed list filled with default elements
+    pub fn with_length(length: usize) -> TypedList<T> {
+        TypedList::new(vec![T::default(); length])
+    }
+
+}
+
+impl<T: TypedElement> InternalSlot for TypedList<T> {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn duplicate(&self) -> Option<Arc<dyn InternalSlot>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Some(Arc::new(TypedList::new(self.elements.borrow().clone())))
+
+    }
+
+}
+
+// Typed list basic properties
+impl<T: TypedElement> TypedList<T> {
+
+    pub fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+
+    pub fn get_length(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.elements.borrow().len()
+
+    }
+
+    pub fn get_element(&self, index: usize) -> Option<T> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.elements.borrow().get(index).copied()
+
+    }
+
+    pub fn set_element(&self, index: usize, element: T) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        match self.elements.borrow_mut().get_mut(index) {
+            Some(value) => {
+                *value = element;
+                true
+            },
+            None => false
+        }
+
+    }
+
+    pub fn as_vec(&self) -> Vec<T> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.elements.borrow().clone()
+
+    }
+
+}
+
+// Typed list operations
+impl<T: TypedElement> TypedList<T> {
+
+    /// Fill elements in range `from .. to` with the element
+    pub fn fill(&self, element: T, from: usize, to: usize) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        match self.elements.borrow_mut().get_mut(from .. to) {
+            Some(slice) => {
+                for value in slice.iter_mut() {
+                    *value = element;
+                }
+                true
+            },
+            None => false
+        }
+
+    }
+
+    /// Copy elements in range `from .. to` to the position started at `target`
+    pub fn copy_within(&self, from: usize, to: usize, target: usize) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut elements = self.elements.borrow_mut();
+        if (from > to) || (to > elements.len()) {
+            return false;
+        }
+        match target.checked_add(to - from) {
+            Some(end) if end <= elements.len() => {},
+            _ => {
+                return false;
+            }
+        }
+
+        elements.copy_within(from .. to, target);
+
+        true
+
+    }
+
+    pub fn resize(&self, length: usize) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.elements.borrow_mut().resize(length, T::default());
+
+    }
+
+}
+
+#[test]
+fn test_typed_list_creation() {
+
+    let list = Int32List::new([1, 2, 3].to_vec());
+    assert_eq!(list.get_length(), 3);
+    assert!(list.list_referenced_values().is_empty());
+
+    let list = Float64List::with_length(4);
+    assert_eq!(list.get_length(), 4);
+    assert_eq!(list.get_element(3), Some(0.0));
+    assert_eq!(list.get_element(4), None);
+
+}
+
+#[test]
+fn test_typed_list_elements() {
+
+    let list = Int64List::new([1, 2, 3].to_vec());
+
+    assert!(list.set_element(1, 5));
+    assert!(!list.set_element(3, 5));
+    assert_eq!(list.as_vec(), [1, 5, 3].to_vec());
+
+    list.resize(5);
+    assert_eq!(list.as_vec(), [1, 5, 3, 0, 0].to_vec());
+
+}
+
+#[test]
+fn test_typed_list_fill() {
+
+    let list = Float32List::with_length(4);
+
+    assert!(list.fill(1.5, 1, 3));
+    assert_eq!(list.as_vec(), [0.0, 1.5, 1.5, 0.0].to_vec());
+
+    assert!(!list.fill(1.5, 3, 5));
+
+}
+
+#[test]
+fn test_typed_list_copy_within() {
+
+    let list = Int32List::new([1, 2, 3, 4, 5].to_vec());
+
+    assert!(list.copy_within(0, 2, 3));
+    assert_eq!(list.as_vec(), [1, 2, 3, 1, 2].to_vec());
+
+    assert!(list.copy_within(1, 4, 0));
+    assert_eq!(list.as_vec(), [2, 3, 1, 1, 2].to_vec());
+
+    assert!(!list.copy_within(0, 3, 3));
+    assert!(!list.copy_within(3, 2, 0));
+    assert!(!list.copy_within(0, 1, usize::MAX));
+
+}
@@ -0,0 +1,460 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::cell::RefCell;
+use super::super::util::HashMap;
+use alloc::sync::Arc;
+use alloc::sync::Weak;
+
+use super::internal_slot::InternalSlot;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+use super::super::base::Value;
+use super::super::context::Context;
+use super::super::isolate::Isolate;
+use super::super::root::DropListener;
+use super::super::root::WeakRoot;
+use super::super::util::RwLock;
+
+/// Drop listener of a key, each key has its own listener knowing the entry
+/// to remove
+struct WeakValueMapDropListener {
+    entries: Weak<WeakValueMapEntries>,
+    entry_id: u64
+}
+
+impl DropListener for WeakValueMapDropListener {
+
+    fn notify_drop(&self) {
+        if let Some(entries) = self.entries.upgrade() {
+            entries.remove_dropped_entry(self.entry_id);
+        }
+    }
+
+}
+
+struct WeakValueMapEntry {
+    id: u64,
+    root: Arc<WeakRoot>,
+    value: Value
+}
+
+/// Entries of a weak value map, shared with the drop listeners of the keys
+/// to remove entries once their keys dropped
+struct WeakValueMapEntries {
+    rw_lock: RwLock,
+    next_entry_id: Cell<u64>,
+    entries: RefCell<HashMap<Value, WeakValueMapEntry>>,
+    keys: RefCell<HashMap<u64, Value>>,
+    released_values: RefCell<Vec<Value>>
+}
+
+impl WeakValueMapEntries {
+
+    fn next_entry_id(&self) -> u64 {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let id = self.next_entry_id.get();
+        self.next_entry_id.set(id + 1);
+
+        id
+
+    }
+
+    /// Remove the entry of a dropped key, the value is kept to release its
+    /// reference later, as the drop is notified without a context
+    fn remove_dropped_entry(&self, entry_id: u64) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let key = self.keys.borrow_mut().remove(&entry_id);
+
+        if let Some(entry) = key.and_then(|key| self.entries.borrow_mut().remove(&key)) {
+            self.released_values.borrow_mut().push(entry.value);
+        }
+
+    }
+
+    fn rehash_moved_keys(&self) {
+
+        let mut entries = self.entries.borrow_mut();
+
+        let moved_keys: Vec<(Value, Value)> = entries.iter()
+            .filter_map(|(key, entry)| match entry.root.get_value() {
+                Some(value) if value != *key => Some((*key, value)),
+                _ => None
+            })
+            .collect();
+
+        let mut keys = self.keys.borrow_mut();
+        for (old_key, new_key) in moved_keys {
+            if let Some(entry) = entries.remove(&old_key) {
+                keys.insert(entry.id, new_key);
+                entries.insert(new_key, entry);
+            }
+        }
+
+    }
+
+}
+
+/// Hash map from slotted keys to values, the keys are held weakly
+///
+/// Entries are removed once their keys are dropped by the isolate. The keys
+/// are registered as weak roots, and the map will be notified through the
+/// drop listeners while the isolate drops the keys. Values are still held
+/// strongly, so a value referencing its own key will keep the entry alive.
+pub struct WeakValueMap {
+    isolate: Weak<Isolate>,
+    subject: Cell<Value>,
+    rw_lock: RwLock,
+    entries: Arc<WeakValueMapEntries>
+}
+
+// Weak value map constructor
+impl WeakValueMap {
+
+    pub fn new(subject: Value, isolate: &Arc<Isolate>) -> WeakValueMap {
+        WeakValueMap {
+            isolate: Arc::downgrade(isolate),
+            subject: Cell::new(subject),
+            rw_lock: RwLock::new(),
+            entries: Arc::new(WeakValueMapEntries {
+                rw_lock: RwLock::new(),
+                next_entry_id: Cell::new(1),
+                entries: RefCell::new(HashMap::new()),
+                keys: RefCell::new(HashMap::new()),
+                released_values: RefCell::new(Vec::new())
+            })
+        }
+    }
+
+}
+
+impl Drop for WeakValueMap {
+    fn drop(&mut self) {
+        let entries: Vec<WeakValueMapEntry> = {
+            let _guard = self.entries.rw_lock.lock_write();
+            self.entries.keys.borrow_mut().clear();
+            self.entries.entries.borrow_mut().drain().map(|(_key, entry)| entry).collect()
+        };
+        // The weak roots are gone with the isolate, and a failed removal
+        // only leaves a weak root whose listener has nothing to notify
+        if let Some(isolate) = self.isolate.upgrade() {
+            for entry in entries {
+                let _ = isolate.remove_weak_root(&entry.root);
+            }
+        }
+    }
+}
+
+impl InternalSlot for WeakValueMap {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_subject(&self) -> Value {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.subject.get()
+
+    }
+
+    fn refresh_subject(&self, subject: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.subject.set(subject);
+
+    }
+
+    fn list_and_autorefresh_referenced_values(&self, self_id: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        for value in self.purge_dropped_entries() {
+            context.remove_value_reference(self_id, value)?;
+        }
+
+        let _guard = self.entries.rw_lock.lock_write();
+
+        self.entries.rehash_moved_keys();
+
+        let mut entries = self.entries.entries.borrow_mut();
+        let mut result = Vec::with_capacity(entries.len());
+        for entry in entries.values_mut() {
+            let old_value = entry.value;
+            let new_value = context.resolve_real_value(old_value)?;
+            if old_value != new_value {
+                context.add_value_reference(self_id, new_value)?;
+                entry.value = new_value;
+                context.remove_value_reference(self_id, old_value)?;
+            }
+            result.push(new_value);
+        }
+
+        Ok(result)
+
+    }
+
+    fn list_referenced_values(&self) -> Vec<Value> {
+
+        let _guard = self.entries.rw_lock.lock_read();
+
+        self.entries.entries.borrow().values().map(|entry| entry.value).collect()
+
+    }
+
+    fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
+
+        let _guard = self.entries.rw_lock.lock_write();
+
+        for entry in self.entries.entries.borrow_mut().values_mut() {
+            if entry.value == old_value {
+                entry.value = new_value;
+            }
+        }
+
+    }
+
+}
+
+// Weak value map basic properties
+impl WeakValueMap {
+
+    pub fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+
+    /// Get the count of entries whose keys are still alive
+    pub fn get_length(&self) -> usize {
+
+        let _guard = self.entries.rw_lock.lock_read();
+
+        self.entries.entries.borrow().len()
+
+    }
+
+    pub fn has_key(&self, key: Value) -> bool {
+        self.get_value(key).is_some()
+    }
+
+    pub fn get_value(&self, key: Value) -> Option<Value> {
+
+        let _guard = self.entries.rw_lock.lock_read();
+
+        let entries = self.entries.entries.borrow();
+        if let Some(entry) = entries.get(&key) {
+            if entry.root.get_value() == Some(key) {
+                return Some(entry.value);
+            }
+        }
+
+        for entry in entries.values() {
+            if entry.root.get_value() == Some(key) {
+                return Some(entry.value);
+            }
+        }
+
+        None
+
+    }
+
+}
+
+// Weak value map operations
+impl WeakValueMap {
+
+    /// Insert an entry, returns the removed and added references
+    pub fn insert(&self, key: Value, value: Value, context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Value>), Error> {
+
+        if !key.is_slotted() {
+            return Err(Error::new(TypeNotMatch, "Only slotted value could be weak key"));
+        }
+
+        let key = context.resolve_real_value(key)?;
+
+        {
+            let _guard = self.entries.rw_lock.lock_write();
+            self.entries.rehash_moved_keys();
+            if let Some(entry) = self.entries.entries.borrow_mut().get_mut(&key) {
+                let old_value = entry.value;
+                entry.value = value;
+                return Ok(([old_value].to_vec(), [value].to_vec()));
+            }
+        }
+
+        let entry_id = self.entries.next_entry_id();
+
+        let drop_listener: Box<dyn DropListener> = Box::new(WeakValueMapDropListener {
+            entries: Arc::downgrade(&self.entries),
+            entry_id: entry_id
+        });
+        let root = context.add_weak_root(key, Some(drop_listener))?;
+
+        let _guard = self.entries.rw_lock.lock_write();
+
+        self.entries.keys.borrow_mut().insert(entry_id, key);
+        self.entries.entries.borrow_mut().insert(key, WeakValueMapEntry {
+            id: entry_id,
+            root: root,
+            value: value
+        });
+
+        Ok((Vec::new(), [value].to_vec()))
+
+    }
+
+    /// Delete an entry, returns the removed and added references
+    pub fn delete(&self, key: Value, context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Value>), Error> {
+
+        let key = context.resolve_real_value(key)?;
+
+        let entry = {
+            let _guard = self.entries.rw_lock.lock_write();
+            self.entries.rehash_moved_keys();
+            let entry = self.entries.entries.borrow_mut().remove(&key);
+            if let Some(entry) = entry.as_ref() {
+                self.entries.keys.borrow_mut().remove(&entry.id);
+            }
+            entry
+        };
+
+        match entry {
+            Some(entry) => {
+                context.remove_weak_root(&entry.root)?;
+                Ok(([entry.value].to_vec(), Vec::new()))
+            },
+            None => Ok((Vec::new(), Vec::new()))
+        }
+
+    }
+
+    /// Take values of entries removed since their keys dropped, returns the
+    /// removed references
+    pub fn purge_dropped_entries(&self) -> Vec<Value> {
+
+        let _guard = self.entries.rw_lock.lock_write();
+
+        core::mem::take(&mut *self.entries.released_values.borrow_mut())
+
+    }
+
+}
+
+#[cfg(test)] use super::super::base::PrimitiveType::Object;
+#[cfg(test)] use super::super::test::TestContext2;
+
+#[test]
+fn test_weak_value_map_entries() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let map = WeakValueMap::new(Value::make_null(), &isolate);
+
+    let key = context.gain_slot(Object, Value::make_null())?;
+
+    assert!(map.insert(Value::make_cardinal(1), Value::make_cardinal(1), &context).is_err());
+
+    let (removes, adds) = map.insert(key, Value::make_cardinal(2), &context)?;
+    assert!(removes.is_empty());
+    assert_eq!(adds, [Value::make_cardinal(2)].to_vec());
+    assert_eq!(map.get_value(key), Some(Value::make_cardinal(2)));
+
+    let (removes, adds) = map.insert(key, Value::make_cardinal(3), &context)?;
+    assert_eq!(removes, [Value::make_cardinal(2)].to_vec());
+    assert_eq!(adds, [Value::make_cardinal(3)].to_vec());
+    assert_eq!(map.get_length(), 1);
+
+    let (removes, _adds) = map.delete(key, &context)?;
+    assert_eq!(removes, [Value::make_cardinal(3)].to_vec());
+    assert!(map.is_empty());
+    assert_eq!(isolate.count_weak_roots(), 0);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_weak_value_map_dropped_key() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let map = WeakValueMap::new(Value::make_null(), &isolate);
+
+    let key = context.gain_slot(Object, Value::make_null())?;
+    let key_2 = context.gain_slot(Object, Value::make_null())?;
+
+    map.insert(key, Value::make_cardinal(1), &context)?;
+    map.insert(key_2, Value::make_cardinal(2), &context)?;
+
+    isolate.move_value_out_from_nursery(key, context.get_slot_layout_token())?;
+    isolate.recycle_slot(key, &context)?;
+
+    assert_eq!(map.get_length(), 1);
+    assert!(!map.has_key(key));
+
+    assert_eq!(map.purge_dropped_entries(), [Value::make_cardinal(1)].to_vec());
+    assert!(map.purge_dropped_entries().is_empty());
+    assert_eq!(map.list_referenced_values(), [Value::make_cardinal(2)].to_vec());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_weak_value_map_moved_key() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let map = WeakValueMap::new(Value::make_null(), &isolate);
+
+    let key = context.gain_slot(Object, Value::make_null())?;
+    let region_id = key.get_region_id()?;
+
+    map.insert(key, Value::make_cardinal(1), &context)?;
+
+    let new_key = isolate.move_slot(key, region_id, &context)?;
+
+    assert_eq!(map.get_value(new_key), Some(Value::make_cardinal(1)));
+
+    map.list_and_autorefresh_referenced_values(Value::make_null(), &context)?;
+    assert_eq!(map.get_value(new_key), Some(Value::make_cardinal(1)));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_weak_value_map_isolate_drop() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+    let weak_isolate = Arc::downgrade(&isolate);
+
+    let map = {
+        let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+        let map = WeakValueMap::new(Value::make_null(), &isolate);
+        let key = context.gain_slot(Object, Value::make_null())?;
+        map.insert(key, Value::make_cardinal(1), &context)?;
+        map
+    };
+
+    assert_eq!(Arc::strong_count(&isolate), 1);
+
+    drop(isolate);
+    assert!(weak_isolate.upgrade().is_none());
+
+    drop(map);
+
+    Ok(())
+
+}
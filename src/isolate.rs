@@ -1,43 +1,136 @@
-use std::any::Any;
-use std::cell::{ Cell, RefCell };
-use std::collections::{ HashMap, HashSet };
-use std::sync::Arc;
-use std::sync::atomic::{ AtomicU64, Ordering };
-
+use alloc::boxed::Box;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::{ Cell, RefCell };
+use super::util::HashMap;
+use super::util::HashSet;
+#[cfg(feature = "std")] use std::io::{ Read, Write };
+#[cfg(feature = "std")] use core::time::Duration;
+use alloc::sync::Arc;
+use core::sync::atomic::{ AtomicU32, AtomicU64, Ordering };
+
+use super::base::CustomErrorInfo;
+use super::base::CustomErrorRegistry;
 use super::base::Error;
 use super::base::ErrorType::*;
 use super::base::PrimitiveType;
 use super::base::PrimitiveType::*;
 use super::base::Symbol;
+use super::base::SymbolCacheEntry;
 use super::base::SymbolInfo;
 use super::base::SymbolIdGenerator;
+use super::base::SymbolRegistry;
 use super::base::SymbolScope;
+use super::base::TextSymbolCache;
+use super::base::WellKnownSymbols;
 use super::base::Value;
+use super::access_policy::check_access;
 use super::barrier::Barrier;
 use super::context::Context;
+use super::context::RecursionGuard;
+use super::convert::FromValue;
+use super::convert::ToValue;
 use super::field_shortcuts::FieldShortcuts;
+use super::fork_color::ForkColor;
+use super::fork_color::ForkColors;
 use super::field_shortcuts::FieldToken;
+use super::internal_slot::BigInt;
+use super::internal_slot::Bytes;
+use super::internal_slot::Decimal;
+use super::internal_slot::Foreign;
 use super::internal_slot::InternalSlot;
 use super::internal_slot::ProtectedInternalSlot;
 use super::internal_slot::List;
 use super::internal_slot::Text;
+use super::internal_slot::Timestamp;
+use super::internal_slot::Tuple;
+use super::coercion::ValueCoercer;
+use super::describe::DescribeOptions;
+use super::describe::DisplayValue;
+use super::describe::TextFormatter;
+use super::describe::ValueDescriber;
+#[cfg(feature = "fault-injection")] use super::fault::FaultInjector;
+#[cfg(feature = "fault-injection")] use super::fault::LockAccess;
+use super::json::JsonParser;
+use super::json::JsonWriter;
+use super::metrics::Metrics;
+use super::collector::GcStressMode;
+use super::gc_listener::GcListener;
+use super::gc_listener::GcListeners;
+use super::metrics::MetricsCounters;
+use super::profiler::AllocationProfile;
+use super::profiler::AllocationSampler;
+use super::profiler::HeapCensus;
+#[cfg(feature = "provenance")] use super::provenance::Provenance;
+#[cfg(feature = "provenance")] use super::provenance::Provenances;
+use super::quota::Quota;
+use super::quota::QuotaUsage;
+use super::quota::Quotas;
+use super::reference_map::ReferenceMap;
+use super::reference_map::ReferenceMapStats;
 use super::region::Region;
+use super::region::RegionKind;
+use super::cold_region::ColdRegionStorage;
+use super::cold_region::MemoryColdRegionStorage;
+use super::isolate_options::IsolateOptions;
+use super::isolate_options::RegionAffinity;
+use super::space::Space;
+use super::space::SpacePolicy;
+use super::watermark::RegionWatermarkListener;
+use super::watermark::RegionWatermarks;
+use super::shared_region::SharedRegion;
 use super::storage::Pinned;
+#[cfg(feature = "std")] use super::transfer::TransferPacket;
 use super::root::Root;
 use super::root::Roots;
+use super::root::RootTable;
 use super::root::WeakRoot;
+use super::root::WeakRootCensus;
 use super::root::WeakIdGenerator;
 use super::root::DropListener;
+use super::root_arena::RootArena;
+use super::root_arena::RootArenas;
+use super::handle_block::HandleBlocks;
+use super::handle_block::HandleBlock;
+#[cfg(feature = "std")] use super::handle_block::HANDLE_BLOCK_CAPACITY;
+use super::storage::PersistentQueue;
+#[cfg(feature = "std")] use super::serialization::CborReader;
+#[cfg(feature = "std")] use super::serialization::CborWriter;
+#[cfg(feature = "std")] use super::serialization::GraphReader;
+#[cfg(feature = "std")] use super::serialization::GraphWriter;
+#[cfg(feature = "std")] use super::serialization::ItemReader;
+#[cfg(feature = "std")] use super::serialization::ItemWriter;
+#[cfg(feature = "std")] use super::serialization::MessagePackReader;
+#[cfg(feature = "std")] use super::serialization::MessagePackWriter;
+#[cfg(feature = "std")] use super::serialization::SerializationFormat;
+use super::trap::Args;
+use super::trap::NativeFunction;
 use super::trap::PropertyTrap;
+use super::trap::ProtectedPropertyTrap;
 use super::trap::SlotTrap;
+use super::transaction::Transaction;
+use super::util::LockClass;
+#[cfg(feature = "lock-stats")] use super::util::LockStats;
+#[cfg(feature = "lock-stats")] use super::util::list_lock_stats;
+#[cfg(feature = "lock-stats")] use super::util::reset_lock_stats;
 use super::util::ReentrantLock;
 use super::util::ReentrantToken;
 use super::util::RwLock;
+use super::util::PageIterator;
+use super::util::FreeIndexIterator;
 use super::util::PageMap;
+use super::util::PageMapStats;
 use super::util::PageItemFactory;
+use super::util::RcuCell;
 
 use super::slot::BASE_BLACK;
 use super::slot::BASE_WHITE;
+use super::slot::SlotRecordSnapshot;
 
 
 
@@ -45,12 +138,299 @@ pub struct RegionFactory {}
 
 impl PageItemFactory<Arc<Region>> for RegionFactory {
 
-    fn create_item(&self, id: usize) -> Box<Arc<Region>> {
-        Box::new(Arc::new(Region::new(id as u32)))
+    fn create_item(&self, id: usize) -> Result<Box<Arc<Region>>, Error> {
+        Ok(Box::new(Arc::new(Region::new(id as u32))))
+    }
+
+}
+
+/// Region IDs from the base are reserved for shared regions, which are
+/// unique in all isolates
+const SHARED_REGION_ID_BASE: u32 = 0x8000_0000;
+
+static NEXT_SHARED_REGION_ID: AtomicU32 = AtomicU32::new(SHARED_REGION_ID_BASE);
+
+/// Generations of symbols are unique in all isolates, so symbol cache
+/// entries resolved by an isolate never match another one
+static NEXT_SYMBOL_GENERATION: AtomicU32 = AtomicU32::new(1);
+
+/// Generate a symbol generation, zero is reserved for entries unresolved
+fn generate_symbol_generation() -> u32 {
+    loop {
+        let generation = NEXT_SYMBOL_GENERATION.fetch_add(1, Ordering::SeqCst);
+        if generation != 0 {
+            return generation;
+        }
+    }
+}
+
+/// Default limit of prototypes visited while walking a prototype chain
+const DEFAULT_PROTOTYPE_CHAIN_LIMIT: u32 = 1024;
+
+/// Default limit of operations nested in a context
+const DEFAULT_RECURSION_LIMIT: u32 = 1024;
+
+/// Regions of an isolate, local regions are allocated by the page map, and
+/// shared regions are attached with their reserved IDs
+///
+/// Local regions inherited by a fork are shared copy-on-write, each of them
+/// is tracked by a token cloned into all isolates sharing the region
+///
+/// Local regions paged out are removed from the page map, while their IDs
+/// are kept reserved with values referenced by them until rehydrated
+pub struct RegionTable {
+    local_regions: PageMap<Arc<Region>, RegionFactory>,
+    shared_regions: HashMap<u32, Arc<Region>>,
+    cow_tokens: HashMap<u32, Arc<()>>,
+    cold_regions: HashMap<u32, Vec<Value>>
+}
+
+impl RegionTable {
+
+    pub fn new() -> RegionTable {
+        RegionTable {
+            local_regions: PageMap::new(RegionFactory {}),
+            shared_regions: HashMap::new(),
+            cow_tokens: HashMap::new(),
+            cold_regions: HashMap::new()
+        }
+    }
+
+    pub fn gain_item(&mut self) -> Result<usize, Error> {
+        self.gain_item_by(|regions| regions.gain_item())
+    }
+
+    /// Gain a region by the function, IDs reserved by regions paged out are
+    /// skipped
+    fn gain_item_by<G>(&mut self, gain: G) -> Result<usize, Error> where G: Fn(&mut PageMap<Arc<Region>, RegionFactory>) -> Result<usize, Error> {
+        let mut id = gain(&mut self.local_regions)?;
+        while self.cold_regions.contains_key(&(id as u32)) {
+            let cold_id = id;
+            id = gain(&mut self.local_regions)?;
+            self.local_regions.recycle_item(cold_id)?;
+        }
+        if id >= SHARED_REGION_ID_BASE as usize {
+            self.local_regions.recycle_item(id)?;
+            return Err(Error::new(OutOfSpace, "No more space is available"));
+        }
+        Ok(id)
+    }
+
+    /// Gain a region dedicated to a large object
+    pub fn gain_large_item(&mut self) -> Result<usize, Error> {
+        self.gain_item_by(|regions| {
+            regions.gain_item_with(|id| Ok(Box::new(Arc::new(Region::new_large(id as u32)))))
+        })
+    }
+
+    pub fn recycle_item(&mut self, index: usize) -> Result<(), Error> {
+        if index >= SHARED_REGION_ID_BASE as usize {
+            return Err(Error::new(FatalError, "Shared region could not be recycled"));
+        }
+        self.cow_tokens.remove(&(index as u32));
+        self.local_regions.recycle_item(index)
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.local_regions.get_size()
+    }
+
+    pub fn peek_next_item_index(&self) -> usize {
+        self.local_regions.peek_next_item_index()
+    }
+
+    pub fn shrink_next_item_index(&mut self, from: usize, to: usize) -> usize {
+        self.local_regions.shrink_next_item_index(from, to)
+    }
+
+    /// Release pages of local regions all recycled
+    pub fn shrink_to_fit(&mut self) -> usize {
+        self.local_regions.shrink_to_fit()
+    }
+
+    /// Iterate IDs below the next ID of local regions, which are recycled
+    /// or paged out
+    pub fn iterate_free_indices(&self) -> FreeIndexIterator<'_, Arc<Region>, RegionFactory> {
+        self.local_regions.iterate_free_indices()
+    }
+
+    pub fn get_page_stats(&self) -> PageMapStats {
+        self.local_regions.get_stats()
+    }
+
+    /// Iterate local regions, shared regions are excluded
+    pub fn iterate_items(&self) -> PageIterator<'_, Arc<Region>, RegionFactory> {
+        self.local_regions.iterate_items()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Arc<Region>> {
+        if index >= SHARED_REGION_ID_BASE as usize {
+            self.shared_regions.get(&(index as u32))
+        } else {
+            self.local_regions.get(index)
+        }
+    }
+
+    pub fn list_shared_region_ids(&self) -> Vec<u32> {
+        self.shared_regions.keys().copied().collect()
+    }
+
+    pub fn insert_shared_region(&mut self, region: Arc<Region>) -> Result<(), Error> {
+        let id = region.get_id();
+        if id < SHARED_REGION_ID_BASE {
+            return Err(Error::new(FatalError, "Region is not in the shared range"));
+        }
+        if self.shared_regions.contains_key(&id) {
+            return Err(Error::new(FatalError, "Shared region already attached"));
+        }
+        self.shared_regions.insert(id, region);
+        Ok(())
+    }
+
+    /// Create a table sharing all local regions copy-on-write
+    pub fn fork(&mut self) -> Result<RegionTable, Error> {
+
+        if !self.cold_regions.is_empty() {
+            return Err(Error::new(FatalError, "Regions paged out could not be forked"));
+        }
+
+        let mut regions = Vec::new();
+        for (index, region) in self.local_regions.iterate_items() {
+            regions.push((index, region.clone()));
+        }
+
+        let mut table = RegionTable::new();
+        for (index, region) in regions {
+            let token = self.cow_tokens.entry(index as u32).or_insert_with(|| Arc::new(())).clone();
+            table.local_regions.insert_item(index, Box::new(region))?;
+            table.cow_tokens.insert(index as u32, token);
+        }
+        table.local_regions.overwrite_next_item_index(self.local_regions.peek_next_item_index());
+
+        for (id, region) in self.shared_regions.iter() {
+            table.shared_regions.insert(*id, region.clone());
+        }
+
+        Ok(table)
+
+    }
+
+    /// Check whether the region is still shared with another isolate
+    pub fn is_copy_on_write(&self, index: usize) -> bool {
+        match self.cow_tokens.get(&(index as u32)) {
+            Some(token) => Arc::strong_count(token) > 1,
+            None => false
+        }
+    }
+
+    pub fn is_cold(&self, index: usize) -> bool {
+        self.cold_regions.contains_key(&(index as u32))
+    }
+
+    pub fn list_cold_region_ids(&self) -> Vec<u32> {
+        self.cold_regions.keys().copied().collect()
+    }
+
+    /// List values in other regions referenced by a paged out region
+    pub fn list_cold_region_references(&self, index: usize) -> Option<Vec<Value>> {
+        self.cold_regions.get(&(index as u32)).cloned()
+    }
+
+    /// Remove a paged out region, its ID and values referenced by it are
+    /// reserved until rehydrated
+    pub fn page_out_item(&mut self, index: usize, references: Vec<Value>) -> Result<(), Error> {
+        if index >= SHARED_REGION_ID_BASE as usize {
+            return Err(Error::new(FatalError, "Shared region could not be paged out"));
+        }
+        if self.is_copy_on_write(index) {
+            return Err(Error::new(FatalError, "Region shared copy-on-write could not be paged out"));
+        }
+        self.local_regions.recycle_item(index)?;
+        self.cow_tokens.remove(&(index as u32));
+        self.cold_regions.insert(index as u32, references);
+        Ok(())
+    }
+
+    /// Insert a rehydrated region back with its reserved ID
+    pub fn page_in_item(&mut self, index: usize, region: Arc<Region>) -> Result<(), Error> {
+        if self.cold_regions.remove(&(index as u32)).is_none() {
+            return Err(Error::new(FatalError, "Region not paged out"));
+        }
+        self.local_regions.insert_item(index, Box::new(region))
+    }
+
+    /// Create an immutable index of local and shared regions, paged out
+    /// regions are excluded
+    pub fn create_index(&self) -> RegionIndex {
+
+        let mut local_regions = Vec::new();
+        local_regions.resize(self.local_regions.peek_next_item_index(), None);
+        for (index, region) in self.local_regions.iterate_items() {
+            if index >= local_regions.len() {
+                local_regions.resize(index + 1, None);
+            }
+            local_regions[index] = Some(region.clone());
+        }
+
+        RegionIndex {
+            local_regions: local_regions,
+            shared_regions: self.shared_regions.clone()
+        }
+
+    }
+
+    /// Copy the region if it is still shared with another isolate
+    pub fn ensure_writable(&mut self, index: usize) -> Result<(), Error> {
+
+        let shared = self.is_copy_on_write(index);
+        if shared {
+            let region = match self.local_regions.get(index) {
+                Some(region) => Arc::new(region.duplicate()),
+                None => {
+                    return Err(Error::new(FatalError, "Region not found"));
+                }
+            };
+            self.local_regions.replace_item(index, Box::new(region))?;
+        }
+
+        self.cow_tokens.remove(&(index as u32));
+
+        Ok(())
+
+    }
+
+}
+
+
+/// Immutable snapshot of a region table, republished after every change of
+/// the table, so regions could be resolved without locks
+pub struct RegionIndex {
+    local_regions: Vec<Option<Arc<Region>>>,
+    shared_regions: HashMap<u32, Arc<Region>>
+}
+
+impl RegionIndex {
+
+    pub fn get(&self, index: usize) -> Option<&Arc<Region>> {
+        if index >= SHARED_REGION_ID_BASE as usize {
+            self.shared_regions.get(&(index as u32))
+        } else {
+            match self.local_regions.get(index) {
+                Some(region) => region.as_ref(),
+                None => None
+            }
+        }
     }
 
 }
 
+/// Space and protection state of a local region, protected regions are
+/// never recycled by the collector
+#[derive(Copy, Clone)]
+struct RegionInfo {
+    space: Space,
+    protected: bool
+}
 
 /// Isolated storage for slotted values
 pub struct Isolate {
@@ -58,18 +438,32 @@ pub struct Isolate {
     barrier: RefCell<Option<Box<dyn Barrier>>>,
 
     region_rw_lock: RwLock,
-    regions: RefCell<PageMap<Arc<Region>, RegionFactory>>,
-    protected_region_ids: RefCell<HashSet<u32>>,
+    regions: RefCell<RegionTable>,
+    region_index: RcuCell<RegionIndex>,
+    region_generation: AtomicU64,
+    region_infos: RefCell<HashMap<u32, RegionInfo>>,
+    region_tags: RefCell<HashMap<u32, Arc<dyn Any>>>,
+    space_policies: RefCell<HashMap<Space, SpacePolicy>>,
+    region_watermarks: RegionWatermarks,
+    quotas: Quotas,
+    cold_region_storage: RefCell<Option<Arc<dyn ColdRegionStorage>>>,
+    options: IsolateOptions,
 
     base_color: Cell<u8>,
+    fork_colors: ForkColors,
     next_internal_slot_id: AtomicU64,
 
     slot_layout_lock: Arc<ReentrantLock>,
 
     symbol_rw_lock: RwLock,
     symbol_id_generator: Arc<SymbolIdGenerator>,
+    symbol_registry: Option<Arc<SymbolRegistry>>,
+    custom_errors: Arc<CustomErrorRegistry>,
     symbol_scopes: RefCell<HashMap<String, Arc<SymbolScope>>>,
     symbol_lut: RefCell<HashMap<Symbol, Arc<SymbolScope>>>,
+    text_symbol_cache: TextSymbolCache,
+    symbol_generation: AtomicU32,
+    value_symbol_roots: RefCell<HashMap<Symbol, Arc<Root>>>,
 
     boolean_prototype: Value,
     integer_prototype: Value,
@@ -78,14 +472,34 @@ pub struct Isolate {
     symbol_prototype: Value,
     list_prototype: Value,
     tuple_prototype: Value,
+    bytes_prototype: Value,
+    big_integer_prototype: Value,
+    decimal_prototype: Value,
+    timestamp_prototype: Value,
     object_prototype: Value,
 
     prototype_symbol: Symbol,
+    well_known_symbols: WellKnownSymbols,
 
-    roots_rw_lock: RwLock,
-    roots: RefCell<HashMap<Value, Arc<Roots>>>,
+    roots: RootTable<Arc<Roots>>,
     weak_id_generator: WeakIdGenerator,
-    weak_roots: RefCell<HashMap<Value, RefCell<HashSet<Arc<WeakRoot>>>>>,
+    weak_roots: RootTable<RefCell<HashSet<Arc<WeakRoot>>>>,
+    fired_weak_roots: AtomicU64,
+    root_arenas: RootArenas,
+    handle_blocks: HandleBlocks,
+    retired_field_shortcuts_rw_lock: RwLock,
+    retired_field_shortcuts: RefCell<Vec<Arc<FieldShortcuts>>>,
+    persistent_rw_lock: RwLock,
+    persistent_roots: RefCell<HashMap<u64, Arc<Root>>>,
+    persistent_queue: Arc<PersistentQueue>,
+
+    object_groups_rw_lock: RwLock,
+    next_object_group_id: AtomicU64,
+    object_groups: RefCell<HashMap<u64, Vec<Value>>>,
+    object_group_ids: RefCell<HashMap<Value, u64>>,
+
+    prototype_chain_limit: AtomicU32,
+    recursion_limit: AtomicU32,
 
     next_protected_id: AtomicU64,
     protection_rw_lock: RwLock,
@@ -95,7 +509,23 @@ pub struct Isolate {
 
     outlets_rw_lock: RwLock,
     next_outlet_id: AtomicU64,
-    outlets: RefCell<HashMap<u64, Arc<dyn Any>>>
+    outlets: RefCell<HashMap<u64, Arc<dyn Any>>>,
+
+    conversions_rw_lock: RwLock,
+    text_formatters: RefCell<HashMap<PrimitiveType, Arc<dyn TextFormatter>>>,
+    value_coercer: RefCell<Option<Arc<dyn ValueCoercer>>>,
+
+    interned_texts_rw_lock: RwLock,
+    interned_texts: RefCell<HashMap<String, Arc<Root>>>,
+
+    metrics_counters: MetricsCounters,
+    allocation_sampler: AllocationSampler,
+    #[cfg(feature = "provenance")] provenances: Provenances,
+    gc_listeners: GcListeners,
+    gc_stress_mode: Cell<GcStressMode>,
+
+    #[cfg(feature = "fault-injection")] fault_injector_rw_lock: RwLock,
+    #[cfg(feature = "fault-injection")] fault_injector: RefCell<Option<Arc<dyn FaultInjector>>>
 
 }
 
@@ -104,24 +534,54 @@ impl Isolate {
 
     /// Create an isolate
     pub fn create() -> Result<Isolate, Error> {
+        Isolate::create_with_options(IsolateOptions::new())
+    }
+
+    /// Create an isolate resolving text symbols by a registry shared with
+    /// other isolates
+    pub fn create_with_symbol_registry(symbol_registry: Arc<SymbolRegistry>) -> Result<Isolate, Error> {
+        Isolate::create_with_options(IsolateOptions::new().symbol_registry(symbol_registry))
+    }
+
+    /// Create an isolate with options
+    pub fn create_with_options(options: IsolateOptions) -> Result<Isolate, Error> {
+
+        let symbol_registry = options.get_symbol_registry().cloned();
 
         let mut isolate = Isolate {
 
             barrier: RefCell::new(None),
 
-            region_rw_lock: RwLock::new(),
-            regions: RefCell::new(PageMap::new(RegionFactory {})),
-            protected_region_ids: RefCell::new(HashSet::new()),
+            region_rw_lock: RwLock::with_class(LockClass::RegionTable),
+            regions: RefCell::new(RegionTable::new()),
+            region_index: RcuCell::new(RegionTable::new().create_index()),
+            region_generation: AtomicU64::new(0),
+            region_infos: RefCell::new(HashMap::new()),
+            region_tags: RefCell::new(HashMap::new()),
+            space_policies: RefCell::new(Space::list_all().iter().map(|space| (*space, SpacePolicy::for_space(*space))).collect()),
+            region_watermarks: RegionWatermarks::new(),
+            quotas: Quotas::new(),
+            cold_region_storage: RefCell::new(None),
+            options: options,
 
             base_color: Cell::new(BASE_WHITE),
+            fork_colors: ForkColors::new(),
             next_internal_slot_id: AtomicU64::new(0),
 
-            slot_layout_lock: Arc::new(ReentrantLock::new()),
+            slot_layout_lock: Arc::new(ReentrantLock::with_class(LockClass::SlotLayout)),
 
-            symbol_rw_lock: RwLock::new(),
-            symbol_id_generator: Arc::new(SymbolIdGenerator::new()),
+            symbol_rw_lock: RwLock::with_class(LockClass::Symbol),
+            symbol_id_generator: match &symbol_registry {
+                Some(symbol_registry) => symbol_registry.get_symbol_id_generator(),
+                None => Arc::new(SymbolIdGenerator::new())
+            },
+            symbol_registry: symbol_registry,
+            custom_errors: Arc::new(CustomErrorRegistry::new()),
             symbol_scopes: RefCell::new(HashMap::new()),
             symbol_lut: RefCell::new(HashMap::new()),
+            text_symbol_cache: TextSymbolCache::new(),
+            symbol_generation: AtomicU32::new(generate_symbol_generation()),
+            value_symbol_roots: RefCell::new(HashMap::new()),
 
             boolean_prototype: Value::make_undefined(),
             integer_prototype: Value::make_undefined(),
@@ -130,14 +590,34 @@ impl Isolate {
             text_prototype: Value::make_undefined(),
             list_prototype: Value::make_undefined(),
             tuple_prototype: Value::make_undefined(),
+            bytes_prototype: Value::make_undefined(),
+            big_integer_prototype: Value::make_undefined(),
+            decimal_prototype: Value::make_undefined(),
+            timestamp_prototype: Value::make_undefined(),
             object_prototype: Value::make_undefined(),
 
             prototype_symbol: Symbol::new(0),
+            well_known_symbols: WellKnownSymbols::new(Symbol::new(0), Symbol::new(0), Symbol::new(0), Symbol::new(0), Symbol::new(0), Symbol::new(0), Symbol::new(0)),
 
-            roots_rw_lock: RwLock::new(),
-            roots: RefCell::new(HashMap::new()),
+            roots: RootTable::new(),
             weak_id_generator: WeakIdGenerator::new(),
-            weak_roots: RefCell::new(HashMap::new()),
+            weak_roots: RootTable::new(),
+            fired_weak_roots: AtomicU64::new(0),
+            root_arenas: RootArenas::new(),
+            handle_blocks: HandleBlocks::new(),
+            retired_field_shortcuts_rw_lock: RwLock::new(),
+            retired_field_shortcuts: RefCell::new(Vec::new()),
+            persistent_rw_lock: RwLock::with_class(LockClass::Root),
+            persistent_roots: RefCell::new(HashMap::new()),
+            persistent_queue: Arc::new(PersistentQueue::new()),
+
+            object_groups_rw_lock: RwLock::new(),
+            next_object_group_id: AtomicU64::new(0),
+            object_groups: RefCell::new(HashMap::new()),
+            object_group_ids: RefCell::new(HashMap::new()),
+
+            prototype_chain_limit: AtomicU32::new(DEFAULT_PROTOTYPE_CHAIN_LIMIT),
+            recursion_limit: AtomicU32::new(DEFAULT_RECURSION_LIMIT),
 
             next_protected_id: AtomicU64::new(0),
             protection_rw_lock: RwLock::new(),
@@ -147,11 +627,26 @@ impl Isolate {
 
             outlets_rw_lock: RwLock::new(),
             next_outlet_id: AtomicU64::new(0),
-            outlets: RefCell::new(HashMap::new())
+            outlets: RefCell::new(HashMap::new()),
+
+            conversions_rw_lock: RwLock::new(),
+            text_formatters: RefCell::new(HashMap::new()),
+            value_coercer: RefCell::new(None),
+
+            interned_texts_rw_lock: RwLock::new(),
+            interned_texts: RefCell::new(HashMap::new()),
+
+            metrics_counters: MetricsCounters::new(),
+            allocation_sampler: AllocationSampler::new(),
+            #[cfg(feature = "provenance")] provenances: Provenances::new(),
+            gc_listeners: GcListeners::new(),
+            gc_stress_mode: Cell::new(GcStressMode::Disabled),
+            #[cfg(feature = "fault-injection")] fault_injector_rw_lock: RwLock::new(),
+            #[cfg(feature = "fault-injection")] fault_injector: RefCell::new(None)
 
         };
 
-        let region_id = isolate.create_region()?;
+        let region_id = isolate.create_region_in_space(Space::Immortal)?;
 
         let layout_token = ReentrantToken::new(isolate.slot_layout_lock.clone());
 
@@ -163,8 +658,23 @@ impl Isolate {
         isolate.text_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
         isolate.list_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
         isolate.tuple_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
+        isolate.bytes_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
+        isolate.big_integer_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
+        isolate.decimal_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
+        isolate.timestamp_prototype = isolate.gain_slot(region_id, Object, isolate.object_prototype, &layout_token)?;
 
         isolate.prototype_symbol = isolate.get_text_symbol("isolate.prototype", "prototype");
+        isolate.pin_symbol(isolate.prototype_symbol)?;
+
+        isolate.set_symbol_scope_collectable("isolate.well_known", false);
+        isolate.well_known_symbols = WellKnownSymbols::new(
+            isolate.prototype_symbol,
+            isolate.get_text_symbol("isolate.well_known", "iterator"),
+            isolate.get_text_symbol("isolate.well_known", "to_text"),
+            isolate.get_text_symbol("isolate.well_known", "to_primitive"),
+            isolate.get_text_symbol("isolate.well_known", "call"),
+            isolate.get_text_symbol("isolate.well_known", "equals"),
+            isolate.get_text_symbol("isolate.well_known", "hash"));
 
         isolate.unprotect_region(region_id)?;
 
@@ -176,9 +686,9 @@ impl Isolate {
 /// Isolate barrier and layout locks
 impl Isolate {
 
-    pub fn overwrite_barrier(&self, barrier: Box<dyn Barrier>) -> Result<(), Error> {
-
-        let layout_token = self.create_slot_layout_token();
+    /// Set the barrier of the isolate, the slot layout is locked by the
+    /// token, so that a collector holding the layout lock could set it
+    pub fn overwrite_barrier(&self, barrier: Box<dyn Barrier>, layout_token: &ReentrantToken) -> Result<(), Error> {
 
         let _layout_guard = layout_token.lock_write();
 
@@ -192,9 +702,9 @@ impl Isolate {
 
     }
 
-    pub fn clear_barrier(&self) -> Result<Box<dyn Barrier>, Error> {
-
-        let layout_token = self.create_slot_layout_token();
+    /// Remove the barrier of the isolate with the slot layout locked by the
+    /// token
+    pub fn clear_barrier(&self, layout_token: &ReentrantToken) -> Result<Box<dyn Barrier>, Error> {
 
         let _layout_guard = layout_token.lock_write();
 
@@ -219,7 +729,7 @@ impl Isolate {
     pub fn could_region_gain_slot_quickly(&self, region_id: u32) -> bool {
 
         let _guard = self.region_rw_lock.lock_read();
-        self.regions.borrow()[region_id as usize].could_gain_slot_quickly()
+        self.regions.borrow().get(region_id as usize).unwrap().could_gain_slot_quickly()
 
     }
 
@@ -227,6 +737,15 @@ impl Isolate {
         self.next_internal_slot_id.fetch_add(1, Ordering::SeqCst)
     }
 
+    pub fn get_options(&self) -> &IsolateOptions {
+        &self.options
+    }
+
+    /// Get the affinity of regions to allocate values of a primitive type
+    pub fn get_region_affinity(&self, primitive_type: PrimitiveType) -> RegionAffinity {
+        self.options.get_region_affinity(primitive_type)
+    }
+
 }
 
 /// Isolate predefined symbols
@@ -237,6 +756,11 @@ impl Isolate {
         self.prototype_symbol
     }
 
+    /// Get symbols predefined in the reserved scope `isolate.well_known`
+    pub fn get_well_known_symbols(&self) -> &WellKnownSymbols {
+        &self.well_known_symbols
+    }
+
 }
 
 /// Isolate predefined prototypes
@@ -282,36 +806,74 @@ impl Isolate {
         self.tuple_prototype
     }
 
+    /// Get the prototype of bytes
+    pub fn get_bytes_prototype(&self) -> Value {
+        self.bytes_prototype
+    }
+
+    /// Get the prototype of big integer
+    pub fn get_big_integer_prototype(&self) -> Value {
+        self.big_integer_prototype
+    }
+
+    /// Get the prototype of decimal
+    pub fn get_decimal_prototype(&self) -> Value {
+        self.decimal_prototype
+    }
+
+    /// Get the prototype of timestamp
+    pub fn get_timestamp_prototype(&self) -> Value {
+        self.timestamp_prototype
+    }
+
 }
 
 /// Isolate value information extraction
 impl Isolate {
 
     /// Extract text from a value 
-    pub fn extract_text(&self, value: Value, context: &Box<dyn Context>) -> String {
+    ///
+    /// Values with a `to_text` well-known property in their prototype chain
+    /// are converted by it, which should be a text or a function returning
+    /// text. Otherwise the text formatter registered for the primitive type
+    /// is used before the default representations
+    pub fn extract_text(&self, value: Value, context: &Box<dyn Context>) -> Result<String, Error> {
+
+        match value.get_primitive_type() {
+            Undefined | Null | Text => {},
+            _ => {
+                if let Some(text) = self.convert_text_by_protocol(value, context)? {
+                    return Ok(text);
+                }
+            }
+        }
+
+        if let Some(text_formatter) = self.get_text_formatter(value.get_primitive_type()) {
+            return text_formatter.format_text(value, context);
+        }
 
         match value.get_primitive_type() {
             Undefined => { 
-                return "<undefined>".to_owned(); 
+                return Ok("<undefined>".to_owned()); 
             },
             Null => { 
-                return "<null>".to_owned(); 
+                return Ok("<null>".to_owned()); 
             },
             Boolean => { 
                 return match value.as_boolean() {
-                    true => "<yes>".to_owned(),
-                    _ => "<no>".to_owned()
+                    true => Ok("<yes>".to_owned()),
+                    _ => Ok("<no>".to_owned())
                 }; 
             },
             Integer => { 
                 if value.is_cardinal() {
-                    return value.extract_cardinal(0).to_string()
+                    return Ok(value.extract_cardinal(0).to_string())
                 } else {
-                    return value.extract_integer(0).to_string()
+                    return Ok(value.extract_integer(0).to_string())
                 }
             },
             Float => { 
-                return value.extract_float(0.0).to_string(); 
+                return Ok(value.extract_float(0.0).to_string()); 
             },
             Symbol => { 
                 match self.resolve_symbol_info(value.extract_symbol(Symbol::new(0))) {
@@ -326,42 +888,50 @@ impl Isolate {
                             result.push_str("<value>");
                         }
                         result.push_str(">");
-                        return result;
+                        return Ok(result);
                     },
                     Err(_) => {
-                        return "<symbol>".to_owned();
+                        return Ok("<symbol>".to_owned());
                     }
                 }
             },
             Text => {
-                match self.get_internal_slot(value, 0, context) {
-                    Ok(Some(internal_slot)) => {
+                match self.get_internal_slot(value, 0, context)? {
+                    Some(internal_slot) => {
                         match internal_slot.as_any().downcast_ref::<Text>() {
                             Some(text) => {
-                                return text.to_string();
+                                return Ok(text.to_string());
                             },
                             None => {
-                                return "<text>".to_owned();
+                                return Err(Error::new(TypeNotMatch, "Internal slot is not text").with_value(value));
                             }
                         }
                     },
-                    Ok(None) => {
-                        return "<text>".to_owned();
-                    },
-                    Err(_) => {
-                        return "<text>".to_owned();
+                    None => {
+                        return Err(Error::new(InternalSlotNotFound, "Internal slot of text not found").with_value(value));
                     }
                 }
             },
-            List => {
-                return "<list>".to_owned();
-            },
-            Tuple => {
-                return "<tuple>".to_owned();
+            List | Tuple | Object => {
+                return Ok(self.describe_value(value, context, &DescribeOptions::new()));
             },
-            Object => {
-                return "<object>".to_owned();
-            } 
+            BigInteger => {
+                match self.get_internal_slot(value, 0, context)? {
+                    Some(internal_slot) => {
+                        match internal_slot.as_any().downcast_ref::<BigInt>() {
+                            Some(big_int) => {
+                                return Ok(big_int.to_string());
+                            },
+                            None => {
+                                return Err(Error::new(TypeNotMatch, "Internal slot is not big integer").with_value(value));
+                            }
+                        }
+                    },
+                    None => {
+                        return Err(Error::new(InternalSlotNotFound, "Internal slot of big integer not found").with_value(value));
+                    }
+                }
+            }
         }
 
     }
@@ -369,2468 +939,8161 @@ impl Isolate {
     pub fn extract_list(&self, value: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
         
         match value.get_primitive_type() {
-            Undefined => { return Err(Error::new(FatalError, "Undefined could not converted to list")); },
-            Null => { return Err(Error::new(FatalError, "Null could not converted to list")); },
-            Boolean => { return Err(Error::new(FatalError, "Boolean could not converted to list")); },
-            Integer => { return Err(Error::new(FatalError, "Integer could not converted to list")); },
-            Float => { return Err(Error::new(FatalError, "Float could not converted to list")); },
-            Symbol => { return Err(Error::new(FatalError, "Symbol could not converted to list")); },
-            Text => { return Err(Error::new(FatalError, "Text could not converted to list")); },
+            Undefined => { return Err(Error::new(TypeNotMatch, "Undefined could not converted to list")); },
+            Null => { return Err(Error::new(TypeNotMatch, "Null could not converted to list")); },
+            Boolean => { return Err(Error::new(TypeNotMatch, "Boolean could not converted to list")); },
+            Integer => { return Err(Error::new(TypeNotMatch, "Integer could not converted to list")); },
+            Float => { return Err(Error::new(TypeNotMatch, "Float could not converted to list")); },
+            Symbol => { return Err(Error::new(TypeNotMatch, "Symbol could not converted to list")); },
+            Text => { return Err(Error::new(TypeNotMatch, "Text could not converted to list")); },
+            BigInteger => { return Err(Error::new(TypeNotMatch, "Big integer could not converted to list")); },
             List => {
-                match self.get_internal_slot(value, 0, context) {
-                    Ok(Some(internal_slot)) => {
+                match self.get_internal_slot(value, 0, context)? {
+                    Some(internal_slot) => {
                         match internal_slot.as_any().downcast_ref::<List>() {
                             Some(list) => { return Ok(list.get_value_list()); },
-                            None => { return Ok(Vec::new()); }
+                            None => { return Err(Error::new(TypeNotMatch, "Internal slot is not list").with_value(value)); }
                         }
                     },
-                    Ok(None) => { return Ok(Vec::new()); },
-                    Err(_) => { return Ok(Vec::new()); }
+                    None => { return Err(Error::new(InternalSlotNotFound, "Internal slot of list not found").with_value(value)); }
                 }
             },
-            Tuple => { return Err(Error::new(FatalError, "Tuple could not converted to list")); },
-            Object => { return Err(Error::new(FatalError, "Object could not converted to list")); }
+            Tuple => { return Err(Error::new(TypeNotMatch, "Tuple could not converted to list")); },
+            Object => { return Err(Error::new(TypeNotMatch, "Object could not converted to list")); }
         }
 
     }
 
 }
 
-/// Isolate regions management
+/// Isolate binary data management
 impl Isolate {
 
-    pub fn get_region_number(&self) -> u32 {
+    /// Create a bytes value in specified region
+    pub fn create_bytes(&self, region_id: u32, bytes: &[u8], context: &Box<dyn Context>) -> Result<Value, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        let value = self.gain_slot(region_id, Object, self.bytes_prototype, context.get_slot_layout_token())?;
 
-        self.regions.borrow().get_size() as u32
+        let bytes: Arc<dyn InternalSlot> = Arc::new(Bytes::new(bytes));
+
+        self.set_internal_slot(value, 0, bytes, context)?;
+
+        Ok(value)
 
     }
 
-    pub fn peek_next_region_id(&self) -> u32 {
+    /// Extract bytes data from a value without copying
+    pub fn extract_bytes(&self, value: Value, context: &Box<dyn Context>) -> Result<Arc<Vec<u8>>, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        if !value.is_object() {
+            return Err(Error::new(TypeNotMatch, "Value is not bytes"));
+        }
 
-        self.regions.borrow().peek_next_item_index() as u32
+        match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Bytes>() {
+                    Some(bytes) => Ok(bytes.share_data()),
+                    None => Err(Error::new(TypeNotMatch, "Value is not bytes"))
+                }
+            },
+            None => Err(Error::new(TypeNotMatch, "Value is not bytes"))
+        }
 
     }
 
-    pub fn shrink_next_region_id(&self, from: u32, to: u32) -> u32 {
+}
 
-        let _guard = self.region_rw_lock.lock_write();
+/// Isolate big integer management
+impl Isolate {
 
-        self.regions.borrow_mut().shrink_next_item_index(from as usize, to as usize) as u32
+    /// Create a big integer value in specified region
+    pub fn create_big_integer(&self, region_id: u32, big_int: BigInt, context: &Box<dyn Context>) -> Result<Value, Error> {
 
-    }
+        let value = self.gain_slot(region_id, BigInteger, self.big_integer_prototype, context.get_slot_layout_token())?;
 
-    /// Create a new empty region
-    pub fn create_region(&self) -> Result<u32, Error> {
+        let big_int: Arc<dyn InternalSlot> = Arc::new(big_int);
 
-        let _guard = self.region_rw_lock.lock_write();
+        self.set_internal_slot(value, 0, big_int, context)?;
 
-        let id = self.regions.borrow_mut().gain_item()? as u32;
+        Ok(value)
 
-        self.protected_region_ids.borrow_mut().insert(id);
+    }
 
-        Ok(id)
+    pub fn extract_big_integer(&self, value: Value, context: &Box<dyn Context>) -> Result<BigInt, Error> {
+
+        if !value.is_big_integer() {
+            return Err(Error::new(TypeNotMatch, "Value is not big integer"));
+        }
+
+        match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<BigInt>() {
+                    Some(big_int) => Ok(big_int.clone()),
+                    None => Err(Error::new(TypeNotMatch, "Value is not big integer"))
+                }
+            },
+            None => Err(Error::new(TypeNotMatch, "Value is not big integer"))
+        }
 
     }
 
-    pub fn list_region_ids(&self) -> Result<Vec<u32>, Error> {
+}
 
-        let _guard = self.region_rw_lock.lock_read();
+/// Isolate decimal management
+impl Isolate {
 
-        let mut ids = Vec::new();
+    /// Create a decimal value in specified region
+    pub fn create_decimal(&self, region_id: u32, decimal: Decimal, context: &Box<dyn Context>) -> Result<Value, Error> {
 
-        for (index, _page) in self.regions.borrow().iterate_items() {
-            ids.push(index as u32);
+        let value = self.gain_slot(region_id, Object, self.decimal_prototype, context.get_slot_layout_token())?;
+
+        let decimal: Arc<dyn InternalSlot> = Arc::new(decimal);
+
+        self.set_internal_slot(value, 0, decimal, context)?;
+
+        Ok(value)
+
+    }
+
+    pub fn extract_decimal(&self, value: Value, context: &Box<dyn Context>) -> Result<Decimal, Error> {
+
+        if !value.is_object() {
+            return Err(Error::new(TypeNotMatch, "Value is not decimal"));
         }
 
-        Ok(ids)
+        match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Decimal>() {
+                    Some(decimal) => Ok(decimal.clone()),
+                    None => Err(Error::new(TypeNotMatch, "Value is not decimal"))
+                }
+            },
+            None => Err(Error::new(TypeNotMatch, "Value is not decimal"))
+        }
 
     }
 
-    pub fn sweep_region(&self, region_id: u32, context: &Box<dyn Context>) -> Result<(), Error> {
+}
 
-        let _guard = self.region_rw_lock.lock_read();
+/// Isolate timestamp management
+impl Isolate {
 
-        let region = match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.clone(),
-            None => {
-                return Err(Error::new(FatalError, "Region not found"));
-            }
-        };
+    /// Create a timestamp value in specified region
+    pub fn create_timestamp(&self, region_id: u32, timestamp: Timestamp, context: &Box<dyn Context>) -> Result<Value, Error> {
 
-        region.sweep_values(self.base_color.get(), context)?;
+        let value = self.gain_slot(region_id, Object, self.timestamp_prototype, context.get_slot_layout_token())?;
 
-        Ok(())
+        let timestamp: Arc<dyn InternalSlot> = Arc::new(timestamp);
+
+        self.set_internal_slot(value, 0, timestamp, context)?;
+
+        Ok(value)
 
     }
 
-    pub fn is_region_empty(&self, region_id: u32) -> Result<bool, Error> {
+    pub fn extract_timestamp(&self, value: Value, context: &Box<dyn Context>) -> Result<Timestamp, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        if !value.is_object() {
+            return Err(Error::new(TypeNotMatch, "Value is not timestamp"));
+        }
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(region) => Ok(region.is_empty()),
-            None => Err(Error::new(FatalError, "Region not found"))
+        match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Timestamp>() {
+                    Some(timestamp) => Ok(*timestamp),
+                    None => Err(Error::new(TypeNotMatch, "Value is not timestamp"))
+                }
+            },
+            None => Err(Error::new(TypeNotMatch, "Value is not timestamp"))
         }
 
     }
 
-    pub fn is_region_full(&self, region_id: u32) -> Result<bool, Error> {
+}
 
-        let _guard = self.region_rw_lock.lock_read();
+/// Isolate text management
+impl Isolate {
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(region) => Ok(region.is_full()),
-            None => Err(Error::new(FatalError, "Region not found"))
-        }
+    /// Concatenate two texts, the underlying strings are shared without copying
+    pub fn concat_text(&self, text: Value, text_2: Value, context: &Box<dyn Context>) -> Result<Pinned, Error> {
 
-    }
+        let text = self.extract_text_slot(text, context)?;
+        let text_2 = self.extract_text_slot(text_2, context)?;
 
-    pub fn need_region_refragment(&self, region_id: u32) -> Result<f32, Error> {
+        let value = context.gain_slot(PrimitiveType::Text, self.text_prototype)?;
 
-        let _guard = self.region_rw_lock.lock_read();
+        let text: Arc<dyn InternalSlot> = Arc::new(Text::concat(&text, &text_2));
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(region) => Ok(region.need_refragment()),
-            None => Err(Error::new(FatalError, "Region not found"))
-        }
+        self.set_internal_slot(value, 0, text, context)?;
+
+        Pinned::new(context, value)
 
     }
 
-    pub fn refragment_region(&self, region_id: u32, target_region_id: u32, context: &Box<dyn Context>) -> Result<bool, Error> {
+    /// Create a text value in specified region wrapping a host owned string,
+    /// the string is released after the slot is recycled
+    pub fn create_external_text(&self, region_id: u32, string: Arc<str>, context: &Box<dyn Context>) -> Result<Value, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        let value = self.gain_slot(region_id, PrimitiveType::Text, self.text_prototype, context.get_slot_layout_token())?;
 
-        let region = match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.clone(),
-            None => {
-                return Err(Error::new(FatalError, "Region not found"));
-            }
-        };
-        let target_region = match self.regions.borrow().get(target_region_id as usize) {
-            Some(region) => region.clone(),
-            None => {
-                return Err(Error::new(FatalError, "Region not found"));
-            }
-        };
+        let text: Arc<dyn InternalSlot> = Arc::new(Text::from_external(string));
 
-        for value in region.list_alive_values()? {
-            if target_region.is_full() {
-                return Ok(false);
-            }
-            self.move_slot(value, target_region_id, context)?;
-        }
-        region.recalculate_next_empty_slot_index()?;
+        self.set_internal_slot(value, 0, text, context)?;
 
-        Ok(true)
+        Ok(value)
 
     }
 
-    pub fn is_region_protected(&self, region_id: u32) -> Result<bool, Error> {
+    /// Get the interned text value of a string, the text will be created in
+    /// specified region if not interned yet
+    ///
+    /// Interned texts are held by roots until they are released, so identical
+    /// strings share one slot
+    pub fn intern_text(&self, text: &str, region_id: u32, context: &Box<dyn Context>) -> Result<Value, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        let _guard = self.interned_texts_rw_lock.lock_write();
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(_) => Ok(self.protected_region_ids.borrow().get(&region_id).is_some()),
-            None => Err(Error::new(FatalError, "Region not found"))
+        if let Some(root) = self.interned_texts.borrow().get(text) {
+            return Ok(root.get_value());
         }
 
+        let value = self.gain_slot(region_id, PrimitiveType::Text, self.text_prototype, context.get_slot_layout_token())?;
+
+        let internal_slot: Arc<dyn InternalSlot> = Arc::new(Text::new(text));
+
+        self.set_internal_slot(value, 0, internal_slot, context)?;
+
+        let root = self.add_root(value, context.get_slot_layout_token())?;
+
+        self.interned_texts.borrow_mut().insert(text.to_owned(), root);
+
+        Ok(value)
+
     }
 
-    pub fn protect_region(&self, region_id: u32) -> Result<(), Error> {
+    pub fn is_text_interned(&self, text: &str) -> bool {
 
-        let _guard = self.region_rw_lock.lock_write();
+        let _guard = self.interned_texts_rw_lock.lock_read();
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(_) => {
-                if self.protected_region_ids.borrow().get(&region_id).is_some() {
-                    return Err(Error::new(FatalError, "Region already protected"));
-                }
-                self.protected_region_ids.borrow_mut().insert(region_id);
-                Ok(())
-            },
-            None => Err(Error::new(FatalError, "Region not found"))
-        }
+        self.interned_texts.borrow().contains_key(text)
 
     }
 
-    pub fn unprotect_region(&self, region_id: u32) -> Result<(), Error> {
+    /// Release an interned text, the slot will be recycled by the collector
+    /// if it is not referenced elsewhere
+    pub fn release_interned_text(&self, text: &str) -> Result<bool, Error> {
 
-        let _guard = self.region_rw_lock.lock_write();
+        let root = {
+            let _guard = self.interned_texts_rw_lock.lock_write();
+            self.interned_texts.borrow_mut().remove(text)
+        };
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(_) => {
-                if self.protected_region_ids.borrow().get(&region_id).is_none() {
-                    return Err(Error::new(FatalError, "Region not protected"));
-                }
-                self.protected_region_ids.borrow_mut().remove(&region_id);
-                Ok(())
+        match root {
+            Some(root) => {
+                self.remove_root(&root)?;
+                Ok(true)
             },
-            None => Err(Error::new(FatalError, "Region not found"))
+            None => Ok(false)
         }
- 
+
     }
 
-    pub fn recycle_region(&self, region_id: u32) -> Result<(), Error> {
+    /// Extract text from a value, the rope is shared without copying
+    pub fn extract_text_slot(&self, value: Value, context: &Box<dyn Context>) -> Result<Text, Error> {
 
-        let _guard = self.region_rw_lock.lock_write();
+        if !value.is_text() {
+            return Err(Error::new(TypeNotMatch, "Value is not text"));
+        }
 
-        let region = match self.regions.borrow().get(region_id as usize) {
-            Some(region) => {
-                if self.protected_region_ids.borrow().get(&region_id).is_some() {
-                    return Err(Error::new(FatalError, "Region protected"));
+        match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Text>() {
+                    Some(text) => Ok(text.clone()),
+                    None => Err(Error::new(TypeNotMatch, "Value is not text"))
                 }
-                region.clone()
             },
-            None => {
-                return Err(Error::new(FatalError, "Region not found"));
-            }
-        };
-
-        if !region.is_empty() {
-            return Err(Error::new(FatalError, "Region not empty"));
+            None => Err(Error::new(TypeNotMatch, "Value is not text"))
         }
 
-        self.regions.borrow_mut().recycle_item(region_id as usize)
-
     }
 
 }
 
-/// Isolate garbage collection 
+/// Isolate tuple management
 impl Isolate {
 
-    /// Resolve redirections generated from refragment of slots
-    pub fn resolve_real_value(&self, value: Value, layout_token: &ReentrantToken) -> Result<Value, Error> {
+    /// Get value of a named field in a tuple, undefined will be returned if
+    /// the field is not defined
+    pub fn get_tuple_field(&self, value: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<Pinned, Error> {
 
-        if !value.is_slotted() {
-            return Ok(value);
+        if !value.is_tuple() {
+            return Err(Error::new(TypeNotMatch, "Value is not tuple"));
         }
 
-        let _guard = layout_token.lock_read();
+        let field = match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Tuple>() {
+                    Some(tuple) => tuple.get_field(symbol),
+                    None => { return Err(Error::new(TypeNotMatch, "Value is not tuple")); }
+                }
+            },
+            None => { return Err(Error::new(TypeNotMatch, "Value is not tuple")); }
+        };
 
-        let mut slot = value;
-        loop {
-            match slot.get_region_id() {
-                Ok(region_id) => {
-                    let region = {
-                        let _guard = self.region_rw_lock.lock_read();
-                        match self.regions.borrow().get(region_id as usize) {
-                            Some(region) => Some(region.clone()),
-                            None => None
-                        }
-                    };
-                    slot = match region {
-                        Some(region) => {
-                            let new_slot = region.resolve_redirection(slot)?;
-                            if new_slot == slot {
-                                return Ok(slot);
-                            }
-                            new_slot
-                        }
-                        None => {
-                            return Ok(slot);
-                        }
-                    }
-                },
-                Err(_) => {
-                    return Ok(slot);
+        Pinned::new(context, field.unwrap_or_else(Value::make_undefined))
+
+    }
+
+    /// Extract all values in a tuple
+    pub fn extract_tuple(&self, value: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        if !value.is_tuple() {
+            return Err(Error::new(TypeNotMatch, "Value is not tuple"));
+        }
+
+        match self.get_internal_slot(value, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Tuple>() {
+                    Some(tuple) => Ok(tuple.get_value_list()),
+                    None => Err(Error::new(TypeNotMatch, "Value is not tuple"))
                 }
-            }
+            },
+            None => Err(Error::new(TypeNotMatch, "Value is not tuple"))
         }
 
     }
 
-    // /// Schedule a collection of younger generations
-    // fn schedule_collect_younger_generations(&self) {
+}
 
-    // }
+/// Isolate value conversions
+impl Isolate {
 
-    // /// Schedule a collection of all generations
-    // fn schedule_collect_all_generations(&self) {
+    /// Convert rust data into a value
+    pub fn convert_to_value<T: ToValue + ?Sized>(&self, data: &T, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        data.to_value(context)
+    }
 
-    // }
+    /// Convert a value into rust data
+    pub fn convert_from_value<T: FromValue>(&self, value: Value, context: &Box<dyn Context>) -> Result<T, Error> {
+        T::from_value(value, context)
+    }
 
 }
 
-/// Isolate root management
+/// Isolate JSON management
 impl Isolate {
 
-    /// Add a value into roots
-    pub fn add_root(&self, value: Value, layout_token: &ReentrantToken) -> Result<Arc<Root>, Error> {
+    /// Parse JSON text into values of the region, keys of objects will be
+    /// text symbols in the JSON symbol scope
+    pub fn parse_json(&self, text: &str, region_id: u32, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        JsonParser::new(self, region_id, text, context).parse()
+    }
 
-        if !value.is_slotted() {
-            return Err(Error::new(FatalError, "Only slot value could added into roots"));
-        }
+    /// Serialize a value into JSON text, keys of objects are sorted
+    ///
+    /// Undefined properties are skipped, and error will be returned if the
+    /// value contains cycles or symbols
+    pub fn to_json(&self, value: Value, context: &Box<dyn Context>) -> Result<String, Error> {
+        JsonWriter::new(self, context).write(value)
+    }
 
-        let _guard = layout_token.lock_read();
+}
 
-        let value = self.resolve_real_value(value, layout_token)?;
+/// Isolate value description
+impl Isolate {
 
-        let _guard_2 = self.roots_rw_lock.lock_write();
+    /// Describe a value for debugging, nested lists, tuples and objects are
+    /// rendered within the limits of the options, and cycles are marked as
+    /// `<cycle>`
+    pub fn describe_value(&self, value: Value, context: &Box<dyn Context>, options: &DescribeOptions) -> String {
+        ValueDescriber::new(self, context, options).describe(value)
+    }
 
-        let mut self_roots = self.roots.borrow_mut();
+    /// Get an adapter displaying the description of a value
+    pub fn display_value<'a>(&'a self, value: Value, context: &'a Box<dyn Context>, options: DescribeOptions) -> DisplayValue<'a> {
+        DisplayValue::new(self, value, context, options)
+    }
 
-        match self_roots.get(&value) {
-            Some(roots) => {
-                let root = roots.get_any_root();
-                root.increase_reference()?;
-                return Ok(root);
-            },
-            None => {}
-        };
-        
-        let roots = Arc::new(Roots::new(value));
+}
 
-        self_roots.insert(value, roots.clone());
+/// Isolate serialization management, only available with the `std` feature
+#[cfg(feature = "std")]
+impl Isolate {
 
-        let root = roots.get_any_root();
-        root.increase_reference()?;
+    /// Stream a value graph in binary format, shared and cyclic references
+    /// are kept
+    pub fn serialize(&self, value: Value, format: SerializationFormat, output: &mut dyn Write, context: &Box<dyn Context>) -> Result<(), Error> {
 
-        self.move_value_out_from_nursery(value, layout_token)?;
+        let mut writer: Box<dyn ItemWriter> = match format {
+            SerializationFormat::Cbor => Box::new(CborWriter::new(output)),
+            SerializationFormat::MessagePack => Box::new(MessagePackWriter::new(output))
+        };
 
-        Ok(root)
+        GraphWriter::new(self, writer.as_mut(), context).write_value(value)
 
     }
 
-    /// Remove a value from roots
-    pub fn remove_root(&self, root: &Arc<Root>) -> Result<(), Error> {
+    /// Read a value graph in binary format into the region
+    pub fn deserialize(&self, input: &mut dyn Read, format: SerializationFormat, region_id: u32, context: &Box<dyn Context>) -> Result<Pinned, Error> {
 
-        let _guard = self.roots_rw_lock.lock_write();
+        let mut reader: Box<dyn ItemReader> = match format {
+            SerializationFormat::Cbor => Box::new(CborReader::new(input)),
+            SerializationFormat::MessagePack => Box::new(MessagePackReader::new(input))
+        };
 
-        root.decrease_reference()?;
+        GraphReader::new(self, region_id, reader.as_mut(), context).read_value()
 
-        let value = root.get_value();
+    }
 
-        self.barrier.borrow().as_ref().map(|barrier| barrier.preremove_value_reference(value));
+}
 
-        let mut self_roots = self.roots.borrow_mut();
+/// Isolate transfer management, only available with the `std` feature
+#[cfg(feature = "std")]
+impl Isolate {
 
-        let alone = match self_roots.get(&value) {
-            None => {
-                return Err(Error::new(FatalError, "Root not found"));
-            },
-            Some(roots) => roots.is_alone()
-        };
+    /// Export a value graph as a packet to be imported by other isolates
+    pub fn export_subgraph(&self, value: Value, context: &Box<dyn Context>) -> Result<TransferPacket, Error> {
 
-        if alone {
-            self_roots.remove(&value);
-        }
+        let mut data = Vec::new();
 
-        Ok(())
+        let foreigns = {
+            let mut writer = CborWriter::new(&mut data);
+            let mut graph_writer = GraphWriter::new(self, &mut writer, context);
+            graph_writer.enable_foreign_transfer();
+            graph_writer.write_value(value)?;
+            graph_writer.take_foreigns()
+        };
+
+        Ok(TransferPacket::new(data, foreigns))
 
     }
 
-    /// Refresh root value
-    pub fn refresh_root(&self, old_value: Value, new_value: Value) -> Result<(), Error> {
+    /// Import a value graph exported by another isolate into the region
+    pub fn import_subgraph(&self, packet: &TransferPacket, region_id: u32, context: &Box<dyn Context>) -> Result<Pinned, Error> {
 
-        if !old_value.is_slotted() {
-            return Err(Error::new(FatalError, "Only slot value could added into roots"));
-        }
+        let mut input = packet.get_data();
 
-        if !new_value.is_slotted() {
-            return Err(Error::new(FatalError, "Only slot value could added into roots"));
-        }
+        let mut reader = CborReader::new(&mut input);
+        let mut graph_reader = GraphReader::new(self, region_id, &mut reader, context);
+        graph_reader.set_foreigns(packet.get_foreigns().to_vec());
 
-        let _guard = self.roots_rw_lock.lock_write();
+        graph_reader.read_value()
 
-        let mut self_roots = self.roots.borrow_mut();
+    }
 
-        let (old_roots, new_roots) = match self_roots.get(&old_value) {
-            None => {
-                return Ok(());
-            },
-            Some(old_roots) => {
-                match self_roots.get(&new_value) {
-                    None => (old_roots.clone(), None),
-                    Some(new_roots) => (old_roots.clone(), Some(new_roots.clone()))
-                }
-            }
-        };
+}
 
-        old_roots.refresh_value(old_value, new_value);
+/// Isolate list management
+impl Isolate {
 
-        match new_roots {
-            None => { self_roots.insert(new_value, old_roots); }
-            Some(new_roots) => { new_roots.merge_roots(old_roots)?; }
-        }
+    pub fn list_push(&self, subject: Value, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+        self.mutate_list(subject, context, |list| {
+            let (removes, adds) = list.push(value);
+            (Ok(()), removes, adds)
+        })?
+    }
 
-        self_roots.remove(&old_value);
+    /// Remove the last value of a list, undefined will be returned if the list is empty
+    pub fn list_pop(&self, subject: Value, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        self.mutate_list(subject, context, |list| {
+            let (removes, adds) = list.pop();
+            let value = removes.first().copied().unwrap_or_else(Value::make_undefined);
+            (Pinned::new(context, value), removes, adds)
+        })?
+    }
 
-        Ok(())
+    pub fn list_insert(&self, subject: Value, index: usize, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+        self.mutate_list(subject, context, |list| {
+            let (removes, adds) = list.insert(index, value);
+            (Ok(()), removes, adds)
+        })?
+    }
 
+    /// Remove the value at the index of a list, undefined will be returned if the index is out of range
+    pub fn list_remove(&self, subject: Value, index: usize, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        self.mutate_list(subject, context, |list| {
+            let (removes, adds) = list.remove(index);
+            let value = removes.first().copied().unwrap_or_else(Value::make_undefined);
+            (Pinned::new(context, value), removes, adds)
+        })?
     }
 
-    pub fn list_roots(&self) -> Vec<Value> {
+    /// Replace values in a list, returns the deleted values
+    pub fn list_splice(&self, subject: Value, from: usize, delete_count: usize, values: Vec<Value>, context: &Box<dyn Context>) -> Result<Vec<Pinned>, Error> {
+        self.mutate_list(subject, context, |list| {
+            let (removes, adds) = list.splice(from, delete_count, values);
+            let mut result = Vec::with_capacity(removes.len());
+            for value in removes.iter() {
+                match Pinned::new(context, *value) {
+                    Ok(pinned) => result.push(pinned),
+                    Err(error) => { return (Err(error), removes, adds); }
+                }
+            }
+            (Ok(result), removes, adds)
+        })?
+    }
 
-        let _guard = self.roots_rw_lock.lock_read();
+    pub fn list_set_at(&self, subject: Value, index: usize, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+        self.mutate_list(subject, context, |list| {
+            let (removes, adds) = list.set_element(index, value);
+            (Ok(()), removes, adds)
+        })?
+    }
 
-        let mut roots = Vec::new();
-        for value in self.roots.borrow().keys() {
-            roots.push(*value);
-        }
+    /// Stable sort a list in place with a host comparator
+    ///
+    /// Values are sorted as a snapshot without the slot layout locked, so the
+    /// comparator could reenter the isolate, and an error is returned if the
+    /// list is mutated or its values moved while sorting
+    pub fn sort_list<F>(&self, subject: Value, comparator: F, context: &Box<dyn Context>) -> Result<(), Error>
+        where F: FnMut(Value, Value) -> Result<core::cmp::Ordering, Error> {
 
-        roots
-    }
+        self.mutate_list(subject, context, |list| (list.sort_by(comparator), Vec::new(), Vec::new()))?
 
-    pub fn list_buitins(&self) -> Vec<Value> {
-        vec!(
-            self.boolean_prototype,
-            self.integer_prototype,
-            self.float_prototype,
-            self.text_prototype,
-            self.symbol_prototype,
-            self.list_prototype,
-            self.tuple_prototype,
-            self.object_prototype
-        )
     }
 
-    pub fn list_values_in_nursery(&self) -> Vec<Value> {
+    /// Binary search a sorted list, the comparator compares an element with the target
+    ///
+    /// The snapshot searched is checked against the list once searched, as
+    /// the slot layout is not locked while comparing
+    pub fn binary_search_list<F>(&self, subject: Value, comparator: F, context: &Box<dyn Context>) -> Result<Result<usize, usize>, Error>
+        where F: FnMut(Value) -> Result<core::cmp::Ordering, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        self.mutate_list(subject, context, |list| (list.binary_search_by(comparator), Vec::new(), Vec::new()))?
 
-        let mut values = Vec::new();
+    }
 
-        for (_index, region) in self.regions.borrow().iterate_items() {
-            for value in region.list_values_in_nursery() {
-                values.push(value);
-            }
+    /// Mutate the list internal slot of a value, and update the references
+    /// of the subject with the removed and added values
+    fn mutate_list<T, F>(&self, subject: Value, context: &Box<dyn Context>, mutate: F) -> Result<T, Error>
+        where F: FnOnce(&List) -> (T, Vec<Value>, Vec<Value>) {
+
+        if !subject.is_list() {
+            return Err(Error::new(TypeNotMatch, "Value is not list"));
         }
 
-        values
+        let (result, removes, adds) = match self.get_internal_slot(subject, 0, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<List>() {
+                    Some(list) => mutate(list),
+                    None => { return Err(Error::new(TypeNotMatch, "Value is not list")); }
+                }
+            },
+            None => { return Err(Error::new(TypeNotMatch, "Value is not list")); }
+        };
+
+        context.update_value_references(subject, &adds, &removes)?;
+
+        Ok(result)
 
     }
 
-    /// Add a value into weak roots with drop listener
-    pub fn add_weak_root(&self, value: Value, drop_listener: Option<Box<dyn DropListener>>, layout_token: &ReentrantToken) -> Result<Arc<WeakRoot>, Error> {
+}
 
-        if !value.is_slotted() {
-            return Err(Error::new(FatalError, "Only slot value could added into roots"));
-        }
+/// Isolate regions management
+impl Isolate {
 
-        let _guard = layout_token.lock_read();
+    pub fn get_region_number(&self) -> u32 {
 
-        let value = self.resolve_real_value(value, layout_token)?;
+        let _guard = self.region_rw_lock.lock_read();
 
-        let _guard_2 = self.roots_rw_lock.lock_write();
+        self.regions.borrow().get_size() as u32
 
-        let mut self_roots = self.weak_roots.borrow_mut();
+    }
 
-        if self_roots.get(&value).is_none() {
-            self_roots.insert(value, RefCell::new(HashSet::new()));
-        }
+    pub fn peek_next_region_id(&self) -> u32 {
 
-        let weak_root = Arc::new(WeakRoot::new(&self.weak_id_generator, value, drop_listener));
+        let _guard = self.region_rw_lock.lock_read();
 
-        self_roots.get(&value).unwrap().borrow_mut().insert(weak_root.clone());
-       
-        Ok(weak_root)
+        self.regions.borrow().peek_next_item_index() as u32
 
     }
 
-    /// Remove a value from weak roots
-    pub fn remove_weak_root(&self, root: &Arc<WeakRoot>) -> Result<(), Error> {
+    pub fn shrink_next_region_id(&self, from: u32, to: u32) -> u32 {
 
-        let _guard = self.roots_rw_lock.lock_write();
+        let _guard = self.region_rw_lock.lock_write();
 
-        let value = root.get_value();
-        if value.is_none() {
-            return Ok(());
-        }
+        self.regions.borrow_mut().shrink_next_item_index(from as usize, to as usize) as u32
 
-        let value = value.unwrap();
+    }
 
-        let mut self_roots = self.weak_roots.borrow_mut();
+    /// Release pages of the region table whose regions are all recycled,
+    /// returns count of pages released
+    pub fn shrink_regions(&self) -> usize {
 
-        if self_roots.get(&value).is_none() {
-            return Err(Error::new(FatalError, "Weak root not found"));
-        }
+        let _guard = self.region_rw_lock.lock_write();
 
-        let drop = {
-            let mut weak_roots = self_roots.get(&value).unwrap().borrow_mut();
-            if !weak_roots.remove(root) {
-                return Err(Error::new(FatalError, "Weak root not found"));
-            }
-            weak_roots.is_empty()
-        };
+        self.regions.borrow_mut().shrink_to_fit()
 
-        if drop {
-            self_roots.remove(&value);
-        }
+    }
 
-        Ok(())
+    /// List IDs below the next region ID which are free to reuse, regions
+    /// paged out are excluded
+    pub fn list_free_region_ids(&self) -> Vec<u32> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        let regions = self.regions.borrow();
+        regions.iterate_free_indices()
+            .filter(|index| !regions.is_cold(*index))
+            .map(|index| index as u32)
+            .collect()
 
     }
 
-    /// Refresh weak root value
-    pub fn refresh_weak_root(&self, old_value: Value, new_value: Value) -> Result<(), Error> {
+    /// Get occupancy of the pages of local regions
+    pub fn get_region_page_stats(&self) -> PageMapStats {
 
-        if !old_value.is_slotted() {
-            return Err(Error::new(FatalError, "Only slot value could added into roots"));
-        }
+        let _guard = self.region_rw_lock.lock_read();
 
-        if !new_value.is_slotted() {
-            return Err(Error::new(FatalError, "Only slot value could added into roots"));
-        }
+        self.regions.borrow().get_page_stats()
 
-        let _guard = self.roots_rw_lock.lock_write();
+    }
 
-        let mut self_roots = self.weak_roots.borrow_mut();
+    /// Change the region table and republish the region index, should be
+    /// called with the region lock written
+    fn update_regions<T>(&self, update: impl FnOnce(&mut RegionTable) -> T) -> T {
 
-        match self_roots.get(&old_value) {
-            None => {
-                return Ok(());
-            },
-            Some(old_roots) => {
-                match self_roots.get(&new_value) {
-                    None => {
-                        let mut new_roots = HashSet::new();
-                        for value in old_roots.borrow().iter() {
-                            value.refresh_value(old_value, new_value);
-                            new_roots.insert(value.clone());
-                        }
-                        self_roots.insert(new_value, RefCell::new(new_roots));
-                    },
-                    Some(new_roots) => {
-                        for value in old_roots.borrow().iter() {
-                            value.refresh_value(old_value, new_value);
-                            new_roots.borrow_mut().insert(value.clone());
-                        }
-                    }
-                }
-            }
-        };
+        let result = update(&mut self.regions.borrow_mut());
 
-        self_roots.remove(&old_value);
+        self.region_index.replace(self.regions.borrow().create_index());
 
-        Ok(())
+        result
 
     }
 
-}
+    /// Create a new empty region in the new space
+    pub fn create_region(&self) -> Result<u32, Error> {
+        self.create_region_in_space(Space::New)
+    }
 
-impl Isolate {
+    /// Create a new empty region in specified space, the region is protected
+    /// until unprotected
+    pub fn create_region_in_space(&self, space: Space) -> Result<u32, Error> {
 
-    pub fn flip_base_color(&self) -> u8 {
+        let _guard = self.region_rw_lock.lock_write();
 
-        let _guard = self.region_rw_lock.lock_read();
+        let id = self.update_regions(|regions| regions.gain_item())? as u32;
 
-        if self.base_color.get() == BASE_WHITE {
-            self.base_color.set(BASE_BLACK);
-            BASE_BLACK
-        } else {
-            self.base_color.set(BASE_WHITE);
-            BASE_WHITE
-        }
+        self.region_infos.borrow_mut().insert(id, RegionInfo { space: space, protected: true });
+
+        self.region_watermarks.update(0, RegionKind::Standard.get_slot_capacity() as isize);
+
+        Ok(id)
 
     }
 
-    pub fn get_base_color(&self) -> u8 {
+    /// Create a region holding only one slot for a large object, the region
+    /// is protected until unprotected as normal regions
+    pub fn create_large_region(&self) -> Result<u32, Error> {
 
-        let _guard = self.region_rw_lock.lock_read();
+        let _guard = self.region_rw_lock.lock_write();
 
-        self.base_color.get()
+        let id = self.update_regions(|regions| regions.gain_large_item())? as u32;
 
-    }
+        self.region_infos.borrow_mut().insert(id, RegionInfo { space: Space::New, protected: true });
 
-    pub fn mark_as_white(&self, value: Value) -> Result<(), Error> {
+        self.region_watermarks.update(0, RegionKind::Large.get_slot_capacity() as isize);
 
-        if value.is_slotted() {
-            return Ok(())
-        }
+        Ok(id)
 
-        let region_id = value.get_region_id()?;
+    }
 
-        let _guard = self.region_rw_lock.lock_read();
+    pub fn get_region_kind(&self, region_id: u32) -> Result<RegionKind, Error> {
 
-        let base = self.base_color.get();
+        let _guard = self.region_rw_lock.lock_read();
 
         match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.mark_as_white(value, base),
+            Some(region) => Ok(region.get_kind()),
             None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
-    pub fn mark_as_black(&self, value: Value) -> Result<(), Error> {
+    /// Create a value with a large payload in a dedicated region, the payload
+    /// is set as the internal slot 0 of the value, and the region will be
+    /// recycled by the collector after the value is swept
+    pub fn create_large_value(&self, primitive_type: PrimitiveType, prototype: Value, internal_slot: Arc<dyn InternalSlot>, context: &Box<dyn Context>) -> Result<Value, Error> {
 
-        if value.is_slotted() {
-            return Ok(())
-        }
+        let region_id = self.create_large_region()?;
 
-        let region_id = value.get_region_id()?;
+        let value = match self.gain_slot(region_id, primitive_type, prototype, context.get_slot_layout_token()) {
+            Ok(value) => value,
+            Err(error) => {
+                self.unprotect_region(region_id)?;
+                self.recycle_region(region_id)?;
+                return Err(error);
+            }
+        };
+
+        self.set_internal_slot(value, 0, internal_slot, context)?;
+
+        self.unprotect_region(region_id)?;
+
+        Ok(value)
+
+    }
+
+    pub fn list_region_ids(&self) -> Result<Vec<u32>, Error> {
 
         let _guard = self.region_rw_lock.lock_read();
 
-        let base = self.base_color.get();
+        let mut ids = Vec::new();
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.mark_as_black(value, base),
-            None => Err(Error::new(FatalError, "Region not found"))
+        for (index, _page) in self.regions.borrow().iterate_items() {
+            ids.push(index as u32);
         }
- 
+
+        Ok(ids)
+
     }
 
-    pub fn mark_as_gray(&self, value: Value) -> Result<bool, Error> {
+    pub fn sweep_region(&self, region_id: u32, context: &Box<dyn Context>) -> Result<(), Error> {
 
-        if value.is_slotted() {
-            return Ok(false);
+        // Regions with colors kept outside are copied only if garbage found
+        if self.fork_colors.is_region_tracked(region_id) {
+            let values = {
+                let _guard = self.region_rw_lock.lock_read();
+                match self.regions.borrow().get(region_id as usize) {
+                    Some(region) => region.list_alive_values()?,
+                    None => {
+                        return Err(Error::new(FatalError, "Region not found"));
+                    }
+                }
+            };
+            if values.iter().all(|value| self.fork_colors.get_color(*value) == Some(ForkColor::Black)) {
+                return Ok(());
+            }
         }
 
-        let region_id = value.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
 
         let _guard = self.region_rw_lock.lock_read();
 
-        let base = self.base_color.get();
+        let region = match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.clone(),
+            None => {
+                return Err(Error::new(FatalError, "Region not found"));
+            }
+        };
+
+        self.track_region_occupancy(&region, || region.sweep_values(self.base_color.get(), context))?;
+
+        Ok(())
+
+    }
+
+    pub fn is_region_empty(&self, region_id: u32) -> Result<bool, Error> {
+
+        let _guard = self.region_rw_lock.lock_read();
 
         match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.mark_as_gray(value, base),
+            Some(region) => Ok(region.is_empty()),
             None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
-    pub fn is_white(&self, value: Value) -> Result<bool, Error> {
-
-        let region_id = value.get_region_id()?;
+    pub fn is_region_full(&self, region_id: u32) -> Result<bool, Error> {
 
         let _guard = self.region_rw_lock.lock_read();
 
-        let base = self.base_color.get();
-
         match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.is_white(value, base),
+            Some(region) => Ok(region.is_full()),
             None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
-    pub fn is_black(&self, value: Value) -> Result<bool, Error> {
-
-        let region_id = value.get_region_id()?;
+    pub fn need_region_refragment(&self, region_id: u32) -> Result<f32, Error> {
 
         let _guard = self.region_rw_lock.lock_read();
 
-        let base = self.base_color.get();
-
         match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.is_black(value, base),
+            Some(region) => Ok(region.need_refragment()),
             None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
-    pub fn is_gray(&self, value: Value) -> Result<bool, Error> {
+    pub fn refragment_region(&self, region_id: u32, target_region_id: u32, context: &Box<dyn Context>) -> Result<bool, Error> {
 
-        let region_id = value.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+        self.ensure_region_writable(target_region_id)?;
 
         let _guard = self.region_rw_lock.lock_read();
 
-        let base = self.base_color.get();
+        let region = match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.clone(),
+            None => {
+                return Err(Error::new(FatalError, "Region not found"));
+            }
+        };
+        let target_region = match self.regions.borrow().get(target_region_id as usize) {
+            Some(region) => region.clone(),
+            None => {
+                return Err(Error::new(FatalError, "Region not found"));
+            }
+        };
 
-        match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.is_gray(value, base),
-            None => Err(Error::new(FatalError, "Region not found"))
+        for value in region.list_alive_values()? {
+            if target_region.is_full() {
+                return Ok(false);
+            }
+            self.move_slot_by_copying(value, target_region_id, context)?;
         }
+        region.recalculate_next_empty_slot_index()?;
 
-    }
+        Ok(true)
 
-    pub fn list_and_autorefresh_referenced_values(&self, value: Value, context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Symbol>), Error> {
+    }
 
-        let region_id = value.get_region_id()?;
+    pub fn is_region_protected(&self, region_id: u32) -> Result<bool, Error> {
 
         let _guard = self.region_rw_lock.lock_read();
 
         match self.regions.borrow().get(region_id as usize) {
-            Some(region) => region.list_and_autorefresh_referenced_values(value, context),
+            Some(_) => Ok(match self.region_infos.borrow().get(&region_id) {
+                Some(info) => info.protected,
+                None => false
+            }),
             None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
-}
-
-/// Isolate references managment
-impl Isolate {
-
-    /// Add a symbol reference record to keep it from garbage collection
-    pub fn add_symbol_reference(&self, symbol: Symbol) -> Result<(), Error> {
+    pub fn protect_region(&self, region_id: u32) -> Result<(), Error> {
 
-        let _guard = self.symbol_rw_lock.lock_read();
+        let _guard = self.region_rw_lock.lock_write();
 
-        match self.symbol_lut.borrow().get(&symbol) {
-            Some(symbol_scope) => {
-                symbol_scope.add_symbol_reference(symbol)
+        match self.regions.borrow().get(region_id as usize) {
+            Some(_) => {
+                match self.region_infos.borrow_mut().get_mut(&region_id) {
+                    Some(info) => {
+                        if info.protected {
+                            return Err(Error::new(FatalError, "Region already protected"));
+                        }
+                        info.protected = true;
+                        Ok(())
+                    },
+                    None => Err(Error::new(FatalError, "Region is shared"))
+                }
             },
-            None => Err(Error::new(FatalError, "Symbol not found"))
+            None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
-    /// Remove a symbol reference record to keep it from garbage collection
-    pub fn remove_symbol_reference(&self, symbol: Symbol) -> Result<(), Error> {
+    pub fn unprotect_region(&self, region_id: u32) -> Result<(), Error> {
 
-        let _guard = self.symbol_rw_lock.lock_read();
+        let _guard = self.region_rw_lock.lock_write();
 
-        match self.symbol_lut.borrow().get(&symbol) {
-            Some(symbol_scope) => {
-                symbol_scope.remove_symbol_reference(symbol)
+        match self.regions.borrow().get(region_id as usize) {
+            Some(_) => {
+                match self.region_infos.borrow_mut().get_mut(&region_id) {
+                    Some(info) => {
+                        if !info.protected {
+                            return Err(Error::new(FatalError, "Region not protected"));
+                        }
+                        info.protected = false;
+                        Ok(())
+                    },
+                    None => Err(Error::new(FatalError, "Region not protected"))
+                }
             },
-            None => Err(Error::new(FatalError, "Symbol not found"))
+            None => Err(Error::new(FatalError, "Region not found"))
         }
-
+ 
     }
 
-    /// Move a value out from the nursery
-    pub fn move_value_out_from_nursery(&self, value: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
-
-        if !value.is_slotted() {
-            return Ok(());
-        }
-
-        let _guard = layout_token.lock_read();
-
-        let region_id = value.get_region_id()?;
+    pub fn recycle_region(&self, region_id: u32) -> Result<(), Error> {
 
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
+        let _guard = self.region_rw_lock.lock_write();
 
-        match region {
-            Some(region) => region.move_out_from_nursery(value)?,
+        let region = match self.regions.borrow().get(region_id as usize) {
+            Some(region) => {
+                if let Some(info) = self.region_infos.borrow().get(&region_id) {
+                    if info.protected {
+                        return Err(Error::new(FatalError, "Region protected"));
+                    }
+                }
+                region.clone()
+            },
             None => {
-                return Err(Error::new(FatalError, "Region of slot not found"));
+                return Err(Error::new(FatalError, "Region not found"));
             }
         };
 
+        if !region.is_empty() {
+            return Err(Error::new(FatalError, "Region not empty"));
+        }
+
+        self.update_regions(|regions| regions.recycle_item(region_id as usize))?;
+        self.region_generation.fetch_add(1, Ordering::SeqCst);
+
+        self.region_infos.borrow_mut().remove(&region_id);
+        self.region_tags.borrow_mut().remove(&region_id);
+        self.quotas.release_region(region_id, region.get_occupied_count());
+
+        self.region_watermarks.update(-(region.get_occupied_count() as isize), -(region.get_kind().get_slot_capacity() as isize));
+
         Ok(())
+
     }
 
-    /// Add a reference relationship
-    pub fn add_value_reference(&self, from: Value, to: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+}
 
-        if !from.is_slotted() {
-            return Ok(());
-        }
+/// Isolate space management
+impl Isolate {
 
-        if to.is_symbol() {
-            return self.add_symbol_reference(to.extract_symbol(Symbol::new(0)));
+    /// Get the space of a region, shared regions are treated as immortal
+    pub fn get_region_space(&self, region_id: u32) -> Result<Space, Error> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        if self.regions.borrow().get(region_id as usize).is_none() {
+            return Err(Error::new(FatalError, "Region not found"));
         }
 
-        if !to.is_slotted() {
-            return Ok(());
+        match self.region_infos.borrow().get(&region_id) {
+            Some(info) => Ok(info.space),
+            None => Ok(Space::Immortal)
         }
 
-        let _guard = layout_token.lock_read();
+    }
 
-        let to_region_id = to.get_region_id()?;
-        let to_region_slot = to.get_region_slot()?;
-        let from_region_id = from.get_region_id()?;
-        let from_region_slot = from.get_region_slot()?;
-        if (to_region_id == from_region_id) && (to_region_slot == from_region_slot) {
-            return Ok(());
-        }
+    /// Move a region into another space, such as promoting a region from the
+    /// new space to the old space
+    pub fn set_region_space(&self, region_id: u32, space: Space) -> Result<(), Error> {
 
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(to_region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => region.add_reference(to, from)?,
-            None => {
-                return Err(Error::new(FatalError, "Region of slot not found"));
-            }
-        };
+        let _guard = self.region_rw_lock.lock_write();
 
-        Ok(())
+        if self.regions.borrow().get(region_id as usize).is_none() {
+            return Err(Error::new(FatalError, "Region not found"));
+        }
+
+        match self.region_infos.borrow_mut().get_mut(&region_id) {
+            Some(info) => {
+                info.space = space;
+                Ok(())
+            },
+            None => Err(Error::new(FatalError, "Region is shared"))
+        }
 
     }
 
-    /// Remove a reference relationship
-    pub fn remove_value_reference(&self, from: Value, to: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+    pub fn list_region_ids_in_space(&self, space: Space) -> Result<Vec<u32>, Error> {
 
-        if !from.is_slotted() {
-            return Ok(());
-        }
+        let _guard = self.region_rw_lock.lock_read();
 
-        if to.is_symbol() {
-            return self.remove_symbol_reference(to.extract_symbol(Symbol::new(0)));
-        }
+        let region_infos = self.region_infos.borrow();
 
-        if !to.is_slotted() {
-            return Ok(());
+        let mut ids = Vec::new();
+
+        for (index, _page) in self.regions.borrow().iterate_items() {
+            if let Some(info) = region_infos.get(&(index as u32)) {
+                if info.space == space {
+                    ids.push(index as u32);
+                }
+            }
         }
 
-        let real_to = self.resolve_real_value(to, layout_token)?;
+        Ok(ids)
 
-        self.barrier.borrow().as_ref().map(|barrier| barrier.preremove_value_reference(real_to));
+    }
 
-        let _guard = layout_token.lock_read();
+    /// Gain a slot in a region of specified space, a new region will be
+    /// created in the space if no region could gain slots quickly
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn gain_slot_in_space(&self, space: Space, primitive_type: PrimitiveType, prototype: Value, layout_token: &ReentrantToken) -> Result<Value, Error> {
 
-        let to_region_id = to.get_region_id()?;
-        let to_region_slot = to.get_region_slot()?;
-        let from_region_id = from.get_region_id()?;
-        let from_region_slot = from.get_region_slot()?;
-        if (to_region_id == from_region_id) && (to_region_slot == from_region_slot) {
-            return Ok(());
-        }
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(to_region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        for region_id in self.list_region_ids_in_space(space)? {
+            if self.is_region_protected(region_id)? {
+                continue;
             }
-        };
-        match region {
-            Some(region) => {
-                let (no_more_reference_map, to_redirection) = region.remove_reference(to, from)?;
-                if no_more_reference_map {
-                    region.remove_redirection_from(to, to_redirection)?;
-                }
-            },
-            None => {
-                return Err(Error::new(FatalError, "Region of slot not found"));
+            if self.get_region_kind(region_id)? != RegionKind::Standard {
+                continue;
+            }
+            if self.could_region_gain_slot_quickly(region_id) {
+                return self.gain_slot(region_id, primitive_type, prototype, layout_token);
             }
         }
 
-        Ok(())
+        let region_id = self.create_region_in_space(space)?;
+
+        let value = self.gain_slot(region_id, primitive_type, prototype, layout_token);
+
+        self.unprotect_region(region_id)?;
+
+        value
 
     }
 
-    /// Update moved value reference relationship
-    pub fn refresh_value_reference(&self, from: Value, old_to: Value, new_to: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+    pub fn get_space_policy(&self, space: Space) -> SpacePolicy {
 
-        if (!from.is_slotted()) || (!old_to.is_slotted()) || (!new_to.is_slotted()) {
-            return Ok(());
-        }
+        let _guard = self.region_rw_lock.lock_read();
 
-        if (from == old_to) || (from == new_to) || (old_to == new_to) {
-            return Ok(());
+        match self.space_policies.borrow().get(&space) {
+            Some(policy) => *policy,
+            None => SpacePolicy::for_space(space)
         }
 
-        let _guard = layout_token.lock_read();
+    }
 
-        self.add_value_reference(from, new_to, layout_token)?;
-        self.remove_value_reference(from, old_to, layout_token)?;
+    /// Overwrite the collection policy of a space
+    pub fn set_space_policy(&self, space: Space, policy: SpacePolicy) {
 
-        Ok(())
+        let _guard = self.region_rw_lock.lock_write();
+
+        self.space_policies.borrow_mut().insert(space, policy);
+
+    }
 
+    /// Get the collection policy of the space a region belongs to
+    pub fn get_region_space_policy(&self, region_id: u32) -> Result<SpacePolicy, Error> {
+        let space = self.get_region_space(region_id)?;
+        Ok(self.get_space_policy(space))
     }
 
 }
 
-/// Isolate symbols management
+/// Isolate region tag management, tags are embedder metadata such as module
+/// IDs, tenants or arenas, they are dropped once their regions recycled and
+/// inherited by forks
 impl Isolate {
 
-    /// Get a symbol with specified scope and text
-    pub fn get_text_symbol(&self, scope: &str, text: &str) -> Symbol {
+    /// Tag a region, the previous tag of the region is replaced
+    pub fn set_region_tag(&self, region_id: u32, tag: Arc<dyn Any>) -> Result<(), Error> {
 
-        {
-            let _guard = self.symbol_rw_lock.lock_read();
-            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
-                let symbol = result.get_text_symbol(text);
-                if self.symbol_lut.borrow().get(&symbol).is_some() {
-                    return symbol;
-                }
-            }
-        }
+        let _guard = self.region_rw_lock.lock_write();
 
-        {
-            let _guard = self.symbol_rw_lock.lock_write();
-            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
-                let symbol = result.get_text_symbol(text);
-                self.symbol_lut.borrow_mut().insert(symbol, result.clone());
-                return symbol;
-            }
-            let symbol_scope = Arc::new(SymbolScope::new(self.symbol_id_generator.clone(), scope));
-            let symbol = symbol_scope.get_text_symbol(text);
-            self.symbol_scopes.borrow_mut().insert(scope.to_owned(), symbol_scope.clone());
-            self.symbol_lut.borrow_mut().insert(symbol, symbol_scope);
-            symbol
+        if self.regions.borrow().get(region_id as usize).is_none() {
+            return Err(Error::new(FatalError, "Region not found"));
         }
 
+        self.region_tags.borrow_mut().insert(region_id, tag);
+
+        Ok(())
+
     }
 
-    /// Get a symbol with specified scope and value
-    pub fn get_value_symbol(&self, scope: &str, value: Value) -> Symbol {
+    pub fn get_region_tag(&self, region_id: u32) -> Result<Option<Arc<dyn Any>>, Error> {
 
-        {
-            let _guard = self.symbol_rw_lock.lock_read();
-            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
-                let symbol = result.get_value_symbol(value);
-                if self.symbol_lut.borrow().get(&symbol).is_some() {
-                    return symbol;
-                }
-            }
-        }
+        let _guard = self.region_rw_lock.lock_read();
 
-        {
-            let _guard = self.symbol_rw_lock.lock_write();
-            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
-                let symbol = result.get_value_symbol(value);
-                self.symbol_lut.borrow_mut().insert(symbol, result.clone());
-                return symbol;
-            }
-            let symbol_scope = Arc::new(SymbolScope::new(self.symbol_id_generator.clone(), scope));
-            let symbol = symbol_scope.get_value_symbol(value);
-            self.symbol_scopes.borrow_mut().insert(scope.to_owned(), symbol_scope.clone());
-            self.symbol_lut.borrow_mut().insert(symbol, symbol_scope);
-            symbol
+        if self.regions.borrow().get(region_id as usize).is_none() {
+            return Err(Error::new(FatalError, "Region not found"));
         }
 
+        Ok(self.region_tags.borrow().get(&region_id).cloned())
+
     }
 
-    /// Resolve symbol info from a symbol
-    pub fn resolve_symbol_info(&self, symbol: Symbol) -> Result<SymbolInfo, Error> {
+    /// Remove the tag of a region, the removed tag is returned
+    pub fn clear_region_tag(&self, region_id: u32) -> Result<Option<Arc<dyn Any>>, Error> {
 
-        let _guard = self.symbol_rw_lock.lock_read();
+        let _guard = self.region_rw_lock.lock_write();
 
-        match self.symbol_lut.borrow().get(&symbol) {
-            Some(symbol_scope) => {
-                match symbol_scope.get_symbol_record(symbol) {
-                    Some(symbol_record) => Ok(SymbolInfo::new(symbol, &symbol_scope, symbol_record)),
-                    None => Err(Error::new(FatalError, "Symbol not found"))
-                }
-            },
-            None => Err(Error::new(FatalError, "Symbol not found"))
+        if self.regions.borrow().get(region_id as usize).is_none() {
+            return Err(Error::new(FatalError, "Region not found"));
         }
 
+        Ok(self.region_tags.borrow_mut().remove(&region_id))
+
     }
 
-    /// Recycle symbol
-    pub fn recycle_symbol(&self, symbol: Symbol) -> Result<(), Error> {
+    /// List regions tagged with tags matched, for accounting and tearing
+    /// down regions of a tenant
+    pub fn list_tagged_region_ids<F>(&self, matched: F) -> Vec<u32> where F: Fn(&Arc<dyn Any>) -> bool {
 
-        let _guard = self.symbol_rw_lock.lock_read();
+        let _guard = self.region_rw_lock.lock_read();
 
-        match self.symbol_lut.borrow().get(&symbol) {
-            Some(symbol_scope) => {
-                symbol_scope.recycle_symbol(symbol)
-            },
-            None => Err(Error::new(FatalError, "Symbol not found"))
-        }
+        let mut ids: Vec<u32> = self.region_tags.borrow().iter()
+            .filter(|(_id, tag)| matched(tag))
+            .map(|(id, _tag)| *id)
+            .collect();
+        ids.sort();
+
+        ids
 
     }
 
 }
 
-/// Internal slot and traps keeper
+/// Isolate quota management, regions are assigned to quotas of tenants
+/// sharing the isolate, and slots gained in regions of a quota exceeding its
+/// budgets fail with `QuotaExceeded`
+///
+/// Slots moved by compactions are counted against the quotas of the regions
+/// they are moved into
 impl Isolate {
 
-    pub fn protect_slot_trap(&self, slot_trap: &Arc<dyn SlotTrap>) -> Result<(u64, Arc<dyn SlotTrap>), Error> {
-        let protected_id = self.next_protected_id.fetch_add(1, Ordering::SeqCst);
-        let _guard = self.protection_rw_lock.lock_write();
-        self.protected_slot_traps.borrow_mut().insert(protected_id, slot_trap.clone());
-        Ok((protected_id, slot_trap.clone()))
+    /// Set budgets of a quota, the quota is created if not exists
+    pub fn set_quota(&self, quota_id: u64, quota: Quota) {
+        self.quotas.set_quota(quota_id, quota);
     }
 
-    pub fn protect_internal_slot(&self, internal_slot: &Arc<dyn InternalSlot>) -> Result<(u64, Arc<dyn InternalSlot>), Error> {
-        let protected_id = self.next_protected_id.fetch_add(1, Ordering::SeqCst);
-        let _guard = self.protection_rw_lock.lock_write();
-        self.protected_internal_slots.borrow_mut().insert(protected_id, internal_slot.clone());
-        Ok((protected_id, internal_slot.clone()))
+    /// Remove a quota, regions assigned to the quota are no longer limited
+    pub fn remove_quota(&self, quota_id: u64) -> Result<(), Error> {
+        self.quotas.remove_quota(quota_id)
     }
 
-    pub fn protect_property_trap(&self, property_trap: &Arc<dyn PropertyTrap>) -> Result<(u64, Arc<dyn PropertyTrap>), Error> {
-        let protected_id = self.next_protected_id.fetch_add(1, Ordering::SeqCst);
-        let _guard = self.protection_rw_lock.lock_write();
-        self.protected_property_traps.borrow_mut().insert(protected_id, property_trap.clone());
-        Ok((protected_id, property_trap.clone()))
+    pub fn get_quota(&self, quota_id: u64) -> Option<Quota> {
+        self.quotas.get_quota(quota_id)
     }
 
-    pub fn unprotect_slot_trap(&self, protected_id: u64) -> Result<(), Error> {
-        let _guard = self.protection_rw_lock.lock_write();
-        match self.protected_slot_traps.borrow_mut().remove(&protected_id) {
-            None => Err(Error::new(FatalError, "No slot trap found")),
-            Some(_) => Ok(())
-        }
+    pub fn get_quota_usage(&self, quota_id: u64) -> Option<QuotaUsage> {
+        self.quotas.get_usage(quota_id)
     }
 
-    pub fn unprotect_internal_slot(&self, protected_id: u64) -> Result<(), Error> {
-        let _guard = self.protection_rw_lock.lock_write();
-        match self.protected_internal_slots.borrow_mut().remove(&protected_id) {
-            None => Err(Error::new(FatalError, "No internal slot found")),
-            Some(_) => Ok(())
+    pub fn get_region_quota_id(&self, region_id: u32) -> Option<u64> {
+        self.quotas.get_region_quota_id(region_id)
+    }
+
+    /// Assign a local region to a quota, slots already occupied in the
+    /// region count against the quota
+    pub fn assign_region_quota(&self, region_id: u32, quota_id: u64) -> Result<(), Error> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        let region = match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.clone(),
+            None => {
+                return Err(Error::new(FatalError, "Region not found"));
+            }
+        };
+        if !self.region_infos.borrow().contains_key(&region_id) {
+            return Err(Error::new(FatalError, "Region is shared"));
         }
+
+        self.quotas.assign_region(quota_id, region_id, region.get_occupied_count())
+
     }
 
-    pub fn unprotect_property_trap(&self, protected_id: u64) -> Result<(), Error> {
-        let _guard = self.protection_rw_lock.lock_write();
-        match self.protected_property_traps.borrow_mut().remove(&protected_id) {
-            None => Err(Error::new(FatalError, "No property trap found")),
-            Some(_) => Ok(())
+    /// Create a new region in the new space assigned to a quota, the region
+    /// is protected until unprotected
+    pub fn create_region_for_quota(&self, quota_id: u64) -> Result<u32, Error> {
+
+        self.quotas.ensure_region_available(quota_id)?;
+
+        let region_id = self.create_region()?;
+
+        if let Err(error) = self.assign_region_quota(region_id, quota_id) {
+            self.unprotect_region(region_id)?;
+            self.recycle_region(region_id)?;
+            return Err(error);
         }
+
+        Ok(region_id)
+
     }
 
 }
 
-/// Isolate slot managements
+/// Isolate region watermark management
 impl Isolate {
 
-    /// Gain a slot with prepared prototype
-    pub fn gain_slot(&self, region_id: u32, primitive_type: PrimitiveType, prototype: Value, layout_token: &ReentrantToken) -> Result<Value, Error> {
+    /// Set the listener notified when the overall occupancy of local regions
+    /// crosses the watermarks
+    pub fn set_region_watermark_listener(&self, listener: Arc<dyn RegionWatermarkListener>) {
+        self.region_watermarks.set_listener(Some(listener));
+    }
 
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => {
-                let id = region.gain_slot(primitive_type)?;
-                let (removed_values, removed_symbols, added_values, added_symbols) = region.overwrite_own_property(id, self.prototype_symbol, prototype)?;
-                for value in added_values {
-                    self.add_value_reference(id, value, layout_token)?;
-                }
-                for symbol in added_symbols {
-                    self.add_symbol_reference(symbol)?;
-                }
-                for value in removed_values {
-                    self.remove_value_reference(id, value, layout_token)?;
-                }
-                for symbol in removed_symbols {
-                    self.remove_symbol_reference(symbol)?;
-                }
-                self.mark_as_white(id)?;
-                self.barrier.borrow().as_ref().map(|barrier| barrier.postgain_value(id));
-                Ok(id)
-            },
-            None => Err(Error::new(FatalError, "Region not found"))
-        }
+    pub fn clear_region_watermark_listener(&self) {
+        self.region_watermarks.set_listener(None);
+    }
 
+    /// Set the watermarks as ratios of occupied slots to the capacity of
+    /// local regions, by default the low watermark is 0.6 and the high
+    /// watermark is 0.8
+    pub fn set_region_watermarks(&self, low: f32, high: f32) -> Result<(), Error> {
+        self.region_watermarks.set_watermarks(low, high)
     }
 
-    /// Recycle a slot
-    pub fn recycle_slot(&self, slot: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+    pub fn get_region_watermarks(&self) -> (f32, f32) {
+        (self.region_watermarks.get_low_watermark(), self.region_watermarks.get_high_watermark())
+    }
 
-        let region_id = slot.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
+    /// Get the ratio of occupied slots to the capacity of local regions
+    pub fn get_region_occupancy(&self) -> f32 {
+        self.region_watermarks.get_occupancy()
+    }
 
-        {
-            let _guard = self.roots_rw_lock.lock_read();
-            if self.roots.borrow().get(&slot).is_some() {
-                return Err(Error::new(FatalError, "Root exists for slot to recycle"));
-            }
-        }
+    /// Track changes of occupied slots in a local region made by an operation
+    fn track_region_occupancy<T, F>(&self, region: &Arc<Region>, operation: F) -> Result<T, Error> where F: FnOnce() -> Result<T, Error> {
 
-        match region {
-            Some(region) => region.recycle_slot(slot, true, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+        if region.get_id() >= SHARED_REGION_ID_BASE {
+            return operation();
         }
 
+        let occupied = region.get_occupied_count() as isize;
+
+        let result = operation();
+
+        let occupied_delta = region.get_occupied_count() as isize - occupied;
+        self.region_watermarks.update(occupied_delta, 0);
+        self.quotas.update(region.get_id(), occupied_delta);
+
+        result
+
     }
 
-    /// Move slot among regions
-    pub fn move_slot(&self, from: Value, to_region_id: u32, context: &Box<dyn Context>) -> Result<Value, Error> {
+}
 
-        let _guard = context.get_slot_layout_token().lock_write();
+/// Isolate cold region management
+impl Isolate {
 
-        let from_region_id = from.get_region_id()?;
-        let from_region = {
-            let _guard = self.region_rw_lock.lock_read();
-            let regions = self.regions.borrow();
-            let region = regions.get(from_region_id as usize);
-            if region.is_none() {
-                return Err(Error::new(FatalError, "Region of slot not found"));
-            }
-            region.unwrap().clone()
-        };
+    /// Set the storage for regions paged out, regions are kept as byte
+    /// buffers in memory if no storage set
+    pub fn set_cold_region_storage(&self, storage: Arc<dyn ColdRegionStorage>) -> Result<(), Error> {
 
-        let (snapshot, in_nursery, reference_map, removed_values, removed_symbols) = from_region.freeze_slot(from)?;
+        let _guard = self.region_rw_lock.lock_write();
 
-        let to_region = {
-            let _guard = self.region_rw_lock.lock_read();
-            let regions = self.regions.borrow();
-            let region = regions.get(to_region_id as usize);
-            if region.is_none() {
-                return Err(Error::new(FatalError, "Region to move slot into not found"));
+        if !self.regions.borrow().list_cold_region_ids().is_empty() {
+            return Err(Error::new(FatalError, "Regions already paged out"));
+        }
+
+        self.cold_region_storage.replace(Some(storage));
+
+        Ok(())
+
+    }
+
+    /// Serialize an unprotected region into the cold region storage and free
+    /// its slots, the region will be rehydrated once any of its values is
+    /// accessed
+    ///
+    /// Regions paged out are not listed in region IDs, the collector treats
+    /// their values as alive and marks values in other regions referenced by
+    /// them, which are recorded while paging out
+    pub fn page_out_region(&self, region_id: u32) -> Result<(), Error> {
+
+        let _guard = self.region_rw_lock.lock_write();
+
+        let region = match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.clone(),
+            None => {
+                return Err(Error::new(FatalError, "Region not found"));
             }
-            region.unwrap().clone()
         };
 
-        let (to, added_values, added_symbols) = to_region.restore_slot(from, snapshot, in_nursery, &reference_map)?;
-
-        for value in added_values {
-            context.add_value_reference(to, value)?;
-        }
-        for symbol in added_symbols {
-            context.add_symbol_reference(symbol)?;
+        if let Some(info) = self.region_infos.borrow().get(&region_id) {
+            if info.protected {
+                return Err(Error::new(FatalError, "Region protected"));
+            }
         }
 
-        let reference_map_is_none = reference_map.is_none();
-        from_region.redirect_slot(from, to, reference_map)?;
-        if reference_map_is_none {
-            from_region.recycle_slot(from, false, context)?;
+        // referenced by the table, the region index and here
+        if Arc::strong_count(&region) > 3 {
+            return Err(Error::new(FatalError, "Region in use"));
         }
 
-        self.refresh_root(from, to)?;
-        self.refresh_weak_root(from, to)?;
+        let data = region.write_cold()?;
+        let references = region.list_outer_referenced_values()?;
 
-        for value in removed_values {
-            context.remove_value_reference(from, value)?;
-        }
-        for symbol in removed_symbols {
-            context.remove_symbol_reference(symbol)?;
+        let storage = self.cold_region_storage.borrow_mut().get_or_insert_with(|| Arc::new(MemoryColdRegionStorage::new())).clone();
+
+        self.update_regions(|regions| regions.page_out_item(region_id as usize, references))?;
+        self.region_generation.fetch_add(1, Ordering::SeqCst);
+
+        if let Err(error) = storage.store(region_id, data) {
+            self.update_regions(|regions| regions.page_in_item(region_id as usize, region))?;
+            return Err(error);
         }
 
-        Ok(to)
+        Ok(())
 
     }
 
-    pub fn is_direct_value_alive(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+    /// Rehydrate a region paged out from the cold region storage
+    pub fn page_in_region(&self, region_id: u32) -> Result<(), Error> {
 
-        let _guard = context.get_slot_layout_token().lock_read();
+        let _guard = self.region_rw_lock.lock_write();
 
-        let region_id = value.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            let regions = self.regions.borrow();
-            let region = regions.get(region_id as usize);
-            if region.is_none() {
-                return Err(Error::new(FatalError, "Region of slot not found"));
+        if !self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(());
+        }
+
+        let storage = match self.cold_region_storage.borrow().as_ref() {
+            Some(storage) => storage.clone(),
+            None => {
+                return Err(Error::new(FatalError, "Cold region storage not found"));
             }
-            region.unwrap().clone()
         };
 
-        region.is_value_alive(value)
+        let data = storage.load(region_id)?;
+        let region = match Region::read_cold(region_id, &data) {
+            Ok(region) => region,
+            Err(error) => {
+                storage.store(region_id, data)?;
+                return Err(error);
+            }
+        };
 
-    }
+        self.update_regions(|regions| regions.page_in_item(region_id as usize, Arc::new(region)))
 
-    pub fn is_direct_value_occupied(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+    }
 
-        let _guard = context.get_slot_layout_token().lock_read();
+    pub fn is_region_cold(&self, region_id: u32) -> bool {
 
-        let region_id = value.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            let regions = self.regions.borrow();
-            let region = regions.get(region_id as usize);
-            if region.is_none() {
-                return Err(Error::new(FatalError, "Region of slot not found"));
-            }
-            region.unwrap().clone()
-        };
+        let _guard = self.region_rw_lock.lock_read();
 
-        region.is_value_occupied(value)
+        self.regions.borrow().is_cold(region_id as usize)
 
     }
 
-    /// Notify a value is dropped from the isolate
-    pub fn notify_slot_drop(&self, slot: Value) -> Result<(), Error> {
+    pub fn list_cold_region_ids(&self) -> Vec<u32> {
 
-        let _guard = self.roots_rw_lock.lock_read();
+        let _guard = self.region_rw_lock.lock_read();
 
-        let weak_roots = self.weak_roots.borrow_mut().remove(&slot);
-        match weak_roots {
-            Some(weak_roots) => {
-                for root in weak_roots.borrow().iter() {
-                    root.notify_drop()?;
-                }
-            },
-            None => {}
-        }
+        self.regions.borrow().list_cold_region_ids()
 
-        Ok(())
+    }
+
+    /// List values in other regions referenced by a region paged out, None
+    /// will be returned if the region is not paged out
+    pub fn list_cold_region_references(&self, region_id: u32) -> Option<Vec<Value>> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        self.regions.borrow().list_cold_region_references(region_id as usize)
 
     }
 
-}
+    /// Find a region loaded, regions paged out are not rehydrated
+    fn find_loaded_region(&self, region_id: u32) -> Option<Arc<Region>> {
 
-/// Isolate value prototype getter and setter
-impl Isolate {
+        if let Some(region) = self.region_index.read().get(region_id as usize) {
+            return Some(region.clone());
+        }
 
-    /// Get prototype of a value
-    pub fn get_prototype(&self, slot: Value, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        let _guard = self.region_rw_lock.lock_read();
 
-        let layout_token = context.get_slot_layout_token();
+        self.regions.borrow().get(region_id as usize).cloned()
 
-        let layout_guard = layout_token.lock_read();
+    }
 
-        let slot = self.resolve_real_value(slot, layout_token)?;
+    /// Find a region to resolve values in, the region will be rehydrated if
+    /// it has been paged out
+    fn find_region(&self, region_id: u32) -> Result<Option<Arc<Region>>, Error> {
 
-        match slot.get_primitive_type() {
-            Undefined => {
-                return Err(Error::new(VisitingUndefinedPrototype, "Undefined has no prototype"));
-            },
-            Null => {
-                return Err(Error::new(VisitingNullPrototype, "Null has no prototype"));
-            },
-            Boolean => {
-                return Pinned::new(context, self.boolean_prototype);
-            },
-            Integer => {
-                return Pinned::new(context, self.integer_prototype);
-            },
-            Float => {
-                return Pinned::new(context, self.float_prototype);
-            },
-            Symbol => {
-                return Pinned::new(context, self.symbol_prototype);
-            },
-            Text => {
-                return Pinned::new(context, self.text_prototype);
-            },
-            Tuple => {
-                return Pinned::new(context, self.tuple_prototype);
-            },
-            List => {
-                return Pinned::new(context, self.list_prototype);
-            },
-            Object => {}
+        if let Some(region) = self.region_index.read().get(region_id as usize) {
+            return Ok(Some(region.clone()));
         }
 
-        let region_id = slot.get_region_id()?;
-        let region = {
+        let cold = {
             let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
+            self.regions.borrow().is_cold(region_id as usize)
         };
+        if cold {
+            self.page_in_region(region_id)?;
+        }
 
-        match region {
-            Some(region) => region.get_own_property_with_layout_guard(slot, slot, self.prototype_symbol, None, context, layout_guard, false),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+        let _guard = self.region_rw_lock.lock_read();
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => Ok(Some(region.clone())),
+            None => Ok(None)
         }
 
     }
 
-    /// Set prototype of a value
-    pub fn set_prototype(&self, slot: Value, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
-
-        let layout_token = context.get_slot_layout_token();
+    /// Find a region through the region cache of the context first, cached
+    /// regions are dropped once any region is recycled, replaced or paged
+    /// out
+    fn find_context_region(&self, region_id: u32, context: &Box<dyn Context>) -> Result<Option<Arc<Region>>, Error> {
 
-        let layout_guard = layout_token.lock_read();
+        let region_cache = match context.get_region_cache() {
+            Some(region_cache) => region_cache,
+            None => {
+                return self.find_region(region_id);
+            }
+        };
 
-        let slot = self.resolve_real_value(slot, layout_token)?;
+        let stamp = (self as *const Isolate as usize, self.region_generation.load(Ordering::SeqCst));
+        if let Some(region) = region_cache.get(stamp, region_id) {
+            return Ok(Some(region));
+        }
 
-        match slot.get_primitive_type() {
-            Undefined => Err(Error::new(MutatingUndefinedPrototype, "Undefined has no prototype")),
-            Null => Err(Error::new(MutatingNullPrototype, "Null has no prototype")),
-            Boolean => Err(Error::new(MutatingSealedPrototype, "Prototype of boolean is immutable")),
-            Integer => Err(Error::new(MutatingSealedPrototype, "Prototype of integer is immutable")),
-            Float => Err(Error::new(MutatingSealedPrototype, "Prototype of float is immutable")),
-            Symbol => Err(Error::new(MutatingSealedPrototype, "Prototype of symbol is immutable")),
-            Text => Err(Error::new(MutatingSealedPrototype, "Prototype of text is immutable")),
-            Tuple => Err(Error::new(MutatingSealedPrototype, "Prototype of tuple is immutable")),
-            List => Err(Error::new(MutatingSealedPrototype, "Prototype of list is immutable")),
-            Object => {
-                let region_id = slot.get_region_id()?;
-                let region = {
-                    let _guard = self.region_rw_lock.lock_read();
-                    match self.regions.borrow().get(region_id as usize) {
-                        Some(region) => Some(region.clone()),
-                        None => None
-                    }
-                };
-                match region {
-                    Some(region) => region.set_prototype_with_layout_guard(slot, prototype, context, layout_guard, false),
-                    None => Err(Error::new(FatalError, "Region of slot not found"))
-                }
-            }
+        let region = self.find_region(region_id)?;
+        if let Some(region) = &region {
+            region_cache.insert(stamp, region_id, region.clone());
         }
 
+        Ok(region)
+
     }
 
-    pub fn set_prototype_ignore_slot_trap(&self, slot: Value, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+}
 
-        let layout_token = context.get_slot_layout_token();
+/// Isolate shared region management
+impl Isolate {
 
-        let _guard = layout_token.lock_read();
+    /// Create a new empty region with an ID reserved for sharing. The region
+    /// works as a normal region until sealed by `share_region`, but slots in
+    /// it are never swept
+    pub fn create_shared_region(&self) -> Result<u32, Error> {
 
-        let region_id = slot.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => region.set_prototype_ignore_slot_trap(slot, prototype, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+        let id = NEXT_SHARED_REGION_ID.fetch_add(1, Ordering::SeqCst);
+        if id < SHARED_REGION_ID_BASE {
+            return Err(Error::new(OutOfSpace, "No more shared region ID is available"));
         }
 
+        let _guard = self.region_rw_lock.lock_write();
+
+        self.update_regions(|regions| regions.insert_shared_region(Arc::new(Region::new(id))))?;
+
+        Ok(id)
+
     }
-}
 
-// Isolate value slot trap management
-impl Isolate {
+    /// Deep seal a region created by `create_shared_region`, the region
+    /// could be attached to other isolates after sealed. Values in the region
+    /// could only reference values in the same region or builtin prototypes,
+    /// and symbols other than the prototype symbol require a symbol registry
+    pub fn share_region(&self, region_id: u32, context: &Box<dyn Context>) -> Result<Arc<SharedRegion>, Error> {
 
-    pub fn has_slot_trap(&self, slot: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
- 
-        let layout_token = context.get_slot_layout_token();
+        if region_id < SHARED_REGION_ID_BASE {
+            return Err(Error::new(FatalError, "Region is not created for sharing"));
+        }
 
-        let _guard = layout_token.lock_read();
+        let region = {
+            let _guard = self.region_rw_lock.lock_read();
+            match self.regions.borrow().get(region_id as usize) {
+                Some(region) => region.clone(),
+                None => {
+                    return Err(Error::new(FatalError, "Region not found"));
+                }
+            }
+        };
 
-        let slot = self.resolve_real_value(slot, layout_token)?;
+        let builtins = self.list_buitins();
 
-        match slot.get_primitive_type() {
-            Undefined => Err(Error::new(MutatingUndefinedPrototype, "Undefined has no slot trap supports")),
-            Null => Err(Error::new(MutatingNullPrototype, "Null has no slot trap supports")),
-            Boolean => Ok(false),
-            Integer => Ok(false),
-            Float => Ok(false),
-            Symbol => Ok(false),
-            Text => Ok(false),
-            Tuple => Ok(false),
-            List => Ok(false),
-            Object => {
-                let region_id = slot.get_region_id()?;
-                let region = {
-                    let _guard = self.region_rw_lock.lock_read();
-                    match self.regions.borrow().get(region_id as usize) {
-                        Some(region) => Some(region.clone()),
-                        None => None
+        for value in region.list_alive_values()? {
+            let (values, symbols) = region.list_and_autorefresh_referenced_values(value, context)?;
+            for value in values {
+                if value.is_slotted() && (value.get_region_id()? != region_id) && !builtins.contains(&value) {
+                    return Err(Error::new(FatalError, "Value outside shared region referenced"));
+                }
+            }
+            if self.symbol_registry.is_none() {
+                for symbol in symbols {
+                    if symbol != self.prototype_symbol {
+                        return Err(Error::new(FatalError, "Symbol registry required to share properties"));
                     }
-                };
-                match region {
-                    Some(region) => region.has_slot_trap(slot),
-                    None => Err(Error::new(FatalError, "Region of slot not found"))
                 }
             }
         }
 
-    }
+        region.seal_as_shared()?;
 
-    /// Set slot trap of a value
-    pub fn set_slot_trap(&self, slot: Value, slot_trap: Arc<dyn SlotTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
- 
-        let layout_token = context.get_slot_layout_token();
+        Ok(Arc::new(SharedRegion::new(region, self.prototype_symbol, self.symbol_registry.clone())))
 
-        let _guard = layout_token.lock_read();
+    }
 
-        let slot = self.resolve_real_value(slot, layout_token)?;
+    /// Attach a shared region read-only, values in the region could be
+    /// accessed with the same values as in the isolate shared from
+    pub fn attach_shared_region(&self, shared_region: &Arc<SharedRegion>) -> Result<(), Error> {
 
-        match slot.get_primitive_type() {
-            Undefined => Err(Error::new(MutatingUndefinedProperty, "Undefined has no slot trap support")),
-            Null => Err(Error::new(MutatingNullProperty, "Null has no slot trap support")),
-            Boolean => Err(Error::new(MutatingSealedProperty, "Slot trap of boolean is immutable")),
-            Integer => Err(Error::new(MutatingSealedProperty, "Slot trap of integer is immutable")),
-            Float => Err(Error::new(MutatingSealedProperty, "Slot trap of float is immutable")),
-            Symbol => Err(Error::new(MutatingSealedProperty , "Slot trap of symbol is immutable")),
-            Text => Err(Error::new(MutatingSealedProperty, "Slot trap of text is immutable")),
-            Tuple => Err(Error::new(MutatingSealedProperty, "Slot trap of tuple is immutable")),
-            List => Err(Error::new(MutatingSealedProperty, "Slot trap of list is immutable")),
-            Object => {
-                let region_id = slot.get_region_id()?;
-                let region = {
-                    let _guard = self.region_rw_lock.lock_read();
-                    match self.regions.borrow().get(region_id as usize) {
-                        Some(region) => Some(region.clone()),
-                        None => None
-                    }
-                };
-                match region {
-                    Some(region) => region.set_slot_trap(slot, slot_trap, context),
-                    None => Err(Error::new(FatalError, "Region of slot not found"))
+        match (shared_region.get_symbol_registry(), &self.symbol_registry) {
+            (Some(symbol_registry), Some(self_symbol_registry)) => {
+                if !Arc::ptr_eq(symbol_registry, self_symbol_registry) {
+                    return Err(Error::new(FatalError, "Symbol registry not matched"));
+                }
+            },
+            (Some(_), None) => {
+                return Err(Error::new(FatalError, "Symbol registry not matched"));
+            },
+            (None, _) => {
+                if shared_region.get_prototype_symbol() != self.prototype_symbol {
+                    return Err(Error::new(FatalError, "Prototype symbol not matched"));
                 }
             }
         }
 
-    }
+        let _guard = self.region_rw_lock.lock_write();
 
-    /// Clear slot trap of a value
-    pub fn clear_slot_trap(&self, slot: Value, context: &Box<dyn Context>) -> Result<(), Error> {
- 
-        let layout_token = context.get_slot_layout_token();
+        self.update_regions(|regions| regions.insert_shared_region(shared_region.get_region().clone()))
 
-        let _guard = layout_token.lock_read();
+    }
 
-        let slot = self.resolve_real_value(slot, layout_token)?;
+    pub fn is_region_shared(&self, region_id: u32) -> Result<bool, Error> {
 
-        match slot.get_primitive_type() {
-            Undefined => Err(Error::new(MutatingUndefinedProperty, "Undefined has no slot trap support")),
-            Null => Err(Error::new(MutatingNullProperty, "Null has no slot trap support")),
-            Boolean => Err(Error::new(MutatingSealedProperty, "Slot trap of boolean is immutable")),
-            Integer => Err(Error::new(MutatingSealedProperty, "Slot trap of integer is immutable")),
-            Float => Err(Error::new(MutatingSealedProperty, "Slot trap of float is immutable")),
-            Symbol => Err(Error::new(MutatingSealedProperty , "Slot trap of symbol is immutable")),
-            Text => Err(Error::new(MutatingSealedProperty, "Slot trap of text is immutable")),
-            Tuple => Err(Error::new(MutatingSealedProperty, "Slot trap of tuple is immutable")),
-            List => Err(Error::new(MutatingSealedProperty, "Slot trap of list is immutable")),
-            Object => {
-                let region_id = slot.get_region_id()?;
-                let region = {
-                    let _guard = self.region_rw_lock.lock_read();
-                    match self.regions.borrow().get(region_id as usize) {
-                        Some(region) => Some(region.clone()),
-                        None => None
-                    }
-                };
-                match region {
-                    Some(region) => region.clear_slot_trap(slot, context),
-                    None => Err(Error::new(FatalError, "Region of slot not found"))
-                }
-            }
+        let _guard = self.region_rw_lock.lock_read();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => Ok(region.is_shared()),
+            None => Err(Error::new(FatalError, "Region not found"))
         }
 
     }
 
+    pub fn list_shared_region_ids(&self) -> Vec<u32> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        self.regions.borrow().list_shared_region_ids()
+
+    }
+
 }
 
-/// Isolate object internal slot management
+/// Isolate fork management
 impl Isolate {
 
-    pub fn list_internal_slot_ids(&self, subject: Value, context: &Box<dyn Context>) -> Result<Vec<u64>, Error> {
+    /// Fork the isolate, all local regions are shared copy-on-write with the
+    /// forked isolate, and the first mutation to a shared region in either
+    /// isolate copies the region
+    ///
+    /// Only roots of value symbols and interned texts are carried over, the
+    /// host should root values again in the forked isolate to keep them
+    ///
+    /// Weak roots, barriers and protected traps are not carried over, and
+    /// internal slots and property traps which could not be duplicated are
+    /// shared by both isolates
+    pub fn fork(&self) -> Result<Isolate, Error> {
 
-        let layout_token = context.get_slot_layout_token();
+        let layout_token = self.create_slot_layout_token();
 
-        let _guard = layout_token.lock_read();
+        let _layout_guard = layout_token.lock_write();
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+        let regions = {
+            let _guard = self.region_rw_lock.lock_write();
+            self.regions.borrow_mut().fork()?
+        };
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Ok(Vec::new()); },
-            Integer => { return Ok(Vec::new()); },
-            Float => { return Ok(Vec::new()); },
-            Symbol => { return Ok(Vec::new()); },
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+        // Both isolates keep colors of the regions shared copy-on-write by
+        // themselves, so that collections do not copy them
+        let forked_region_ids: Vec<u32> = regions.iterate_items().map(|(index, _)| index as u32).collect();
+        self.fork_colors.track_regions(&forked_region_ids);
+        let fork_colors = ForkColors::new();
+        fork_colors.track_regions(&forked_region_ids);
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        let symbol_id_generator = match &self.symbol_registry {
+            Some(symbol_registry) => symbol_registry.get_symbol_id_generator(),
+            None => Arc::new(self.symbol_id_generator.duplicate())
+        };
+
+        let (symbol_scopes, symbol_lut) = {
+            let _guard = self.symbol_rw_lock.lock_read();
+            let mut symbol_scopes = HashMap::new();
+            for (id, symbol_scope) in self.symbol_scopes.borrow().iter() {
+                symbol_scopes.insert(id.clone(), Arc::new(symbol_scope.duplicate(symbol_id_generator.clone())));
             }
+            let mut symbol_lut = HashMap::new();
+            for (symbol, symbol_scope) in self.symbol_lut.borrow().iter() {
+                if let Some(symbol_scope) = symbol_scopes.get(symbol_scope.get_id().as_str()) {
+                    symbol_lut.insert(*symbol, symbol_scope.clone());
+                }
+            }
+            (symbol_scopes, symbol_lut)
         };
-        match region {
-            Some(region) => region.list_internal_slot_ids(subject),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
 
-    }
+        // Only roots owned by the isolate itself are carried over, roots held
+        // by the host, handle blocks and persistents could not be released
+        // in the forked isolate
+        let mut roots: HashMap<Value, Arc<Roots>> = HashMap::new();
+        let mut add_owned_root = |value: Value| -> Result<Arc<Root>, Error> {
+            let root = roots.entry(value).or_insert_with(|| Arc::new(Roots::new(value))).get_any_root();
+            root.increase_reference()?;
+            Ok(root)
+        };
 
-    pub fn has_internal_slot(&self, subject: Value, index: u64, context: &Box<dyn Context>) -> Result<bool, Error> {
+        let value_symbol_roots = {
+            let _guard = self.symbol_rw_lock.lock_read();
+            let mut value_symbol_roots = HashMap::new();
+            for (symbol, root) in self.value_symbol_roots.borrow().iter() {
+                value_symbol_roots.insert(*symbol, add_owned_root(root.get_value())?);
+            }
+            value_symbol_roots
+        };
 
-        let layout_token = context.get_slot_layout_token();
+        let interned_texts = {
+            let _guard = self.interned_texts_rw_lock.lock_read();
+            let mut interned_texts = HashMap::new();
+            for (text, root) in self.interned_texts.borrow().iter() {
+                interned_texts.insert(text.clone(), add_owned_root(root.get_value())?);
+            }
+            interned_texts
+        };
 
-        let _guard = layout_token.lock_read();
+        let outlets = {
+            let _guard = self.outlets_rw_lock.lock_read();
+            self.outlets.borrow().clone()
+        };
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+        let (text_formatters, value_coercer) = {
+            let _guard = self.conversions_rw_lock.lock_read();
+            (self.text_formatters.borrow().clone(), self.value_coercer.borrow().clone())
+        };
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Ok(false); },
-            Integer => { return Ok(false); },
-            Float => { return Ok(false); },
-            Symbol => { return Ok(false); },
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+        Ok(Isolate {
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => region.has_internal_slot(subject, index),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+            barrier: RefCell::new(None),
 
-    }
+            region_rw_lock: RwLock::with_class(LockClass::RegionTable),
+            region_index: RcuCell::new(regions.create_index()),
+            region_generation: AtomicU64::new(0),
+            regions: RefCell::new(regions),
+            region_infos: RefCell::new(self.region_infos.borrow().clone()),
+            region_tags: RefCell::new(self.region_tags.borrow().clone()),
+            space_policies: RefCell::new(self.space_policies.borrow().clone()),
+            region_watermarks: self.region_watermarks.duplicate(),
+            quotas: self.quotas.duplicate(),
+            cold_region_storage: RefCell::new(self.cold_region_storage.borrow().clone()),
+            options: self.options.clone(),
+
+            base_color: Cell::new(self.base_color.get()),
+            fork_colors: fork_colors,
+            next_internal_slot_id: AtomicU64::new(self.next_internal_slot_id.load(Ordering::SeqCst)),
+
+            slot_layout_lock: Arc::new(ReentrantLock::with_class(LockClass::SlotLayout)),
+
+            symbol_rw_lock: RwLock::with_class(LockClass::Symbol),
+            symbol_id_generator: symbol_id_generator,
+            symbol_registry: self.symbol_registry.clone(),
+            custom_errors: self.custom_errors.clone(),
+            symbol_scopes: RefCell::new(symbol_scopes),
+            symbol_lut: RefCell::new(symbol_lut),
+            text_symbol_cache: TextSymbolCache::new(),
+            symbol_generation: AtomicU32::new(generate_symbol_generation()),
+            value_symbol_roots: RefCell::new(value_symbol_roots),
+
+            boolean_prototype: self.boolean_prototype,
+            integer_prototype: self.integer_prototype,
+            float_prototype: self.float_prototype,
+            symbol_prototype: self.symbol_prototype,
+            text_prototype: self.text_prototype,
+            list_prototype: self.list_prototype,
+            tuple_prototype: self.tuple_prototype,
+            bytes_prototype: self.bytes_prototype,
+            big_integer_prototype: self.big_integer_prototype,
+            decimal_prototype: self.decimal_prototype,
+            timestamp_prototype: self.timestamp_prototype,
+            object_prototype: self.object_prototype,
+
+            prototype_symbol: self.prototype_symbol,
+            well_known_symbols: self.well_known_symbols,
+
+            roots: RootTable::from_map(roots),
+            weak_id_generator: WeakIdGenerator::new(),
+            weak_roots: RootTable::new(),
+            fired_weak_roots: AtomicU64::new(0),
+            root_arenas: RootArenas::new(),
+            handle_blocks: HandleBlocks::new(),
+            retired_field_shortcuts_rw_lock: RwLock::new(),
+            retired_field_shortcuts: RefCell::new(Vec::new()),
+            persistent_rw_lock: RwLock::with_class(LockClass::Root),
+            persistent_roots: RefCell::new(HashMap::new()),
+            persistent_queue: Arc::new(PersistentQueue::new()),
+
+            object_groups_rw_lock: RwLock::new(),
+            next_object_group_id: AtomicU64::new(0),
+            object_groups: RefCell::new(HashMap::new()),
+            object_group_ids: RefCell::new(HashMap::new()),
+
+            prototype_chain_limit: AtomicU32::new(self.prototype_chain_limit.load(Ordering::SeqCst)),
+            recursion_limit: AtomicU32::new(self.recursion_limit.load(Ordering::SeqCst)),
 
-    /// Set a specified internal slot of a value
-    pub fn set_internal_slot(&self, subject: Value, index: u64, internal_slot: Arc<dyn InternalSlot>, context: &Box<dyn Context>) -> Result<(), Error> {
+            next_protected_id: AtomicU64::new(0),
+            protection_rw_lock: RwLock::new(),
+            protected_internal_slots: RefCell::new(HashMap::new()),
+            protected_slot_traps: RefCell::new(HashMap::new()),
+            protected_property_traps: RefCell::new(HashMap::new()),
 
-        let layout_token = context.get_slot_layout_token();
+            outlets_rw_lock: RwLock::new(),
+            next_outlet_id: AtomicU64::new(self.next_outlet_id.load(Ordering::SeqCst)),
+            outlets: RefCell::new(outlets),
 
-        let _guard = layout_token.lock_read();
+            conversions_rw_lock: RwLock::new(),
+            text_formatters: RefCell::new(text_formatters),
+            value_coercer: RefCell::new(value_coercer),
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+            interned_texts_rw_lock: RwLock::new(),
+            interned_texts: RefCell::new(interned_texts),
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+            metrics_counters: MetricsCounters::new(),
+            allocation_sampler: AllocationSampler::new(),
+            #[cfg(feature = "provenance")] provenances: self.provenances.duplicate(),
+            gc_listeners: GcListeners::new(),
+            gc_stress_mode: Cell::new(self.gc_stress_mode.get()),
+            #[cfg(feature = "fault-injection")] fault_injector_rw_lock: RwLock::new(),
+            #[cfg(feature = "fault-injection")] fault_injector: RefCell::new(None)
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => {
-                region.set_internal_slot(subject, index, internal_slot, context)
-            },
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+        })
 
     }
 
-    /// Clear a specified internal slot of a value
-    pub fn clear_internal_slot(&self, subject: Value, index: u64, context: &Box<dyn Context>) -> Result<(), Error> {
+    /// Check whether a local region is still shared with a forked isolate
+    pub fn is_region_copy_on_write(&self, region_id: u32) -> bool {
 
-        let layout_token = context.get_slot_layout_token();
+        let _guard = self.region_rw_lock.lock_read();
 
-        let _guard = layout_token.lock_read();
+        self.regions.borrow().is_copy_on_write(region_id as usize)
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+    }
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        };
+    /// Copy the region before mutations if it is still shared with a forked
+    /// isolate
+    fn ensure_region_writable(&self, region_id: u32) -> Result<(), Error> {
 
-        let region_id = subject.get_region_id()?;
-        let region = {
+        {
             let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+            if !self.regions.borrow().is_copy_on_write(region_id as usize) &&
+               !self.fork_colors.is_region_tracked(region_id) {
+                return Ok(());
             }
-        };
-        match region {
-            Some(region) => region.clear_internal_slot(subject, index, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
         }
 
-    }
-
-    /// Get specified internal slot from a value
-    pub fn get_internal_slot<'a>(&self, subject: Value, index: u64, context: &'a Box<dyn Context>) -> Result<Option<ProtectedInternalSlot::<'a>>, Error> {
-
-        let layout_token = context.get_slot_layout_token();
-
-        let _guard = layout_token.lock_read();
-
-        let subject = self.resolve_real_value(subject, layout_token)?;
-
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        };
+        let _guard = self.region_rw_lock.lock_write();
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        self.update_regions(|regions| regions.ensure_writable(region_id as usize))?;
+        self.region_generation.fetch_add(1, Ordering::SeqCst);
+
+        // Colors kept outside while the region was shared are written back,
+        // slots not marked are white
+        if let Some(colors) = self.fork_colors.untrack_region(region_id) {
+            if let Some(region) = self.regions.borrow().get(region_id as usize) {
+                let base = self.base_color.get();
+                for value in region.list_alive_values()? {
+                    region.mark_as_white(value, base)?;
+                    match colors.get(&value) {
+                        Some(ForkColor::Black) => { region.mark_as_black(value, base)?; },
+                        Some(ForkColor::Gray) => { region.mark_as_gray(value, base)?; },
+                        None => {}
+                    }
+                }
             }
-        };
-        match region {
-            Some(region) => region.get_internal_slot(subject, index, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
         }
 
+        Ok(())
+
     }
 
 }
 
+/// Isolate transaction management
 impl Isolate {
 
-    pub fn get_field_shortcuts(&self, subject: Value, context: &Box<dyn Context>) -> Result<Option<Arc<FieldShortcuts>>, Error> {
+    /// Begin a transaction recording mutations made through it, which could
+    /// be committed or rolled back as a whole
+    pub fn begin_transaction<'a>(&self, context: &'a Box<dyn Context>) -> Transaction<'a> {
+        Transaction::new(context)
+    }
 
-        let layout_token = context.get_slot_layout_token();
+}
 
-        let _layout_guard = layout_token.lock_read();
+/// Isolate garbage collection 
+impl Isolate {
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+    /// Resolve redirections generated from refragment of slots
+    pub fn resolve_real_value(&self, value: Value, layout_token: &ReentrantToken) -> Result<Value, Error> {
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => { return Ok(None); },
-            Integer => { return Ok(None); },
-            Float => { return Ok(None); },
-            Symbol => { return Ok(None); },
-            Text => { return Ok(None); },
-            List => {},
-            Tuple => {},
-            Object => {} 
+        if !value.is_slotted() {
+            return Ok(value);
         }
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        let _guard = layout_token.lock_read();
+
+        // Regions paged out have no redirections, so they are not rehydrated
+        // to resolve their values
+        let mut slot = value;
+        loop {
+            match slot.get_region_id() {
+                Ok(region_id) => {
+                    let region = self.find_loaded_region(region_id);
+                    slot = match region {
+                        Some(region) => {
+                            let new_slot = region.resolve_redirection(slot)?;
+                            if new_slot == slot {
+                                return Ok(slot);
+                            }
+                            new_slot
+                        }
+                        None => {
+                            return Ok(slot);
+                        }
+                    }
+                },
+                Err(_) => {
+                    return Ok(slot);
+                }
             }
-        };
-        match region {
-            Some(region) => region.get_field_shortcuts(subject),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
         }
 
     }
 
-    pub fn has_field_shortcuts(&self, subject: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+    /// Add a listener of garbage collections, ID of the listener is returned
+    pub fn add_gc_listener(&self, listener: Arc<dyn GcListener>) -> u64 {
+        self.gc_listeners.add(listener)
+    }
 
-        let layout_token = context.get_slot_layout_token();
+    /// Remove a listener of garbage collections with its ID
+    pub fn remove_gc_listener(&self, id: u64) -> bool {
+        self.gc_listeners.remove(id)
+    }
 
-        let _layout_guard = layout_token.lock_read();
+    pub fn get_gc_listeners(&self) -> &GcListeners {
+        &self.gc_listeners
+    }
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+    pub fn get_gc_stress_mode(&self) -> GcStressMode {
+        self.gc_stress_mode.get()
+    }
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => { return Ok(false); },
-            Integer => { return Ok(false); },
-            Float => { return Ok(false); },
-            Symbol => { return Ok(false); },
-            Text => { return Ok(false); },
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+    /// Collect garbages on every slot gained through standard contexts, for
+    /// testing rooting and value references of embedders
+    pub fn set_gc_stress_mode(&self, mode: GcStressMode) {
+        self.gc_stress_mode.set(mode);
+    }
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => region.has_field_shortcuts(subject),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+    // /// Schedule a collection of younger generations
+    // fn schedule_collect_younger_generations(&self) {
 
-    }
+    // }
 
-    pub fn update_field_shortcuts(&self, subject: Value, field_shortcuts: Arc<FieldShortcuts>, context: &Box<dyn Context>) -> Result<(), Error> {
+    // /// Schedule a collection of all generations
+    // fn schedule_collect_all_generations(&self) {
 
-        let layout_token = context.get_slot_layout_token();
+    // }
 
-        let _layout_guard = layout_token.lock_read();
+}
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+/// Isolate root management
+impl Isolate {
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null has no properties")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean value is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer value is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float value is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol value is immutable")); },
-            Text => { return Err(Error::new(MutatingSealedProperty, "Text value is immutable")); },
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+    /// Add a value into roots
+    pub fn add_root(&self, value: Value, layout_token: &ReentrantToken) -> Result<Arc<Root>, Error> {
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => region.update_field_shortcuts(subject, field_shortcuts),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+        if !value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
         }
-    }
 
-    pub fn clear_field_shortcuts(&self, subject: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+        let _guard = layout_token.lock_read();
 
-        let layout_token = context.get_slot_layout_token();
+        let value = self.resolve_real_value(value, layout_token)?;
 
-        let _layout_guard = layout_token.lock_read();
+        let shard = self.roots.get_shard(value);
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+        let _guard_2 = shard.lock_write();
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null has no properties")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean value is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer value is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float value is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol value is immutable")); },
-            Text => { return Err(Error::new(MutatingSealedProperty, "Text value is immutable")); },
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+        let mut self_roots = shard.borrow_mut();
 
-        let region_id = subject.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
+        match self_roots.get(&value) {
+            Some(roots) => {
+                let root = roots.get_any_root();
+                root.increase_reference()?;
+                return Ok(root);
+            },
+            None => {}
         };
-        match region {
-            Some(region) => region.clear_field_shortcuts(subject),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
-    }
+        
+        let roots = Arc::new(Roots::new(value));
 
-}
+        self_roots.insert(value, roots.clone());
 
-/// Isolate object own property management
-impl Isolate {
+        let root = roots.get_any_root();
+        root.increase_reference()?;
 
-    /// Get own property of a value for a symbol
-    pub fn get_own_property(&self, id: Value, subject: Value, symbol: Symbol, field_token: Option<&FieldToken>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
-        
-        let layout_token = context.get_slot_layout_token();
+        self.move_value_out_from_nursery(value, layout_token)?;
 
-        let layout_guard = layout_token.lock_read();
+        Ok(root)
 
-        let id = self.resolve_real_value(id, layout_token)?;
+    }
 
-        match id.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => { return Pinned::new(context, Value::make_undefined()); },
-            Integer => { return Pinned::new(context, Value::make_undefined()); },
-            Float => { return Pinned::new(context, Value::make_undefined()); },
-            Symbol => { return Pinned::new(context, Value::make_undefined()); },
-            Text => { return Pinned::new(context, Value::make_undefined()); },
-            List => {},
-            Tuple => {},
-            Object => {} 
+    /// Add a value into roots with a dedicated root, which could be released
+    /// regardless of references by `release_root`
+    pub fn add_dedicated_root(&self, value: Value, layout_token: &ReentrantToken) -> Result<Arc<Root>, Error> {
+
+        if !value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
         }
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        let _guard = layout_token.lock_read();
+
+        let value = self.resolve_real_value(value, layout_token)?;
+
+        let shard = self.roots.get_shard(value);
+
+        let _guard_2 = shard.lock_write();
+
+        let roots = {
+            let mut self_roots = shard.borrow_mut();
+            match self_roots.get(&value) {
+                Some(roots) => roots.clone(),
+                None => {
+                    let roots = Arc::new(Roots::new(value));
+                    self_roots.insert(value, roots.clone());
+                    roots
+                }
             }
         };
-        match region {
-            Some(region) => region.get_own_property_with_layout_guard(id, subject, symbol, field_token, context, layout_guard, false),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+
+        let root = roots.add_dedicated_root();
+        root.increase_reference()?;
+
+        self.move_value_out_from_nursery(value, layout_token)?;
+
+        Ok(root)
 
     }
 
-    pub fn get_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<Pinned, Error> {
- 
-        let layout_token = context.get_slot_layout_token();
+    /// Release a root regardless of its references, removing the root after
+    /// released will be ignored
+    pub fn release_root(&self, root: &Arc<Root>) -> Result<(), Error> {
 
-        let _guard = layout_token.lock_read();
+        let shard = self.roots.get_shard(root.get_value());
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        let _guard = shard.lock_write();
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        if root.is_released() {
+            return Ok(());
+        }
+
+        root.release();
+
+        let value = root.get_value();
+
+        self.barrier.borrow().as_ref().map(|barrier| barrier.preremove_value_reference(value));
+
+        let mut self_roots = shard.borrow_mut();
+
+        // the root may be removed already while its references cleared
+        let alone = match self_roots.get(&value) {
+            None => {
+                return Ok(());
+            },
+            Some(roots) => {
+                roots.remove_released_roots();
+                roots.is_alone()
             }
         };
 
-        match region {
-            Some(region) => region.get_own_property_ignore_slot_trap(id, subject, symbol, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+        if alone {
+            self_roots.remove(&value);
         }
 
+        Ok(())
+
     }
 
-    /// Set own property of a value for a symbol
-    pub fn set_own_property(&self, id: Value, subject: Value, symbol: Symbol, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+    /// Remove a value from roots, removing a root without references left
+    /// is an error instead of unpinning values of other holders
+    pub fn remove_root(&self, root: &Arc<Root>) -> Result<(), Error> {
 
-        let layout_token = context.get_slot_layout_token();
+        #[cfg(feature = "std")]
+        if root.is_local() && self.remove_local_root(root)? {
+            return Ok(());
+        }
 
-        let layout_guard = layout_token.lock_read();
+        let shard = self.roots.get_shard(root.get_value());
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        let _guard = shard.lock_write();
 
-        match id.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
-            Text => { return Err(Error::new(MutatingSealedProperty, "Text is immutable")); },
-            List => {},
-            Tuple => {},
-            Object => {} 
+        if root.is_released() {
+            return Ok(());
         }
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        if root.is_alone() {
+            return Err(Error::new(FatalError, "Value already unpinned"));
+        }
+
+        root.decrease_reference()?;
+
+        let value = root.get_value();
+
+        self.barrier.borrow().as_ref().map(|barrier| barrier.preremove_value_reference(value));
+
+        let mut self_roots = shard.borrow_mut();
+
+        let alone = match self_roots.get(&value) {
+            None => {
+                return Err(Error::new(FatalError, "Root not found"));
+            },
+            Some(roots) => {
+                roots.remove_alone_roots();
+                roots.is_alone()
             }
         };
-        match region {
-            Some(region) => region.set_own_property_with_layout_guard(id, subject, symbol, value, context, layout_guard, false),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+
+        if alone {
+            self_roots.remove(&value);
         }
 
+        Ok(())
+
     }
 
-    /// Set own property of a value for a symbol
-    pub fn set_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+    /// Add a value into roots through the handle block of the current
+    /// thread, only the block is locked, and the root is flushed into the
+    /// shared roots at safepoints or once the block is full
+    #[cfg(feature = "std")]
+    pub fn add_local_root(&self, value: Value, layout_token: &ReentrantToken) -> Result<Arc<Root>, Error> {
 
-        let layout_token = context.get_slot_layout_token();
+        if !value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
 
         let _guard = layout_token.lock_read();
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        let value = self.resolve_real_value(value, layout_token)?;
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
-        match region {
-            Some(region) => region.set_own_property_ignore_slot_trap(id, subject, symbol, value, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+        let root = Arc::new(Root::new(value));
+        root.increase_reference()?;
+        root.set_local(true);
+
+        let block = self.handle_blocks.get_thread_block();
+        if block.push(root.clone()) >= HANDLE_BLOCK_CAPACITY {
+            self.flush_handle_block(&block, layout_token)?;
         }
 
+        Ok(root)
+
     }
 
-    /// Define own property of a value for a symbol
-    pub fn define_own_property(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
-        
-        let layout_token = context.get_slot_layout_token();
+    /// Add a value into roots, the same as `add_root` without the `std`
+    /// feature as there are no handle blocks of threads
+    #[cfg(not(feature = "std"))]
+    pub fn add_local_root(&self, value: Value, layout_token: &ReentrantToken) -> Result<Arc<Root>, Error> {
+        self.add_root(value, layout_token)
+    }
 
-        let layout_guard = layout_token.lock_read();
+    /// Remove a root still held by a handle block, `false` is returned if the
+    /// root has been flushed into the shared roots
+    #[cfg(feature = "std")]
+    fn remove_local_root(&self, root: &Arc<Root>) -> Result<bool, Error> {
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        for block in self.handle_blocks.list_blocks() {
 
-        match id.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
-            Text => { return Err(Error::new(MutatingSealedProperty, "Text is immutable")); },
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+            let _guard = block.lock_write();
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+            if !root.is_local() {
+                return Ok(false);
             }
-        };
-        match region {
-            Some(region) => region.define_own_property_with_layout_guard(id, subject, symbol, property_trap, context, layout_guard, false),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+
+            let mut roots = block.borrow_mut();
+
+            let index = match HandleBlock::position(&roots, root) {
+                Some(index) => index,
+                None => continue
+            };
+
+            if root.is_released() {
+                roots.remove(index);
+                return Ok(true);
+            }
+
+            if root.is_alone() {
+                return Err(Error::new(FatalError, "Value already unpinned"));
+            }
+
+            root.decrease_reference()?;
+
+            if root.is_alone() {
+                roots.remove(index);
+            }
+
+            return Ok(true);
+
+        }
+
+        Ok(false)
+
+    }
+
+    /// Flush roots held by handle blocks of all threads into the shared
+    /// roots, which is done at safepoints such as collections and slot
+    /// moves, blocks of exited threads are pruned after flushed, count of
+    /// roots flushed is returned
+    pub fn flush_handle_blocks(&self, layout_token: &ReentrantToken) -> Result<usize, Error> {
+
+        let mut count = 0;
+        for block in self.handle_blocks.list_blocks() {
+            count += self.flush_handle_block(&block, layout_token)?;
         }
 
+        self.handle_blocks.prune_blocks();
+
+        Ok(count)
+
     }
 
-    /// Define own property of a value for a symbol
-    pub fn define_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
-        
-        let layout_token = context.get_slot_layout_token();
+    fn flush_handle_block(&self, block: &HandleBlock, layout_token: &ReentrantToken) -> Result<usize, Error> {
 
         let _guard = layout_token.lock_read();
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        let _guard_2 = block.lock_write();
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        let roots: Vec<Arc<Root>> = block.borrow_mut().drain(..).collect();
+
+        let mut count = 0;
+        for root in roots {
+
+            root.set_local(false);
+
+            if root.is_released() || root.is_alone() {
+                continue;
             }
-        };
-        match region {
-            Some(region) => region.define_own_property_ignore_slot_trap(id, subject, symbol, property_trap, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+
+            let value = root.get_value();
+
+            {
+                let shard = self.roots.get_shard(value);
+                let _guard = shard.lock_write();
+                let mut self_roots = shard.borrow_mut();
+                match self_roots.get(&value) {
+                    Some(roots) => roots.push_root(root),
+                    None => {
+                        let roots = Arc::new(Roots::new(value));
+                        roots.push_root(root);
+                        self_roots.insert(value, roots);
+                    }
+                }
+            }
+
+            self.move_value_out_from_nursery(value, layout_token)?;
+
+            count += 1;
+
         }
 
+        Ok(count)
+
     }
 
-    /// Delete own property from a value for a symbol
-    pub fn delete_own_property(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<(), Error> {
- 
-        let layout_token = context.get_slot_layout_token();
+    /// Refresh root value
+    pub fn refresh_root(&self, old_value: Value, new_value: Value) -> Result<(), Error> {
 
-        let layout_guard = layout_token.lock_read();
+        if !old_value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        if !new_value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
 
-        match id.get_primitive_type() {
-            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
-            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
-            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
-            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
-            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
-            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
-            Text => { return Err(Error::new(MutatingSealedProperty, "Text is immutable")); },
-            List => {},
-            Tuple => {},
-            Object => {}
+        self.root_arenas.refresh_values(&[(old_value, new_value)]);
+
+        let _guards = self.roots.lock_write_pair(old_value, new_value);
+
+        self.refresh_root_without_lock(old_value, new_value)
+
+    }
+
+    /// Refresh root values of moved slots in a single pass, all shards of
+    /// roots are locked once
+    pub fn refresh_roots(&self, moves: &[(Value, Value)]) -> Result<(), Error> {
+
+        for (old_value, new_value) in moves.iter() {
+            if !old_value.is_slotted() || !new_value.is_slotted() {
+                return Err(Error::new(FatalError, "Only slot value could added into roots"));
+            }
         }
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        self.root_arenas.refresh_values(moves);
+
+        let _guards = self.roots.lock_write_all();
+
+        for (old_value, new_value) in moves.iter() {
+            self.refresh_root_without_lock(*old_value, *new_value)?;
+        }
+
+        Ok(())
+
+    }
+
+    fn refresh_root_without_lock(&self, old_value: Value, new_value: Value) -> Result<(), Error> {
+
+        let old_shard = self.roots.get_shard(old_value);
+        let new_shard = self.roots.get_shard(new_value);
+
+        let old_roots = match old_shard.borrow().get(&old_value) {
+            None => {
+                return Ok(());
+            },
+            Some(old_roots) => old_roots.clone()
+        };
+        let new_roots = new_shard.borrow().get(&new_value).cloned();
+
+        old_roots.refresh_value(old_value, new_value);
+
+        match new_roots {
+            None => { new_shard.borrow_mut().insert(new_value, old_roots); }
+            Some(new_roots) => { new_roots.merge_roots(old_roots)?; }
+        }
+
+        old_shard.borrow_mut().remove(&old_value);
+
+        Ok(())
+
+    }
+
+    /// Count pins of a value, which are references of all its roots
+    pub fn pin_count(&self, value: Value) -> usize {
+
+        let references = {
+            let shard = self.roots.get_shard(value);
+            let _guard = shard.lock_read();
+            match shard.borrow().get(&value) {
+                None => 0,
+                Some(roots) => roots.count_references()
             }
         };
-        match region {
-            Some(region) => region.delete_own_property_with_layout_guard(id, subject, symbol, context, layout_guard, false),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+
+        references + self.handle_blocks.count_references(value)
+
+    }
+
+    /// List values kept as roots, including values rooted by root arenas and
+    /// handle blocks
+    pub fn list_roots(&self) -> Vec<Value> {
+
+        let mut values = self.roots.list_values();
+        values.extend(self.root_arenas.list_values());
+        values.extend(self.handle_blocks.list_values());
+
+        values
+
+    }
+
+    /// Create an arena rooting a batch of short-lived values, all values
+    /// rooted by the arena are released once it drops
+    pub fn create_root_arena(&self) -> RootArena<'_> {
+
+        let (id, values) = self.root_arenas.register();
+
+        RootArena::new(self, id, values)
+
+    }
+
+    /// Release all values rooted by a root arena
+    pub fn release_root_arena(&self, id: u64) -> bool {
+        self.root_arenas.unregister(id)
+    }
+
+    /// Count root arenas alive
+    pub fn count_root_arenas(&self) -> usize {
+        self.root_arenas.count()
+    }
+
+    pub fn list_buitins(&self) -> Vec<Value> {
+        vec!(
+            self.boolean_prototype,
+            self.integer_prototype,
+            self.float_prototype,
+            self.text_prototype,
+            self.symbol_prototype,
+            self.list_prototype,
+            self.tuple_prototype,
+            self.bytes_prototype,
+            self.big_integer_prototype,
+            self.decimal_prototype,
+            self.timestamp_prototype,
+            self.object_prototype
+        )
+    }
+
+    pub fn list_values_in_nursery(&self) -> Vec<Value> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        let mut values = Vec::new();
+
+        for (_index, region) in self.regions.borrow().iterate_items() {
+            for value in region.list_values_in_nursery() {
+                values.push(value);
+            }
         }
 
+        values
+
     }
 
-    /// Delete own property from a value for a symbol
-    pub fn delete_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<(), Error> {
- 
-        let layout_token = context.get_slot_layout_token();
+    /// Add a value into weak roots with drop listener
+    pub fn add_weak_root(&self, value: Value, drop_listener: Option<Box<dyn DropListener>>, layout_token: &ReentrantToken) -> Result<Arc<WeakRoot>, Error> {
+
+        if !value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
 
         let _guard = layout_token.lock_read();
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        let value = self.resolve_real_value(value, layout_token)?;
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        let shard = self.weak_roots.get_shard(value);
+
+        let _guard_2 = shard.lock_write();
+
+        let mut self_roots = shard.borrow_mut();
+
+        if self_roots.get(&value).is_none() {
+            self_roots.insert(value, RefCell::new(HashSet::new()));
+        }
+
+        let weak_root = Arc::new(WeakRoot::new(&self.weak_id_generator, value, drop_listener));
+
+        self_roots.get(&value).unwrap().borrow_mut().insert(weak_root.clone());
+       
+        Ok(weak_root)
+
+    }
+
+    /// List weak roots not fired yet, ordered by their weak IDs
+    pub fn list_weak_roots(&self) -> Vec<Arc<WeakRoot>> {
+
+        let mut weak_roots = Vec::new();
+        for shard in self.weak_roots.iterate_shards() {
+            let _guard = shard.lock_read();
+            for value_weak_roots in shard.borrow().values() {
+                weak_roots.extend(value_weak_roots.borrow().iter().cloned());
             }
-        };
-        match region {
-            Some(region) => region.delete_own_property_ignore_slot_trap(id, subject, symbol, context),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
         }
 
+        weak_roots.sort_by_key(|weak_root| weak_root.get_weak_id());
+
+        weak_roots
+
     }
 
-    /// Check whether an own property of a value for a symbol exists
-    pub fn has_own_property(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
+    /// Remove a value from weak roots
+    pub fn remove_weak_root(&self, root: &Arc<WeakRoot>) -> Result<(), Error> {
 
-        let layout_token = context.get_slot_layout_token();
+        let value = root.get_value();
+        if value.is_none() {
+            return Ok(());
+        }
 
-        let layout_guard = layout_token.lock_read();
+        let value = value.unwrap();
 
-        let id = self.resolve_real_value(id, layout_token)?;
+        let shard = self.weak_roots.get_shard(value);
 
-        match id.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => { return Ok(false); },
-            Integer => { return Ok(false); },
-            Float => { return Ok(false); },
-            Text => { return Ok(false); },
-            Symbol => { return Ok(false); },
-            List => {},
-            Tuple => {},
-            Object => {}
+        let _guard = shard.lock_write();
+
+        let mut self_roots = shard.borrow_mut();
+
+        if self_roots.get(&value).is_none() {
+            return Err(Error::new(FatalError, "Weak root not found"));
         }
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
+        let drop = {
+            let mut weak_roots = self_roots.get(&value).unwrap().borrow_mut();
+            if !weak_roots.remove(root) {
+                return Err(Error::new(FatalError, "Weak root not found"));
             }
+            weak_roots.is_empty()
         };
-        match region {
-            Some(region) => region.has_own_property_with_layout_guard(id, subject, symbol, context, layout_guard),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
+
+        if drop {
+            self_roots.remove(&value);
         }
 
-    }
+        Ok(())
+
+    }
+
+    /// Refresh weak root value
+    pub fn refresh_weak_root(&self, old_value: Value, new_value: Value) -> Result<(), Error> {
+
+        if !old_value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
+
+        if !new_value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
+
+        let _guards = self.weak_roots.lock_write_pair(old_value, new_value);
+
+        self.refresh_weak_root_without_lock(old_value, new_value);
+
+        Ok(())
+
+    }
+
+    /// Refresh weak root values of moved slots in a single pass, all shards
+    /// of weak roots are locked once
+    pub fn refresh_weak_roots(&self, moves: &[(Value, Value)]) -> Result<(), Error> {
+
+        for (old_value, new_value) in moves.iter() {
+            if !old_value.is_slotted() || !new_value.is_slotted() {
+                return Err(Error::new(FatalError, "Only slot value could added into roots"));
+            }
+        }
+
+        let _guards = self.weak_roots.lock_write_all();
+
+        for (old_value, new_value) in moves.iter() {
+            self.refresh_weak_root_without_lock(*old_value, *new_value);
+        }
+
+        Ok(())
+
+    }
+
+    fn refresh_weak_root_without_lock(&self, old_value: Value, new_value: Value) {
+
+        let old_roots = match self.weak_roots.get_shard(old_value).borrow_mut().remove(&old_value) {
+            None => {
+                return;
+            },
+            Some(old_roots) => old_roots.into_inner()
+        };
+
+        for value in old_roots.iter() {
+            value.refresh_value(old_value, new_value);
+        }
+
+        let mut new_shard = self.weak_roots.get_shard(new_value).borrow_mut();
+        match new_shard.get(&new_value) {
+            None => { new_shard.insert(new_value, RefCell::new(old_roots)); },
+            Some(new_roots) => { new_roots.borrow_mut().extend(old_roots); }
+        }
+
+    }
+
+}
+
+/// Isolate persistent management, persistents could be dropped from other
+/// threads, and their roots are removed once the owner thread drains them
+impl Isolate {
+
+    /// Add a persistent rooting the value with a dedicated root, dropped
+    /// persistents are drained before
+    pub fn add_persistent(&self, value: Value, layout_token: &ReentrantToken) -> Result<u64, Error> {
+
+        self.drain_persistents()?;
+
+        let root = self.add_dedicated_root(value, layout_token)?;
+
+        let id = self.persistent_queue.generate_id();
+
+        let _guard = self.persistent_rw_lock.lock_write();
+
+        self.persistent_roots.borrow_mut().insert(id, root);
+
+        Ok(id)
+
+    }
+
+    /// Get the current value of a persistent, refreshed if its slot moved
+    pub fn get_persistent_value(&self, id: u64) -> Result<Value, Error> {
+
+        let _guard = self.persistent_rw_lock.lock_read();
+
+        match self.persistent_roots.borrow().get(&id) {
+            Some(root) => Ok(root.get_value()),
+            None => Err(Error::new(FatalError, "Persistent not found"))
+        }
+
+    }
+
+    pub fn get_persistent_queue(&self) -> &Arc<PersistentQueue> {
+        &self.persistent_queue
+    }
+
+    /// Remove roots of persistents dropped, which should be called by the
+    /// owner thread of the isolate, count of roots removed is returned
+    pub fn drain_persistents(&self) -> Result<usize, Error> {
+
+        let dropped_ids = self.persistent_queue.drain_dropped_ids();
+        if dropped_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let roots: Vec<Arc<Root>> = {
+            let _guard = self.persistent_rw_lock.lock_write();
+            let mut persistent_roots = self.persistent_roots.borrow_mut();
+            dropped_ids.iter().filter_map(|id| persistent_roots.remove(id)).collect()
+        };
+
+        for root in roots.iter() {
+            self.remove_root(root)?;
+        }
+
+        Ok(roots.len())
+
+    }
+
+    /// Count persistents alive, including the dropped ones not drained yet
+    pub fn count_persistents(&self) -> usize {
+
+        let _guard = self.persistent_rw_lock.lock_read();
+
+        self.persistent_roots.borrow().len()
+
+    }
+
+}
+
+/// Isolate root diagnostics, only available with the `std` feature
+#[cfg(feature = "std")]
+impl Isolate {
+
+    /// Write every strong root with its references, value type and age, and
+    /// every weak root with whether it has a drop listener, creation
+    /// backtraces of strong roots are written with the `backtrace` feature
+    pub fn dump_roots(&self, output: &mut dyn Write, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        self.flush_handle_blocks(context.get_slot_layout_token())?;
+
+        let mut roots = Vec::new();
+        for shard in self.roots.iterate_shards() {
+            let _guard = shard.lock_read();
+            for (value, value_roots) in shard.borrow().iter() {
+                roots.push((*value, value_roots.list_roots()));
+            }
+        }
+
+        let mut weak_roots = Vec::new();
+        for shard in self.weak_roots.iterate_shards() {
+            let _guard = shard.lock_read();
+            for (value, value_weak_roots) in shard.borrow().iter() {
+                weak_roots.push((*value, value_weak_roots.borrow().iter().cloned().collect::<Vec<_>>()));
+            }
+        }
+
+        let options = DescribeOptions::new().max_depth(1).max_items(4);
+
+        let mut lines = Vec::new();
+
+        lines.push(format!("roots: {}", roots.len()));
+        for (value, value_roots) in roots.iter() {
+            lines.push(format!("  {:?} {}", value.get_primitive_type(), self.describe_value(*value, context, &options)));
+            for root in value_roots.iter() {
+                if root.is_released() {
+                    continue;
+                }
+                lines.push(format!("    references: {}, age: {:?}", root.get_references(), root.get_age()));
+                #[cfg(feature = "backtrace")]
+                lines.push(format!("    created at:\n{}", root.get_backtrace()));
+            }
+        }
+
+        lines.push(format!("weak roots: {}", weak_roots.len()));
+        for (value, value_weak_roots) in weak_roots.iter() {
+            lines.push(format!("  {:?} {}", value.get_primitive_type(), self.describe_value(*value, context, &options)));
+            for weak_root in value_weak_roots.iter() {
+                lines.push(format!("    weak id: {}, drop listener: {}", weak_root.get_weak_id(), weak_root.has_drop_listener()));
+            }
+        }
+
+        for line in lines.iter() {
+            if writeln!(output, "{}", line).is_err() {
+                return Err(Error::new(FatalError, "Failed to write roots"));
+            }
+        }
+
+        Ok(())
+
+    }
+
+    /// Find roots still referenced after living longer than the threshold,
+    /// which are likely leaked by the embedder
+    pub fn find_leaked_roots(&self, age_threshold: Duration) -> Vec<Arc<Root>> {
+
+        let mut leaked_roots = Vec::new();
+        for shard in self.roots.iterate_shards() {
+            let _guard = shard.lock_read();
+            for value_roots in shard.borrow().values() {
+                for root in value_roots.list_roots() {
+                    if !root.is_released() && !root.is_alone() && root.get_age() >= age_threshold {
+                        leaked_roots.push(root);
+                    }
+                }
+            }
+        }
+
+        leaked_roots
+
+    }
+
+}
+
+impl Isolate {
+
+    pub fn flip_base_color(&self) -> u8 {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        self.fork_colors.clear_colors();
+
+        if self.base_color.get() == BASE_WHITE {
+            self.base_color.set(BASE_BLACK);
+            BASE_BLACK
+        } else {
+            self.base_color.set(BASE_WHITE);
+            BASE_WHITE
+        }
+
+    }
+
+    pub fn get_base_color(&self) -> u8 {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        self.base_color.get()
+
+    }
+
+    pub fn mark_as_white(&self, value: Value) -> Result<(), Error> {
+
+        if !value.is_slotted() {
+            return Ok(())
+        }
+
+        let region_id = value.get_region_id()?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        // Regions paged out are alive as a whole
+        if self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(());
+        }
+
+        if self.fork_colors.is_region_tracked(region_id) {
+            self.fork_colors.mark_as_white(value);
+            return Ok(());
+        }
+
+        let base = self.base_color.get();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.mark_as_white(value, base),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+    pub fn mark_as_black(&self, value: Value) -> Result<(), Error> {
+
+        if !value.is_slotted() {
+            return Ok(())
+        }
+
+        let region_id = value.get_region_id()?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        if self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(());
+        }
+
+        if self.fork_colors.is_region_tracked(region_id) {
+            self.fork_colors.mark_as_black(value);
+            return Ok(());
+        }
+
+        let base = self.base_color.get();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.mark_as_black(value, base),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+ 
+    }
+
+    pub fn mark_as_gray(&self, value: Value) -> Result<bool, Error> {
+
+        if !value.is_slotted() {
+            return Ok(false);
+        }
+
+        let region_id = value.get_region_id()?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        if self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(false);
+        }
+
+        if self.fork_colors.is_region_tracked(region_id) {
+            return Ok(self.fork_colors.mark_as_gray(value));
+        }
+
+        let base = self.base_color.get();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.mark_as_gray(value, base),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+    pub fn is_white(&self, value: Value) -> Result<bool, Error> {
+
+        let region_id = value.get_region_id()?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        if self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(false);
+        }
+
+        if self.fork_colors.is_region_tracked(region_id) {
+            return Ok(self.fork_colors.get_color(value).is_none());
+        }
+
+        let base = self.base_color.get();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.is_white(value, base),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+    pub fn is_black(&self, value: Value) -> Result<bool, Error> {
+
+        let region_id = value.get_region_id()?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        if self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(true);
+        }
+
+        if self.fork_colors.is_region_tracked(region_id) {
+            return Ok(self.fork_colors.get_color(value) == Some(ForkColor::Black));
+        }
+
+        let base = self.base_color.get();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.is_black(value, base),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+    pub fn is_gray(&self, value: Value) -> Result<bool, Error> {
+
+        let region_id = value.get_region_id()?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        if self.regions.borrow().is_cold(region_id as usize) {
+            return Ok(false);
+        }
+
+        if self.fork_colors.is_region_tracked(region_id) {
+            return Ok(self.fork_colors.get_color(value) == Some(ForkColor::Gray));
+        }
+
+        let base = self.base_color.get();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.is_gray(value, base),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+    pub fn list_and_autorefresh_referenced_values(&self, value: Value, context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Symbol>), Error> {
+
+        let region_id = value.get_region_id()?;
+
+        // References in regions shared copy-on-write are resolved without
+        // refreshing them, so that marking does not copy the regions
+        if self.is_region_copy_on_write(region_id) {
+            let (values, symbols) = {
+                let _guard = self.region_rw_lock.lock_read();
+                match self.regions.borrow().get(region_id as usize) {
+                    Some(region) => region.list_referenced_values_without_autorefresh(value)?,
+                    None => {
+                        return Err(Error::new(FatalError, "Region not found"));
+                    }
+                }
+            };
+            let mut real_values = Vec::with_capacity(values.len());
+            for value in values {
+                real_values.push(context.resolve_real_value(value)?);
+            }
+            return Ok((real_values, symbols));
+        }
+
+        self.ensure_region_writable(region_id)?;
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        match self.regions.borrow().get(region_id as usize) {
+            Some(region) => region.list_and_autorefresh_referenced_values(value, context),
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+}
+
+/// Isolate object group management, values in a group live or die together
+/// as far as the collector is concerned
+impl Isolate {
+
+    /// Declare values to be retained together, groups sharing values with
+    /// the new one are merged into it, ID of the group is returned
+    pub fn add_object_group(&self, values: &[Value]) -> Result<u64, Error> {
+
+        for value in values.iter() {
+            if !value.is_slotted() {
+                return Err(Error::new(FatalError, "Only slot value could added into object groups"));
+            }
+        }
+
+        let _guard = self.object_groups_rw_lock.lock_write();
+
+        let mut object_groups = self.object_groups.borrow_mut();
+        let mut object_group_ids = self.object_group_ids.borrow_mut();
+
+        let id = self.next_object_group_id.fetch_add(1, Ordering::SeqCst);
+
+        let mut members: Vec<Value> = Vec::new();
+        for value in values.iter() {
+            let old_id = object_group_ids.get(value).copied();
+            match old_id.and_then(|old_id| object_groups.remove(&old_id)) {
+                Some(old_members) => {
+                    for member in old_members {
+                        if !members.contains(&member) {
+                            members.push(member);
+                        }
+                    }
+                },
+                None => {
+                    if !members.contains(value) {
+                        members.push(*value);
+                    }
+                }
+            }
+        }
+
+        for member in members.iter() {
+            object_group_ids.insert(*member, id);
+        }
+
+        object_groups.insert(id, members);
+
+        Ok(id)
+
+    }
+
+    /// Remove an object group, values in it are no longer retained together
+    pub fn remove_object_group(&self, id: u64) -> bool {
+
+        let _guard = self.object_groups_rw_lock.lock_write();
+
+        match self.object_groups.borrow_mut().remove(&id) {
+            Some(members) => {
+                let mut object_group_ids = self.object_group_ids.borrow_mut();
+                for member in members.iter() {
+                    object_group_ids.remove(member);
+                }
+                true
+            },
+            None => false
+        }
+
+    }
+
+    /// Get ID of the object group containing the value
+    pub fn get_object_group_id(&self, value: Value) -> Option<u64> {
+
+        let _guard = self.object_groups_rw_lock.lock_read();
+
+        self.object_group_ids.borrow().get(&value).copied()
+
+    }
+
+    /// List values in the same object group with the value, the value
+    /// itself is included, and nothing will be listed if it is not grouped
+    pub fn list_object_group_members(&self, value: Value) -> Vec<Value> {
+
+        let _guard = self.object_groups_rw_lock.lock_read();
+
+        match self.object_group_ids.borrow().get(&value) {
+            Some(id) => match self.object_groups.borrow().get(id) {
+                Some(members) => members.clone(),
+                None => Vec::new()
+            },
+            None => Vec::new()
+        }
+
+    }
+
+    /// Count object groups declared
+    pub fn count_object_groups(&self) -> usize {
+
+        let _guard = self.object_groups_rw_lock.lock_read();
+
+        self.object_groups.borrow().len()
+
+    }
+
+    /// Refresh values in object groups after slots moved
+    pub fn refresh_object_groups(&self, moves: &[(Value, Value)]) {
+
+        let _guard = self.object_groups_rw_lock.lock_write();
+
+        let mut object_groups = self.object_groups.borrow_mut();
+        let mut object_group_ids = self.object_group_ids.borrow_mut();
+
+        for (old_value, new_value) in moves.iter() {
+            let id = match object_group_ids.remove(old_value) {
+                Some(id) => id,
+                None => continue
+            };
+            if let Some(members) = object_groups.get_mut(&id) {
+                for member in members.iter_mut() {
+                    if *member == *old_value {
+                        *member = *new_value;
+                    }
+                }
+            }
+            object_group_ids.insert(*new_value, id);
+        }
+
+    }
+
+    /// Remove a dropped value from its object group, the group is removed
+    /// if no values left
+    fn remove_object_group_member(&self, value: Value) {
+
+        let _guard = self.object_groups_rw_lock.lock_write();
+
+        let id = match self.object_group_ids.borrow_mut().remove(&value) {
+            Some(id) => id,
+            None => { return; }
+        };
+
+        let mut object_groups = self.object_groups.borrow_mut();
+        let empty = match object_groups.get_mut(&id) {
+            Some(members) => {
+                members.retain(|member| *member != value);
+                members.is_empty()
+            },
+            None => false
+        };
+        if empty {
+            object_groups.remove(&id);
+        }
+
+    }
+
+}
+
+/// Isolate references managment
+impl Isolate {
+
+    /// Add a symbol reference record to keep it from garbage collection
+    pub fn add_symbol_reference(&self, symbol: Symbol) -> Result<(), Error> {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        match self.symbol_lut.borrow().get(&symbol) {
+            Some(symbol_scope) => {
+                symbol_scope.add_symbol_reference(symbol)
+            },
+            None => Err(Error::new(FatalError, "Symbol not found"))
+        }
+
+    }
+
+    /// Remove a symbol reference record to keep it from garbage collection
+    pub fn remove_symbol_reference(&self, symbol: Symbol) -> Result<(), Error> {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        match self.symbol_lut.borrow().get(&symbol) {
+            Some(symbol_scope) => {
+                symbol_scope.remove_symbol_reference(symbol)
+            },
+            None => Err(Error::new(FatalError, "Symbol not found"))
+        }
+
+    }
+
+    /// Move a value out from the nursery
+    pub fn move_value_out_from_nursery(&self, value: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+
+        if !value.is_slotted() {
+            return Ok(());
+        }
+
+        let _guard = layout_token.lock_read();
+
+        let region_id = value.get_region_id()?;
+
+        // Values already out of the nursery leave regions shared
+        // copy-on-write uncopied, so that the forked isolate could root them
+        if self.is_region_copy_on_write(region_id) {
+            if let Some(region) = self.find_region(region_id)? {
+                if !region.is_in_nursery(value) {
+                    return Ok(());
+                }
+            }
+        }
+
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_region(region_id)?;
+
+        let promoted = match region {
+            Some(region) => region.move_out_from_nursery(value)?,
+            None => {
+                return Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id));
+            }
+        };
+
+        if promoted {
+            self.gc_listeners.notify(|listener| listener.notify_value_promoted(value));
+        }
+
+        Ok(())
+    }
+
+    /// Add a reference relationship
+    pub fn add_value_reference(&self, from: Value, to: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+
+        if !from.is_slotted() {
+            return Ok(());
+        }
+
+        if to.is_symbol() {
+            return self.add_symbol_reference(to.extract_symbol(Symbol::new(0)));
+        }
+
+        if !to.is_slotted() {
+            return Ok(());
+        }
+
+        let _guard = layout_token.lock_read();
+
+        let to_region_id = to.get_region_id()?;
+        let to_region_slot = to.get_region_slot()?;
+        let from_region_id = from.get_region_id()?;
+        let from_region_slot = from.get_region_slot()?;
+        if (to_region_id == from_region_id) && (to_region_slot == from_region_slot) {
+            return Ok(());
+        }
+
+        self.ensure_region_writable(to_region_id)?;
+
+        let region = self.find_region(to_region_id)?;
+        match region {
+            Some(region) => region.add_reference(to, from)?,
+            None => {
+                return Err(Error::new(FatalError, "Region of slot not found"));
+            }
+        };
+
+        Ok(())
+
+    }
+
+    /// Remove a reference relationship
+    pub fn remove_value_reference(&self, from: Value, to: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+
+        if !from.is_slotted() {
+            return Ok(());
+        }
+
+        if to.is_symbol() {
+            return self.remove_symbol_reference(to.extract_symbol(Symbol::new(0)));
+        }
+
+        if !to.is_slotted() {
+            return Ok(());
+        }
+
+        let real_to = self.resolve_real_value(to, layout_token)?;
+
+        self.barrier.borrow().as_ref().map(|barrier| barrier.preremove_value_reference(real_to));
+
+        let _guard = layout_token.lock_read();
+
+        let to_region_id = to.get_region_id()?;
+        let to_region_slot = to.get_region_slot()?;
+        let from_region_id = from.get_region_id()?;
+        let from_region_slot = from.get_region_slot()?;
+        if (to_region_id == from_region_id) && (to_region_slot == from_region_slot) {
+            return Ok(());
+        }
+        self.ensure_region_writable(to_region_id)?;
+
+        let region = self.find_region(to_region_id)?;
+        match region {
+            Some(region) => {
+                let redirection = self.track_region_occupancy(&region, || {
+                    let (no_more_reference_map, to_redirection) = region.remove_reference(to, from)?;
+                    Ok(if no_more_reference_map { Some(to_redirection) } else { None })
+                })?;
+                if let Some(to_redirection) = redirection {
+                    self.remove_redirection_from(to, to_redirection)?;
+                }
+            },
+            None => {
+                return Err(Error::new(FatalError, "Region of slot not found"));
+            }
+        }
+
+        Ok(())
+
+    }
+
+    /// Add and remove references from a value in batch, references to slots
+    /// are grouped by regions, and each region is locked once
+    pub fn update_value_references(&self, from: Value, added: &[Value], removed: &[Value], layout_token: &ReentrantToken) -> Result<(), Error> {
+
+        if !from.is_slotted() {
+            return Ok(());
+        }
+
+        let mut region_ids = Vec::new();
+        let mut groups: HashMap<u32, (Vec<Value>, Vec<Value>)> = HashMap::new();
+
+        for (values, removing) in [(added, false), (removed, true)] {
+            for value in values.iter() {
+                if value.is_symbol() {
+                    let symbol = value.extract_symbol(Symbol::new(0));
+                    if removing {
+                        self.remove_symbol_reference(symbol)?;
+                    } else {
+                        self.add_symbol_reference(symbol)?;
+                    }
+                    continue;
+                }
+                if !value.is_slotted() {
+                    continue;
+                }
+                if removing {
+                    let real_value = self.resolve_real_value(*value, layout_token)?;
+                    self.barrier.borrow().as_ref().map(|barrier| barrier.preremove_value_reference(real_value));
+                }
+                if (value.get_region_id()? == from.get_region_id()?) && (value.get_region_slot()? == from.get_region_slot()?) {
+                    continue;
+                }
+                let region_id = value.get_region_id()?;
+                let group = groups.entry(region_id).or_insert_with(|| {
+                    region_ids.push(region_id);
+                    (Vec::new(), Vec::new())
+                });
+                if removing {
+                    group.1.push(*value);
+                } else {
+                    group.0.push(*value);
+                }
+            }
+        }
+
+        let _guard = layout_token.lock_read();
+
+        for region_id in region_ids {
+
+            let (added, removed) = groups.remove(&region_id).unwrap();
+
+            self.ensure_region_writable(region_id)?;
+
+            let region = match self.find_region(region_id)? {
+                Some(region) => region,
+                None => {
+                    return Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id));
+                }
+            };
+
+            let redirections = self.track_region_occupancy(&region, || region.update_references(from, &added, &removed))?;
+            for (to, to_redirection) in redirections {
+                self.remove_redirection_from(to, to_redirection)?;
+            }
+
+        }
+
+        Ok(())
+
+    }
+
+    /// Remove a moved slot from sources of its redirection, which are tracked
+    /// by the region of the redirection
+    fn remove_redirection_from(&self, from: Value, to: Value) -> Result<(), Error> {
+
+        // Slots swept are redirected to undefined
+        if !to.is_slotted() {
+            return Ok(());
+        }
+
+        match self.find_region(to.get_region_id()?)? {
+            Some(region) => {
+                region.remove_redirection_from(from, to)?;
+                Ok(())
+            },
+            None => Err(Error::new(FatalError, "Region of redirection not found"))
+        }
+
+    }
+
+    /// Update moved value reference relationship
+    pub fn refresh_value_reference(&self, from: Value, old_to: Value, new_to: Value, layout_token: &ReentrantToken) -> Result<(), Error> {
+
+        if (!from.is_slotted()) || (!old_to.is_slotted()) || (!new_to.is_slotted()) {
+            return Ok(());
+        }
+
+        if (from == old_to) || (from == new_to) || (old_to == new_to) {
+            return Ok(());
+        }
+
+        let _guard = layout_token.lock_read();
+
+        self.add_value_reference(from, new_to, layout_token)?;
+        self.remove_value_reference(from, old_to, layout_token)?;
+
+        Ok(())
+
+    }
+
+}
+
+/// Isolate symbols management
+impl Isolate {
+
+    /// Get the symbol registry shared with other isolates
+    pub fn get_symbol_registry(&self) -> Option<&Arc<SymbolRegistry>> {
+        self.symbol_registry.as_ref()
+    }
+
+    fn create_symbol_scope(&self, scope: &str) -> SymbolScope {
+        match &self.symbol_registry {
+            Some(symbol_registry) => SymbolScope::with_registry(symbol_registry.clone(), scope),
+            None => SymbolScope::new(self.symbol_id_generator.clone(), scope)
+        }
+    }
+
+    /// Get a symbol with specified scope and text, symbols resolved are
+    /// cached to be looked up without locks later
+    pub fn get_text_symbol(&self, scope: &str, text: &str) -> Symbol {
+
+        if let Some(symbol) = self.text_symbol_cache.get(scope, text) {
+            return symbol;
+        }
+
+        {
+            let _guard = self.symbol_rw_lock.lock_read();
+            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
+                let symbol = result.get_text_symbol(text);
+                if self.symbol_lut.borrow().get(&symbol).is_some() {
+                    self.text_symbol_cache.insert(scope, text, symbol);
+                    return symbol;
+                }
+            }
+        }
+
+        {
+            let guard = self.symbol_rw_lock.lock_upgradeable_read();
+            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
+                let symbol = result.get_text_symbol(text);
+                if self.symbol_lut.borrow().get(&symbol).is_none() {
+                    let _guard = guard.upgrade();
+                    self.symbol_lut.borrow_mut().insert(symbol, result.clone());
+                }
+                self.text_symbol_cache.insert(scope, text, symbol);
+                return symbol;
+            }
+            let _guard = guard.upgrade();
+            let symbol_scope = Arc::new(self.create_symbol_scope(scope));
+            let symbol = symbol_scope.get_text_symbol(text);
+            self.symbol_scopes.borrow_mut().insert(scope.to_owned(), symbol_scope.clone());
+            self.symbol_lut.borrow_mut().insert(symbol, symbol_scope);
+            self.text_symbol_cache.insert(scope, text, symbol);
+            symbol
+        }
+
+    }
+
+    /// Get the text symbol of a cache entry held by the embedder, the symbol
+    /// is only resolved by its scope and text if the entry is unresolved, or
+    /// resolved before symbols of the isolate recycled
+    pub fn get_text_symbol_cached(&self, entry: &SymbolCacheEntry) -> Symbol {
+
+        let generation = self.get_symbol_generation();
+        if let Some(symbol) = entry.get_symbol(generation) {
+            return symbol;
+        }
+
+        let symbol = self.get_text_symbol(entry.get_scope(), entry.get_text());
+        entry.set_symbol(symbol, generation);
+
+        symbol
+
+    }
+
+    /// Get the generation of symbols, which changes once symbols recycled
+    pub fn get_symbol_generation(&self) -> u32 {
+        self.symbol_generation.load(Ordering::SeqCst)
+    }
+
+    /// Get a symbol with specified scope and value, slotted values will be
+    /// kept in roots until the symbol recycled
+    pub fn get_value_symbol(&self, scope: &str, value: Value) -> Symbol {
+
+        if !value.is_slotted() {
+            return self.get_scoped_value_symbol(scope, value);
+        }
+
+        let layout_token = self.create_slot_layout_token();
+        let _guard = layout_token.lock_read();
+
+        let value = self.resolve_real_value(value, &layout_token).unwrap_or(value);
+
+        let symbol = self.get_scoped_value_symbol(scope, value);
+
+        {
+            let _guard = self.symbol_rw_lock.lock_read();
+            if self.value_symbol_roots.borrow().get(&symbol).is_some() {
+                return symbol;
+            }
+        }
+
+        let root = match self.add_root(value, &layout_token) {
+            Ok(root) => root,
+            Err(_) => {
+                return symbol;
+            }
+        };
+
+        let duplicated = {
+            let _guard = self.symbol_rw_lock.lock_write();
+            let mut value_symbol_roots = self.value_symbol_roots.borrow_mut();
+            if value_symbol_roots.get(&symbol).is_some() {
+                true
+            } else {
+                value_symbol_roots.insert(symbol, root.clone());
+                false
+            }
+        };
+
+        if duplicated {
+            let _ = self.remove_root(&root);
+        }
+
+        symbol
+
+    }
+
+    fn get_scoped_value_symbol(&self, scope: &str, value: Value) -> Symbol {
+
+        {
+            let _guard = self.symbol_rw_lock.lock_read();
+            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
+                let symbol = result.get_value_symbol(value);
+                if self.symbol_lut.borrow().get(&symbol).is_some() {
+                    return symbol;
+                }
+            }
+        }
+
+        {
+            let _guard = self.symbol_rw_lock.lock_write();
+            if let Some(result) = self.symbol_scopes.borrow().get(scope) {
+                let symbol = result.get_value_symbol(value);
+                self.symbol_lut.borrow_mut().insert(symbol, result.clone());
+                return symbol;
+            }
+            let symbol_scope = Arc::new(self.create_symbol_scope(scope));
+            let symbol = symbol_scope.get_value_symbol(value);
+            self.symbol_scopes.borrow_mut().insert(scope.to_owned(), symbol_scope.clone());
+            self.symbol_lut.borrow_mut().insert(symbol, symbol_scope);
+            symbol
+        }
+
+    }
+
+    /// Resolve symbol info from a symbol
+    pub fn resolve_symbol_info(&self, symbol: Symbol) -> Result<SymbolInfo, Error> {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        match self.symbol_lut.borrow().get(&symbol) {
+            Some(symbol_scope) => {
+                match symbol_scope.get_symbol_record(symbol) {
+                    Some(symbol_record) => Ok(SymbolInfo::new(symbol, &symbol_scope, symbol_record)),
+                    None => Err(Error::new(FatalError, "Symbol not found"))
+                }
+            },
+            None => Err(Error::new(FatalError, "Symbol not found"))
+        }
+
+    }
+
+    /// Recycle symbol
+    pub fn recycle_symbol(&self, symbol: Symbol) -> Result<(), Error> {
+
+        {
+            let _guard = self.symbol_rw_lock.lock_read();
+            match self.symbol_lut.borrow().get(&symbol) {
+                Some(symbol_scope) => {
+                    symbol_scope.recycle_symbol(symbol)?;
+                },
+                None => {
+                    return Err(Error::new(FatalError, "Symbol not found"));
+                }
+            }
+        }
+
+        let root = {
+            let _guard = self.symbol_rw_lock.lock_write();
+            self.text_symbol_cache.retain(|cached| cached != symbol);
+            self.symbol_generation.store(generate_symbol_generation(), Ordering::SeqCst);
+            self.value_symbol_roots.borrow_mut().remove(&symbol)
+        };
+        if let Some(root) = root {
+            self.remove_root(&root)?;
+        }
+
+        Ok(())
+
+    }
+
+    /// Keep the symbol from recycling by symbol sweeping
+    pub fn pin_symbol(&self, symbol: Symbol) -> Result<(), Error> {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        match self.symbol_lut.borrow().get(&symbol) {
+            Some(symbol_scope) => symbol_scope.pin_symbol(symbol),
+            None => Err(Error::new(FatalError, "Symbol not found"))
+        }
+
+    }
+
+    pub fn unpin_symbol(&self, symbol: Symbol) -> Result<(), Error> {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        match self.symbol_lut.borrow().get(&symbol) {
+            Some(symbol_scope) => symbol_scope.unpin_symbol(symbol),
+            None => Err(Error::new(FatalError, "Symbol not found"))
+        }
+
+    }
+
+    /// Set whether symbols of the scope could be recycled by symbol
+    /// sweeping, the scope will be created if not exists
+    pub fn set_symbol_scope_collectable(&self, scope: &str, collectable: bool) {
+
+        let _guard = self.symbol_rw_lock.lock_write();
+
+        let mut symbol_scopes = self.symbol_scopes.borrow_mut();
+        match symbol_scopes.get(scope) {
+            Some(symbol_scope) => symbol_scope.set_collectable(collectable),
+            None => {
+                let symbol_scope = Arc::new(self.create_symbol_scope(scope));
+                symbol_scope.set_collectable(collectable);
+                symbol_scopes.insert(scope.to_owned(), symbol_scope);
+            }
+        }
+
+    }
+
+    /// Recycle symbols not referenced in all collectable scopes, symbols in
+    /// nursery or pinned are kept. The count of symbols recycled will be
+    /// returned
+    pub fn sweep_symbols(&self) -> Result<usize, Error> {
+
+        let mut roots = Vec::new();
+
+        let mut count = 0;
+        {
+            let _guard = self.symbol_rw_lock.lock_write();
+
+            let mut symbol_lut = self.symbol_lut.borrow_mut();
+            let mut value_symbol_roots = self.value_symbol_roots.borrow_mut();
+
+            for symbol_scope in self.symbol_scopes.borrow().values() {
+                for symbol in symbol_scope.sweep_symbols() {
+                    symbol_lut.remove(&symbol);
+                    if let Some(root) = value_symbol_roots.remove(&symbol) {
+                        roots.push(root);
+                    }
+                    count += 1;
+                }
+            }
+
+            if count > 0 {
+                self.text_symbol_cache.retain(|symbol| symbol_lut.contains_key(&symbol));
+                self.symbol_generation.store(generate_symbol_generation(), Ordering::SeqCst);
+            }
+        }
+
+        for root in roots.iter() {
+            self.remove_root(root)?;
+        }
+
+        Ok(count)
+
+    }
+
+    /// Refresh value symbols after a slot moved
+    pub fn refresh_value_symbols(&self, old_value: Value, new_value: Value) {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        for symbol_scope in self.symbol_scopes.borrow().values() {
+            symbol_scope.refresh_value_symbol(old_value, new_value);
+        }
+
+    }
+
+    /// Refresh value symbols after slots moved in a single pass
+    pub fn refresh_values_symbols(&self, moves: &[(Value, Value)]) {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        for symbol_scope in self.symbol_scopes.borrow().values() {
+            for (old_value, new_value) in moves.iter() {
+                symbol_scope.refresh_value_symbol(*old_value, *new_value);
+            }
+        }
+
+    }
+
+}
+
+/// Internal slot and traps keeper
+impl Isolate {
+
+    pub fn protect_slot_trap(&self, slot_trap: &Arc<dyn SlotTrap>) -> Result<(u64, Arc<dyn SlotTrap>), Error> {
+        let protected_id = self.next_protected_id.fetch_add(1, Ordering::SeqCst);
+        let _guard = self.protection_rw_lock.lock_write();
+        self.protected_slot_traps.borrow_mut().insert(protected_id, slot_trap.clone());
+        Ok((protected_id, slot_trap.clone()))
+    }
+
+    pub fn protect_internal_slot(&self, internal_slot: &Arc<dyn InternalSlot>) -> Result<(u64, Arc<dyn InternalSlot>), Error> {
+        let protected_id = self.next_protected_id.fetch_add(1, Ordering::SeqCst);
+        let _guard = self.protection_rw_lock.lock_write();
+        self.protected_internal_slots.borrow_mut().insert(protected_id, internal_slot.clone());
+        Ok((protected_id, internal_slot.clone()))
+    }
+
+    pub fn protect_property_trap(&self, property_trap: &Arc<dyn PropertyTrap>) -> Result<(u64, Arc<dyn PropertyTrap>), Error> {
+        let protected_id = self.next_protected_id.fetch_add(1, Ordering::SeqCst);
+        let _guard = self.protection_rw_lock.lock_write();
+        self.protected_property_traps.borrow_mut().insert(protected_id, property_trap.clone());
+        Ok((protected_id, property_trap.clone()))
+    }
+
+    pub fn unprotect_slot_trap(&self, protected_id: u64) -> Result<(), Error> {
+        let _guard = self.protection_rw_lock.lock_write();
+        match self.protected_slot_traps.borrow_mut().remove(&protected_id) {
+            None => Err(Error::new(FatalError, "No slot trap found")),
+            Some(_) => Ok(())
+        }
+    }
+
+    pub fn unprotect_internal_slot(&self, protected_id: u64) -> Result<(), Error> {
+        let _guard = self.protection_rw_lock.lock_write();
+        match self.protected_internal_slots.borrow_mut().remove(&protected_id) {
+            None => Err(Error::new(FatalError, "No internal slot found")),
+            Some(_) => Ok(())
+        }
+    }
+
+    pub fn unprotect_property_trap(&self, protected_id: u64) -> Result<(), Error> {
+        let _guard = self.protection_rw_lock.lock_write();
+        match self.protected_property_traps.borrow_mut().remove(&protected_id) {
+            None => Err(Error::new(FatalError, "No property trap found")),
+            Some(_) => Ok(())
+        }
+    }
+
+}
+
+/// Isolate slot managements
+impl Isolate {
+
+    /// Gain a slot with prepared prototype
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn gain_slot(&self, region_id: u32, primitive_type: PrimitiveType, prototype: Value, layout_token: &ReentrantToken) -> Result<Value, Error> {
+
+        self.gain_tagged_slot(region_id, primitive_type, prototype, None, layout_token)
+
+    }
+
+    /// Gain a slot with the tag recorded if the allocation is sampled, and
+    /// with the allocation site recorded in the provenance mode
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn gain_tagged_slot(&self, region_id: u32, primitive_type: PrimitiveType, prototype: Value, tag: Option<&str>, layout_token: &ReentrantToken) -> Result<Value, Error> {
+
+        self.ensure_region_writable(region_id)?;
+
+        #[cfg(feature = "fault-injection")] self.inject_gain_slot_fault(region_id, primitive_type)?;
+
+        self.quotas.ensure_slot_available(region_id)?;
+
+        let region = self.find_region(region_id)?;
+        match region {
+            Some(region) => {
+                let id = self.track_region_occupancy(&region, || region.gain_slot(primitive_type))?;
+                self.metrics_counters.increase_slots_gained();
+                self.allocation_sampler.record(primitive_type, region_id, tag);
+                #[cfg(feature = "provenance")] self.provenances.record(id, primitive_type, tag, core::panic::Location::caller());
+                let old_prototype = region.overwrite_prototype(id, prototype)?;
+                self.add_value_reference(id, prototype, layout_token)?;
+                self.remove_value_reference(id, old_prototype, layout_token)?;
+                self.mark_as_white(id)?;
+                self.barrier.borrow().as_ref().map(|barrier| barrier.postgain_value(id));
+                Ok(id)
+            },
+            None => Err(Error::new(FatalError, "Region not found"))
+        }
+
+    }
+
+    /// Recycle a slot
+    pub fn recycle_slot(&self, slot: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let region_id = slot.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+
+        {
+            let shard = self.roots.get_shard(slot);
+            let _guard = shard.lock_read();
+            if shard.borrow().get(&slot).is_some() {
+                return Err(Error::new(FatalError, "Root exists for slot to recycle"));
+            }
+        }
+
+        if self.root_arenas.contains(slot) || self.handle_blocks.contains(slot) {
+            return Err(Error::new(FatalError, "Root exists for slot to recycle"));
+        }
+
+        match region {
+            Some(region) => self.track_region_occupancy(&region, || region.recycle_slot(slot, true, context)),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Recycle slots of values grouped by regions, each region is locked
+    /// once, and no slot will be recycled if any of the values is pinned by a
+    /// root or not found
+    ///
+    /// Values in each region are validated as a whole before recycled, so a
+    /// batch failed in a region leaves slots of the region untouched
+    pub fn recycle_slots(&self, values: &[Value], context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let mut region_ids = Vec::new();
+        let mut groups: HashMap<u32, Vec<Value>> = HashMap::new();
+        for value in values.iter() {
+            let region_id = value.get_region_id()?;
+            groups.entry(region_id).or_insert_with(|| {
+                region_ids.push(region_id);
+                Vec::new()
+            }).push(*value);
+        }
+
+        for value in values.iter() {
+            let shard = self.roots.get_shard(*value);
+            let _guard = shard.lock_read();
+            if shard.borrow().get(value).is_some() {
+                return Err(Error::new(FatalError, "Root exists for slot to recycle"));
+            }
+        }
+
+        let local_values: HashSet<Value> = self.handle_blocks.list_values().into_iter().collect();
+        for value in values.iter() {
+            if self.root_arenas.contains(*value) || local_values.contains(value) {
+                return Err(Error::new(FatalError, "Root exists for slot to recycle"));
+            }
+        }
+
+        let mut regions = Vec::new();
+        for region_id in region_ids {
+            self.ensure_region_writable(region_id)?;
+            match self.find_context_region(region_id, context)? {
+                Some(region) => regions.push(region),
+                None => {
+                    return Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id));
+                }
+            }
+        }
+
+        for region in regions {
+            let values = &groups[&region.get_id()];
+            self.track_region_occupancy(&region, || region.bulk_recycle(values, true, context))?;
+        }
+
+        Ok(())
+
+    }
+
+    /// Move slot among regions
+    pub fn move_slot(&self, from: Value, to_region_id: u32, context: &Box<dyn Context>) -> Result<Value, Error> {
+
+        let _guard = context.get_slot_layout_token().lock_write();
+
+        self.ensure_region_writable(to_region_id)?;
+        let to_region = self.get_region_to_move_into(to_region_id)?;
+
+        self.flush_handle_blocks(context.get_slot_layout_token())?;
+
+        let (to, removed_values, removed_symbols) = self.move_slot_without_layout_lock(from, &to_region, context)?;
+
+        self.refresh_root(from, to)?;
+        self.refresh_weak_root(from, to)?;
+        self.refresh_value_symbols(from, to);
+        self.refresh_object_groups(&[(from, to)]);
+        #[cfg(feature = "provenance")] self.provenances.refresh(&[(from, to)]);
+
+        context.update_value_references(from, &[], &removed_values)?;
+        for symbol in removed_symbols {
+            context.remove_symbol_reference(symbol)?;
+        }
+
+        Ok(to)
+
+    }
+
+    /// Move slots of values into a region under a single layout write lock,
+    /// roots, weak roots and value symbols are refreshed in one pass after
+    /// all slots moved
+    ///
+    /// Slots moved before a failure stay moved, and the batch stops at the
+    /// failed value
+    pub fn move_slots(&self, values: &[Value], to_region_id: u32, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        let _guard = context.get_slot_layout_token().lock_write();
+
+        self.ensure_region_writable(to_region_id)?;
+        let to_region = self.get_region_to_move_into(to_region_id)?;
+
+        self.flush_handle_blocks(context.get_slot_layout_token())?;
+
+        let mut moves = Vec::with_capacity(values.len());
+        let mut removed_references = Vec::with_capacity(values.len());
+        let mut result = Ok(());
+        for from in values.iter() {
+            match self.move_slot_without_layout_lock(*from, &to_region, context) {
+                Ok((to, removed_values, removed_symbols)) => {
+                    moves.push((*from, to));
+                    removed_references.push((*from, removed_values, removed_symbols));
+                },
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+
+        self.refresh_roots(&moves)?;
+        self.refresh_weak_roots(&moves)?;
+        self.refresh_values_symbols(&moves);
+        self.refresh_object_groups(&moves);
+        #[cfg(feature = "provenance")] self.provenances.refresh(&moves);
+
+        for (from, removed_values, removed_symbols) in removed_references {
+            context.update_value_references(from, &[], &removed_values)?;
+            for symbol in removed_symbols {
+                context.remove_symbol_reference(symbol)?;
+            }
+        }
+
+        result?;
+
+        Ok(moves.into_iter().map(|(_, to)| to).collect())
+
+    }
+
+    fn get_region_to_move_into(&self, to_region_id: u32) -> Result<Arc<Region>, Error> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        match self.regions.borrow().get(to_region_id as usize) {
+            Some(region) => Ok(region.clone()),
+            None => Err(Error::new(FatalError, "Region to move slot into not found"))
+        }
+
+    }
+
+    /// Move a slot into a region with the layout write lock held, references
+    /// removed from the old slot are returned to be released after roots
+    /// refreshed
+    fn move_slot_without_layout_lock(&self, from: Value, to_region: &Arc<Region>, context: &Box<dyn Context>) -> Result<(Value, Vec<Value>, Vec<Symbol>), Error> {
+
+        let from_region_id = from.get_region_id()?;
+        self.ensure_region_writable(from_region_id)?;
+        let from_region = {
+            let _guard = self.region_rw_lock.lock_read();
+            let regions = self.regions.borrow();
+            let region = regions.get(from_region_id as usize);
+            if region.is_none() {
+                return Err(Error::new(FatalError, "Region of slot not found"));
+            }
+            region.unwrap().clone()
+        };
+
+        #[cfg(feature = "fault-injection")] self.inject_restore_slot_fault(from, to_region.get_id())?;
+
+        let (snapshot, in_nursery, reference_map, removed_values, removed_symbols) = from_region.freeze_slot(from)?;
+
+        let to = self.restore_moved_slot(from, to_region, snapshot, in_nursery, reference_map, context)?;
+
+        Ok((to, removed_values, removed_symbols))
+
+    }
+
+    /// Restore a frozen slot into a region, and redirect the old slot to the
+    /// restored one
+    fn restore_moved_slot(&self, 
+                          from: Value, to_region: &Arc<Region>, 
+                          snapshot: SlotRecordSnapshot, in_nursery: bool, reference_map: Option<Box<ReferenceMap>>, 
+                          context: &Box<dyn Context>) -> Result<Value, Error> {
+
+        let from_region = match self.find_region(from.get_region_id()?)? {
+            Some(region) => region,
+            None => {
+                return Err(Error::new(FatalError, "Region of slot not found"));
+            }
+        };
+
+        let property_traps = snapshot.list_own_property_traps();
+
+        let (to, added_values, added_symbols) = self.track_region_occupancy(to_region, || to_region.restore_slot(from, snapshot, in_nursery, &reference_map))?;
+
+        context.update_value_references(to, &added_values, &[])?;
+        for symbol in added_symbols {
+            context.add_symbol_reference(symbol)?;
+        }
+
+        let reference_map_is_none = reference_map.is_none();
+        from_region.redirect_slot(from, to, reference_map)?;
+        if reference_map_is_none {
+            from_region.recycle_slot(from, false, context)?;
+        }
+
+        for property_trap in property_traps {
+            property_trap.notify_subject_moved(from, to);
+        }
+
+        Ok(to)
+
+    }
+
+    /// Move slot among regions by copying, the slot is copied with only the
+    /// layout read lock held, so readers continue against the old slot, and
+    /// the redirection is installed under the layout write lock at the end
+    ///
+    /// If the slot is modified during the copy, the copy is dropped and the
+    /// slot is moved as `move_slot` does
+    pub fn move_slot_by_copying(&self, from: Value, to_region_id: u32, context: &Box<dyn Context>) -> Result<Value, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        self.ensure_region_writable(from.get_region_id()?)?;
+        self.ensure_region_writable(to_region_id)?;
+
+        let to_region = self.get_region_to_move_into(to_region_id)?;
+
+        #[cfg(feature = "fault-injection")] self.inject_restore_slot_fault(from, to_region_id)?;
+
+        let (from_region, snapshot, version) = {
+            let _guard = layout_token.lock_read();
+            let from_region = match self.find_region(from.get_region_id()?)? {
+                Some(region) => region,
+                None => {
+                    return Err(Error::new(FatalError, "Region of slot not found"));
+                }
+            };
+            let (snapshot, version) = from_region.copy_slot(from)?;
+            (from_region, snapshot, version)
+        };
+
+        let _guard = layout_token.lock_write();
+
+        self.flush_handle_blocks(layout_token)?;
+
+        let (to, removed_values, removed_symbols) = match from_region.freeze_copied_slot(from, version)? {
+            Some((in_nursery, reference_map, removed_values, removed_symbols)) => {
+                let to = self.restore_moved_slot(from, &to_region, snapshot, in_nursery, reference_map, context)?;
+                (to, removed_values, removed_symbols)
+            },
+            None => self.move_slot_without_layout_lock(from, &to_region, context)?
+        };
+
+        self.refresh_root(from, to)?;
+        self.refresh_weak_root(from, to)?;
+        self.refresh_value_symbols(from, to);
+        self.refresh_object_groups(&[(from, to)]);
+        #[cfg(feature = "provenance")] self.provenances.refresh(&[(from, to)]);
+
+        context.update_value_references(from, &[], &removed_values)?;
+        for symbol in removed_symbols {
+            context.remove_symbol_reference(symbol)?;
+        }
+
+        Ok(to)
+
+    }
+
+    pub fn is_direct_value_alive(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let _guard = context.get_slot_layout_token().lock_read();
+
+        let region_id = value.get_region_id()?;
+        let region = {
+            let _guard = self.region_rw_lock.lock_read();
+            let regions = self.regions.borrow();
+            let region = regions.get(region_id as usize);
+            if region.is_none() {
+                return Err(Error::new(FatalError, "Region of slot not found"));
+            }
+            region.unwrap().clone()
+        };
+
+        region.is_value_alive(value)
+
+    }
+
+    pub fn is_direct_value_occupied(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let _guard = context.get_slot_layout_token().lock_read();
+
+        let region_id = value.get_region_id()?;
+        let region = {
+            let _guard = self.region_rw_lock.lock_read();
+            let regions = self.regions.borrow();
+            let region = regions.get(region_id as usize);
+            if region.is_none() {
+                return Err(Error::new(FatalError, "Region of slot not found"));
+            }
+            region.unwrap().clone()
+        };
+
+        region.is_value_occupied(value)
+
+    }
+
+    /// Notify a value is dropped from the isolate, weak roots of the value
+    /// are notified in the order they were added
+    pub fn notify_slot_drop(&self, slot: Value) -> Result<(), Error> {
+
+        let weak_roots = {
+            let shard = self.weak_roots.get_shard(slot);
+            let _guard = shard.lock_write();
+            shard.borrow_mut().remove(&slot)
+        };
+        match weak_roots {
+            Some(weak_roots) => {
+                let mut weak_roots: Vec<Arc<WeakRoot>> = weak_roots.into_inner().into_iter().collect();
+                weak_roots.sort_by_key(|root| root.get_weak_id());
+                for root in weak_roots.iter() {
+                    let replacement = root.notify_drop()?;
+                    self.fired_weak_roots.fetch_add(1, Ordering::SeqCst);
+                    if let Some(replacement) = replacement {
+                        let shard = self.weak_roots.get_shard(replacement);
+                        let _guard = shard.lock_write();
+                        shard.borrow_mut().entry(replacement).or_insert_with(|| RefCell::new(HashSet::new())).borrow_mut().insert(root.clone());
+                    }
+                }
+            },
+            None => {}
+        }
+
+        self.remove_object_group_member(slot);
+        #[cfg(feature = "provenance")] self.provenances.remove(slot);
+
+        Ok(())
+
+    }
+
+}
+
+/// Isolate value prototype getter and setter
+impl Isolate {
+
+    /// Get prototype of a value
+    pub fn get_prototype(&self, slot: Value, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let slot = self.resolve_real_value(slot, layout_token)?;
+
+        match slot.get_primitive_type() {
+            Undefined => {
+                return Err(Error::new(VisitingUndefinedPrototype, "Undefined has no prototype"));
+            },
+            Null => {
+                return Err(Error::new(VisitingNullPrototype, "Null has no prototype"));
+            },
+            Boolean => {
+                return Pinned::new(context, self.boolean_prototype);
+            },
+            Integer => {
+                return Pinned::new(context, self.integer_prototype);
+            },
+            Float => {
+                return Pinned::new(context, self.float_prototype);
+            },
+            Symbol => {
+                return Pinned::new(context, self.symbol_prototype);
+            },
+            Text => {
+                return Pinned::new(context, self.text_prototype);
+            },
+            Tuple => {
+                return Pinned::new(context, self.tuple_prototype);
+            },
+            List => {
+                return Pinned::new(context, self.list_prototype);
+            },
+            BigInteger => {
+                return Pinned::new(context, self.big_integer_prototype);
+            },
+            Object => {}
+        }
+
+        let region_id = slot.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) => region.get_prototype_with_layout_guard(slot, context, layout_guard, false),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Set prototype of a value
+    pub fn set_prototype(&self, slot: Value, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let slot = self.resolve_real_value(slot, layout_token)?;
+
+        check_access(context, |policy| policy.allow_prototype_mutation(slot), "Prototype mutation denied")?;
+
+        match slot.get_primitive_type() {
+            Undefined => Err(Error::new(MutatingUndefinedPrototype, "Undefined has no prototype")),
+            Null => Err(Error::new(MutatingNullPrototype, "Null has no prototype")),
+            Boolean => Err(Error::new(MutatingSealedPrototype, "Prototype of boolean is immutable")),
+            Integer => Err(Error::new(MutatingSealedPrototype, "Prototype of integer is immutable")),
+            Float => Err(Error::new(MutatingSealedPrototype, "Prototype of float is immutable")),
+            Symbol => Err(Error::new(MutatingSealedPrototype, "Prototype of symbol is immutable")),
+            Text => Err(Error::new(MutatingSealedPrototype, "Prototype of text is immutable")),
+            BigInteger => Err(Error::new(MutatingSealedPrototype, "Prototype of big integer is immutable")),
+            Tuple => Err(Error::new(MutatingSealedPrototype, "Prototype of tuple is immutable")),
+            List => Err(Error::new(MutatingSealedPrototype, "Prototype of list is immutable")),
+            Object => {
+                let region_id = slot.get_region_id()?;
+                self.ensure_region_writable(region_id)?;
+
+                self.check_prototype_cycle(slot, prototype, context)?;
+
+                let region = self.find_context_region(region_id, context)?;
+                match region {
+                    Some(region) => region.set_prototype_with_layout_guard(slot, prototype, context, layout_guard, false),
+                    None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+                }
+            }
+        }
+
+    }
+
+    pub fn set_prototype_ignore_slot_trap(&self, slot: Value, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        check_access(context, |policy| policy.allow_prototype_mutation(slot), "Prototype mutation denied")?;
+
+        let region_id = slot.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        self.check_prototype_cycle(slot, prototype, context)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.set_prototype_ignore_slot_trap(slot, prototype, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Get the limit of prototypes visited while walking a prototype chain
+    pub fn get_prototype_chain_limit(&self) -> u32 {
+        self.prototype_chain_limit.load(Ordering::SeqCst)
+    }
+
+    /// Set the limit of prototypes visited while walking a prototype chain,
+    /// `PrototypeCycle` will be returned if a chain is deeper than the limit
+    pub fn set_prototype_chain_limit(&self, limit: u32) {
+        self.prototype_chain_limit.store(limit, Ordering::SeqCst);
+    }
+
+    /// Get the limit of operations nested in a context, such as traps
+    /// dispatched, prototypes stepped and nested values converted
+    pub fn get_recursion_limit(&self) -> u32 {
+        self.recursion_limit.load(Ordering::SeqCst)
+    }
+
+    /// Set the limit of operations nested in a context,
+    /// `RecursionLimitExceeded` will be returned by operations nested deeper
+    /// than the limit, instead of overflowing the stack
+    pub fn set_recursion_limit(&self, limit: u32) {
+        self.recursion_limit.store(limit, Ordering::SeqCst);
+    }
+
+    /// Step to the next prototype in a chain, with the depth walked counted
+    fn get_next_prototype(&self, prototype: Value, depth: &mut u32, context: &Box<dyn Context>) -> Result<Value, Error> {
+
+        context.check_interrupted()?;
+
+        *depth += 1;
+        if *depth > self.get_prototype_chain_limit() {
+            return Err(Error::new(PrototypeCycle, "Prototype chain too deep").with_value(prototype));
+        }
+
+        let _guard = RecursionGuard::enter(context)?;
+
+        Ok(self.get_prototype(prototype, context)?.get_value())
+
+    }
+
+    /// Reject prototypes whose chain reaches the slot itself
+    fn check_prototype_cycle(&self, slot: Value, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let mut depth = 0;
+        let mut prototype = prototype;
+        while !prototype.is_nil() {
+            if self.resolve_real_value(prototype, layout_token)? == slot {
+                return Err(Error::new(PrototypeCycle, "Prototype chain cycle detected").with_value(slot));
+            }
+            prototype = self.get_next_prototype(prototype, &mut depth, context)?;
+        }
+
+        Ok(())
+
+    }
+
+}
+
+// Isolate value slot trap management
+impl Isolate {
+
+    pub fn has_slot_trap(&self, slot: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+ 
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let slot = self.resolve_real_value(slot, layout_token)?;
+
+        match slot.get_primitive_type() {
+            Undefined => Err(Error::new(MutatingUndefinedPrototype, "Undefined has no slot trap supports")),
+            Null => Err(Error::new(MutatingNullPrototype, "Null has no slot trap supports")),
+            Boolean => Ok(false),
+            Integer => Ok(false),
+            Float => Ok(false),
+            Symbol => Ok(false),
+            Text => Ok(false),
+            BigInteger => Ok(false),
+            Tuple => Ok(false),
+            List => Ok(false),
+            Object => {
+                let region_id = slot.get_region_id()?;
+                let region = self.find_context_region(region_id, context)?;
+                match region {
+                    Some(region) => region.has_slot_trap(slot),
+                    None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+                }
+            }
+        }
+
+    }
+
+    /// Set slot trap of a value
+    pub fn set_slot_trap(&self, slot: Value, slot_trap: Arc<dyn SlotTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
+ 
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let slot = self.resolve_real_value(slot, layout_token)?;
+
+        check_access(context, |policy| policy.allow_trap_installation(slot), "Trap installation denied")?;
+
+        match slot.get_primitive_type() {
+            Undefined => Err(Error::new(MutatingUndefinedProperty, "Undefined has no slot trap support")),
+            Null => Err(Error::new(MutatingNullProperty, "Null has no slot trap support")),
+            Boolean => Err(Error::new(MutatingSealedProperty, "Slot trap of boolean is immutable")),
+            Integer => Err(Error::new(MutatingSealedProperty, "Slot trap of integer is immutable")),
+            Float => Err(Error::new(MutatingSealedProperty, "Slot trap of float is immutable")),
+            Symbol => Err(Error::new(MutatingSealedProperty , "Slot trap of symbol is immutable")),
+            Text => Err(Error::new(MutatingSealedProperty, "Slot trap of text is immutable")),
+            BigInteger => Err(Error::new(MutatingSealedProperty, "Slot trap of big integer is immutable")),
+            Tuple => Err(Error::new(MutatingSealedProperty, "Slot trap of tuple is immutable")),
+            List => Err(Error::new(MutatingSealedProperty, "Slot trap of list is immutable")),
+            Object => {
+                let region_id = slot.get_region_id()?;
+                self.ensure_region_writable(region_id)?;
+
+                let region = self.find_context_region(region_id, context)?;
+                match region {
+                    Some(region) => region.set_slot_trap(slot, slot_trap, context),
+                    None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+                }
+            }
+        }
+
+    }
+
+    /// Clear slot trap of a value
+    pub fn clear_slot_trap(&self, slot: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+ 
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let slot = self.resolve_real_value(slot, layout_token)?;
+
+        check_access(context, |policy| policy.allow_trap_installation(slot), "Trap installation denied")?;
+
+        match slot.get_primitive_type() {
+            Undefined => Err(Error::new(MutatingUndefinedProperty, "Undefined has no slot trap support")),
+            Null => Err(Error::new(MutatingNullProperty, "Null has no slot trap support")),
+            Boolean => Err(Error::new(MutatingSealedProperty, "Slot trap of boolean is immutable")),
+            Integer => Err(Error::new(MutatingSealedProperty, "Slot trap of integer is immutable")),
+            Float => Err(Error::new(MutatingSealedProperty, "Slot trap of float is immutable")),
+            Symbol => Err(Error::new(MutatingSealedProperty , "Slot trap of symbol is immutable")),
+            Text => Err(Error::new(MutatingSealedProperty, "Slot trap of text is immutable")),
+            BigInteger => Err(Error::new(MutatingSealedProperty, "Slot trap of big integer is immutable")),
+            Tuple => Err(Error::new(MutatingSealedProperty, "Slot trap of tuple is immutable")),
+            List => Err(Error::new(MutatingSealedProperty, "Slot trap of list is immutable")),
+            Object => {
+                let region_id = slot.get_region_id()?;
+                self.ensure_region_writable(region_id)?;
+
+                let region = self.find_context_region(region_id, context)?;
+                match region {
+                    Some(region) => region.clear_slot_trap(slot, context),
+                    None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+                }
+            }
+        }
+
+    }
+
+    /// Call a value as function through its slot trap
+    pub fn call(&self, function: Value, this: Value, arguments: Vec<Value>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let function = self.resolve_real_value(function, layout_token)?;
+
+        match function.get_primitive_type() {
+            Object => {
+                let region_id = function.get_region_id()?;
+                let region = self.find_context_region(region_id, context)?;
+                match region {
+                    Some(region) => region.call_with_layout_guard(function, this, arguments, context, layout_guard),
+                    None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+                }
+            },
+            _ => Err(Error::new(TypeNotMatch, "Value is not callable"))
+        }
+
+    }
+
+    /// Create an object callable with a rust closure
+    pub fn create_native_function<F>(&self, region_id: u32, function: F, context: &Box<dyn Context>) -> Result<Value, Error> where F: Fn(&Args, &Box<dyn Context>) -> Result<Value, Error> + 'static {
+
+        let value = self.gain_slot(region_id, Object, self.object_prototype, context.get_slot_layout_token())?;
+
+        self.set_slot_trap(value, Arc::new(NativeFunction::new(function)), context)?;
+
+        Ok(value)
+
+    }
+
+}
+
+/// Isolate object internal slot management
+impl Isolate {
+
+    pub fn list_internal_slot_ids(&self, subject: Value, context: &Box<dyn Context>) -> Result<Vec<u64>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Ok(Vec::new()); },
+            Integer => { return Ok(Vec::new()); },
+            Float => { return Ok(Vec::new()); },
+            Symbol => { return Ok(Vec::new()); },
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.list_internal_slot_ids(subject),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    pub fn has_internal_slot(&self, subject: Value, index: u64, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        check_access(context, |policy| policy.allow_internal_slot_access(subject, index), "Internal slot access denied")?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Ok(false); },
+            Integer => { return Ok(false); },
+            Float => { return Ok(false); },
+            Symbol => { return Ok(false); },
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.has_internal_slot(subject, index),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Set a specified internal slot of a value
+    pub fn set_internal_slot(&self, subject: Value, index: u64, internal_slot: Arc<dyn InternalSlot>, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        check_access(context, |policy| policy.allow_internal_slot_access(subject, index), "Internal slot access denied")?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => {
+                region.set_internal_slot(subject, index, internal_slot, context)
+            },
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Clear a specified internal slot of a value
+    pub fn clear_internal_slot(&self, subject: Value, index: u64, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        check_access(context, |policy| policy.allow_internal_slot_access(subject, index), "Internal slot access denied")?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        };
+
+        let region_id = subject.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.clear_internal_slot(subject, index, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Get specified internal slot from a value
+    pub fn get_internal_slot<'a>(&self, subject: Value, index: u64, context: &'a Box<dyn Context>) -> Result<Option<ProtectedInternalSlot::<'a>>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        check_access(context, |policy| policy.allow_internal_slot_access(subject, index), "Internal slot access denied")?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        };
+
+        let region_id = subject.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.get_internal_slot(subject, index, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+}
+
+impl Isolate {
+
+    pub fn get_field_shortcuts(&self, subject: Value, context: &Box<dyn Context>) -> Result<Option<Arc<FieldShortcuts>>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _layout_guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => { return Ok(None); },
+            Integer => { return Ok(None); },
+            Float => { return Ok(None); },
+            Symbol => { return Ok(None); },
+            Text => { return Ok(None); },
+            BigInteger => { return Ok(None); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.get_field_shortcuts(subject),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    pub fn has_field_shortcuts(&self, subject: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _layout_guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => { return Ok(false); },
+            Integer => { return Ok(false); },
+            Float => { return Ok(false); },
+            Symbol => { return Ok(false); },
+            Text => { return Ok(false); },
+            BigInteger => { return Ok(false); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.has_field_shortcuts(subject),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    pub fn update_field_shortcuts(&self, subject: Value, field_shortcuts: Arc<FieldShortcuts>, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _layout_guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null has no properties")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean value is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer value is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float value is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol value is immutable")); },
+            Text => { return Err(Error::new(MutatingSealedProperty, "Text value is immutable")); },
+            BigInteger => { return Err(Error::new(MutatingSealedProperty, "Big integer value is immutable")); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => {
+                self.retire_field_shortcuts(region.update_field_shortcuts(subject, field_shortcuts)?);
+                Ok(())
+            },
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+    }
+
+    pub fn clear_field_shortcuts(&self, subject: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _layout_guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null has no properties")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean value is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer value is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float value is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol value is immutable")); },
+            Text => { return Err(Error::new(MutatingSealedProperty, "Text value is immutable")); },
+            BigInteger => { return Err(Error::new(MutatingSealedProperty, "Big integer value is immutable")); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = subject.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => {
+                self.retire_field_shortcuts(region.clear_field_shortcuts(subject)?);
+                Ok(())
+            },
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+    }
+
+    /// Keep field shortcuts released by a slot, since readers holding the
+    /// slot layout could still read them optimistically
+    fn retire_field_shortcuts(&self, field_shortcuts: Option<Arc<FieldShortcuts>>) {
+
+        if let Some(field_shortcuts) = field_shortcuts {
+
+            let _guard = self.retired_field_shortcuts_rw_lock.lock_write();
+
+            self.retired_field_shortcuts.borrow_mut().push(field_shortcuts);
+
+        }
+
+    }
+
+    /// Release field shortcuts retired, which is done at safepoints such as
+    /// collections, count of field shortcuts released is returned
+    pub fn release_retired_field_shortcuts(&self, layout_token: &ReentrantToken) -> usize {
+
+        let _guard = layout_token.lock_write();
+
+        let _guard_2 = self.retired_field_shortcuts_rw_lock.lock_write();
+
+        self.retired_field_shortcuts.borrow_mut().drain(..).count()
+
+    }
+
+}
+
+/// Isolate object own property management
+impl Isolate {
+
+    /// Get own property of a value for a symbol
+    pub fn get_own_property(&self, id: Value, subject: Value, symbol: Symbol, field_token: Option<&FieldToken>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+        
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_read(id, symbol), "Property read denied")?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => { return Pinned::new(context, Value::make_undefined()); },
+            Integer => { return Pinned::new(context, Value::make_undefined()); },
+            Float => { return Pinned::new(context, Value::make_undefined()); },
+            Symbol => { return Pinned::new(context, Value::make_undefined()); },
+            Text => { return Pinned::new(context, Value::make_undefined()); },
+            BigInteger => { return Pinned::new(context, Value::make_undefined()); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = id.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) if symbol == self.prototype_symbol => region.get_prototype_with_layout_guard(id, context, layout_guard, false),
+            Some(region) => region.get_own_property_with_layout_guard(id, subject, symbol, field_token, context, layout_guard, false),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Get own property of a value for a symbol, `None` will be returned if
+    /// the property is absent, to distinguish from a property storing
+    /// undefined
+    pub fn get_own_property_entry(&self, id: Value, subject: Value, symbol: Symbol, field_token: Option<&FieldToken>, context: &Box<dyn Context>) -> Result<Option<Pinned>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => { return Ok(None); },
+            Integer => { return Ok(None); },
+            Float => { return Ok(None); },
+            Symbol => { return Ok(None); },
+            Text => { return Ok(None); },
+            BigInteger => { return Ok(None); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        if !self.has_own_property(id, subject, symbol, context)? {
+            return Ok(None);
+        }
+
+        Ok(Some(self.get_own_property(id, subject, symbol, field_token, context)?))
+
+    }
+
+    pub fn get_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+ 
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_read(id, symbol), "Property read denied")?;
+
+        let region_id = id.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) if symbol == self.prototype_symbol => region.get_prototype_ignore_slot_trap(id, context),
+            Some(region) => region.get_own_property_ignore_slot_trap(id, subject, symbol, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Get the raw own property trap of a value for a symbol, slot traps are
+    /// ignored
+    pub fn get_own_property_trap<'a>(&self, id: Value, symbol: Symbol, context: &'a Box<dyn Context>) -> Result<Option<ProtectedPropertyTrap<'a>>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_read(id, symbol), "Property read denied")?;
+
+        let region_id = id.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) => region.get_own_property_trap(id, symbol, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Set own property of a value for a symbol
+    pub fn set_own_property(&self, id: Value, subject: Value, symbol: Symbol, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_write(id, symbol), "Property write denied")?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
+            Text => { return Err(Error::new(MutatingSealedProperty, "Text is immutable")); },
+            BigInteger => { return Err(Error::new(MutatingSealedProperty, "Big integer is immutable")); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = id.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) if symbol == self.prototype_symbol => {
+                self.check_prototype_cycle(id, value, context)?;
+                region.set_prototype_with_layout_guard(id, value, context, layout_guard, false)
+            },
+            Some(region) => region.set_own_property_with_layout_guard(id, subject, symbol, value, context, layout_guard, false),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Set own property of a value for a symbol
+    pub fn set_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_write(id, symbol), "Property write denied")?;
+
+        let region_id = id.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) if symbol == self.prototype_symbol => {
+                self.check_prototype_cycle(id, value, context)?;
+                region.set_prototype_ignore_slot_trap(id, value, context)
+            },
+            Some(region) => region.set_own_property_ignore_slot_trap(id, subject, symbol, value, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Define own property of a value for a symbol, returns whether an
+    /// existing property trap was replaced
+    pub fn define_own_property(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<bool, Error> {
+        
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_write(id, symbol), "Property write denied")?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
+            Text => { return Err(Error::new(MutatingSealedProperty, "Text is immutable")); },
+            BigInteger => { return Err(Error::new(MutatingSealedProperty, "Big integer is immutable")); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = id.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.define_own_property_with_layout_guard(id, subject, symbol, property_trap, context, layout_guard, false),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Define own property of a value for a symbol
+    pub fn define_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<bool, Error> {
+        
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_write(id, symbol), "Property write denied")?;
+
+        let region_id = id.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.define_own_property_ignore_slot_trap(id, subject, symbol, property_trap, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Delete own property from a value for a symbol, returns whether the
+    /// property existed
+    pub fn delete_own_property(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
+ 
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_write(id, symbol), "Property write denied")?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(MutatingUndefinedProperty, "Undefined is immutable")); },
+            Null => { return Err(Error::new(MutatingNullProperty, "Null is immutable")); },
+            Boolean => { return Err(Error::new(MutatingSealedProperty, "Boolean is immutable")); },
+            Integer => { return Err(Error::new(MutatingSealedProperty, "Integer is immutable")); },
+            Float => { return Err(Error::new(MutatingSealedProperty, "Float is immutable")); },
+            Symbol => { return Err(Error::new(MutatingSealedProperty, "Symbol is immutable")); },
+            Text => { return Err(Error::new(MutatingSealedProperty, "Text is immutable")); },
+            BigInteger => { return Err(Error::new(MutatingSealedProperty, "Big integer is immutable")); },
+            List => {},
+            Tuple => {},
+            Object => {}
+        }
+
+        let region_id = id.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.delete_own_property_with_layout_guard(id, subject, symbol, context, layout_guard, false),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Delete own property from a value for a symbol
+    pub fn delete_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
+ 
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_write(id, symbol), "Property write denied")?;
+
+        let region_id = id.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(region) => region.delete_own_property_ignore_slot_trap(id, subject, symbol, context),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// Check whether an own property of a value for a symbol exists
+    pub fn has_own_property(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        check_access(context, |policy| policy.allow_property_read(id, symbol), "Property read denied")?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => { return Ok(false); },
+            Integer => { return Ok(false); },
+            Float => { return Ok(false); },
+            Text => { return Ok(false); },
+            BigInteger => { return Ok(false); },
+            Symbol => { return Ok(false); },
+            List => {},
+            Tuple => {},
+            Object => {}
+        }
+
+        let region_id = id.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+        match region {
+            Some(_) if symbol == self.prototype_symbol => Ok(true),
+            Some(region) => region.has_own_property_with_layout_guard(id, subject, symbol, context, layout_guard),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// List own property symbols of a value, the prototype symbol comes
+    /// first and the others follow in the order of definition
+    pub fn list_own_property_symbols(&self, id: Value, subject: Value, context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let layout_guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        match id.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => { return Ok(Vec::new()); },
+            Integer => { return Ok(Vec::new()); },
+            Float => { return Ok(Vec::new()); },
+            Symbol => { return Ok(Vec::new()); },
+            Text => { return Ok(Vec::new()); },
+            BigInteger => { return Ok(Vec::new()); },
+            List => {},
+            Tuple => {},
+            Object =>{} 
+        }
+
+        let region_id = id.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) => {
+                let mut symbols = vec!(self.prototype_symbol);
+                for value in region.list_own_property_symbols_with_layout_guard(id, subject, context, layout_guard, false)? {
+                    if value != self.prototype_symbol {
+                        symbols.push(value);
+                    }
+                }
+                Ok(symbols)
+            },
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    /// List own property symbols of a value, in the same order as
+    /// `list_own_property_symbols`
+    pub fn list_own_property_symbols_ignore_slot_trap(&self, id: Value, subject: Value, context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let id = self.resolve_real_value(id, layout_token)?;
+
+        let region_id = id.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) => {
+                let mut symbols = vec!(self.prototype_symbol);
+                for value in region.list_own_property_symbols_ignore_slot_trap(id, subject, context)? {
+                    if value != self.prototype_symbol {
+                        symbols.push(value);
+                    }
+                }
+                Ok(symbols)
+            },
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+}
+
+/// Isolate object property managment
+impl Isolate {
+
+    /// List property symbols of a value, own property symbols come first and
+    /// symbols from prototypes follow, each symbol is listed once
+    pub fn list_property_symbols(&self, subject: Value, context: &Box<dyn Context>) -> Result<Vec<Symbol>, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => {},
+            Integer => {},
+            Float => {},
+            Symbol => {},
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let mut symbols = Vec::new();
+        let mut hash_set = HashSet::new();
+
+        let mut depth = 0;
+        let mut prototype = subject;
+        while !prototype.is_nil() {
+            for value in self.list_own_property_symbols(prototype, subject, context)? {
+                if hash_set.insert(value) {
+                    symbols.push(value);
+                }
+            }
+            prototype = self.get_next_prototype(prototype, &mut depth, context)?;
+        }
+
+        Ok(symbols)
+
+    }
+
+    /// Check whether an property of a value for a symbol exists
+    pub fn has_property(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => {},
+            Integer => {},
+            Float => {},
+            Symbol => {},
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let mut depth = 0;
+        let mut prototype = subject;
+        while !prototype.is_nil() {
+            if self.has_own_property(prototype, subject, symbol, context)? {
+                return Ok(true);
+            }
+            prototype = self.get_next_prototype(prototype, &mut depth, context)?;
+        } 
+
+        Ok(false)
+
+    }
+    
+    /// Get property of a value for a symbol
+    pub fn get_property(&self, subject: Value, symbol: Symbol, field_token: Option<&FieldToken>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let subject = self.resolve_real_value(subject, layout_token)?;
+
+        match subject.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
+            Boolean => {},
+            Integer => {},
+            Float => {},
+            Symbol => {},
+            Text => {},
+            BigInteger => {},
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let mut depth = 0;
+        let mut prototype = subject;
+        while !prototype.is_nil() {
+            if let Some(value) = self.get_own_property_entry(prototype, subject, symbol, field_token, context)? {
+                return Ok(value);
+            }
+            prototype = self.get_next_prototype(prototype, &mut depth, context)?;
+        } 
+        
+        Pinned::new(context, Value::make_undefined())
+
+    }
+
+}
+
+impl Isolate {
+
+    pub fn is_sealed(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let value = self.resolve_real_value(value, layout_token)?;
+
+        match value.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no feature for seal")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no feature for seal")); },
+            Boolean => { return Ok(true); },
+            Integer => { return Ok(true); },
+            Float => { return Ok(true); },
+            Symbol => { return Ok(true); },
+            Text => {return Ok(true); },
+            BigInteger => {return Ok(true); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = value.get_region_id()?;
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) => region.is_sealed(value),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+    pub fn seal_slot(&self, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let layout_token = context.get_slot_layout_token();
+
+        let _guard = layout_token.lock_read();
+
+        let value = self.resolve_real_value(value, layout_token)?;
+
+        match value.get_primitive_type() {
+            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no feature for seal")); },
+            Null => { return Err(Error::new(VisitingNullProperty, "Null has no feature for seal")); },
+            Boolean => { return Ok(()); },
+            Integer => { return Ok(()); },
+            Float => { return Ok(()); },
+            Symbol => { return Ok(()); },
+            Text => {return Ok(()); },
+            BigInteger => {return Ok(()); },
+            List => {},
+            Tuple => {},
+            Object => {} 
+        }
+
+        let region_id = value.get_region_id()?;
+        self.ensure_region_writable(region_id)?;
+
+        let region = self.find_context_region(region_id, context)?;
+
+        match region {
+            Some(region) => region.seal_slot(value),
+            None => Err(Error::new(FatalError, "Region of slot not found").with_region_id(region_id))
+        }
+
+    }
+
+}
+
+/// Isolate property path management
+impl Isolate {
+
+    /// Get property following a chain of symbols, prototypes are followed
+    /// for each segment. `PropertyPathBroken` will be returned if visiting
+    /// properties of undefined or null
+    pub fn get_property_path(&self, subject: Value, symbols: &[Symbol], context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let mut current = Pinned::new(context, subject)?;
+
+        for (index, symbol) in symbols.iter().enumerate() {
+            current = self.get_property_path_segment(current.get_value(), *symbol, index, context)?;
+        }
+
+        Ok(current)
+
+    }
+
+    /// Set own property of the value resolved by all segments except the
+    /// last one
+    pub fn set_property_path(&self, subject: Value, symbols: &[Symbol], value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let (last_symbol, symbols) = match symbols.split_last() {
+            Some(split) => split,
+            None => { return Err(Error::new(FatalError, "Property path is empty")); }
+        };
+
+        let mut current = Pinned::new(context, subject)?;
+
+        for (index, symbol) in symbols.iter().enumerate() {
+            current = self.get_property_path_segment(current.get_value(), *symbol, index, context)?;
+        }
+
+        let current = current.get_value();
+        if current.is_nil() {
+            return Err(Error::new(PropertyPathBroken(symbols.len()), &format!("Property path broken at segment {}", symbols.len())));
+        }
+
+        self.set_own_property(current, current, *last_symbol, value, context)
+
+    }
+
+    fn get_property_path_segment(&self, subject: Value, symbol: Symbol, index: usize, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        if subject.is_nil() {
+            return Err(Error::new(PropertyPathBroken(index), &format!("Property path broken at segment {}", index)));
+        }
+
+        self.get_property(subject, symbol, None, context)
+
+    }
+
+}
+
+/// Isolate foreign object management
+impl Isolate {
+
+    /// Attach a host object to a value as internal slot with specified ID,
+    /// the drop listener will be notified after the slot is recycled
+    pub fn set_foreign(&self, subject: Value, id: u64, data: Arc<dyn Any + Send + Sync>, drop_listener: Option<Box<dyn DropListener>>, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let foreign: Arc<dyn InternalSlot> = match drop_listener {
+            Some(drop_listener) => Arc::new(Foreign::with_drop_listener(data, drop_listener)),
+            None => Arc::new(Foreign::new(data))
+        };
+
+        self.set_internal_slot(subject, id, foreign, context)
+
+    }
+
+    /// Attach a host object which will be shared with other isolates when
+    /// the value is transferred
+    pub fn set_transferable_foreign(&self, subject: Value, id: u64, data: Arc<dyn Any + Send + Sync>, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let foreign: Arc<dyn InternalSlot> = Arc::new(Foreign::new_transferable(data));
+
+        self.set_internal_slot(subject, id, foreign, context)
+
+    }
+
+    /// Get the host object attached to a value
+    ///
+    /// `None` will be returned if no internal slot with the ID, and error will
+    /// be returned if the internal slot is not a host object of the type
+    pub fn get_foreign<T: Any + Send + Sync>(&self, subject: Value, id: u64, context: &Box<dyn Context>) -> Result<Option<Arc<T>>, Error> {
+
+        match self.get_internal_slot(subject, id, context)? {
+            Some(internal_slot) => {
+                match internal_slot.as_any().downcast_ref::<Foreign>() {
+                    Some(foreign) => {
+                        match foreign.downcast::<T>() {
+                            Some(data) => Ok(Some(data)),
+                            None => Err(Error::new(TypeNotMatch, "Foreign object type not match"))
+                        }
+                    },
+                    None => Err(Error::new(TypeNotMatch, "Internal slot is not foreign object"))
+                }
+            },
+            None => Ok(None)
+        }
+
+    }
+
+}
+
+/// Isolate custom error management, custom error types are shared with
+/// forked isolates
+impl Isolate {
+
+    /// Register a custom error type for embedders to surface domain errors
+    pub fn register_custom_error(&self, code: u32, name: &str, default_message: &str) -> Result<(), Error> {
+        self.custom_errors.register(code, name, default_message)
+    }
+
+    pub fn get_custom_error_info(&self, code: u32) -> Option<CustomErrorInfo> {
+        self.custom_errors.get_info(code)
+    }
+
+    /// Make an error of a custom error type registered
+    pub fn make_custom_error(&self, code: u32, message: Option<&str>) -> Result<Error, Error> {
+        self.custom_errors.make_error(code, message)
+    }
+
+}
+
+/// Isolate text conversion management
+impl Isolate {
+
+    /// Set the formatter converting values of a primitive type into texts
+    pub fn set_text_formatter(&self, primitive_type: PrimitiveType, text_formatter: Arc<dyn TextFormatter>) {
+
+        let _guard = self.conversions_rw_lock.lock_write();
+
+        self.text_formatters.borrow_mut().insert(primitive_type, text_formatter);
+
+    }
+
+    pub fn clear_text_formatter(&self, primitive_type: PrimitiveType) {
+
+        let _guard = self.conversions_rw_lock.lock_write();
+
+        self.text_formatters.borrow_mut().remove(&primitive_type);
+
+    }
+
+    fn get_text_formatter(&self, primitive_type: PrimitiveType) -> Option<Arc<dyn TextFormatter>> {
+
+        let _guard = self.conversions_rw_lock.lock_read();
+
+        self.text_formatters.borrow().get(&primitive_type).cloned()
+
+    }
+
+    /// Convert a value by the `to_text` well-known property, `None` will be
+    /// returned if the property is not defined
+    fn convert_text_by_protocol(&self, value: Value, context: &Box<dyn Context>) -> Result<Option<String>, Error> {
+
+        let to_text = self.get_property(value, self.well_known_symbols.get_to_text(), None, context)?;
+
+        let text = match to_text.get_value().get_primitive_type() {
+            Undefined | Null => { return Ok(None); },
+            Text => to_text,
+            Object => self.call(to_text.get_value(), value, Vec::new(), context)?,
+            _ => { return Err(Error::new(TypeNotMatch, "Conversion to text should be a text or a function").with_value(value)); }
+        };
+
+        if !text.get_value().is_text() {
+            return Err(Error::new(TypeNotMatch, "Conversion to text should return a text").with_value(value));
+        }
+
+        Ok(Some(self.extract_text_slot(text.get_value(), context)?.to_string()))
+
+    }
+
+}
+
+/// Isolate fault injection management
+#[cfg(feature = "fault-injection")]
+impl Isolate {
+
+    /// Set the injector asked to fail gaining and restoring slots, and to
+    /// delay acquisitions of the slot layout lock
+    pub fn set_fault_injector(&self, fault_injector: Arc<dyn FaultInjector>) {
+
+        let _guard = self.fault_injector_rw_lock.lock_write();
+
+        let delayed_injector = fault_injector.clone();
+        self.slot_layout_lock.set_acquisition_delay(Some(Arc::new(move |writing| {
+            delayed_injector.delay_lock(if writing { LockAccess::Write } else { LockAccess::Read });
+        })));
+
+        self.fault_injector.replace(Some(fault_injector));
+
+    }
+
+    pub fn clear_fault_injector(&self) {
+
+        let _guard = self.fault_injector_rw_lock.lock_write();
+
+        self.slot_layout_lock.set_acquisition_delay(None);
+
+        self.fault_injector.replace(None);
+
+    }
+
+    fn get_fault_injector(&self) -> Option<Arc<dyn FaultInjector>> {
+
+        let _guard = self.fault_injector_rw_lock.lock_read();
+
+        self.fault_injector.borrow().clone()
+
+    }
+
+    fn inject_gain_slot_fault(&self, region_id: u32, primitive_type: PrimitiveType) -> Result<(), Error> {
+
+        if let Some(fault_injector) = self.get_fault_injector() {
+            if fault_injector.fail_gain_slot(region_id, primitive_type) {
+                return Err(Error::new(OutOfSpace, "Out of slots by injected fault").with_region_id(region_id));
+            }
+        }
+
+        Ok(())
+
+    }
+
+    fn inject_restore_slot_fault(&self, from: Value, to_region_id: u32) -> Result<(), Error> {
+
+        if let Some(fault_injector) = self.get_fault_injector() {
+            if fault_injector.fail_restore_slot(from, to_region_id) {
+                return Err(Error::new(FatalError, "Slot restoring failed by injected fault").with_value(from).with_region_id(to_region_id));
+            }
+        }
+
+        Ok(())
+
+    }
+
+}
+
+/// Isolate value coercion management
+impl Isolate {
+
+    /// Set the coercer asked before the default coercions
+    pub fn set_value_coercer(&self, value_coercer: Arc<dyn ValueCoercer>) {
+
+        let _guard = self.conversions_rw_lock.lock_write();
+
+        self.value_coercer.replace(Some(value_coercer));
+
+    }
+
+    pub fn clear_value_coercer(&self) {
+
+        let _guard = self.conversions_rw_lock.lock_write();
+
+        self.value_coercer.replace(None);
+
+    }
+
+    fn get_value_coercer(&self) -> Option<Arc<dyn ValueCoercer>> {
+
+        let _guard = self.conversions_rw_lock.lock_read();
+
+        self.value_coercer.borrow().clone()
+
+    }
+
+    /// Coerce a value into a 64-bit float
+    ///
+    /// * Booleans and numbers are converted directly
+    /// * Values with a `to_primitive` well-known property in their prototype
+    ///   chain are coerced by the primitive it provides, which should be a
+    ///   primitive or a function returning primitive
+    /// * Otherwise the registered value coercer is asked
+    /// * Texts are parsed, and big integers in 64-bit range are converted
+    /// * `TypeNotMatch` will be returned for the others
+    pub fn to_float(&self, value: Value, context: &Box<dyn Context>) -> Result<f64, Error> {
+
+        match value.get_primitive_type() {
+            Boolean | Integer | Float => { return value.try_extract_float(); },
+            Undefined | Null | Text => {},
+            _ => {
+                if let Some(primitive) = self.convert_primitive_by_protocol(value, context)? {
+                    return self.coerce_float_by_default(primitive.get_value(), context);
+                }
+            }
+        }
+
+        if let Some(value_coercer) = self.get_value_coercer() {
+            if let Some(float) = value_coercer.coerce_to_float(value, context)? {
+                return Ok(float);
+            }
+        }
+
+        self.coerce_float_by_default(value, context)
+
+    }
+
+    /// Coerce a value into a 32-bit integer, following the coercions of
+    /// `to_float` with fractions truncated
+    ///
+    /// `IntegerOutOfRange` will be returned for numbers out of range
+    pub fn to_integer(&self, value: Value, context: &Box<dyn Context>) -> Result<i32, Error> {
+
+        if value.is_integer() {
+            return value.try_extract_integer();
+        }
+
+        Value::make_float(self.to_float(value, context)?).try_extract_integer()
+
+    }
+
+    /// Coerce a value into a boolean
+    ///
+    /// * Booleans and numbers are converted directly, zeros are `false`
+    /// * Values with a `to_primitive` well-known property in their prototype
+    ///   chain are coerced by the primitive it provides
+    /// * Otherwise the registered value coercer is asked
+    /// * Undefined, null, empty texts and zero big integers are `false`,
+    ///   and the others are `true`
+    pub fn to_boolean(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        match value.get_primitive_type() {
+            Boolean | Integer | Float => { return value.try_as_boolean(); },
+            Undefined | Null | Text => {},
+            _ => {
+                if let Some(primitive) = self.convert_primitive_by_protocol(value, context)? {
+                    return self.coerce_boolean_by_default(primitive.get_value(), context);
+                }
+            }
+        }
+
+        if let Some(value_coercer) = self.get_value_coercer() {
+            if let Some(boolean) = value_coercer.coerce_to_boolean(value, context)? {
+                return Ok(boolean);
+            }
+        }
+
+        self.coerce_boolean_by_default(value, context)
+
+    }
+
+    /// Convert a value by the `to_primitive` well-known property, `None` will
+    /// be returned if the property is not defined
+    fn convert_primitive_by_protocol(&self, value: Value, context: &Box<dyn Context>) -> Result<Option<Pinned>, Error> {
+
+        let to_primitive = self.get_property(value, self.well_known_symbols.get_to_primitive(), None, context)?;
+
+        let primitive = match to_primitive.get_value().get_primitive_type() {
+            Undefined | Null => { return Ok(None); },
+            Object => self.call(to_primitive.get_value(), value, Vec::new(), context)?,
+            _ => to_primitive
+        };
+
+        match primitive.get_value().get_primitive_type() {
+            List | Tuple | Object => Err(Error::new(TypeNotMatch, "Conversion to primitive should return a primitive").with_value(value)),
+            _ => Ok(Some(primitive))
+        }
+
+    }
+
+    fn coerce_float_by_default(&self, value: Value, context: &Box<dyn Context>) -> Result<f64, Error> {
+
+        match value.get_primitive_type() {
+            Boolean | Integer | Float => value.try_extract_float(),
+            Text => {
+                let text = self.extract_text_slot(value, context)?.to_string();
+                match text.trim().parse::<f64>() {
+                    Ok(float) => Ok(float),
+                    Err(_) => Err(Error::new(TypeNotMatch, "Text could not be coerced into number").with_value(value))
+                }
+            },
+            BigInteger => {
+                match self.extract_big_integer(value, context)?.to_i64() {
+                    Some(integer) => Ok(integer as f64),
+                    None => Err(Error::new(IntegerOutOfRange, "Big integer out of range").with_value(value))
+                }
+            },
+            _ => Err(Error::new(TypeNotMatch, "Value could not be coerced into number"))
+        }
+
+    }
+
+    fn coerce_boolean_by_default(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+
+        match value.get_primitive_type() {
+            Undefined | Null => Ok(false),
+            Boolean | Integer | Float => value.try_as_boolean(),
+            Text => Ok(!self.extract_text_slot(value, context)?.is_empty()),
+            BigInteger => Ok(!self.extract_big_integer(value, context)?.is_zero()),
+            Symbol | List | Tuple | Object => Ok(true)
+        }
+
+    }
+
+}
+
+/// Isolate outlet management
+impl Isolate {
+
+    /// Set the outlet with specified ID
+    pub fn add_outlet(&self, outlet: Arc<dyn Any>) -> u64 {
+
+        let _guard = self.outlets_rw_lock.lock_write();
+
+        let id = self.next_outlet_id.fetch_add(1, Ordering::SeqCst);
+
+        self.outlets.borrow_mut().insert(id, outlet);
+
+        id
+
+    }
+
+    /// Get the outlet with specified ID
+    pub fn get_outlet(&self, id: u64) -> Option<Arc<dyn Any>> {
+
+        let _guard = self.outlets_rw_lock.lock_read();
+
+        match self.outlets.borrow().get(&id) {
+            None => None,
+            Some(outlet) => Some(outlet.clone())
+        }
+
+    }
+
+    /// Remove the outlet with specified ID
+    pub fn clear_outlet(&self, id: u64) -> Option<Arc<dyn Any>> {
+
+        let _guard = self.outlets_rw_lock.lock_read();
+
+        self.outlets.borrow_mut().remove(&id)
+
+    }
+
+}
+
+/// Isolate metrics
+impl Isolate {
+
+    /// Get the pull-style metrics of the isolate
+    pub fn metrics(&self) -> Metrics<'_> {
+        Metrics::new(self)
+    }
+
+    pub fn get_metrics_counters(&self) -> &MetricsCounters {
+        &self.metrics_counters
+    }
+
+    /// Start sampling every N-th slot gained, sampled slots are aggregated
+    /// by their primitive types, regions and tags of contexts
+    pub fn start_allocation_sampling(&self, sample_interval: u64) {
+        self.allocation_sampler.start(sample_interval);
+    }
+
+    /// Stop sampling slots gained, sampled slots are kept in the profile
+    pub fn stop_allocation_sampling(&self) {
+        self.allocation_sampler.stop();
+    }
+
+    pub fn is_allocation_sampling(&self) -> bool {
+        self.allocation_sampler.is_sampling()
+    }
+
+    /// Get the report of allocation hot spots sampled
+    pub fn allocation_profile(&self) -> AllocationProfile {
+        self.allocation_sampler.create_profile()
+    }
+
+    /// Clear slots sampled before
+    pub fn reset_allocation_profile(&self) {
+        self.allocation_sampler.reset();
+    }
+
+    /// Count live slots by primitive types, prototypes and regions, compare
+    /// censuses taken at different times to find slots accumulating
+    pub fn heap_census(&self, context: &Box<dyn Context>) -> Result<HeapCensus, Error> {
+
+        let _guard = context.get_slot_layout_token().lock_read();
+
+        let mut census = HeapCensus::new();
+
+        for region_id in self.list_region_ids()? {
+            let region = match self.find_region(region_id)? {
+                Some(region) => region,
+                None => continue
+            };
+            for value in region.list_alive_values()? {
+                let prototype = region.get_prototype_ignore_slot_trap(value, context)?;
+                census.record(value.get_primitive_type(), prototype.get_value(), region_id);
+            }
+        }
+
+        Ok(census)
+
+    }
+
+    /// Get the site where the slot of the value was gained, with the tag of
+    /// the context and the location of the caller
+    #[cfg(feature = "provenance")]
+    pub fn get_allocation_site(&self, value: Value) -> Option<Provenance> {
+        self.provenances.get(value)
+    }
+
+    /// Get contention of locks by their classes, such as regions, roots and
+    /// symbols, the stats are shared by all isolates of the process
+    #[cfg(feature = "lock-stats")]
+    pub fn lock_stats(&self) -> Vec<LockStats> {
+        list_lock_stats()
+    }
+
+    /// Reset contention of locks of all isolates
+    #[cfg(feature = "lock-stats")]
+    pub fn reset_lock_stats(&self) {
+        reset_lock_stats();
+    }
+
+    /// Count slots occupied in all regions
+    pub fn count_live_slots(&self) -> usize {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        let mut count = 0;
+        for (_index, region) in self.regions.borrow().iterate_items() {
+            count += region.get_occupied_count();
+        }
+
+        count
+
+    }
+
+    /// Collect statistics of outer reference maps in all local regions
+    pub fn get_reference_map_stats(&self) -> ReferenceMapStats {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        let mut stats = ReferenceMapStats::new();
+        for (_index, region) in self.regions.borrow().iterate_items() {
+            region.collect_reference_map_stats(&mut stats);
+        }
+
+        stats
+
+    }
+
+    /// Verify consistency of all local regions, the first inconsistency
+    /// found is returned with the ID of its region
+    pub fn verify_regions(&self) -> Result<(), Error> {
+
+        let _guard = self.region_rw_lock.lock_read();
+
+        for (_index, region) in self.regions.borrow().iterate_items() {
+            region.verify()?;
+        }
+
+        Ok(())
+
+    }
+
+    /// Count values kept as roots, including values only rooted by handle
+    /// blocks not flushed yet
+    pub fn count_roots(&self) -> usize {
+
+        let local_values: HashSet<Value> = self.handle_blocks.list_values().into_iter().filter(|value| {
+            let shard = self.roots.get_shard(*value);
+            let _guard = shard.lock_read();
+            !shard.borrow().contains_key(value)
+        }).collect();
+
+        self.roots.count() + local_values.len()
+
+    }
+
+    /// Count values observed by weak roots
+    pub fn count_weak_roots(&self) -> usize {
+
+        self.weak_roots.count()
+
+    }
+
+    /// Take a census of weak roots, counting weak roots targeting each value
+    /// and weak roots fired since the isolate created
+    pub fn weak_root_census(&self) -> WeakRootCensus {
+
+        let mut targets = HashMap::new();
+        for shard in self.weak_roots.iterate_shards() {
+            let _guard = shard.lock_read();
+            for (value, value_weak_roots) in shard.borrow().iter() {
+                targets.insert(*value, value_weak_roots.borrow().len());
+            }
+        }
+
+        WeakRootCensus::new(targets, self.fired_weak_roots.load(Ordering::SeqCst))
+
+    }
+
+    /// Count symbols registered in all symbol scopes
+    pub fn count_symbols(&self) -> usize {
+
+        let _guard = self.symbol_rw_lock.lock_read();
+
+        self.symbol_lut.borrow().len()
+
+    }
+
+}
+
+#[cfg(test)] use core::sync::atomic::AtomicUsize;
+#[cfg(test)] use super::field_shortcuts::FieldTemplate;
+#[cfg(test)] use super::json::JSON_SYMBOL_SCOPE;
+#[cfg(test)] use super::test::TestContext2;
+#[cfg(test)] use super::root::DropNotice;
+#[cfg(test)] use super::root::PayloadDropListener;
+#[cfg(test)] use super::storage::Local;
+#[cfg(test)] use super::storage::Persistent;
+#[cfg(test)] use super::storage::PinGuard;
+
+#[test]
+fn test_isolate_creation() -> Result<(), Error> {
+    Isolate::create()?;
+    Ok(())
+}
+
+#[test]
+fn test_isolate_text_symbol() -> Result<(), Error> {
+
+    let isolate = Isolate::create()?;
+
+    let test_2 = isolate.get_text_symbol("test", "test2");
+    let test_2_2 = isolate.get_text_symbol("test", "test2");
+    let test_2_3 = isolate.get_text_symbol("test", "test3");
+    let test_3 = isolate.get_text_symbol("test2", "test3");
+
+    assert_eq!(test_2, test_2_2);
+    assert_ne!(test_2, test_2_3);
+    assert_ne!(test_2, test_3);
+    assert_ne!(test_2_3, test_3);
+
+    let test_2_symbol_info = isolate.resolve_symbol_info(test_2)?;
+    assert_eq!(test_2_symbol_info.get_symbol(), test_2);
+    assert_eq!(test_2_symbol_info.get_symbol_scope().as_ref(), "test");
+    assert!(test_2_symbol_info.is_text_symbol());
+    assert!(!test_2_symbol_info.is_value_symbol());
+    assert_eq!(test_2_symbol_info.get_text().unwrap().as_ref(), "test2");
+    assert!(test_2_symbol_info.get_value().is_none());
+
+    assert!(isolate.recycle_symbol(test_2).is_err());
+    isolate.add_symbol_reference(test_2)?;
+    assert!(isolate.recycle_symbol(test_2).is_err());
+    isolate.remove_symbol_reference(test_2)?;
+    assert!(isolate.recycle_symbol(test_2).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_isolate_value_symbol() -> Result<(), Error> {
+
+    let isolate = Isolate::create()?;
+
+    let test_2 = isolate.get_value_symbol("test", Value::make_null());
+    let test_2_2 = isolate.get_value_symbol("test", Value::make_null());
+    let test_2_3 = isolate.get_value_symbol("test", Value::make_float(4.0));
+    let test_3 = isolate.get_value_symbol("test2", Value::make_float(4.0));
+
+    assert_eq!(test_2, test_2_2);
+    assert_ne!(test_2, test_2_3);
+    assert_ne!(test_2, test_3);
+    assert_ne!(test_2_3, test_3);
+
+    let test_2_symbol_info = isolate.resolve_symbol_info(test_2)?;
+    assert_eq!(test_2_symbol_info.get_symbol(), test_2);
+    assert_eq!(test_2_symbol_info.get_symbol_scope().as_ref(), "test");
+    assert!(!test_2_symbol_info.is_text_symbol());
+    assert!(test_2_symbol_info.is_value_symbol());
+    assert_eq!(test_2_symbol_info.get_value().unwrap(), Value::make_null());
+    assert!(test_2_symbol_info.get_text().is_none());
+
+    assert!(isolate.recycle_symbol(test_2).is_err());
+    isolate.add_symbol_reference(test_2)?;
+    assert!(isolate.recycle_symbol(test_2).is_err());
+    isolate.remove_symbol_reference(test_2)?;
+    assert!(isolate.recycle_symbol(test_2).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_isolate_region_management() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    // region 0 is for builtin objects
+
+    assert_eq!(region_id, 1);
+
+    assert!(isolate.recycle_region(region_id).is_err());
+
+    isolate.unprotect_region(region_id)?;
+    isolate.recycle_region(region_id)?;
+
+    let region_id = isolate.create_region()?;
+
+    isolate.unprotect_region(region_id)?;
+
+    assert_eq!(region_id, 2);
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+
+    assert!(isolate.recycle_region(region_id).is_err());
+
+    assert!(isolate.recycle_slot(value, &context).is_err());
+
+    isolate.add_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+
+    assert!(isolate.recycle_slot(value, &context).is_err());
+
+    isolate.remove_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+
+    isolate.recycle_slot(value, &context)?;
+
+    isolate.recycle_region(region_id)?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_verify_regions() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+    isolate.unprotect_region(region_id_2)?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    for value in [value, value_2, value_3].iter() {
+        isolate.move_value_out_from_nursery(*value, &layout_token)?;
+    }
+    isolate.add_value_reference(value, value_2, &layout_token)?;
+    isolate.verify_regions()?;
+
+    isolate.recycle_slot(value_3, &context)?;
+    isolate.remove_value_reference(value, value_2, &layout_token)?;
+    isolate.recycle_slot(value_2, &context)?;
+    isolate.recycle_region(region_id_2)?;
+    isolate.verify_regions()?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_region_page_stats() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    for _ in 0..2 {
+        let region_id = isolate.create_region()?;
+        isolate.unprotect_region(region_id)?;
+        isolate.recycle_region(region_id)?;
+    }
+
+    let stats = isolate.get_region_page_stats();
+    assert_eq!(stats.get_page_count(), 1);
+    assert_eq!(stats.get_free_index_count(), 2);
+    assert_eq!(isolate.list_free_region_ids(), vec!(1, 2));
+
+    // region 0 keeps the first page
+    assert_eq!(isolate.shrink_regions(), 0);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_slot_management() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+
+    assert!(isolate.recycle_slot(value, &context).is_err());
+
+    isolate.add_value_reference(value, value_2, &layout_token)?;
+
+    isolate.add_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+
+    assert!(isolate.recycle_slot(value, &context).is_err());
+
+    isolate.remove_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+
+    isolate.recycle_slot(value, &context)?;
+
+    assert!(isolate.recycle_slot(value_2, &context).is_err());
+
+    isolate.remove_value_reference(value, value_2, &layout_token)?;
+
+    isolate.recycle_slot(value_2, &context)?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_slot_snapshot() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_slot = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+
+    isolate.move_value_out_from_nursery(value_slot, &layout_token)?;
+    isolate.recycle_slot(value_slot, &context)?;
+
+    let value_4 = isolate.move_slot(value_3, region_id, &context)?;
+
+    assert_eq!(value_slot, value_4);
+
+    assert!(isolate.is_direct_value_alive(value_slot, &context)?);
+    assert!(!isolate.is_direct_value_alive(value_3, &context)?);
+    assert!(isolate.resolve_real_value(value_3, &layout_token).is_err());
+
+    let symbol = isolate.get_text_symbol("test", "test");
+
+    isolate.set_own_property(value_2, value_2, symbol, value_4, &context)?;
+
+    isolate.move_value_out_from_nursery(value, &layout_token)?;
+    isolate.recycle_slot(value, &context)?;
+
+    let value_5 = isolate.move_slot(value_4, region_id, &context)?;
+    assert!(!isolate.is_direct_value_alive(value_4, &context)?);
+    assert!(isolate.is_direct_value_occupied(value_4, &context)?);
+    assert_eq!(isolate.resolve_real_value(value_4, &layout_token)?, value_5);
+    assert_eq!(isolate.get_own_property(value_2, value_2, symbol, None, &context)?.get_value(), value_5);
+    assert!(!isolate.is_direct_value_occupied(value_4, &context)?);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_outlets() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let outlet: Arc<dyn Any> = Arc::new(Value::make_undefined());
+    let outlet_2: Arc<dyn Any> = Arc::new(Value::make_null());
+
+    let outlet_id = isolate.add_outlet(outlet.clone());
+    let outlet_2_id = isolate.add_outlet(outlet_2.clone());
+
+    assert!(Arc::ptr_eq(&isolate.get_outlet(outlet_id).unwrap(), &outlet));
+    assert!(Arc::ptr_eq(&isolate.get_outlet(outlet_2_id).unwrap(), &outlet_2));
+
+    isolate.clear_outlet(outlet_id);
+    assert!(isolate.get_outlet(outlet_id).is_none());
+
+    isolate.clear_outlet(outlet_2_id);
+    assert!(isolate.get_outlet(outlet_2_id).is_none());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_own_properties() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+
+    let symbol = isolate.get_text_symbol("test", "test");
+
+    isolate.set_own_property(value, value, symbol, Value::make_float(3.14), &context)?;
+
+    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_float(3.14));
+
+    let symbols = isolate.list_own_property_symbols(value, value, &context)?;
+    assert_eq!(symbols.len(), 2);
+    assert!(symbols.contains(&isolate.get_prototype_symbol()));
+    assert!(symbols.contains(&symbol));
+
+    isolate.delete_own_property(value, value, symbol, &context)?;
+
+    let symbols = isolate.list_own_property_symbols(value, value, &context)?;
+    assert_eq!(symbols.len(), 1);
+    assert!(symbols.contains(&isolate.get_prototype_symbol()));
+
+    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_undefined());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_properties() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), prototype);
+
+    let symbol = isolate.get_text_symbol("test", "test");
+
+    isolate.set_own_property(prototype, prototype, symbol, Value::make_float(3.14), &context)?;
+
+    assert_eq!(isolate.get_property(value, symbol, None, &context)?.get_value(), Value::make_float(3.14));
+    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_undefined());
+
+    let symbols = isolate.list_property_symbols(value, &context)?;
+    assert_eq!(symbols.len(), 2);
+    assert!(symbols.contains(&isolate.get_prototype_symbol()));
+    assert!(symbols.contains(&symbol));
+
+    let symbols = isolate.list_own_property_symbols(value, value, &context)?;
+    assert_eq!(symbols.len(), 1);
+    assert!(symbols.contains(&isolate.get_prototype_symbol()));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_seals() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+
+    assert!(!isolate.is_sealed(value, &context)?);
+
+    isolate.seal_slot(value, &context)?;
+
+    assert!(isolate.is_sealed(value, &context)?);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_bytes() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.create_bytes(region_id, &[1, 2, 3], &context)?;
+
+    assert_eq!(isolate.extract_bytes(value, &context)?.as_ref(), &[1, 2, 3].to_vec());
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), isolate.get_bytes_prototype());
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    assert!(isolate.extract_bytes(object, &context).is_err());
+    assert!(isolate.extract_bytes(Value::make_integer(1), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_concat_text() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let foo = context.make_text("foo ", &context)?;
+    let bar = context.make_text("bar", &context)?;
+
+    let foo_bar = isolate.concat_text(*foo, *bar, &context)?;
+
+    assert_eq!(isolate.extract_text_slot(*foo_bar, &context)?, Text::new("foo bar"));
+    assert_eq!(isolate.get_prototype(*foo_bar, &context)?.get_value(), isolate.get_text_prototype());
+
+    assert!(isolate.concat_text(*foo, Value::make_integer(1), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_external_text() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let string: Arc<str> = Arc::from("foo bar");
+
+    let value = isolate.create_external_text(region_id, string.clone(), &context)?;
+
+    assert_eq!(Arc::strong_count(&string), 2);
+    assert_eq!(isolate.extract_text_slot(value, &context)?, Text::new("foo bar"));
+
+    isolate.move_value_out_from_nursery(value, context.get_slot_layout_token())?;
+    isolate.recycle_slot(value, &context)?;
+
+    assert_eq!(Arc::strong_count(&string), 1);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_interned_text() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let foo = isolate.intern_text("foo", region_id, &context)?;
+    let foo_2 = isolate.intern_text("foo", region_id, &context)?;
+    let bar = isolate.intern_text("bar", region_id, &context)?;
+
+    assert_eq!(foo, foo_2);
+    assert_ne!(foo, bar);
+    assert!(isolate.is_text_interned("foo"));
+    assert_eq!(isolate.extract_text_slot(foo, &context)?, Text::new("foo"));
+    assert!(isolate.recycle_slot(foo, &context).is_err());
+
+    assert!(isolate.release_interned_text("foo")?);
+    assert!(!isolate.release_interned_text("foo")?);
+    assert!(!isolate.is_text_interned("foo"));
+
+    isolate.recycle_slot(foo, &context)?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_list_operations() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let list = context.make_list([Value::make_cardinal(1)].to_vec(), &context)?;
+
+    isolate.list_push(*list, Value::make_cardinal(2), &context)?;
+    isolate.list_insert(*list, 0, Value::make_cardinal(0), &context)?;
+    assert_eq!(isolate.extract_list(*list, &context)?, [
+        Value::make_cardinal(0), Value::make_cardinal(1), Value::make_cardinal(2)
+    ].to_vec());
+
+    assert_eq!(*isolate.list_pop(*list, &context)?, Value::make_cardinal(2));
+    assert_eq!(*isolate.list_remove(*list, 0, &context)?, Value::make_cardinal(0));
+    assert_eq!(*isolate.list_remove(*list, 5, &context)?, Value::make_undefined());
+
+    isolate.list_set_at(*list, 1, Value::make_cardinal(3), &context)?;
+
+    let removes = isolate.list_splice(*list, 0, 1, [Value::make_cardinal(4)].to_vec(), &context)?;
+    assert_eq!(removes.len(), 1);
+    assert_eq!(*removes[0], Value::make_cardinal(1));
+    assert_eq!(isolate.extract_list(*list, &context)?, [Value::make_cardinal(4), Value::make_cardinal(3)].to_vec());
+
+    assert!(isolate.list_push(Value::make_cardinal(1), Value::make_null(), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_sort_list() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let list = context.make_list([
+        Value::make_cardinal(2), Value::make_cardinal(3), Value::make_cardinal(1)
+    ].to_vec(), &context)?;
+
+    isolate.sort_list(*list, |value, value_2| {
+        Ok(value_2.extract_cardinal(0).cmp(&value.extract_cardinal(0)))
+    }, &context)?;
+    assert_eq!(isolate.extract_list(*list, &context)?, [
+        Value::make_cardinal(3), Value::make_cardinal(2), Value::make_cardinal(1)
+    ].to_vec());
+
+    let index = isolate.binary_search_list(*list, |value| Ok(2.cmp(&value.extract_cardinal(0))), &context)?;
+    assert_eq!(index, Ok(1));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_sort_list_reentrance() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let object = context.gain_slot(PrimitiveType::Object, Value::make_null())?;
+    let region_id = object.get_region_id()?;
+    let list = context.make_list([
+        Value::make_cardinal(2), Value::make_cardinal(3), Value::make_cardinal(1)
+    ].to_vec(), &context)?;
+
+    // Moving slots locks the slot layout for writing within the comparator
+    let moved = Cell::new(object);
+    isolate.sort_list(*list, |value, value_2| {
+        moved.set(isolate.move_slot(moved.get(), region_id, &context)?);
+        Ok(value.extract_cardinal(0).cmp(&value_2.extract_cardinal(0)))
+    }, &context)?;
+    assert_eq!(isolate.extract_list(*list, &context)?, [
+        Value::make_cardinal(1), Value::make_cardinal(2), Value::make_cardinal(3)
+    ].to_vec());
+
+    let index = isolate.binary_search_list(*list, |value| {
+        moved.set(isolate.move_slot(moved.get(), region_id, &context)?);
+        Ok(value.extract_cardinal(0).cmp(&3))
+    }, &context)?;
+    assert_eq!(index, Ok(2));
+
+    assert!(isolate.sort_list(*list, |value, value_2| {
+        isolate.list_push(*list, Value::make_cardinal(4), &context)?;
+        Ok(value.extract_cardinal(0).cmp(&value_2.extract_cardinal(0)))
+    }, &context).is_err());
+
+    assert!(isolate.binary_search_list(*list, |value| {
+        isolate.list_push(*list, Value::make_cardinal(5), &context)?;
+        Ok(value.extract_cardinal(0).cmp(&3))
+    }, &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_tuple_fields() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let x = isolate.get_text_symbol("test", "x");
+    let y = isolate.get_text_symbol("test", "y");
+
+    let field_template = Arc::new(FieldTemplate::new(1));
+    field_template.add_symbol(x)?;
+    field_template.add_symbol(y)?;
+
+    let point = context.make_named_tuple(Value::make_null(), 1, &field_template, [
+        Value::make_cardinal(1), Value::make_cardinal(2)
+    ].to_vec(), &context)?;
+
+    assert_eq!(*isolate.get_tuple_field(*point, x, &context)?, Value::make_cardinal(1));
+    assert_eq!(*isolate.get_tuple_field(*point, y, &context)?, Value::make_cardinal(2));
+
+    let tuple = context.make_tuple(Value::make_null(), 1, [Value::make_cardinal(1)].to_vec(), &context)?;
+    assert_eq!(*isolate.get_tuple_field(*tuple, x, &context)?, Value::make_undefined());
+
+    assert!(isolate.get_tuple_field(Value::make_null(), x, &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_big_integer() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let big_int = BigInt::parse("-123456789012345678901234567890")?;
+
+    let value = isolate.create_big_integer(region_id, big_int.clone(), &context)?;
+
+    assert!(value.is_big_integer());
+    assert!(value.is_slotted());
+    assert_eq!(isolate.extract_big_integer(value, &context)?, big_int);
+    assert_eq!(isolate.extract_text(value, &context)?, "-123456789012345678901234567890");
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), isolate.get_big_integer_prototype());
+    assert!(isolate.set_prototype(value, Value::make_null(), &context).is_err());
+
+    assert!(isolate.extract_big_integer(Value::make_integer(1), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_decimal() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.create_decimal(region_id, Decimal::parse("19.99")?, &context)?;
+
+    assert_eq!(isolate.extract_decimal(value, &context)?, Decimal::parse("19.990")?);
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), isolate.get_decimal_prototype());
+
+    let bytes = isolate.create_bytes(region_id, &[1], &context)?;
+    assert!(isolate.extract_decimal(bytes, &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_timestamp() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let time = std::time::SystemTime::now();
+
+    let value = isolate.create_timestamp(region_id, Timestamp::from_system_time(time, 3600), &context)?;
+
+    let timestamp = isolate.extract_timestamp(value, &context)?;
+    assert_eq!(timestamp.to_system_time(), Some(time));
+    assert_eq!(timestamp.get_offset_seconds(), 3600);
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), isolate.get_timestamp_prototype());
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    assert!(isolate.extract_timestamp(object, &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_foreign() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+
+    let data = Arc::new(String::from("foo"));
+
+    assert!(isolate.get_foreign::<String>(value, 1, &context)?.is_none());
+
+    isolate.set_foreign(value, 1, data.clone(), None, &context)?;
+
+    assert_eq!(isolate.get_foreign::<String>(value, 1, &context)?.unwrap().as_str(), "foo");
+    assert!(isolate.get_foreign::<u32>(value, 1, &context).is_err());
+
+    isolate.move_value_out_from_nursery(value, context.get_slot_layout_token())?;
+    isolate.recycle_slot(value, &context)?;
+
+    assert_eq!(Arc::strong_count(&data), 1);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_native_function() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let function = isolate.create_native_function(region_id, |args, _context| {
+        let mut sum = 0;
+        for index in 0 .. args.len() {
+            sum += args.get(index).get_integer_data()?;
+        }
+        Ok(Value::make_integer(sum))
+    }, &context)?;
+
+    let result = isolate.call(function, Value::make_undefined(), vec!(Value::make_integer(1), Value::make_integer(2)), &context)?;
+    assert_eq!(result.get_value(), Value::make_integer(3));
+
+    let result = context.call(function, Value::make_null(), vec!(), &context)?;
+    assert_eq!(result.get_value(), Value::make_integer(0));
+
+    assert!(isolate.call(function, Value::make_null(), vec!(Value::make_null()), &context).is_err());
+
+    let this = isolate.create_native_function(region_id, |args, _context| {
+        Ok(args.get_this())
+    }, &context)?;
+    let result = isolate.call(this, function, vec!(), &context)?;
+    assert_eq!(result.get_value(), function);
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    assert!(isolate.call(value, Value::make_null(), vec!(), &context).is_err());
+    assert!(isolate.call(Value::make_integer(1), Value::make_null(), vec!(), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_json() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let text = r#" { "name": "foo\n\u00e9\ud83d\ude00", "list": [1, -2.5, true, null, {}], "big": 12345678901, "empty": [] } "#;
+
+    let value = isolate.parse_json(text, region_id, &context)?;
+    assert!(value.get_value().is_object());
+
+    let symbol = isolate.get_text_symbol(JSON_SYMBOL_SCOPE, "name");
+    let name = isolate.get_own_property(value.get_value(), value.get_value(), symbol, None, &context)?;
+    assert_eq!(&isolate.extract_text_slot(name.get_value(), &context)?.to_string(), "foo\n\u{e9}\u{1f600}");
+
+    assert_eq!(&isolate.to_json(value.get_value(), &context)?, r#"{"big":12345678901,"empty":[],"list":[1,-2.5,true,null,{}],"name":"foo\né😀"}"#);
+
+    assert!(isolate.parse_json("[1, 2", region_id, &context).is_err());
+    assert!(isolate.parse_json("[1] 2", region_id, &context).is_err());
+    assert!(isolate.parse_json("01", region_id, &context).is_err());
+    assert!(isolate.parse_json("\"\\ud800\"", region_id, &context).is_err());
+
+    let list = isolate.parse_json("[[]]", region_id, &context)?;
+    let inner = isolate.extract_list(list.get_value(), &context)?[0];
+    isolate.list_push(inner, list.get_value(), &context)?;
+    assert!(isolate.to_json(list.get_value(), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_serialization() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let name = isolate.get_text_symbol("test", "name");
+    let shared = isolate.get_text_symbol("test", "shared");
+    let myself = isolate.get_text_symbol("test", "self");
+
+    let object = isolate.parse_json(r#"{"text": "foo"}"#, region_id, &context)?;
+    let object = object.get_value();
+    let big = isolate.create_big_integer(region_id, BigInt::from_i64(123456789012), &context)?;
+    isolate.set_own_property(object, object, isolate.get_text_symbol("test", "big"), big, &context)?;
+    let list = isolate.parse_json("[1, 2.5, null]", region_id, &context)?;
+    let list = list.get_value();
+    let tuple = context.make_tuple(isolate.get_tuple_prototype(), 7, vec!(list, Value::make_symbol(name)), &context)?;
+
+    isolate.set_own_property(object, object, name, tuple.get_value(), &context)?;
+    isolate.set_own_property(object, object, shared, list, &context)?;
+    isolate.set_own_property(object, object, myself, object, &context)?;
+    isolate.list_push(list, Value::make_undefined(), &context)?;
+
+    for format in [SerializationFormat::Cbor, SerializationFormat::MessagePack].iter().copied() {
+
+        let mut output = Vec::new();
+        isolate.serialize(object, format, &mut output, &context)?;
+
+        let result = isolate.deserialize(&mut output.as_slice(), format, region_id, &context)?;
+        let result = result.get_value();
+        assert_ne!(result, object);
+
+        assert_eq!(isolate.get_prototype(result, &context)?.get_value(), isolate.get_object_prototype());
+        assert_eq!(isolate.get_own_property(result, result, myself, None, &context)?.get_value(), result);
+
+        let tuple = isolate.get_own_property(result, result, name, None, &context)?;
+        let elements = isolate.extract_tuple(tuple.get_value(), &context)?;
+        assert_eq!(elements[1], Value::make_symbol(name));
+        assert_eq!(elements[0], isolate.get_own_property(result, result, shared, None, &context)?.get_value());
+
+        let elements = isolate.extract_list(elements[0], &context)?;
+        assert_eq!(elements.len(), 4);
+        assert_eq!(elements[0], Value::make_integer(1));
+        assert!(elements[3].is_undefined());
+
+        let text = isolate.get_own_property(result, result, isolate.get_text_symbol(JSON_SYMBOL_SCOPE, "text"), None, &context)?;
+        assert_eq!(&isolate.extract_text_slot(text.get_value(), &context)?.to_string(), "foo");
+        let big = isolate.get_own_property(result, result, isolate.get_text_symbol("test", "big"), None, &context)?;
+        assert_eq!(isolate.extract_big_integer(big.get_value(), &context)?, BigInt::from_i64(123456789012));
+
+        assert!(isolate.deserialize(&mut &output[.. output.len() - 1], format, region_id, &context).is_err());
+
+    }
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_transfer() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    // Make symbol IDs differ between the isolates
+    isolate.get_text_symbol("test", "padding");
+    let name = isolate.get_text_symbol("test", "name");
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    let text = context.make_text("foo", &context)?;
+    isolate.set_own_property(object, object, name, text.get_value(), &context)?;
+
+    let data = Arc::new(String::from("host"));
+    isolate.set_transferable_foreign(object, 1, data.clone(), &context)?;
+    isolate.set_foreign(object, 2, Arc::new(42u32), None, &context)?;
+
+    let packet = isolate.export_subgraph(object, &context)?;
+
+    let packet = std::thread::spawn(move || packet).join().unwrap();
+
+    let isolate_2 = Arc::new(Isolate::create()?);
+
+    let context_2: Box<dyn Context> = Box::new(TestContext2::new(isolate_2.clone()));
+
+    let region_id_2 = isolate_2.create_region()?;
+
+    let result = isolate_2.import_subgraph(&packet, region_id_2, &context_2)?;
+    let result = result.get_value();
+
+    let name_2 = isolate_2.get_text_symbol("test", "name");
+    assert_ne!(name, name_2);
+
+    let text = isolate_2.get_own_property(result, result, name_2, None, &context_2)?;
+    assert_eq!(&isolate_2.extract_text_slot(text.get_value(), &context_2)?.to_string(), "foo");
+    assert_eq!(isolate_2.get_prototype(result, &context_2)?.get_value(), isolate_2.get_object_prototype());
+
+    let transferred = isolate_2.get_foreign::<String>(result, 1, &context_2)?.unwrap();
+    assert!(Arc::ptr_eq(&transferred, &data));
+    assert!(isolate_2.get_foreign::<u32>(result, 2, &context_2)?.is_none());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_property_path() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let foo = isolate.get_text_symbol("test", "foo");
+    let bar = isolate.get_text_symbol("test", "bar");
+    let baz = isolate.get_text_symbol("test", "baz");
+
+    let root = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    let child = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, context.get_slot_layout_token())?;
+    isolate.set_own_property(root, root, foo, child, &context)?;
+    isolate.set_own_property(prototype, prototype, bar, Value::make_integer(1), &context)?;
+
+    assert_eq!(isolate.get_property_path(root, &[], &context)?.get_value(), root);
+    assert_eq!(isolate.get_property_path(root, &[foo, bar], &context)?.get_value(), Value::make_integer(1));
+    assert!(isolate.get_property_path(root, &[foo, baz], &context)?.is_undefined());
+    assert!(isolate.get_property_path(root, &[baz, bar], &context).is_err());
+
+    isolate.set_property_path(root, &[foo, bar], Value::make_integer(2), &context)?;
+    assert_eq!(isolate.get_property_path(root, &[foo, bar], &context)?.get_value(), Value::make_integer(2));
+    assert_eq!(isolate.get_own_property(prototype, prototype, bar, None, &context)?.get_value(), Value::make_integer(1));
+
+    assert!(isolate.set_property_path(root, &[baz, bar], Value::make_null(), &context).is_err());
+    assert!(isolate.set_property_path(root, &[], Value::make_null(), &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_symbol_sweep() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    isolate.set_symbol_scope_collectable("stable", false);
+
+    let foo = isolate.get_text_symbol("test", "foo");
+    let bar = isolate.get_text_symbol("stable", "bar");
+
+    let cached_foo = SymbolCacheEntry::new("test", "foo");
+    let cached_bar = SymbolCacheEntry::new("stable", "bar");
+    assert_eq!(isolate.get_text_symbol_cached(&cached_foo), foo);
+    assert_eq!(isolate.get_text_symbol_cached(&cached_foo), foo);
+    assert_eq!(cached_foo.get_symbol(isolate.get_symbol_generation()), Some(foo));
+    assert_eq!(isolate.get_text_symbol_cached(&cached_bar), bar);
+
+    let generation = isolate.get_symbol_generation();
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    isolate.set_own_property(object, object, foo, Value::make_integer(1), &context)?;
+    isolate.set_own_property(object, object, bar, Value::make_integer(2), &context)?;
+    isolate.delete_own_property(object, object, foo, &context)?;
+    isolate.delete_own_property(object, object, bar, &context)?;
+
+    isolate.set_prototype(object, Value::make_null(), &context)?;
+
+    assert_eq!(isolate.sweep_symbols()?, 1);
+    assert_ne!(isolate.get_symbol_generation(), generation);
+    assert!(cached_foo.get_symbol(isolate.get_symbol_generation()).is_none());
+    assert!(isolate.resolve_symbol_info(foo).is_err());
+    assert!(isolate.resolve_symbol_info(bar).is_ok());
+    assert!(isolate.resolve_symbol_info(isolate.get_prototype_symbol()).is_ok());
+    assert_eq!(isolate.sweep_symbols()?, 0);
+
+    assert_eq!(isolate.get_text_symbol_cached(&cached_bar), bar);
+    assert_ne!(isolate.get_text_symbol_cached(&cached_foo), foo);
+
+    let foo_2 = isolate.get_text_symbol("test", "foo");
+    assert_ne!(foo_2, foo);
+    assert_eq!(isolate.resolve_symbol_info(foo_2)?.get_text().unwrap().as_str(), "foo");
+    assert_eq!(isolate.get_text_symbol("stable", "bar"), bar);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_value_symbol_roots() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let roots_count = isolate.count_roots();
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+
+    let symbol = isolate.get_value_symbol("test", value);
+    assert_eq!(isolate.get_value_symbol("test", value), symbol);
+    assert_eq!(isolate.count_roots(), roots_count + 1);
+
+    let value_2 = isolate.move_slot(value, region_id_2, &context)?;
+
+    assert_eq!(isolate.resolve_symbol_info(symbol)?.get_value(), Some(value_2));
+    assert_eq!(isolate.get_value_symbol("test", value_2), symbol);
+    assert!(isolate.recycle_slot(value_2, &context).is_err());
+
+    let holder = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    isolate.set_own_property(holder, holder, symbol, Value::make_integer(1), &context)?;
+    isolate.delete_own_property(holder, holder, symbol, &context)?;
+
+    assert_eq!(isolate.sweep_symbols()?, 1);
+    assert!(isolate.resolve_symbol_info(symbol).is_err());
+    assert_eq!(isolate.count_roots(), roots_count);
+
+    isolate.recycle_slot(value_2, &context)?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_well_known_symbols() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let well_known_symbols = isolate.get_well_known_symbols();
+
+    assert_eq!(well_known_symbols.get_prototype(), isolate.get_prototype_symbol());
+    assert_eq!(well_known_symbols.get_iterator(), isolate.get_text_symbol("isolate.well_known", "iterator"));
+    assert_eq!(well_known_symbols.get_hash(), isolate.get_text_symbol("isolate.well_known", "hash"));
+    assert_ne!(well_known_symbols.get_call(), isolate.get_text_symbol("test", "call"));
+
+    let symbol_info = isolate.resolve_symbol_info(well_known_symbols.get_to_text())?;
+    assert_eq!(symbol_info.get_symbol_scope().as_str(), "isolate.well_known");
+    assert_eq!(symbol_info.get_text().unwrap().as_str(), "to_text");
+
+    isolate.sweep_symbols()?;
+    assert!(isolate.resolve_symbol_info(well_known_symbols.get_equals()).is_ok());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_symbol_registry() -> Result<(), Error> {
+
+    let symbol_registry = Arc::new(SymbolRegistry::new());
+
+    let isolate = Arc::new(Isolate::create_with_symbol_registry(symbol_registry.clone())?);
+    let isolate_2 = Arc::new(Isolate::create_with_symbol_registry(symbol_registry.clone())?);
+    let isolate_3 = Arc::new(Isolate::create()?);
+
+    assert!(isolate.get_symbol_registry().is_some());
+    assert!(isolate_3.get_symbol_registry().is_none());
+
+    assert_eq!(isolate.get_prototype_symbol(), isolate_2.get_prototype_symbol());
+    assert_eq!(isolate.get_well_known_symbols().get_iterator(), isolate_2.get_well_known_symbols().get_iterator());
+
+    let foo = isolate.get_text_symbol("test", "foo");
+    let bar = isolate_2.get_text_symbol("test", "bar");
+    assert_eq!(isolate_2.get_text_symbol("test", "foo"), foo);
+    assert_eq!(isolate.get_text_symbol("test", "bar"), bar);
+    assert_eq!(isolate_2.resolve_symbol_info(foo)?.get_text().unwrap().as_str(), "foo");
+
+    let value_symbol = isolate.get_value_symbol("test", Value::make_integer(1));
+    assert_ne!(value_symbol, foo);
+    assert_ne!(value_symbol, bar);
+    assert!(isolate_2.resolve_symbol_info(value_symbol).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_shared_region() -> Result<(), Error> {
+
+    let symbol_registry = Arc::new(SymbolRegistry::new());
+
+    let isolate = Arc::new(Isolate::create_with_symbol_registry(symbol_registry.clone())?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_shared_region()?;
+    assert!(!isolate.list_region_ids()?.contains(&region_id));
+    assert_eq!(isolate.list_shared_region_ids(), [region_id].to_vec());
+
+    let foo = isolate.get_text_symbol("test", "foo");
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    let child = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    isolate.set_own_property(object, object, foo, child, &context)?;
+
+    let local_region_id = isolate.create_region()?;
+    let local = isolate.gain_slot(local_region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    isolate.set_own_property(child, child, foo, local, &context)?;
+    assert!(isolate.share_region(region_id, &context).is_err());
+    isolate.set_own_property(child, child, foo, Value::make_integer(1), &context)?;
+
+    let shared_region = isolate.share_region(region_id, &context)?;
+    assert_eq!(shared_region.get_region_id(), region_id);
+    assert!(isolate.is_region_shared(region_id)?);
+    assert!(!isolate.is_region_shared(local_region_id)?);
+
+    assert!(isolate.set_own_property(child, child, foo, Value::make_integer(2), &context).is_err());
+    assert!(isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token()).is_err());
+    assert!(isolate.move_slot(child, local_region_id, &context).is_err());
+
+    let isolate_2 = Arc::new(Isolate::create_with_symbol_registry(symbol_registry.clone())?);
+    let context_2: Box<dyn Context> = Box::new(TestContext2::new(isolate_2.clone()));
+
+    let isolate_3 = Arc::new(Isolate::create()?);
+    assert!(isolate_3.attach_shared_region(&shared_region).is_err());
+
+    isolate_2.attach_shared_region(&shared_region)?;
+    assert!(isolate_2.attach_shared_region(&shared_region).is_err());
+
+    let foo_2 = isolate_2.get_text_symbol("test", "foo");
+    assert_eq!(isolate_2.get_property_path(object, &[foo_2, foo_2], &context_2)?.get_value(), Value::make_integer(1));
+    assert_eq!(isolate_2.get_prototype(child, &context_2)?.get_value(), isolate_2.get_object_prototype());
+
+    let region_id_2 = isolate_2.create_region()?;
+    let holder = isolate_2.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), context_2.get_slot_layout_token())?;
+    isolate_2.set_own_property(holder, holder, foo_2, object, &context_2)?;
+    isolate_2.delete_own_property(holder, holder, foo_2, &context_2)?;
+
+    isolate_2.mark_as_white(child)?;
+    assert!(isolate_2.is_black(child)?);
+    assert!(!isolate_2.mark_as_gray(child)?);
+
+    assert!(isolate_2.is_direct_value_alive(child, &context_2)?);
+    assert_eq!(isolate_2.get_own_property(child, child, foo_2, None, &context_2)?.get_value(), Value::make_integer(1));
+    assert_eq!(isolate.get_own_property(child, child, foo, None, &context)?.get_value(), Value::make_integer(1));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_fork() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let foo = isolate.get_text_symbol("test", "foo");
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    isolate.add_root(object, context.get_slot_layout_token())?;
+    isolate.set_own_property(object, object, foo, Value::make_integer(1), &context)?;
+
+    let list = *context.make_list([Value::make_integer(1)].to_vec(), &context)?;
+    isolate.add_root(list, context.get_slot_layout_token())?;
+
+    let isolate_2 = Arc::new(isolate.fork()?);
+    let context_2: Box<dyn Context> = Box::new(TestContext2::new(isolate_2.clone()));
+
+    assert!(isolate.is_region_copy_on_write(region_id));
+    assert!(isolate_2.is_region_copy_on_write(region_id));
+
+    let foo_2 = isolate_2.get_text_symbol("test", "foo");
+    assert_eq!(foo_2, foo);
+    assert_eq!(isolate_2.get_own_property(object, object, foo_2, None, &context_2)?.get_value(), Value::make_integer(1));
+
+    isolate_2.set_own_property(object, object, foo_2, Value::make_integer(2), &context_2)?;
+    assert!(!isolate_2.is_region_copy_on_write(region_id));
+    assert!(!isolate.is_region_copy_on_write(region_id));
+    assert_eq!(isolate_2.get_own_property(object, object, foo_2, None, &context_2)?.get_value(), Value::make_integer(2));
+    assert_eq!(isolate.get_own_property(object, object, foo, None, &context)?.get_value(), Value::make_integer(1));
+
+    isolate.set_own_property(object, object, foo, Value::make_integer(3), &context)?;
+    assert_eq!(isolate_2.get_own_property(object, object, foo_2, None, &context_2)?.get_value(), Value::make_integer(2));
+
+    isolate_2.list_push(list, Value::make_integer(2), &context_2)?;
+    assert_eq!(isolate_2.extract_list(list, &context_2)?.len(), 2);
+    assert_eq!(isolate.extract_list(list, &context)?.len(), 1);
+
+    let object_2 = isolate_2.gain_slot(region_id, PrimitiveType::Object, isolate_2.get_object_prototype(), context_2.get_slot_layout_token())?;
+    assert!(isolate_2.is_direct_value_alive(object_2, &context_2)?);
+    assert!(!isolate.is_direct_value_alive(object_2, &context)?);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_fork_roots() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+
+    let _root = isolate.add_root(value, context.get_slot_layout_token())?;
+    isolate.add_persistent(value_2, context.get_slot_layout_token())?;
+    let symbol = isolate.get_value_symbol("test", value_3);
+    let _root_3 = isolate.add_root(value_3, context.get_slot_layout_token())?;
+    let text = isolate.intern_text("foo", region_id, &context)?;
+    assert_eq!(isolate.pin_count(value_3), 2);
+
+    let isolate_2 = Arc::new(isolate.fork()?);
+    let context_2: Box<dyn Context> = Box::new(TestContext2::new(isolate_2.clone()));
+
+    assert_eq!(isolate_2.pin_count(value), 0);
+    assert_eq!(isolate_2.pin_count(value_2), 0);
+    assert_eq!(isolate_2.pin_count(value_3), 1);
+    assert_eq!(isolate_2.pin_count(text), 1);
+    assert_eq!(isolate_2.count_persistents(), 0);
+
+    let region_id_2 = isolate_2.create_region()?;
+    let holder = isolate_2.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), context_2.get_slot_layout_token())?;
+    isolate_2.set_own_property(holder, holder, symbol, Value::make_integer(1), &context_2)?;
+    isolate_2.delete_own_property(holder, holder, symbol, &context_2)?;
+
+    let roots_count = isolate_2.count_roots();
+    assert_eq!(isolate_2.sweep_symbols()?, 1);
+    assert!(isolate_2.resolve_symbol_info(symbol).is_err());
+    assert!(isolate_2.release_interned_text("foo")?);
+    assert_eq!(isolate_2.count_roots(), roots_count - 2);
+    assert_eq!(isolate_2.pin_count(value_3), 0);
+    assert_eq!(isolate_2.pin_count(text), 0);
+
+    assert_eq!(isolate.pin_count(value_3), 2);
+    assert_eq!(isolate.pin_count(text), 1);
+    assert!(isolate.resolve_symbol_info(symbol).is_ok());
+
+    let _root_2 = isolate_2.add_root(value, context_2.get_slot_layout_token())?;
+    assert_eq!(isolate_2.pin_count(value), 1);
+    assert!(isolate_2.is_region_copy_on_write(region_id));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_retired_field_shortcuts() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+
+    let field_template = Arc::new(FieldTemplate::new(1));
+    let field_shortcuts = Arc::new(FieldShortcuts::new(field_template.clone()));
+    let field_shortcuts_2 = Arc::new(FieldShortcuts::new(field_template));
+
+    isolate.update_field_shortcuts(value, field_shortcuts.clone(), &context)?;
+    isolate.update_field_shortcuts(value, field_shortcuts_2, &context)?;
+    isolate.clear_field_shortcuts(value, &context)?;
+    assert_eq!(Arc::strong_count(&field_shortcuts), 2);
+
+    assert_eq!(isolate.release_retired_field_shortcuts(context.get_slot_layout_token()), 2);
+    assert_eq!(Arc::strong_count(&field_shortcuts), 1);
+    assert_eq!(isolate.release_retired_field_shortcuts(context.get_slot_layout_token()), 0);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_large_value() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let payload = Arc::new(Bytes::new(&[7u8; 1 << 16]));
+    let value = isolate.create_large_value(PrimitiveType::Object, isolate.get_object_prototype(), payload, &context)?;
+
+    let region_id = value.get_region_id()?;
+    assert_eq!(isolate.get_region_kind(region_id)?, RegionKind::Large);
+    assert!(!isolate.is_region_protected(region_id)?);
+    assert!(isolate.is_region_full(region_id)?);
+    assert!(!isolate.could_region_gain_slot_quickly(region_id));
+    assert!(isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token()).is_err());
+    assert!(isolate.get_internal_slot(value, 0, &context)?.is_some());
+
+    let region_id_2 = isolate.create_region()?;
+    assert_eq!(isolate.get_region_kind(region_id_2)?, RegionKind::Standard);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_spaces() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let builtin_region_id = isolate.get_object_prototype().get_region_id()?;
+    assert_eq!(isolate.get_region_space(builtin_region_id)?, Space::Immortal);
+    assert!(!isolate.get_region_space_policy(builtin_region_id)?.is_sweepable());
+
+    let region_id = isolate.create_region()?;
+    assert_eq!(isolate.get_region_space(region_id)?, Space::New);
+
+    let old_region_id = isolate.create_region_in_space(Space::Old)?;
+    assert_eq!(isolate.list_region_ids_in_space(Space::Old)?, [old_region_id].to_vec());
+    isolate.unprotect_region(old_region_id)?;
+
+    let value = isolate.gain_slot_in_space(Space::Old, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    assert_eq!(value.get_region_id()?, old_region_id);
+
+    isolate.set_region_space(region_id, Space::Old)?;
+    assert_eq!(isolate.get_region_space(region_id)?, Space::Old);
+    assert!(isolate.list_region_ids_in_space(Space::New)?.is_empty());
+
+    assert_eq!(isolate.get_space_policy(Space::Old), SpacePolicy::for_space(Space::Old));
+    isolate.set_space_policy(Space::Old, SpacePolicy::new(false, false, false));
+    assert!(!isolate.get_region_space_policy(old_region_id)?.is_recyclable());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_cold_regions() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let storage = Arc::new(MemoryColdRegionStorage::new());
+    isolate.set_cold_region_storage(storage.clone())?;
+
+    let region_id = isolate.create_region()?;
+    isolate.unprotect_region(region_id)?;
+
+    let symbol = context.get_text_symbol("test", "foo");
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    let _object = Pinned::new(&context, object)?;
+    let text = isolate.gain_slot(region_id, PrimitiveType::Text, isolate.get_text_prototype(), context.get_slot_layout_token())?;
+    isolate.set_internal_slot(text, 0, Arc::new(Text::new("bar")), &context)?;
+    context.set_own_property(object, symbol, text, &context)?;
+    for value in isolate.list_values_in_nursery() {
+        isolate.move_value_out_from_nursery(value, context.get_slot_layout_token())?;
+    }
+
+    isolate.page_out_region(region_id)?;
+    assert!(isolate.is_region_cold(region_id));
+    assert_eq!(isolate.list_cold_region_ids(), [region_id].to_vec());
+    assert!(!isolate.list_region_ids()?.contains(&region_id));
+    assert!(storage.get_stored_size() > 0);
+
+    let region_id_2 = isolate.create_region()?;
+    assert_ne!(region_id_2, region_id);
+
+    let value = context.get_own_property(object, symbol, None, &context)?;
+    assert_eq!(value.get_value(), text);
+    assert!(!isolate.is_region_cold(region_id));
+    assert_eq!(storage.get_stored_size(), 0);
+    assert_eq!(isolate.extract_text(text, &context)?, "bar");
+
+    let function = isolate.create_native_function(region_id_2, |_args, _context| {
+        Ok(Value::make_null())
+    }, &context)?;
+    let _function = Pinned::new(&context, function)?;
+    isolate.unprotect_region(region_id_2)?;
+    assert!(isolate.page_out_region(region_id_2).is_err());
+    assert!(!isolate.is_region_cold(region_id_2));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_recycle_slots() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let mut values = Vec::new();
+    for region_id in [region_id, region_id_2, region_id] {
+        let value = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+        isolate.move_value_out_from_nursery(value, context.get_slot_layout_token())?;
+        values.push(value);
+    }
+
+    let nursery_value = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    assert!(isolate.recycle_slots(&[values[0], nursery_value], &context).is_err());
+    assert!(isolate.is_direct_value_alive(values[0], &context)?);
+
+    assert!(isolate.recycle_slots(&[values[0], values[0]], &context).is_err());
+
+    let pinned = Pinned::new(&context, values[1])?;
+    assert!(isolate.recycle_slots(&values, &context).is_err());
+    drop(pinned);
+
+    isolate.recycle_slots(&values, &context)?;
+    for value in values.iter() {
+        assert!(!isolate.is_direct_value_alive(*value, &context)?);
+    }
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_region_tags() -> Result<(), Error> {
+
+    let isolate = Isolate::create()?;
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    assert!(isolate.get_region_tag(region_id)?.is_none());
+    assert!(isolate.set_region_tag(1000, Arc::new("tenant-a")).is_err());
+
+    isolate.set_region_tag(region_id, Arc::new("tenant-a"))?;
+    isolate.set_region_tag(region_id_2, Arc::new("tenant-b"))?;
+
+    let tag = isolate.get_region_tag(region_id)?.unwrap();
+    assert_eq!(tag.downcast_ref::<&str>(), Some(&"tenant-a"));
+
+    let ids = isolate.list_tagged_region_ids(|tag| tag.downcast_ref::<&str>() == Some(&"tenant-b"));
+    assert_eq!(ids, vec!(region_id_2));
+
+    let forked = isolate.fork()?;
+    assert!(forked.get_region_tag(region_id_2)?.is_some());
+
+    isolate.unprotect_region(region_id_2)?;
+    isolate.recycle_region(region_id_2)?;
+    assert_eq!(isolate.list_tagged_region_ids(|_tag| true), vec!(region_id));
+
+    assert!(isolate.clear_region_tag(region_id)?.is_some());
+    assert!(isolate.get_region_tag(region_id)?.is_none());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_region_cache() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let foo = context.get_text_symbol("test", "foo");
+
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let _object = Pinned::new(&context, object)?;
+
+    context.set_own_property(object, foo, Value::make_integer(1), &context)?;
+    assert_eq!(context.get_region_cache().unwrap().get_size(), 1);
+
+    let region_id = isolate.create_region()?;
+    isolate.unprotect_region(region_id)?;
+    isolate.recycle_region(region_id)?;
+
+    assert_eq!(context.get_own_property(object, foo, None, &context)?.get_value(), Value::make_integer(1));
+    assert_eq!(context.get_region_cache().unwrap().get_size(), 1);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_reference_map_stats() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let target = context.allocate(PrimitiveType::Object, Value::make_null())?;
+    let _target = Pinned::new(&context, target)?;
+    let object = context.allocate(PrimitiveType::Object, Value::make_null())?;
+    let _object = Pinned::new(&context, object)?;
+
+    let stats = isolate.get_reference_map_stats();
+
+    for name in ["foo", "bar", "baz"] {
+        context.set_own_property(object, context.get_text_symbol("test", name), target, &context)?;
+    }
+
+    let new_stats = isolate.get_reference_map_stats();
+    assert_eq!(new_stats.get_map_count(), stats.get_map_count() + 1);
+    assert_eq!(new_stats.get_inline_map_count(), stats.get_inline_map_count() + 1);
+    assert_eq!(new_stats.get_referrer_count(), stats.get_referrer_count() + 1);
+    assert_eq!(new_stats.get_reference_count(), stats.get_reference_count() + 3);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_update_value_references() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let object = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+    let mut targets = Vec::new();
+    for region_id in [region_id, region_id_2, region_id_2] {
+        targets.push(isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?);
+    }
+
+    let stats = isolate.get_reference_map_stats();
+
+    context.update_value_references(object, &targets, &[])?;
+    let nursery = isolate.list_values_in_nursery();
+    assert!(targets.iter().all(|target| !nursery.contains(target)));
+    assert!(nursery.contains(&object));
+
+    let new_stats = isolate.get_reference_map_stats();
+    assert_eq!(new_stats.get_map_count(), stats.get_map_count() + 3);
+    assert_eq!(new_stats.get_reference_count(), stats.get_reference_count() + 3);
+
+    context.update_value_references(object, &targets[1 ..], &targets[.. 1])?;
+    assert_eq!(isolate.get_reference_map_stats().get_reference_count(), stats.get_reference_count() + 4);
+
+    context.update_value_references(object, &[], &[targets[1], targets[1], targets[2], targets[2]])?;
+    assert_eq!(isolate.get_reference_map_stats().get_reference_count(), stats.get_reference_count());
+
+    assert!(context.update_value_references(object, &[], &targets[.. 1]).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_direct_prototype() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let prototype_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+
+    let prototype_symbol = isolate.get_prototype_symbol();
+
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), prototype);
+    assert_eq!(isolate.get_own_property(value, value, prototype_symbol, None, &context)?.get_value(), prototype);
+    assert!(isolate.has_own_property(value, value, prototype_symbol, &context)?);
+
+    isolate.set_prototype(value, prototype_2, &context)?;
+    assert_eq!(isolate.get_own_property(value, value, prototype_symbol, None, &context)?.get_value(), prototype_2);
+
+    isolate.set_own_property(value, value, prototype_symbol, prototype, &context)?;
+    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), prototype);
+    assert_eq!(isolate.get_own_property_ignore_slot_trap(value, value, prototype_symbol, &context)?.get_value(), prototype);
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(prototype, prototype, symbol, Value::make_integer(1), &context)?;
+    assert_eq!(isolate.get_property(value, symbol, None, &context)?.get_value(), Value::make_integer(1));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_move_slots() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = context.get_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), layout_token)?;
+    let holder = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), layout_token)?;
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(holder, holder, symbol, value_2, &context)?;
+
+    let root = isolate.add_root(value, layout_token)?;
+    let weak_root = isolate.add_weak_root(value_2, None, layout_token)?;
+    let value_symbol = isolate.get_value_symbol("test", value_2);
+
+    let moved = isolate.move_slots(&[value, value_2], region_id_2, &context)?;
+    assert_eq!(moved.len(), 2);
+    assert_eq!(moved[0].get_region_id()?, region_id_2);
+    assert_eq!(moved[1].get_region_id()?, region_id_2);
+
+    assert_eq!(root.get_value(), moved[0]);
+    assert_eq!(weak_root.get_value(), Some(moved[1]));
+    assert_eq!(isolate.resolve_symbol_info(value_symbol)?.get_value(), Some(moved[1]));
+    assert_eq!(isolate.resolve_real_value(value_2, layout_token)?, moved[1]);
+    assert_eq!(isolate.get_own_property(holder, holder, symbol, None, &context)?.get_value(), moved[1]);
+
+    assert!(isolate.move_slots(&[holder, value], region_id_2, &context).is_err());
+    assert!(!isolate.is_direct_value_alive(holder, &context)?);
+
+    isolate.remove_root(&root)?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_move_slot_by_copying() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = context.get_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), layout_token)?;
+    let holder = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), layout_token)?;
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(value, value, symbol, Value::make_integer(42), &context)?;
+    isolate.set_own_property(holder, holder, symbol, value, &context)?;
+
+    let root = isolate.add_root(value, layout_token)?;
+
+    let value_2 = isolate.move_slot_by_copying(value, region_id_2, &context)?;
+    assert_eq!(value_2.get_region_id()?, region_id_2);
+    assert_eq!(root.get_value(), value_2);
+    assert!(!isolate.is_direct_value_alive(value, &context)?);
+    assert_eq!(isolate.get_own_property(value_2, value_2, symbol, None, &context)?.get_value(), Value::make_integer(42));
+    assert_eq!(isolate.get_own_property(holder, holder, symbol, None, &context)?.get_value(), value_2);
+
+    isolate.remove_root(&root)?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_custom_errors() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    isolate.register_custom_error(1, "QuotaExceeded", "Quota exceeded")?;
+    assert_eq!(isolate.get_custom_error_info(1).unwrap().get_default_message(), "Quota exceeded");
+
+    let region_id = isolate.create_region()?;
+    let function = isolate.create_native_function(region_id, |_args, context| {
+        Err(context.make_custom_error(1, Some("Too many slots"))?)
+    }, &context)?;
+
+    match isolate.call(function, Value::make_undefined(), vec!(), &context) {
+        Err(error) => {
+            assert!(matches!(error.get_error_type(), Custom(1)));
+            assert_eq!(error.get_message(), "Too many slots");
+        },
+        Ok(_) => panic!("Custom error expected")
+    }
+
+    assert!(isolate.fork()?.get_custom_error_info(1).is_some());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_fallible_extraction() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let text = isolate.gain_slot(region_id, PrimitiveType::Text, isolate.get_text_prototype(), context.get_slot_layout_token())?;
+    let _text = Pinned::new(&context, text)?;
+    assert!(matches!(isolate.extract_text(text, &context).unwrap_err().get_error_type(), InternalSlotNotFound));
+    isolate.set_internal_slot(text, 0, Arc::new(List::new(text, vec!())), &context)?;
+    assert!(matches!(isolate.extract_text(text, &context).unwrap_err().get_error_type(), TypeNotMatch));
+
+    let list = isolate.gain_slot(region_id, PrimitiveType::List, isolate.get_list_prototype(), context.get_slot_layout_token())?;
+    let _list = Pinned::new(&context, list)?;
+    assert!(matches!(isolate.extract_list(list, &context).unwrap_err().get_error_type(), InternalSlotNotFound));
+    isolate.set_internal_slot(list, 0, Arc::new(List::new(list, vec!(Value::make_integer(1)))), &context)?;
+    assert_eq!(isolate.extract_list(list, &context)?, vec!(Value::make_integer(1)));
+
+    assert!(matches!(isolate.extract_list(Value::make_integer(1), &context).unwrap_err().get_error_type(), TypeNotMatch));
+    assert_eq!(isolate.extract_text(Value::make_integer(-1), &context)?, "-1");
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_own_property_entry() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.set_own_property(prototype, prototype, symbol, Value::make_integer(1), &context)?;
+
+    assert!(isolate.get_own_property_entry(value, value, symbol, None, &context)?.is_none());
+    assert_eq!(isolate.get_property(value, symbol, None, &context)?.get_value(), Value::make_integer(1));
+
+    isolate.set_own_property(value, value, symbol, Value::make_undefined(), &context)?;
+    let entry = isolate.get_own_property_entry(value, value, symbol, None, &context)?;
+    assert!(entry.unwrap().is_undefined());
+    assert!(isolate.get_property(value, symbol, None, &context)?.is_undefined());
+
+    assert!(isolate.get_own_property_entry(Value::make_integer(1), Value::make_integer(1), symbol, None, &context)?.is_none());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_prototype_cycle() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+
+    let error = isolate.set_prototype(prototype, value, &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), PrototypeCycle));
+    assert!(isolate.set_prototype(value, value, &context).is_err());
+    let prototype_symbol = isolate.get_prototype_symbol();
+    assert!(isolate.set_own_property(prototype, prototype, prototype_symbol, value, &context).is_err());
+    assert_eq!(isolate.get_prototype(prototype, &context)?.get_value(), Value::make_null());
+
+    let symbol = isolate.get_text_symbol("test", "test");
+    assert!(!isolate.has_property(value, symbol, &context)?);
+
+    isolate.set_prototype_chain_limit(1);
+    let error = isolate.get_property(value, symbol, None, &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), PrototypeCycle));
+    assert!(isolate.list_property_symbols(value, &context).is_err());
+
+    isolate.set_prototype_chain_limit(2);
+    assert!(isolate.get_property(value, symbol, None, &context)?.is_undefined());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_property_order() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+
+    let prototype_symbol = isolate.get_prototype_symbol();
+    let symbols: Vec<Symbol> = (0 .. 12).map(|index| isolate.get_text_symbol("test", &format!("field{}", index))).collect();
+
+    for symbol in symbols.iter().rev() {
+        isolate.set_own_property(value, value, *symbol, Value::make_integer(1), &context)?;
+    }
+    isolate.set_own_property(prototype, prototype, symbols[0], Value::make_integer(1), &context)?;
+    isolate.set_own_property(prototype, prototype, symbols[11], Value::make_integer(1), &context)?;
+
+    isolate.delete_own_property(value, value, symbols[5], &context)?;
+    isolate.set_own_property(value, value, symbols[5], Value::make_integer(1), &context)?;
+    isolate.delete_own_property(value, value, symbols[0], &context)?;
+
+    let mut expected = vec!(prototype_symbol);
+    expected.extend((1 .. 12).rev().filter(|index| *index != 5).map(|index| symbols[index]));
+    expected.push(symbols[5]);
+    assert_eq!(isolate.list_own_property_symbols(value, value, &context)?, expected);
+
+    expected.push(symbols[0]);
+    assert_eq!(isolate.list_property_symbols(value, &context)?, expected);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_isolate_describe_value() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+
+    let value = isolate.parse_json(r#"{ "name": "foo", "list": [1, [2.5, [true, [null]]]], "empty": {} }"#, region_id, &context)?;
+    let value = value.get_value();
+
+    let options = DescribeOptions::new();
+    assert_eq!(isolate.describe_value(value, &context, &options), r#"{name: "foo", list: [1, [2.5, [<yes>, [...]]]], empty: {}}"#);
+    assert_eq!(isolate.extract_text(value, &context)?, isolate.describe_value(value, &context, &options));
+
+    let options = DescribeOptions::new().max_depth(1).max_items(2);
+    assert_eq!(isolate.describe_value(value, &context, &options), r#"{name: "foo", list: [...], <1 more>}"#);
+    assert_eq!(format!("{}", isolate.display_value(value, &context, options)), r#"{name: "foo", list: [...], <1 more>}"#);
+
+    let symbol = isolate.get_text_symbol(JSON_SYMBOL_SCOPE, "self");
+    isolate.set_own_property(value, value, symbol, value, &context)?;
+    let options = DescribeOptions::new().max_items(1);
+    assert_eq!(isolate.describe_value(value, &context, &options), r#"{name: "foo", <3 more>}"#);
+    let description = format!("{}", isolate.display_value(value, &context, DescribeOptions::new()));
+    assert!(description.ends_with("self: <cycle>}"));
+
+    Ok(())
+
+}
+
+#[cfg(test)]
+struct TestBooleanFormatter {}
+
+#[cfg(test)]
+impl TextFormatter for TestBooleanFormatter {
+
+    fn format_text(&self, value: Value, _context: &Box<dyn Context>) -> Result<String, Error> {
+        Ok(value.as_boolean().to_string())
+    }
+
+}
+
+#[test]
+fn test_isolate_text_conversion() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+
+    let to_text = isolate.get_well_known_symbols().get_to_text();
+
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let _prototype = Pinned::new(&context, prototype)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+    let _value = Pinned::new(&context, value)?;
+
+    let text = isolate.intern_text("custom", region_id, &context)?;
+    isolate.set_own_property(prototype, prototype, to_text, text, &context)?;
+    assert_eq!(isolate.extract_text(value, &context)?, "custom");
+
+    let function = isolate.create_native_function(region_id, move |args, context| {
+        context.get_isolate().intern_text(if args.get_this().is_object() { "called" } else { "other" }, region_id, context)
+    }, &context)?;
+    isolate.set_own_property(value, value, to_text, function, &context)?;
+    assert_eq!(isolate.extract_text(value, &context)?, "called");
+
+    isolate.set_own_property(value, value, to_text, Value::make_integer(1), &context)?;
+    assert!(matches!(isolate.extract_text(value, &context).unwrap_err().get_error_type(), TypeNotMatch));
+
+    assert_eq!(isolate.extract_text(Value::make_boolean(true), &context)?, "<yes>");
+    isolate.set_text_formatter(PrimitiveType::Boolean, Arc::new(TestBooleanFormatter {}));
+    assert_eq!(isolate.extract_text(Value::make_boolean(true), &context)?, "true");
+    assert_eq!(isolate.fork()?.extract_text(Value::make_boolean(false), &context)?, "false");
+    isolate.clear_text_formatter(PrimitiveType::Boolean);
+    assert_eq!(isolate.extract_text(Value::make_boolean(false), &context)?, "<no>");
+
+    Ok(())
+
+}
+
+#[cfg(test)]
+struct TestSymbolCoercer {}
+
+#[cfg(test)]
+impl ValueCoercer for TestSymbolCoercer {
+
+    fn coerce_to_float(&self, value: Value, _context: &Box<dyn Context>) -> Result<Option<f64>, Error> {
+        match value.is_symbol() {
+            true => Ok(Some(value.extract_symbol(Symbol::new(0)).get_id() as f64)),
+            false => Ok(None)
+        }
+    }
+
+}
+
+#[test]
+fn test_isolate_coercion() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    /// List own property symbols of a value
-    pub fn list_own_property_symbols(&self, id: Value, subject: Value, context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    let layout_token = isolate.create_slot_layout_token();
 
-        let layout_token = context.get_slot_layout_token();
+    let region_id = isolate.create_region()?;
 
-        let layout_guard = layout_token.lock_read();
+    assert_eq!(isolate.to_integer(Value::make_float(-3.7), &context)?, -3);
+    assert_eq!(isolate.to_float(Value::make_boolean(true), &context)?, 1.0);
+    assert!(!isolate.to_boolean(Value::make_null(), &context)?);
+    assert!(isolate.to_integer(Value::make_float(1e12), &context).is_err());
 
-        let id = self.resolve_real_value(id, layout_token)?;
+    let text = isolate.intern_text(" 42.5 ", region_id, &context)?;
+    assert_eq!(isolate.to_float(text, &context)?, 42.5);
+    assert_eq!(isolate.to_integer(text, &context)?, 42);
+    assert!(isolate.to_boolean(text, &context)?);
+    let empty = isolate.intern_text("", region_id, &context)?;
+    assert!(!isolate.to_boolean(empty, &context)?);
+    assert!(matches!(isolate.to_float(empty, &context).unwrap_err().get_error_type(), TypeNotMatch));
 
-        match id.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => { return Ok(HashSet::new()); },
-            Integer => { return Ok(HashSet::new()); },
-            Float => { return Ok(HashSet::new()); },
-            Symbol => { return Ok(HashSet::new()); },
-            Text => { return Ok(HashSet::new()); },
-            List => {},
-            Tuple => {},
-            Object =>{} 
-        }
+    let to_primitive = isolate.get_well_known_symbols().get_to_primitive();
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let _prototype = Pinned::new(&context, prototype)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+    let _value = Pinned::new(&context, value)?;
 
-        match region {
-            Some(region) => {
-                let mut hash_set = HashSet::new();
-                for value in region.list_own_property_symbols_with_layout_guard(id, subject, context, layout_guard, false)?.iter() {
-                    hash_set.insert(*value);
-                }
-                Ok(hash_set)
-            },
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+    assert!(isolate.to_float(value, &context).is_err());
+    assert!(isolate.to_boolean(value, &context)?);
 
-    }
+    isolate.set_own_property(prototype, prototype, to_primitive, Value::make_integer(7), &context)?;
+    assert_eq!(isolate.to_integer(value, &context)?, 7);
 
-    /// List own property symbols of a value
-    pub fn list_own_property_symbols_ignore_slot_trap(&self, id: Value, subject: Value, context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    let function = isolate.create_native_function(region_id, |_args, _context| {
+        Ok(Value::make_integer(0))
+    }, &context)?;
+    isolate.set_own_property(value, value, to_primitive, function, &context)?;
+    assert_eq!(isolate.to_float(value, &context)?, 0.0);
+    assert!(!isolate.to_boolean(value, &context)?);
 
-        let layout_token = context.get_slot_layout_token();
+    isolate.set_own_property(value, value, to_primitive, value, &context)?;
+    assert!(isolate.to_boolean(value, &context).is_err());
 
-        let _guard = layout_token.lock_read();
+    let symbol = isolate.get_text_symbol("test", "test");
+    assert!(isolate.to_float(Value::make_symbol(symbol), &context).is_err());
+    isolate.set_value_coercer(Arc::new(TestSymbolCoercer {}));
+    assert_eq!(isolate.to_float(Value::make_symbol(symbol), &context)?, symbol.get_id() as f64);
+    isolate.clear_value_coercer();
+    assert!(isolate.to_float(Value::make_symbol(symbol), &context).is_err());
 
-        let id = self.resolve_real_value(id, layout_token)?;
+    Ok(())
 
-        let region_id = id.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
+}
 
-        match region {
-            Some(region) => {
-                let mut hash_set = HashSet::new();
-                for value in region.list_own_property_symbols_ignore_slot_trap(id, subject, context)?.iter() {
-                    hash_set.insert(*value);
-                }
-                Ok(hash_set)
-            },
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+#[test]
+fn test_isolate_object_groups() -> Result<(), Error> {
 
-    }
+    let isolate = Arc::new(Isolate::create()?);
 
-}
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-/// Isolate object property managment
-impl Isolate {
+    let layout_token = isolate.create_slot_layout_token();
 
-    /// List property symbols of a value
-    pub fn list_property_symbols(&self, subject: Value, context: &Box<dyn Context>) -> Result<HashSet<Symbol>, Error> {
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
 
-        let layout_token = context.get_slot_layout_token();
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_4 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-        let _guard = layout_token.lock_read();
+    assert!(isolate.add_object_group(&[value, Value::make_integer(1)]).is_err());
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+    let group = isolate.add_object_group(&[value, value_2])?;
+    let group_2 = isolate.add_object_group(&[value_3])?;
+    assert_eq!(isolate.count_object_groups(), 2);
+    assert_eq!(isolate.list_object_group_members(value_2), vec![value, value_2]);
+    assert!(isolate.list_object_group_members(value_4).is_empty());
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => {},
-            Integer => {},
-            Float => {},
-            Symbol => {},
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+    let merged = isolate.add_object_group(&[value_2, value_3, value_4])?;
+    assert_eq!(isolate.count_object_groups(), 1);
+    assert_eq!(isolate.get_object_group_id(value), Some(merged));
+    assert_eq!(isolate.list_object_group_members(value_4), vec![value, value_2, value_3, value_4]);
+    assert!(!isolate.remove_object_group(group));
+    assert!(!isolate.remove_object_group(group_2));
 
-        let mut hash_set = HashSet::new();
+    let value_5 = isolate.move_slot(value_3, region_id_2, &context)?;
+    assert_eq!(isolate.get_object_group_id(value_3), None);
+    assert_eq!(isolate.list_object_group_members(value), vec![value, value_2, value_5, value_4]);
 
-        let mut prototype = subject;
-        while !prototype.is_nil() {
-            for value in self.list_own_property_symbols(prototype, subject, context)?.iter() {
-                hash_set.insert(*value);
-            }
-            prototype = self.get_prototype(prototype, context)?.get_value();
-        }
+    isolate.notify_slot_drop(value_2)?;
+    assert_eq!(isolate.list_object_group_members(value), vec![value, value_5, value_4]);
 
-        Ok(hash_set)
+    assert!(isolate.remove_object_group(merged));
+    assert_eq!(isolate.count_object_groups(), 0);
+    assert!(isolate.list_object_group_members(value).is_empty());
 
+    isolate.add_object_group(&[value])?;
+    isolate.notify_slot_drop(value)?;
+    assert_eq!(isolate.count_object_groups(), 0);
+
+    Ok(())
+
+}
+
+#[cfg(test)]
+struct RecordingGcListener {
+    promoted: RefCell<Vec<Value>>
+}
+
+#[cfg(test)]
+impl GcListener for RecordingGcListener {
+
+    fn notify_value_promoted(&self, value: Value) {
+        self.promoted.borrow_mut().push(value);
     }
 
-    /// Check whether an property of a value for a symbol exists
-    pub fn has_property(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
+}
 
-        let layout_token = context.get_slot_layout_token();
+#[test]
+fn test_isolate_gc_listeners() -> Result<(), Error> {
 
-        let _guard = layout_token.lock_read();
+    let isolate = Arc::new(Isolate::create()?);
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+    let layout_token = isolate.create_slot_layout_token();
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => {},
-            Integer => {},
-            Float => {},
-            Symbol => {},
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+    let region_id = isolate.create_region()?;
 
-        let mut prototype = subject;
-        while !prototype.is_nil() {
-            if self.has_own_property(prototype, subject, symbol, context)? {
-                return Ok(true);
-            }
-            prototype = self.get_prototype(prototype, context)?.get_value();
-        } 
+    let listener = Arc::new(RecordingGcListener {
+        promoted: RefCell::new(Vec::new())
+    });
+    let id = isolate.add_gc_listener(listener.clone());
+    assert!(!isolate.get_gc_listeners().is_empty());
 
-        Ok(false)
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-    }
-    
-    /// Get property of a value for a symbol
-    pub fn get_property(&self, subject: Value, symbol: Symbol, field_token: Option<&FieldToken>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+    isolate.move_value_out_from_nursery(value, &layout_token)?;
+    isolate.move_value_out_from_nursery(value, &layout_token)?;
+    assert_eq!(*listener.promoted.borrow(), vec![value]);
 
-        let layout_token = context.get_slot_layout_token();
+    assert!(isolate.remove_gc_listener(id));
+    assert!(!isolate.remove_gc_listener(id));
+    assert!(isolate.get_gc_listeners().is_empty());
 
-        let _guard = layout_token.lock_read();
+    isolate.move_value_out_from_nursery(value_2, &layout_token)?;
+    assert_eq!(*listener.promoted.borrow(), vec![value]);
 
-        let subject = self.resolve_real_value(subject, layout_token)?;
+    Ok(())
 
-        match subject.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no properties")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no properties")); },
-            Boolean => {},
-            Integer => {},
-            Float => {},
-            Symbol => {},
-            Text => {},
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+}
 
-        let mut prototype = subject;
-        while !prototype.is_nil() {
-            let value = self.get_own_property(prototype, subject, symbol, field_token, context)?;
-            if !value.is_undefined() {
-                return Ok(value);
-            }
-            prototype = self.get_prototype(prototype, context)?.get_value();
-        } 
-        
-        Pinned::new(context, Value::make_undefined())
+#[cfg(all(test, feature = "fault-injection"))]
+struct TestFaultInjector {
+    failing_region_id: AtomicU32,
+    failing_restore: AtomicU32,
+    delayed_writes: AtomicUsize
+}
+
+#[cfg(all(test, feature = "fault-injection"))]
+impl FaultInjector for TestFaultInjector {
+
+    fn fail_gain_slot(&self, region_id: u32, _primitive_type: PrimitiveType) -> bool {
+        self.failing_region_id.load(Ordering::SeqCst) == region_id
+    }
+
+    fn fail_restore_slot(&self, _from: Value, _to_region_id: u32) -> bool {
+        self.failing_restore.load(Ordering::SeqCst) > 0
+    }
 
+    fn delay_lock(&self, access: LockAccess) {
+        if access == LockAccess::Write {
+            self.delayed_writes.fetch_add(1, Ordering::SeqCst);
+        }
     }
 
 }
 
-impl Isolate {
+#[cfg(feature = "fault-injection")]
+#[test]
+fn test_isolate_fault_injector() -> Result<(), Error> {
 
-    pub fn is_sealed(&self, value: Value, context: &Box<dyn Context>) -> Result<bool, Error> {
+    let isolate = Arc::new(Isolate::create()?);
 
-        let layout_token = context.get_slot_layout_token();
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-        let _guard = layout_token.lock_read();
+    let layout_token = isolate.create_slot_layout_token();
 
-        let value = self.resolve_real_value(value, layout_token)?;
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let injector = Arc::new(TestFaultInjector {
+        failing_region_id: AtomicU32::new(region_id),
+        failing_restore: AtomicU32::new(1),
+        delayed_writes: AtomicUsize::new(0)
+    });
+    isolate.set_fault_injector(injector.clone());
+
+    let error = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token).unwrap_err();
+    assert!(matches!(error.get_error_type(), OutOfSpace));
+    let value = isolate.gain_slot(region_id_2, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+
+    let delayed_writes = injector.delayed_writes.load(Ordering::SeqCst);
+    assert!(isolate.move_slot(value, region_id, &context).is_err());
+    assert!(injector.delayed_writes.load(Ordering::SeqCst) > delayed_writes);
+    assert!(isolate.move_slot_by_copying(value, region_id, &context).is_err());
+    assert!(isolate.is_direct_value_alive(value, &context)?);
+
+    injector.failing_restore.store(0, Ordering::SeqCst);
+    let value = isolate.move_slot(value, region_id, &context)?;
+    assert_eq!(value.get_region_id()?, region_id);
+
+    isolate.clear_fault_injector();
+    let delayed_writes = injector.delayed_writes.load(Ordering::SeqCst);
+    isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    isolate.move_slot(value, region_id_2, &context)?;
+    assert_eq!(injector.delayed_writes.load(Ordering::SeqCst), delayed_writes);
 
-        match value.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no feature for seal")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no feature for seal")); },
-            Boolean => { return Ok(true); },
-            Integer => { return Ok(true); },
-            Float => { return Ok(true); },
-            Symbol => { return Ok(true); },
-            Text => {return Ok(true); },
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+    Ok(())
 
-        let region_id = value.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
+}
 
-        match region {
-            Some(region) => region.is_sealed(value),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+#[cfg(test)]
+struct CountingRegionWatermarkListener {
+    high_reached: AtomicUsize,
+    low_reached: AtomicUsize
+}
+
+#[cfg(test)]
+impl RegionWatermarkListener for CountingRegionWatermarkListener {
 
+    fn notify_high_watermark_reached(&self, _occupancy: f32) {
+        self.high_reached.fetch_add(1, Ordering::SeqCst);
     }
 
-    pub fn seal_slot(&self, value: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+    fn notify_low_watermark_reached(&self, _occupancy: f32) {
+        self.low_reached.fetch_add(1, Ordering::SeqCst);
+    }
 
-        let layout_token = context.get_slot_layout_token();
+}
 
-        let _guard = layout_token.lock_read();
+#[test]
+fn test_isolate_region_watermarks() -> Result<(), Error> {
 
-        let value = self.resolve_real_value(value, layout_token)?;
+    let isolate = Arc::new(Isolate::create()?);
 
-        match value.get_primitive_type() {
-            Undefined => { return Err(Error::new(VisitingUndefinedProperty, "Undefined has no feature for seal")); },
-            Null => { return Err(Error::new(VisitingNullProperty, "Null has no feature for seal")); },
-            Boolean => { return Ok(()); },
-            Integer => { return Ok(()); },
-            Float => { return Ok(()); },
-            Symbol => { return Ok(()); },
-            Text => {return Ok(()); },
-            List => {},
-            Tuple => {},
-            Object => {} 
-        }
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-        let region_id = value.get_region_id()?;
-        let region = {
-            let _guard = self.region_rw_lock.lock_read();
-            match self.regions.borrow().get(region_id as usize) {
-                Some(region) => Some(region.clone()),
-                None => None
-            }
-        };
+    let region_id = isolate.create_region()?;
+    isolate.unprotect_region(region_id)?;
 
-        match region {
-            Some(region) => region.seal_slot(value),
-            None => Err(Error::new(FatalError, "Region of slot not found"))
-        }
+    let listener = Arc::new(CountingRegionWatermarkListener {
+        high_reached: AtomicUsize::new(0),
+        low_reached: AtomicUsize::new(0)
+    });
+    isolate.set_region_watermark_listener(listener.clone());
 
-    }
+    assert!(isolate.set_region_watermarks(0.9, 0.1).is_err());
+    assert_eq!(isolate.get_region_watermarks(), (0.6, 0.8));
+
+    let occupancy = isolate.get_region_occupancy();
+    assert!(occupancy > 0.0);
+    isolate.set_region_watermarks(occupancy, occupancy)?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    assert!(isolate.get_region_occupancy() > occupancy);
+    assert_eq!(listener.high_reached.load(Ordering::SeqCst), 1);
+    assert_eq!(listener.low_reached.load(Ordering::SeqCst), 0);
+
+    isolate.move_value_out_from_nursery(value, context.get_slot_layout_token())?;
+    isolate.recycle_slot(value, &context)?;
+    assert_eq!(isolate.get_region_occupancy(), occupancy);
+    assert_eq!(listener.high_reached.load(Ordering::SeqCst), 1);
+    assert_eq!(listener.low_reached.load(Ordering::SeqCst), 1);
+
+    isolate.clear_region_watermark_listener();
+    isolate.gain_slot(region_id, PrimitiveType::Object, isolate.get_object_prototype(), context.get_slot_layout_token())?;
+    assert_eq!(listener.high_reached.load(Ordering::SeqCst), 1);
+
+    Ok(())
 
 }
 
-/// Isolate outlet management
-impl Isolate {
+#[test]
+fn test_isolate_roots() -> Result<(), Error> {
 
-    /// Set the outlet with specified ID
-    pub fn add_outlet(&self, outlet: Arc<dyn Any>) -> u64 {
+    let isolate = Arc::new(Isolate::create()?);
 
-        let _guard = self.outlets_rw_lock.lock_write();
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-        let id = self.next_outlet_id.fetch_add(1, Ordering::SeqCst);
+    let layout_token = isolate.create_slot_layout_token();
 
-        self.outlets.borrow_mut().insert(id, outlet);
+    let region_id = isolate.create_region()?;
 
-        id
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-    }
+    let root = isolate.add_root(value, &layout_token)?;
 
-    /// Get the outlet with specified ID
-    pub fn get_outlet(&self, id: u64) -> Option<Arc<dyn Any>> {
+    assert!(isolate.recycle_slot(value, &context).is_err());
+
+    isolate.remove_root(&root)?;
+
+    isolate.recycle_slot(value, &context)?;
 
-        let _guard = self.outlets_rw_lock.lock_read();
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-        match self.outlets.borrow().get(&id) {
-            None => None,
-            Some(outlet) => Some(outlet.clone())
-        }
+    let root = isolate.add_root(value, &layout_token)?;
+    let weak_root = isolate.add_weak_root(value, None, &layout_token)?;
 
-    }
+    let value_2 = isolate.move_slot(value, region_id, &context)?;
 
-    /// Remove the outlet with specified ID
-    pub fn clear_outlet(&self, id: u64) -> Option<Arc<dyn Any>> {
+    assert!(isolate.recycle_slot(value, &context).is_err());
 
-        let _guard = self.outlets_rw_lock.lock_read();
+    assert!(isolate.recycle_slot(value_2, &context).is_err());
 
-        self.outlets.borrow_mut().remove(&id)
+    assert!(!weak_root.is_dropped());
 
-    }
+    isolate.remove_root(&root)?;
 
-}
+    isolate.recycle_slot(value_2, &context)?;
 
-#[cfg(test)] use super::test::TestContext2;
+    assert!(weak_root.is_dropped());
 
-#[test]
-fn test_isolate_creation() -> Result<(), Error> {
-    Isolate::create()?;
     Ok(())
+
 }
 
 #[test]
-fn test_isolate_text_symbol() -> Result<(), Error> {
+fn test_isolate_dump_roots() -> Result<(), Error> {
 
-    let isolate = Isolate::create()?;
+    let isolate = Arc::new(Isolate::create()?);
 
-    let test_2 = isolate.get_text_symbol("test", "test2");
-    let test_2_2 = isolate.get_text_symbol("test", "test2");
-    let test_2_3 = isolate.get_text_symbol("test", "test3");
-    let test_3 = isolate.get_text_symbol("test2", "test3");
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    assert_eq!(test_2, test_2_2);
-    assert_ne!(test_2, test_2_3);
-    assert_ne!(test_2, test_3);
-    assert_ne!(test_2_3, test_3);
+    let layout_token = isolate.create_slot_layout_token();
 
-    let test_2_symbol_info = isolate.resolve_symbol_info(test_2)?;
-    assert_eq!(test_2_symbol_info.get_symbol(), test_2);
-    assert_eq!(test_2_symbol_info.get_symbol_scope().as_ref(), "test");
-    assert!(test_2_symbol_info.is_text_symbol());
-    assert!(!test_2_symbol_info.is_value_symbol());
-    assert_eq!(test_2_symbol_info.get_text().unwrap().as_ref(), "test2");
-    assert!(test_2_symbol_info.get_value().is_none());
+    let region_id = isolate.create_region()?;
 
-    assert!(isolate.recycle_symbol(test_2).is_err());
-    isolate.add_symbol_reference(test_2)?;
-    assert!(isolate.recycle_symbol(test_2).is_err());
-    isolate.remove_symbol_reference(test_2)?;
-    assert!(isolate.recycle_symbol(test_2).is_ok());
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-    Ok(())
-}
+    let root = isolate.add_root(value, &layout_token)?;
+    let weak_root = isolate.add_weak_root(value, None, &layout_token)?;
 
-#[test]
-fn test_isolate_value_symbol() -> Result<(), Error> {
+    let mut output = Vec::new();
+    isolate.dump_roots(&mut output, &context)?;
 
-    let isolate = Isolate::create()?;
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("references: 1"));
+    assert!(output.contains(&format!("weak id: {}, drop listener: false", weak_root.get_weak_id())));
 
-    let test_2 = isolate.get_value_symbol("test", Value::make_null());
-    let test_2_2 = isolate.get_value_symbol("test", Value::make_null());
-    let test_2_3 = isolate.get_value_symbol("test", Value::make_float(4.0));
-    let test_3 = isolate.get_value_symbol("test2", Value::make_float(4.0));
+    let leaked_roots = isolate.find_leaked_roots(Duration::from_secs(0));
+    assert!(leaked_roots.iter().any(|leaked_root| Arc::ptr_eq(leaked_root, &root)));
+    assert!(isolate.find_leaked_roots(Duration::from_secs(3600)).is_empty());
 
-    assert_eq!(test_2, test_2_2);
-    assert_ne!(test_2, test_2_3);
-    assert_ne!(test_2, test_3);
-    assert_ne!(test_2_3, test_3);
+    isolate.remove_root(&root)?;
+    isolate.remove_weak_root(&weak_root)?;
 
-    let test_2_symbol_info = isolate.resolve_symbol_info(test_2)?;
-    assert_eq!(test_2_symbol_info.get_symbol(), test_2);
-    assert_eq!(test_2_symbol_info.get_symbol_scope().as_ref(), "test");
-    assert!(!test_2_symbol_info.is_text_symbol());
-    assert!(test_2_symbol_info.is_value_symbol());
-    assert_eq!(test_2_symbol_info.get_value().unwrap(), Value::make_null());
-    assert!(test_2_symbol_info.get_text().is_none());
+    assert!(!isolate.find_leaked_roots(Duration::from_secs(0)).iter().any(|leaked_root| Arc::ptr_eq(leaked_root, &root)));
 
-    assert!(isolate.recycle_symbol(test_2).is_err());
-    isolate.add_symbol_reference(test_2)?;
-    assert!(isolate.recycle_symbol(test_2).is_err());
-    isolate.remove_symbol_reference(test_2)?;
-    assert!(isolate.recycle_symbol(test_2).is_ok());
+    isolate.recycle_slot(value, &context)?;
 
     Ok(())
+
 }
 
 #[test]
-fn test_isolate_region_management() -> Result<(), Error> {
+fn test_isolate_root_arena() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
     let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    let region_id = isolate.create_region()?;
-
-    // region 0 is for builtin objects
-
-    assert_eq!(region_id, 1);
-
-    assert!(isolate.recycle_region(region_id).is_err());
-
-    isolate.unprotect_region(region_id)?;
-    isolate.recycle_region(region_id)?;
+    let layout_token = isolate.create_slot_layout_token();
 
     let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
 
-    isolate.unprotect_region(region_id)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-    assert_eq!(region_id, 2);
+    let roots_count = isolate.list_roots().len();
 
-    let layout_token = isolate.create_slot_layout_token();
+    let value_3 = {
 
-    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+        let root_arena = isolate.create_root_arena();
+        assert!(root_arena.add(Value::make_integer(1), &layout_token).is_err());
 
-    assert!(isolate.recycle_region(region_id).is_err());
+        root_arena.add(value, &layout_token)?;
+        root_arena.add(value_2, &layout_token)?;
+        assert_eq!(root_arena.get_size(), 2);
+        assert_eq!(isolate.count_root_arenas(), 1);
+        assert_eq!(isolate.list_roots().len(), roots_count + 2);
 
-    assert!(isolate.recycle_slot(value, &context).is_err());
+        assert!(isolate.recycle_slot(value, &context).is_err());
+        assert!(isolate.recycle_slots(&[value_2], &context).is_err());
 
-    isolate.add_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+        let value_3 = isolate.move_slot(value_2, region_id_2, &context)?;
+        assert!(root_arena.list_values().contains(&value_3));
+        assert!(!root_arena.list_values().contains(&value_2));
 
-    assert!(isolate.recycle_slot(value, &context).is_err());
+        value_3
 
-    isolate.remove_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+    };
 
-    isolate.recycle_slot(value, &context)?;
+    assert_eq!(isolate.count_root_arenas(), 0);
+    assert_eq!(isolate.list_roots().len(), roots_count);
 
-    isolate.recycle_region(region_id)?;
+    isolate.recycle_slots(&[value, value_3], &context)?;
 
     Ok(())
 
 }
 
 #[test]
-fn test_isolate_slot_management() -> Result<(), Error> {
+fn test_isolate_weak_root_census() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
@@ -2843,30 +9106,45 @@ fn test_isolate_slot_management() -> Result<(), Error> {
     let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
     let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-    assert!(isolate.recycle_slot(value, &context).is_err());
-
-    isolate.add_value_reference(value, value_2, &layout_token)?;
+    let weak_root = isolate.add_weak_root(value, None, &layout_token)?;
+    let weak_root_2 = isolate.add_weak_root(value, None, &layout_token)?;
+    let weak_root_3 = isolate.add_weak_root(value_2, None, &layout_token)?;
 
-    isolate.add_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+    let weak_ids: Vec<u32> = isolate.list_weak_roots().iter().map(|weak_root| weak_root.get_weak_id()).collect();
+    assert_eq!(weak_ids, vec!(weak_root.get_weak_id(), weak_root_2.get_weak_id(), weak_root_3.get_weak_id()));
 
-    assert!(isolate.recycle_slot(value, &context).is_err());
+    let census = isolate.weak_root_census();
+    assert_eq!(census.get_target_count(), 2);
+    assert_eq!(census.get_weak_root_count(), 3);
+    assert_eq!(census.get_weak_root_count_of(value), 2);
+    assert_eq!(census.list_targets()[0], (value, 2));
+    assert_eq!(census.get_fired_count(), 0);
 
-    isolate.remove_value_reference(isolate.get_object_prototype(), value, &layout_token)?;
+    isolate.move_value_out_from_nursery(value, &layout_token)?;
+    isolate.move_value_out_from_nursery(value_2, &layout_token)?;
 
     isolate.recycle_slot(value, &context)?;
 
-    assert!(isolate.recycle_slot(value_2, &context).is_err());
+    assert!(weak_root.is_dropped());
+    assert!(weak_root_2.is_dropped());
 
-    isolate.remove_value_reference(value, value_2, &layout_token)?;
+    let census = isolate.weak_root_census();
+    assert_eq!(census.get_target_count(), 1);
+    assert_eq!(census.get_weak_root_count_of(value), 0);
+    assert_eq!(census.get_fired_count(), 2);
+    assert_eq!(isolate.list_weak_roots().len(), 1);
 
+    isolate.remove_weak_root(&weak_root_3)?;
     isolate.recycle_slot(value_2, &context)?;
 
+    assert_eq!(isolate.weak_root_census().get_fired_count(), 2);
+
     Ok(())
 
 }
 
 #[test]
-fn test_isolate_slot_snapshot() -> Result<(), Error> {
+fn test_isolate_weak_root_requeue() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
@@ -2877,101 +9155,127 @@ fn test_isolate_slot_snapshot() -> Result<(), Error> {
     let region_id = isolate.create_region()?;
 
     let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
-    let value_slot = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
     let value_2 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
-    let value_3 = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
-
-    isolate.move_value_out_from_nursery(value_slot, &layout_token)?;
-    isolate.recycle_slot(value_slot, &context)?;
 
-    let value_4 = isolate.move_slot(value_3, region_id, &context)?;
+    let drop_listener = PayloadDropListener::new("mirror".to_owned(), move |_payload: &String, notice: &DropNotice| {
+        if notice.get_value() == value_2 { None } else { Some(value_2) }
+    });
 
-    assert_eq!(value_slot, value_4);
+    let weak_root = isolate.add_weak_root(value, Some(Box::new(drop_listener)), &layout_token)?;
+    assert_eq!(weak_root.get_payload::<String>(), Some("mirror".to_owned()));
 
-    assert!(isolate.is_direct_value_alive(value_slot, &context)?);
-    assert!(!isolate.is_direct_value_alive(value_3, &context)?);
-    assert!(isolate.resolve_real_value(value_3, &layout_token).is_err());
+    isolate.move_value_out_from_nursery(value, &layout_token)?;
+    isolate.move_value_out_from_nursery(value_2, &layout_token)?;
 
-    let symbol = isolate.get_text_symbol("test", "test");
+    isolate.recycle_slot(value, &context)?;
 
-    isolate.set_own_property(value_2, value_2, symbol, value_4, &context)?;
+    assert!(!weak_root.is_dropped());
+    assert_eq!(weak_root.get_value(), Some(value_2));
+    assert_eq!(isolate.weak_root_census().get_weak_root_count_of(value_2), 1);
 
-    isolate.move_value_out_from_nursery(value, &layout_token)?;
-    isolate.recycle_slot(value, &context)?;
+    isolate.recycle_slot(value_2, &context)?;
 
-    let value_5 = isolate.move_slot(value_4, region_id, &context)?;
-    assert!(!isolate.is_direct_value_alive(value_4, &context)?);
-    assert!(isolate.is_direct_value_occupied(value_4, &context)?);
-    assert_eq!(isolate.resolve_real_value(value_4, &layout_token)?, value_5);
-    assert_eq!(isolate.get_own_property(value_2, value_2, symbol, None, &context)?.get_value(), value_5);
-    assert!(!isolate.is_direct_value_occupied(value_4, &context)?);
+    assert!(weak_root.is_dropped());
+    assert_eq!(isolate.weak_root_census().get_fired_count(), 2);
+    assert_eq!(isolate.count_weak_roots(), 0);
 
     Ok(())
 
 }
 
 #[test]
-fn test_isolate_outlets() -> Result<(), Error> {
+fn test_isolate_pin_guard() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
-    let outlet: Arc<dyn Any> = Arc::new(Value::make_undefined());
-    let outlet_2: Arc<dyn Any> = Arc::new(Value::make_null());
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    let outlet_id = isolate.add_outlet(outlet.clone());
-    let outlet_2_id = isolate.add_outlet(outlet_2.clone());
+    let layout_token = isolate.create_slot_layout_token();
 
-    assert!(Arc::ptr_eq(&isolate.get_outlet(outlet_id).unwrap(), &outlet));
-    assert!(Arc::ptr_eq(&isolate.get_outlet(outlet_2_id).unwrap(), &outlet_2));
+    let region_id = isolate.create_region()?;
 
-    isolate.clear_outlet(outlet_id);
-    assert!(isolate.get_outlet(outlet_id).is_none());
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
 
-    isolate.clear_outlet(outlet_2_id);
-    assert!(isolate.get_outlet(outlet_2_id).is_none());
+    assert_eq!(isolate.pin_count(value), 0);
+
+    let root = isolate.add_root(value, &layout_token)?;
+    let root_2 = isolate.add_root(value, &layout_token)?;
+    assert_eq!(isolate.pin_count(value), 2);
+
+    let pin = |value: Value| -> Result<(), Error> {
+        let guard = PinGuard::new(&context, value)?;
+        assert!(guard.is_pinned());
+        assert_eq!(isolate.pin_count(value), 3);
+        isolate.recycle_slot(guard.get_value(), &context)?;
+        Ok(())
+    };
+    assert!(pin(value).is_err());
+    assert_eq!(isolate.pin_count(value), 2);
+
+    let guard = PinGuard::new(&context, value)?;
+    assert_eq!(isolate.pin_count(value), 3);
+    guard.unpin()?;
+    assert_eq!(isolate.pin_count(value), 2);
+
+    assert!(!PinGuard::new(&context, Value::make_integer(1))?.is_pinned());
+
+    isolate.remove_root(&root)?;
+    assert_eq!(isolate.pin_count(value), 1);
+
+    let dedicated_root = isolate.add_dedicated_root(value, &layout_token)?;
+    isolate.remove_root(&dedicated_root)?;
+    assert!(isolate.remove_root(&dedicated_root).is_err());
+    assert_eq!(isolate.pin_count(value), 1);
+
+    isolate.remove_root(&root_2)?;
+    assert_eq!(isolate.pin_count(value), 0);
+    assert!(isolate.remove_root(&root_2).is_err());
+
+    isolate.recycle_slot(value, &context)?;
 
     Ok(())
 
 }
 
 #[test]
-fn test_isolate_own_properties() -> Result<(), Error> {
+fn test_isolate_persistents() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
     let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    let layout_token = isolate.create_slot_layout_token();
-
     let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
 
-    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
 
-    let symbol = isolate.get_text_symbol("test", "test");
+    let persistent = Persistent::from_local(&Local::new(&context, value)?)?;
+    assert_eq!(isolate.count_persistents(), 1);
+    assert_eq!(isolate.pin_count(value), 1);
 
-    isolate.set_own_property(value, value, symbol, Value::make_float(3.14), &context)?;
+    let value_2 = isolate.move_slot(value, region_id_2, &context)?;
+    assert_eq!(persistent.to_local(&context)?.get_value(), value_2);
 
-    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_float(3.14));
+    let persistent = std::thread::spawn(move || persistent).join().unwrap();
+    assert_eq!(persistent.to_local(&context)?.get_value(), value_2);
 
-    let symbols = isolate.list_own_property_symbols(value, value, &context)?;
-    assert_eq!(symbols.len(), 2);
-    assert!(symbols.get(&isolate.get_prototype_symbol()).is_some());
-    assert!(symbols.get(&symbol).is_some());
+    std::thread::spawn(move || drop(persistent)).join().unwrap();
 
-    isolate.delete_own_property(value, value, symbol, &context)?;
+    assert_eq!(isolate.count_persistents(), 1);
+    assert_eq!(isolate.pin_count(value_2), 1);
 
-    let symbols = isolate.list_own_property_symbols(value, value, &context)?;
-    assert_eq!(symbols.len(), 1);
-    assert!(symbols.get(&isolate.get_prototype_symbol()).is_some());
+    assert_eq!(isolate.drain_persistents()?, 1);
+    assert_eq!(isolate.count_persistents(), 0);
+    assert_eq!(isolate.pin_count(value_2), 0);
 
-    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_undefined());
+    isolate.recycle_slot(value_2, &context)?;
 
     Ok(())
 
 }
 
 #[test]
-fn test_isolate_properties() -> Result<(), Error> {
+fn test_isolate_local_roots() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
@@ -2980,95 +9284,163 @@ fn test_isolate_properties() -> Result<(), Error> {
     let layout_token = isolate.create_slot_layout_token();
 
     let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
 
-    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
-    let value = isolate.gain_slot(region_id, PrimitiveType::Object, prototype, &layout_token)?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    isolate.move_value_out_from_nursery(value, &layout_token)?;
 
-    assert_eq!(isolate.get_prototype(value, &context)?.get_value(), prototype);
+    let roots_count = isolate.count_roots();
 
-    let symbol = isolate.get_text_symbol("test", "test");
+    let root = isolate.add_local_root(value, &layout_token)?;
+    assert!(root.is_local());
+    assert_eq!(isolate.pin_count(value), 1);
+    assert_eq!(isolate.count_roots(), roots_count + 1);
+    assert!(isolate.recycle_slot(value, &context).is_err());
 
-    isolate.set_own_property(prototype, prototype, symbol, Value::make_float(3.14), &context)?;
+    isolate.remove_root(&root)?;
+    assert!(isolate.remove_root(&root).is_err());
+    assert_eq!(isolate.pin_count(value), 0);
+    assert_eq!(isolate.count_roots(), roots_count);
 
-    assert_eq!(isolate.get_property(value, symbol, None, &context)?.get_value(), Value::make_float(3.14));
-    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_undefined());
+    let root = isolate.add_local_root(value, &layout_token)?;
+    let root_2 = isolate.add_local_root(value, &layout_token)?;
 
-    let symbols = isolate.list_property_symbols(value, &context)?;
-    assert_eq!(symbols.len(), 2);
-    assert!(symbols.get(&isolate.get_prototype_symbol()).is_some());
-    assert!(symbols.get(&symbol).is_some());
+    let value_2 = isolate.move_slot(value, region_id_2, &context)?;
+    assert!(!root.is_local());
+    assert_eq!(root.get_value(), value_2);
+    assert_eq!(root_2.get_value(), value_2);
+    assert_eq!(isolate.pin_count(value_2), 2);
 
-    let symbols = isolate.list_own_property_symbols(value, value, &context)?;
-    assert_eq!(symbols.len(), 1);
-    assert!(symbols.get(&isolate.get_prototype_symbol()).is_some());
+    isolate.remove_root(&root)?;
+    assert!(isolate.recycle_slot(value_2, &context).is_err());
+    isolate.remove_root(&root_2)?;
+
+    let roots: Vec<Arc<Root>> = (0 .. HANDLE_BLOCK_CAPACITY).map(|_| isolate.add_local_root(value_2, &layout_token)).collect::<Result<_, _>>()?;
+    assert!(roots.iter().all(|root| !root.is_local()));
+    assert_eq!(isolate.pin_count(value_2), HANDLE_BLOCK_CAPACITY);
+    for root in roots.iter() {
+        isolate.remove_root(root)?;
+    }
+
+    assert_eq!(isolate.flush_handle_blocks(&layout_token)?, 0);
+
+    isolate.recycle_slot(value_2, &context)?;
 
     Ok(())
 
 }
 
+#[cfg(feature = "provenance")]
 #[test]
-fn test_isolate_seals() -> Result<(), Error> {
+fn test_isolate_allocation_sites() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
     let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    let layout_token = isolate.create_slot_layout_token();
-
     let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
 
-    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let value = isolate.gain_tagged_slot(region_id, PrimitiveType::Object, Value::make_null(), Some("mirror"), context.get_slot_layout_token())?; let line = line!();
+    let value_2 = isolate.gain_slot(region_id, PrimitiveType::List, Value::make_null(), context.get_slot_layout_token())?;
 
-    assert!(!isolate.is_sealed(value, &context)?);
+    let site = isolate.get_allocation_site(value).unwrap();
+    assert_eq!(site.get_tag(), Some("mirror"));
+    assert_eq!(site.get_primitive_type(), PrimitiveType::Object);
+    assert_eq!(site.get_location().file(), file!());
+    assert_eq!(site.get_location().line(), line);
 
-    isolate.seal_slot(value, &context)?;
+    let site_2 = isolate.get_allocation_site(value_2).unwrap();
+    assert_eq!(site_2.get_tag(), None);
+    assert_eq!(site_2.get_location().line(), line + 1);
 
-    assert!(isolate.is_sealed(value, &context)?);
+    let value_3 = isolate.move_slot(value, region_id_2, &context)?;
+    assert!(isolate.get_allocation_site(value).is_none());
+    assert_eq!(isolate.get_allocation_site(value_3).unwrap().get_location().line(), line);
+
+    isolate.move_value_out_from_nursery(value_3, context.get_slot_layout_token())?;
+    isolate.recycle_slot(value_3, &context)?;
+    assert!(isolate.get_allocation_site(value_3).is_none());
 
     Ok(())
 
 }
 
 #[test]
-fn test_isolate_roots() -> Result<(), Error> {
+fn test_isolate_heap_census() -> Result<(), Error> {
 
     let isolate = Arc::new(Isolate::create()?);
 
     let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
 
-    let layout_token = isolate.create_slot_layout_token();
-
     let region_id = isolate.create_region()?;
 
-    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let prototype = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
 
-    let root = isolate.add_root(value, &layout_token)?;
+    let census = isolate.heap_census(&context)?;
+    assert_eq!(census.get_region_count(region_id), 1);
 
-    assert!(isolate.recycle_slot(value, &context).is_err());
+    for _ in 0..3 {
+        isolate.gain_slot(region_id, PrimitiveType::Object, prototype, context.get_slot_layout_token())?;
+    }
+    isolate.gain_slot(region_id, PrimitiveType::List, Value::make_null(), context.get_slot_layout_token())?;
 
-    isolate.remove_root(&root)?;
+    let census_2 = isolate.heap_census(&context)?;
+    assert_eq!(census_2.get_slot_count(), census.get_slot_count() + 4);
+    assert_eq!(census_2.get_prototype_count(prototype), 3);
 
-    isolate.recycle_slot(value, &context)?;
+    let diff = census_2.diff(&census);
+    assert_eq!(diff.get_slot_growth(), 4);
+    assert_eq!(diff.list_prototypes()[0].0, prototype);
+    assert_eq!(diff.list_prototypes()[0].1, 3);
+    assert_eq!(diff.list_primitive_types(), &[(PrimitiveType::Object, 3), (PrimitiveType::List, 1)]);
+    assert_eq!(diff.list_regions(), &[(region_id, 4)]);
 
-    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    Ok(())
 
-    let root = isolate.add_root(value, &layout_token)?;
-    let weak_root = isolate.add_weak_root(value, None, &layout_token)?;
+}
 
-    let value_2 = isolate.move_slot(value, region_id, &context)?;
+#[cfg(test)]
+struct MovedSubjectPropertyTrap {
+    moves: RefCell<Vec<(Value, Value)>>
+}
 
-    assert!(isolate.recycle_slot(value, &context).is_err());
+#[cfg(test)]
+impl PropertyTrap for MovedSubjectPropertyTrap {
 
-    assert!(isolate.recycle_slot(value_2, &context).is_err());
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 
-    assert!(!weak_root.is_dropped());
+    fn notify_subject_moved(&self, old_subject: Value, new_subject: Value) {
+        self.moves.borrow_mut().push((old_subject, new_subject));
+    }
 
-    isolate.remove_root(&root)?;
+}
 
-    isolate.recycle_slot(value_2, &context)?;
+#[test]
+fn test_isolate_property_trap_subject_moved() -> Result<(), Error> {
 
-    assert!(weak_root.is_dropped());
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let region_id_2 = isolate.create_region()?;
+
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+
+    let property_trap = Arc::new(MovedSubjectPropertyTrap {
+        moves: RefCell::new(Vec::new())
+    });
+    isolate.define_own_property(value, value, Symbol::new(1), property_trap.clone(), &context)?;
+
+    let value_2 = isolate.move_slot(value, region_id_2, &context)?;
+    assert_eq!(*property_trap.moves.borrow(), vec!((value, value_2)));
+
+    let value_3 = isolate.move_slot_by_copying(value_2, region_id, &context)?;
+    assert_eq!(*property_trap.moves.borrow(), vec!((value, value_2), (value_2, value_3)));
 
     Ok(())
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,75 @@
+use alloc::sync::Arc;
+
+use super::base::PrimitiveType;
+use super::base::SymbolRegistry;
+use super::util::HashMap;
+
+/// Groups of primitive types preferentially allocated in separate regions
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum RegionAffinity {
+    /// Regions shared by types without a specific affinity
+    General,
+    /// Regions for texts
+    Text,
+    /// Regions for lists and tuples
+    Sequence,
+    /// Regions for objects
+    Object
+}
+
+/// Options to create an isolate
+#[derive(Clone)]
+pub struct IsolateOptions {
+    symbol_registry: Option<Arc<SymbolRegistry>>,
+    region_affinities: HashMap<PrimitiveType, RegionAffinity>
+}
+
+impl IsolateOptions {
+
+    /// Create options with default region affinities, texts, lists and
+    /// tuples, and objects are allocated in separate regions
+    pub fn new() -> IsolateOptions {
+
+        let mut region_affinities = HashMap::new();
+        region_affinities.insert(PrimitiveType::Text, RegionAffinity::Text);
+        region_affinities.insert(PrimitiveType::List, RegionAffinity::Sequence);
+        region_affinities.insert(PrimitiveType::Tuple, RegionAffinity::Sequence);
+        region_affinities.insert(PrimitiveType::Object, RegionAffinity::Object);
+
+        IsolateOptions {
+            symbol_registry: None,
+            region_affinities: region_affinities
+        }
+
+    }
+
+    /// Resolve text symbols by a registry shared with other isolates
+    pub fn symbol_registry(mut self, symbol_registry: Arc<SymbolRegistry>) -> IsolateOptions {
+        self.symbol_registry = Some(symbol_registry);
+        self
+    }
+
+    /// Overwrite the region affinity of a primitive type
+    pub fn region_affinity(mut self, primitive_type: PrimitiveType, affinity: RegionAffinity) -> IsolateOptions {
+        self.region_affinities.insert(primitive_type, affinity);
+        self
+    }
+
+    /// Allocate values of all primitive types in the same regions
+    pub fn without_region_affinities(mut self) -> IsolateOptions {
+        self.region_affinities.clear();
+        self
+    }
+
+    pub fn get_symbol_registry(&self) -> Option<&Arc<SymbolRegistry>> {
+        self.symbol_registry.as_ref()
+    }
+
+    pub fn get_region_affinity(&self, primitive_type: PrimitiveType) -> RegionAffinity {
+        match self.region_affinities.get(&primitive_type) {
+            Some(affinity) => *affinity,
+            None => RegionAffinity::General
+        }
+    }
+
+}
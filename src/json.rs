@@ -0,0 +1,563 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::base::PrimitiveType;
+use super::base::Value;
+use super::context::Context;
+use super::context::RecursionGuard;
+use super::internal_slot::InternalSlot;
+use super::internal_slot::List;
+use super::internal_slot::Text;
+use super::isolate::Isolate;
+use super::storage::Pinned;
+
+/// Scope of symbols used as keys of JSON objects
+pub const JSON_SYMBOL_SCOPE: &str = "json";
+
+const MAX_JSON_DEPTH: usize = 512;
+
+/// Parser building values in a region from JSON text
+pub struct JsonParser<'a> {
+    isolate: &'a Isolate,
+    region_id: u32,
+    context: &'a Box<dyn Context>,
+    text: &'a [u8],
+    offset: usize,
+    depth: usize
+}
+
+impl<'a> JsonParser<'a> {
+
+    pub fn new(isolate: &'a Isolate, region_id: u32, text: &'a str, context: &'a Box<dyn Context>) -> JsonParser<'a> {
+        JsonParser {
+            isolate: isolate,
+            region_id: region_id,
+            context: context,
+            text: text.as_bytes(),
+            offset: 0,
+            depth: 0
+        }
+    }
+
+    /// Parse the whole text as a single JSON value
+    pub fn parse(&mut self) -> Result<Pinned, Error> {
+
+        let value = self.parse_value()?;
+
+        self.skip_whitespaces();
+        if self.offset < self.text.len() {
+            return Err(self.make_error("Unexpected trailing characters"));
+        }
+
+        Ok(value)
+
+    }
+
+    fn make_error(&self, message: &str) -> Error {
+        Error::new(MalformedData, &format!("Invalid JSON at offset {}: {}", self.offset, message))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.offset).copied()
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        if self.peek() != Some(byte) {
+            return Err(self.make_error(&format!("Expected '{}'", byte as char)));
+        }
+        self.offset += 1;
+        Ok(())
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if !self.text[self.offset ..].starts_with(keyword.as_bytes()) {
+            return Err(self.make_error("Unexpected character"));
+        }
+        self.offset += keyword.len();
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Pinned, Error> {
+
+        self.skip_whitespaces();
+
+        match self.peek() {
+            None => Err(self.make_error("Unexpected end of text")),
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_list(),
+            Some(b'"') => {
+                let string = self.parse_string()?;
+                self.make_text(&string)
+            },
+            Some(b't') => {
+                self.expect_keyword("true")?;
+                Pinned::new(self.context, Value::make_boolean(true))
+            },
+            Some(b'f') => {
+                self.expect_keyword("false")?;
+                Pinned::new(self.context, Value::make_boolean(false))
+            },
+            Some(b'n') => {
+                self.expect_keyword("null")?;
+                Pinned::new(self.context, Value::make_null())
+            },
+            Some(_) => self.parse_number()
+        }
+
+    }
+
+    /// Enter a nested value, the recursion of the context is left once the
+    /// guard returned dropped
+    fn enter(&mut self) -> Result<RecursionGuard<'a>, Error> {
+        self.context.check_interrupted()?;
+        self.depth += 1;
+        if self.depth > MAX_JSON_DEPTH {
+            return Err(self.make_error("Too deep nested"));
+        }
+        RecursionGuard::enter(self.context)
+    }
+
+    fn parse_object(&mut self) -> Result<Pinned, Error> {
+
+        let _guard = self.enter()?;
+        self.expect(b'{')?;
+
+        let layout_token = self.context.get_slot_layout_token();
+
+        let object = self.isolate.gain_slot(self.region_id, PrimitiveType::Object, self.isolate.get_object_prototype(), layout_token)?;
+        let object = Pinned::new(self.context, object)?;
+
+        self.skip_whitespaces();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+        } else {
+            loop {
+                self.skip_whitespaces();
+                let key = self.parse_string()?;
+                self.skip_whitespaces();
+                self.expect(b':')?;
+                let value = self.parse_value()?;
+                let symbol = self.isolate.get_text_symbol(JSON_SYMBOL_SCOPE, &key);
+                let subject = object.get_value();
+                self.isolate.set_own_property(subject, subject, symbol, value.get_value(), self.context)?;
+                self.skip_whitespaces();
+                match self.peek() {
+                    Some(b',') => { self.offset += 1; },
+                    Some(b'}') => { self.offset += 1; break; },
+                    _ => { return Err(self.make_error("Expected ',' or '}'")); }
+                }
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(object)
+
+    }
+
+    fn parse_list(&mut self) -> Result<Pinned, Error> {
+
+        let _guard = self.enter()?;
+        self.expect(b'[')?;
+
+        let mut elements = Vec::new();
+
+        self.skip_whitespaces();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+        } else {
+            loop {
+                elements.push(self.parse_value()?);
+                self.skip_whitespaces();
+                match self.peek() {
+                    Some(b',') => { self.offset += 1; },
+                    Some(b']') => { self.offset += 1; break; },
+                    _ => { return Err(self.make_error("Expected ',' or ']'")); }
+                }
+            }
+        }
+
+        self.depth -= 1;
+
+        let layout_token = self.context.get_slot_layout_token();
+
+        let value = self.isolate.gain_slot(self.region_id, PrimitiveType::List, self.isolate.get_list_prototype(), layout_token)?;
+        let value = Pinned::new(self.context, value)?;
+
+        let list: Arc<dyn InternalSlot> = Arc::new(List::new(value.get_value(), elements.iter().map(|element| element.get_value()).collect()));
+
+        self.isolate.set_internal_slot(value.get_value(), 0, list, self.context)?;
+
+        Ok(value)
+
+    }
+
+    fn make_text(&mut self, string: &str) -> Result<Pinned, Error> {
+
+        let layout_token = self.context.get_slot_layout_token();
+
+        let value = self.isolate.gain_slot(self.region_id, PrimitiveType::Text, self.isolate.get_text_prototype(), layout_token)?;
+        let value = Pinned::new(self.context, value)?;
+
+        let text: Arc<dyn InternalSlot> = Arc::new(Text::new(string));
+
+        self.isolate.set_internal_slot(value.get_value(), 0, text, self.context)?;
+
+        Ok(value)
+
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+
+        self.expect(b'"')?;
+
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(self.make_error("Unterminated string"));
+                },
+                Some(b'"') => {
+                    self.offset += 1;
+                    break;
+                },
+                Some(b'\\') => {
+                    self.offset += 1;
+                    let escaped = match self.peek() {
+                        Some(b'u') => {
+                            self.offset += 1;
+                            self.parse_unicode_escape()?
+                        },
+                        Some(byte) => {
+                            self.offset += 1;
+                            match byte {
+                                b'"' => '"',
+                                b'\\' => '\\',
+                                b'/' => '/',
+                                b'b' => '\u{8}',
+                                b'f' => '\u{c}',
+                                b'n' => '\n',
+                                b'r' => '\r',
+                                b't' => '\t',
+                                _ => { return Err(self.make_error("Invalid escape")); }
+                            }
+                        },
+                        None => {
+                            return Err(self.make_error("Unterminated string"));
+                        }
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                },
+                Some(byte) if byte < 0x20 => {
+                    return Err(self.make_error("Control character in string"));
+                },
+                Some(byte) => {
+                    bytes.push(byte);
+                    self.offset += 1;
+                }
+            }
+        }
+
+        match String::from_utf8(bytes) {
+            Ok(string) => Ok(string),
+            Err(_) => Err(self.make_error("Invalid UTF-8 in string"))
+        }
+
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, Error> {
+
+        if self.offset + 4 > self.text.len() {
+            return Err(self.make_error("Invalid unicode escape"));
+        }
+
+        let mut code = 0;
+        for _ in 0 .. 4 {
+            let digit = match (self.text[self.offset] as char).to_digit(16) {
+                Some(digit) => digit,
+                None => { return Err(self.make_error("Invalid unicode escape")); }
+            };
+            code = (code << 4) | digit;
+            self.offset += 1;
+        }
+
+        Ok(code)
+
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, Error> {
+
+        let high = self.parse_hex4()?;
+
+        let code = if (0xd800 .. 0xdc00).contains(&high) {
+            if !self.text[self.offset ..].starts_with(b"\\u") {
+                return Err(self.make_error("Unpaired surrogate"));
+            }
+            self.offset += 2;
+            let low = self.parse_hex4()?;
+            if !(0xdc00 .. 0xe000).contains(&low) {
+                return Err(self.make_error("Unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+
+        match core::char::from_u32(code) {
+            Some(character) => Ok(character),
+            None => Err(self.make_error("Unpaired surrogate"))
+        }
+
+    }
+
+    fn parse_number(&mut self) -> Result<Pinned, Error> {
+
+        let start = self.offset;
+        let mut is_integer = true;
+
+        if self.peek() == Some(b'-') {
+            self.offset += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => { self.offset += 1; },
+            Some(b'1' ..= b'9') => { self.skip_digits(); },
+            _ => { return Err(self.make_error("Unexpected character")); }
+        }
+
+        if self.peek() == Some(b'.') {
+            is_integer = false;
+            self.offset += 1;
+            if !self.skip_digits() {
+                return Err(self.make_error("Expected digits"));
+            }
+        }
+
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            is_integer = false;
+            self.offset += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.offset += 1;
+            }
+            if !self.skip_digits() {
+                return Err(self.make_error("Expected digits"));
+            }
+        }
+
+        // Only ASCII characters are consumed
+        let number = core::str::from_utf8(&self.text[start .. self.offset]).unwrap_or("");
+
+        if is_integer {
+            if let Ok(integer) = number.parse::<i32>() {
+                return Pinned::new(self.context, Value::make_integer(integer));
+            }
+        }
+
+        match number.parse::<f64>() {
+            Ok(float) => Pinned::new(self.context, Value::make_float(float)),
+            Err(_) => Err(self.make_error("Invalid number"))
+        }
+
+    }
+
+    fn skip_digits(&mut self) -> bool {
+        let start = self.offset;
+        while let Some(b'0' ..= b'9') = self.peek() {
+            self.offset += 1;
+        }
+        self.offset > start
+    }
+
+}
+
+/// Writer serializing values into JSON text
+pub struct JsonWriter<'a> {
+    isolate: &'a Isolate,
+    context: &'a Box<dyn Context>,
+    visiting: Vec<Value>,
+    output: String
+}
+
+impl<'a> JsonWriter<'a> {
+
+    pub fn new(isolate: &'a Isolate, context: &'a Box<dyn Context>) -> JsonWriter<'a> {
+        JsonWriter {
+            isolate: isolate,
+            context: context,
+            visiting: Vec::new(),
+            output: String::new()
+        }
+    }
+
+    /// Write the value and get the JSON text
+    pub fn write(mut self, value: Value) -> Result<String, Error> {
+        self.write_value(value)?;
+        Ok(self.output)
+    }
+
+    fn write_value(&mut self, value: Value) -> Result<(), Error> {
+
+        match value.get_primitive_type() {
+            PrimitiveType::Undefined | PrimitiveType::Null => {
+                self.output.push_str("null");
+            },
+            PrimitiveType::Boolean => {
+                self.output.push_str(if value.get_boolean_data()? { "true" } else { "false" });
+            },
+            PrimitiveType::Integer => {
+                if value.is_cardinal() {
+                    self.output.push_str(&value.get_cardinal_data()?.to_string());
+                } else {
+                    self.output.push_str(&value.get_integer_data()?.to_string());
+                }
+            },
+            PrimitiveType::Float => {
+                let float = value.get_float_data()?;
+                if float.is_finite() {
+                    self.output.push_str(&float.to_string());
+                } else {
+                    self.output.push_str("null");
+                }
+            },
+            PrimitiveType::Symbol => {
+                return Err(Error::new(TypeNotMatch, "Symbol could not be converted to JSON"));
+            },
+            PrimitiveType::Text => {
+                let text = self.isolate.extract_text_slot(value, self.context)?;
+                self.write_string(&text.to_string());
+            },
+            PrimitiveType::BigInteger => {
+                let big_int = self.isolate.extract_big_integer(value, self.context)?;
+                self.output.push_str(&big_int.to_string());
+            },
+            PrimitiveType::List => {
+                let elements = self.isolate.extract_list(value, self.context)?;
+                self.write_elements(value, elements)?;
+            },
+            PrimitiveType::Tuple => {
+                let elements = self.isolate.extract_tuple(value, self.context)?;
+                self.write_elements(value, elements)?;
+            },
+            PrimitiveType::Object => {
+                self.write_object(value)?;
+            }
+        }
+
+        Ok(())
+
+    }
+
+    fn enter(&mut self, value: Value) -> Result<RecursionGuard<'a>, Error> {
+        self.context.check_interrupted()?;
+        if self.visiting.contains(&value) {
+            return Err(Error::new(TypeNotMatch, "Cyclic value could not be converted to JSON"));
+        }
+        let guard = RecursionGuard::enter(self.context)?;
+        self.visiting.push(value);
+        Ok(guard)
+    }
+
+    fn leave(&mut self) {
+        self.visiting.pop();
+    }
+
+    fn write_elements(&mut self, value: Value, elements: Vec<Value>) -> Result<(), Error> {
+
+        let _guard = self.enter(value)?;
+
+        let mut pinneds = Vec::new();
+        for element in elements {
+            pinneds.push(Pinned::new(self.context, element)?);
+        }
+
+        self.output.push('[');
+        for (index, element) in pinneds.iter().enumerate() {
+            if index > 0 {
+                self.output.push(',');
+            }
+            self.write_value(element.get_value())?;
+        }
+        self.output.push(']');
+
+        self.leave();
+
+        Ok(())
+
+    }
+
+    fn write_object(&mut self, value: Value) -> Result<(), Error> {
+
+        let _guard = self.enter(value)?;
+
+        let prototype_symbol = self.isolate.get_prototype_symbol();
+
+        let mut keys = Vec::new();
+        for symbol in self.isolate.list_own_property_symbols(value, value, self.context)? {
+            if symbol == prototype_symbol {
+                continue;
+            }
+            if let Some(text) = self.isolate.resolve_symbol_info(symbol)?.get_text() {
+                keys.push((text.as_ref().clone(), symbol));
+            }
+        }
+        keys.sort_by(|(key, _), (key_2, _)| key.cmp(key_2));
+
+        self.output.push('{');
+        let mut first = true;
+        for (key, symbol) in keys {
+            let property = self.isolate.get_own_property(value, value, symbol, None, self.context)?;
+            if property.get_value().is_undefined() {
+                continue;
+            }
+            if !first {
+                self.output.push(',');
+            }
+            first = false;
+            self.write_string(&key);
+            self.output.push(':');
+            self.write_value(property.get_value())?;
+        }
+        self.output.push('}');
+
+        self.leave();
+
+        Ok(())
+
+    }
+
+    fn write_string(&mut self, string: &str) {
+
+        self.output.push('"');
+        for character in string.chars() {
+            match character {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\u{8}' => self.output.push_str("\\b"),
+                '\u{c}' => self.output.push_str("\\f"),
+                character if (character as u32) < 0x20 => {
+                    self.output.push_str(&format!("\\u{:04x}", character as u32));
+                },
+                character => self.output.push(character)
+            }
+        }
+        self.output.push('"');
+
+    }
+
+}
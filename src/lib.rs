@@ -1,62 +1,196 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))] extern crate alloc;
+#[cfg(feature = "std")] extern crate std as alloc;
+
+mod access_policy;
 mod base;
 mod barrier;
+#[cfg(feature = "capi")] pub mod capi;
+mod cold_region;
+mod coercion;
+mod cancellation;
 mod collector;
 mod context;
+mod context_builder;
+mod convert;
+mod describe;
+#[cfg(feature = "fault-injection")] mod fault;
 mod field_shortcuts;
+mod fork_color;
+#[cfg(feature = "fuzzing")] pub mod fuzzing;
+mod gc_listener;
+mod handle_block;
 mod isolate;
+mod isolate_options;
 mod internal_slot;
+mod json;
+mod metrics;
+mod profiler;
+#[cfg(feature = "provenance")] mod provenance;
+mod quota;
 mod reference_map;
 mod region;
+mod region_cache;
 mod root;
+mod root_arena;
+#[cfg(feature = "std")] mod serialization;
+mod shared_region;
 mod slot;
+mod space;
+mod standard_context;
 mod storage;
+mod transaction;
+#[cfg(feature = "std")] mod transfer;
 mod trap;
 mod util;
+mod watermark;
 
-#[cfg(test)] mod test;
+#[cfg(any(test, feature = "testing"))] mod test;
+#[cfg(feature = "testing")] pub mod testing;
 
+pub use access_policy::AccessPolicy;
+
+pub use base::CustomErrorInfo;
 pub use base::Error;
+pub use base::ErrorCode;
 pub use base::ErrorType;
 pub use base::PrimitiveType;
 pub use base::Value;
 pub use base::Symbol;
+pub use base::SymbolCacheEntry;
 pub use base::SymbolInfo;
+pub use base::SymbolRegistry;
+pub use base::WellKnownSymbols;
 
+pub use coercion::ValueCoercer;
+pub use cancellation::CancellationToken;
 pub use collector::Collector;
+pub use collector::GcStressMode;
+pub use cold_region::ColdRegionStorage;
+pub use cold_region::MemoryColdRegionStorage;
+
 pub use context::Context;
+pub use context::RecursionGuard;
+pub use context_builder::ContextBuilder;
+pub use convert::FromValue;
+pub use convert::ToValue;
+pub use describe::DescribeOptions;
+pub use describe::DisplayValue;
+pub use describe::TextFormatter;
+#[cfg(feature = "fault-injection")] pub use fault::FaultInjector;
+#[cfg(feature = "fault-injection")] pub use fault::LockAccess;
+pub use gc_listener::GcListener;
+pub use gc_listener::GcPhase;
+pub use gc_listener::GcPhaseStats;
+pub use gc_listener::GcStats;
 pub use isolate::Isolate;
+pub use isolate_options::IsolateOptions;
+pub use isolate_options::RegionAffinity;
 
 pub use field_shortcuts::FieldShortcuts;
 pub use field_shortcuts::FieldTemplate;
 pub use field_shortcuts::FieldToken;
 
+pub use internal_slot::BigInt;
+pub use internal_slot::Bytes;
+pub use internal_slot::Decimal;
+pub use internal_slot::Foreign;
 pub use internal_slot::InternalSlot;
 pub use internal_slot::List;
+pub use internal_slot::Map;
+pub use internal_slot::Set;
 pub use internal_slot::Text;
+pub use internal_slot::Timestamp;
 pub use internal_slot::Tuple;
+pub use internal_slot::Float32List;
+pub use internal_slot::Float64List;
+pub use internal_slot::Int32List;
+pub use internal_slot::Int64List;
+pub use internal_slot::TypedElement;
+pub use internal_slot::TypedList;
+pub use internal_slot::WeakValueMap;
+
+pub use json::JSON_SYMBOL_SCOPE;
+
+pub use metrics::MetricKind;
+pub use metrics::MetricSample;
+pub use metrics::Metrics;
+pub use metrics::MetricsSnapshot;
+
+pub use profiler::AllocationProfile;
+pub use profiler::AllocationSite;
+pub use profiler::HeapCensus;
+pub use profiler::HeapCensusDiff;
+#[cfg(feature = "provenance")] pub use provenance::Provenance;
+pub use quota::Quota;
+pub use quota::QuotaUsage;
+
+pub use reference_map::ReferenceMapStats;
+
+pub use region::RegionKind;
+pub use region_cache::RegionCache;
 
 pub use root::DropListener;
+pub use root::DropNotice;
+pub use root::PayloadDropListener;
 pub use root::Root;
 pub use root::Roots;
 pub use root::WeakRoot;
+pub use root::WeakRootCensus;
 pub use root::WeakIdGenerator;
+pub use root_arena::RootArena;
+
+#[cfg(feature = "std")] pub use serialization::SerializationFormat;
+
+pub use shared_region::SharedRegion;
+
+pub use space::Space;
+pub use space::SpacePolicy;
+
+pub use standard_context::ContextListener;
+pub use standard_context::StandardContext;
 
 pub use storage::Local;
 pub use storage::Persistent;
+pub use storage::PinGuard;
 pub use storage::Pinned;
 pub use storage::Weak;
 
+pub use transaction::Transaction;
+
+#[cfg(feature = "std")] pub use transfer::TransferPacket;
+
+pub use trap::Args;
+pub use trap::ConstPropertyTrap;
+pub use trap::NativeFunction;
 pub use trap::PropertyTrap;
 pub use trap::SlotTrap;
 pub use trap::SlotTrapResult;
+pub use trap::PooledTrapInfo;
+pub use trap::StandardTrapInfo;
+pub use trap::StandardTrapInfoFactory;
 pub use trap::TrapInfo;
+pub use trap::TrapInfoFactory;
+pub use trap::TrapInfoPool;
 
+pub use util::LockClass;
+#[cfg(feature = "lock-diagnostics")] pub use util::LockIssue;
+#[cfg(feature = "lock-diagnostics")] pub use util::LockReport;
+#[cfg(feature = "lock-diagnostics")] pub use util::clear_lock_orders;
+#[cfg(feature = "lock-diagnostics")] pub use util::set_long_held_threshold;
+#[cfg(feature = "lock-diagnostics")] pub use util::take_lock_reports;
+#[cfg(feature = "lock-stats")] pub use util::LockStats;
+pub use util::PageMapStats;
 pub use util::ReentrantLock;
 pub use util::ReentrantLockReadGuard;
+pub use util::ReentrantLockUpgradeableReadGuard;
 pub use util::ReentrantLockWriteGuard;
 pub use util::ReentrantToken;
 pub use util::RwLock;
 pub use util::RwLockReadGuard;
+pub use util::RwLockUpgradeableReadGuard;
 pub use util::RwLockWriteGuard;
 pub use util::SpinLock;
-pub use util::SpinLockGuard;
\ No newline at end of file
+pub use util::SpinLockGuard;
+pub use watermark::RegionWatermarkListener;
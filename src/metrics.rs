@@ -0,0 +1,262 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+use super::isolate::Isolate;
+
+/// Kind of a metric sample
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MetricKind {
+
+    /// Monotonically increasing value
+    Counter,
+
+    /// Value which may go up and down
+    Gauge
+
+}
+
+/// A single named metric sample for exporters
+#[derive(Clone, Debug)]
+pub struct MetricSample {
+    name: &'static str,
+    help: &'static str,
+    kind: MetricKind,
+    value: u64
+}
+
+impl MetricSample {
+
+    fn new(name: &'static str, help: &'static str, kind: MetricKind, value: u64) -> MetricSample {
+        MetricSample {
+            name: name,
+            help: help,
+            kind: kind,
+            value: value
+        }
+    }
+
+    /// Get the name of the metric
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the help text of the metric
+    pub fn get_help(&self) -> &'static str {
+        self.help
+    }
+
+    /// Get the kind of the metric
+    pub fn get_kind(&self) -> MetricKind {
+        self.kind
+    }
+
+    /// Get the value of the metric
+    pub fn get_value(&self) -> u64 {
+        self.value
+    }
+
+}
+
+/// Point-in-time values of all isolate metrics
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSnapshot {
+
+    /// Slots currently occupied in all regions
+    pub live_slots: u64,
+
+    /// Regions currently allocated
+    pub regions: u64,
+
+    /// Values currently kept as roots
+    pub roots: u64,
+
+    /// Values currently observed by weak roots
+    pub weak_roots: u64,
+
+    /// Symbols currently registered
+    pub symbols: u64,
+
+    /// Slots gained since the isolate created
+    pub slots_gained: u64,
+
+    /// Garbage collections finished since the isolate created
+    pub gc_runs: u64,
+
+    /// Slot trap calls since the isolate created
+    pub slot_trap_calls: u64,
+
+    /// Property trap calls since the isolate created
+    pub property_trap_calls: u64
+
+}
+
+impl MetricsSnapshot {
+
+    /// List all samples of the snapshot in a stable order
+    pub fn list_samples(&self) -> Vec<MetricSample> {
+        vec!(
+            MetricSample::new("rogiso_live_slots", "Slots currently occupied in all regions", MetricKind::Gauge, self.live_slots),
+            MetricSample::new("rogiso_regions", "Regions currently allocated", MetricKind::Gauge, self.regions),
+            MetricSample::new("rogiso_roots", "Values currently kept as roots", MetricKind::Gauge, self.roots),
+            MetricSample::new("rogiso_weak_roots", "Values currently observed by weak roots", MetricKind::Gauge, self.weak_roots),
+            MetricSample::new("rogiso_symbols", "Symbols currently registered", MetricKind::Gauge, self.symbols),
+            MetricSample::new("rogiso_slots_gained_total", "Slots gained since the isolate created", MetricKind::Counter, self.slots_gained),
+            MetricSample::new("rogiso_gc_runs_total", "Garbage collections finished", MetricKind::Counter, self.gc_runs),
+            MetricSample::new("rogiso_slot_trap_calls_total", "Slot trap calls", MetricKind::Counter, self.slot_trap_calls),
+            MetricSample::new("rogiso_property_trap_calls_total", "Property trap calls", MetricKind::Counter, self.property_trap_calls)
+        )
+    }
+
+}
+
+/// Counters recorded by an isolate
+pub struct MetricsCounters {
+    slots_gained: AtomicU64,
+    gc_runs: AtomicU64,
+    slot_trap_calls: AtomicU64,
+    property_trap_calls: AtomicU64
+}
+
+impl MetricsCounters {
+
+    pub fn new() -> MetricsCounters {
+        MetricsCounters {
+            slots_gained: AtomicU64::new(0),
+            gc_runs: AtomicU64::new(0),
+            slot_trap_calls: AtomicU64::new(0),
+            property_trap_calls: AtomicU64::new(0)
+        }
+    }
+
+    #[inline]
+    pub fn increase_slots_gained(&self) {
+        self.slots_gained.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn increase_gc_runs(&self) {
+        self.gc_runs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn increase_slot_trap_calls(&self) {
+        self.slot_trap_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn increase_property_trap_calls(&self) {
+        self.property_trap_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+}
+
+/// Pull-style metrics view of an isolate
+pub struct Metrics<'a> {
+    isolate: &'a Isolate
+}
+
+impl<'a> Metrics<'a> {
+
+    pub fn new(isolate: &'a Isolate) -> Metrics<'a> {
+        Metrics {
+            isolate: isolate
+        }
+    }
+
+    /// Take a snapshot of current metrics
+    pub fn snapshot(&self) -> MetricsSnapshot {
+
+        let counters = self.isolate.get_metrics_counters();
+
+        MetricsSnapshot {
+            live_slots: self.isolate.count_live_slots() as u64,
+            regions: self.isolate.get_region_number() as u64,
+            roots: self.isolate.count_roots() as u64,
+            weak_roots: self.isolate.count_weak_roots() as u64,
+            symbols: self.isolate.count_symbols() as u64,
+            slots_gained: counters.slots_gained.load(Ordering::Relaxed),
+            gc_runs: counters.gc_runs.load(Ordering::Relaxed),
+            slot_trap_calls: counters.slot_trap_calls.load(Ordering::Relaxed),
+            property_trap_calls: counters.property_trap_calls.load(Ordering::Relaxed)
+        }
+
+    }
+
+}
+
+#[cfg(test)] use super::base::Error;
+#[cfg(test)] use super::base::PrimitiveType;
+#[cfg(test)] use super::base::Value;
+
+#[test]
+fn test_metrics_snapshot() -> Result<(), Error> {
+
+    let isolate = Isolate::create()?;
+
+    let snapshot = isolate.metrics().snapshot();
+    assert_eq!(snapshot.regions, 1);
+    assert_eq!(snapshot.live_slots, 12);
+    assert_eq!(snapshot.roots, 0);
+    assert_eq!(snapshot.slots_gained, 12);
+    assert_eq!(snapshot.gc_runs, 0);
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let region_id = isolate.create_region()?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), &layout_token)?;
+    let root = isolate.add_root(value, &layout_token)?;
+
+    let snapshot = isolate.metrics().snapshot();
+    assert_eq!(snapshot.regions, 2);
+    assert_eq!(snapshot.live_slots, 13);
+    assert_eq!(snapshot.roots, 1);
+    assert_eq!(snapshot.slots_gained, 13);
+
+    isolate.remove_root(&root)?;
+
+    let snapshot = isolate.metrics().snapshot();
+    assert_eq!(snapshot.roots, 0);
+
+    let samples = snapshot.list_samples();
+    assert_eq!(samples[0].get_name(), "rogiso_live_slots");
+    assert_eq!(samples[0].get_kind(), MetricKind::Gauge);
+    assert_eq!(samples[0].get_value(), 13);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_metrics_trap_calls() -> Result<(), Error> {
+
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+    use super::context::Context;
+    use super::test::TestContext2;
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let value = isolate.gain_slot(region_id, PrimitiveType::Object, Value::make_null(), context.get_slot_layout_token())?;
+
+    let symbol = isolate.get_text_symbol("test", "foo");
+
+    let snapshot = isolate.metrics().snapshot();
+
+    isolate.set_own_property(value, value, symbol, Value::make_integer(1), &context)?;
+    assert_eq!(isolate.metrics().snapshot().property_trap_calls, snapshot.property_trap_calls);
+
+    isolate.set_own_property(value, value, symbol, Value::make_integer(2), &context)?;
+    assert_eq!(isolate.get_own_property(value, value, symbol, None, &context)?.get_value(), Value::make_integer(2));
+
+    let snapshot_2 = isolate.metrics().snapshot();
+    assert_eq!(snapshot_2.property_trap_calls, snapshot.property_trap_calls + 2);
+    assert_eq!(snapshot_2.slot_trap_calls, snapshot.slot_trap_calls);
+
+    Ok(())
+
+}
@@ -0,0 +1,379 @@
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::hash::Hash;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+use super::base::PrimitiveType;
+use super::base::Value;
+use super::util::HashMap;
+use super::util::RwLock;
+
+/// Allocations recorded at the same primitive type, region and tag
+#[derive(Clone, Debug)]
+pub struct AllocationSite {
+    primitive_type: PrimitiveType,
+    region_id: u32,
+    tag: Option<String>,
+    samples: u64
+}
+
+impl AllocationSite {
+
+    pub fn get_primitive_type(&self) -> PrimitiveType {
+        self.primitive_type
+    }
+
+    pub fn get_region_id(&self) -> u32 {
+        self.region_id
+    }
+
+    /// Get the tag provided by the context gaining the slots
+    pub fn get_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// Get count of sampled allocations of the site
+    pub fn get_samples(&self) -> u64 {
+        self.samples
+    }
+
+}
+
+/// Report of sampled allocations, sites are sorted from the hottest
+#[derive(Clone, Debug)]
+pub struct AllocationProfile {
+    sample_interval: u64,
+    sites: Vec<AllocationSite>
+}
+
+impl AllocationProfile {
+
+    /// Get the interval allocations sampled by
+    pub fn get_sample_interval(&self) -> u64 {
+        self.sample_interval
+    }
+
+    pub fn list_sites(&self) -> &[AllocationSite] {
+        &self.sites
+    }
+
+    /// Count sampled allocations of all sites
+    pub fn count_samples(&self) -> u64 {
+        self.sites.iter().map(|site| site.samples).sum()
+    }
+
+    /// Estimate allocations of a site, which is its samples multiplied by
+    /// the sample interval
+    pub fn estimate_allocations(&self, site: &AllocationSite) -> u64 {
+        site.samples * self.sample_interval
+    }
+
+}
+
+/// Sampler recording every N-th slot gained by an isolate
+pub struct AllocationSampler {
+    sampling: AtomicBool,
+    sample_interval: AtomicU64,
+    allocations: AtomicU64,
+    sites_rw_lock: RwLock,
+    sites: RefCell<HashMap<(PrimitiveType, u32, Option<String>), u64>>
+}
+
+impl AllocationSampler {
+
+    pub fn new() -> AllocationSampler {
+        AllocationSampler {
+            sampling: AtomicBool::new(false),
+            sample_interval: AtomicU64::new(1),
+            allocations: AtomicU64::new(0),
+            sites_rw_lock: RwLock::new(),
+            sites: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Start sampling every N-th allocation, sites recorded before are kept
+    pub fn start(&self, sample_interval: u64) {
+        self.allocations.store(0, Ordering::SeqCst);
+        self.sample_interval.store(sample_interval.max(1), Ordering::SeqCst);
+        self.sampling.store(true, Ordering::SeqCst);
+    }
+
+    pub fn stop(&self) {
+        self.sampling.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_sampling(&self) -> bool {
+        self.sampling.load(Ordering::Relaxed)
+    }
+
+    /// Record an allocation if it is sampled
+    #[inline]
+    pub fn record(&self, primitive_type: PrimitiveType, region_id: u32, tag: Option<&str>) {
+
+        if !self.sampling.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let sample_interval = self.sample_interval.load(Ordering::Relaxed);
+        if !(self.allocations.fetch_add(1, Ordering::Relaxed) + 1).is_multiple_of(sample_interval) {
+            return;
+        }
+
+        let _guard = self.sites_rw_lock.lock_write();
+
+        let key = (primitive_type, region_id, tag.map(|tag| tag.to_string()));
+        *self.sites.borrow_mut().entry(key).or_insert(0) += 1;
+
+    }
+
+    /// Aggregate sampled allocations into a profile
+    pub fn create_profile(&self) -> AllocationProfile {
+
+        let _guard = self.sites_rw_lock.lock_read();
+
+        let mut sites: Vec<AllocationSite> = self.sites.borrow().iter().map(|((primitive_type, region_id, tag), samples)| {
+            AllocationSite {
+                primitive_type: *primitive_type,
+                region_id: *region_id,
+                tag: tag.clone(),
+                samples: *samples
+            }
+        }).collect();
+
+        sites.sort_by(|a, b| {
+            b.samples.cmp(&a.samples)
+                .then(a.region_id.cmp(&b.region_id))
+                .then(a.tag.cmp(&b.tag))
+        });
+
+        AllocationProfile {
+            sample_interval: self.sample_interval.load(Ordering::SeqCst),
+            sites: sites
+        }
+
+    }
+
+    /// Clear all sampled allocations
+    pub fn reset(&self) {
+
+        let _guard = self.sites_rw_lock.lock_write();
+
+        self.sites.borrow_mut().clear();
+
+        self.allocations.store(0, Ordering::SeqCst);
+
+    }
+
+}
+
+/// Live slots of an isolate counted by primitive types, prototypes and
+/// regions at a point in time
+#[derive(Clone, Debug)]
+pub struct HeapCensus {
+    slots: u64,
+    primitive_types: HashMap<PrimitiveType, u64>,
+    prototypes: HashMap<Value, u64>,
+    regions: HashMap<u32, u64>
+}
+
+impl HeapCensus {
+
+    pub fn new() -> HeapCensus {
+        HeapCensus {
+            slots: 0,
+            primitive_types: HashMap::new(),
+            prototypes: HashMap::new(),
+            regions: HashMap::new()
+        }
+    }
+
+    /// Count a live slot
+    pub fn record(&mut self, primitive_type: PrimitiveType, prototype: Value, region_id: u32) {
+        self.slots += 1;
+        *self.primitive_types.entry(primitive_type).or_insert(0) += 1;
+        *self.prototypes.entry(prototype).or_insert(0) += 1;
+        *self.regions.entry(region_id).or_insert(0) += 1;
+    }
+
+    /// Count all live slots
+    pub fn get_slot_count(&self) -> u64 {
+        self.slots
+    }
+
+    pub fn get_primitive_type_count(&self, primitive_type: PrimitiveType) -> u64 {
+        self.primitive_types.get(&primitive_type).copied().unwrap_or(0)
+    }
+
+    pub fn get_prototype_count(&self, prototype: Value) -> u64 {
+        self.prototypes.get(&prototype).copied().unwrap_or(0)
+    }
+
+    pub fn get_region_count(&self, region_id: u32) -> u64 {
+        self.regions.get(&region_id).copied().unwrap_or(0)
+    }
+
+    /// List primitive types with their counts, sorted from the most
+    pub fn list_primitive_types(&self) -> Vec<(PrimitiveType, u64)> {
+        sort_counts(self.primitive_types.iter().map(|(key, count)| (*key, *count)).collect())
+    }
+
+    /// List prototypes with counts of slots inheriting them directly, sorted
+    /// from the most
+    pub fn list_prototypes(&self) -> Vec<(Value, u64)> {
+        sort_counts(self.prototypes.iter().map(|(key, count)| (*key, *count)).collect())
+    }
+
+    /// List regions with their counts, sorted from the most
+    pub fn list_regions(&self) -> Vec<(u32, u64)> {
+        sort_counts(self.regions.iter().map(|(key, count)| (*key, *count)).collect())
+    }
+
+    /// Compare with an earlier census, only kinds of slots with counts
+    /// changed are reported
+    ///
+    /// Prototypes are compared by their values, a prototype moved by
+    /// compactions between the censuses is reported as two prototypes
+    pub fn diff(&self, earlier: &HeapCensus) -> HeapCensusDiff {
+        HeapCensusDiff {
+            slots: self.slots as i64 - earlier.slots as i64,
+            primitive_types: diff_counts(&self.primitive_types, &earlier.primitive_types),
+            prototypes: diff_counts(&self.prototypes, &earlier.prototypes),
+            regions: diff_counts(&self.regions, &earlier.regions)
+        }
+    }
+
+}
+
+/// Growth of live slots between two censuses, kinds of slots are sorted
+/// from the most grown, and shrunk ones come last
+#[derive(Clone, Debug)]
+pub struct HeapCensusDiff {
+    slots: i64,
+    primitive_types: Vec<(PrimitiveType, i64)>,
+    prototypes: Vec<(Value, i64)>,
+    regions: Vec<(u32, i64)>
+}
+
+impl HeapCensusDiff {
+
+    /// Get growth of all live slots
+    pub fn get_slot_growth(&self) -> i64 {
+        self.slots
+    }
+
+    pub fn list_primitive_types(&self) -> &[(PrimitiveType, i64)] {
+        &self.primitive_types
+    }
+
+    pub fn list_prototypes(&self) -> &[(Value, i64)] {
+        &self.prototypes
+    }
+
+    pub fn list_regions(&self) -> &[(u32, i64)] {
+        &self.regions
+    }
+
+    /// Check whether no slots changed between the censuses
+    pub fn is_empty(&self) -> bool {
+        self.slots == 0 && self.primitive_types.is_empty() && self.prototypes.is_empty() && self.regions.is_empty()
+    }
+
+}
+
+fn sort_counts<K>(mut counts: Vec<(K, u64)>) -> Vec<(K, u64)> {
+    counts.sort_by_key(|(_key, count)| core::cmp::Reverse(*count));
+    counts
+}
+
+fn diff_counts<K: Copy + Eq + Hash>(later: &HashMap<K, u64>, earlier: &HashMap<K, u64>) -> Vec<(K, i64)> {
+
+    let mut growths: Vec<(K, i64)> = later.iter().map(|(key, count)| {
+        (*key, *count as i64 - earlier.get(key).copied().unwrap_or(0) as i64)
+    }).collect();
+    for (key, count) in earlier.iter() {
+        if !later.contains_key(key) {
+            growths.push((*key, -(*count as i64)));
+        }
+    }
+
+    growths.retain(|(_key, growth)| *growth != 0);
+    growths.sort_by_key(|(_key, growth)| core::cmp::Reverse(*growth));
+
+    growths
+
+}
+
+#[test]
+fn test_allocation_sampler() {
+
+    let sampler = AllocationSampler::new();
+
+    sampler.record(PrimitiveType::Object, 1, None);
+    assert_eq!(sampler.create_profile().count_samples(), 0);
+
+    sampler.start(2);
+    assert!(sampler.is_sampling());
+    for _ in 0..6 {
+        sampler.record(PrimitiveType::Object, 1, Some("hot"));
+    }
+    for _ in 0..2 {
+        sampler.record(PrimitiveType::Text, 2, None);
+    }
+
+    let profile = sampler.create_profile();
+    assert_eq!(profile.get_sample_interval(), 2);
+    assert_eq!(profile.count_samples(), 4);
+    let sites = profile.list_sites();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].get_tag(), Some("hot"));
+    assert_eq!(sites[0].get_samples(), 3);
+    assert_eq!(profile.estimate_allocations(&sites[0]), 6);
+    assert_eq!(sites[1].get_primitive_type(), PrimitiveType::Text);
+
+    sampler.stop();
+    sampler.record(PrimitiveType::Object, 1, Some("hot"));
+    sampler.record(PrimitiveType::Object, 1, Some("hot"));
+    let profile = sampler.create_profile();
+    assert_eq!(profile.count_samples(), 4);
+    assert_eq!(profile.estimate_allocations(&profile.list_sites()[0]), 6);
+
+    sampler.reset();
+    assert!(sampler.create_profile().list_sites().is_empty());
+
+}
+
+#[test]
+fn test_heap_census() {
+
+    let prototype = Value::make_object(1, 1);
+    let prototype_2 = Value::make_object(1, 2);
+
+    let mut census = HeapCensus::new();
+    census.record(PrimitiveType::Object, prototype, 2);
+    census.record(PrimitiveType::List, prototype_2, 2);
+    assert_eq!(census.get_slot_count(), 2);
+    assert_eq!(census.get_region_count(2), 2);
+
+    let mut census_2 = census.clone();
+    for _ in 0..3 {
+        census_2.record(PrimitiveType::Object, prototype, 3);
+    }
+    assert_eq!(census_2.list_primitive_types()[0], (PrimitiveType::Object, 4));
+    assert_eq!(census_2.get_prototype_count(prototype), 4);
+
+    let diff = census_2.diff(&census);
+    assert_eq!(diff.get_slot_growth(), 3);
+    assert_eq!(diff.list_primitive_types(), &[(PrimitiveType::Object, 3)]);
+    assert_eq!(diff.list_prototypes().len(), 1);
+    assert_eq!(diff.list_regions(), &[(3, 3)]);
+
+    let diff = census.diff(&census_2);
+    assert_eq!(diff.get_slot_growth(), -3);
+    assert_eq!(diff.list_regions(), &[(3, -3)]);
+    assert!(census.diff(&census).is_empty());
+
+}
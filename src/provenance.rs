@@ -0,0 +1,148 @@
+//! Allocation sites of slots recorded in the provenance mode, available with
+//! the `provenance` feature
+
+use alloc::string::String;
+use alloc::string::ToString;
+use core::cell::RefCell;
+use core::panic::Location;
+
+use super::base::PrimitiveType;
+use super::base::Value;
+use super::util::HashMap;
+use super::util::RwLock;
+
+/// Site where a slot was gained, with the tag provided by the host and the
+/// location of the caller gaining the slot
+#[derive(Clone, Debug)]
+pub struct Provenance {
+    primitive_type: PrimitiveType,
+    tag: Option<String>,
+    location: &'static Location<'static>
+}
+
+impl Provenance {
+
+    pub fn get_primitive_type(&self) -> PrimitiveType {
+        self.primitive_type
+    }
+
+    /// Get the tag provided by the host gaining the slot
+    pub fn get_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// Get the location of the caller gaining the slot
+    pub fn get_location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+}
+
+/// Allocation sites of slots alive in an isolate, refreshed once slots moved
+/// and removed once slots dropped
+pub struct Provenances {
+    rw_lock: RwLock,
+    sites: RefCell<HashMap<Value, Provenance>>
+}
+
+impl Provenances {
+
+    pub fn new() -> Provenances {
+        Provenances {
+            rw_lock: RwLock::new(),
+            sites: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Duplicate the allocation sites for a forked isolate
+    pub fn duplicate(&self) -> Provenances {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Provenances {
+            rw_lock: RwLock::new(),
+            sites: RefCell::new(self.sites.borrow().clone())
+        }
+
+    }
+
+    pub fn record(&self, value: Value, primitive_type: PrimitiveType, tag: Option<&str>, location: &'static Location<'static>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.sites.borrow_mut().insert(value, Provenance {
+            primitive_type: primitive_type,
+            tag: tag.map(|tag| tag.to_string()),
+            location: location
+        });
+
+    }
+
+    pub fn get(&self, value: Value) -> Option<Provenance> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.sites.borrow().get(&value).cloned()
+
+    }
+
+    pub fn remove(&self, value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.sites.borrow_mut().remove(&value);
+
+    }
+
+    /// Move allocation sites of moved slots to their new values
+    pub fn refresh(&self, moves: &[(Value, Value)]) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut sites = self.sites.borrow_mut();
+
+        let moved: alloc::vec::Vec<(Value, Provenance)> = moves.iter().filter_map(|(old_value, new_value)| {
+            sites.remove(old_value).map(|provenance| (*new_value, provenance))
+        }).collect();
+
+        sites.extend(moved);
+
+    }
+
+    /// Count slots with allocation sites recorded
+    pub fn count(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.sites.borrow().len()
+
+    }
+
+}
+
+#[test]
+fn test_provenances() {
+
+    let provenances = Provenances::new();
+
+    let value = Value::make_object(1, 1);
+    let value_2 = Value::make_object(1, 2);
+
+    let location = Location::caller();
+    provenances.record(value, PrimitiveType::Object, Some("mirror"), location);
+    provenances.record(value_2, PrimitiveType::List, None, location);
+    assert_eq!(provenances.count(), 2);
+
+    let provenance = provenances.get(value).unwrap();
+    assert_eq!(provenance.get_tag(), Some("mirror"));
+    assert_eq!(provenance.get_location().file(), file!());
+
+    provenances.refresh(&[(value, value_2), (value_2, value)]);
+    assert_eq!(provenances.get(value_2).unwrap().get_tag(), Some("mirror"));
+    assert_eq!(provenances.get(value).unwrap().get_primitive_type(), PrimitiveType::List);
+
+    provenances.remove(value);
+    assert!(provenances.get(value).is_none());
+    assert_eq!(provenances.duplicate().count(), 1);
+
+}
@@ -0,0 +1,275 @@
+use core::cell::RefCell;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::util::HashMap;
+use super::util::RwLock;
+
+/// Budgets of a quota shared by tenants of an isolate, regions are assigned
+/// to quotas, and slots alive in the regions count against their quotas
+#[derive(Copy, Clone, Debug)]
+pub struct Quota {
+    max_live_slots: Option<usize>,
+    max_regions: Option<usize>
+}
+
+impl Quota {
+
+    /// Create a quota without budgets
+    pub fn new() -> Quota {
+        Quota {
+            max_live_slots: None,
+            max_regions: None
+        }
+    }
+
+    /// Limit slots alive in regions of the quota
+    pub fn max_live_slots(mut self, max_live_slots: usize) -> Quota {
+        self.max_live_slots = Some(max_live_slots);
+        self
+    }
+
+    /// Limit regions assigned to the quota
+    pub fn max_regions(mut self, max_regions: usize) -> Quota {
+        self.max_regions = Some(max_regions);
+        self
+    }
+
+    pub fn get_max_live_slots(&self) -> Option<usize> {
+        self.max_live_slots
+    }
+
+    pub fn get_max_regions(&self) -> Option<usize> {
+        self.max_regions
+    }
+
+}
+
+/// Usage of a quota
+#[derive(Copy, Clone, Debug)]
+pub struct QuotaUsage {
+    live_slots: usize,
+    regions: usize
+}
+
+impl QuotaUsage {
+
+    pub fn get_live_slots(&self) -> usize {
+        self.live_slots
+    }
+
+    pub fn get_regions(&self) -> usize {
+        self.regions
+    }
+
+}
+
+#[derive(Copy, Clone)]
+struct QuotaState {
+    quota: Quota,
+    usage: QuotaUsage
+}
+
+/// Quotas of an isolate with their usages, usages are updated once slots
+/// gained or recycled in regions assigned to quotas
+pub struct Quotas {
+    rw_lock: RwLock,
+    states: RefCell<HashMap<u64, QuotaState>>,
+    region_quota_ids: RefCell<HashMap<u32, u64>>
+}
+
+impl Quotas {
+
+    pub fn new() -> Quotas {
+        Quotas {
+            rw_lock: RwLock::new(),
+            states: RefCell::new(HashMap::new()),
+            region_quota_ids: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Duplicate quotas and their usages for a forked isolate
+    pub fn duplicate(&self) -> Quotas {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Quotas {
+            rw_lock: RwLock::new(),
+            states: RefCell::new(self.states.borrow().clone()),
+            region_quota_ids: RefCell::new(self.region_quota_ids.borrow().clone())
+        }
+
+    }
+
+    /// Set budgets of a quota, the usage is kept if the quota exists, and
+    /// usages already over the budgets only fail later gains
+    pub fn set_quota(&self, quota_id: u64, quota: Quota) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.states.borrow_mut().entry(quota_id).or_insert(QuotaState {
+            quota: quota,
+            usage: QuotaUsage { live_slots: 0, regions: 0 }
+        }).quota = quota;
+
+    }
+
+    /// Remove a quota, regions assigned to it are no longer limited
+    pub fn remove_quota(&self, quota_id: u64) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if self.states.borrow_mut().remove(&quota_id).is_none() {
+            return Err(Error::new(FatalError, "Quota not found"));
+        }
+
+        self.region_quota_ids.borrow_mut().retain(|_region_id, id| *id != quota_id);
+
+        Ok(())
+
+    }
+
+    pub fn get_quota(&self, quota_id: u64) -> Option<Quota> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.states.borrow().get(&quota_id).map(|state| state.quota)
+
+    }
+
+    pub fn get_usage(&self, quota_id: u64) -> Option<QuotaUsage> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.states.borrow().get(&quota_id).map(|state| state.usage)
+
+    }
+
+    pub fn get_region_quota_id(&self, region_id: u32) -> Option<u64> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.region_quota_ids.borrow().get(&region_id).copied()
+
+    }
+
+    /// Check whether one more region could be assigned to the quota
+    pub fn ensure_region_available(&self, quota_id: u64) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        match self.states.borrow().get(&quota_id) {
+            Some(state) => Self::check_region_budget(state),
+            None => Err(Error::new(FatalError, "Quota not found"))
+        }
+
+    }
+
+    fn check_region_budget(state: &QuotaState) -> Result<(), Error> {
+
+        if let Some(max_regions) = state.quota.max_regions {
+            if state.usage.regions >= max_regions {
+                return Err(Error::new(QuotaExceeded, "Region budget of quota exceeded"));
+            }
+        }
+
+        Ok(())
+
+    }
+
+    /// Assign a region to a quota, slots occupied in the region count
+    /// against the quota from then on
+    pub fn assign_region(&self, quota_id: u64, region_id: u32, occupied: usize) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if self.region_quota_ids.borrow().contains_key(&region_id) {
+            return Err(Error::new(FatalError, "Region already assigned to a quota").with_region_id(region_id));
+        }
+
+        let mut states = self.states.borrow_mut();
+        let state = match states.get_mut(&quota_id) {
+            Some(state) => state,
+            None => {
+                return Err(Error::new(FatalError, "Quota not found"));
+            }
+        };
+
+        Self::check_region_budget(state).map_err(|error| error.with_region_id(region_id))?;
+
+        state.usage.regions += 1;
+        state.usage.live_slots += occupied;
+
+        self.region_quota_ids.borrow_mut().insert(region_id, quota_id);
+
+        Ok(())
+
+    }
+
+    /// Release a region from its quota, slots occupied in the region no
+    /// longer count against the quota
+    pub fn release_region(&self, region_id: u32, occupied: usize) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let quota_id = match self.region_quota_ids.borrow_mut().remove(&region_id) {
+            Some(quota_id) => quota_id,
+            None => {
+                return;
+            }
+        };
+
+        if let Some(state) = self.states.borrow_mut().get_mut(&quota_id) {
+            state.usage.regions -= 1;
+            state.usage.live_slots = state.usage.live_slots.saturating_sub(occupied);
+        }
+
+    }
+
+    /// Check whether a slot could be gained in the region by its quota
+    pub fn ensure_slot_available(&self, region_id: u32) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let quota_id = match self.region_quota_ids.borrow().get(&region_id) {
+            Some(quota_id) => *quota_id,
+            None => {
+                return Ok(());
+            }
+        };
+
+        if let Some(state) = self.states.borrow().get(&quota_id) {
+            if let Some(max_live_slots) = state.quota.max_live_slots {
+                if state.usage.live_slots >= max_live_slots {
+                    return Err(Error::new(QuotaExceeded, "Live slot budget of quota exceeded").with_region_id(region_id));
+                }
+            }
+        }
+
+        Ok(())
+
+    }
+
+    /// Apply changes of occupied slots in a region to its quota
+    pub fn update(&self, region_id: u32, occupied_delta: isize) {
+
+        if occupied_delta == 0 {
+            return;
+        }
+
+        let quota_id = match self.get_region_quota_id(region_id) {
+            Some(quota_id) => quota_id,
+            None => {
+                return;
+            }
+        };
+
+        let _guard = self.rw_lock.lock_write();
+
+        if let Some(state) = self.states.borrow_mut().get_mut(&quota_id) {
+            state.usage.live_slots = (state.usage.live_slots as isize + occupied_delta).max(0) as usize;
+        }
+
+    }
+
+}
@@ -1,42 +1,213 @@
-use std::collections::HashMap;
-use std::cell::Cell;
-use std::cell::RefCell;
+use alloc::vec::Vec;
+use super::util::HashMap;
+use core::cell::Cell;
+use core::cell::RefCell;
 
 use super::base::Error;
 use super::base::ErrorType::*;
 use super::base::Value;
+use super::cold_region::ColdRegionReader;
+use super::cold_region::ColdRegionWriter;
+use super::util::LockClass;
 use super::util::SpinLock;
 
+/// Count of referrers kept inline before the full map is allocated
+const INLINE_REFERRER_COUNT: usize = 2;
+
+/// Reference counts by referrers, most slots are referred by one or two
+/// referrers, which are kept inline with their counts
+enum ReferenceCounts {
+    Inline(usize, [(Value, u32); INLINE_REFERRER_COUNT]),
+    Full(HashMap<Value, u32>)
+}
+
+impl ReferenceCounts {
+
+    fn new() -> ReferenceCounts {
+        ReferenceCounts::Inline(0, [(Value::make_undefined(), 0); INLINE_REFERRER_COUNT])
+    }
+
+    fn duplicate(&self) -> ReferenceCounts {
+        match self {
+            ReferenceCounts::Inline(length, entries) => ReferenceCounts::Inline(*length, *entries),
+            ReferenceCounts::Full(counts) => ReferenceCounts::Full(counts.clone())
+        }
+    }
+
+    fn get_referrer_count(&self) -> usize {
+        match self {
+            ReferenceCounts::Inline(length, _) => *length,
+            ReferenceCounts::Full(counts) => counts.len()
+        }
+    }
+
+    fn get_capacity(&self) -> usize {
+        match self {
+            ReferenceCounts::Inline(_, _) => INLINE_REFERRER_COUNT,
+            ReferenceCounts::Full(counts) => counts.capacity()
+        }
+    }
+
+    fn list_counts(&self) -> Vec<(Value, u32)> {
+        match self {
+            ReferenceCounts::Inline(length, entries) => entries[.. *length].to_vec(),
+            ReferenceCounts::Full(counts) => counts.iter().map(|(value, count)| (*value, *count)).collect()
+        }
+    }
+
+    fn increase(&mut self, value: Value, delta: u32) {
+
+        let (length, entries) = match self {
+            ReferenceCounts::Inline(length, entries) => (length, entries),
+            ReferenceCounts::Full(counts) => {
+                *counts.entry(value).or_insert(0) += delta;
+                return;
+            }
+        };
+
+        for (referrer, count) in entries[.. *length].iter_mut() {
+            if *referrer == value {
+                *count += delta;
+                return;
+            }
+        }
+
+        if *length < INLINE_REFERRER_COUNT {
+            entries[*length] = (value, delta);
+            *length += 1;
+            return;
+        }
+
+        let mut counts: HashMap<Value, u32> = entries.iter().copied().collect();
+        counts.insert(value, delta);
+        *self = ReferenceCounts::Full(counts);
+
+    }
+
+    /// Decrease the count of a referrer, returns `None` if the referrer not
+    /// recorded
+    fn decrease(&mut self, value: Value) -> Option<u32> {
+        match self {
+            ReferenceCounts::Inline(length, entries) => {
+                let index = entries[.. *length].iter().position(|(referrer, _)| *referrer == value)?;
+                let count = entries[index].1;
+                if count > 1 {
+                    entries[index].1 = count - 1;
+                } else {
+                    entries.copy_within(index + 1 .. *length, index);
+                    *length -= 1;
+                }
+                Some(count)
+            },
+            ReferenceCounts::Full(counts) => {
+                let count = *counts.get(&value)?;
+                if count > 1 {
+                    counts.insert(value, count - 1);
+                } else {
+                    counts.remove(&value);
+                }
+                Some(count)
+            }
+        }
+    }
+
+}
+
+/// Statistics of reference maps for tuning the inline representation
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ReferenceMapStats {
+    maps: usize,
+    inline_maps: usize,
+    referrers: usize,
+    references: usize,
+    capacity: usize
+}
+
+impl ReferenceMapStats {
+
+    pub fn new() -> ReferenceMapStats {
+        ReferenceMapStats {
+            maps: 0,
+            inline_maps: 0,
+            referrers: 0,
+            references: 0,
+            capacity: 0
+        }
+    }
+
+    /// Get count of reference maps
+    pub fn get_map_count(&self) -> usize {
+        self.maps
+    }
+
+    /// Get count of reference maps still kept inline
+    pub fn get_inline_map_count(&self) -> usize {
+        self.inline_maps
+    }
+
+    /// Get count of distinct referrers in all reference maps
+    pub fn get_referrer_count(&self) -> usize {
+        self.referrers
+    }
+
+    /// Get count of references including duplicated ones
+    pub fn get_reference_count(&self) -> usize {
+        self.references
+    }
+
+    /// Get count of referrers could be kept without growing the maps
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+}
+
 pub struct ReferenceMap {
     spin_lock: SpinLock,
     count: Cell<u32>,
-    counts: RefCell<HashMap<Value, u32>>
+    counts: RefCell<ReferenceCounts>
 }
 
 impl ReferenceMap {
 
     pub fn new() -> ReferenceMap {
         ReferenceMap {
-            spin_lock: SpinLock::new(),
+            spin_lock: SpinLock::with_class(LockClass::ReferenceMap),
             count: Cell::new(0),
-            counts: RefCell::new(HashMap::new())
+            counts: RefCell::new(ReferenceCounts::new())
+        }
+    }
+
+    /// Duplicate the reference map for a forked isolate
+    pub fn duplicate(&self) -> ReferenceMap {
+
+        let _guard = self.spin_lock.lock();
+
+        ReferenceMap {
+            spin_lock: SpinLock::with_class(LockClass::ReferenceMap),
+            count: Cell::new(self.count.get()),
+            counts: RefCell::new(self.counts.borrow().duplicate())
         }
+
     }
 
     pub fn is_empty(&self) -> bool {
         self.count.get() == 0
     }
 
-    pub fn add_reference(&self, value: Value) -> Result<(), Error> {
+    pub fn is_inline(&self) -> bool {
 
         let _guard = self.spin_lock.lock();
 
-        let count = match self.counts.borrow().get(&value) {
-            None => 0,
-            Some(count) => *count
-        };
+        matches!(*self.counts.borrow(), ReferenceCounts::Inline(_, _))
 
-        self.counts.borrow_mut().insert(value, count + 1);
+    }
+
+    pub fn add_reference(&self, value: Value) -> Result<(), Error> {
+
+        let _guard = self.spin_lock.lock();
+
+        self.counts.borrow_mut().increase(value, 1);
         self.count.set(self.count.get() + 1);
 
         Ok(())
@@ -47,26 +218,67 @@ impl ReferenceMap {
 
         let _guard = self.spin_lock.lock();
 
-        let count = match self.counts.borrow().get(&value) {
+        match self.counts.borrow_mut().decrease(value) {
             None => {
                 return Err(Error::new(FatalError, "No references recorded"));
             },
-            Some(count) => *count
-        };
+            Some(0) => {
+                return Err(Error::new(FatalError, "Reference count should always greater than or equals to zero"));
+            },
+            Some(_) => {}
+        }
+
+        self.count.set(self.count.get() - 1);
+
+        Ok(())
+
+    }
+
+    /// Accumulate statistics of the reference map
+    pub fn collect_stats(&self, stats: &mut ReferenceMapStats) {
+
+        let _guard = self.spin_lock.lock();
 
-        if count == 0 {
-            return Err(Error::new(FatalError, "Reference count should always greater than or equals to zero"))
+        let counts = self.counts.borrow();
+
+        stats.maps += 1;
+        if let ReferenceCounts::Inline(_, _) = *counts {
+            stats.inline_maps += 1;
         }
+        stats.referrers += counts.get_referrer_count();
+        stats.references += self.count.get() as usize;
+        stats.capacity += counts.get_capacity();
+
+    }
 
-        if count > 1 {
-            self.counts.borrow_mut().insert(value, count - 1);
-        } else {
-            self.counts.borrow_mut().remove(&value);
+    /// Write reference counts for a cold region
+    pub fn write_cold(&self, writer: &mut ColdRegionWriter) {
+
+        let _guard = self.spin_lock.lock();
+
+        let counts = self.counts.borrow().list_counts();
+
+        writer.write_u32(counts.len() as u32);
+        for (value, count) in counts.iter() {
+            writer.write_value(*value);
+            writer.write_u32(*count);
         }
 
-        self.count.set(self.count.get() - 1);
+    }
 
-        Ok(())
+    pub fn read_cold(reader: &mut ColdRegionReader) -> Result<ReferenceMap, Error> {
+
+        let reference_map = ReferenceMap::new();
+
+        let length = reader.read_u32()?;
+        for _ in 0 .. length {
+            let value = reader.read_value()?;
+            let count = reader.read_u32()?;
+            reference_map.counts.borrow_mut().increase(value, count);
+            reference_map.count.set(reference_map.count.get() + count);
+        }
+
+        Ok(reference_map)
 
     }
 
@@ -147,4 +359,41 @@ fn test_remove_reference() -> Result<(), Error> {
 
     Ok(())
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_reference_map_inline() -> Result<(), Error> {
+
+    let reference_map = ReferenceMap::new();
+
+    for _ in 0 .. 3 {
+        reference_map.add_reference(Value::make_integer(1))?;
+    }
+    reference_map.add_reference(Value::make_integer(2))?;
+    assert!(reference_map.is_inline());
+
+    reference_map.add_reference(Value::make_integer(3))?;
+    assert!(!reference_map.is_inline());
+
+    let mut stats = ReferenceMapStats::new();
+    reference_map.collect_stats(&mut stats);
+    assert_eq!(stats.get_map_count(), 1);
+    assert_eq!(stats.get_inline_map_count(), 0);
+    assert_eq!(stats.get_referrer_count(), 3);
+    assert_eq!(stats.get_reference_count(), 5);
+    assert!(stats.get_capacity() >= 3);
+
+    let reference_map = ReferenceMap::new();
+    reference_map.add_reference(Value::make_integer(1))?;
+    reference_map.add_reference(Value::make_integer(2))?;
+    reference_map.remove_reference(Value::make_integer(1))?;
+    reference_map.add_reference(Value::make_integer(3))?;
+    assert!(reference_map.is_inline());
+    reference_map.remove_reference(Value::make_integer(2))?;
+    reference_map.remove_reference(Value::make_integer(3))?;
+    assert!(reference_map.is_empty());
+    assert!(reference_map.remove_reference(Value::make_integer(3)).is_err());
+
+    Ok(())
+
+}
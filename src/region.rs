@@ -1,9 +1,14 @@
-use std::cell::Cell;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::mem::MaybeUninit;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cell::RefCell;
+use super::util::BitmapIterator;
+use super::util::HashMap;
+use super::util::HashSet;
+use core::mem::MaybeUninit;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+use alloc::sync::Arc;
 
 use super::base::Error;
 use super::base::ErrorType::*;
@@ -11,18 +16,24 @@ use super::base::PrimitiveType;
 use super::base::PrimitiveType::*;
 use super::base::Symbol;
 use super::base::Value;
+use super::cold_region::ColdRegionReader;
+use super::cold_region::ColdRegionWriter;
 use super::context::Context;
 use super::field_shortcuts::FieldToken;
 use super::field_shortcuts::FieldShortcuts;
 use super::internal_slot::InternalSlot;
 use super::internal_slot::ProtectedInternalSlot;
 use super::reference_map::ReferenceMap;
+use super::reference_map::ReferenceMapStats;
 use super::storage::Pinned;
 use super::slot::RegionSlot;
 use super::slot::SlotRecordSnapshot;
 use super::trap::PropertyTrap;
+use super::trap::ProtectedPropertyTrap;
 use super::trap::SlotTrap;
+use super::util::LockClass;
 use super::util::RwLock;
+use super::util::find_last_set_bit;
 use super::util::ReentrantLockReadGuard;
 
 
@@ -34,6 +45,29 @@ const REGION_SLOT_SIZE: usize = 578;
 /// `ceil(REGION_SLOT_SIZE / 64)`
 const REGION_BITMAP_SIZE: usize = 10; 
 
+/// Kinds of regions
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RegionKind {
+    /// Region holding generic fixed-size slots
+    Standard,
+    /// Region dedicated to one object with a large payload, so giant buffers
+    /// are not mixed into generic regions
+    Large
+}
+
+impl RegionKind {
+
+    /// Get count of slots could be gained in a region of the kind
+    #[inline]
+    pub fn get_slot_capacity(&self) -> usize {
+        match self {
+            RegionKind::Standard => REGION_SLOT_SIZE,
+            RegionKind::Large => 1
+        }
+    }
+
+}
+
 struct RegionRedirectionReference {
     redirection: Value,
     reference_map: RefCell<Box<ReferenceMap>>
@@ -59,11 +93,13 @@ impl RegionRedirectionReference {
 pub struct Region {
 
     id: u32,
+    kind: RegionKind,
 
     rw_lock: RwLock,
 
     occupied: Cell<u16>,
     next_empty_slot_index: Cell<u16>,
+    free_slots: RefCell<Vec<u16>>,
 
     bitmap: RefCell<[u64; REGION_BITMAP_SIZE]>,
     empties: RefCell<[u64; REGION_BITMAP_SIZE]>,
@@ -73,7 +109,9 @@ pub struct Region {
     redirection_froms: RefCell<HashMap<Value, HashSet<Value>>>,
 
     nursery: RefCell<HashSet<Value>>,
-    slots: [RegionSlot; REGION_SLOT_SIZE] 
+    slots: [RegionSlot; REGION_SLOT_SIZE],
+
+    shared: AtomicBool
 
     // TODO: add more fields
     // base_color: u8
@@ -84,20 +122,31 @@ pub struct Region {
 impl Region {
 
     pub fn new(id: u32) -> Region {
+        Region::with_kind(id, RegionKind::Standard)
+    }
+
+    /// Create a region holding only one slot for a large object
+    pub fn new_large(id: u32) -> Region {
+        Region::with_kind(id, RegionKind::Large)
+    }
+
+    pub fn with_kind(id: u32, kind: RegionKind) -> Region {
 
         let region = Region {
 
             id: id,
+            kind: kind,
 
-            rw_lock: RwLock::new(),
+            rw_lock: RwLock::with_class(LockClass::Region),
 
             occupied: Cell::new(0),
             next_empty_slot_index: Cell::new(0),
+            free_slots: RefCell::new(Vec::new()),
 
             bitmap: RefCell::new([0; REGION_BITMAP_SIZE]),
             empties: RefCell::new([!0; REGION_BITMAP_SIZE]),
 
-            redirection_rw_lock: RwLock::new(),
+            redirection_rw_lock: RwLock::with_class(LockClass::RegionRedirection),
             redirections: RefCell::new(HashMap::new()),
             redirection_froms: RefCell::new(HashMap::new()),
 
@@ -110,10 +159,12 @@ impl Region {
                     *slot = MaybeUninit::new(region_slot);
                 }
                 unsafe { 
-                    std::mem::transmute::<_, [RegionSlot; REGION_SLOT_SIZE]>(array) 
+                    core::mem::transmute::<_, [RegionSlot; REGION_SLOT_SIZE]>(array) 
                 }
             },
-            nursery: RefCell::new(HashSet::new())
+            nursery: RefCell::new(HashSet::new()),
+
+            shared: AtomicBool::new(false)
 
         };
 
@@ -121,20 +172,218 @@ impl Region {
 
     }
 
+    /// Duplicate the region with all slots for a forked isolate
+    pub fn duplicate(&self) -> Region {
+
+        let _guard = self.rw_lock.lock_write();
+        let _guard_2 = self.redirection_rw_lock.lock_read();
+
+        let mut redirections = HashMap::new();
+        for (value, redirection_reference) in self.redirections.borrow().iter() {
+            redirections.insert(*value, RegionRedirectionReference {
+                redirection: redirection_reference.redirection,
+                reference_map: RefCell::new(Box::new(redirection_reference.reference_map.borrow().duplicate()))
+            });
+        }
+
+        Region {
+
+            id: self.id,
+            kind: self.kind,
+
+            rw_lock: RwLock::with_class(LockClass::Region),
+
+            occupied: Cell::new(self.occupied.get()),
+            next_empty_slot_index: Cell::new(self.next_empty_slot_index.get()),
+            free_slots: RefCell::new(self.free_slots.borrow().clone()),
+
+            bitmap: RefCell::new(*self.bitmap.borrow()),
+            empties: RefCell::new(*self.empties.borrow()),
+
+            redirection_rw_lock: RwLock::with_class(LockClass::RegionRedirection),
+            redirections: RefCell::new(redirections),
+            redirection_froms: RefCell::new(self.redirection_froms.borrow().clone()),
+
+            slots: {
+                let mut array: [MaybeUninit<RegionSlot>; REGION_SLOT_SIZE] = unsafe { 
+                    MaybeUninit::uninit().assume_init() 
+                };
+                for (index, slot) in array.iter_mut().enumerate() {
+                    *slot = MaybeUninit::new(self.slots[index].duplicate());
+                }
+                unsafe { 
+                    core::mem::transmute::<_, [RegionSlot; REGION_SLOT_SIZE]>(array) 
+                }
+            },
+            nursery: RefCell::new(self.nursery.borrow().clone()),
+
+            shared: AtomicBool::new(false)
+
+        }
+
+    }
+
+
+    /// List values in other regions referenced by alive slots, which are
+    /// kept alive by the region while it is paged out
+    pub fn list_outer_referenced_values(&self) -> Result<Vec<Value>, Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let mut values = HashSet::new();
+
+        let bitmap = *self.bitmap.borrow();
+        for slot in BitmapIterator::new(&bitmap, REGION_SLOT_SIZE) {
+            let record = &self.slots[slot];
+            if !record.is_alive() {
+                continue;
+            }
+            let (referenced_values, _symbols) = record.list_self_references_without_autorefresh()?;
+            for value in referenced_values {
+                if value.is_slotted() && value.get_region_id()? != self.id {
+                    values.insert(value);
+                }
+            }
+        }
+
+        Ok(values.into_iter().collect())
+
+    }
+
+    /// Write the region as bytes to page it out
+    ///
+    /// Regions with values in nursery, redirections, slot traps, property
+    /// traps other than fields or internal slots not supported by the cold
+    /// region writer could not be paged out
+    pub fn write_cold(&self) -> Result<Vec<u8>, Error> {
+
+        let _guard = self.rw_lock.lock_read();
+        let _guard_2 = self.redirection_rw_lock.lock_read();
+
+        if self.is_shared() {
+            return Err(Error::new(FatalError, "Shared region could not be paged out"));
+        }
+        if !self.nursery.borrow().is_empty() {
+            return Err(Error::new(FatalError, "Region with values in nursery could not be paged out"));
+        }
+        if !self.redirections.borrow().is_empty() || !self.redirection_froms.borrow().is_empty() {
+            return Err(Error::new(FatalError, "Region with redirections could not be paged out"));
+        }
+
+        let mut writer = ColdRegionWriter::new();
+
+        writer.write_u8(match self.kind {
+            RegionKind::Standard => 0,
+            RegionKind::Large => 1
+        });
+
+        writer.write_u16(self.occupied.get());
+        writer.write_u16(self.next_empty_slot_index.get());
+
+        let free_slots = self.free_slots.borrow();
+        writer.write_u32(free_slots.len() as u32);
+        for index in free_slots.iter() {
+            writer.write_u16(*index);
+        }
+
+        for word in self.bitmap.borrow().iter() {
+            writer.write_u64(*word);
+        }
+        for word in self.empties.borrow().iter() {
+            writer.write_u64(*word);
+        }
+
+        for slot in self.slots[0 .. self.next_empty_slot_index.get() as usize].iter() {
+            slot.write_cold(&mut writer)?;
+        }
+
+        Ok(writer.into_data())
+
+    }
+
+    /// Rehydrate a region from bytes written while paging it out
+    pub fn read_cold(id: u32, data: &[u8]) -> Result<Region, Error> {
+
+        let mut reader = ColdRegionReader::new(data);
+
+        let kind = match reader.read_u8()? {
+            0 => RegionKind::Standard,
+            1 => RegionKind::Large,
+            _ => { return Err(Error::new(MalformedData, "Invalid region kind in cold region")); }
+        };
+
+        let region = Region::with_kind(id, kind);
+
+        region.occupied.set(reader.read_u16()?);
+        region.next_empty_slot_index.set(reader.read_u16()?);
+        if region.next_empty_slot_index.get() as usize > kind.get_slot_capacity() {
+            return Err(Error::new(MalformedData, "Invalid slot index in cold region"));
+        }
+
+        let length = reader.read_u32()?;
+        for _ in 0 .. length {
+            region.free_slots.borrow_mut().push(reader.read_u16()?);
+        }
+
+        for word in region.bitmap.borrow_mut().iter_mut() {
+            *word = reader.read_u64()?;
+        }
+        for word in region.empties.borrow_mut().iter_mut() {
+            *word = reader.read_u64()?;
+        }
+
+        for slot in region.slots[0 .. region.next_empty_slot_index.get() as usize].iter() {
+            slot.read_cold(&mut reader)?;
+        }
+
+        if !reader.is_end() {
+            return Err(Error::new(MalformedData, "Unexpected data after cold region"));
+        }
+
+        Ok(region)
+
+    }
+
 }
 
 // Region basic properties
 impl Region {
 
+    #[inline]
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    #[inline]
+    pub fn get_kind(&self) -> RegionKind {
+        self.kind
+    }
+
+    /// Check whether the region is sealed to be shared between isolates
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        self.shared.load(Ordering::SeqCst)
+    }
+
     #[inline]
     pub fn is_full(&self) -> bool {
         let _guard = self.rw_lock.lock_read();
         self.is_full_without_lock()
     }
 
+    /// Large regions are used only once, they are full once a slot gained
     #[inline]
     fn is_full_without_lock(&self) -> bool {
-        (self.occupied.get() as usize) == REGION_SLOT_SIZE
+        match self.kind {
+            RegionKind::Standard => (self.occupied.get() as usize) == REGION_SLOT_SIZE,
+            RegionKind::Large => (self.next_empty_slot_index.get() as usize) >= 1
+        }
+    }
+
+    #[inline]
+    pub fn get_occupied_count(&self) -> usize {
+        let _guard = self.rw_lock.lock_read();
+        self.occupied.get() as usize
     }
 
     #[inline]
@@ -159,10 +408,10 @@ impl Region {
         let next_empty_slot_index = self.next_empty_slot_index.get();
         if next_empty_slot_index == 0 {
             0.0
-        } else if next_empty_slot_index < REGION_SLOT_SIZE as u16 {
+        } else if (next_empty_slot_index as usize) < self.kind.get_slot_capacity() {
             1.0 - self.occupied.get() as f32 / next_empty_slot_index as f32
         } else {
-            1.0 - self.occupied.get() as f32 / REGION_SLOT_SIZE as f32
+            1.0 - self.occupied.get() as f32 / self.kind.get_slot_capacity() as f32
         }
     }
 
@@ -174,7 +423,9 @@ impl Region {
 
     #[inline]
     pub fn could_gain_slot_quickly_without_lock(&self) -> bool {
-        self.next_empty_slot_index.get() != REGION_SLOT_SIZE as u16
+        !self.is_full_without_lock() && (
+            self.next_empty_slot_index.get() as usize != self.kind.get_slot_capacity() ||
+            !self.free_slots.borrow().is_empty())
     }
 
 }
@@ -241,6 +492,7 @@ impl Region {
             Float => { return Err(Error::new(FatalError, "Region slot is not available for float type")); },
             Symbol => { return Err(Error::new(FatalError, "Region slot is not available for symbol type")); },
             Text => {},
+            BigInteger => {},
             List => {},
             Tuple => {},
             Object => {}
@@ -250,15 +502,20 @@ impl Region {
 
             let _guard = self.rw_lock.lock_write();
 
-            if self.is_full_without_lock() {
-                return Err(Error::new(OutOfSpace, "Out of slots"));
+            if self.is_shared() {
+                return Err(Error::new(FatalError, "Region is shared"));
             }
 
-            if !self.could_gain_slot_quickly_without_lock() {
+            if self.is_full_without_lock() {
                 return Err(Error::new(OutOfSpace, "Out of slots"));
             }
 
-            let slot = self.next_empty_slot_index.get();
+            let slot = match self.take_empty_slot_without_lock() {
+                Some(slot) => slot,
+                None => {
+                    return Err(Error::new(OutOfSpace, "Out of slots"));
+                }
+            };
 
             let offset = (slot >> 6) as usize;
             let shift = slot & 0x3f;
@@ -274,7 +531,6 @@ impl Region {
             self.empties.borrow_mut()[offset] &= !(0b1 << shift);
 
             self.occupied.set(self.occupied.get() + 1);
-            self.next_empty_slot_index.set(self.next_empty_slot_index.get() + 1);
 
             let id = match primitive_type {
                 Undefined => { return Err(Error::new(FatalError, "Region slot is not available for undefined type")); },
@@ -284,6 +540,7 @@ impl Region {
                 Float => { return Err(Error::new(FatalError, "Region slot is not available for float type")); },
                 Symbol => { return Err(Error::new(FatalError, "Region slot is not available for symbol type")); },
                 Text => { Value::make_text(self.id, slot as u32) },
+                BigInteger => { Value::make_big_integer(self.id, slot as u32) },
                 List => { Value::make_list(self.id, slot as u32) },
                 Tuple => { Value::make_tuple(self.id, slot as u32) },
                 Object => { Value::make_object(self.id, slot as u32) }
@@ -308,54 +565,115 @@ impl Region {
 
             let _guard = self.rw_lock.lock_write();
 
-            if self.id != value.get_region_id()? {
-                return Err(Error::new(FatalError, "Incorrect region ID"));
+            if self.is_shared() {
+                return Err(Error::new(FatalError, "Region is shared"));
             }
 
-            let slot = value.get_region_slot()?;
+            let slot = self.check_slot_recyclable_without_lock(value)?;
 
-            let offset = (slot >> 6) as usize;
-            let shift = slot & 0x3f;
+            self.release_slot_without_lock(value, slot, drop_value);
 
-            if (self.bitmap.borrow()[offset] >> shift) & 0b1 == 0 {
-                return Err(Error::new(FatalError, "Incorrect slot state"));
-            }
+            &self.slots[slot as usize]
 
-            if self.nursery.borrow().get(&value).is_some() {
-                return Err(Error::new(FatalError, "Value in nursery"));
-            }
+        };
+
+        record.recycle(drop_value, context)?;
 
-            let record = &self.slots[slot as usize];
+        Ok(())
 
-            if record.is_alive() && (!record.has_no_outer_references()?) {
-                return Err(Error::new(FatalError, "Slot has outer references"));
+    }
+
+    /// Recycle slots of values in the region with the region locked once,
+    /// no slot will be recycled if any of the values could not be recycled
+    pub fn bulk_recycle(&self, values: &[Value], drop_value: bool, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let records = {
+
+            let _guard = self.rw_lock.lock_write();
+
+            if self.is_shared() {
+                return Err(Error::new(FatalError, "Region is shared"));
             }
 
-            {
-                let _guard = self.redirection_rw_lock.lock_read();
-                if self.redirection_froms.borrow().get(&value).is_some() {
-                    return Err(Error::new(FatalError, "Slot has outer references"));
+            let mut slots = Vec::new();
+            let mut checked = HashSet::new();
+            for value in values.iter() {
+                if !checked.insert(*value) {
+                    return Err(Error::new(FatalError, "Duplicated values to recycle"));
                 }
+                slots.push(self.check_slot_recyclable_without_lock(*value)?);
             }
 
-            if drop_value {
-                self.empties.borrow_mut()[offset] |= 1 << shift;
-                self.occupied.set(self.occupied.get() - 1);
+            let mut records = Vec::new();
+            for (value, slot) in values.iter().zip(slots) {
+                self.release_slot_without_lock(*value, slot, drop_value);
+                records.push(&self.slots[slot as usize]);
             }
 
-            self.bitmap.borrow_mut()[offset] &= !(1 << shift);
-            self.nursery.borrow_mut().remove(&value);
-
-            record
+            records
 
         };
 
-        record.recycle(drop_value, context)?;
+        for record in records {
+            record.recycle(drop_value, context)?;
+        }
 
         Ok(())
 
     }
 
+    fn check_slot_recyclable_without_lock(&self, value: Value) -> Result<u32, Error> {
+
+        if self.id != value.get_region_id()? {
+            return Err(Error::new(FatalError, "Incorrect region ID"));
+        }
+
+        let slot = value.get_region_slot()?;
+
+        let offset = (slot >> 6) as usize;
+        let shift = slot & 0x3f;
+
+        if (self.bitmap.borrow()[offset] >> shift) & 0b1 == 0 {
+            return Err(Error::new(FatalError, "Incorrect slot state"));
+        }
+
+        if self.nursery.borrow().get(&value).is_some() {
+            return Err(Error::new(FatalError, "Value in nursery"));
+        }
+
+        let record = &self.slots[slot as usize];
+
+        if record.is_alive() && (!record.has_no_outer_references()?) {
+            return Err(Error::new(FatalError, "Slot has outer references"));
+        }
+
+        {
+            let _guard = self.redirection_rw_lock.lock_read();
+            if self.redirection_froms.borrow().get(&value).is_some() {
+                return Err(Error::new(FatalError, "Slot has outer references"));
+            }
+        }
+
+        Ok(slot)
+
+    }
+
+    fn release_slot_without_lock(&self, value: Value, slot: u32, drop_value: bool) {
+
+        let offset = (slot >> 6) as usize;
+        let shift = slot & 0x3f;
+
+        if drop_value {
+            self.empties.borrow_mut()[offset] |= 1 << shift;
+            self.occupied.set(self.occupied.get() - 1);
+            self.free_slots.borrow_mut().push(slot as u16);
+        }
+
+        self.bitmap.borrow_mut()[offset] &= !(1 << shift);
+        self.nursery.borrow_mut().remove(&value);
+
+    }
+
     pub fn recalculate_next_empty_slot_index(&self) -> Result<(), Error> {
 
         let _guard = self.rw_lock.lock_write();
@@ -364,24 +682,49 @@ impl Region {
             return Ok(());
         }
 
-        let mut slot = self.next_empty_slot_index.get();
+        let mut useds = [0u64; REGION_BITMAP_SIZE];
+        for (offset, used) in useds.iter_mut().enumerate() {
+            *used = self.bitmap.borrow()[offset] | !self.empties.borrow()[offset];
+        }
+
+        let slot = match find_last_set_bit(&useds, self.next_empty_slot_index.get() as usize) {
+            Some(slot) => (slot + 1) as u16,
+            None => 0
+        };
+
+        self.next_empty_slot_index.set(slot);
+
+        self.free_slots.borrow_mut().retain(|free_slot| *free_slot < slot);
+
+        Ok(())
+
+    }
+
+    /// Take an empty slot, recycled slots are reused before the slots never
+    /// used
+    fn take_empty_slot_without_lock(&self) -> Option<u16> {
+
         loop {
+            let slot = match self.free_slots.borrow_mut().pop() {
+                Some(slot) => slot,
+                None => break
+            };
             let offset = (slot >> 6) as usize;
             let shift = slot & 0x3f;
-            if ((self.bitmap.borrow()[offset] >> shift) & 0b1 == 1) ||
-                ((self.empties.borrow()[offset] >> shift) & 0b1 == 0) {
-                slot += 1;
-                break;
-            }
-            if slot == 0 {
-                break;
+            if ((self.bitmap.borrow()[offset] >> shift) & 0b1 == 0) &&
+                ((self.empties.borrow()[offset] >> shift) & 0b1 == 1) {
+                return Some(slot);
             }
-            slot -= 1;
         }
 
-        self.next_empty_slot_index.set(slot);
+        let slot = self.next_empty_slot_index.get();
+        if slot as usize >= self.kind.get_slot_capacity() {
+            return None;
+        }
 
-        Ok(())
+        self.next_empty_slot_index.set(slot + 1);
+
+        Some(slot)
 
     }
 
@@ -428,6 +771,10 @@ impl Region {
 
     pub fn redirect_slot_without_lock(&self, value: Value, redirection: Value, reference_map: Option<Box<ReferenceMap>>) -> Result<(), Error> {
 
+        if self.is_shared() {
+            return Err(Error::new(FatalError, "Region is shared"));
+        }
+
         if self.id != value.get_region_id()? {
             return Err(Error::new(FatalError, "Incorrect region ID"));
         }
@@ -460,7 +807,17 @@ impl Region {
 
     }
 
-    pub fn move_out_from_nursery(&self, value: Value) -> Result<(), Error> {
+    pub fn is_in_nursery(&self, value: Value) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.nursery.borrow().contains(&value)
+
+    }
+
+    /// Move a value out from the nursery, false will be returned if it is
+    /// not in the nursery
+    pub fn move_out_from_nursery(&self, value: Value) -> Result<bool, Error> {
 
         let _guard = self.rw_lock.lock_write();
 
@@ -477,9 +834,7 @@ impl Region {
             return Err(Error::new(FatalError, "Incorrect slot state"));
         }
 
-        self.nursery.borrow_mut().remove(&value);
-
-        Ok(())
+        Ok(self.nursery.borrow_mut().remove(&value))
 
     }
 
@@ -551,6 +906,10 @@ impl Region {
 
         let _guard = self.rw_lock.lock_write();
 
+        if self.is_shared() {
+            return Err(Error::new(FatalError, "Region is shared"));
+        }
+
         let slot = self.ensure_slot_available(slot)?;
 
         let record = &self.slots[slot as usize];
@@ -563,6 +922,51 @@ impl Region {
 
     }
 
+    /// Copy a slot to move it while readers continue against the slot, the
+    /// version of the slot copied is returned
+    pub fn copy_slot(&self, slot: Value) -> Result<(SlotRecordSnapshot, u32), Error> {
+
+        let record = {
+
+            let _guard = self.rw_lock.lock_read();
+
+            if self.is_shared() {
+                return Err(Error::new(FatalError, "Region is shared"));
+            }
+
+            let slot = self.ensure_slot_available(slot)?;
+
+            &self.slots[slot as usize]
+
+        };
+
+        record.copy()
+
+    }
+
+    /// Freeze a slot copied if it is not modified after the version copied,
+    /// `None` is returned if the slot is modified
+    pub fn freeze_copied_slot(&self, slot: Value, version: u32) 
+        -> Result<Option<(bool, Option<Box<ReferenceMap>>, Vec<Value>, Vec<Symbol>)>, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if self.is_shared() {
+            return Err(Error::new(FatalError, "Region is shared"));
+        }
+
+        let slot = self.ensure_slot_available(slot)?;
+
+        let record = &self.slots[slot as usize];
+
+        let in_nursery = self.nursery.borrow().get(&record.get_id()?).is_some();
+
+        Ok(record.freeze_copied(version)?.map(|(reference_map, removed_values, removed_symbols)| {
+            (in_nursery, reference_map, removed_values, removed_symbols)
+        }))
+
+    }
+
     pub fn restore_slot(&self, 
                         from: Value, snapshot: SlotRecordSnapshot, 
                         in_nursery: bool, reference_map: &Option<Box<ReferenceMap>>) 
@@ -572,27 +976,23 @@ impl Region {
 
             let _guard = self.rw_lock.lock_write();
 
+            if self.is_shared() {
+                return Err(Error::new(FatalError, "Region is shared"));
+            }
+
             if self.is_full_without_lock() {
                 return Err(Error::new(OutOfSpace, "Out of slots"));
             }
 
-            let mut slot = 0;
-            let (offset, shift) = loop {
-                let offset = (slot >> 6) as usize;
-                let shift = slot & 0x3f;
-                if ((self.bitmap.borrow()[offset] >> shift) & 0b1 == 0) &&
-                    ((self.empties.borrow()[offset] >> shift) & 0b1 == 1) {
-                    break (offset, shift);
-                }
-                slot += 1;
-                if slot >= REGION_SLOT_SIZE {
+            let slot = match self.take_empty_slot_without_lock() {
+                Some(slot) => slot,
+                None => {
                     return Err(Error::new(OutOfSpace, "No empty slot is available"));
                 }
             };
 
-            if slot >= self.next_empty_slot_index.get() as usize {
-                self.next_empty_slot_index.set((slot + 1) as u16);
-            }
+            let offset = (slot >> 6) as usize;
+            let shift = slot & 0x3f;
 
             self.bitmap.borrow_mut()[offset] |= 0b1u64 << shift;
             self.empties.borrow_mut()[offset] &= !(0b1u64 << shift);
@@ -631,21 +1031,20 @@ impl Region {
 
     pub fn add_reference(&self, reference: Value, from: Value) -> Result<(), Error> {
 
-        let (record, removing_nursery) = {
+        // Slots of shared regions never move or die, and references from
+        // multiple isolates are not tracked
+        if self.is_shared() {
+            return Ok(());
+        }
 
+        let (record, removing_nursery) = {
             let _guard = self.rw_lock.lock_read();
-
-            let slot = self.ensure_slot_referencable(reference)?;
-
-            {
-                let _guard = self.redirection_rw_lock.lock_read();
-                if let Some(reference_map) = self.redirections.borrow().get(&reference) {
-                    return reference_map.add_reference(from);
+            match self.prepare_add_reference_without_lock(reference, from)? {
+                Some(record) => (record, self.nursery.borrow().get(&reference).is_some()),
+                None => {
+                    return Ok(());
                 }
             }
-
-            (&self.slots[slot as usize], self.nursery.borrow().get(&reference).is_some())
-
         };
 
         record.add_outer_reference(from)?;
@@ -659,46 +1058,135 @@ impl Region {
 
     }
 
+    /// Record a reference into the redirection of the value, or return the
+    /// record to add the outer reference into once the region unlocked
+    fn prepare_add_reference_without_lock(&self, reference: Value, from: Value) -> Result<Option<&RegionSlot>, Error> {
+
+        let slot = self.ensure_slot_referencable(reference)?;
+
+        {
+            let _guard = self.redirection_rw_lock.lock_read();
+            if let Some(reference_map) = self.redirections.borrow().get(&reference) {
+                reference_map.add_reference(from)?;
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(&self.slots[slot as usize]))
+
+    }
+
     pub fn remove_reference(&self, reference: Value, from: Value) -> Result<(bool, Value), Error> {
 
-        let record = {
+        if self.is_shared() {
+            return Ok((false, Value::make_undefined()));
+        }
 
+        let record = {
             let _guard = self.rw_lock.lock_read();
+            match self.prepare_remove_reference_without_lock(reference, from)? {
+                Ok(result) => {
+                    return Ok(result);
+                },
+                Err(record) => record
+            }
+        };
 
-            let slot = self.ensure_slot_referencable(reference)?;
+        record.remove_outer_reference(from)?;
 
-            {
-                let _guard = self.redirection_rw_lock.lock_read();
-                let mut has_reference_map = false;
-                let mut reference_map_is_empty = false;
-                let mut redirection = Value::make_undefined();
-                let offset = (slot >> 6) as usize;
-                let shift = slot & 0x3f;
-                if let Some(reference_map) = self.redirections.borrow().get(&reference) {
-                    has_reference_map = true;
-                    redirection = reference_map.redirection;
-                    if (self.empties.borrow()[offset] >> shift) & 0b1 == 1 {
-                        return Err(Error::new(FatalError, "Invalid slot state"));
+        Ok((false, Value::make_undefined()))
+
+    }
+
+    /// Remove a reference from the redirection of the value, returns whether
+    /// the redirection has no more references, and the redirection, or
+    /// return the record to remove the outer reference from once the region
+    /// unlocked
+    fn prepare_remove_reference_without_lock(&self, reference: Value, from: Value) -> Result<Result<(bool, Value), &RegionSlot>, Error> {
+
+        let slot = self.ensure_slot_referencable(reference)?;
+
+        let _guard = self.redirection_rw_lock.lock_read();
+        let mut has_reference_map = false;
+        let mut reference_map_is_empty = false;
+        let mut redirection = Value::make_undefined();
+        let offset = (slot >> 6) as usize;
+        let shift = slot & 0x3f;
+        if let Some(reference_map) = self.redirections.borrow().get(&reference) {
+            has_reference_map = true;
+            redirection = reference_map.redirection;
+            if (self.empties.borrow()[offset] >> shift) & 0b1 == 1 {
+                return Err(Error::new(FatalError, "Invalid slot state"));
+            }
+            reference_map.remove_reference(from)?;
+            reference_map_is_empty = reference_map.is_empty();
+        }
+        if has_reference_map {
+            if reference_map_is_empty {
+                self.redirections.borrow_mut().remove(&reference);
+                self.empties.borrow_mut()[offset] |= 0b1 << shift;
+                self.occupied.set(self.occupied.get() - 1);
+                self.free_slots.borrow_mut().push(slot as u16);
+            }
+            return Ok(Ok((reference_map_is_empty, redirection)));
+        }
+
+        Ok(Err(&self.slots[slot as usize]))
+
+    }
+
+    /// Add and remove references from a value to slots in the region with
+    /// the region locked once, returns the removed values whose redirections
+    /// have no more references, with their redirections
+    ///
+    /// Outer references of records are updated after the region unlocked,
+    /// as records are locked before the region when traps mutate references
+    pub fn update_references(&self, from: Value, added: &[Value], removed: &[Value]) -> Result<Vec<(Value, Value)>, Error> {
+
+        if self.is_shared() {
+            return Ok(Vec::new());
+        }
+
+        let mut added_records = Vec::new();
+        let mut removed_records = Vec::new();
+        let mut removing_nursery = Vec::new();
+        let mut unreferenced_redirections = Vec::new();
+
+        {
+            let _guard = self.rw_lock.lock_read();
+            for reference in added.iter() {
+                if let Some(record) = self.prepare_add_reference_without_lock(*reference, from)? {
+                    if self.nursery.borrow().get(reference).is_some() {
+                        removing_nursery.push(*reference);
                     }
-                    reference_map.remove_reference(from)?;
-                    reference_map_is_empty = reference_map.is_empty();
+                    added_records.push(record);
                 }
-                if has_reference_map {
-                    if reference_map_is_empty {
-                        self.redirections.borrow_mut().remove(&reference);
-                        self.empties.borrow_mut()[offset] |= 0b1 << shift;
-                        self.occupied.set(self.occupied.get() - 1);
-                    }
-                    return Ok((reference_map_is_empty, redirection));
+            }
+            for reference in removed.iter() {
+                match self.prepare_remove_reference_without_lock(*reference, from)? {
+                    Ok((true, redirection)) => unreferenced_redirections.push((*reference, redirection)),
+                    Ok((false, _)) => {},
+                    Err(record) => removed_records.push(record)
                 }
             }
+        }
 
-            &self.slots[slot as usize]
-        };
+        for record in added_records.iter() {
+            record.add_outer_reference(from)?;
+        }
+        for record in removed_records.iter() {
+            record.remove_outer_reference(from)?;
+        }
 
-        record.remove_outer_reference(from)?;
+        if !removing_nursery.is_empty() {
+            let _guard = self.rw_lock.lock_write();
+            let mut nursery = self.nursery.borrow_mut();
+            for reference in removing_nursery.iter() {
+                nursery.remove(reference);
+            }
+        }
 
-        Ok((false, Value::make_undefined()))
+        Ok(unreferenced_redirections)
 
     }
 
@@ -731,11 +1219,36 @@ impl Region {
 
             let slot = self.ensure_slot_available(value)?;
 
-            &self.slots[slot as usize]
+            &self.slots[slot as usize]
+
+        };
+
+        record.seal_slot()
+
+    }
+
+    /// Seal all slots and make the region read-only, so it could be shared
+    /// between isolates
+    pub fn seal_as_shared(&self) -> Result<(), Error> {
+
+        if self.is_shared() {
+            return Err(Error::new(FatalError, "Region already shared"));
+        }
+
+        {
+            let _guard = self.redirection_rw_lock.lock_read();
+            if !self.redirections.borrow().is_empty() {
+                return Err(Error::new(FatalError, "Region has redirections"));
+            }
+        }
+
+        for value in self.list_alive_values()? {
+            self.seal_slot(value)?;
+        }
 
-        };
+        self.shared.store(true, Ordering::SeqCst);
 
-        record.seal_slot()
+        Ok(())
 
     }
 
@@ -792,6 +1305,22 @@ impl Region {
 
     }
 
+    pub fn call_with_layout_guard(&self, value: Value, this: Value, arguments: Vec<Value>, context: &Box<dyn Context>, layout_guard: ReentrantLockReadGuard) -> Result<Pinned, Error> {
+
+        let record = {
+
+            let _guard = self.rw_lock.lock_read();
+
+            let slot = self.ensure_slot_available(value)?;
+
+            &self.slots[slot as usize]
+
+        };
+
+        record.call_with_layout_guard(this, arguments, context, layout_guard)
+
+    }
+
 }
 
 // Region field shortcuts
@@ -829,7 +1358,10 @@ impl Region {
 
     }
 
-    pub fn update_field_shortcuts(&self, value: Value, field_shortcuts: Arc<FieldShortcuts>) -> Result<(), Error> {
+    /// Update the field shortcuts of the slot, the field shortcuts replaced
+    /// are returned, which could still be read optimistically until the
+    /// slot layout locked for writing
+    pub fn update_field_shortcuts(&self, value: Value, field_shortcuts: Arc<FieldShortcuts>) -> Result<Option<Arc<FieldShortcuts>>, Error> {
 
         let record = {
 
@@ -841,13 +1373,14 @@ impl Region {
 
         };
 
-        record.set_field_shortcuts(field_shortcuts)?;
-
-        Ok(())
+        record.set_field_shortcuts(field_shortcuts)
 
     }
 
-    pub fn clear_field_shortcuts(&self, value: Value) -> Result<(), Error> {
+    /// Clear the field shortcuts of the slot, the field shortcuts cleared are
+    /// returned, which could still be read optimistically until the slot
+    /// layout locked for writing
+    pub fn clear_field_shortcuts(&self, value: Value) -> Result<Option<Arc<FieldShortcuts>>, Error> {
 
         let record = {
 
@@ -859,9 +1392,7 @@ impl Region {
 
         };
 
-        record.clear_field_shortcuts()?;
-
-        Ok(())
+        record.clear_field_shortcuts()
         
     }
 
@@ -970,6 +1501,40 @@ impl Region {
 
     }
 
+    pub fn get_prototype_ignore_slot_trap(&self, subject: Value, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let record = {
+
+            let _guard = self.rw_lock.lock_read();
+
+            let slot = self.ensure_slot_available(subject)?;
+
+            &self.slots[slot as usize]
+
+        };
+
+        record.get_prototype_ignore_slot_trap(context)
+
+    }
+
+    /// Overwrite the prototype of a slot directly, the old prototype is
+    /// returned and references should be updated by the caller
+    pub fn overwrite_prototype(&self, subject: Value, prototype: Value) -> Result<Value, Error> {
+
+        let record = {
+
+            let _guard = self.rw_lock.lock_read();
+
+            let slot = self.ensure_slot_available(subject)?;
+
+            &self.slots[slot as usize]
+
+        };
+
+        record.overwrite_prototype(prototype)
+
+    }
+
     pub fn set_prototype_with_layout_guard(&self, subject: Value, prototype: Value, context: &Box<dyn Context>, layout_guard: ReentrantLockReadGuard, no_redirection: bool) -> Result<(), Error> {
 
         let record = {
@@ -1039,6 +1604,22 @@ impl Region {
 
     }
 
+    pub fn get_own_property_trap<'a>(&self, id: Value, symbol: Symbol, context: &'a Box<dyn Context>) -> Result<Option<ProtectedPropertyTrap<'a>>, Error> {
+
+        let record = {
+
+            let _guard = self.rw_lock.lock_read();
+
+            let slot = self.ensure_slot_available(id)?;
+
+            &self.slots[slot as usize]
+
+        };
+
+        record.get_own_property_trap(symbol, context)
+
+    }
+
     pub fn overwrite_own_property(&self, id: Value, symbol: Symbol, value: Value) -> Result<(Vec<Value>, Vec<Symbol>, Vec<Value>, Vec<Symbol>), Error> {
 
         let record = {
@@ -1087,7 +1668,7 @@ impl Region {
  
     }
 
-    pub fn define_own_property_with_layout_guard<'a>(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>, layout_guard: ReentrantLockReadGuard<'a>, no_redirection: bool) -> Result<(), Error> {
+    pub fn define_own_property_with_layout_guard<'a>(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>, layout_guard: ReentrantLockReadGuard<'a>, no_redirection: bool) -> Result<bool, Error> {
 
         let record = {
 
@@ -1103,7 +1684,7 @@ impl Region {
         
     }
 
-    pub fn define_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<(), Error> {
+    pub fn define_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<bool, Error> {
 
         let record = {
 
@@ -1119,7 +1700,7 @@ impl Region {
         
     }
 
-    pub fn delete_own_property_with_layout_guard<'a>(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>, layout_guard: ReentrantLockReadGuard<'a>, no_redirection: bool) -> Result<(), Error> {
+    pub fn delete_own_property_with_layout_guard<'a>(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>, layout_guard: ReentrantLockReadGuard<'a>, no_redirection: bool) -> Result<bool, Error> {
 
         let record = {
 
@@ -1135,7 +1716,7 @@ impl Region {
         
     }
 
-    pub fn delete_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<(), Error> {
+    pub fn delete_own_property_ignore_slot_trap(&self, id: Value, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
 
         let record = {
 
@@ -1210,16 +1791,13 @@ impl Region {
 
         let mut values = Vec::new();
 
-        let mut slot = 0;
-        while slot < REGION_SLOT_SIZE {
+        let bitmap = *self.bitmap.borrow();
+        for slot in BitmapIterator::new(&bitmap, REGION_SLOT_SIZE) {
             let record = &self.slots[slot];
-            let offset = (slot >> 6) as usize;
-            let shift = slot & 0x3f;
-            if ((self.bitmap.borrow()[offset] >> shift) & 0b1 == 1) && record.is_alive() {
+            if record.is_alive() {
                 let id = record.get_id()?;
                 values.push(id);
             }
-            slot += 1;
         }
 
         Ok(values)
@@ -1242,6 +1820,43 @@ impl Region {
 
     }
 
+    /// List values and symbols referenced by the slot as they are recorded,
+    /// without refreshing redirected references
+    pub fn list_referenced_values_without_autorefresh(&self, value: Value) -> Result<(Vec<Value>, Vec<Symbol>), Error> {
+
+        let record = {
+
+            let _guard = self.rw_lock.lock_read();
+
+            let slot = self.ensure_slot_available(value)?;
+
+            &self.slots[slot as usize]
+
+        };
+
+        record.list_self_references_without_autorefresh()
+
+    }
+
+    /// Accumulate statistics of outer reference maps of alive slots and
+    /// redirections
+    pub fn collect_reference_map_stats(&self, stats: &mut ReferenceMapStats) {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let bitmap = *self.bitmap.borrow();
+        for slot in BitmapIterator::new(&bitmap, REGION_SLOT_SIZE) {
+            self.slots[slot].collect_reference_map_stats(stats);
+        }
+
+        let _guard = self.redirection_rw_lock.lock_read();
+
+        for redirection_reference in self.redirections.borrow().values() {
+            redirection_reference.reference_map.borrow().collect_stats(stats);
+        }
+
+    }
+
     pub fn list_values_in_nursery(&self) -> Vec<Value> {
 
         let _guard = self.rw_lock.lock_read();
@@ -1258,42 +1873,53 @@ impl Region {
 
     pub fn sweep_values(&self, base: u8, context: &Box<dyn Context>) -> Result<(), Error> {
 
+        if self.is_shared() {
+            return Ok(());
+        }
+
         let records = {
 
             let mut records = Vec::new();
 
             let _guard = self.rw_lock.lock_write();
 
-            let mut slot = 0;
-            while slot < REGION_SLOT_SIZE {
+            let bitmap = *self.bitmap.borrow();
+            for slot in BitmapIterator::new(&bitmap, REGION_SLOT_SIZE) {
 
                 let record = &self.slots[slot];
 
-                let offset = (slot >> 6) as usize;
+                let offset = slot >> 6;
                 let shift = slot & 0x3f;
 
-                if ((self.bitmap.borrow()[offset] >> shift) & 0b1 == 1) && 
-                   record.is_alive() && record.is_white(base)? {
+                if record.is_alive() && record.is_white(base)? {
 
                     let id = record.get_id()?;
 
-                    let reference_map = record.sweep_outer_reference_map()?;
-
-                    let reference_map_is_none = reference_map.is_none();
-                    self.redirect_slot_without_lock(id, Value::make_undefined(), reference_map)?;
-                    if reference_map_is_none {
-                        records.push(record);
+                    // Values referencing the slot from other regions are
+                    // swept in the same collection, the slot is redirected
+                    // to undefined and emptied once they removed their
+                    // references
+                    match record.sweep_outer_reference_map()? {
+                        Some(reference_map) => {
+                            let _guard = self.redirection_rw_lock.lock_write();
+                            self.redirections.borrow_mut().insert(id, RegionRedirectionReference {
+                                redirection: Value::make_undefined(),
+                                reference_map: RefCell::new(reference_map)
+                            });
+                        },
+                        None => {
+                            self.empties.borrow_mut()[offset] |= 1 << shift;
+                            self.occupied.set(self.occupied.get() - 1);
+                            self.free_slots.borrow_mut().push(slot as u16);
+                        }
                     }
 
-                    self.empties.borrow_mut()[offset] |= 1 << shift;
-                    self.occupied.set(self.occupied.get() - 1);
-
                     self.bitmap.borrow_mut()[offset] &= !(1 << shift);
                     self.nursery.borrow_mut().remove(&id);
 
+                    records.push(record);
 
                 }
-                slot += 1;
             }
 
             records
@@ -1309,6 +1935,11 @@ impl Region {
 
     pub fn mark_as_white(&self, value: Value, base: u8) -> Result<(), Error> {
 
+        // Shared regions are always alive, colors of isolates are ignored
+        if self.is_shared() {
+            return Ok(());
+        }
+
         let record = {
 
             let _guard = self.rw_lock.lock_read();
@@ -1325,6 +1956,10 @@ impl Region {
 
     pub fn mark_as_black(&self, value: Value, base: u8) -> Result<(), Error> {
 
+        if self.is_shared() {
+            return Ok(());
+        }
+
         let record = {
 
             let _guard = self.rw_lock.lock_read();
@@ -1341,6 +1976,10 @@ impl Region {
 
     pub fn mark_as_gray(&self, value: Value, base: u8) -> Result<bool, Error> {
 
+        if self.is_shared() {
+            return Ok(false);
+        }
+
         let record = {
 
             let _guard = self.rw_lock.lock_read();
@@ -1362,6 +2001,10 @@ impl Region {
 
     pub fn is_white(&self, value: Value, base: u8) -> Result<bool, Error> {
 
+        if self.is_shared() {
+            return Ok(false);
+        }
+
         let record = {
 
             let _guard = self.rw_lock.lock_read();
@@ -1378,6 +2021,10 @@ impl Region {
 
     pub fn is_black(&self, value: Value, base: u8) -> Result<bool, Error> {
 
+        if self.is_shared() {
+            return Ok(true);
+        }
+
         let record = {
 
             let _guard = self.rw_lock.lock_read();
@@ -1394,6 +2041,10 @@ impl Region {
 
     pub fn is_gray(&self, value: Value, base: u8) -> Result<bool, Error> {
 
+        if self.is_shared() {
+            return Ok(false);
+        }
+
         let record = {
 
             let _guard = self.rw_lock.lock_read();
@@ -1410,6 +2061,72 @@ impl Region {
 
 }
 
+// Region verification
+impl Region {
+
+    /// Check the bitmaps, counts and slot IDs of the region are consistent,
+    /// for catching states corrupted by misbehaving traps early
+    pub fn verify(&self) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let next_empty_slot_index = self.next_empty_slot_index.get() as usize;
+        if next_empty_slot_index > self.kind.get_slot_capacity() {
+            return Err(Error::new(FatalError, "Next empty slot out of capacity").with_region_id(self.id));
+        }
+
+        let bitmap = *self.bitmap.borrow();
+        let empties = *self.empties.borrow();
+
+        let mut occupied = 0;
+        for slot in 0..(REGION_BITMAP_SIZE << 6) {
+
+            let offset = slot >> 6;
+            let shift = slot & 0x3f;
+
+            let alive = (bitmap[offset] >> shift) & 0b1 != 0;
+            let empty = (empties[offset] >> shift) & 0b1 != 0;
+            if alive && empty {
+                return Err(Error::new(FatalError, "Alive slot marked as empty").with_region_id(self.id));
+            }
+            if empty {
+                continue;
+            }
+
+            if slot >= next_empty_slot_index {
+                return Err(Error::new(FatalError, "Slot used beyond next empty slot").with_region_id(self.id));
+            }
+            occupied += 1;
+
+            if alive {
+                let id = self.slots[slot].get_id()?;
+                if (id.get_region_id()? != self.id) || (id.get_region_slot()? as usize != slot) {
+                    return Err(Error::new(FatalError, "Slot ID mismatched with its position").with_value(id).with_region_id(self.id));
+                }
+            }
+
+        }
+
+        if occupied != self.occupied.get() as usize {
+            return Err(Error::new(FatalError, "Occupied count mismatched with slots").with_region_id(self.id));
+        }
+
+        for value in self.nursery.borrow().iter() {
+            if value.get_region_id()? != self.id {
+                return Err(Error::new(FatalError, "Value in nursery from another region").with_value(*value).with_region_id(self.id));
+            }
+            let slot = value.get_region_slot()? as usize;
+            if (slot >= REGION_SLOT_SIZE) || ((bitmap[slot >> 6] >> (slot & 0x3f)) & 0b1 == 0) {
+                return Err(Error::new(FatalError, "Value in nursery not alive").with_value(*value).with_region_id(self.id));
+            }
+        }
+
+        Ok(())
+
+    }
+
+}
+
 
 #[cfg(test)] use super::field_shortcuts::FieldTemplate;
 #[cfg(test)] use super::isolate::Isolate;
@@ -1473,7 +2190,7 @@ fn test_region_basic_slot_management() -> Result<(), Error> {
         assert!(!region.is_full());
         assert!(!region.is_empty());
         assert_eq!(region.occupied.get(), (REGION_SLOT_SIZE - i) as u16);
-        assert!(!region.could_gain_slot_quickly());
+        assert!(region.could_gain_slot_quickly());
         assert!(region.need_refragment() > 0.0);
         i += 1;
     }
@@ -1499,6 +2216,69 @@ fn test_region_basic_slot_management() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_region_free_slots() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext::new(isolate));
+
+    let region = Region::new(0);
+
+    let mut slots = Vec::new();
+    while !region.is_full() {
+        let slot = region.gain_slot(Object)?;
+        region.move_out_from_nursery(slot)?;
+        slots.push(slot);
+    }
+    assert!(region.gain_slot(Object).is_err());
+
+    region.recycle_slot(slots[10], true, &context)?;
+    region.recycle_slot(slots[20], true, &context)?;
+    assert!(region.could_gain_slot_quickly());
+
+    assert_eq!(region.gain_slot(Object)?.get_region_slot()?, 20);
+    assert_eq!(region.gain_slot(Object)?.get_region_slot()?, 10);
+    assert!(region.gain_slot(Object).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_region_verify() -> Result<(), Error> {
+
+    let region = Region::new(1);
+
+    region.verify()?;
+
+    let mut slots = Vec::new();
+    for _ in 0..100 {
+        slots.push(region.gain_slot(Object)?);
+    }
+    region.move_out_from_nursery(slots[0])?;
+    region.verify()?;
+
+    region.occupied.set(region.occupied.get() + 1);
+    let error = region.verify().unwrap_err();
+    assert_eq!(error.get_region_id(), Some(1));
+    region.occupied.set(region.occupied.get() - 1);
+    region.verify()?;
+
+    region.empties.borrow_mut()[0] |= 0b100;
+    assert!(region.verify().is_err());
+    region.empties.borrow_mut()[0] &= !0b100;
+
+    region.next_empty_slot_index.set(50);
+    assert!(region.verify().is_err());
+    region.next_empty_slot_index.set(100);
+
+    region.verify()?;
+
+    Ok(())
+
+}
+
 #[test]
 fn test_region_snapshot() -> Result<(), Error> {
 
@@ -1517,6 +2297,35 @@ fn test_region_snapshot() -> Result<(), Error> {
 
 }
 
+#[test]
+fn test_region_copied_snapshot() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let context: Box<dyn Context> = Box::new(TestContext::new(isolate));
+
+    let region = Region::new(0);
+
+    let slot = region.gain_slot(Object)?;
+    region.set_own_property_with_layout_guard(slot, slot, Symbol::new(1), Value::make_float(43.0), &context, layout_token.lock_read(), true)?;
+
+    let (_snapshot, version) = region.copy_slot(slot)?;
+    assert_eq!(region.get_own_property_with_layout_guard(slot, slot, Symbol::new(1), None, &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(43.0));
+
+    region.set_own_property_with_layout_guard(slot, slot, Symbol::new(1), Value::make_float(44.0), &context, layout_token.lock_read(), true)?;
+    assert!(region.freeze_copied_slot(slot, version)?.is_none());
+
+    let (snapshot, version) = region.copy_slot(slot)?;
+    let (in_nursery, reference_map, _removed_values, _removed_symbols) = region.freeze_copied_slot(slot, version)?.unwrap();
+    let (slot_2, _added_values, _added_symbols) = region.restore_slot(slot, snapshot, in_nursery, &reference_map)?;
+    assert_eq!(region.get_own_property_with_layout_guard(slot_2, slot_2, Symbol::new(1), None, &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(44.0));
+
+    Ok(())
+
+}
+
 #[test]
 fn test_region_references() -> Result<(), Error> {
 
@@ -1583,7 +2392,7 @@ fn test_region_slot_trap() -> Result<(), Error> {
 
     region.set_own_property_with_layout_guard(slot, slot, Symbol::new(2), Value::make_float(32.0), &context, layout_token.lock_read(), true)?;
     let test_property_trap: Arc<dyn PropertyTrap> = Arc::new(TestPropertyTrap::new(Value::make_float(64.0)));
-    region.define_own_property_with_layout_guard(slot, slot, Symbol::new(3), test_property_trap, &context, layout_token.lock_read(), true)?;
+    assert!(!region.define_own_property_with_layout_guard(slot, slot, Symbol::new(3), test_property_trap, &context, layout_token.lock_read(), true)?);
 
     assert_eq!(region.get_own_property_with_layout_guard(slot, slot, Symbol::new(1), None, &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(1.0));
     assert_eq!(region.get_own_property_with_layout_guard(slot, slot, Symbol::new(2), None, &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(32.0));
@@ -1620,6 +2429,37 @@ fn test_region_own_property() -> Result<(), Error> {
     
 }
 
+#[test]
+fn test_region_own_property_results() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let context: Box<dyn Context> = Box::new(TestContext::new(isolate));
+
+    let region = Region::new(0);
+
+    let slot = region.gain_slot(Object)?;
+
+    let test_property_trap: Arc<dyn PropertyTrap> = Arc::new(TestPropertyTrap::new(Value::make_float(64.0)));
+    assert!(!region.define_own_property_with_layout_guard(slot, slot, Symbol::new(1), test_property_trap.clone(), &context, layout_token.lock_read(), true)?);
+    assert!(region.define_own_property_ignore_slot_trap(slot, slot, Symbol::new(1), test_property_trap, &context)?);
+
+    assert!(region.delete_own_property_with_layout_guard(slot, slot, Symbol::new(1), &context, layout_token.lock_read(), true)?);
+    assert!(!region.delete_own_property_with_layout_guard(slot, slot, Symbol::new(1), &context, layout_token.lock_read(), true)?);
+    assert!(!region.delete_own_property_ignore_slot_trap(slot, slot, Symbol::new(2), &context)?);
+
+    let slot_trap: Arc<dyn SlotTrap> = Arc::new(TestSlotTrap2::new(slot));
+    region.set_slot_trap(slot, slot_trap, &context)?;
+
+    region.set_own_property_with_layout_guard(slot, slot, Symbol::new(2), Value::make_float(32.0), &context, layout_token.lock_read(), true)?;
+    assert!(region.delete_own_property_with_layout_guard(slot, slot, Symbol::new(2), &context, layout_token.lock_read(), true)?);
+
+    Ok(())
+
+}
+
 #[test]
 fn test_region_field_shortcuts() -> Result<(), Error> {
 
@@ -1724,3 +2564,67 @@ fn test_region_seal() -> Result<(), Error> {
     Ok(())
 
 }
+
+#[cfg(all(test, loom))] use super::test::LoomShared;
+
+/// Check a loom model in a thread with the stack large enough for regions
+#[cfg(all(test, loom))]
+fn loom_model<F>(model: F) where F: Fn() + Sync + Send + 'static {
+    let model = Arc::new(model);
+    loom::model(move || {
+        let model = model.clone();
+        loom::thread::Builder::new().stack_size(16 * 1024 * 1024).spawn(move || model()).unwrap().join().unwrap();
+    });
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_region_gain_slot() {
+
+    loom_model(|| {
+
+        let region = Arc::new(LoomShared::new(Region::new(0)));
+
+        let thread = {
+            let region = region.clone();
+            loom::thread::spawn(move || region.gain_slot(Object).unwrap())
+        };
+
+        let slot = region.gain_slot(Object).unwrap();
+        let slot_2 = thread.join().unwrap();
+
+        assert_ne!(slot, slot_2);
+        assert_eq!(region.occupied.get(), 2);
+        assert!(region.is_value_occupied(slot).unwrap());
+        assert!(region.is_value_occupied(slot_2).unwrap());
+
+    });
+
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_region_redirection() {
+
+    loom_model(|| {
+
+        let region = Arc::new(LoomShared::new(Region::new(0)));
+
+        let slot = region.gain_slot(Object).unwrap();
+        let redirection = Value::make_object(1, 0);
+        region.freeze_slot(slot).unwrap();
+
+        let thread = {
+            let region = region.clone();
+            loom::thread::spawn(move || region.resolve_redirection(slot).unwrap())
+        };
+
+        region.redirect_slot(slot, redirection, Some(Box::new(ReferenceMap::new()))).unwrap();
+        assert_eq!(region.resolve_redirection(slot).unwrap(), redirection);
+
+        let resolved = thread.join().unwrap();
+        assert!(resolved == slot || resolved == redirection);
+
+    });
+
+}
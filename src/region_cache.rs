@@ -0,0 +1,102 @@
+use alloc::sync::Arc;
+use alloc::sync::Weak;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cell::RefCell;
+
+use super::region::Region;
+
+/// Count of regions kept in a region cache
+const REGION_CACHE_SIZE: usize = 8;
+
+/// Cache of recently used regions of a context, keyed by region IDs
+///
+/// Entries are stamped by the isolate and its region generation, which is
+/// advanced once a region is recycled, replaced or paged out, so the cache
+/// is cleared lazily on the next lookup. Regions are cached weakly, so
+/// regions in use are still tracked by their strong references
+pub struct RegionCache {
+    stamp: Cell<(usize, u64)>,
+    next_index: Cell<usize>,
+    regions: RefCell<Vec<(u32, Weak<Region>)>>
+}
+
+impl RegionCache {
+
+    pub fn new() -> RegionCache {
+        RegionCache {
+            stamp: Cell::new((0, 0)),
+            next_index: Cell::new(0),
+            regions: RefCell::new(Vec::with_capacity(REGION_CACHE_SIZE))
+        }
+    }
+
+    /// Get a cached region, the cache is cleared if the stamp changed
+    pub fn get(&self, stamp: (usize, u64), region_id: u32) -> Option<Arc<Region>> {
+
+        if self.stamp.get() != stamp {
+            self.clear();
+            self.stamp.set(stamp);
+            return None;
+        }
+
+        for (id, region) in self.regions.borrow().iter() {
+            if *id == region_id {
+                return region.upgrade();
+            }
+        }
+
+        None
+
+    }
+
+    /// Cache a region, the oldest cached region is replaced if the cache is
+    /// full
+    pub fn insert(&self, stamp: (usize, u64), region_id: u32, region: Arc<Region>) {
+
+        if self.stamp.get() != stamp {
+            self.clear();
+            self.stamp.set(stamp);
+        }
+
+        let mut regions = self.regions.borrow_mut();
+        if regions.len() < REGION_CACHE_SIZE {
+            regions.push((region_id, Arc::downgrade(&region)));
+        } else {
+            let index = self.next_index.get();
+            regions[index] = (region_id, Arc::downgrade(&region));
+            self.next_index.set((index + 1) % REGION_CACHE_SIZE);
+        }
+
+    }
+
+    pub fn clear(&self) {
+        self.regions.borrow_mut().clear();
+        self.next_index.set(0);
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.regions.borrow().len()
+    }
+
+}
+
+#[test]
+fn test_region_cache() {
+
+    let region_cache = RegionCache::new();
+
+    assert!(region_cache.get((1, 0), 3).is_none());
+
+    let regions: Vec<Arc<Region>> = (0 .. 10).map(|id| Arc::new(Region::new(id))).collect();
+    for region in regions.iter() {
+        region_cache.insert((1, 0), region.get_id(), region.clone());
+    }
+    assert_eq!(region_cache.get_size(), REGION_CACHE_SIZE);
+    assert!(region_cache.get((1, 0), 0).is_none());
+    assert_eq!(region_cache.get((1, 0), 9).unwrap().get_id(), 9);
+
+    assert!(region_cache.get((1, 1), 9).is_none());
+    assert_eq!(region_cache.get_size(), 0);
+
+}
@@ -1,29 +1,49 @@
-use std::cell::Cell;
-use std::cell::RefCell;
-use std::hash::Hash;
-use std::hash::Hasher;
-use std::sync::Arc;
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::cell::Ref;
+use core::cell::RefCell;
+use core::cell::RefMut;
+use core::hash::Hash;
+use core::hash::Hasher;
+use alloc::sync::Arc;
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::Ordering;
+#[cfg(feature = "std")] use core::time::Duration;
+#[cfg(feature = "std")] use std::time::Instant;
+#[cfg(feature = "backtrace")] use std::backtrace::Backtrace;
 
 use super::base::Error;
 use super::base::ErrorType::*;
 use super::base::Value;
+use super::util::HashMap;
+use super::util::LockClass;
 use super::util::RwLock;
+use super::util::RwLockReadGuard;
+use super::util::RwLockWriteGuard;
 
 pub struct Root {
     rw_lock: RwLock,
     value: Cell<Value>,
-    references: Cell<u32>
+    references: Cell<u32>,
+    released: Cell<bool>,
+    local: Cell<bool>,
+    #[cfg(feature = "std")] created: Instant,
+    #[cfg(feature = "backtrace")] backtrace: Arc<Backtrace>
 }
 
 impl Root {
 
     pub fn new(value: Value) -> Root {
         Root {
-            rw_lock: RwLock::new(),
+            rw_lock: RwLock::with_class(LockClass::Root),
             value: Cell::new(value),
-            references: Cell::new(0)
+            references: Cell::new(0),
+            released: Cell::new(false),
+            local: Cell::new(false),
+            #[cfg(feature = "std")] created: Instant::now(),
+            #[cfg(feature = "backtrace")] backtrace: Arc::new(Backtrace::capture())
         }
     }
 
@@ -63,6 +83,10 @@ impl Root {
 
         let _guard = self.rw_lock.lock_write();
 
+        if self.released.get() {
+            return Ok(0);
+        }
+
         let references = self.references.get();
         if references == 0 {
             return Err(Error::new(FatalError, "Reference count over released"))
@@ -74,6 +98,14 @@ impl Root {
 
     }
 
+    pub fn get_references(&self) -> u32 {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.references.get()
+
+    }
+
     pub fn is_alone(&self) -> bool {
 
         let _guard = self.rw_lock.lock_read();
@@ -82,6 +114,56 @@ impl Root {
 
     }
 
+    /// Get time elapsed since the root was created
+    #[cfg(feature = "std")]
+    pub fn get_age(&self) -> Duration {
+        self.created.elapsed()
+    }
+
+    /// Get the backtrace where the root was created, only captured when
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set
+    #[cfg(feature = "backtrace")]
+    pub fn get_backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// Release all references of the root, decreasing references of a
+    /// released root will be ignored
+    pub fn release(&self) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.references.set(0);
+        self.released.set(true);
+
+    }
+
+    pub fn is_released(&self) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.released.get()
+
+    }
+
+    /// Check whether the root is held by a handle block instead of the
+    /// shared root table
+    pub fn is_local(&self) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.local.get()
+
+    }
+
+    pub fn set_local(&self, local: bool) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.local.set(local);
+
+    }
+
 }
 
 pub struct Roots {
@@ -94,7 +176,7 @@ impl Roots {
 
     pub fn new(value: Value) -> Roots {
         Roots {
-            rw_lock: RwLock::new(),
+            rw_lock: RwLock::with_class(LockClass::Root),
             value: Cell::new(value),
             roots: RefCell::new(Vec::new())
         }
@@ -127,6 +209,62 @@ impl Roots {
 
     }
 
+    /// Add a dedicated root not shared by others
+    pub fn add_dedicated_root(&self) -> Arc<Root> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let root = Arc::new(Root::new(self.value.get()));
+        self.roots.borrow_mut().push(root.clone());
+
+        root
+
+    }
+
+    pub fn list_roots(&self) -> Vec<Arc<Root>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.roots.borrow().clone()
+
+    }
+
+    pub fn remove_released_roots(&self) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.roots.borrow_mut().retain(|root| !root.is_released());
+
+    }
+
+    /// Add a root flushed from a handle block
+    pub fn push_root(&self, root: Arc<Root>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.roots.borrow_mut().push(root);
+
+    }
+
+    /// Remove roots without references, a root removed will not be shared
+    /// by pins added later
+    pub fn remove_alone_roots(&self) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.roots.borrow_mut().retain(|root| !root.is_alone());
+
+    }
+
+    /// Count references of all roots not released
+    pub fn count_references(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.roots.borrow().iter().map(|root| root.get_references() as usize).sum()
+
+    }
+
     // TODO: check whether the code below is needed
     #[allow(dead_code)]
     pub fn get_value(&self) -> Value {
@@ -187,10 +325,95 @@ impl Roots {
 
 }
 
+/// Notice of a value dropped, passed to drop listeners of weak roots
+#[derive(Copy, Clone, Debug)]
+pub struct DropNotice {
+    value: Value,
+    weak_id: u32
+}
+
+impl DropNotice {
+
+    pub fn new(value: Value, weak_id: u32) -> DropNotice {
+        DropNotice {
+            value: value,
+            weak_id: weak_id
+        }
+    }
+
+    /// Get the value dropped, its slot may be reused by values gained later
+    pub fn get_value(&self) -> Value {
+        self.value
+    }
+
+    /// Get the identity hash of the value dropped, the same as the one got
+    /// from the value while it was alive
+    pub fn get_identity_hash(&self) -> u64 {
+        self.value.get_identity_hash()
+    }
+
+    /// Get ID of the weak root notified
+    pub fn get_weak_id(&self) -> u32 {
+        self.weak_id
+    }
+
+}
+
 pub trait DropListener {
 
     fn notify_drop(&self);
 
+    /// Notify the drop of the value observed by a weak root, a slotted
+    /// replacement could be returned to keep the weak root and the listener
+    /// registered against it, and the listener will be notified again once
+    /// the replacement drops
+    fn notify_value_drop(&self, _notice: &DropNotice) -> Option<Value> {
+        self.notify_drop();
+        None
+    }
+
+    /// Get the payload carried by the listener, such as a key of an external
+    /// cache to invalidate
+    fn get_payload(&self) -> Option<&dyn Any> {
+        None
+    }
+
+}
+
+/// Drop listener carrying a typed payload, notified through a callback which
+/// could return a replacement to observe next
+pub struct PayloadDropListener<T> {
+    payload: T,
+    callback: Box<dyn Fn(&T, &DropNotice) -> Option<Value>>
+}
+
+impl<T: Any> PayloadDropListener<T> {
+
+    pub fn new<F>(payload: T, callback: F) -> PayloadDropListener<T> where F: Fn(&T, &DropNotice) -> Option<Value> + 'static {
+        PayloadDropListener {
+            payload: payload,
+            callback: Box::new(callback)
+        }
+    }
+
+    pub fn get_payload(&self) -> &T {
+        &self.payload
+    }
+
+}
+
+impl<T: Any> DropListener for PayloadDropListener<T> {
+
+    fn notify_drop(&self) {}
+
+    fn notify_value_drop(&self, notice: &DropNotice) -> Option<Value> {
+        (self.callback)(&self.payload, notice)
+    }
+
+    fn get_payload(&self) -> Option<&dyn Any> {
+        Some(&self.payload)
+    }
+
 }
 
 pub struct WeakIdGenerator {
@@ -226,13 +449,18 @@ impl WeakRoot {
 
         WeakRoot {
             weak_id: weak_id_generator.generate(),
-            rw_lock: RwLock::new(),
+            rw_lock: RwLock::with_class(LockClass::Root),
             value: Cell::new(Some(value)),            
             drop_listener: RefCell::new(drop_listener)
         }
 
     }
 
+    /// Get ID of the weak root, weak roots created later have greater IDs
+    pub fn get_weak_id(&self) -> u32 {
+        self.weak_id
+    }
+
     pub fn is_dropped(&self) -> bool {
 
         let _guard = self.rw_lock.lock_read();
@@ -241,23 +469,59 @@ impl WeakRoot {
 
     }
 
-    pub fn notify_drop(&self) -> Result<(), Error> {
+    pub fn has_drop_listener(&self) -> bool {
 
-        let _guard = self.rw_lock.lock_write();
+        let _guard = self.rw_lock.lock_read();
 
-        if self.value.get().is_none() {
-            return Err(Error::new(FatalError, "Value already dropped"));
-        }
+        self.drop_listener.borrow().is_some()
+
+    }
+
+    /// Get a copy of the payload carried by the drop listener, `None` will be
+    /// returned if there is no listener or the payload is not of the type
+    pub fn get_payload<T: Any + Clone>(&self) -> Option<T> {
 
-        self.value.set(None);
+        let _guard = self.rw_lock.lock_read();
+
+        let drop_listener = self.drop_listener.borrow();
+        drop_listener.as_ref()?.get_payload()?.downcast_ref::<T>().cloned()
+
+    }
+
+    /// Notify the drop listener that the value dropped, the replacement
+    /// returned by the listener is observed instead and returned, while
+    /// replacements not slotted or the same as the value are ignored
+    pub fn notify_drop(&self) -> Result<Option<Value>, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let value = match self.value.get() {
+            None => {
+                return Err(Error::new(FatalError, "Value already dropped"));
+            },
+            Some(value) => value
+        };
 
         let mut drop_listener = self.drop_listener.borrow_mut();
-        if drop_listener.is_some() {
-            drop_listener.as_ref().unwrap().notify_drop();
-            *drop_listener = None;
+
+        let replacement = match drop_listener.as_ref() {
+            None => None,
+            Some(drop_listener) => {
+                drop_listener.notify_value_drop(&DropNotice::new(value, self.weak_id))
+            }
+        }.filter(|replacement| replacement.is_slotted() && *replacement != value);
+
+        match replacement {
+            Some(replacement) => {
+                self.value.set(Some(replacement));
+            },
+            None => {
+                self.value.set(None);
+                *drop_listener = None;
+            }
         }
 
-        Ok(())
+        Ok(replacement)
 
     }
 
@@ -309,8 +573,231 @@ impl Hash for WeakRoot {
 
 }
 
+/// Census of weak roots of an isolate, counting weak roots targeting each
+/// value and weak roots fired since the isolate created
+#[derive(Clone, Debug)]
+pub struct WeakRootCensus {
+    targets: HashMap<Value, usize>,
+    fired: u64
+}
+
+impl WeakRootCensus {
+
+    pub fn new(targets: HashMap<Value, usize>, fired: u64) -> WeakRootCensus {
+        WeakRootCensus {
+            targets: targets,
+            fired: fired
+        }
+    }
+
+    /// Get count of values targeted by weak roots
+    pub fn get_target_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Get count of weak roots not fired yet
+    pub fn get_weak_root_count(&self) -> usize {
+        self.targets.values().sum()
+    }
+
+    /// Get count of weak roots targeting a value
+    pub fn get_weak_root_count_of(&self, value: Value) -> usize {
+        self.targets.get(&value).copied().unwrap_or(0)
+    }
+
+    /// Get count of weak roots fired as their values dropped
+    pub fn get_fired_count(&self) -> u64 {
+        self.fired
+    }
+
+    /// List values targeted by weak roots with counts of their weak roots,
+    /// values targeted by most weak roots come first
+    pub fn list_targets(&self) -> Vec<(Value, usize)> {
+
+        let mut targets: Vec<(Value, usize)> = self.targets.iter().map(|(value, count)| (*value, *count)).collect();
+        targets.sort_by(|(value, count), (value_2, count_2)| count_2.cmp(count).then(value.to_bits().cmp(&value_2.to_bits())));
+
+        targets
+
+    }
+
+}
+
+/// Count of shards in a root table, should be a power of two
+const ROOT_SHARD_COUNT: usize = 16;
+
+/// Shard of a root table guarded by its own lock
+pub struct RootShard<T> {
+    rw_lock: RwLock,
+    roots: RefCell<HashMap<Value, T>>
+}
+
+impl<T> RootShard<T> {
+
+    fn new(roots: HashMap<Value, T>) -> RootShard<T> {
+        RootShard {
+            rw_lock: RwLock::with_class(LockClass::Root),
+            roots: RefCell::new(roots)
+        }
+    }
+
+    pub fn lock_read(&self) -> RwLockReadGuard<'_> {
+        self.rw_lock.lock_read()
+    }
+
+    pub fn lock_write(&self) -> RwLockWriteGuard<'_> {
+        self.rw_lock.lock_write()
+    }
+
+    pub fn borrow(&self) -> Ref<'_, HashMap<Value, T>> {
+        self.roots.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, HashMap<Value, T>> {
+        self.roots.borrow_mut()
+    }
+
+}
+
+/// Table from values to their roots sharded by value hashes, so values
+/// rooted from different threads rarely contend on the same lock
+pub struct RootTable<T> {
+    shards: Vec<RootShard<T>>
+}
+
+impl<T> RootTable<T> {
+
+    pub fn new() -> RootTable<T> {
+        RootTable::from_map(HashMap::new())
+    }
+
+    pub fn from_map(roots: HashMap<Value, T>) -> RootTable<T> {
+
+        let mut maps: Vec<HashMap<Value, T>> = (0 .. ROOT_SHARD_COUNT).map(|_| HashMap::new()).collect();
+        for (value, root) in roots {
+            maps[RootTable::<T>::get_shard_index(value)].insert(value, root);
+        }
+
+        RootTable {
+            shards: maps.into_iter().map(RootShard::new).collect()
+        }
+
+    }
+
+    pub fn get_shard_index(value: Value) -> usize {
+        (value.to_bits().wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32) as usize & (ROOT_SHARD_COUNT - 1)
+    }
+
+    pub fn get_shard(&self, value: Value) -> &RootShard<T> {
+        &self.shards[RootTable::<T>::get_shard_index(value)]
+    }
+
+    /// Lock shards of two values for writing in ascending order of shard
+    /// indices, the same shard is locked only once
+    pub fn lock_write_pair(&self, value: Value, another_value: Value) -> Vec<RwLockWriteGuard<'_>> {
+
+        let index = RootTable::<T>::get_shard_index(value);
+        let another_index = RootTable::<T>::get_shard_index(another_value);
+
+        let mut guards = Vec::new();
+        guards.push(self.shards[index.min(another_index)].lock_write());
+        if index != another_index {
+            guards.push(self.shards[index.max(another_index)].lock_write());
+        }
+
+        guards
+
+    }
+
+    /// Lock all shards for writing in ascending order of shard indices
+    pub fn lock_write_all(&self) -> Vec<RwLockWriteGuard<'_>> {
+        self.shards.iter().map(|shard| shard.lock_write()).collect()
+    }
+
+    pub fn iterate_shards(&self) -> core::slice::Iter<'_, RootShard<T>> {
+        self.shards.iter()
+    }
+
+    /// Count values in all shards
+    pub fn count(&self) -> usize {
+        self.shards.iter().map(|shard| {
+            let _guard = shard.lock_read();
+            shard.borrow().len()
+        }).sum()
+    }
+
+    pub fn list_values(&self) -> Vec<Value> {
+
+        let mut values = Vec::new();
+        for shard in self.shards.iter() {
+            let _guard = shard.lock_read();
+            values.extend(shard.borrow().keys().copied());
+        }
+
+        values
+
+    }
+
+}
+
 #[cfg(test)] use super::test::TestDropListener;
 
+#[test]
+fn test_root_table() {
+
+    let mut roots = HashMap::new();
+    for index in 0 .. 64 {
+        roots.insert(Value::make_object(1, index), index);
+    }
+
+    let root_table = RootTable::from_map(roots);
+    assert_eq!(root_table.count(), 64);
+    assert!(root_table.iterate_shards().filter(|shard| !shard.borrow().is_empty()).count() > 1);
+
+    let value = Value::make_object(1, 7);
+    assert_eq!(root_table.get_shard(value).borrow().get(&value), Some(&7));
+
+    root_table.get_shard(value).borrow_mut().remove(&value);
+    assert_eq!(root_table.count(), 63);
+    assert!(!root_table.list_values().contains(&value));
+
+}
+
+#[cfg(all(test, loom))] use super::test::LoomShared;
+
+#[cfg(loom)]
+#[test]
+fn loom_root_table() {
+
+    loom::model(|| {
+
+        let root_table = Arc::new(LoomShared::new(RootTable::new()));
+
+        let value = Value::make_object(1, 1);
+        let value_2 = Value::make_object(1, 2);
+
+        let thread = {
+            let root_table = root_table.clone();
+            loom::thread::spawn(move || {
+                let _guards = root_table.lock_write_pair(value, value_2);
+                root_table.get_shard(value).borrow_mut().insert(value, 1);
+            })
+        };
+
+        {
+            let shard = root_table.get_shard(value_2);
+            let _guard = shard.lock_write();
+            shard.borrow_mut().insert(value_2, 2);
+        }
+
+        thread.join().unwrap();
+
+        assert_eq!(root_table.count(), 2);
+
+    });
+
+}
+
 #[test]
 fn test_root_creation() {
 
@@ -364,6 +851,24 @@ fn test_root_references() -> Result<(), Error> {
 
 }
 
+#[test]
+fn test_root_get_references() -> Result<(), Error> {
+
+    let root = Root::new(Value::make_float(4.3));
+
+    assert_eq!(root.get_references(), 0);
+
+    root.increase_reference()?;
+    root.increase_reference()?;
+    assert_eq!(root.get_references(), 2);
+
+    root.decrease_reference()?;
+    assert_eq!(root.get_references(), 1);
+
+    Ok(())
+
+}
+
 #[test]
 fn test_roots_creation() {
 
@@ -458,6 +963,24 @@ fn test_roots_merge() -> Result<(), Error> {
 
 }
 
+#[test]
+fn test_weak_root_census() {
+
+    let mut targets = HashMap::new();
+    targets.insert(Value::make_object(1, 1), 1);
+    targets.insert(Value::make_object(1, 2), 3);
+
+    let census = WeakRootCensus::new(targets, 2);
+
+    assert_eq!(census.get_target_count(), 2);
+    assert_eq!(census.get_weak_root_count(), 4);
+    assert_eq!(census.get_weak_root_count_of(Value::make_object(1, 2)), 3);
+    assert_eq!(census.get_weak_root_count_of(Value::make_object(1, 3)), 0);
+    assert_eq!(census.get_fired_count(), 2);
+    assert_eq!(census.list_targets(), vec!((Value::make_object(1, 2), 3), (Value::make_object(1, 1), 1)));
+
+}
+
 #[test]
 fn test_weak_id_generator() {
 
@@ -504,3 +1027,67 @@ fn test_weak_root() -> Result<(), Error> {
     Ok(())
 
 }
+
+#[test]
+fn test_weak_root_has_drop_listener() -> Result<(), Error> {
+
+    let weak_id_generator = WeakIdGenerator::new();
+
+    let weak_root = WeakRoot::new(&weak_id_generator, Value::make_float(44.0), None);
+    assert!(!weak_root.has_drop_listener());
+
+    let drop_value = Arc::new(Cell::new(Value::make_float(22.0)));
+
+    let drop_listener = Box::new(TestDropListener::new(drop_value.clone()));
+
+    let weak_root = WeakRoot::new(&weak_id_generator, Value::make_float(44.0), Some(drop_listener));
+    assert!(weak_root.has_drop_listener());
+
+    weak_root.notify_drop()?;
+    assert!(!weak_root.has_drop_listener());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_weak_root_drop_notice() -> Result<(), Error> {
+
+    let weak_id_generator = WeakIdGenerator::new();
+
+    let notices = Arc::new(RefCell::new(Vec::new()));
+
+    let drop_listener = {
+        let notices = notices.clone();
+        Box::new(PayloadDropListener::new(7u32, move |payload: &u32, notice: &DropNotice| {
+            notices.borrow_mut().push((*payload, notice.get_value(), notice.get_identity_hash()));
+            if notice.get_value() == Value::make_object(1, 1) {
+                Some(Value::make_object(1, 2))
+            } else {
+                None
+            }
+        }))
+    };
+
+    let weak_root = WeakRoot::new(&weak_id_generator, Value::make_object(1, 1), Some(drop_listener));
+
+    assert_eq!(weak_root.get_payload::<u32>(), Some(7));
+    assert_eq!(weak_root.get_payload::<u64>(), None);
+
+    assert_eq!(weak_root.notify_drop()?, Some(Value::make_object(1, 2)));
+    assert!(!weak_root.is_dropped());
+    assert_eq!(weak_root.get_value(), Some(Value::make_object(1, 2)));
+    assert!(weak_root.has_drop_listener());
+
+    assert_eq!(weak_root.notify_drop()?, None);
+    assert!(weak_root.is_dropped());
+    assert_eq!(weak_root.get_payload::<u32>(), None);
+
+    let notices = notices.borrow();
+    assert_eq!(notices.len(), 2);
+    assert_eq!(notices[0], (7, Value::make_object(1, 1), Value::make_object(1, 1).get_identity_hash()));
+    assert_eq!(notices[1].1, Value::make_object(1, 2));
+
+    Ok(())
+
+}
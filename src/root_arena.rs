@@ -0,0 +1,254 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+use super::base::Value;
+use super::isolate::Isolate;
+use super::util::HashMap;
+use super::util::LockClass;
+use super::util::ReentrantToken;
+use super::util::RwLock;
+
+/// Values rooted by a root arena, appended in order of rooting
+pub struct RootArenaValues {
+    rw_lock: RwLock,
+    values: RefCell<Vec<Value>>
+}
+
+impl RootArenaValues {
+
+    fn new() -> RootArenaValues {
+        RootArenaValues {
+            rw_lock: RwLock::with_class(LockClass::Root),
+            values: RefCell::new(Vec::new())
+        }
+    }
+
+    fn push(&self, value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.values.borrow_mut().push(value);
+
+    }
+
+    pub fn get_size(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.values.borrow().len()
+
+    }
+
+    pub fn list_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.values.borrow().clone()
+
+    }
+
+    pub fn contains(&self, value: Value) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.values.borrow().contains(&value)
+
+    }
+
+    pub fn refresh_values(&self, moves: &HashMap<Value, Value>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        for value in self.values.borrow_mut().iter_mut() {
+            if let Some(new_value) = moves.get(value) {
+                *value = *new_value;
+            }
+        }
+
+    }
+
+}
+
+/// Root arenas alive in an isolate, each arena is registered once however
+/// many values it roots
+pub struct RootArenas {
+    rw_lock: RwLock,
+    next_id: AtomicU64,
+    arenas: RefCell<HashMap<u64, Arc<RootArenaValues>>>
+}
+
+impl RootArenas {
+
+    pub fn new() -> RootArenas {
+        RootArenas {
+            rw_lock: RwLock::with_class(LockClass::Root),
+            next_id: AtomicU64::new(1),
+            arenas: RefCell::new(HashMap::new())
+        }
+    }
+
+    pub fn register(&self) -> (u64, Arc<RootArenaValues>) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let values = Arc::new(RootArenaValues::new());
+
+        self.arenas.borrow_mut().insert(id, values.clone());
+
+        (id, values)
+
+    }
+
+    pub fn unregister(&self, id: u64) -> bool {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.arenas.borrow_mut().remove(&id).is_some()
+
+    }
+
+    /// Count arenas alive
+    pub fn count(&self) -> usize {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.arenas.borrow().len()
+
+    }
+
+    pub fn list_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let mut values = Vec::new();
+        for arena_values in self.arenas.borrow().values() {
+            values.extend(arena_values.list_values());
+        }
+
+        values
+
+    }
+
+    pub fn contains(&self, value: Value) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.arenas.borrow().values().any(|arena_values| arena_values.contains(value))
+
+    }
+
+    /// Refresh values of moved slots in all arenas
+    pub fn refresh_values(&self, moves: &[(Value, Value)]) {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let arenas = self.arenas.borrow();
+        if arenas.is_empty() {
+            return;
+        }
+
+        let moves: HashMap<Value, Value> = moves.iter().copied().collect();
+        for arena_values in arenas.values() {
+            arena_values.refresh_values(&moves);
+        }
+
+    }
+
+}
+
+/// Arena rooting a batch of short-lived values, values are appended to the
+/// arena instead of registered as roots one by one, and all of them are
+/// released at once when the arena drops
+///
+/// Values returned by `add` are not refreshed once their slots are moved by
+/// compactions, use `list_values` to get the moved ones
+pub struct RootArena<'a> {
+    isolate: &'a Isolate,
+    id: u64,
+    values: Arc<RootArenaValues>
+}
+
+impl<'a> RootArena<'a> {
+
+    pub fn new(isolate: &'a Isolate, id: u64, values: Arc<RootArenaValues>) -> RootArena<'a> {
+        RootArena {
+            isolate: isolate,
+            id: id,
+            values: values
+        }
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    /// Root a value until the arena drops
+    pub fn add(&self, value: Value, layout_token: &ReentrantToken) -> Result<Value, Error> {
+
+        if !value.is_slotted() {
+            return Err(Error::new(FatalError, "Only slot value could added into roots"));
+        }
+
+        let _guard = layout_token.lock_read();
+
+        let value = self.isolate.resolve_real_value(value, layout_token)?;
+
+        self.values.push(value);
+
+        self.isolate.move_value_out_from_nursery(value, layout_token)?;
+
+        Ok(value)
+
+    }
+
+    /// Get count of values rooted
+    pub fn get_size(&self) -> usize {
+        self.values.get_size()
+    }
+
+    pub fn list_values(&self) -> Vec<Value> {
+        self.values.list_values()
+    }
+
+}
+
+impl<'a> Drop for RootArena<'a> {
+
+    fn drop(&mut self) {
+        self.isolate.release_root_arena(self.id);
+    }
+
+}
+
+#[test]
+fn test_root_arenas() {
+
+    let root_arenas = RootArenas::new();
+
+    let (id, values) = root_arenas.register();
+    let (id_2, values_2) = root_arenas.register();
+    assert_ne!(id, id_2);
+    assert_eq!(root_arenas.count(), 2);
+
+    values.push(Value::make_object(1, 1));
+    values.push(Value::make_object(1, 2));
+    values_2.push(Value::make_object(2, 1));
+
+    assert_eq!(root_arenas.list_values().len(), 3);
+    assert!(root_arenas.contains(Value::make_object(2, 1)));
+
+    root_arenas.refresh_values(&[(Value::make_object(1, 2), Value::make_object(3, 1))]);
+    assert_eq!(values.list_values(), vec!(Value::make_object(1, 1), Value::make_object(3, 1)));
+
+    assert!(root_arenas.unregister(id_2));
+    assert!(!root_arenas.unregister(id_2));
+    assert!(!root_arenas.contains(Value::make_object(2, 1)));
+    assert_eq!(root_arenas.count(), 1);
+
+}
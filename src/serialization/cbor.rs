@@ -0,0 +1,226 @@
+use std::io::Read;
+use std::io::Write;
+
+use super::item::Item;
+use super::item::ItemReader;
+use super::item::ItemWriter;
+use super::item::read_byte;
+use super::item::read_text;
+use super::item::read_uint;
+use super::item::write_bytes;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_FALSE: u8 = 20;
+const SIMPLE_TRUE: u8 = 21;
+const SIMPLE_NULL: u8 = 22;
+const SIMPLE_UNDEFINED: u8 = 23;
+const SIMPLE_FLOAT16: u8 = 25;
+const SIMPLE_FLOAT32: u8 = 26;
+const SIMPLE_FLOAT64: u8 = 27;
+
+/// Item writer of CBOR
+pub struct CborWriter<'a> {
+    output: &'a mut dyn Write
+}
+
+impl<'a> CborWriter<'a> {
+
+    pub fn new(output: &'a mut dyn Write) -> CborWriter<'a> {
+        CborWriter {
+            output: output
+        }
+    }
+
+    fn write_head(&mut self, major: u8, argument: u64) -> Result<(), Error> {
+
+        let major = major << 5;
+
+        if argument < 24 {
+            write_bytes(self.output, &[major | (argument as u8)])
+        } else if argument <= 0xff {
+            write_bytes(self.output, &[major | 24, argument as u8])
+        } else if argument <= 0xffff {
+            write_bytes(self.output, &[major | 25])?;
+            write_bytes(self.output, &(argument as u16).to_be_bytes())
+        } else if argument <= 0xffff_ffff {
+            write_bytes(self.output, &[major | 26])?;
+            write_bytes(self.output, &(argument as u32).to_be_bytes())
+        } else {
+            write_bytes(self.output, &[major | 27])?;
+            write_bytes(self.output, &argument.to_be_bytes())
+        }
+
+    }
+
+}
+
+impl<'a> ItemWriter for CborWriter<'a> {
+
+    fn write_item(&mut self, item: &Item) -> Result<(), Error> {
+
+        match item {
+            Item::Undefined => write_bytes(self.output, &[(MAJOR_SIMPLE << 5) | SIMPLE_UNDEFINED]),
+            Item::Null => write_bytes(self.output, &[(MAJOR_SIMPLE << 5) | SIMPLE_NULL]),
+            Item::Boolean(false) => write_bytes(self.output, &[(MAJOR_SIMPLE << 5) | SIMPLE_FALSE]),
+            Item::Boolean(true) => write_bytes(self.output, &[(MAJOR_SIMPLE << 5) | SIMPLE_TRUE]),
+            Item::Integer(integer) => {
+                if *integer >= 0 {
+                    self.write_head(MAJOR_UNSIGNED, *integer as u64)
+                } else {
+                    self.write_head(MAJOR_NEGATIVE, !(*integer) as u64)
+                }
+            },
+            Item::Float(float) => {
+                write_bytes(self.output, &[(MAJOR_SIMPLE << 5) | SIMPLE_FLOAT64])?;
+                write_bytes(self.output, &float.to_bits().to_be_bytes())
+            },
+            Item::Text(text) => {
+                self.write_head(MAJOR_TEXT, text.len() as u64)?;
+                write_bytes(self.output, text.as_bytes())
+            },
+            Item::Array(length) => self.write_head(MAJOR_ARRAY, *length as u64),
+            Item::Map(length) => self.write_head(MAJOR_MAP, *length as u64),
+            Item::Tag(tag) => self.write_head(MAJOR_TAG, *tag)
+        }
+
+    }
+
+}
+
+/// Item reader of CBOR, indefinite lengths and byte strings are not supported
+pub struct CborReader<'a> {
+    input: &'a mut dyn Read
+}
+
+impl<'a> CborReader<'a> {
+
+    pub fn new(input: &'a mut dyn Read) -> CborReader<'a> {
+        CborReader {
+            input: input
+        }
+    }
+
+    fn read_argument(&mut self, additional: u8) -> Result<u64, Error> {
+        match additional {
+            0 ..= 23 => Ok(additional as u64),
+            24 => read_uint(self.input, 1),
+            25 => read_uint(self.input, 2),
+            26 => read_uint(self.input, 4),
+            27 => read_uint(self.input, 8),
+            _ => Err(Error::new(MalformedData, "Unsupported CBOR argument"))
+        }
+    }
+
+}
+
+impl<'a> ItemReader for CborReader<'a> {
+
+    fn read_item(&mut self) -> Result<Item, Error> {
+
+        let initial = read_byte(self.input)?;
+        let major = initial >> 5;
+        let additional = initial & 0x1f;
+
+        match major {
+            MAJOR_UNSIGNED => {
+                let argument = self.read_argument(additional)?;
+                if argument > (i64::MAX as u64) {
+                    return Err(Error::new(IntegerOutOfRange, "CBOR integer out of range"));
+                }
+                Ok(Item::Integer(argument as i64))
+            },
+            MAJOR_NEGATIVE => {
+                let argument = self.read_argument(additional)?;
+                if argument > (i64::MAX as u64) {
+                    return Err(Error::new(IntegerOutOfRange, "CBOR integer out of range"));
+                }
+                Ok(Item::Integer(!(argument as i64)))
+            },
+            MAJOR_TEXT => {
+                let length = self.read_argument(additional)?;
+                Ok(Item::Text(read_text(self.input, length as usize)?))
+            },
+            MAJOR_ARRAY => Ok(Item::Array(self.read_argument(additional)? as usize)),
+            MAJOR_MAP => Ok(Item::Map(self.read_argument(additional)? as usize)),
+            MAJOR_TAG => Ok(Item::Tag(self.read_argument(additional)?)),
+            MAJOR_SIMPLE => {
+                match additional {
+                    SIMPLE_FALSE => Ok(Item::Boolean(false)),
+                    SIMPLE_TRUE => Ok(Item::Boolean(true)),
+                    SIMPLE_NULL => Ok(Item::Null),
+                    SIMPLE_UNDEFINED => Ok(Item::Undefined),
+                    SIMPLE_FLOAT16 => Ok(Item::Float(convert_float16(read_uint(self.input, 2)? as u16))),
+                    SIMPLE_FLOAT32 => Ok(Item::Float(f32::from_bits(read_uint(self.input, 4)? as u32) as f64)),
+                    SIMPLE_FLOAT64 => Ok(Item::Float(f64::from_bits(read_uint(self.input, 8)?))),
+                    _ => Err(Error::new(MalformedData, "Unsupported CBOR simple value"))
+                }
+            },
+            _ => Err(Error::new(MalformedData, "Unsupported CBOR major type"))
+        }
+
+    }
+
+}
+
+fn convert_float16(bits: u16) -> f64 {
+
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let fraction = (bits & 0x3ff) as f64;
+
+    match exponent {
+        0 => sign * fraction * 2f64.powi(-24),
+        31 => {
+            if fraction == 0.0 {
+                sign * f64::INFINITY
+            } else {
+                f64::NAN
+            }
+        },
+        _ => sign * (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15)
+    }
+
+}
+
+#[test]
+fn test_cbor_items() -> Result<(), Error> {
+
+    let items = vec!(
+        Item::Undefined, Item::Null, Item::Boolean(true), Item::Integer(0), Item::Integer(-1),
+        Item::Integer(500), Item::Integer(-70000), Item::Integer(i64::MIN), Item::Float(1.5),
+        Item::Text(String::from("foo")), Item::Array(30), Item::Map(2), Item::Tag(28)
+    );
+
+    let mut output = Vec::new();
+    {
+        let mut writer = CborWriter::new(&mut output);
+        for item in items.iter() {
+            writer.write_item(item)?;
+        }
+    }
+
+    assert_eq!(&output[.. 9], &[0xf7, 0xf6, 0xf5, 0x00, 0x20, 0x19, 0x01, 0xf4, 0x3a]);
+
+    let mut input = output.as_slice();
+    let mut reader = CborReader::new(&mut input);
+    for item in items.iter() {
+        assert_eq!(&reader.read_item()?, item);
+    }
+    assert!(reader.read_item().is_err());
+
+    let mut input: &[u8] = &[0xf9, 0x3e, 0x00];
+    assert_eq!(CborReader::new(&mut input).read_item()?, Item::Float(1.5));
+
+    Ok(())
+
+}
@@ -0,0 +1,555 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::any::Any;
+use super::super::util::HashMap;
+use alloc::sync::Arc;
+
+use super::item::Item;
+use super::item::ItemReader;
+use super::item::ItemWriter;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+use super::super::base::PrimitiveType;
+use super::super::base::Symbol;
+use super::super::base::Value;
+use super::super::context::Context;
+use super::super::context::RecursionGuard;
+use super::super::internal_slot::BigInt;
+use super::super::internal_slot::Foreign;
+use super::super::internal_slot::InternalSlot;
+use super::super::internal_slot::List;
+use super::super::internal_slot::Text;
+use super::super::internal_slot::Tuple;
+use super::super::isolate::Isolate;
+use super::super::storage::Pinned;
+
+/// Tag of a value which may be referenced later, RFC 8949 value sharing
+pub const TAG_SHAREABLE: u64 = 28;
+
+/// Tag of a reference to a shareable value by its index
+pub const TAG_SHARED_REFERENCE: u64 = 29;
+
+/// Tag of a builtin prototype by its index in the isolate builtins
+pub const TAG_BUILTIN: u64 = 0x726f_6700;
+
+/// Tag of a text symbol as array of scope and text
+pub const TAG_SYMBOL: u64 = 0x726f_6701;
+
+/// Tag of a big integer as decimal text
+pub const TAG_BIG_INTEGER: u64 = 0x726f_6702;
+
+/// Tag of a tuple as array of ID and elements
+pub const TAG_TUPLE: u64 = 0x726f_6703;
+
+/// Tag of an object as map from symbols to property values
+pub const TAG_OBJECT: u64 = 0x726f_6704;
+
+/// Tag of an object with transferred foreign internal slots, as array of
+/// properties map and pairs of internal slot ID and foreign index
+pub const TAG_OBJECT_WITH_FOREIGNS: u64 = 0x726f_6705;
+
+const MAX_GRAPH_DEPTH: usize = 512;
+
+/// Writer streaming a value graph as items
+///
+/// Lists, tuples and objects are written as shareable values, so values
+/// referenced more than once, including cycles, are written only once.
+/// Internal slots other than lists and tuples are not serialized, except
+/// transferable foreigns if foreign transfer enabled.
+pub struct GraphWriter<'a> {
+    isolate: &'a Isolate,
+    context: &'a Box<dyn Context>,
+    writer: &'a mut dyn ItemWriter,
+    builtins: Vec<Value>,
+    shareables: HashMap<Value, u64>,
+    pinneds: Vec<Pinned>,
+    foreigns: Option<Vec<Arc<dyn Any + Send + Sync>>>,
+    depth: usize
+}
+
+impl<'a> GraphWriter<'a> {
+
+    pub fn new(isolate: &'a Isolate, writer: &'a mut dyn ItemWriter, context: &'a Box<dyn Context>) -> GraphWriter<'a> {
+        GraphWriter {
+            isolate: isolate,
+            context: context,
+            writer: writer,
+            builtins: isolate.list_buitins(),
+            shareables: HashMap::new(),
+            pinneds: Vec::new(),
+            foreigns: None,
+            depth: 0
+        }
+    }
+
+    /// Collect host objects of transferable foreign internal slots
+    pub fn enable_foreign_transfer(&mut self) {
+        self.foreigns = Some(Vec::new());
+    }
+
+    /// Take host objects collected, referenced by index in the items
+    pub fn take_foreigns(&mut self) -> Vec<Arc<dyn Any + Send + Sync>> {
+        self.foreigns.take().unwrap_or_default()
+    }
+
+    pub fn write_value(&mut self, value: Value) -> Result<(), Error> {
+
+        self.context.check_interrupted()?;
+
+        let _guard = RecursionGuard::enter(self.context)?;
+
+        match value.get_primitive_type() {
+            PrimitiveType::Undefined => self.writer.write_item(&Item::Undefined),
+            PrimitiveType::Null => self.writer.write_item(&Item::Null),
+            PrimitiveType::Boolean => self.writer.write_item(&Item::Boolean(value.get_boolean_data()?)),
+            PrimitiveType::Integer => {
+                if value.is_cardinal() {
+                    self.writer.write_item(&Item::Integer(value.get_cardinal_data()? as i64))
+                } else {
+                    self.writer.write_item(&Item::Integer(value.get_integer_data()? as i64))
+                }
+            },
+            PrimitiveType::Float => self.writer.write_item(&Item::Float(value.get_float_data()?)),
+            PrimitiveType::Symbol => self.write_symbol(value.extract_symbol(Symbol::new(0))),
+            PrimitiveType::Text => {
+                let text = self.isolate.extract_text_slot(value, self.context)?;
+                self.writer.write_item(&Item::Text(text.to_string()))
+            },
+            PrimitiveType::BigInteger => {
+                let big_int = self.isolate.extract_big_integer(value, self.context)?;
+                self.writer.write_item(&Item::Tag(TAG_BIG_INTEGER))?;
+                self.writer.write_item(&Item::Text(big_int.to_string()))
+            },
+            PrimitiveType::List | PrimitiveType::Tuple | PrimitiveType::Object => self.write_shareable(value)
+        }
+
+    }
+
+    fn write_symbol(&mut self, symbol: Symbol) -> Result<(), Error> {
+
+        let symbol_info = self.isolate.resolve_symbol_info(symbol)?;
+
+        let text = match symbol_info.get_text() {
+            Some(text) => text.as_ref().clone(),
+            None => { return Err(Error::new(TypeNotMatch, "Value symbol could not be serialized")); }
+        };
+
+        self.writer.write_item(&Item::Tag(TAG_SYMBOL))?;
+        self.writer.write_item(&Item::Array(2))?;
+        self.writer.write_item(&Item::Text(symbol_info.get_symbol_scope().as_ref().clone()))?;
+        self.writer.write_item(&Item::Text(text))
+
+    }
+
+    fn write_shareable(&mut self, value: Value) -> Result<(), Error> {
+
+        if let Some(index) = self.builtins.iter().position(|builtin| *builtin == value) {
+            self.writer.write_item(&Item::Tag(TAG_BUILTIN))?;
+            return self.writer.write_item(&Item::Integer(index as i64));
+        }
+
+        if let Some(index) = self.shareables.get(&value) {
+            let index = *index;
+            self.writer.write_item(&Item::Tag(TAG_SHARED_REFERENCE))?;
+            return self.writer.write_item(&Item::Integer(index as i64));
+        }
+
+        self.depth += 1;
+        if self.depth > MAX_GRAPH_DEPTH {
+            return Err(Error::new(FatalError, "Value graph too deep to serialize"));
+        }
+
+        let index = self.shareables.len() as u64;
+        self.shareables.insert(value, index);
+        self.pinneds.push(Pinned::new(self.context, value)?);
+
+        self.writer.write_item(&Item::Tag(TAG_SHAREABLE))?;
+
+        match value.get_primitive_type() {
+            PrimitiveType::List => {
+                let elements = self.pin_values(self.isolate.extract_list(value, self.context)?)?;
+                self.write_elements(&elements)?;
+            },
+            PrimitiveType::Tuple => {
+                let (id, elements) = match self.isolate.get_internal_slot(value, 0, self.context)? {
+                    Some(internal_slot) => {
+                        match internal_slot.as_any().downcast_ref::<Tuple>() {
+                            Some(tuple) => (tuple.get_id(), tuple.get_value_list()),
+                            None => { return Err(Error::new(TypeNotMatch, "Value is not tuple")); }
+                        }
+                    },
+                    None => { return Err(Error::new(TypeNotMatch, "Value is not tuple")); }
+                };
+                let elements = self.pin_values(elements)?;
+                self.writer.write_item(&Item::Tag(TAG_TUPLE))?;
+                self.writer.write_item(&Item::Array(2))?;
+                self.writer.write_item(&Item::Integer(id as i64))?;
+                self.write_elements(&elements)?;
+            },
+            _ => {
+                let mut properties = Vec::new();
+                for symbol in self.isolate.list_own_property_symbols(value, value, self.context)? {
+                    let property = self.isolate.get_own_property(value, value, symbol, None, self.context)?;
+                    properties.push((symbol, property));
+                }
+                let foreigns = self.list_transferable_foreigns(value)?;
+                if foreigns.is_empty() {
+                    self.writer.write_item(&Item::Tag(TAG_OBJECT))?;
+                } else {
+                    self.writer.write_item(&Item::Tag(TAG_OBJECT_WITH_FOREIGNS))?;
+                    self.writer.write_item(&Item::Array(2))?;
+                }
+                self.writer.write_item(&Item::Map(properties.len()))?;
+                for (symbol, property) in properties.iter() {
+                    self.write_symbol(*symbol)?;
+                    self.write_value(property.get_value())?;
+                }
+                if !foreigns.is_empty() {
+                    self.writer.write_item(&Item::Array(foreigns.len()))?;
+                    for (id, foreign_index) in foreigns {
+                        self.writer.write_item(&Item::Array(2))?;
+                        self.writer.write_item(&Item::Integer(id as i64))?;
+                        self.writer.write_item(&Item::Integer(foreign_index as i64))?;
+                    }
+                }
+            }
+        }
+
+        self.depth -= 1;
+
+        Ok(())
+
+    }
+
+    fn list_transferable_foreigns(&mut self, value: Value) -> Result<Vec<(u64, usize)>, Error> {
+
+        let foreigns = match self.foreigns.as_mut() {
+            Some(foreigns) => foreigns,
+            None => { return Ok(Vec::new()); }
+        };
+
+        let mut result = Vec::new();
+        for id in self.isolate.list_internal_slot_ids(value, self.context)? {
+            if id > (i64::MAX as u64) {
+                continue;
+            }
+            if let Some(internal_slot) = self.isolate.get_internal_slot(value, id, self.context)? {
+                if let Some(foreign) = internal_slot.as_any().downcast_ref::<Foreign>() {
+                    if foreign.is_transferable() {
+                        result.push((id, foreigns.len()));
+                        foreigns.push(foreign.get_data());
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+
+    }
+
+    fn pin_values(&self, values: Vec<Value>) -> Result<Vec<Pinned>, Error> {
+        values.into_iter().map(|value| Pinned::new(self.context, value)).collect()
+    }
+
+    fn write_elements(&mut self, elements: &[Pinned]) -> Result<(), Error> {
+
+        self.writer.write_item(&Item::Array(elements.len()))?;
+        for element in elements.iter() {
+            self.write_value(element.get_value())?;
+        }
+
+        Ok(())
+
+    }
+
+}
+
+/// Reader building a value graph in a region from items
+pub struct GraphReader<'a> {
+    isolate: &'a Isolate,
+    region_id: u32,
+    context: &'a Box<dyn Context>,
+    reader: &'a mut dyn ItemReader,
+    builtins: Vec<Value>,
+    shareables: Vec<Pinned>,
+    foreigns: Vec<Arc<dyn Any + Send + Sync>>,
+    depth: usize
+}
+
+impl<'a> GraphReader<'a> {
+
+    pub fn new(isolate: &'a Isolate, region_id: u32, reader: &'a mut dyn ItemReader, context: &'a Box<dyn Context>) -> GraphReader<'a> {
+        GraphReader {
+            isolate: isolate,
+            region_id: region_id,
+            context: context,
+            reader: reader,
+            builtins: isolate.list_buitins(),
+            shareables: Vec::new(),
+            foreigns: Vec::new(),
+            depth: 0
+        }
+    }
+
+    /// Set host objects referenced by transferred foreign internal slots
+    pub fn set_foreigns(&mut self, foreigns: Vec<Arc<dyn Any + Send + Sync>>) {
+        self.foreigns = foreigns;
+    }
+
+    pub fn read_value(&mut self) -> Result<Pinned, Error> {
+
+        self.context.check_interrupted()?;
+
+        let _guard = RecursionGuard::enter(self.context)?;
+
+        let item = self.reader.read_item()?;
+
+        self.depth += 1;
+        if self.depth > MAX_GRAPH_DEPTH {
+            return Err(Error::new(MalformedData, "Serialized value graph too deep"));
+        }
+
+        let value = match item {
+            Item::Undefined => Pinned::new(self.context, Value::make_undefined()),
+            Item::Null => Pinned::new(self.context, Value::make_null()),
+            Item::Boolean(boolean) => Pinned::new(self.context, Value::make_boolean(boolean)),
+            Item::Integer(integer) => self.make_integer(integer),
+            Item::Float(float) => Pinned::new(self.context, Value::make_float(float)),
+            Item::Text(text) => self.make_text(&text),
+            Item::Array(length) => {
+                let list = self.gain_slot(PrimitiveType::List, self.isolate.get_list_prototype())?;
+                self.read_list(list, length)
+            },
+            Item::Map(_) => Err(Error::new(MalformedData, "Unexpected map without object tag")),
+            Item::Tag(tag) => self.read_tagged(tag)
+        };
+
+        self.depth -= 1;
+
+        value
+
+    }
+
+    fn read_tagged(&mut self, tag: u64) -> Result<Pinned, Error> {
+
+        match tag {
+            TAG_SHAREABLE => {
+                let index = self.shareables.len();
+                match self.reader.read_item()? {
+                    Item::Array(length) => {
+                        let list = self.gain_slot(PrimitiveType::List, self.isolate.get_list_prototype())?;
+                        self.shareables.push(Pinned::new(self.context, list.get_value())?);
+                        self.read_list(list, length)
+                    },
+                    Item::Tag(TAG_TUPLE) => {
+                        let tuple = self.gain_slot(PrimitiveType::Tuple, self.isolate.get_tuple_prototype())?;
+                        self.shareables.push(Pinned::new(self.context, tuple.get_value())?);
+                        self.read_tuple(tuple)
+                    },
+                    Item::Tag(TAG_OBJECT) => {
+                        let object = self.gain_slot(PrimitiveType::Object, Value::make_null())?;
+                        self.shareables.push(Pinned::new(self.context, object.get_value())?);
+                        self.read_object(object)
+                    },
+                    Item::Tag(TAG_OBJECT_WITH_FOREIGNS) => {
+                        let object = self.gain_slot(PrimitiveType::Object, Value::make_null())?;
+                        self.shareables.push(Pinned::new(self.context, object.get_value())?);
+                        self.read_object_with_foreigns(object)
+                    },
+                    _ => Err(Error::new(MalformedData, &format!("Unsupported shareable value at {}", index)))
+                }
+            },
+            TAG_SHARED_REFERENCE => {
+                let index = self.read_index()?;
+                match self.shareables.get(index) {
+                    Some(shareable) => Pinned::new(self.context, shareable.get_value()),
+                    None => Err(Error::new(MalformedData, "Shared reference not found"))
+                }
+            },
+            TAG_BUILTIN => {
+                let index = self.read_index()?;
+                match self.builtins.get(index) {
+                    Some(builtin) => Pinned::new(self.context, *builtin),
+                    None => Err(Error::new(MalformedData, "Builtin not found"))
+                }
+            },
+            TAG_SYMBOL => {
+                let symbol = self.read_symbol()?;
+                Pinned::for_symbol(self.context, symbol)
+            },
+            TAG_BIG_INTEGER => {
+                match self.reader.read_item()? {
+                    Item::Text(text) => {
+                        let value = self.isolate.create_big_integer(self.region_id, BigInt::parse(&text)?, self.context)?;
+                        Pinned::new(self.context, value)
+                    },
+                    _ => Err(Error::new(MalformedData, "Big integer should be text"))
+                }
+            },
+            TAG_TUPLE => {
+                let tuple = self.gain_slot(PrimitiveType::Tuple, self.isolate.get_tuple_prototype())?;
+                self.read_tuple(tuple)
+            },
+            TAG_OBJECT => {
+                let object = self.gain_slot(PrimitiveType::Object, Value::make_null())?;
+                self.read_object(object)
+            },
+            TAG_OBJECT_WITH_FOREIGNS => {
+                let object = self.gain_slot(PrimitiveType::Object, Value::make_null())?;
+                self.read_object_with_foreigns(object)
+            },
+            _ => Err(Error::new(MalformedData, "Unsupported tag"))
+        }
+
+    }
+
+    fn read_index(&mut self) -> Result<usize, Error> {
+        match self.reader.read_item()? {
+            Item::Integer(index) if index >= 0 => Ok(index as usize),
+            _ => Err(Error::new(MalformedData, "Index should be integer"))
+        }
+    }
+
+    fn read_symbol(&mut self) -> Result<Symbol, Error> {
+
+        if self.reader.read_item()? != Item::Array(2) {
+            return Err(Error::new(MalformedData, "Symbol should be array of scope and text"));
+        }
+
+        match (self.reader.read_item()?, self.reader.read_item()?) {
+            (Item::Text(scope), Item::Text(text)) => Ok(self.isolate.get_text_symbol(&scope, &text)),
+            _ => Err(Error::new(MalformedData, "Symbol should be array of scope and text"))
+        }
+
+    }
+
+    fn gain_slot(&self, primitive_type: PrimitiveType, prototype: Value) -> Result<Pinned, Error> {
+        let value = self.isolate.gain_slot(self.region_id, primitive_type, prototype, self.context.get_slot_layout_token())?;
+        Pinned::new(self.context, value)
+    }
+
+    fn make_integer(&self, integer: i64) -> Result<Pinned, Error> {
+
+        if (i32::MIN as i64 ..= i32::MAX as i64).contains(&integer) {
+            return Pinned::new(self.context, Value::make_integer(integer as i32));
+        }
+
+        if (0 ..= u32::MAX as i64).contains(&integer) {
+            return Pinned::new(self.context, Value::make_cardinal(integer as u32));
+        }
+
+        let value = self.isolate.create_big_integer(self.region_id, BigInt::from_i64(integer), self.context)?;
+        Pinned::new(self.context, value)
+
+    }
+
+    fn make_text(&self, string: &str) -> Result<Pinned, Error> {
+
+        let value = self.gain_slot(PrimitiveType::Text, self.isolate.get_text_prototype())?;
+
+        let text: Arc<dyn InternalSlot> = Arc::new(Text::new(string));
+
+        self.isolate.set_internal_slot(value.get_value(), 0, text, self.context)?;
+
+        Ok(value)
+
+    }
+
+    fn read_elements(&mut self, length: usize) -> Result<Vec<Pinned>, Error> {
+
+        let mut elements = Vec::new();
+        for _ in 0 .. length {
+            elements.push(self.read_value()?);
+        }
+
+        Ok(elements)
+
+    }
+
+    fn read_list(&mut self, list: Pinned, length: usize) -> Result<Pinned, Error> {
+
+        let elements = self.read_elements(length)?;
+
+        let internal_slot: Arc<dyn InternalSlot> = Arc::new(List::new(list.get_value(), elements.iter().map(|element| element.get_value()).collect()));
+
+        self.isolate.set_internal_slot(list.get_value(), 0, internal_slot, self.context)?;
+
+        Ok(list)
+
+    }
+
+    fn read_tuple(&mut self, tuple: Pinned) -> Result<Pinned, Error> {
+
+        let id = match (self.reader.read_item()?, self.reader.read_item()?) {
+            (Item::Array(2), Item::Integer(id)) if (0 ..= u32::MAX as i64).contains(&id) => id as u32,
+            _ => { return Err(Error::new(MalformedData, "Tuple should be array of ID and elements")); }
+        };
+
+        let elements = match self.reader.read_item()? {
+            Item::Array(length) => self.read_elements(length)?,
+            _ => { return Err(Error::new(MalformedData, "Tuple should be array of ID and elements")); }
+        };
+
+        let internal_slot: Arc<dyn InternalSlot> = Arc::new(Tuple::new(tuple.get_value(), id, elements.iter().map(|element| element.get_value()).collect()));
+
+        self.isolate.set_internal_slot(tuple.get_value(), 0, internal_slot, self.context)?;
+
+        Ok(tuple)
+
+    }
+
+    fn read_object(&mut self, object: Pinned) -> Result<Pinned, Error> {
+
+        let length = match self.reader.read_item()? {
+            Item::Map(length) => length,
+            _ => { return Err(Error::new(MalformedData, "Object should be map of properties")); }
+        };
+
+        for _ in 0 .. length {
+            if self.reader.read_item()? != Item::Tag(TAG_SYMBOL) {
+                return Err(Error::new(MalformedData, "Property key should be symbol"));
+            }
+            let symbol = self.read_symbol()?;
+            let value = self.read_value()?;
+            let subject = object.get_value();
+            self.isolate.set_own_property(subject, subject, symbol, value.get_value(), self.context)?;
+        }
+
+        Ok(object)
+
+    }
+
+    fn read_object_with_foreigns(&mut self, object: Pinned) -> Result<Pinned, Error> {
+
+        if self.reader.read_item()? != Item::Array(2) {
+            return Err(Error::new(MalformedData, "Object should be array of properties and foreigns"));
+        }
+
+        let object = self.read_object(object)?;
+
+        let length = match self.reader.read_item()? {
+            Item::Array(length) => length,
+            _ => { return Err(Error::new(MalformedData, "Foreigns should be array")); }
+        };
+
+        for _ in 0 .. length {
+            let (id, index) = match (self.reader.read_item()?, self.reader.read_item()?, self.reader.read_item()?) {
+                (Item::Array(2), Item::Integer(id), Item::Integer(index)) if id >= 0 && index >= 0 => (id as u64, index as usize),
+                _ => { return Err(Error::new(MalformedData, "Foreign should be pair of ID and index")); }
+            };
+            let data = match self.foreigns.get(index) {
+                Some(data) => data.clone(),
+                None => { return Err(Error::new(MalformedData, "Foreign not found")); }
+            };
+            let foreign: Arc<dyn InternalSlot> = Arc::new(Foreign::new_transferable(data));
+            self.isolate.set_internal_slot(object.get_value(), id, foreign, self.context)?;
+        }
+
+        Ok(object)
+
+    }
+
+}
@@ -0,0 +1,89 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::io::Read;
+use std::io::Write;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+
+/// Binary format of serialized value graphs
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SerializationFormat {
+
+    /// Concise binary object representation, RFC 8949
+    Cbor,
+
+    /// MessagePack
+    MessagePack
+
+}
+
+/// Data item shared by all binary formats
+///
+/// Containers and tags are written as headers, their contents follow as
+/// separated items.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Item {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Array(usize),
+    Map(usize),
+    Tag(u64)
+}
+
+/// Writer encoding items into a binary format
+pub trait ItemWriter {
+
+    fn write_item(&mut self, item: &Item) -> Result<(), Error>;
+
+}
+
+/// Reader decoding items from a binary format
+pub trait ItemReader {
+
+    fn read_item(&mut self) -> Result<Item, Error>;
+
+}
+
+pub fn write_bytes(output: &mut dyn Write, bytes: &[u8]) -> Result<(), Error> {
+    match output.write_all(bytes) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(Error::new(FatalError, "Failed to write serialized data"))
+    }
+}
+
+pub fn read_bytes(input: &mut dyn Read, length: usize) -> Result<Vec<u8>, Error> {
+
+    let mut bytes = Vec::new();
+    match input.take(length as u64).read_to_end(&mut bytes) {
+        Ok(read) if read == length => Ok(bytes),
+        _ => Err(Error::new(MalformedData, "Unexpected end of serialized data"))
+    }
+
+}
+
+pub fn read_byte(input: &mut dyn Read) -> Result<u8, Error> {
+    Ok(read_bytes(input, 1)?[0])
+}
+
+pub fn read_uint(input: &mut dyn Read, size: usize) -> Result<u64, Error> {
+
+    let mut result = 0u64;
+    for byte in read_bytes(input, size)? {
+        result = (result << 8) | (byte as u64);
+    }
+
+    Ok(result)
+
+}
+
+pub fn read_text(input: &mut dyn Read, length: usize) -> Result<String, Error> {
+    match String::from_utf8(read_bytes(input, length)?) {
+        Ok(text) => Ok(text),
+        Err(_) => Err(Error::new(MalformedData, "Invalid UTF-8 in serialized text"))
+    }
+}
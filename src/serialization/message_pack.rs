@@ -0,0 +1,221 @@
+use std::io::Read;
+use std::io::Write;
+
+use super::item::Item;
+use super::item::ItemReader;
+use super::item::ItemWriter;
+use super::item::read_byte;
+use super::item::read_bytes;
+use super::item::read_text;
+use super::item::read_uint;
+use super::item::write_bytes;
+
+use super::super::base::Error;
+use super::super::base::ErrorType::*;
+
+/// Extension type of undefined, with 1-byte zero payload
+const EXTENSION_UNDEFINED: i8 = 0;
+
+/// Extension type of tags, with 8-byte tag number payload, the tagged item
+/// follows the extension as the next item
+const EXTENSION_TAG: i8 = 1;
+
+/// Item writer of MessagePack
+pub struct MessagePackWriter<'a> {
+    output: &'a mut dyn Write
+}
+
+impl<'a> MessagePackWriter<'a> {
+
+    pub fn new(output: &'a mut dyn Write) -> MessagePackWriter<'a> {
+        MessagePackWriter {
+            output: output
+        }
+    }
+
+    fn write_length(&mut self, length: usize, fixed: u8, fixed_limit: usize, marker_16: u8, marker_32: u8) -> Result<(), Error> {
+
+        if length < fixed_limit {
+            write_bytes(self.output, &[fixed | (length as u8)])
+        } else if length <= 0xffff {
+            write_bytes(self.output, &[marker_16])?;
+            write_bytes(self.output, &(length as u16).to_be_bytes())
+        } else if length <= 0xffff_ffff {
+            write_bytes(self.output, &[marker_32])?;
+            write_bytes(self.output, &(length as u32).to_be_bytes())
+        } else {
+            Err(Error::new(IntegerOutOfRange, "Length too large for MessagePack"))
+        }
+
+    }
+
+}
+
+impl<'a> ItemWriter for MessagePackWriter<'a> {
+
+    fn write_item(&mut self, item: &Item) -> Result<(), Error> {
+
+        match item {
+            Item::Undefined => write_bytes(self.output, &[0xd4, EXTENSION_UNDEFINED as u8, 0]),
+            Item::Null => write_bytes(self.output, &[0xc0]),
+            Item::Boolean(false) => write_bytes(self.output, &[0xc2]),
+            Item::Boolean(true) => write_bytes(self.output, &[0xc3]),
+            Item::Integer(integer) => {
+                let integer = *integer;
+                if (0 ..= 0x7f).contains(&integer) || (-32 .. 0).contains(&integer) {
+                    write_bytes(self.output, &[integer as u8])
+                } else if (0 ..= 0xffff_ffff).contains(&integer) {
+                    write_bytes(self.output, &[0xce])?;
+                    write_bytes(self.output, &(integer as u32).to_be_bytes())
+                } else if (i32::MIN as i64 .. 0).contains(&integer) {
+                    write_bytes(self.output, &[0xd2])?;
+                    write_bytes(self.output, &(integer as i32).to_be_bytes())
+                } else {
+                    write_bytes(self.output, &[0xd3])?;
+                    write_bytes(self.output, &integer.to_be_bytes())
+                }
+            },
+            Item::Float(float) => {
+                write_bytes(self.output, &[0xcb])?;
+                write_bytes(self.output, &float.to_bits().to_be_bytes())
+            },
+            Item::Text(text) => {
+                if text.len() <= 0xff && text.len() >= 32 {
+                    write_bytes(self.output, &[0xd9, text.len() as u8])?;
+                } else {
+                    self.write_length(text.len(), 0xa0, 32, 0xda, 0xdb)?;
+                }
+                write_bytes(self.output, text.as_bytes())
+            },
+            Item::Array(length) => self.write_length(*length, 0x90, 16, 0xdc, 0xdd),
+            Item::Map(length) => self.write_length(*length, 0x80, 16, 0xde, 0xdf),
+            Item::Tag(tag) => {
+                write_bytes(self.output, &[0xd7, EXTENSION_TAG as u8])?;
+                write_bytes(self.output, &tag.to_be_bytes())
+            }
+        }
+
+    }
+
+}
+
+/// Item reader of MessagePack, binaries and unknown extensions are not
+/// supported
+pub struct MessagePackReader<'a> {
+    input: &'a mut dyn Read
+}
+
+impl<'a> MessagePackReader<'a> {
+
+    pub fn new(input: &'a mut dyn Read) -> MessagePackReader<'a> {
+        MessagePackReader {
+            input: input
+        }
+    }
+
+    fn read_extension(&mut self, length: usize) -> Result<Item, Error> {
+
+        let extension_type = read_byte(self.input)? as i8;
+
+        match (extension_type, length) {
+            (EXTENSION_UNDEFINED, 1) => {
+                read_bytes(self.input, 1)?;
+                Ok(Item::Undefined)
+            },
+            (EXTENSION_TAG, 1) | (EXTENSION_TAG, 2) | (EXTENSION_TAG, 4) | (EXTENSION_TAG, 8) => {
+                Ok(Item::Tag(read_uint(self.input, length)?))
+            },
+            _ => Err(Error::new(MalformedData, "Unsupported MessagePack extension"))
+        }
+
+    }
+
+}
+
+impl<'a> ItemReader for MessagePackReader<'a> {
+
+    fn read_item(&mut self) -> Result<Item, Error> {
+
+        let marker = read_byte(self.input)?;
+
+        match marker {
+            0x00 ..= 0x7f => Ok(Item::Integer(marker as i64)),
+            0x80 ..= 0x8f => Ok(Item::Map((marker & 0x0f) as usize)),
+            0x90 ..= 0x9f => Ok(Item::Array((marker & 0x0f) as usize)),
+            0xa0 ..= 0xbf => Ok(Item::Text(read_text(self.input, (marker & 0x1f) as usize)?)),
+            0xc0 => Ok(Item::Null),
+            0xc2 => Ok(Item::Boolean(false)),
+            0xc3 => Ok(Item::Boolean(true)),
+            0xca => Ok(Item::Float(f32::from_bits(read_uint(self.input, 4)? as u32) as f64)),
+            0xcb => Ok(Item::Float(f64::from_bits(read_uint(self.input, 8)?))),
+            0xcc => Ok(Item::Integer(read_uint(self.input, 1)? as i64)),
+            0xcd => Ok(Item::Integer(read_uint(self.input, 2)? as i64)),
+            0xce => Ok(Item::Integer(read_uint(self.input, 4)? as i64)),
+            0xcf => {
+                let integer = read_uint(self.input, 8)?;
+                if integer > (i64::MAX as u64) {
+                    return Err(Error::new(IntegerOutOfRange, "MessagePack integer out of range"));
+                }
+                Ok(Item::Integer(integer as i64))
+            },
+            0xd0 => Ok(Item::Integer(read_uint(self.input, 1)? as u8 as i8 as i64)),
+            0xd1 => Ok(Item::Integer(read_uint(self.input, 2)? as u16 as i16 as i64)),
+            0xd2 => Ok(Item::Integer(read_uint(self.input, 4)? as u32 as i32 as i64)),
+            0xd3 => Ok(Item::Integer(read_uint(self.input, 8)? as i64)),
+            0xd4 => self.read_extension(1),
+            0xd5 => self.read_extension(2),
+            0xd6 => self.read_extension(4),
+            0xd7 => self.read_extension(8),
+            0xd9 => {
+                let length = read_uint(self.input, 1)? as usize;
+                Ok(Item::Text(read_text(self.input, length)?))
+            },
+            0xda => {
+                let length = read_uint(self.input, 2)? as usize;
+                Ok(Item::Text(read_text(self.input, length)?))
+            },
+            0xdb => {
+                let length = read_uint(self.input, 4)? as usize;
+                Ok(Item::Text(read_text(self.input, length)?))
+            },
+            0xdc => Ok(Item::Array(read_uint(self.input, 2)? as usize)),
+            0xdd => Ok(Item::Array(read_uint(self.input, 4)? as usize)),
+            0xde => Ok(Item::Map(read_uint(self.input, 2)? as usize)),
+            0xdf => Ok(Item::Map(read_uint(self.input, 4)? as usize)),
+            0xe0 ..= 0xff => Ok(Item::Integer(marker as i8 as i64)),
+            _ => Err(Error::new(MalformedData, "Unsupported MessagePack format"))
+        }
+
+    }
+
+}
+
+#[test]
+fn test_message_pack_items() -> Result<(), Error> {
+
+    let items = vec!(
+        Item::Undefined, Item::Null, Item::Boolean(false), Item::Integer(5), Item::Integer(-5),
+        Item::Integer(300), Item::Integer(-300), Item::Integer(i64::MAX), Item::Float(-2.25),
+        Item::Text(String::from("bar")), Item::Text("x".repeat(40)), Item::Array(3), Item::Map(20), Item::Tag(29)
+    );
+
+    let mut output = Vec::new();
+    {
+        let mut writer = MessagePackWriter::new(&mut output);
+        for item in items.iter() {
+            writer.write_item(item)?;
+        }
+    }
+
+    assert_eq!(&output[.. 8], &[0xd4, 0x00, 0x00, 0xc0, 0xc2, 0x05, 0xfb, 0xce]);
+
+    let mut input = output.as_slice();
+    let mut reader = MessagePackReader::new(&mut input);
+    for item in items.iter() {
+        assert_eq!(&reader.read_item()?, item);
+    }
+    assert!(reader.read_item().is_err());
+
+    Ok(())
+
+}
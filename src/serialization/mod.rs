@@ -0,0 +1,14 @@
+mod cbor;
+mod graph;
+mod item;
+mod message_pack;
+
+pub use cbor::CborReader;
+pub use cbor::CborWriter;
+pub use graph::GraphReader;
+pub use graph::GraphWriter;
+pub use item::ItemReader;
+pub use item::ItemWriter;
+pub use item::SerializationFormat;
+pub use message_pack::MessagePackReader;
+pub use message_pack::MessagePackWriter;
@@ -0,0 +1,45 @@
+use alloc::sync::Arc;
+
+use super::base::Symbol;
+use super::base::SymbolRegistry;
+use super::region::Region;
+
+/// Region sealed read-only to be attached to multiple isolates
+///
+/// Slotted values within the region only reference values in the same
+/// region or builtin prototypes, and properties of the values are resolved
+/// by the symbol registry the region shared with
+pub struct SharedRegion {
+    region: Arc<Region>,
+    prototype_symbol: Symbol,
+    symbol_registry: Option<Arc<SymbolRegistry>>
+}
+
+impl SharedRegion {
+
+    pub fn new(region: Arc<Region>, prototype_symbol: Symbol, symbol_registry: Option<Arc<SymbolRegistry>>) -> SharedRegion {
+        SharedRegion {
+            region: region,
+            prototype_symbol: prototype_symbol,
+            symbol_registry: symbol_registry
+        }
+    }
+
+    /// Get the region ID, which is the same in all isolates attached
+    pub fn get_region_id(&self) -> u32 {
+        self.region.get_id()
+    }
+
+    pub fn get_region(&self) -> &Arc<Region> {
+        &self.region
+    }
+
+    pub fn get_prototype_symbol(&self) -> Symbol {
+        self.prototype_symbol
+    }
+
+    pub fn get_symbol_registry(&self) -> Option<&Arc<SymbolRegistry>> {
+        self.symbol_registry.as_ref()
+    }
+
+}
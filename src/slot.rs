@@ -1,7 +1,17 @@
-use std::collections::HashMap;
-use std::collections::hash_map::Keys;
-use std::cell::RefCell;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use super::util::HashMap;
+use super::util::hash_map::Keys;
+use core::cell::RefCell;
+use core::cell::RefMut;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ops::DerefMut;
+use core::ptr;
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::Ordering;
+use core::sync::atomic::fence;
+use alloc::sync::Arc;
 
 use super::base::Error;
 use super::base::ErrorType::*;
@@ -9,20 +19,27 @@ use super::base::PrimitiveType;
 use super::base::PrimitiveType::*;
 use super::base::Symbol;
 use super::base::Value;
+use super::cold_region::ColdRegionReader;
+use super::cold_region::ColdRegionWriter;
 use super::context::Context;
 use super::field_shortcuts::FieldShortcuts;
 use super::field_shortcuts::FieldToken;
 use super::internal_slot::InternalSlot;
 use super::internal_slot::ProtectedInternalSlot;
 use super::reference_map::ReferenceMap;
+use super::reference_map::ReferenceMapStats;
 use super::storage::Pinned;
 use super::trap::PropertyTrap;
 use super::trap::ProtectedPropertyTrap;
+use super::trap::ConstPropertyTrap;
 use super::trap::FieldPropertyTrap;
 use super::trap::SlotTrap;
 use super::trap::SlotTrapResult::*;
 use super::trap::ProtectedSlotTrap;
+use super::util::LockClass;
 use super::util::RwLock;
+use super::util::SmallMap;
+use super::util::small_map;
 use super::util::ReentrantLockReadGuard;
 
 const LIVE_FLAG: u32 = 0b1;
@@ -53,7 +70,7 @@ impl<'a> Iterator for InternalSlotIterator<'a> {
 }
 
 struct OwnPropertySymbolIterator<'a> {
-    keys: Keys<'a, Symbol, Arc<dyn PropertyTrap>>
+    keys: small_map::Keys<'a, Symbol, Arc<dyn PropertyTrap>>
 }
 
 impl<'a> Iterator for OwnPropertySymbolIterator<'a> {
@@ -92,6 +109,12 @@ impl AtomicSlotOptimizationData {
         }
     }
 
+    fn duplicate(&self) -> AtomicSlotOptimizationData {
+        AtomicSlotOptimizationData {
+            u128_data: unsafe { self.u128_data }
+        }
+    }
+
     #[allow(unused_unsafe)]
     fn reset(&mut self) {
         unsafe {
@@ -232,7 +255,7 @@ struct AtomicSlot {
 
     slot_trap: Option<Arc<dyn SlotTrap>>,
 
-    own_property_traps: HashMap<Symbol, Arc<dyn PropertyTrap>>,
+    own_property_traps: SmallMap<Symbol, Arc<dyn PropertyTrap>>,
 
     field_shortcuts: Option<Arc<FieldShortcuts>>,
 
@@ -247,7 +270,6 @@ struct AtomicSlot {
 
 }
 
-// TODO: add direct prototype support
 // TODO: add optimization supports
 
 /// Slot managements
@@ -260,7 +282,7 @@ impl AtomicSlot {
             primitive_type: Undefined,
             prototype: Value::make_undefined(),
             slot_trap: None,
-            own_property_traps: HashMap::new(),
+            own_property_traps: SmallMap::new(),
             field_shortcuts: None,
             internal_slots: None,
             optimization_flags: 0,
@@ -270,6 +292,70 @@ impl AtomicSlot {
 
     }
 
+    /// Duplicate the slot for a forked isolate, traps and internal slots with
+    /// states are duplicated too
+    pub fn duplicate(&self) -> AtomicSlot {
+
+        let mut own_property_traps = SmallMap::new();
+        for (symbol, property_trap) in self.own_property_traps.iter() {
+            let property_trap = match property_trap.duplicate() {
+                Some(property_trap) => property_trap,
+                None => property_trap.clone()
+            };
+            own_property_traps.insert(*symbol, property_trap);
+        }
+
+        let internal_slots = self.internal_slots.as_ref().map(|internal_slots| {
+            let mut new_internal_slots = HashMap::new();
+            for (id, internal_slot) in internal_slots.iter() {
+                let internal_slot = match internal_slot.duplicate() {
+                    Some(internal_slot) => internal_slot,
+                    None => internal_slot.clone()
+                };
+                new_internal_slots.insert(*id, internal_slot);
+            }
+            Box::new(new_internal_slots)
+        });
+
+        AtomicSlot {
+            flags: self.flags,
+            primitive_type: self.primitive_type,
+            prototype: self.prototype,
+            slot_trap: self.slot_trap.clone(),
+            own_property_traps: own_property_traps,
+            field_shortcuts: self.field_shortcuts.as_ref().map(|field_shortcuts| Arc::new(field_shortcuts.duplicate())),
+            internal_slots: internal_slots,
+            optimization_flags: self.optimization_flags,
+            optimization_type: self.optimization_type,
+            optimization_data: self.optimization_data.duplicate()
+        }
+
+    }
+
+    /// Copy the slot to move it, traps and internal slots are shared with
+    /// the copy instead of duplicated
+    pub fn copy(&self) -> AtomicSlot {
+
+        let mut own_property_traps = SmallMap::new();
+        for (symbol, property_trap) in self.own_property_traps.iter() {
+            own_property_traps.insert(*symbol, property_trap.clone());
+        }
+
+        AtomicSlot {
+            flags: self.flags,
+            primitive_type: self.primitive_type,
+            prototype: self.prototype,
+            slot_trap: self.slot_trap.clone(),
+            own_property_traps: own_property_traps,
+            field_shortcuts: self.field_shortcuts.clone(),
+            internal_slots: self.internal_slots.clone(),
+            optimization_flags: self.optimization_flags,
+            optimization_type: self.optimization_type,
+            optimization_data: self.optimization_data.duplicate()
+        }
+
+    }
+
     pub fn reset(&mut self) -> (Vec<Value>, Vec<Symbol>) {
 
         self.optimization_flags = 0;
@@ -279,7 +365,7 @@ impl AtomicSlot {
 
         self.prototype = Value::make_undefined();
         self.slot_trap = None;
-        self.own_property_traps = HashMap::new();
+        self.own_property_traps = SmallMap::new();
         self.internal_slots = None;
 
         self.field_shortcuts = None;
@@ -511,6 +597,12 @@ impl AtomicSlot {
 
     }
 
+    pub fn list_own_property_traps(&self) -> Vec<Arc<dyn PropertyTrap>> {
+
+        self.own_property_traps.iter().map(|(_, property_trap)| property_trap.clone()).collect()
+
+    }
+
     pub fn iterate_own_property_symbols(&self) -> OwnPropertySymbolIterator {
 
         OwnPropertySymbolIterator { 
@@ -550,12 +642,34 @@ pub struct SlotRecordSnapshot {
     atomic_slot: Box<AtomicSlot>
 }
 
+impl SlotRecordSnapshot {
+
+    /// List property traps reused by the slot restored from the snapshot
+    pub fn list_own_property_traps(&self) -> Vec<Arc<dyn PropertyTrap>> {
+        self.atomic_slot.list_own_property_traps()
+    }
+
+}
+
+
+/// Count a slot trap dispatched in the metrics of the isolate
+#[inline]
+fn count_slot_trap_call(context: &Box<dyn Context>) {
+    context.get_isolate().get_metrics_counters().increase_slot_trap_calls();
+}
+
+/// Count a property trap dispatched in the metrics of the isolate
+#[inline]
+fn count_property_trap_call(context: &Box<dyn Context>) {
+    context.get_isolate().get_metrics_counters().increase_property_trap_calls();
+}
 
 /// Record for slot stored in region
 struct SlotRecord {
     region_id: u32,
     slot_index: u32,
     color: u8,
+    version: u32,
     outer_reference_map: Option<Box<ReferenceMap>>,
     atomic_slot: Box<AtomicSlot>
 }
@@ -568,11 +682,109 @@ impl SlotRecord {
             region_id: region_id,
             slot_index: slot_index,
             color: 0,
+            version: 0,
             outer_reference_map: None,
             atomic_slot: Box::new(AtomicSlot::new())
         }
     }
 
+    pub fn duplicate(&self) -> SlotRecord {
+        SlotRecord {
+            region_id: self.region_id,
+            slot_index: self.slot_index,
+            color: self.color,
+            version: self.version,
+            outer_reference_map: self.outer_reference_map.as_ref().map(|reference_map| Box::new(reference_map.duplicate())),
+            atomic_slot: Box::new(self.atomic_slot.duplicate())
+        }
+    }
+
+    /// Write the record for a cold region, field shortcuts and optimization
+    /// data are dropped and rebuilt after rehydration
+    pub fn write_cold(&self, writer: &mut ColdRegionWriter) -> Result<(), Error> {
+
+        let atomic_slot = &self.atomic_slot;
+
+        if atomic_slot.slot_trap.is_some() {
+            return Err(Error::new(FatalError, "Slot trap could not be paged out"));
+        }
+
+        writer.write_u8(self.color);
+        match &self.outer_reference_map {
+            Some(reference_map) => {
+                writer.write_u8(1);
+                reference_map.write_cold(writer);
+            },
+            None => writer.write_u8(0)
+        }
+
+        writer.write_u32(atomic_slot.flags);
+        writer.write_primitive_type(atomic_slot.primitive_type);
+        writer.write_value(atomic_slot.prototype);
+
+        writer.write_u32(atomic_slot.own_property_traps.len() as u32);
+        for (symbol, property_trap) in atomic_slot.own_property_traps.iter() {
+            let value = match property_trap.list_referenced_values().first() {
+                Some(value) if property_trap.is_simple_field() && !property_trap.as_any().is::<ConstPropertyTrap>() => *value,
+                _ => { return Err(Error::new(FatalError, "Property trap could not be paged out")); }
+            };
+            writer.write_symbol(*symbol);
+            writer.write_value(value);
+        }
+
+        match &atomic_slot.internal_slots {
+            Some(internal_slots) => {
+                writer.write_u32(internal_slots.len() as u32);
+                for (id, internal_slot) in internal_slots.iter() {
+                    writer.write_u64(*id);
+                    writer.write_internal_slot(internal_slot)?;
+                }
+            },
+            None => writer.write_u32(0)
+        }
+
+        Ok(())
+
+    }
+
+    /// Read the record written for a cold region
+    pub fn read_cold(&mut self, reader: &mut ColdRegionReader) -> Result<(), Error> {
+
+        self.color = reader.read_u8()?;
+        self.outer_reference_map = match reader.read_u8()? {
+            0 => None,
+            _ => Some(Box::new(ReferenceMap::read_cold(reader)?))
+        };
+
+        let mut atomic_slot = Box::new(AtomicSlot::new());
+        atomic_slot.flags = reader.read_u32()?;
+        atomic_slot.primitive_type = reader.read_primitive_type()?;
+        atomic_slot.prototype = reader.read_value()?;
+
+        let length = reader.read_u32()?;
+        for _ in 0 .. length {
+            let symbol = reader.read_symbol()?;
+            let property_trap: Arc<dyn PropertyTrap> = Arc::new(FieldPropertyTrap::new(reader.read_value()?));
+            atomic_slot.own_property_traps.insert(symbol, property_trap);
+        }
+
+        self.atomic_slot = atomic_slot;
+
+        let length = reader.read_u32()?;
+        if length > 0 {
+            let subject = self.get_id()?;
+            let mut internal_slots = HashMap::new();
+            for _ in 0 .. length {
+                let id = reader.read_u64()?;
+                internal_slots.insert(id, reader.read_internal_slot(subject)?);
+            }
+            self.atomic_slot.internal_slots = Some(Box::new(internal_slots));
+        }
+
+        Ok(())
+
+    }
+
     pub fn reset(&mut self) -> (Vec<Value>, Vec<Symbol>) {
 
         self.color = 0;
@@ -589,7 +801,7 @@ impl SlotRecord {
         let (removed_values, remove_symbols) = self.atomic_slot.list_self_references_without_autorefresh();
 
         let mut atomic_slot = Box::new(AtomicSlot::new());
-        std::mem::swap(&mut atomic_slot, &mut self.atomic_slot);
+        core::mem::swap(&mut atomic_slot, &mut self.atomic_slot);
 
         let outer_reference_map = self.outer_reference_map.take();
         let slot_record_snapshot = SlotRecordSnapshot {
@@ -600,6 +812,27 @@ impl SlotRecord {
 
     }
 
+    /// Copy the record into a snapshot, the record is kept readable
+    pub fn copy(&self) -> (SlotRecordSnapshot, u32) {
+
+        let slot_record_snapshot = SlotRecordSnapshot {
+            atomic_slot: Box::new(self.atomic_slot.copy())
+        };
+
+        (slot_record_snapshot, self.version)
+
+    }
+
+    /// Freeze the record after copied, the content of the record is dropped
+    /// since the copy has taken it over
+    pub fn freeze_copied(&mut self) -> (Option<Box<ReferenceMap>>, Vec<Value>, Vec<Symbol>) {
+
+        let (_, reference_map, removed_values, removed_symbols) = self.freeze();
+
+        (reference_map, removed_values, removed_symbols)
+
+    }
+
     pub fn sweep_outer_reference_map(&mut self) -> Option<Box<ReferenceMap>> {
 
         self.outer_reference_map.take()
@@ -610,7 +843,7 @@ impl SlotRecord {
 
         self.color = 0;
         self.outer_reference_map = None;
-        std::mem::swap(&mut self.atomic_slot, &mut snapshot.atomic_slot);
+        core::mem::swap(&mut self.atomic_slot, &mut snapshot.atomic_slot);
 
     }
 
@@ -628,6 +861,7 @@ impl SlotRecord {
             Float => Err(Error::new(FatalError, "Slot is not supported for float value")),
             Symbol => Err(Error::new(FatalError, "Slot is not supported for symbol value")),
             Text => Ok(Value::make_text(self.region_id, self.slot_index)),
+            BigInteger => Ok(Value::make_big_integer(self.region_id, self.slot_index)),
             List => Ok(Value::make_list(self.region_id, self.slot_index)),
             Tuple => Ok(Value::make_tuple(self.region_id, self.slot_index)),
             Object => Ok(Value::make_object(self.region_id, self.slot_index)),
@@ -723,6 +957,20 @@ impl SlotRecord {
         self.atomic_slot.set_prototype(prototype);
     }
 
+    /// Replace the prototype, references are updated if the prototype changed
+    pub fn replace_prototype(&mut self, self_id: Value, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
+
+        let old_prototype = self.get_prototype();
+        if prototype != old_prototype {
+            context.add_value_reference(self_id, prototype)?;
+            self.set_prototype(prototype);
+            context.remove_value_reference(self_id, old_prototype)?;
+        }
+
+        Ok(())
+
+    }
+
 }
 
 /// Slot own property trap
@@ -766,6 +1014,12 @@ impl SlotRecord {
 /// Slot value references
 impl SlotRecord {
 
+    pub fn collect_reference_map_stats(&self, stats: &mut ReferenceMapStats) {
+        if let Some(reference_map) = &self.outer_reference_map {
+            reference_map.collect_stats(stats);
+        }
+    }
+
     pub fn has_no_outer_references(&self) -> bool {
         match &self.outer_reference_map {
             Some(map) => map.is_empty(),
@@ -844,26 +1098,142 @@ impl SlotRecord {
 /// 
 /// `4` GiB may store 16M slots at most
 
+///
+/// Simple fields could be read optimistically without the lock, readers
+/// validate the sequence of the slot, which stays odd while the record is
+/// modified, and discard what they read if the sequence changed
 pub struct RegionSlot {
     rw_lock: RwLock,
+    sequence: AtomicU32,
     record: RefCell<SlotRecord>
 }
 
+/// Record borrowed for modification, the sequence of the slot stays odd
+/// until the guard dropped
+struct SlotRecordWriteGuard<'a> {
+    sequence: &'a AtomicU32,
+    record: Option<RefMut<'a, SlotRecord>>
+}
+
+impl<'a> Deref for SlotRecordWriteGuard<'a> {
+
+    type Target = SlotRecord;
+
+    fn deref(&self) -> &SlotRecord {
+        self.record.as_ref().unwrap()
+    }
+
+}
+
+impl<'a> DerefMut for SlotRecordWriteGuard<'a> {
+
+    fn deref_mut(&mut self) -> &mut SlotRecord {
+        self.record.as_mut().unwrap()
+    }
+
+}
+
+impl<'a> Drop for SlotRecordWriteGuard<'a> {
+
+    fn drop(&mut self) {
+        self.record.take();
+        self.sequence.fetch_add(1, Ordering::SeqCst);
+    }
+
+}
+
 /// Slot constructor, snapshot and initialization
 impl RegionSlot {
 
     pub fn new(region_id: u32, slot_index: u32) -> RegionSlot {
         RegionSlot {
-            rw_lock: RwLock::new(),
+            rw_lock: RwLock::with_class(LockClass::Slot),
+            sequence: AtomicU32::new(0),
             record: RefCell::new(SlotRecord::new(region_id, slot_index))
         }
     }
 
+    /// Borrow the record for modification, optimistic readers fall back to
+    /// locking until the guard dropped
+    fn write_record(&self) -> SlotRecordWriteGuard<'_> {
+
+        self.sequence.fetch_add(1, Ordering::SeqCst);
+
+        SlotRecordWriteGuard {
+            sequence: &self.sequence,
+            record: Some(self.record.borrow_mut())
+        }
+
+    }
+
+    /// Borrow the record for modification, the version of the record is
+    /// advanced to invalidate copies taken before
+    fn borrow_record_mut(&self) -> SlotRecordWriteGuard<'_> {
+        let mut record = self.write_record();
+        record.version = record.version.wrapping_add(1);
+        record
+    }
+
+    /// Read the record without locking the slot, `None` will be returned if
+    /// the record is modified meanwhile, and the read should fall back to
+    /// locking
+    ///
+    /// The record could be modified while read, so it is only read through
+    /// the raw pointer with volatile reads, and nothing read is trusted
+    /// before the sequence validated
+    fn read_record_optimistically<F, R>(&self, read: F) -> Option<R> where F: FnOnce(*const SlotRecord) -> Option<R> {
+
+        let sequence = self.sequence.load(Ordering::Acquire);
+        if sequence & 1 != 0 {
+            return None;
+        }
+
+        let result = read(self.record.as_ptr());
+
+        fence(Ordering::Acquire);
+
+        if self.sequence.load(Ordering::Relaxed) != sequence {
+            return None;
+        }
+
+        result
+
+    }
+
+    /// Duplicate the slot for a forked isolate
+    pub fn duplicate(&self) -> RegionSlot {
+
+        let _guard = self.rw_lock.lock_read();
+
+        RegionSlot {
+            rw_lock: RwLock::with_class(LockClass::Slot),
+            sequence: AtomicU32::new(0),
+            record: RefCell::new(self.record.borrow().duplicate())
+        }
+
+    }
+
+    pub fn write_cold(&self, writer: &mut ColdRegionWriter) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.record.borrow().write_cold(writer)
+
+    }
+
+    pub fn read_cold(&self, reader: &mut ColdRegionReader) -> Result<(), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.borrow_record_mut().read_cold(reader)
+
+    }
+
     pub fn recycle(&self, drop_value: bool, context: &Box<dyn Context>) -> Result<(), Error> {
 
         let (id, slot_trap, removed_values, removed_symbols) = {
             let _guard = self.rw_lock.lock_write();
-            let mut record = self.record.borrow_mut();
+            let mut record = self.borrow_record_mut();
             if !record.is_alive() {
                 return Ok(());
             }
@@ -895,7 +1265,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot is not alive"));
@@ -905,11 +1275,47 @@ impl RegionSlot {
 
     }
 
+    /// Copy the slot while readers continue, the version is returned to
+    /// check whether the slot is modified before the copy installed
+    pub fn copy(&self) -> Result<(SlotRecordSnapshot, u32), Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let record = self.record.borrow();
+
+        if !record.is_alive() {
+            return Err(Error::new(FatalError, "Slot is not alive"));
+        }
+
+        Ok(record.copy())
+
+    }
+
+    /// Freeze the slot copied, `None` is returned if the slot is modified
+    /// after copied
+    pub fn freeze_copied(&self, version: u32) -> Result<Option<(Option<Box<ReferenceMap>>, Vec<Value>, Vec<Symbol>)>, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut record = self.write_record();
+
+        if !record.is_alive() {
+            return Err(Error::new(FatalError, "Slot is not alive"));
+        }
+
+        if record.version != version {
+            return Ok(None);
+        }
+
+        Ok(Some(record.freeze_copied()))
+
+    }
+
     pub fn restore(&self, snapshot: SlotRecordSnapshot) -> Result<(Value, Vec<Value>, Vec<Symbol>), Error> {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if record.is_alive() {
             return Err(Error::new(FatalError, "Slot is alive"));
@@ -960,7 +1366,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -984,7 +1390,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        self.record.borrow_mut().mark_as_alive()
+        self.borrow_record_mut().mark_as_alive()
 
     }
 
@@ -992,7 +1398,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        self.record.borrow_mut().overwrite_primitive_type(primitive_type)
+        self.borrow_record_mut().overwrite_primitive_type(primitive_type)
 
     }
 
@@ -1005,7 +1411,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -1016,9 +1422,7 @@ impl RegionSlot {
 
         let id = record.get_id()?;
 
-        for value in slot_trap.list_internal_referenced_values() {
-            context.add_value_reference(id, value)?;
-        }
+        context.update_value_references(id, &slot_trap.list_internal_referenced_values(), &[])?;
 
         for symbol in slot_trap.list_internal_referenced_symbols() {
             context.add_symbol_reference(symbol)?;
@@ -1030,9 +1434,7 @@ impl RegionSlot {
             for symbol in old_slot_trap.list_internal_referenced_symbols() {
                 context.remove_symbol_reference(symbol)?;
             }
-            for value in old_slot_trap.list_internal_referenced_values() {
-                context.remove_value_reference(id, value)?;
-            }
+            context.update_value_references(id, &[], &old_slot_trap.list_internal_referenced_values())?;
         }
 
         Ok(())
@@ -1043,7 +1445,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -1060,9 +1462,7 @@ impl RegionSlot {
             for symbol in old_slot_trap.list_internal_referenced_symbols() {
                 context.remove_symbol_reference(symbol)?;
             }
-            for value in old_slot_trap.list_internal_referenced_values() {
-                context.remove_value_reference(id, value)?;
-            }
+            context.update_value_references(id, &[], &old_slot_trap.list_internal_referenced_values())?;
         }
 
         Ok(())
@@ -1083,6 +1483,42 @@ impl RegionSlot {
 
     }
 
+    pub fn call_with_layout_guard(&self, this: Value, arguments: Vec<Value>, context: &Box<dyn Context>, mut layout_guard: ReentrantLockReadGuard) -> Result<Pinned, Error> {
+
+        let (id, slot_trap) = {
+            let _guard = self.rw_lock.lock_read();
+            let record = self.record.borrow();
+            if !record.is_alive() {
+                return Err(Error::new(FatalError, "Slot not alive"));
+            }
+            let id = record.get_id()?;
+            let slot_trap = record.get_slot_trap();
+            match slot_trap {
+                None => {
+                    return Err(Error::new(TypeNotMatch, "Value is not callable"));
+                },
+                Some(slot_trap) => (id, ProtectedSlotTrap::new(slot_trap, context)?)
+            }
+        };
+
+        layout_guard.unlock();
+
+        let mut parameters = Vec::with_capacity(arguments.len() + 1);
+        parameters.push(this);
+        parameters.extend(arguments);
+
+        slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
+        let trap_info = context.create_trap_info(id, parameters, context);
+        count_slot_trap_call(context);
+        let result = slot_trap.call(trap_info, context)?;
+        match result {
+            Trapped(value) => Ok(value),
+            Thrown(value) => Err(Error::new(RogicError(value), "Rogic error happened")),
+            Skipped => Err(Error::new(TypeNotMatch, "Value is not callable"))
+        }
+
+    }
+
 }
 
 /// Slot internal slot
@@ -1092,7 +1528,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -1129,7 +1565,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -1226,7 +1662,8 @@ impl RegionSlot {
         layout_guard.unlock();
 
         slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-        let trap_info = context.create_trap_info(id, vec!(id), context);
+        let trap_info = context.acquire_trap_info(id, &[id], context);
+        count_slot_trap_call(context);
         let result = slot_trap.get_prototype(trap_info, context)?;
         match result {
             Trapped(value) => Ok(value),
@@ -1236,11 +1673,44 @@ impl RegionSlot {
 
     }
 
+    pub fn get_prototype_ignore_slot_trap(&self, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let record = self.record.borrow();
+        if !record.is_alive() {
+            return Err(Error::new(FatalError, "Slot not alive"));
+        }
+
+        Pinned::new(context, record.get_prototype())
+
+    }
+
+    /// Overwrite the prototype directly, the old prototype is returned and
+    /// references should be updated by the caller
+    pub fn overwrite_prototype(&self, prototype: Value) -> Result<Value, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let mut record = self.borrow_record_mut();
+        if !record.is_alive() {
+            return Err(Error::new(FatalError, "Slot not alive"));
+        }
+
+        let old_prototype = record.get_prototype();
+        record.set_prototype(prototype);
+
+        Ok(old_prototype)
+
+    }
+
     pub fn set_prototype_with_layout_guard(&self, prototype: Value, context: &Box<dyn Context>, mut layout_guard: ReentrantLockReadGuard, no_redirection: bool) -> Result<(), Error> {
 
+        let prototype = context.resolve_real_value(prototype)?;
+
         let (id, slot_trap) = {
             let _guard = self.rw_lock.lock_write();
-            let mut record = self.record.borrow_mut();
+            let mut record = self.borrow_record_mut();
             if !record.is_alive() {
                 return Err(Error::new(FatalError, "Slot not alive"));
             }
@@ -1248,8 +1718,7 @@ impl RegionSlot {
             let slot_trap = record.get_slot_trap();
             match slot_trap {
                 None => {
-                    record.set_prototype(prototype);
-                    return Ok(());
+                    return record.replace_prototype(id, prototype, context);
                 },
                 Some(slot_trap) => (id, ProtectedSlotTrap::new(slot_trap, context)?)
             }
@@ -1258,7 +1727,8 @@ impl RegionSlot {
         layout_guard.unlock();
 
         slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-        let trap_info = context.create_trap_info(id, vec!(id, prototype), context);
+        let trap_info = context.acquire_trap_info(id, &[id, prototype], context);
+        count_slot_trap_call(context);
         let result = slot_trap.set_prototype(trap_info, context)?;
         match result {
             Trapped(_) => {
@@ -1280,16 +1750,18 @@ impl RegionSlot {
 
     pub fn set_prototype_ignore_slot_trap(&self, prototype: Value, context: &Box<dyn Context>) -> Result<(), Error> {
 
+        let prototype = context.resolve_real_value(prototype)?;
+
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
         }
 
-        record.set_prototype(prototype);
+        let id = record.get_id()?;
 
-        Ok(())
+        record.replace_prototype(id, prototype, context)
 
     }
 
@@ -1321,7 +1793,8 @@ impl RegionSlot {
 
         let symbol_value = Value::make_symbol(symbol);
         slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-        let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+        let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
+        count_slot_trap_call(context);
         let result = slot_trap.has_own_property(trap_info, context)?;
         match result {
             Trapped(value) => Ok(value.as_boolean()),
@@ -1339,6 +1812,14 @@ impl RegionSlot {
             }
         }
 
+        if let Some(field_token) = field_token {
+            if let Some(field_value) = self.get_simple_field_optimistically(symbol, field_token) {
+                if context.resolve_real_value(field_value)? == field_value {
+                    return Pinned::new(context, field_value);
+                }
+            }
+        }
+
         let (id, slot_trap) = {
             let _guard = self.rw_lock.lock_read();
             let record = self.record.borrow();
@@ -1369,7 +1850,8 @@ impl RegionSlot {
                         let property_trap = property_trap.iter().next().unwrap();
                         if property_trap.is_simple_field() {
                             let symbol_value = Value::make_symbol(symbol);
-                            let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+                            let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
+                            count_property_trap_call(context);
                             let field_value = property_trap.get_property(trap_info, context)?;
                             let origin_value = field_value.get_origin_value();
                             let new_value = context.resolve_real_value(origin_value)?;
@@ -1397,7 +1879,8 @@ impl RegionSlot {
         let symbol_value = Value::make_symbol(symbol);
         if let Some(slot_trap) = slot_trap {
             slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-            let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+            let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
+            count_slot_trap_call(context);
             let result = slot_trap.get_own_property(trap_info, context)?;
             match result {
                 Trapped(value) => { return Ok(value); },
@@ -1414,6 +1897,33 @@ impl RegionSlot {
 
     }
 
+    /// Read a simple field cached in the field shortcuts without locking the
+    /// slot, `None` will be returned if the slot has a slot trap, the field
+    /// is not cached, or the slot is modified meanwhile
+    ///
+    /// Atomic slots are only replaced under the slot layout write lock, and
+    /// field shortcuts released by the slot are retired by the isolate until
+    /// the slot layout locked for writing, so the pointers read stay valid
+    /// for readers holding the slot layout even if the read discarded
+    fn get_simple_field_optimistically(&self, symbol: Symbol, field_token: &FieldToken) -> Option<Value> {
+
+        if field_token.get_symbol() != symbol {
+            return None;
+        }
+
+        self.read_record_optimistically(|record| unsafe {
+            let atomic_slot = ptr::read_volatile(ptr::addr_of!((*record).atomic_slot) as *const *const AtomicSlot);
+            let flags = ptr::read_volatile(ptr::addr_of!((*atomic_slot).flags));
+            let slot_trap = ManuallyDrop::new(ptr::read_volatile(ptr::addr_of!((*atomic_slot).slot_trap)));
+            if (flags & LIVE_FLAG) == 0 || slot_trap.is_some() {
+                return None;
+            }
+            let field_shortcuts = ManuallyDrop::new(ptr::read_volatile(ptr::addr_of!((*atomic_slot).field_shortcuts)));
+            field_token.get_field_optimistically(field_shortcuts.as_ref()?)
+        })
+
+    }
+
     pub fn get_own_property_ignore_slot_trap(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<Pinned, Error> {
 
         let (id, property_trap) = {
@@ -1436,19 +1946,38 @@ impl RegionSlot {
 
         property_trap.list_and_autorefresh_referenced_values(id, context)?;
 
-        let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+        let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
 
+        count_property_trap_call(context);
         property_trap.get_property(trap_info, context)
 
     } 
 
+    /// Get the raw own property trap of a symbol, slot traps are ignored
+    pub fn get_own_property_trap<'a>(&self, symbol: Symbol, context: &'a Box<dyn Context>) -> Result<Option<ProtectedPropertyTrap<'a>>, Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let record = self.record.borrow();
+
+        if !record.is_alive() {
+            return Err(Error::new(FatalError, "Slot not alive"));
+        }
+
+        match record.get_own_property_trap(symbol) {
+            None => Ok(None),
+            Some(property_trap) => Ok(Some(ProtectedPropertyTrap::new(property_trap, context)?))
+        }
+
+    }
+
     pub fn overwrite_own_property(&self, 
         symbol: Symbol, 
         value: Value) -> Result<(Vec<Value>, Vec<Symbol>, Vec<Value>, Vec<Symbol>), Error> {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
         }
@@ -1486,7 +2015,7 @@ impl RegionSlot {
 
         let (id, slot_trap) = {
             let _guard = self.rw_lock.lock_write();
-            let mut record = self.record.borrow_mut();
+            let mut record = self.borrow_record_mut();
             if !record.is_alive() {
                 return Err(Error::new(FatalError, "Slot not alive"));
             }
@@ -1512,10 +2041,14 @@ impl RegionSlot {
                         return Ok(());
                     },
                     Some(property_trap) => {
+                        if property_trap.is_constant() {
+                            return Err(Error::new(MutatingConstantProperty, "Constant property assigned"));
+                        }
                         if let Some(field_shortcuts) = field_shortcuts {
                             if property_trap.is_simple_field() {
                                 let symbol_value = Value::make_symbol(symbol);
-                                let trap_info = context.create_trap_info(id, vec!(subject, symbol_value, value), context);
+                                let trap_info = context.acquire_trap_info(id, &[subject, symbol_value, value], context);
+                                count_property_trap_call(context);
                                 let (removed_values, added_values, removed_symbols, added_symbols) = property_trap.set_property(trap_info, context)?;
                                 for value in added_values {
                                     context.add_value_reference(id, value)?;
@@ -1549,7 +2082,8 @@ impl RegionSlot {
         let symbol_value = Value::make_symbol(symbol);
         if let Some(slot_trap) = slot_trap {
             slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-            let trap_info = context.create_trap_info(id, vec!(subject, symbol_value, value), context);
+            let trap_info = context.acquire_trap_info(id, &[subject, symbol_value, value], context);
+            count_slot_trap_call(context);
             let result = slot_trap.set_own_property(trap_info, context)?;
             match result {
                 Trapped(_) => { return Ok(()); },
@@ -1574,7 +2108,7 @@ impl RegionSlot {
 
         let (id, property_trap) = {
             let _guard = self.rw_lock.lock_write();
-            let mut record = self.record.borrow_mut();
+            let mut record = self.borrow_record_mut();
             if !record.is_alive() {
                 return Err(Error::new(FatalError, "Slot not alive"));
             }
@@ -1598,10 +2132,14 @@ impl RegionSlot {
                     return Ok(());
                 },
                 Some(property_trap) => {
+                    if property_trap.is_constant() {
+                        return Err(Error::new(MutatingConstantProperty, "Constant property assigned"));
+                    }
                     if let Some(field_shortcuts) = field_shortcuts {
                         if property_trap.is_simple_field() {
                             let symbol_value = Value::make_symbol(symbol);
-                            let trap_info = context.create_trap_info(id, vec!(subject, symbol_value, value), context);
+                            let trap_info = context.acquire_trap_info(id, &[subject, symbol_value, value], context);
+                            count_property_trap_call(context);
                             let (removed_values, added_values, removed_symbols, added_symbols) = property_trap.set_property(trap_info, context)?;
                             for value in added_values {
                                 context.add_value_reference(id, value)?;
@@ -1628,7 +2166,8 @@ impl RegionSlot {
 
         let symbol_value = Value::make_symbol(symbol);
 
-        let trap_info = context.create_trap_info(id, vec!(subject, symbol_value, value), context);
+        let trap_info = context.acquire_trap_info(id, &[subject, symbol_value, value], context);
+        count_property_trap_call(context);
         let (removed_values, added_values, removed_symbols, added_symbols) = property_trap.set_property(trap_info, context)?;
         for value in added_values {
             context.add_value_reference(id, value)?;
@@ -1639,7 +2178,7 @@ impl RegionSlot {
 
         {
             let _guard = self.rw_lock.lock_write();
-            let record = self.record.borrow_mut();
+            let record = self.borrow_record_mut();
             let field_shortcuts = record.get_field_shortcuts();
             if let Some(field_shortcuts) = field_shortcuts {
                 field_shortcuts.clear_field(symbol);
@@ -1662,11 +2201,11 @@ impl RegionSlot {
         property_trap: Arc<dyn PropertyTrap>, 
         context: &Box<dyn Context>, 
         mut layout_guard: ReentrantLockReadGuard<'a>, 
-        no_redirection: bool) -> Result<(), Error> {
+        no_redirection: bool) -> Result<bool, Error> {
 
         let (id, slot_trap) = {
             let _guard = self.rw_lock.lock_write();
-            let mut record = self.record.borrow_mut();
+            let mut record = self.borrow_record_mut();
             if !record.is_alive() {
                 return Err(Error::new(FatalError, "Slot not alive"));
             }
@@ -1686,7 +2225,8 @@ impl RegionSlot {
                 if let Some(field_shortcuts) = field_shortcuts {
                     if property_trap.is_simple_field() {
                         let symbol_value = Value::make_symbol(symbol);
-                        let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+                        let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
+                        count_property_trap_call(context);
                         let value = property_trap.get_property(trap_info, context)?;
                         field_shortcuts.set_symbol_field(symbol, value.get_value());
                     } else {
@@ -1694,6 +2234,7 @@ impl RegionSlot {
                     }
                 }
                 let old_property_trap = record.define_own_property_trap(symbol, property_trap);
+                let replaced = old_property_trap.is_some();
                 if let Some(old_property_trap) = old_property_trap {
                     for value in old_property_trap.list_referenced_values() {
                         context.remove_value_reference(id, value)?;
@@ -1704,7 +2245,7 @@ impl RegionSlot {
                 } else {
                     context.add_symbol_reference(symbol)?;
                 }
-                return Ok(());
+                return Ok(replaced);
             }
             match slot_trap {
                 None => (id, None),
@@ -1718,10 +2259,11 @@ impl RegionSlot {
         if let Some(slot_trap) = slot_trap {
             slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
             let trap_value = context.make_property_trap_value(property_trap.clone(), context)?;
-            let trap_info = context.create_trap_info(id, vec!(subject, symbol_value, trap_value), context);
+            let trap_info = context.acquire_trap_info(id, &[subject, symbol_value, trap_value], context);
+            count_slot_trap_call(context);
             let result = slot_trap.define_own_property(trap_info, context)?;
             match result {
-                Trapped(_) => { return Ok(()); },
+                Trapped(value) => { return Ok(value.as_boolean()); },
                 Thrown(value) => { return Err(Error::new(RogicError(value), "Rogic error happened")); },
                 Skipped => {}
             }
@@ -1738,10 +2280,10 @@ impl RegionSlot {
     pub fn define_own_property_ignore_slot_trap(&self, 
         subject: Value, symbol: Symbol, 
         property_trap: Arc<dyn PropertyTrap>, 
-        context: &Box<dyn Context>) -> Result<(), Error> {
+        context: &Box<dyn Context>) -> Result<bool, Error> {
 
         let _guard = self.rw_lock.lock_write();
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
         }
@@ -1759,7 +2301,8 @@ impl RegionSlot {
         if let Some(field_shortcuts) = field_shortcuts {
             if property_trap.is_simple_field() {
                 let symbol_value = Value::make_symbol(symbol);
-                let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+                let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
+                count_property_trap_call(context);
                 let value = property_trap.get_property(trap_info, context)?;
                 field_shortcuts.set_symbol_field(symbol, value.get_value());
             } else {
@@ -1767,6 +2310,7 @@ impl RegionSlot {
             }
         }
         let old_property_trap = record.define_own_property_trap(symbol, property_trap);
+        let replaced = old_property_trap.is_some();
         if let Some(old_property_trap) = old_property_trap {
             for value in old_property_trap.list_referenced_values() {
                 context.remove_value_reference(id, value)?;
@@ -1778,7 +2322,7 @@ impl RegionSlot {
             context.add_symbol_reference(symbol)?;
         }
 
-        Ok(())
+        Ok(replaced)
 
     }
 
@@ -1786,11 +2330,11 @@ impl RegionSlot {
         subject: Value, symbol: Symbol, 
         context: &Box<dyn Context>, 
         mut layout_guard: ReentrantLockReadGuard<'a>,
-        no_redirection: bool) -> Result<(), Error> {
+        no_redirection: bool) -> Result<bool, Error> {
 
         let (id, slot_trap) = {
             let _guard = self.rw_lock.lock_write();
-            let mut record = self.record.borrow_mut();
+            let mut record = self.borrow_record_mut();
             if !record.is_alive() {
                 return Err(Error::new(FatalError, "Slot not alive"));
             }
@@ -1805,6 +2349,7 @@ impl RegionSlot {
                     field_shortcuts.clear_field(symbol);
                 }
                 let old_property_trap = record.clear_own_property_trap(symbol);
+                let existed = old_property_trap.is_some();
                 if let Some(old_property_trap) = old_property_trap {
                     for value in old_property_trap.list_referenced_values() {
                         context.remove_value_reference(id, value)?;
@@ -1814,7 +2359,7 @@ impl RegionSlot {
                     }
                     context.remove_symbol_reference(symbol)?;
                 }
-                return Ok(());
+                return Ok(existed);
             }
             match slot_trap {
                 None => (id, None),
@@ -1827,10 +2372,11 @@ impl RegionSlot {
         let symbol_value = Value::make_symbol(symbol);
         if let Some(slot_trap) = slot_trap {
             slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-            let trap_info = context.create_trap_info(id, vec!(subject, symbol_value), context);
+            let trap_info = context.acquire_trap_info(id, &[subject, symbol_value], context);
+            count_slot_trap_call(context);
             let result = slot_trap.delete_own_property(trap_info, context)?;
             match result {
-                Trapped(_) => { return Ok(()); },
+                Trapped(value) => { return Ok(value.as_boolean()); },
                 Thrown(value) => { return Err(Error::new(RogicError(value), "Rogic error happened")); },
                 Skipped => {}
             }
@@ -1844,10 +2390,10 @@ impl RegionSlot {
 
     }
 
-    pub fn delete_own_property_ignore_slot_trap(&self, subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<(), Error> {
+    pub fn delete_own_property_ignore_slot_trap(&self, _subject: Value, symbol: Symbol, context: &Box<dyn Context>) -> Result<bool, Error> {
 
         let _guard = self.rw_lock.lock_write();
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
         }
@@ -1861,6 +2407,7 @@ impl RegionSlot {
             field_shortcuts.clear_field(symbol);
         }
         let old_property_trap = record.clear_own_property_trap(symbol);
+        let existed = old_property_trap.is_some();
         if let Some(old_property_trap) = old_property_trap {
             for value in old_property_trap.list_referenced_values() {
                 context.remove_value_reference(id, value)?;
@@ -1871,7 +2418,7 @@ impl RegionSlot {
             context.remove_symbol_reference(symbol)?;
         }
 
-        Ok(())
+        Ok(existed)
 
     }
 
@@ -1900,7 +2447,8 @@ impl RegionSlot {
         layout_guard.unlock();
 
         slot_trap.list_and_autorefresh_internal_referenced_values(id, context)?;
-        let trap_info = context.create_trap_info(id, vec!(subject), context);
+        let trap_info = context.acquire_trap_info(id, &[subject], context);
+        count_slot_trap_call(context);
         let result = slot_trap.list_own_property_symbols(trap_info, context)?;
         match result {
             Trapped(list_value) => { 
@@ -1983,7 +2531,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -1997,7 +2545,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2016,7 +2564,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2026,6 +2574,18 @@ impl RegionSlot {
 
     }
  
+    pub fn collect_reference_map_stats(&self, stats: &mut ReferenceMapStats) {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let record = self.record.borrow();
+
+        if record.is_alive() {
+            record.collect_reference_map_stats(stats);
+        }
+
+    }
+
     pub fn has_no_outer_references(&self) -> Result<bool, Error> {
 
         let _guard = self.rw_lock.lock_read();
@@ -2044,7 +2604,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2058,7 +2618,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2077,7 +2637,21 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        self.record.borrow_mut().list_and_autorefresh_self_references(context)
+        self.borrow_record_mut().list_and_autorefresh_self_references(context)
+
+    }
+
+    pub fn list_self_references_without_autorefresh(&self) -> Result<(Vec<Value>, Vec<Symbol>), Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        let record = self.record.borrow();
+
+        if !record.is_alive() {
+            return Err(Error::new(FatalError, "Slot not alive"));
+        }
+
+        Ok(record.list_self_references_without_autorefresh())
 
     }
 
@@ -2085,7 +2659,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2101,7 +2675,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2117,7 +2691,7 @@ impl RegionSlot {
 
         let _guard = self.rw_lock.lock_write();
 
-        let mut record = self.record.borrow_mut();
+        let mut record = self.borrow_record_mut();
 
         if !record.is_alive() {
             return Err(Error::new(FatalError, "Slot not alive"));
@@ -2183,7 +2757,7 @@ impl RegionSlot {
 
 #[test]
 fn test_atomic_slot_size() {
-    assert_eq!(std::mem::size_of::<AtomicSlot>(), 128);
+    assert_eq!(std::mem::size_of::<AtomicSlot>(), 96);
 }
 
 #[test]
@@ -2359,9 +2933,10 @@ fn test_slot_record_references() -> Result<(), Error> {
 
 }
 
+#[cfg(not(any(feature = "lock-diagnostics", feature = "lock-stats")))]
 #[test]
 fn test_region_slot_size() {
-    assert_eq!(std::mem::size_of::<RegionSlot>(), 56);
+    assert_eq!(std::mem::size_of::<RegionSlot>(), 64);
 }
 
 #[test]
@@ -2705,3 +3280,87 @@ fn test_region_slot_own_property_with_field_shortcuts() -> Result<(), Error> {
     Ok(())
 
 }
+
+#[test]
+fn test_region_slot_const_property() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let context: Box<dyn Context> = Box::new(TestContext::new(isolate));
+
+    let region_slot = RegionSlot::new(1, 1);
+    region_slot.mark_as_alive();
+    region_slot.overwrite_primitive_type(Object)?;
+
+    let id = region_slot.get_id()?;
+
+    let field_template = Arc::new(FieldTemplate::new(1));
+    field_template.add_symbol(Symbol::new(1))?;
+
+    let field_shortcuts = Arc::new(FieldShortcuts::new(field_template.clone()));
+    region_slot.set_field_shortcuts(field_shortcuts.clone())?;
+
+    let field_token = field_shortcuts.get_field_token(Symbol::new(1)).unwrap();
+
+    let property_trap = Arc::new(ConstPropertyTrap::new());
+    region_slot.define_own_property_with_layout_guard(id, Symbol::new(1), property_trap.clone(), &context, layout_token.lock_read(), true)?;
+    assert!(!property_trap.is_assigned());
+
+    region_slot.set_own_property_with_layout_guard(id, Symbol::new(1), Value::make_float(43.0), &context, layout_token.lock_read(), true)?;
+    assert!(property_trap.is_assigned());
+    assert_eq!(field_token.get_field(&field_shortcuts).unwrap(), Value::make_float(43.0));
+
+    let error = region_slot.set_own_property_with_layout_guard(id, Symbol::new(1), Value::make_float(53.0), &context, layout_token.lock_read(), true).unwrap_err();
+    assert!(matches!(error.get_error_type(), MutatingConstantProperty));
+    let error = region_slot.set_own_property_ignore_slot_trap(id, Symbol::new(1), Value::make_float(53.0), &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), MutatingConstantProperty));
+    assert_eq!(region_slot.get_own_property_with_layout_guard(id, Symbol::new(1), Some(&field_token), &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(43.0));
+
+    region_slot.define_own_property_with_layout_guard(id, Symbol::new(2), Arc::new(ConstPropertyTrap::with_value(Value::make_boolean(true))), &context, layout_token.lock_read(), true)?;
+    assert!(region_slot.set_own_property_with_layout_guard(id, Symbol::new(2), Value::make_boolean(false), &context, layout_token.lock_read(), true).is_err());
+    assert_eq!(region_slot.get_own_property_with_layout_guard(id, Symbol::new(2), None, &context, layout_token.lock_read(), true)?.get_value(), Value::make_boolean(true));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_region_slot_optimistic_read() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let layout_token = isolate.create_slot_layout_token();
+
+    let context: Box<dyn Context> = Box::new(TestContext::new(isolate));
+
+    let region_slot = RegionSlot::new(1, 1);
+    region_slot.mark_as_alive();
+    region_slot.overwrite_primitive_type(Object)?;
+
+    let id = region_slot.get_id()?;
+
+    let field_template = Arc::new(FieldTemplate::new(1));
+    field_template.add_symbol(Symbol::new(1))?;
+    let field_shortcuts = Arc::new(FieldShortcuts::new(field_template.clone()));
+    region_slot.set_field_shortcuts(field_shortcuts.clone())?;
+
+    let field_token = field_shortcuts.get_field_token(Symbol::new(1)).unwrap();
+    assert!(region_slot.get_simple_field_optimistically(Symbol::new(1), &field_token).is_none());
+
+    region_slot.set_own_property_with_layout_guard(id, Symbol::new(1), Value::make_float(43.0), &context, layout_token.lock_read(), true)?;
+    assert_eq!(region_slot.get_own_property_with_layout_guard(id, Symbol::new(1), Some(&field_token), &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(43.0));
+    let field_token = field_shortcuts.get_field_token(Symbol::new(1)).unwrap();
+    assert_eq!(region_slot.get_simple_field_optimistically(Symbol::new(1), &field_token), Some(Value::make_float(43.0)));
+
+    {
+        let _record = region_slot.borrow_record_mut();
+        assert!(region_slot.get_simple_field_optimistically(Symbol::new(1), &field_token).is_none());
+    }
+    assert_eq!(region_slot.get_simple_field_optimistically(Symbol::new(1), &field_token), Some(Value::make_float(43.0)));
+    assert_eq!(region_slot.get_own_property_with_layout_guard(id, Symbol::new(1), Some(&field_token), &context, layout_token.lock_read(), true)?.get_value(), Value::make_float(43.0));
+
+    Ok(())
+
+}
@@ -0,0 +1,63 @@
+/// Spaces grouping regions by the lifetime of their slots
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Space {
+    /// Space of regions for newly allocated slots
+    New,
+    /// Space of regions for slots survived from collections
+    Old,
+    /// Space of regions never collected, such as the builtin prototypes
+    Immortal
+}
+
+impl Space {
+
+    pub fn list_all() -> [Space; 3] {
+        [Space::New, Space::Old, Space::Immortal]
+    }
+
+}
+
+/// Collection policy of regions in a space
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SpacePolicy {
+    sweepable: bool,
+    refragmentable: bool,
+    recyclable: bool
+}
+
+impl SpacePolicy {
+
+    pub fn new(sweepable: bool, refragmentable: bool, recyclable: bool) -> SpacePolicy {
+        SpacePolicy {
+            sweepable: sweepable,
+            refragmentable: refragmentable,
+            recyclable: recyclable
+        }
+    }
+
+    /// Get the default policy of a space
+    pub fn for_space(space: Space) -> SpacePolicy {
+        match space {
+            Space::New => SpacePolicy::new(true, true, true),
+            Space::Old => SpacePolicy::new(true, false, true),
+            Space::Immortal => SpacePolicy::new(false, false, false)
+        }
+    }
+
+    /// Check whether unreachable slots in regions of the space are swept
+    pub fn is_sweepable(&self) -> bool {
+        self.sweepable
+    }
+
+    /// Check whether regions of the space are refragmented, as both sources
+    /// and targets
+    pub fn is_refragmentable(&self) -> bool {
+        self.refragmentable
+    }
+
+    /// Check whether empty regions of the space are recycled
+    pub fn is_recyclable(&self) -> bool {
+        self.recyclable
+    }
+
+}
@@ -0,0 +1,905 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::cell::RefCell;
+use super::util::HashMap;
+use alloc::sync::Arc;
+
+use super::access_policy::AccessPolicy;
+use super::base::Error;
+use super::cancellation::CancellationToken;
+use super::base::ErrorType::*;
+use super::base::PrimitiveType;
+use super::base::Value;
+use super::collector::Collector;
+use super::collector::GcStressMode;
+use super::context::Context;
+use super::internal_slot::InternalSlot;
+use super::isolate::Isolate;
+use super::isolate_options::RegionAffinity;
+use super::region_cache::RegionCache;
+use super::root::Root;
+use super::trap::PropertyTrap;
+use super::trap::StandardTrapInfoFactory;
+use super::trap::TrapInfo;
+use super::trap::TrapInfoFactory;
+use super::trap::TrapInfoPool;
+use super::util::ReentrantToken;
+
+/// Default limit of traps nested in a context
+const DEFAULT_TRAP_DEPTH_LIMIT: u32 = 256;
+
+/// Internal slot keeping the property trap of a property trap value
+struct PropertyTrapSlot {
+    property_trap: Arc<dyn PropertyTrap>
+}
+
+impl InternalSlot for PropertyTrapSlot {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+}
+
+/// Listener of context operations, for instrumentation
+pub trait ContextListener {
+
+    fn notify_slot_gained(&self, _value: Value) {}
+
+    fn notify_value_reference_added(&self, _from: Value, _to: Value) {}
+
+    fn notify_value_reference_removed(&self, _from: Value, _to: Value) {}
+
+    fn notify_trap_info_created(&self, _subject: Value) {}
+
+}
+
+/// Roots added by a child context
+struct ContextScope {
+    roots: RefCell<Vec<Arc<Root>>>
+}
+
+impl ContextScope {
+
+    fn new() -> ContextScope {
+        ContextScope {
+            roots: RefCell::new(Vec::new())
+        }
+    }
+
+}
+
+/// Default context of an isolate
+///
+/// New slots are gained in the allocation region if specified, otherwise in
+/// a region created by the context, and another region will be created once
+/// the region could not gain slots quickly. Trap infos are created by the
+/// trap info factory of the context.
+///
+/// Roots added by a child context are released once the child context
+/// dropped, values pinned by the child context will not be kept from
+/// recycling after then. Value references added by a child context are kept,
+/// as the fields holding them may outlive the child context.
+pub struct StandardContext {
+    isolate: Arc<Isolate>,
+    slot_layout_token: ReentrantToken,
+    allocation_region_id: Option<u32>,
+    allocation_tag: Option<String>,
+    quota_id: Option<u64>,
+    cancellation_token: Option<CancellationToken>,
+    trap_depth: Cell<u32>,
+    trap_depth_limit: u32,
+    recursion_depth: Cell<u32>,
+    new_born_region_ids: RefCell<HashMap<RegionAffinity, u32>>,
+    region_cache: RegionCache,
+    trap_info_factory: Arc<dyn TrapInfoFactory>,
+    trap_info_pool: Arc<TrapInfoPool>,
+    listeners: Vec<Arc<dyn ContextListener>>,
+    access_policy: Option<Arc<dyn AccessPolicy>>,
+    scope: Option<ContextScope>
+}
+
+impl StandardContext {
+
+    pub fn new(isolate: Arc<Isolate>) -> StandardContext {
+        StandardContext::with_trap_info_factory(isolate, Arc::new(StandardTrapInfoFactory::new()))
+    }
+
+    pub fn with_trap_info_factory(isolate: Arc<Isolate>, trap_info_factory: Arc<dyn TrapInfoFactory>) -> StandardContext {
+        let slot_layout_token = isolate.create_slot_layout_token();
+        StandardContext {
+            isolate: isolate,
+            slot_layout_token: slot_layout_token,
+            allocation_region_id: None,
+            allocation_tag: None,
+            quota_id: None,
+            cancellation_token: None,
+            trap_depth: Cell::new(0),
+            trap_depth_limit: DEFAULT_TRAP_DEPTH_LIMIT,
+            recursion_depth: Cell::new(0),
+            new_born_region_ids: RefCell::new(HashMap::new()),
+            region_cache: RegionCache::new(),
+            trap_info_factory: trap_info_factory,
+            trap_info_pool: Arc::new(TrapInfoPool::new()),
+            listeners: Vec::new(),
+            access_policy: None,
+            scope: None
+        }
+    }
+
+    /// Create a child context with the same configuration
+    pub fn create_child_context(&self) -> StandardContext {
+        let mut context = StandardContext::with_trap_info_factory(self.isolate.clone(), self.trap_info_factory.clone());
+        context.allocation_region_id = self.allocation_region_id;
+        context.allocation_tag = self.allocation_tag.clone();
+        context.quota_id = self.quota_id;
+        context.cancellation_token = self.cancellation_token.clone();
+        context.trap_depth_limit = self.trap_depth_limit;
+        context.listeners = self.listeners.clone();
+        context.access_policy = self.access_policy.clone();
+        context.scope = Some(ContextScope::new());
+        context
+    }
+
+    pub fn is_child_context(&self) -> bool {
+        self.scope.is_some()
+    }
+
+    pub fn get_trap_info_factory(&self) -> &Arc<dyn TrapInfoFactory> {
+        &self.trap_info_factory
+    }
+
+    pub fn get_trap_info_pool(&self) -> &Arc<TrapInfoPool> {
+        &self.trap_info_pool
+    }
+
+    pub fn get_allocation_region_id(&self) -> Option<u32> {
+        self.allocation_region_id
+    }
+
+    /// Prefer the region to gain new slots, regions created by the context
+    /// will be used once the region could not gain slots quickly
+    pub fn set_allocation_region_id(&mut self, region_id: Option<u32>) {
+        self.allocation_region_id = region_id;
+    }
+
+    /// Tag slots gained by the context in allocation profiles of the
+    /// isolate
+    pub fn set_allocation_tag(&mut self, allocation_tag: Option<String>) {
+        self.allocation_tag = allocation_tag;
+    }
+
+    /// Count slots gained by the context against a quota of the isolate,
+    /// regions created by the context are assigned to the quota, and the
+    /// allocation region should be assigned to the quota too
+    pub fn set_quota_id(&mut self, quota_id: Option<u64>) {
+        self.quota_id = quota_id;
+        self.new_born_region_ids.borrow_mut().clear();
+    }
+
+    /// Cancel long operations of the context by the token, operations fail
+    /// with `Interrupted` once the token cancelled
+    pub fn set_cancellation_token(&mut self, cancellation_token: Option<CancellationToken>) {
+        self.cancellation_token = cancellation_token;
+    }
+
+    /// Limit traps nested in the context, such as slot traps reentering
+    /// their own subjects, traps nested deeper fail with `TrapDepthExceeded`
+    /// instead of overflowing the stack, 256 by default
+    pub fn set_trap_depth_limit(&mut self, trap_depth_limit: u32) {
+        self.trap_depth_limit = trap_depth_limit;
+    }
+
+    /// Get count of traps currently nested in the context
+    pub fn get_trap_depth(&self) -> u32 {
+        self.trap_depth.get()
+    }
+
+    pub fn add_listener(&mut self, listener: Arc<dyn ContextListener>) {
+        self.listeners.push(listener);
+    }
+
+    pub fn set_access_policy(&mut self, access_policy: Option<Arc<dyn AccessPolicy>>) {
+        self.access_policy = access_policy;
+    }
+
+    /// Get the region to gain a slot of the primitive type, values are
+    /// gained in separate regions according to the region affinity of
+    /// their primitive types
+    fn get_new_born_region_id(&self, primitive_type: PrimitiveType) -> Result<u32, Error> {
+
+        if let Some(region_id) = self.allocation_region_id {
+            if self.isolate.could_region_gain_slot_quickly(region_id) {
+                return Ok(region_id);
+            }
+        }
+
+        let affinity = self.isolate.get_region_affinity(primitive_type);
+
+        let region_id = self.new_born_region_ids.borrow().get(&affinity).copied();
+        if let Some(region_id) = region_id {
+            if self.isolate.could_region_gain_slot_quickly(region_id) {
+                return Ok(region_id);
+            }
+        }
+
+        let region_id = match self.quota_id {
+            Some(quota_id) => self.isolate.create_region_for_quota(quota_id)?,
+            None => self.isolate.create_region()?
+        };
+        self.new_born_region_ids.borrow_mut().insert(affinity, region_id);
+
+        Ok(region_id)
+
+    }
+
+}
+
+impl Context for StandardContext {
+
+    fn get_isolate<'a>(&'a self) -> &'a Arc<Isolate> {
+        &self.isolate
+    }
+
+    fn get_slot_layout_token<'a>(&'a self) -> &'a ReentrantToken {
+        &self.slot_layout_token
+    }
+
+    fn get_access_policy(&self) -> Option<&Arc<dyn AccessPolicy>> {
+        self.access_policy.as_ref()
+    }
+
+    fn get_region_cache(&self) -> Option<&RegionCache> {
+        Some(&self.region_cache)
+    }
+
+    fn get_allocation_tag(&self) -> Option<&str> {
+        self.allocation_tag.as_deref()
+    }
+
+    fn get_quota_id(&self) -> Option<u64> {
+        self.quota_id
+    }
+
+    fn get_cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    fn enter_trap(&self) -> Result<(), Error> {
+        let trap_depth = self.trap_depth.get();
+        if trap_depth >= self.trap_depth_limit {
+            return Err(Error::new(TrapDepthExceeded, "Traps nested too deep"));
+        }
+        self.enter_recursion()?;
+        self.trap_depth.set(trap_depth + 1);
+        Ok(())
+    }
+
+    fn leave_trap(&self) {
+        self.trap_depth.set(self.trap_depth.get().saturating_sub(1));
+        self.leave_recursion();
+    }
+
+    fn enter_recursion(&self) -> Result<(), Error> {
+        let recursion_depth = self.recursion_depth.get();
+        if recursion_depth >= self.isolate.get_recursion_limit() {
+            return Err(Error::new(RecursionLimitExceeded, "Operations nested too deep"));
+        }
+        self.recursion_depth.set(recursion_depth + 1);
+        Ok(())
+    }
+
+    fn leave_recursion(&self) {
+        self.recursion_depth.set(self.recursion_depth.get().saturating_sub(1));
+    }
+
+    fn add_value_reference(&self, from: Value, to: Value) -> Result<(), Error> {
+        self.isolate.add_value_reference(from, to, &self.slot_layout_token)?;
+        for listener in self.listeners.iter() {
+            listener.notify_value_reference_added(from, to);
+        }
+        Ok(())
+    }
+
+    fn remove_value_reference(&self, from: Value, to: Value) -> Result<(), Error> {
+        self.isolate.remove_value_reference(from, to, &self.slot_layout_token)?;
+        for listener in self.listeners.iter() {
+            listener.notify_value_reference_removed(from, to);
+        }
+        Ok(())
+    }
+
+    fn update_value_references(&self, from: Value, added: &[Value], removed: &[Value]) -> Result<(), Error> {
+        self.isolate.update_value_references(from, added, removed, &self.slot_layout_token)?;
+        for listener in self.listeners.iter() {
+            for to in added.iter() {
+                listener.notify_value_reference_added(from, *to);
+            }
+            for to in removed.iter() {
+                listener.notify_value_reference_removed(from, *to);
+            }
+        }
+        Ok(())
+    }
+
+    fn create_trap_info(&self, subject: Value, parameters: Vec<Value>, context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        for listener in self.listeners.iter() {
+            listener.notify_trap_info_created(subject);
+        }
+        self.trap_info_factory.create_trap_info(subject, parameters, context)
+    }
+
+    fn acquire_trap_info(&self, subject: Value, parameters: &[Value], context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        for listener in self.listeners.iter() {
+            listener.notify_trap_info_created(subject);
+        }
+        self.trap_info_factory.create_pooled_trap_info(subject, parameters, &self.trap_info_pool, context)
+    }
+
+    #[cfg_attr(feature = "provenance", track_caller)]
+    fn gain_slot(&self, primitive_type: PrimitiveType, prototype: Value) -> Result<Value, Error> {
+
+        match self.isolate.get_gc_stress_mode() {
+            GcStressMode::Disabled => {},
+            GcStressMode::Collect => {
+                Collector::new(&self.isolate).collect_for_stress(false)?;
+            },
+            GcStressMode::CollectAndCompact => {
+                Collector::new(&self.isolate).collect_for_stress(true)?;
+            }
+        }
+
+        let region_id = self.get_new_born_region_id(primitive_type)?;
+
+        let value = self.isolate.gain_tagged_slot(region_id, primitive_type, prototype, self.get_allocation_tag(), &self.slot_layout_token)?;
+        for listener in self.listeners.iter() {
+            listener.notify_slot_gained(value);
+        }
+
+        Ok(value)
+
+    }
+
+    fn make_property_trap_value(&self, property_trap: Arc<dyn PropertyTrap>, context: &Box<dyn Context>) -> Result<Value, Error> {
+
+        let value = self.gain_slot(PrimitiveType::Object, self.isolate.get_object_prototype())?;
+
+        let property_trap_slot: Arc<dyn InternalSlot> = Arc::new(PropertyTrapSlot {
+            property_trap: property_trap
+        });
+
+        self.set_internal_slot(value, 0, property_trap_slot, context)?;
+
+        Ok(value)
+
+    }
+
+    fn extract_property_trap(&self, value: Value, context: &Box<dyn Context>) -> Result<Arc<dyn PropertyTrap>, Error> {
+
+        if let Some(internal_slot) = context.get_internal_slot(value, 0, context)? {
+            if let Some(property_trap_slot) = internal_slot.as_any().downcast_ref::<PropertyTrapSlot>() {
+                return Ok(property_trap_slot.property_trap.clone());
+            }
+        }
+
+        Err(Error::new(FatalError, "No property trap found"))
+
+    }
+
+    fn add_root(&self, value: Value) -> Result<Arc<Root>, Error> {
+        match &self.scope {
+            Some(scope) => {
+                let root = self.isolate.add_dedicated_root(value, &self.slot_layout_token)?;
+                scope.roots.borrow_mut().push(root.clone());
+                Ok(root)
+            },
+            None => self.isolate.add_local_root(value, &self.slot_layout_token)
+        }
+    }
+
+    fn create_child(&self) -> Box<dyn Context> {
+        Box::new(self.create_child_context())
+    }
+
+}
+
+impl Drop for StandardContext {
+    fn drop(&mut self) {
+        // A root failed to release is left to the isolate, panicking
+        // while dropping may abort the process
+        if let Some(scope) = &self.scope {
+            for root in scope.roots.borrow_mut().drain(..) {
+                let _ = self.isolate.release_root(&root);
+            }
+        }
+    }
+}
+
+#[cfg(test)] use core::sync::atomic::AtomicUsize;
+#[cfg(test)] use core::sync::atomic::Ordering;
+#[cfg(test)] use super::trap::StandardTrapInfo;
+#[cfg(test)] use super::isolate_options::IsolateOptions;
+#[cfg(test)] use super::storage::Pinned;
+#[cfg(test)] use super::test::PropertyTrapCall;
+#[cfg(test)] use super::test::RecordingDropListener;
+#[cfg(test)] use super::test::RecordingPropertyTrap;
+#[cfg(test)] use super::test::TestPropertyTrap;
+
+#[cfg(test)]
+struct CountingTrapInfoFactory {
+    count: AtomicUsize
+}
+
+#[cfg(test)]
+impl TrapInfoFactory for CountingTrapInfoFactory {
+
+    fn create_trap_info(&self, subject: Value, parameters: Vec<Value>, _context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Box::new(StandardTrapInfo::new(subject, parameters))
+    }
+
+}
+
+#[test]
+fn test_standard_context() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let symbol = context.get_text_symbol("test", "foo");
+    context.set_own_property(object, symbol, Value::make_integer(42), &context)?;
+    assert_eq!(context.get_own_property(object, symbol, None, &context)?.get_value(), Value::make_integer(42));
+
+    let text = context.make_text("bar", &context)?;
+    assert_eq!(context.extract_text(text.get_value(), &context)?, "bar");
+
+    let property_trap: Arc<dyn PropertyTrap> = Arc::new(TestPropertyTrap::new(Value::make_integer(7)));
+    let trap_value = context.make_property_trap_value(property_trap.clone(), &context)?;
+    assert!(Arc::ptr_eq(&context.extract_property_trap(trap_value, &context)?, &property_trap));
+    assert!(context.extract_property_trap(object, &context).is_err());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_recording_traps() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let symbol = context.get_text_symbol("test", "foo");
+    let property_trap = Arc::new(RecordingPropertyTrap::new(Value::make_integer(7)));
+    context.define_own_property(object, symbol, property_trap.clone(), &context)?;
+
+    assert_eq!(context.get_own_property(object, symbol, None, &context)?.get_value(), Value::make_integer(7));
+    context.set_own_property(object, symbol, Value::make_integer(8), &context)?;
+    assert_eq!(property_trap.get_value(), Value::make_integer(8));
+    assert_eq!(property_trap.list_calls(), vec![
+        PropertyTrapCall::Get { subject: object, symbol: symbol },
+        PropertyTrapCall::Set { subject: object, symbol: symbol, value: Value::make_integer(8) }
+    ]);
+    assert_eq!(property_trap.count_gets(), 1);
+    assert_eq!(property_trap.count_sets(), 1);
+    property_trap.clear_calls();
+    assert!(property_trap.list_calls().is_empty());
+
+    let drop_listener = RecordingDropListener::new();
+    let weak_root = isolate.add_weak_root(object, Some(Box::new(drop_listener.clone())), context.get_slot_layout_token())?;
+    assert!(!drop_listener.is_dropped());
+    isolate.notify_slot_drop(object)?;
+    assert!(weak_root.is_dropped());
+    assert_eq!(drop_listener.count_drops(), 1);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_trap_info_pool() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let standard_context = StandardContext::new(isolate.clone());
+    let trap_info_pool = standard_context.get_trap_info_pool().clone();
+    let context: Box<dyn Context> = Box::new(standard_context);
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let symbol = context.get_text_symbol("test", "foo");
+    context.define_own_property(object, symbol, Arc::new(TestPropertyTrap::new(Value::make_integer(7))), &context)?;
+    assert_eq!(trap_info_pool.get_size(), 0);
+
+    for _ in 0 .. 3 {
+        assert_eq!(context.get_own_property(object, symbol, None, &context)?.get_value(), Value::make_integer(7));
+        assert_eq!(trap_info_pool.get_size(), 1);
+    }
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_trap_info_factory() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let trap_info_factory = Arc::new(CountingTrapInfoFactory { count: AtomicUsize::new(0) });
+
+    let context: Box<dyn Context> = Box::new(StandardContext::with_trap_info_factory(isolate.clone(), trap_info_factory.clone()));
+
+    let region_id = isolate.create_region()?;
+    let function = isolate.create_native_function(region_id, |args, _context| {
+        Ok(Value::make_integer(args.len() as i32))
+    }, &context)?;
+
+    let result = context.call(function, Value::make_null(), vec!(Value::make_null()), &context)?;
+    assert_eq!(result.get_value(), Value::make_integer(1));
+    assert_eq!(trap_info_factory.count.load(Ordering::SeqCst), 1);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_child() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let pinned_object = Pinned::new(&context, object)?;
+    assert_eq!(isolate.count_roots(), 1);
+
+    let symbol = context.get_text_symbol("test", "foo");
+
+    let leaked = {
+
+        let child = context.create_child();
+
+        let temporary = child.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+        let pinned_temporary = Pinned::new(&child, temporary)?;
+        let pinned_object_2 = Pinned::new(&child, object)?;
+        assert_eq!(isolate.count_roots(), 2);
+
+        child.set_own_property(temporary, symbol, object, &child)?;
+        drop(pinned_object_2);
+
+        let leaked = Pinned::new(&child, temporary)?;
+        drop(pinned_temporary);
+        assert_eq!(isolate.count_roots(), 2);
+
+        leaked
+
+    };
+
+    assert_eq!(isolate.count_roots(), 1);
+    drop(leaked);
+    assert_eq!(isolate.count_roots(), 1);
+
+    drop(pinned_object);
+    assert_eq!(isolate.count_roots(), 0);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_child_references() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let pinned_object = Pinned::new(&context, object)?;
+
+    let symbol = context.get_text_symbol("test", "foo");
+
+    let value = {
+        let child = context.create_child();
+        let value = child.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+        let _pinned_value = Pinned::new(&child, value)?;
+        child.set_own_property(object, symbol, value, &child)?;
+        value
+    };
+    assert_eq!(isolate.count_roots(), 1);
+
+    assert!(Collector::new(&isolate).collect_for_stress(false)?);
+    assert!(isolate.is_direct_value_alive(value, &context)?);
+
+    let moved = isolate.move_slot(value, isolate.create_region()?, &context)?;
+    assert_eq!(context.get_own_property(object, symbol, None, &context)?.get_value(), moved);
+
+    drop(pinned_object);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_region_affinities() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let object_2 = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let list = context.gain_slot(PrimitiveType::List, isolate.get_list_prototype())?;
+    let tuple = context.gain_slot(PrimitiveType::Tuple, isolate.get_tuple_prototype())?;
+    let text = context.gain_slot(PrimitiveType::Text, isolate.get_text_prototype())?;
+    assert_eq!(object.get_region_id()?, object_2.get_region_id()?);
+    assert_eq!(list.get_region_id()?, tuple.get_region_id()?);
+    assert_ne!(object.get_region_id()?, list.get_region_id()?);
+    assert_ne!(object.get_region_id()?, text.get_region_id()?);
+    assert_ne!(list.get_region_id()?, text.get_region_id()?);
+
+    let options = IsolateOptions::new()
+        .without_region_affinities()
+        .region_affinity(PrimitiveType::Text, RegionAffinity::Text);
+    let isolate = Arc::new(Isolate::create_with_options(options)?);
+    assert_eq!(isolate.get_region_affinity(PrimitiveType::List), RegionAffinity::General);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let list = context.gain_slot(PrimitiveType::List, isolate.get_list_prototype())?;
+    let text = context.gain_slot(PrimitiveType::Text, isolate.get_text_prototype())?;
+    assert_eq!(object.get_region_id()?, list.get_region_id()?);
+    assert_ne!(object.get_region_id()?, text.get_region_id()?);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_allocation_profile() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let mut standard_context = StandardContext::new(isolate.clone());
+    standard_context.set_allocation_tag(Some(String::from("widgets")));
+    let context: Box<dyn Context> = Box::new(standard_context);
+
+    assert!(!isolate.is_allocation_sampling());
+    context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    assert!(isolate.allocation_profile().list_sites().is_empty());
+
+    isolate.start_allocation_sampling(3);
+    let mut objects = Vec::new();
+    for _ in 0 .. 9 {
+        objects.push(context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?);
+    }
+    let text = context.gain_slot(PrimitiveType::Text, isolate.get_text_prototype())?;
+    isolate.stop_allocation_sampling();
+
+    let profile = isolate.allocation_profile();
+    assert_eq!(profile.count_samples(), 3);
+    let sites = profile.list_sites();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].get_primitive_type(), PrimitiveType::Object);
+    assert_eq!(sites[0].get_region_id(), objects[0].get_region_id()?);
+    assert_eq!(sites[0].get_tag(), Some("widgets"));
+    assert_ne!(sites[0].get_region_id(), text.get_region_id()?);
+
+    isolate.reset_allocation_profile();
+    assert_eq!(isolate.allocation_profile().count_samples(), 0);
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_gc_stress() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let garbage = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let rooted = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let _root = isolate.add_root(rooted, context.get_slot_layout_token())?;
+    for value in [garbage, rooted].iter() {
+        isolate.move_value_out_from_nursery(*value, context.get_slot_layout_token())?;
+    }
+    let weak_root = isolate.add_weak_root(garbage, None, context.get_slot_layout_token())?;
+    let rooted_weak_root = isolate.add_weak_root(rooted, None, context.get_slot_layout_token())?;
+
+    context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    assert!(!weak_root.is_dropped());
+
+    isolate.set_gc_stress_mode(GcStressMode::Collect);
+    assert_eq!(isolate.get_gc_stress_mode(), GcStressMode::Collect);
+    let value = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    assert!(weak_root.is_dropped());
+    assert!(!rooted_weak_root.is_dropped());
+
+    isolate.set_gc_stress_mode(GcStressMode::CollectAndCompact);
+    context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    assert!(isolate.is_direct_value_alive(value, &context)?);
+    assert!(!rooted_weak_root.is_dropped());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_quota() -> Result<(), Error> {
+
+    use super::quota::Quota;
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    isolate.set_quota(7, Quota::new().max_live_slots(3).max_regions(1));
+
+    let mut context = StandardContext::new(isolate.clone());
+    context.set_quota_id(Some(7));
+    let context: Box<dyn Context> = Box::new(context);
+    assert_eq!(context.get_quota_id(), Some(7));
+
+    let mut objects = Vec::new();
+    for _ in 0..3 {
+        objects.push(context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?);
+    }
+    assert_eq!(isolate.get_region_quota_id(objects[0].get_region_id()?), Some(7));
+
+    let error = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype()).unwrap_err();
+    assert!(matches!(error.get_error_type(), QuotaExceeded));
+
+    let error = context.gain_slot(PrimitiveType::List, isolate.get_list_prototype()).unwrap_err();
+    assert!(matches!(error.get_error_type(), QuotaExceeded));
+
+    let usage = isolate.get_quota_usage(7).unwrap();
+    assert_eq!(usage.get_live_slots(), 3);
+    assert_eq!(usage.get_regions(), 1);
+
+    isolate.set_quota(7, Quota::new().max_live_slots(5).max_regions(2));
+    context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    context.gain_slot(PrimitiveType::List, isolate.get_list_prototype())?;
+    assert_eq!(isolate.get_quota_usage(7).unwrap().get_regions(), 2);
+
+    let unlimited: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+    unlimited.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+
+    isolate.remove_quota(7)?;
+    assert!(isolate.get_quota_usage(7).is_none());
+    context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_cancellation() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let token = CancellationToken::new();
+
+    let mut context = StandardContext::new(isolate.clone());
+    context.set_cancellation_token(Some(token.clone()));
+    let child: Box<dyn Context> = Box::new(context.create_child_context());
+    let context: Box<dyn Context> = Box::new(context);
+
+    let region_id = isolate.create_region()?;
+    let list = isolate.parse_json("[1, [2, 3], {\"a\": 4}]", region_id, &context)?;
+
+    assert!(!isolate.list_property_symbols(list.get_value(), &context)?.is_empty());
+    assert!(isolate.to_json(list.get_value(), &context).is_ok());
+
+    token.cancel();
+
+    let error = isolate.list_property_symbols(list.get_value(), &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), Interrupted));
+    let error = isolate.to_json(list.get_value(), &child).unwrap_err();
+    assert!(matches!(error.get_error_type(), Interrupted));
+    assert!(isolate.parse_json("[1]", region_id, &context).is_err());
+
+    token.reset();
+    assert!(isolate.to_json(list.get_value(), &context).is_ok());
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_trap_depth() -> Result<(), Error> {
+
+    use super::base::Symbol;
+    use super::trap::SlotTrap;
+    use super::trap::SlotTrapResult;
+
+    struct ReenteringSlotTrap {
+        symbol: Symbol
+    }
+
+    impl SlotTrap for ReenteringSlotTrap {
+        fn get_own_property(&self, trap_info: Box<dyn TrapInfo>, context: &Box<dyn Context>) -> Result<SlotTrapResult, Error> {
+            let value = context.get_own_property(trap_info.get_subject(), self.symbol, None, context)?;
+            Ok(SlotTrapResult::Trapped(value))
+        }
+    }
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let mut standard_context = StandardContext::new(isolate.clone());
+    standard_context.set_trap_depth_limit(8);
+    standard_context.enter_trap()?;
+    assert_eq!(standard_context.get_trap_depth(), 1);
+    standard_context.leave_trap();
+    assert_eq!(standard_context.get_trap_depth(), 0);
+    let child = standard_context.create_child_context();
+    assert_eq!(child.trap_depth_limit, 8);
+    let context: Box<dyn Context> = Box::new(standard_context);
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let symbol = context.get_text_symbol("test", "foo");
+    context.set_slot_trap(object, Arc::new(ReenteringSlotTrap { symbol: symbol }), &context)?;
+
+    let error = context.get_own_property(object, symbol, None, &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), TrapDepthExceeded));
+
+    let other = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?;
+    context.define_own_property(other, symbol, Arc::new(TestPropertyTrap::new(Value::make_integer(7))), &context)?;
+    assert_eq!(context.get_own_property(other, symbol, None, &context)?.get_value(), Value::make_integer(7));
+
+    Ok(())
+
+}
+
+#[test]
+fn test_standard_context_recursion_limit() -> Result<(), Error> {
+
+    use super::describe::DescribeOptions;
+
+    let isolate = Arc::new(Isolate::create()?);
+    isolate.set_recursion_limit(3);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let region_id = isolate.create_region()?;
+    let error = isolate.parse_json("[[[[1]]]]", region_id, &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), RecursionLimitExceeded));
+
+    let list = isolate.parse_json("[[[1]]]", region_id, &context)?;
+    assert_eq!(isolate.to_json(list.get_value(), &context)?, "[[[1]]]");
+
+    let options = DescribeOptions::new();
+
+    isolate.set_recursion_limit(2);
+    let error = isolate.to_json(list.get_value(), &context).unwrap_err();
+    assert!(matches!(error.get_error_type(), RecursionLimitExceeded));
+    assert_eq!(isolate.describe_value(list.get_value(), &context, &options), "[[[...]]]");
+
+    isolate.set_recursion_limit(3);
+    assert_eq!(isolate.to_json(list.get_value(), &context)?, "[[[1]]]");
+
+    Ok(())
+
+}
+
+#[cfg(feature = "provenance")]
+#[test]
+fn test_standard_context_allocation_sites() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(StandardContext::new(isolate.clone()));
+
+    let object = context.gain_slot(PrimitiveType::Object, isolate.get_object_prototype())?; let line = line!();
+
+    let site = isolate.get_allocation_site(object).unwrap();
+    assert_eq!(site.get_location().file(), file!());
+    assert_eq!(site.get_location().line(), line);
+
+    Ok(())
+
+}
@@ -1,5 +1,6 @@
-use std::collections::HashSet;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
 use super::super::base::Error;
 use super::super::base::ErrorType::*;
@@ -97,17 +98,17 @@ impl<'a> Local<'a> {
     }
 
     /// Define own property of a value
-    pub fn define_own_property(&self, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>) -> Result<(), Error> {
+    pub fn define_own_property(&self, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>) -> Result<bool, Error> {
         self.context.define_own_property(self.root.get_value(), symbol, property_trap, self.context)
     }
 
     /// List own property symbols in a value
-    pub fn list_own_property_symbols(&self) -> Result<HashSet<Symbol>, Error> {
+    pub fn list_own_property_symbols(&self) -> Result<Vec<Symbol>, Error> {
         self.context.list_own_property_symbols(self.root.get_value(), self.context)
     }
 
     /// List property symbols in a value
-    pub fn list_property_symbols(&self) -> Result<HashSet<Symbol>, Error> {
+    pub fn list_property_symbols(&self) -> Result<Vec<Symbol>, Error> {
         self.context.list_property_symbols(self.root.get_value(), self.context)
     }
 
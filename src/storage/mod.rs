@@ -4,6 +4,8 @@ mod pinned;
 mod weak;
 
 pub use local::Local;
+pub use pinned::PinGuard;
 pub use pinned::Pinned;
 pub use persistent::Persistent;
+pub use persistent::PersistentQueue;
 pub use weak::Weak;
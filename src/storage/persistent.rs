@@ -1,16 +1,79 @@
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
 
 use super::super::base::Error;
 use super::super::base::ErrorType::*;
 use super::super::context::Context;
-use super::super::isolate::Isolate;
 use super::super::storage::Local;
-use super::super::root::Root;
+use super::super::util::LockClass;
+use super::super::util::SpinLock;
 
-/// Persistent record of object
+/// Queue of persistents dropped from any thread, drained by the thread
+/// owning the isolate to remove their roots
+pub struct PersistentQueue {
+    next_id: AtomicU64,
+    spin_lock: SpinLock,
+    dropped_ids: UnsafeCell<Vec<u64>>
+}
+
+unsafe impl Send for PersistentQueue {}
+unsafe impl Sync for PersistentQueue {}
+
+impl PersistentQueue {
+
+    pub fn new() -> PersistentQueue {
+        PersistentQueue {
+            next_id: AtomicU64::new(1),
+            spin_lock: SpinLock::with_class(LockClass::Root),
+            dropped_ids: UnsafeCell::new(Vec::new())
+        }
+    }
+
+    pub fn generate_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn push_dropped_id(&self, id: u64) {
+
+        let _guard = self.spin_lock.lock();
+
+        unsafe { &mut *self.dropped_ids.get() }.push(id);
+
+    }
+
+    /// Take IDs of persistents dropped since the last drain
+    pub fn drain_dropped_ids(&self) -> Vec<u64> {
+
+        let _guard = self.spin_lock.lock();
+
+        core::mem::take(unsafe { &mut *self.dropped_ids.get() })
+
+    }
+
+    /// Count persistents dropped but not drained yet
+    pub fn count_dropped_ids(&self) -> usize {
+
+        let _guard = self.spin_lock.lock();
+
+        unsafe { &*self.dropped_ids.get() }.len()
+
+    }
+
+}
+
+/// Persistent record of object, which could be sent to and dropped in other
+/// threads
+///
+/// The root of the value is kept by the isolate, a persistent dropped only
+/// queues its ID, and the root is removed once the owner thread of the
+/// isolate drains the queue, such as before a collection marks roots
 pub struct Persistent {
-    isolate: Arc<Isolate>,
-    root: Arc<Root>
+    id: u64,
+    queue: Arc<PersistentQueue>
 }
 
 impl Persistent {
@@ -18,24 +81,30 @@ impl Persistent {
     /// Create persistent from a local object
     pub fn from_local<'a>(local: &Local<'a>) -> Result<Persistent, Error> {
 
-        let isolate = local.get_isolate().clone();
-        let root = isolate.add_root(local.get_value(), local.get_slot_layout_token())?;
+        let isolate = local.get_isolate();
+        let id = isolate.add_persistent(local.get_value(), local.get_slot_layout_token())?;
 
         Ok(Persistent {
-            isolate: isolate,
-            root: root
+            id: id,
+            queue: isolate.get_persistent_queue().clone()
         })
 
     }
 
-    /// Create local object
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    /// Create local object, the context should be of the isolate owning the
+    /// persistent
     pub fn to_local<'a>(&self, context: &'a Box<dyn Context>) -> Result<Local<'a>, Error> {
 
-        if !Arc::ptr_eq(context.get_isolate(), &self.isolate) {
+        let isolate = context.get_isolate();
+        if !Arc::ptr_eq(isolate.get_persistent_queue(), &self.queue) {
             return Err(Error::new(FatalError, "Invalid context with different isolate"));
         }
 
-        Local::new(context, self.root.get_value())
+        Local::new(context, isolate.get_persistent_value(self.id)?)
 
     }
 
@@ -43,11 +112,34 @@ impl Persistent {
 
 impl Drop for Persistent {
     fn drop(&mut self) {
-        match self.isolate.remove_root(&self.root) {
-            Err(_) => {
-                panic!("Failed to drop root");
-            },
-            _ => {}
-        }
+        self.queue.push_dropped_id(self.id);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_persistent_queue() {
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Persistent>();
+
+    let queue = Arc::new(PersistentQueue::new());
+
+    let id = queue.generate_id();
+    let id_2 = queue.generate_id();
+    assert_ne!(id, id_2);
+
+    let thread = {
+        let queue = queue.clone();
+        std::thread::spawn(move || queue.push_dropped_id(id_2))
+    };
+    thread.join().unwrap();
+
+    queue.push_dropped_id(id);
+    assert_eq!(queue.count_dropped_ids(), 2);
+
+    let mut dropped_ids = queue.drain_dropped_ids();
+    dropped_ids.sort();
+    assert_eq!(dropped_ids, vec!(id, id_2));
+    assert_eq!(queue.count_dropped_ids(), 0);
+
+}
@@ -1,8 +1,9 @@
-use std::fmt;
+use alloc::boxed::Box;
+use core::fmt;
 
-use std::ops::Deref;
-use std::ptr::NonNull;
-use std::sync::Arc;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use alloc::sync::Arc;
 
 use super::super::base::Error;
 use super::super::base::Symbol;
@@ -102,3 +103,73 @@ impl Deref for Pinned {
         &self.value
     }
 }
+
+/// Guard pinning a value with a dedicated root, the value is unpinned exactly
+/// once, either by `unpin` or when the guard drops, even across early
+/// returns
+pub struct PinGuard<'a> {
+    context: &'a Box<dyn Context>,
+    root: Option<Arc<Root>>,
+    value: Value
+}
+
+impl<'a> PinGuard<'a> {
+
+    /// Pin a value until the guard unpinned or dropped, values not slotted
+    /// are kept without pinning
+    pub fn new(context: &'a Box<dyn Context>, value: Value) -> Result<PinGuard<'a>, Error> {
+
+        let root = match value.is_slotted() {
+            true => Some(context.get_isolate().add_dedicated_root(value, context.get_slot_layout_token())?),
+            false => None
+        };
+
+        Ok(PinGuard {
+            context: context,
+            root: root,
+            value: value
+        })
+
+    }
+
+    /// Get the value pinned, refreshed if its slot moved
+    pub fn get_value(&self) -> Value {
+        match &self.root {
+            Some(root) => root.get_value(),
+            None => self.value
+        }
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.root.is_some()
+    }
+
+    /// Unpin the value before the guard drops, reporting failures instead of
+    /// panicking
+    pub fn unpin(mut self) -> Result<(), Error> {
+
+        match self.root.take() {
+            Some(root) => self.context.remove_root(&root),
+            None => Ok(())
+        }
+
+    }
+
+}
+
+impl<'a> Drop for PinGuard<'a> {
+    fn drop(&mut self) {
+        if let Some(root) = self.root.take() {
+            if self.context.remove_root(&root).is_err() {
+                panic!("Failed to unpin value");
+            }
+        }
+    }
+}
+
+impl<'a> Deref for PinGuard<'a> {
+    type Target = Value;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
 
 use super::super::base::Error;
 use super::super::base::ErrorType::*;
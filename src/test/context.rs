@@ -11,6 +11,7 @@ use super::super::base::Value;
 use super::super::context::Context;
 use super::super::internal_slot::InternalSlot;
 use super::super::isolate::Isolate;
+use super::super::region_cache::RegionCache;
 use super::super::trap::PropertyTrap;
 use super::super::trap::TrapInfo;
 use super::super::util::ReentrantToken;
@@ -86,6 +87,10 @@ impl Context for TestContext {
         &self.slot_layout_token
     }
 
+    fn create_child(&self) -> Box<dyn Context> {
+        Box::new(TestContext::new(self.isolate.clone()))
+    }
+
     // The implementation has no considering GC
     fn add_value_reference(&self, _from: Value, _to: Value) -> Result<(), Error> {
         Ok(())
@@ -94,6 +99,10 @@ impl Context for TestContext {
     fn remove_value_reference(&self, _from: Value, _to: Value) -> Result<(), Error> {
         Ok(())
     }
+
+    fn update_value_references(&self, _from: Value, _added: &[Value], _removed: &[Value]) -> Result<(), Error> {
+        Ok(())
+    }
     
     fn add_symbol_reference(&self, _symbol: Symbol) -> Result<(), Error> {
         Ok(())
@@ -151,7 +160,8 @@ pub struct TestContext2 {
     new_born_region_ready: Cell<bool>,
     new_born_region_id: Cell<u32>,
     isolate: Arc<Isolate>,
-    slot_layout_token: ReentrantToken
+    slot_layout_token: ReentrantToken,
+    region_cache: RegionCache
 }
 
 impl TestContext2 {
@@ -163,6 +173,7 @@ impl TestContext2 {
             new_born_region_id: Cell::new(0),
             isolate: isolate,
             slot_layout_token: layout_token,
+            region_cache: RegionCache::new()
         }
     }
 
@@ -194,6 +205,14 @@ impl Context for TestContext2 {
         &self.slot_layout_token
     }
 
+    fn create_child(&self) -> Box<dyn Context> {
+        Box::new(TestContext2::new(self.isolate.clone()))
+    }
+
+    fn get_region_cache(&self) -> Option<&RegionCache> {
+        Some(&self.region_cache)
+    }
+
     fn gain_slot(&self, primitive_type: PrimitiveType, prototype: Value) -> Result<Value, Error> {
 
         self.ensure_new_born_region()?;
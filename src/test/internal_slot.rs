@@ -53,20 +53,13 @@ impl InternalSlot for TestInternalSlot {
 
     fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
 
-        {
-            let _guard = self.rw_lock.lock_read();
-            if self.reference.get() != old_value {
-                return;
-            }
+        let guard = self.rw_lock.lock_upgradeable_read();
+        if self.reference.get() != old_value {
+            return;
         }
 
-        {
-            let _guard = self.rw_lock.lock_write();
-            if self.reference.get() != old_value {
-                return;
-            }
-            self.reference.set(new_value);
-        }
+        let _guard = guard.upgrade();
+        self.reference.set(new_value);
 
     }
 }
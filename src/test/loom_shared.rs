@@ -0,0 +1,28 @@
+use core::ops::Deref;
+
+/// Wrapper sending values not thread safe into loom threads, accesses of
+/// the values should be guarded by the locks explored
+pub struct LoomShared<T> {
+    value: T
+}
+
+unsafe impl<T> Send for LoomShared<T> {}
+unsafe impl<T> Sync for LoomShared<T> {}
+
+impl<T> LoomShared<T> {
+    pub fn new(value: T) -> LoomShared<T> {
+        LoomShared {
+            value: value
+        }
+    }
+}
+
+impl<T> Deref for LoomShared<T> {
+
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+
+}
@@ -1,17 +1,23 @@
 mod context;
 mod drop_listener;
 mod internal_slot;
-mod page_item_factory;
+#[cfg(loom)] mod loom_shared;
+#[cfg(test)] mod page_item_factory;
 mod slot_trap;
 mod property_trap;
+mod recording;
 mod trap_info;
 
 pub use context::TestContext;
 pub use context::TestContext2;
 pub use drop_listener::TestDropListener;
 pub use internal_slot::TestInternalSlot;
-pub use page_item_factory::TestPageItemFactory;
+#[cfg(loom)] pub use loom_shared::LoomShared;
+#[cfg(test)] pub use page_item_factory::TestPageItemFactory;
 pub use slot_trap::TestSlotTrap;
 pub use slot_trap::TestSlotTrap2;
 pub use property_trap::TestPropertyTrap;
+pub use recording::PropertyTrapCall;
+pub use recording::RecordingDropListener;
+pub use recording::RecordingPropertyTrap;
 pub use trap_info::TestTrapInfo;
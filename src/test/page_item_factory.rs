@@ -1,3 +1,4 @@
+use super::super::base::Error;
 use super::super::util::PageItemFactory;
 
 pub struct TestPageItemFactory {}
@@ -12,8 +13,8 @@ impl TestPageItemFactory {
 
 impl PageItemFactory<u32> for TestPageItemFactory {
 
-    fn create_item(&self, id: usize) -> Box<u32> {
-        Box::new(id as u32)
+    fn create_item(&self, id: usize) -> Result<Box<u32>, Error> {
+        Ok(Box::new(id as u32))
     }
 
 }
@@ -82,20 +82,13 @@ impl PropertyTrap for TestPropertyTrap {
 
     fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
 
-        {
-            let _guard = self.rw_lock.lock_read();
-            if self.value.get() != old_value {
-                return;
-            }
+        let guard = self.rw_lock.lock_upgradeable_read();
+        if self.value.get() != old_value {
+            return;
         }
 
-        {
-            let _guard = self.rw_lock.lock_write();
-            if self.value.get() != old_value {
-                return;
-            }
-            self.value.set(new_value);
-        }
+        let _guard = guard.upgrade();
+        self.value.set(new_value);
         
     }
     
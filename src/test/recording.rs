@@ -0,0 +1,184 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use super::super::base::Error;
+use super::super::base::Symbol;
+use super::super::base::Value;
+use super::super::context::Context;
+use super::super::root::DropListener;
+use super::super::storage::Pinned;
+use super::super::trap::PropertyTrap;
+use super::super::trap::TrapInfo;
+use super::super::util::RwLock;
+
+/// Call recorded by a `RecordingPropertyTrap`, the subject is the value
+/// the property accessed from, which may inherit the trapped object
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PropertyTrapCall {
+    Get { subject: Value, symbol: Symbol },
+    Set { subject: Value, symbol: Symbol, value: Value }
+}
+
+/// Property trap holding a value, and recording all calls of getting and
+/// setting the property in order
+pub struct RecordingPropertyTrap {
+    rw_lock: RwLock,
+    value: Cell<Value>,
+    calls: RefCell<Vec<PropertyTrapCall>>
+}
+
+impl RecordingPropertyTrap {
+
+    pub fn new(value: Value) -> RecordingPropertyTrap {
+        RecordingPropertyTrap {
+            rw_lock: RwLock::new(),
+            value: Cell::new(value),
+            calls: RefCell::new(Vec::new())
+        }
+    }
+
+    pub fn get_value(&self) -> Value {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.value.get()
+
+    }
+
+    pub fn list_calls(&self) -> Vec<PropertyTrapCall> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.calls.borrow().clone()
+
+    }
+
+    pub fn count_gets(&self) -> usize {
+        self.list_calls().iter().filter(|call| matches!(call, PropertyTrapCall::Get { .. })).count()
+    }
+
+    pub fn count_sets(&self) -> usize {
+        self.list_calls().iter().filter(|call| matches!(call, PropertyTrapCall::Set { .. })).count()
+    }
+
+    pub fn clear_calls(&self) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.calls.borrow_mut().clear();
+
+    }
+
+}
+
+impl PropertyTrap for RecordingPropertyTrap {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_property(&self, trap_info: Box<dyn TrapInfo>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        self.calls.borrow_mut().push(PropertyTrapCall::Get {
+            subject: trap_info.get_parameter(0),
+            symbol: trap_info.get_parameter(1).try_extract_symbol()?
+        });
+
+        Pinned::new(context, self.value.get())
+
+    }
+
+    fn set_property(&self, trap_info: Box<dyn TrapInfo>, _context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Value>, Vec<Symbol>, Vec<Symbol>), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let value = trap_info.get_parameter(2);
+        self.calls.borrow_mut().push(PropertyTrapCall::Set {
+            subject: trap_info.get_parameter(0),
+            symbol: trap_info.get_parameter(1).try_extract_symbol()?,
+            value: value
+        });
+
+        let old_value = self.value.replace(value);
+        if old_value != value {
+            Ok((vec![old_value], vec![value], Vec::new(), Vec::new()))
+        } else {
+            Ok((Vec::new(), Vec::new(), Vec::new(), Vec::new()))
+        }
+
+    }
+
+    fn list_and_autorefresh_referenced_values(&self, self_id: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        let reference = self.value.get();
+        let new_reference = context.resolve_real_value(reference)?;
+        if reference != new_reference {
+            context.add_value_reference(self_id, new_reference)?;
+            self.value.set(new_reference);
+            context.remove_value_reference(self_id, reference)?;
+        }
+
+        Ok(vec![new_reference])
+
+    }
+
+    fn list_referenced_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        vec![self.value.get()]
+
+    }
+
+    fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if self.value.get() == old_value {
+            self.value.set(new_value);
+        }
+
+    }
+
+}
+
+/// Drop listener counting drops, clones share the same count so one could
+/// be kept to check the drops notified to the others
+#[derive(Clone)]
+pub struct RecordingDropListener {
+    drops: Arc<AtomicUsize>
+}
+
+impl RecordingDropListener {
+
+    pub fn new() -> RecordingDropListener {
+        RecordingDropListener {
+            drops: Arc::new(AtomicUsize::new(0))
+        }
+    }
+
+    pub fn count_drops(&self) -> usize {
+        self.drops.load(Ordering::SeqCst)
+    }
+
+    pub fn is_dropped(&self) -> bool {
+        self.count_drops() > 0
+    }
+
+}
+
+impl DropListener for RecordingDropListener {
+
+    fn notify_drop(&self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+
+}
@@ -270,7 +270,7 @@ impl SlotTrap for TestSlotTrap2 {
             context.remove_value_reference(self.subject.get(), value)?;
         }
 
-        Ok(SlotTrapResult::Trapped(Pinned::new(context, Value::make_undefined())?))
+        Ok(SlotTrapResult::Trapped(Pinned::new(context, Value::make_boolean(!defined))?))
     }
 
     fn delete_own_property(&self, 
@@ -307,7 +307,7 @@ impl SlotTrap for TestSlotTrap2 {
             context.remove_value_reference(self.subject.get(), *value)?;
         }
 
-        Ok(SlotTrapResult::Trapped(Pinned::new(context, Value::make_undefined())?))
+        Ok(SlotTrapResult::Trapped(Pinned::new(context, Value::make_boolean(true))?))
 
     }
 
@@ -0,0 +1,21 @@
+//! Utilities for unit testing traps, internal slots and drop listeners of
+//! embedders, available with the `testing` feature
+//!
+//! * `TestContext` gains slots in new born regions without counting value
+//!   references, and `TestContext2` counts them for garbage collections
+//! * `RecordingPropertyTrap` records calls of getting and setting the
+//!   property, and `RecordingDropListener` counts drops notified
+//! * `TestSlotTrap`, `TestPropertyTrap` and `TestInternalSlot` are simple
+//!   implementations referencing a value
+
+pub use super::test::PropertyTrapCall;
+pub use super::test::RecordingDropListener;
+pub use super::test::RecordingPropertyTrap;
+pub use super::test::TestContext;
+pub use super::test::TestContext2;
+pub use super::test::TestDropListener;
+pub use super::test::TestInternalSlot;
+pub use super::test::TestPropertyTrap;
+pub use super::test::TestSlotTrap;
+pub use super::test::TestSlotTrap2;
+pub use super::test::TestTrapInfo;
@@ -0,0 +1,254 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use super::base::Error;
+use super::base::Symbol;
+use super::base::Value;
+use super::context::Context;
+use super::internal_slot::InternalSlot;
+use super::internal_slot::ProtectedInternalSlot;
+use super::storage::Pinned;
+use super::trap::PropertyTrap;
+use super::trap::ProtectedPropertyTrap;
+
+enum TransactionRecord<'a> {
+    Property {
+        subject: Pinned,
+        symbol: Symbol,
+        property_trap: Option<ProtectedPropertyTrap<'a>>
+    },
+    Prototype {
+        subject: Pinned,
+        prototype: Pinned
+    },
+    InternalSlot {
+        subject: Pinned,
+        index: u64,
+        internal_slot: Option<ProtectedInternalSlot<'a>>
+    }
+}
+
+/// Mutation scope recording an undo log of property, prototype and internal
+/// slot mutations
+///
+/// Mutations made through the transaction could be committed or rolled back
+/// as a whole, and a transaction dropped without commit will be rolled back.
+/// Own properties are restored ignoring slot traps, and internal slots are
+/// restored by reference, so mutations inside an internal slot are not
+/// recorded
+pub struct Transaction<'a> {
+    context: &'a Box<dyn Context>,
+    records: Vec<TransactionRecord<'a>>
+}
+
+impl<'a> Transaction<'a> {
+
+    pub fn new(context: &'a Box<dyn Context>) -> Transaction<'a> {
+        Transaction {
+            context: context,
+            records: Vec::new()
+        }
+    }
+
+    pub fn get_context(&self) -> &'a Box<dyn Context> {
+        self.context
+    }
+
+    /// Get count of recorded mutations
+    pub fn get_size(&self) -> usize {
+        self.records.len()
+    }
+
+    fn record_property(&mut self, subject: Value, symbol: Symbol) -> Result<(), Error> {
+
+        let context = self.context;
+
+        let property_trap = match context.get_isolate().get_own_property_trap(subject, symbol, context)? {
+            None => None,
+            Some(property_trap) => {
+                let snapshot = match property_trap.duplicate() {
+                    Some(snapshot) => snapshot,
+                    None => Arc::clone(&property_trap)
+                };
+                Some(ProtectedPropertyTrap::new(&snapshot, context)?)
+            }
+        };
+
+        self.records.push(TransactionRecord::Property {
+            subject: Pinned::new(context, subject)?,
+            symbol: symbol,
+            property_trap: property_trap
+        });
+
+        Ok(())
+
+    }
+
+    fn record_internal_slot(&mut self, subject: Value, index: u64) -> Result<(), Error> {
+
+        let context = self.context;
+
+        let internal_slot = context.get_isolate().get_internal_slot(subject, index, context)?;
+
+        self.records.push(TransactionRecord::InternalSlot {
+            subject: Pinned::new(context, subject)?,
+            index: index,
+            internal_slot: internal_slot
+        });
+
+        Ok(())
+
+    }
+
+    pub fn set_own_property(&mut self, subject: Value, symbol: Symbol, value: Value) -> Result<(), Error> {
+        self.record_property(subject, symbol)?;
+        self.context.set_own_property(subject, symbol, value, self.context)
+    }
+
+    pub fn define_own_property(&mut self, subject: Value, symbol: Symbol, property_trap: Arc<dyn PropertyTrap>) -> Result<bool, Error> {
+        self.record_property(subject, symbol)?;
+        self.context.define_own_property(subject, symbol, property_trap, self.context)
+    }
+
+    pub fn delete_own_property(&mut self, subject: Value, symbol: Symbol) -> Result<bool, Error> {
+        self.record_property(subject, symbol)?;
+        self.context.delete_own_property(subject, symbol, self.context)
+    }
+
+    pub fn set_prototype(&mut self, subject: Value, prototype: Value) -> Result<(), Error> {
+
+        let context = self.context;
+
+        let record = TransactionRecord::Prototype {
+            subject: Pinned::new(context, subject)?,
+            prototype: context.get_prototype(subject, context)?
+        };
+        self.records.push(record);
+
+        context.set_prototype(subject, prototype, context)
+
+    }
+
+    pub fn set_internal_slot(&mut self, subject: Value, index: u64, internal_slot: Arc<dyn InternalSlot>) -> Result<(), Error> {
+        self.record_internal_slot(subject, index)?;
+        self.context.set_internal_slot(subject, index, internal_slot, self.context)
+    }
+
+    pub fn clear_internal_slot(&mut self, subject: Value, index: u64) -> Result<(), Error> {
+        self.record_internal_slot(subject, index)?;
+        self.context.clear_internal_slot(subject, index, self.context)
+    }
+
+    /// Keep all mutations and release the undo log
+    pub fn commit(mut self) {
+        self.records.clear();
+    }
+
+    /// Revert all mutations in reverse order
+    pub fn rollback(mut self) -> Result<(), Error> {
+        self.revert()
+    }
+
+    fn revert(&mut self) -> Result<(), Error> {
+
+        let context = self.context;
+
+        let isolate = context.get_isolate();
+
+        while let Some(record) = self.records.pop() {
+            match record {
+                TransactionRecord::Property { subject, symbol, property_trap } => {
+                    let subject = subject.get_value();
+                    match property_trap {
+                        Some(property_trap) => {
+                            isolate.define_own_property_ignore_slot_trap(subject, subject, symbol, Arc::clone(&property_trap), context)?;
+                        },
+                        None => {
+                            if isolate.get_own_property_trap(subject, symbol, context)?.is_some() {
+                                isolate.delete_own_property_ignore_slot_trap(subject, subject, symbol, context)?;
+                            }
+                        }
+                    }
+                },
+                TransactionRecord::Prototype { subject, prototype } => {
+                    context.set_prototype(subject.get_value(), prototype.get_value(), context)?;
+                },
+                TransactionRecord::InternalSlot { subject, index, internal_slot } => {
+                    let subject = subject.get_value();
+                    match internal_slot {
+                        Some(internal_slot) => {
+                            isolate.set_internal_slot(subject, index, Arc::clone(&internal_slot), context)?;
+                        },
+                        None => {
+                            isolate.clear_internal_slot(subject, index, context)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+
+    }
+
+}
+
+impl<'a> Drop for Transaction<'a> {
+    fn drop(&mut self) {
+        if self.revert().is_err() {
+            panic!("Failed to rollback transaction");
+        }
+    }
+}
+
+#[cfg(test)] use super::base::PrimitiveType;
+#[cfg(test)] use super::internal_slot::List;
+#[cfg(test)] use super::isolate::Isolate;
+#[cfg(test)] use super::test::TestContext2;
+
+#[test]
+fn test_transaction() -> Result<(), Error> {
+
+    let isolate = Arc::new(Isolate::create()?);
+
+    let context: Box<dyn Context> = Box::new(TestContext2::new(isolate.clone()));
+
+    let foo = context.get_text_symbol("test", "foo");
+    let bar = context.get_text_symbol("test", "bar");
+
+    let object = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let _object = Pinned::new(&context, object)?;
+    let prototype = context.allocate(PrimitiveType::Object, isolate.get_object_prototype())?;
+    let _prototype = Pinned::new(&context, prototype)?;
+
+    context.set_own_property(object, foo, Value::make_integer(1), &context)?;
+
+    let mut transaction = isolate.begin_transaction(&context);
+    transaction.set_own_property(object, foo, Value::make_integer(2))?;
+    transaction.set_own_property(object, bar, prototype)?;
+    transaction.delete_own_property(object, foo)?;
+    transaction.set_prototype(object, prototype)?;
+    transaction.set_internal_slot(object, 0, Arc::new(List::new(object, Vec::new())))?;
+    assert_eq!(transaction.get_size(), 5);
+    assert!(!context.has_own_property(object, foo, &context)?);
+    transaction.rollback()?;
+
+    assert_eq!(context.get_own_property(object, foo, None, &context)?.get_value(), Value::make_integer(1));
+    assert!(!context.has_own_property(object, bar, &context)?);
+    assert!(context.get_internal_slot(object, 0, &context)?.is_none());
+
+    let mut transaction = isolate.begin_transaction(&context);
+    transaction.set_own_property(object, foo, Value::make_integer(3))?;
+    transaction.commit();
+    assert_eq!(context.get_own_property(object, foo, None, &context)?.get_value(), Value::make_integer(3));
+
+    {
+        let mut transaction = isolate.begin_transaction(&context);
+        transaction.set_own_property(object, foo, Value::make_integer(4))?;
+    }
+    assert_eq!(context.get_own_property(object, foo, None, &context)?.get_value(), Value::make_integer(3));
+
+    Ok(())
+
+}
@@ -0,0 +1,35 @@
+use alloc::vec::Vec;
+use core::any::Any;
+use alloc::sync::Arc;
+
+/// Packet of a value graph exported from an isolate
+///
+/// The packet is `Send`, so it could be moved to another thread and imported
+/// into another isolate. Symbols are remapped by their scopes and texts, and
+/// builtin prototypes are remapped to the builtins of the importing isolate.
+/// Host objects of transferable foreign internal slots are shared.
+pub struct TransferPacket {
+    data: Vec<u8>,
+    foreigns: Vec<Arc<dyn Any + Send + Sync>>
+}
+
+impl TransferPacket {
+
+    pub fn new(data: Vec<u8>, foreigns: Vec<Arc<dyn Any + Send + Sync>>) -> TransferPacket {
+        TransferPacket {
+            data: data,
+            foreigns: foreigns
+        }
+    }
+
+    /// Get the value graph encoded in CBOR
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get host objects of transferred foreign internal slots
+    pub fn get_foreigns(&self) -> &[Arc<dyn Any + Send + Sync>] {
+        &self.foreigns
+    }
+
+}
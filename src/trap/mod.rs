@@ -1,14 +1,24 @@
+mod native_function;
 mod property_trap;
 mod slot_trap;
 mod trap_info;
 
+pub use native_function::Args;
+pub use native_function::NativeFunction;
+
 pub use property_trap::PropertyTrap;
 pub use property_trap::ProtectedPropertyTrap;
 
+pub use property_trap::ConstPropertyTrap;
 pub use property_trap::FieldPropertyTrap;
 
 pub use slot_trap::SlotTrap;
 pub use slot_trap::SlotTrapResult;
 pub use slot_trap::ProtectedSlotTrap;
 
+pub use trap_info::PooledTrapInfo;
+pub use trap_info::StandardTrapInfo;
+pub use trap_info::StandardTrapInfoFactory;
 pub use trap_info::TrapInfo;
+pub use trap_info::TrapInfoFactory;
+pub use trap_info::TrapInfoPool;
@@ -0,0 +1,83 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use super::super::base::Error;
+use super::super::base::Value;
+use super::super::context::Context;
+use super::super::storage::Pinned;
+use super::super::trap::SlotTrap;
+use super::super::trap::SlotTrapResult;
+use super::super::trap::TrapInfo;
+
+/// Arguments of a native function call
+///
+/// All values are pinned, so they will be kept from garbage collection and
+/// memory refragment until the call finished.
+pub struct Args {
+    this: Pinned,
+    arguments: Vec<Pinned>
+}
+
+impl Args {
+
+    /// Get the `this` value of the call
+    pub fn get_this(&self) -> Value {
+        self.this.get_value()
+    }
+
+    /// Get arguments count
+    pub fn len(&self) -> usize {
+        self.arguments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arguments.is_empty()
+    }
+
+    /// Get argument at specified index, undefined will be returned if absent
+    pub fn get(&self, index: usize) -> Value {
+        match self.arguments.get(index) {
+            Some(argument) => argument.get_value(),
+            None => Value::make_undefined()
+        }
+    }
+
+}
+
+/// Slot trap making a value callable with a rust closure
+pub struct NativeFunction {
+    function: Box<dyn Fn(&Args, &Box<dyn Context>) -> Result<Value, Error>>
+}
+
+impl NativeFunction {
+
+    pub fn new<F>(function: F) -> NativeFunction where F: Fn(&Args, &Box<dyn Context>) -> Result<Value, Error> + 'static {
+        NativeFunction {
+            function: Box::new(function)
+        }
+    }
+
+}
+
+impl SlotTrap for NativeFunction {
+
+    fn call(&self, trap_info: Box<dyn TrapInfo>, context: &Box<dyn Context>) -> Result<SlotTrapResult, Error> {
+
+        let this = Pinned::new(context, trap_info.get_parameter(0))?;
+
+        let mut arguments = Vec::new();
+        for index in 1 .. trap_info.get_parameters_count() {
+            arguments.push(Pinned::new(context, trap_info.get_parameter(index))?);
+        }
+
+        let args = Args {
+            this: this,
+            arguments: arguments
+        };
+
+        let result = (self.function)(&args, context)?;
+
+        Ok(SlotTrapResult::Trapped(Pinned::new(context, result)?))
+
+    }
+
+}
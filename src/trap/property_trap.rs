@@ -1,7 +1,10 @@
-use std::any::Any;
-use std::sync::Arc;
-use std::cell::Cell;
-use std::ops::Deref;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use alloc::sync::Arc;
+use core::cell::Cell;
+use core::ops::Deref;
 
 use super::super::base::Error;
 use super::super::base::ErrorType::*;
@@ -52,6 +55,14 @@ pub trait PropertyTrap {
         false
     }
 
+    /// Check whether the property trap rejects sets, sets of constant
+    /// properties are rejected before trap infos acquired
+    ///
+    /// **Default** return `false`
+    fn is_constant(&self) -> bool {
+        false
+    }
+
     /// Get the property value with specified symbol from the object
     ///
     /// The `trap_info` object records the information of the object and symbol
@@ -96,6 +107,23 @@ pub trait PropertyTrap {
     fn refresh_referenced_value(&self, _old_value: Value, _new_value: Value) {
         // Do nothing
     }
+
+    /// Notify the property trap that its subject moved, property traps are
+    /// reused by the moved slot, so traps caching the subject could update
+    /// themselves
+    ///
+    /// **Default** do nothing
+    fn notify_subject_moved(&self, _old_subject: Value, _new_subject: Value) {
+        // Do nothing
+    }
+
+    /// Duplicate the property trap for a forked isolate
+    ///
+    /// **Default** return `None`, that means the property trap is shared
+    /// between the isolates
+    fn duplicate(&self) -> Option<Arc<dyn PropertyTrap>> {
+        None
+    }
     
 }
 
@@ -107,7 +135,14 @@ pub struct ProtectedPropertyTrap<'a> {
 
 impl<'a> ProtectedPropertyTrap<'a> {
     pub fn new(property_trap: &Arc<dyn PropertyTrap>, context: &'a Box<dyn Context>) -> Result<ProtectedPropertyTrap<'a>, Error> {
-        let (protected_id, property_trap) = context.protect_property_trap(property_trap)?;
+        context.enter_trap()?;
+        let (protected_id, property_trap) = match context.protect_property_trap(property_trap) {
+            Ok(protected) => protected,
+            Err(error) => {
+                context.leave_trap();
+                return Err(error);
+            }
+        };
         Ok(ProtectedPropertyTrap {
             context: context,
             property_trap: property_trap,
@@ -121,6 +156,7 @@ impl<'a> Drop for ProtectedPropertyTrap<'a> {
         if self.context.unprotect_property_trap(self.protected_id).is_err() {
             panic!("Failed to unprotect property trap");
         }
+        self.context.leave_trap();
     }
 }
 
@@ -155,6 +191,14 @@ impl PropertyTrap for FieldPropertyTrap {
         true
     }
 
+    fn duplicate(&self) -> Option<Arc<dyn PropertyTrap>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Some(Arc::new(FieldPropertyTrap::new(self.value.get())))
+
+    }
+
     fn get_property(&self, _trap_info: Box<dyn TrapInfo>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
 
         let _guard = self.rw_lock.lock_read();
@@ -181,14 +225,17 @@ impl PropertyTrap for FieldPropertyTrap {
 
     fn list_and_autorefresh_referenced_values(&self, self_id: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
  
-        let _guard = self.rw_lock.lock_read();
+        let guard = self.rw_lock.lock_upgradeable_read();
 
         let value = self.value.get();
         let new_value = context.resolve_real_value(value)?;
 
         if value != new_value {
             context.add_value_reference(self_id, new_value)?;    
-            self.value.set(new_value);
+            {
+                let _guard = guard.upgrade();
+                self.value.set(new_value);
+            }
             context.remove_value_reference(self_id, value)?;
         }
 
@@ -206,25 +253,156 @@ impl PropertyTrap for FieldPropertyTrap {
 
     fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
 
-        {
-            let _guard = self.rw_lock.lock_read();
-            if self.value.get() != old_value {
-                return;
-            }
+        let guard = self.rw_lock.lock_upgradeable_read();
+        if self.value.get() != old_value {
+            return;
         }
 
-        {
-            let _guard = self.rw_lock.lock_write();
-            if self.value.get() != old_value {
-                return;
-            }
-            self.value.set(new_value);
-        }
+        let _guard = guard.upgrade();
+        self.value.set(new_value);
         
     }
     
 }
 
+/// Property trap allowing one assignment, like a `const` or final binding,
+/// later sets are rejected with `MutatingConstantProperty`
+///
+/// The property is undefined until assigned, and is cached in field
+/// shortcuts as a simple field
+pub struct ConstPropertyTrap {
+    rw_lock: RwLock,
+    value: Cell<Value>,
+    assigned: Cell<bool>
+}
+
+impl ConstPropertyTrap {
+
+    /// Create a constant property to be assigned by the first set
+    pub fn new() -> ConstPropertyTrap {
+        ConstPropertyTrap {
+            rw_lock: RwLock::new(),
+            value: Cell::new(Value::make_undefined()),
+            assigned: Cell::new(false)
+        }
+    }
+
+    /// Create a constant property assigned already
+    pub fn with_value(value: Value) -> ConstPropertyTrap {
+        ConstPropertyTrap {
+            rw_lock: RwLock::new(),
+            value: Cell::new(value),
+            assigned: Cell::new(true)
+        }
+    }
+
+    pub fn is_assigned(&self) -> bool {
+
+        let _guard = self.rw_lock.lock_read();
+
+        self.assigned.get()
+
+    }
+
+}
+
+impl PropertyTrap for ConstPropertyTrap {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn is_simple_field(&self) -> bool {
+        true
+    }
+
+    fn is_constant(&self) -> bool {
+        self.is_assigned()
+    }
+
+    fn duplicate(&self) -> Option<Arc<dyn PropertyTrap>> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Some(Arc::new(ConstPropertyTrap {
+            rw_lock: RwLock::new(),
+            value: Cell::new(self.value.get()),
+            assigned: Cell::new(self.assigned.get())
+        }))
+
+    }
+
+    fn get_property(&self, _trap_info: Box<dyn TrapInfo>, context: &Box<dyn Context>) -> Result<Pinned, Error> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        Pinned::new(context, self.value.get())
+
+    }
+
+    fn set_property(&self, trap_info: Box<dyn TrapInfo>, _context: &Box<dyn Context>) -> Result<(Vec<Value>, Vec<Value>, Vec<Symbol>, Vec<Symbol>), Error> {
+
+        let _guard = self.rw_lock.lock_write();
+
+        if self.assigned.get() {
+            return Err(Error::new(MutatingConstantProperty, "Constant property assigned"));
+        }
+
+        let old_value = self.value.get();
+        let value = trap_info.get_parameter(2);
+        self.value.replace(value);
+        self.assigned.set(true);
+
+        if old_value != value {
+            Ok((vec!(old_value), vec!(value), Vec::new(), Vec::new()))
+        } else {
+            Ok((Vec::new(), Vec::new(), Vec::new(), Vec::new()))
+        }
+
+    }
+
+    fn list_and_autorefresh_referenced_values(&self, self_id: Value, context: &Box<dyn Context>) -> Result<Vec<Value>, Error> {
+
+        let guard = self.rw_lock.lock_upgradeable_read();
+
+        let value = self.value.get();
+        let new_value = context.resolve_real_value(value)?;
+
+        if value != new_value {
+            context.add_value_reference(self_id, new_value)?;
+            {
+                let _guard = guard.upgrade();
+                self.value.set(new_value);
+            }
+            context.remove_value_reference(self_id, value)?;
+        }
+
+        Ok(vec!(new_value))
+
+    }
+
+    fn list_referenced_values(&self) -> Vec<Value> {
+
+        let _guard = self.rw_lock.lock_read();
+
+        vec!(self.value.get())
+
+    }
+
+    fn refresh_referenced_value(&self, old_value: Value, new_value: Value) {
+
+        let guard = self.rw_lock.lock_upgradeable_read();
+        if self.value.get() != old_value {
+            return;
+        }
+
+        let _guard = guard.upgrade();
+        self.value.set(new_value);
+
+    }
+
+}
+
 // #[cfg(test)] use super::super::test::TestTrapInfo;
 
 // #[test]
@@ -1,5 +1,8 @@
-use std::ops::Deref;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Deref;
+use alloc::sync::Arc;
 
 use super::super::base::Error;
 use super::super::base::Symbol;
@@ -60,14 +63,16 @@ pub trait SlotTrap {
         Ok(SlotTrapResult::Skipped)
     }
 
-    /// Define own property of a value
+    /// Define own property of a value, the trapped value tells whether an
+    /// existing property trap was replaced
     fn define_own_property(&self, 
                            _trap_info: Box<dyn TrapInfo>, 
                            _context: &Box<dyn Context>) -> Result<SlotTrapResult, Error> {
         Ok(SlotTrapResult::Skipped)
     }
 
-    /// Delete own property from a value
+    /// Delete own property from a value, the trapped value tells whether the
+    /// property existed
     fn delete_own_property(&self, 
                            _trap_info: Box<dyn TrapInfo>, 
                            _context: &Box<dyn Context>) -> Result<SlotTrapResult, Error> {
@@ -81,6 +86,13 @@ pub trait SlotTrap {
         Ok(SlotTrapResult::Skipped)
     }
 
+    /// Call a slot as function, parameters start with the `this` value
+    fn call(&self, 
+            _trap_info: Box<dyn TrapInfo>, 
+            _context: &Box<dyn Context>) -> Result<SlotTrapResult, Error> {
+        Ok(SlotTrapResult::Skipped)
+    }
+
     /// Notify when the value is dropped
     fn notify_drop(&self) -> Result<SlotTrapResult, Error> {
         Ok(SlotTrapResult::Skipped)
@@ -115,7 +127,14 @@ pub struct ProtectedSlotTrap<'a> {
 
 impl<'a> ProtectedSlotTrap<'a> {
     pub fn new(slot_trap: &Arc<dyn SlotTrap>, context: &'a Box<dyn Context>) -> Result<ProtectedSlotTrap<'a>, Error> {
-        let (protected_id, slot_trap) = context.protect_slot_trap(slot_trap)?;
+        context.enter_trap()?;
+        let (protected_id, slot_trap) = match context.protect_slot_trap(slot_trap) {
+            Ok(protected) => protected,
+            Err(error) => {
+                context.leave_trap();
+                return Err(error);
+            }
+        };
         Ok(ProtectedSlotTrap {
             context: context,
             slot_trap: slot_trap,
@@ -129,6 +148,7 @@ impl<'a> Drop for ProtectedSlotTrap<'a> {
         if self.context.unprotect_slot_trap(self.protected_id).is_err() {
             panic!("Failed to unprotect slot trap");
         }
+        self.context.leave_trap();
     }
 }
 
@@ -1,4 +1,14 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
 use super::super::base::Value;
+use super::super::context::Context;
+use super::super::util::SpinLock;
+
+/// Count of parameter buffers kept in a trap info pool
+const TRAP_INFO_POOL_SIZE: usize = 16;
 
 /// Information of trap bridge calling 
 pub trait TrapInfo {
@@ -13,3 +23,204 @@ pub trait TrapInfo {
     fn get_parameter(&self, index: usize) -> Value;
 
 }
+
+/// Factory of trap infos, to customize the trap infos created by contexts
+pub trait TrapInfoFactory {
+
+    /// Create a new trap info
+    fn create_trap_info(&self, subject: Value, parameters: Vec<Value>, context: &Box<dyn Context>) -> Box<dyn TrapInfo>;
+
+    /// Create a trap info with parameters copied into a buffer of the pool,
+    /// the buffer should be released back to the pool once the trap info
+    /// dropped
+    fn create_pooled_trap_info(&self, subject: Value, parameters: &[Value], _pool: &Arc<TrapInfoPool>, context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        self.create_trap_info(subject, parameters.to_vec(), context)
+    }
+
+}
+
+/// Pool of parameter buffers reused by trap infos, to avoid allocating a
+/// buffer for every trap call
+pub struct TrapInfoPool {
+    spin_lock: SpinLock,
+    buffers: RefCell<Vec<Vec<Value>>>
+}
+
+impl TrapInfoPool {
+
+    pub fn new() -> TrapInfoPool {
+        TrapInfoPool {
+            spin_lock: SpinLock::new(),
+            buffers: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Get a buffer filled with the parameters, a new buffer is allocated if
+    /// the pool is empty
+    pub fn acquire(&self, parameters: &[Value]) -> Vec<Value> {
+
+        let buffer = {
+            let _guard = self.spin_lock.lock();
+            self.buffers.borrow_mut().pop()
+        };
+
+        let mut buffer = buffer.unwrap_or_default();
+        buffer.extend_from_slice(parameters);
+
+        buffer
+
+    }
+
+    /// Return a buffer back to the pool, the buffer is dropped if the pool
+    /// is full
+    pub fn release(&self, mut buffer: Vec<Value>) {
+
+        buffer.clear();
+
+        let _guard = self.spin_lock.lock();
+
+        let mut buffers = self.buffers.borrow_mut();
+        if buffers.len() < TRAP_INFO_POOL_SIZE {
+            buffers.push(buffer);
+        }
+
+    }
+
+    /// Get count of buffers kept in the pool
+    pub fn get_size(&self) -> usize {
+
+        let _guard = self.spin_lock.lock();
+
+        self.buffers.borrow().len()
+
+    }
+
+}
+
+/// Trap info with parameters in a pooled buffer, which is released back to
+/// the pool once the trap info dropped
+pub struct PooledTrapInfo {
+    subject: Value,
+    parameters: Vec<Value>,
+    pool: Arc<TrapInfoPool>
+}
+
+impl PooledTrapInfo {
+
+    pub fn new(subject: Value, parameters: &[Value], pool: &Arc<TrapInfoPool>) -> PooledTrapInfo {
+        PooledTrapInfo {
+            subject: subject,
+            parameters: pool.acquire(parameters),
+            pool: pool.clone()
+        }
+    }
+
+}
+
+impl TrapInfo for PooledTrapInfo {
+
+    fn get_subject(&self) -> Value {
+        self.subject
+    }
+
+    fn get_parameters_count(&self) -> usize {
+        self.parameters.len()
+    }
+
+    fn get_parameter(&self, index: usize) -> Value {
+        match self.parameters.get(index) {
+            Some(parameter) => *parameter,
+            None => Value::make_undefined()
+        }
+    }
+
+}
+
+impl Drop for PooledTrapInfo {
+    fn drop(&mut self) {
+        self.pool.release(core::mem::take(&mut self.parameters));
+    }
+}
+
+/// Trap info with only the subject and parameters
+pub struct StandardTrapInfo {
+    subject: Value,
+    parameters: Vec<Value>
+}
+
+impl StandardTrapInfo {
+
+    pub fn new(subject: Value, parameters: Vec<Value>) -> StandardTrapInfo {
+        StandardTrapInfo {
+            subject: subject,
+            parameters: parameters
+        }
+    }
+
+}
+
+impl TrapInfo for StandardTrapInfo {
+
+    fn get_subject(&self) -> Value {
+        self.subject
+    }
+
+    fn get_parameters_count(&self) -> usize {
+        self.parameters.len()
+    }
+
+    fn get_parameter(&self, index: usize) -> Value {
+        match self.parameters.get(index) {
+            Some(parameter) => *parameter,
+            None => Value::make_undefined()
+        }
+    }
+
+}
+
+/// Trap info factory creating `StandardTrapInfo`
+pub struct StandardTrapInfoFactory {}
+
+impl StandardTrapInfoFactory {
+
+    pub fn new() -> StandardTrapInfoFactory {
+        StandardTrapInfoFactory {}
+    }
+
+}
+
+impl TrapInfoFactory for StandardTrapInfoFactory {
+
+    fn create_trap_info(&self, subject: Value, parameters: Vec<Value>, _context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        Box::new(StandardTrapInfo::new(subject, parameters))
+    }
+
+    fn create_pooled_trap_info(&self, subject: Value, parameters: &[Value], pool: &Arc<TrapInfoPool>, _context: &Box<dyn Context>) -> Box<dyn TrapInfo> {
+        Box::new(PooledTrapInfo::new(subject, parameters, pool))
+    }
+
+}
+
+#[test]
+fn test_trap_info_pool() {
+
+    let pool = Arc::new(TrapInfoPool::new());
+
+    let trap_info = PooledTrapInfo::new(Value::make_null(), &[Value::make_integer(1), Value::make_integer(2)], &pool);
+    assert_eq!(trap_info.get_parameters_count(), 2);
+    assert_eq!(trap_info.get_parameter(1), Value::make_integer(2));
+    assert_eq!(trap_info.get_parameter(2), Value::make_undefined());
+    assert_eq!(pool.get_size(), 0);
+
+    drop(trap_info);
+    assert_eq!(pool.get_size(), 1);
+
+    let trap_info = PooledTrapInfo::new(Value::make_null(), &[Value::make_integer(3)], &pool);
+    assert_eq!(pool.get_size(), 0);
+    assert_eq!(trap_info.get_parameters_count(), 1);
+    assert_eq!(trap_info.get_parameter(0), Value::make_integer(3));
+
+    drop(trap_info);
+    assert_eq!(pool.get_size(), 1);
+
+}
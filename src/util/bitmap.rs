@@ -0,0 +1,90 @@
+/// Iterator of set bits in bitmap words, words are scanned with
+/// `trailing_zeros`, so empty words are skipped at once
+pub struct BitmapIterator<'a> {
+    words: &'a [u64],
+    offset: usize,
+    word: u64,
+    limit: usize
+}
+
+impl<'a> BitmapIterator<'a> {
+
+    /// Iterate set bits with indices less than the limit
+    pub fn new(words: &'a [u64], limit: usize) -> BitmapIterator<'a> {
+        BitmapIterator {
+            words: words,
+            offset: 0,
+            word: words.first().copied().unwrap_or(0),
+            limit: limit
+        }
+    }
+
+}
+
+impl<'a> Iterator for BitmapIterator<'a> {
+
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+
+        while self.word == 0 {
+            self.offset += 1;
+            if self.offset >= self.words.len() || (self.offset << 6) >= self.limit {
+                return None;
+            }
+            self.word = self.words[self.offset];
+        }
+
+        let index = (self.offset << 6) + self.word.trailing_zeros() as usize;
+        if index >= self.limit {
+            self.word = 0;
+            return None;
+        }
+
+        self.word &= self.word - 1;
+
+        Some(index)
+
+    }
+
+}
+
+/// Find the last set bit with index less than the limit
+pub fn find_last_set_bit(words: &[u64], limit: usize) -> Option<usize> {
+
+    let mut offset = core::cmp::min((limit + 63) >> 6, words.len());
+    while offset > 0 {
+        offset -= 1;
+        let mut word = words[offset];
+        let end = limit - (offset << 6);
+        if end < 64 {
+            word &= (1u64 << end) - 1;
+        }
+        if word != 0 {
+            return Some((offset << 6) + 63 - word.leading_zeros() as usize);
+        }
+    }
+
+    None
+
+}
+
+#[test]
+fn test_bitmap_iterator() {
+
+    let words = [0b1001u64, 0, 1u64 << 63, 0b11];
+
+    let indices: std::vec::Vec<usize> = BitmapIterator::new(&words, 256).collect();
+    assert_eq!(indices, [0, 3, 191, 192, 193].to_vec());
+
+    let indices: std::vec::Vec<usize> = BitmapIterator::new(&words, 193).collect();
+    assert_eq!(indices, [0, 3, 191, 192].to_vec());
+
+    assert_eq!(BitmapIterator::new(&[], 64).next(), None);
+
+    assert_eq!(find_last_set_bit(&words, 256), Some(193));
+    assert_eq!(find_last_set_bit(&words, 192), Some(191));
+    assert_eq!(find_last_set_bit(&words, 191), Some(3));
+    assert_eq!(find_last_set_bit(&words, 0), None);
+
+}
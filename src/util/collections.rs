@@ -0,0 +1,12 @@
+//! Hash collections used inside the crate, `std::collections` is only
+//! available with the `std` feature, otherwise `hashbrown` is used
+
+#[cfg(feature = "std")] pub use std::collections::HashMap;
+#[cfg(feature = "std")] pub use std::collections::HashSet;
+#[cfg(feature = "std")] pub use std::collections::hash_map;
+#[cfg(feature = "std")] pub use std::collections::hash_set;
+
+#[cfg(not(feature = "std"))] pub use hashbrown::HashMap;
+#[cfg(not(feature = "std"))] pub use hashbrown::HashSet;
+#[cfg(not(feature = "std"))] pub use hashbrown::hash_map;
+#[cfg(not(feature = "std"))] pub use hashbrown::hash_set;
@@ -0,0 +1,308 @@
+//! Diagnostics of lock acquisitions with the `lock-diagnostics` feature
+//!
+//! Locks acquired by each thread are recorded with their call sites, a lock
+//! acquired while holding a lock of another class records the order of the
+//! two classes, and once both orders of two classes are seen, the later one
+//! is reported as an inversion which could deadlock. Guards held longer
+//! than a threshold are reported too
+
+/// Classes of locks checked by lock diagnostics and counted by lock stats,
+/// unclassified locks are not recorded by lock diagnostics
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum LockClass {
+    Unclassified,
+    SlotLayout,
+    RegionTable,
+    Region,
+    RegionRedirection,
+    Slot,
+    Root,
+    Symbol,
+    ReferenceMap
+}
+
+#[cfg(feature = "lock-diagnostics")] pub use self::diagnostics::*;
+
+#[cfg(feature = "lock-diagnostics")]
+mod diagnostics {
+
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+    use std::panic::Location;
+    use std::string::String;
+    use std::sync::Mutex;
+    use std::sync::MutexGuard;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+    use std::vec::Vec;
+
+    use super::LockClass;
+
+    /// Issues found by lock diagnostics
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+    pub enum LockIssue {
+
+        /// A lock is acquired while holding a lock of another class, which
+        /// is acquired in the reversed order elsewhere
+        OrderInversion,
+
+        /// A guard is held longer than the threshold
+        LongHeld
+
+    }
+
+    /// Report of an issue found by lock diagnostics
+    #[derive(Clone, Debug)]
+    pub struct LockReport {
+        issue: LockIssue,
+        class: LockClass,
+        location: &'static Location<'static>,
+        held_class: Option<LockClass>,
+        held_location: Option<&'static Location<'static>>,
+        reversed_location: Option<&'static Location<'static>>,
+        held_duration: Option<Duration>,
+        thread_name: Option<String>
+    }
+
+    impl LockReport {
+
+        pub fn get_issue(&self) -> LockIssue {
+            self.issue
+        }
+
+        /// Get class of the lock acquired for inversions, or the lock held
+        /// too long
+        pub fn get_class(&self) -> LockClass {
+            self.class
+        }
+
+        /// Get call site acquiring the lock
+        pub fn get_location(&self) -> &'static Location<'static> {
+            self.location
+        }
+
+        /// Get class of the lock held while the inverted acquisition
+        pub fn get_held_class(&self) -> Option<LockClass> {
+            self.held_class
+        }
+
+        /// Get call site acquiring the lock held while the inverted
+        /// acquisition
+        pub fn get_held_location(&self) -> Option<&'static Location<'static>> {
+            self.held_location
+        }
+
+        /// Get call site where the two classes were first acquired in the
+        /// reversed order
+        pub fn get_reversed_location(&self) -> Option<&'static Location<'static>> {
+            self.reversed_location
+        }
+
+        pub fn get_held_duration(&self) -> Option<Duration> {
+            self.held_duration
+        }
+
+        pub fn get_thread_name(&self) -> Option<&str> {
+            self.thread_name.as_deref()
+        }
+
+    }
+
+    struct HeldLock {
+        key: usize,
+        class: LockClass,
+        location: &'static Location<'static>,
+        since: Instant
+    }
+
+    struct Orders {
+        first_locations: HashMap<(LockClass, LockClass), &'static Location<'static>>,
+        reported: HashSet<(LockClass, LockClass, &'static Location<'static>)>,
+        reports: Vec<LockReport>
+    }
+
+    static ORDERS: Mutex<Option<Orders>> = Mutex::new(None);
+
+    static LONG_HELD_THRESHOLD: AtomicU64 = AtomicU64::new(100_000_000);
+
+    thread_local! {
+        static HELD_LOCKS: RefCell<Vec<HeldLock>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn lock_orders() -> MutexGuard<'static, Option<Orders>> {
+        ORDERS.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn with_orders<F, R>(action: F) -> R where F: FnOnce(&mut Orders) -> R {
+        let mut orders = lock_orders();
+        let orders = orders.get_or_insert_with(|| Orders {
+            first_locations: HashMap::new(),
+            reported: HashSet::new(),
+            reports: Vec::new()
+        });
+        action(orders)
+    }
+
+    fn get_thread_name() -> Option<String> {
+        thread::current().name().map(String::from)
+    }
+
+    /// Check the lock about to be acquired blocking against locks held by
+    /// the current thread, before it could deadlock
+    pub fn check_acquisition(key: usize, class: LockClass, location: &'static Location<'static>) {
+
+        if class == LockClass::Unclassified {
+            return;
+        }
+
+        let helds: Vec<(LockClass, &'static Location<'static>)> = HELD_LOCKS.with(|held_locks| {
+            held_locks.borrow().iter()
+                .filter(|held| (held.key != key) && (held.class != class))
+                .map(|held| (held.class, held.location))
+                .collect()
+        });
+        if helds.is_empty() {
+            return;
+        }
+
+        with_orders(|orders| {
+            for (held_class, held_location) in helds {
+                orders.first_locations.entry((held_class, class)).or_insert(location);
+                if let Some(reversed_location) = orders.first_locations.get(&(class, held_class)) {
+                    let reversed_location = *reversed_location;
+                    if orders.reported.insert((held_class, class, location)) {
+                        orders.reports.push(LockReport {
+                            issue: LockIssue::OrderInversion,
+                            class: class,
+                            location: location,
+                            held_class: Some(held_class),
+                            held_location: Some(held_location),
+                            reversed_location: Some(reversed_location),
+                            held_duration: None,
+                            thread_name: get_thread_name()
+                        });
+                    }
+                }
+            }
+        });
+
+    }
+
+    /// Record a lock acquired by the current thread
+    pub fn record_acquired(key: usize, class: LockClass, location: &'static Location<'static>) {
+
+        if class == LockClass::Unclassified {
+            return;
+        }
+
+        HELD_LOCKS.with(|held_locks| {
+            held_locks.borrow_mut().push(HeldLock {
+                key: key,
+                class: class,
+                location: location,
+                since: Instant::now()
+            });
+        });
+
+    }
+
+    /// Record a lock released by the current thread, the latest acquisition
+    /// of the lock is released
+    pub fn record_released(key: usize, class: LockClass) {
+
+        if class == LockClass::Unclassified {
+            return;
+        }
+
+        let held = HELD_LOCKS.with(|held_locks| {
+            let mut held_locks = held_locks.borrow_mut();
+            held_locks.iter().rposition(|held| held.key == key).map(|index| held_locks.remove(index))
+        });
+
+        if let Some(held) = held {
+            let held_duration = held.since.elapsed();
+            if held_duration.as_nanos() > LONG_HELD_THRESHOLD.load(Ordering::Relaxed) as u128 {
+                with_orders(|orders| {
+                    orders.reports.push(LockReport {
+                        issue: LockIssue::LongHeld,
+                        class: class,
+                        location: held.location,
+                        held_class: None,
+                        held_location: None,
+                        reversed_location: None,
+                        held_duration: Some(held_duration),
+                        thread_name: get_thread_name()
+                    });
+                });
+            }
+        }
+
+    }
+
+    /// Set the duration guards held longer than are reported, 100ms by
+    /// default
+    pub fn set_long_held_threshold(threshold: Duration) {
+        LONG_HELD_THRESHOLD.store(threshold.as_nanos().min(u64::MAX as u128) as u64, Ordering::Relaxed);
+    }
+
+    /// Take reports of issues found since last taken
+    pub fn take_lock_reports() -> Vec<LockReport> {
+        with_orders(|orders| orders.reports.drain(..).collect())
+    }
+
+    /// Forget recorded orders of lock classes and reports, so orders could be
+    /// checked again from scratch
+    pub fn clear_lock_orders() {
+        *lock_orders() = None;
+    }
+
+}
+
+#[cfg(feature = "lock-diagnostics")]
+#[test]
+fn test_lock_diagnostics() {
+
+    use std::time::Duration;
+
+    use super::RwLock;
+
+    let region_lock = RwLock::with_class(LockClass::Region);
+    let root_lock = RwLock::with_class(LockClass::Root);
+
+    {
+        let _guard = region_lock.lock_read();
+        let _guard_2 = root_lock.lock_write();
+    }
+
+    let line = line!() + 3;
+    {
+        let _guard = root_lock.lock_read();
+        let _guard_2 = region_lock.lock_write();
+    }
+
+    set_long_held_threshold(Duration::from_millis(5));
+    {
+        let _guard = root_lock.lock_read();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    set_long_held_threshold(Duration::from_millis(100));
+
+    let reports = take_lock_reports();
+
+    let inversion = reports.iter().find(|report| {
+        (report.get_issue() == LockIssue::OrderInversion) && (report.get_location().file() == file!()) && (report.get_location().line() == line)
+    }).unwrap();
+    assert_eq!(inversion.get_class(), LockClass::Region);
+    assert_eq!(inversion.get_held_class(), Some(LockClass::Root));
+    assert_eq!(inversion.get_held_location().unwrap().line(), line - 1);
+    assert!(inversion.get_reversed_location().is_some());
+
+    assert!(reports.iter().any(|report| {
+        (report.get_issue() == LockIssue::LongHeld) && (report.get_location().file() == file!()) && (report.get_held_duration().unwrap() >= Duration::from_millis(10))
+    }));
+
+}
@@ -0,0 +1,149 @@
+//! Contention of lock acquisitions counted by lock classes with the
+//! `lock-stats` feature
+//!
+//! Only blocking acquisitions are counted, an acquisition is contended once
+//! it has to wait, and the time waited is accumulated for its class. Stats
+//! are shared by all isolates of the process
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::vec::Vec;
+
+use super::lock_diagnostics::LockClass;
+
+const LOCK_CLASSES: [LockClass; 9] = [
+    LockClass::Unclassified,
+    LockClass::SlotLayout,
+    LockClass::RegionTable,
+    LockClass::Region,
+    LockClass::RegionRedirection,
+    LockClass::Slot,
+    LockClass::Root,
+    LockClass::Symbol,
+    LockClass::ReferenceMap
+];
+
+struct LockCounters {
+    acquisitions: AtomicU64,
+    contended_acquisitions: AtomicU64,
+    wait_nanos: AtomicU64
+}
+
+static LOCK_COUNTERS: [LockCounters; LOCK_CLASSES.len()] = [const { LockCounters {
+    acquisitions: AtomicU64::new(0),
+    contended_acquisitions: AtomicU64::new(0),
+    wait_nanos: AtomicU64::new(0)
+} }; LOCK_CLASSES.len()];
+
+/// Contention of locks of a class
+#[derive(Copy, Clone, Debug)]
+pub struct LockStats {
+    class: LockClass,
+    acquisitions: u64,
+    contended_acquisitions: u64,
+    wait_time: Duration
+}
+
+impl LockStats {
+
+    pub fn get_class(&self) -> LockClass {
+        self.class
+    }
+
+    /// Get count of blocking acquisitions
+    pub fn get_acquisitions(&self) -> u64 {
+        self.acquisitions
+    }
+
+    /// Get count of acquisitions which had to wait
+    pub fn get_contended_acquisitions(&self) -> u64 {
+        self.contended_acquisitions
+    }
+
+    /// Get cumulative time waited by contended acquisitions
+    pub fn get_wait_time(&self) -> Duration {
+        self.wait_time
+    }
+
+}
+
+#[inline]
+fn get_counters(class: LockClass) -> &'static LockCounters {
+    &LOCK_COUNTERS[LOCK_CLASSES.iter().position(|lock_class| *lock_class == class).unwrap_or(0)]
+}
+
+/// Record a blocking acquisition with the time waited, none if it is not
+/// contended
+#[inline]
+pub fn record_acquisition(class: LockClass, waited: Option<Duration>) {
+
+    let counters = get_counters(class);
+
+    counters.acquisitions.fetch_add(1, Ordering::Relaxed);
+
+    if let Some(waited) = waited {
+        counters.contended_acquisitions.fetch_add(1, Ordering::Relaxed);
+        counters.wait_nanos.fetch_add(waited.as_nanos().min(u64::MAX as u128) as u64, Ordering::Relaxed);
+    }
+
+}
+
+/// List stats of all lock classes acquired
+pub fn list_lock_stats() -> Vec<LockStats> {
+
+    LOCK_CLASSES.iter().zip(LOCK_COUNTERS.iter()).filter_map(|(class, counters)| {
+        let acquisitions = counters.acquisitions.load(Ordering::Relaxed);
+        if acquisitions == 0 {
+            return None;
+        }
+        Some(LockStats {
+            class: *class,
+            acquisitions: acquisitions,
+            contended_acquisitions: counters.contended_acquisitions.load(Ordering::Relaxed),
+            wait_time: Duration::from_nanos(counters.wait_nanos.load(Ordering::Relaxed))
+        })
+    }).collect()
+
+}
+
+/// Reset stats of all lock classes
+pub fn reset_lock_stats() {
+    for counters in LOCK_COUNTERS.iter() {
+        counters.acquisitions.store(0, Ordering::Relaxed);
+        counters.contended_acquisitions.store(0, Ordering::Relaxed);
+        counters.wait_nanos.store(0, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_lock_stats() {
+
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::RwLock;
+
+    let get_stats = || list_lock_stats().into_iter().find(|stats| stats.get_class() == LockClass::Symbol);
+
+    let before = get_stats().map(|stats| (stats.get_acquisitions(), stats.get_contended_acquisitions(), stats.get_wait_time())).unwrap_or_default();
+
+    let lock = Arc::new(RwLock::with_class(LockClass::Symbol));
+
+    let guard = lock.lock_write();
+
+    let lock_2 = lock.clone();
+    let handle = thread::spawn(move || {
+        let _guard = lock_2.lock_read();
+    });
+
+    thread::sleep(Duration::from_millis(20));
+    drop(guard);
+    handle.join().unwrap();
+
+    let after = get_stats().unwrap();
+    assert!(after.get_acquisitions() >= before.0 + 2);
+    assert!(after.get_contended_acquisitions() > before.1);
+    assert!(after.get_wait_time() > before.2);
+
+}
@@ -1,16 +1,45 @@
+mod bitmap;
+mod collections;
+mod lock_diagnostics;
+#[cfg(feature = "lock-stats")] mod lock_stats;
 mod page_map;
+mod rcu_cell;
 mod reentrant_lock;
 mod rw_lock;
+pub mod small_map;
 mod spin_lock;
+mod sync;
 
+pub use bitmap::BitmapIterator;
+pub use bitmap::find_last_set_bit;
+pub use collections::HashMap;
+pub use collections::HashSet;
+pub use collections::hash_map;
+pub use collections::hash_set;
+pub use lock_diagnostics::LockClass;
+#[cfg(feature = "lock-diagnostics")] pub use lock_diagnostics::LockIssue;
+#[cfg(feature = "lock-diagnostics")] pub use lock_diagnostics::LockReport;
+#[cfg(feature = "lock-diagnostics")] pub use lock_diagnostics::clear_lock_orders;
+#[cfg(feature = "lock-diagnostics")] pub use lock_diagnostics::set_long_held_threshold;
+#[cfg(feature = "lock-diagnostics")] pub use lock_diagnostics::take_lock_reports;
+#[cfg(feature = "lock-stats")] pub use lock_stats::LockStats;
+#[cfg(feature = "lock-stats")] pub use lock_stats::list_lock_stats;
+#[cfg(feature = "lock-stats")] pub use lock_stats::reset_lock_stats;
+pub use page_map::FreeIndexIterator;
 pub use page_map::PageItemFactory;
+pub use page_map::PageIterator;
 pub use page_map::PageMap;
+pub use page_map::PageMapStats;
+pub use rcu_cell::RcuCell;
 pub use reentrant_lock::ReentrantLock;
 pub use reentrant_lock::ReentrantLockReadGuard;
+pub use reentrant_lock::ReentrantLockUpgradeableReadGuard;
 pub use reentrant_lock::ReentrantLockWriteGuard;
 pub use reentrant_lock::ReentrantToken;
 pub use rw_lock::RwLock;
 pub use rw_lock::RwLockReadGuard;
+pub use rw_lock::RwLockUpgradeableReadGuard;
 pub use rw_lock::RwLockWriteGuard;
+pub use small_map::SmallMap;
 pub use spin_lock::SpinLock;
 pub use spin_lock::SpinLockGuard;
\ No newline at end of file
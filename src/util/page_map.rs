@@ -1,8 +1,11 @@
-use std::collections::HashSet;
+use alloc::boxed::Box;
 
-use std::mem::MaybeUninit;
+use core::mem::MaybeUninit;
 
-use std::ops::Index;
+use core::ops::Index;
+
+use super::HashSet;
+use super::hash_set;
 
 use super::super::base::Error;
 use super::super::base::ErrorType::*;
@@ -14,13 +17,19 @@ const MAX_TABLE_ITEMS: usize = MAX_PAGE_ITEMS - 8;
 
 const MAX_ITEMS: usize = (((MAX_TABLE_ITEMS << MAX_PAGE_SHIFT) + MAX_PAGE_ITEMS) << MAX_PAGE_SHIFT) + MAX_PAGE_ITEMS;
 
+/// Factory creating items of a page map, items are created one by one when
+/// their indices are gained, pages only hold empty places for them
 pub trait PageItemFactory<T> {
-    fn create_item(&self, id: usize) -> Box<T>;
+
+    /// Create the item of the index gained, the error is returned from
+    /// gaining and the index is kept free
+    fn create_item(&self, id: usize) -> Result<Box<T>, Error>;
+
 }
 
 
 pub struct PageIterator<'a, T, F: PageItemFactory<T>> {
-    index_iterator: std::collections::hash_set::Iter<'a, usize>,
+    index_iterator: hash_set::Iter<'a, usize>,
     page_map: &'a PageMap<T, F>
 }
 
@@ -38,6 +47,74 @@ impl<'a, T, F: PageItemFactory<T>> Iterator for PageIterator<'a, T, F> {
 }
 
 
+/// Iterator of indices below the next index to gain items from, which have
+/// no items
+pub struct FreeIndexIterator<'a, T, F: PageItemFactory<T>> {
+    index: usize,
+    end: usize,
+    page_map: &'a PageMap<T, F>
+}
+
+impl<'a, T, F: PageItemFactory<T>> Iterator for FreeIndexIterator<'a, T, F> {
+
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.end {
+            let index = self.index;
+            self.index += 1;
+            if self.page_map.get(index).is_none() {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+}
+
+/// Occupancy of a page map
+#[derive(Copy, Clone, Debug)]
+pub struct PageMapStats {
+    items: usize,
+    free_indices: usize,
+    pages: usize,
+    tables: usize
+}
+
+impl PageMapStats {
+
+    /// Get count of items in the map
+    pub fn get_item_count(&self) -> usize {
+        self.items
+    }
+
+    /// Get count of indices below the next index to gain items from, which
+    /// have no items
+    pub fn get_free_index_count(&self) -> usize {
+        self.free_indices
+    }
+
+    /// Get count of pages allocated
+    pub fn get_page_count(&self) -> usize {
+        self.pages
+    }
+
+    /// Get count of page tables allocated
+    pub fn get_table_count(&self) -> usize {
+        self.tables
+    }
+
+    /// Get ratio of items to item capacity of allocated pages
+    pub fn get_occupancy(&self) -> f32 {
+        if self.pages == 0 {
+            return 0.0;
+        }
+        self.items as f32 / (self.pages * MAX_PAGE_ITEMS) as f32
+    }
+
+}
+
+
 struct Page<T> where {
     items: [Option<Box<T>>; MAX_PAGE_ITEMS]
 }
@@ -74,25 +151,106 @@ impl<T, F: PageItemFactory<T>> PageMap<T, F> {
                     *slot = MaybeUninit::new(table);
                 }
                 unsafe { 
-                    std::mem::transmute::<_, [Option<Box<PageTable<T>>>; MAX_TABLE_ITEMS]>(array) 
+                    core::mem::transmute::<_, [Option<Box<PageTable<T>>>; MAX_TABLE_ITEMS]>(array) 
                 }
             },
             occupieds: HashSet::new()
         }
     }
 
-    pub fn gain_item<'a>(&'a mut self) -> Result<usize, Error> {
+    pub fn gain_item(&mut self) -> Result<usize, Error> {
+
+        self.gain_created_item(|factory, index| factory.create_item(index))
+
+    }
+
+    /// Gain an item created by the function instead of the factory, items
+    /// are initialized lazily once the index is known
+    pub fn gain_item_with<C>(&mut self, create: C) -> Result<usize, Error> where C: FnOnce(usize) -> Result<Box<T>, Error> {
+
+        self.gain_created_item(|_factory, index| create(index))
+
+    }
+
+    fn gain_created_item<C>(&mut self, create: C) -> Result<usize, Error> where C: FnOnce(&F, usize) -> Result<Box<T>, Error> {
+
+        let next_index = self.next_index;
+
+        let index = self.gain_index()?;
 
-        let index = loop {
+        let item = match create(&self.page_item_factory, index) {
+            Ok(item) => item,
+            Err(error) => {
+                self.next_index = next_index;
+                return Err(error);
+            }
+        };
+
+        self.place_item(index, item);
+
+        Ok(index)
+
+    }
+
+    fn gain_index(&mut self) -> Result<usize, Error> {
+
+        loop {
             let index = self.next_index as usize;
             if index >= MAX_ITEMS {
                 return Err(Error::new(OutOfSpace, "No more space is available"));
             }
             self.next_index += 1;
             if self.get(index).is_none() {
-                break index;
+                return Ok(index);
             }
-        };
+        }
+
+    }
+
+    fn place_item(&mut self, index: usize, item: Box<T>) {
+
+        let page = self.prepare_page(index);
+
+        page.items[index & (MAX_PAGE_ITEMS - 1)] = Some(item);
+
+        self.size += 1;
+
+        self.occupieds.insert(index);
+
+    }
+
+    /// Insert an item with specified index, the item is not created by the
+    /// factory
+    pub fn insert_item(&mut self, index: usize, item: Box<T>) -> Result<(), Error> {
+
+        if index >= MAX_ITEMS {
+            return Err(Error::new(OutOfSpace, "No more space is available"));
+        }
+
+        if self.get(index).is_some() {
+            return Err(Error::new(FatalError, "Item already exists"));
+        }
+
+        self.place_item(index, item);
+
+        Ok(())
+
+    }
+
+    /// Replace an existing item with specified index
+    pub fn replace_item(&mut self, index: usize, item: Box<T>) -> Result<Box<T>, Error> {
+
+        if self.get(index).is_none() {
+            return Err(Error::new(FatalError, "Item not found"));
+        }
+
+        let page = self.prepare_page(index);
+
+        Ok(page.items[index & (MAX_PAGE_ITEMS - 1)].replace(item).unwrap())
+
+    }
+
+    fn prepare_page(&mut self, index: usize) -> &mut Box<Page<T>> {
 
         let table_index = (index >> (MAX_PAGE_SHIFT << 1)) & (MAX_PAGE_ITEMS - 1);
         let table_is_none = self.tables[table_index].is_none();
@@ -107,17 +265,17 @@ impl<T, F: PageItemFactory<T>> PageMap<T, F> {
                         *slot = MaybeUninit::new(page);
                     }
                     unsafe { 
-                        std::mem::transmute::<_, [Option<Box<Page<T>>>; MAX_PAGE_ITEMS]>(array) 
+                        core::mem::transmute::<_, [Option<Box<Page<T>>>; MAX_PAGE_ITEMS]>(array) 
                     }
                 }
             }));
         }
-        let mut table: Option<&'a mut Box<PageTable<T>>> = Option::from(&mut self.tables[table_index]);
+        let table = self.tables[table_index].as_mut().unwrap();
 
         let page_index = (index >> MAX_PAGE_SHIFT) & (MAX_PAGE_ITEMS - 1);
-        let page_is_none = table.as_ref().unwrap().pages[page_index].is_none();
+        let page_is_none = table.pages[page_index].is_none();
         if page_is_none {
-            table.as_mut().unwrap().pages[page_index] = Some(Box::new(Page {
+            table.pages[page_index] = Some(Box::new(Page {
                 items: {
                     let mut array: [MaybeUninit<Option<Box<T>>>; MAX_PAGE_ITEMS] = unsafe { 
                         MaybeUninit::uninit().assume_init() 
@@ -127,24 +285,13 @@ impl<T, F: PageItemFactory<T>> PageMap<T, F> {
                         *slot = MaybeUninit::new(page);
                     }
                     unsafe { 
-                        std::mem::transmute::<_, [Option<Box<T>>; MAX_PAGE_ITEMS]>(array) 
+                        core::mem::transmute::<_, [Option<Box<T>>; MAX_PAGE_ITEMS]>(array) 
                     }
                 }
             }));
         }
-        let mut page: Option<&'a mut Box<Page<T>>> = Option::from(&mut table.unwrap().pages[page_index]);
-
-        let item_index = index & (MAX_PAGE_ITEMS - 1);
-        let item_is_none = page.as_ref().unwrap().items[item_index].is_none();
-        if item_is_none {
-            page.as_mut().unwrap().items[item_index] = Some(self.page_item_factory.create_item(index));
-        }
 
-        self.size += 1;
-
-        self.occupieds.insert(index);
-
-        Ok(index)
+        table.pages[page_index].as_mut().unwrap()
 
     }
 
@@ -188,6 +335,11 @@ impl<T, F: PageItemFactory<T>> PageMap<T, F> {
 
     }
 
+    /// Overwrite the index to gain next item from
+    pub fn overwrite_next_item_index(&mut self, next_index: usize) {
+        self.next_index = next_index as u32;
+    }
+
     pub fn shrink_next_item_index(&mut self, from: usize, to: usize) -> usize {
 
         if (self.next_index == from as u32) && (to < from) {
@@ -198,6 +350,68 @@ impl<T, F: PageItemFactory<T>> PageMap<T, F> {
 
     }
 
+    /// Release pages with all items recycled, and page tables without
+    /// pages, pages are allocated again once items gained into them
+    pub fn shrink_to_fit(&mut self) -> usize {
+
+        let mut released = 0;
+
+        for table in self.tables.iter_mut() {
+            if let Some(page_table) = table.as_mut() {
+                for page in page_table.pages.iter_mut() {
+                    let recycled = match page {
+                        Some(page) => page.items.iter().all(|item| item.is_none()),
+                        None => false
+                    };
+                    if recycled {
+                        *page = None;
+                        released += 1;
+                    }
+                }
+                if page_table.pages.iter().all(|page| page.is_none()) {
+                    *table = None;
+                }
+            }
+        }
+
+        released
+
+    }
+
+    /// Iterate indices below the next index to gain items from, which have
+    /// no items
+    pub fn iterate_free_indices<'a>(&'a self) -> FreeIndexIterator<'a, T, F> {
+
+        FreeIndexIterator {
+            index: 0,
+            end: self.next_index as usize,
+            page_map: self
+        }
+
+    }
+
+    /// Count items, free indices and allocated pages of the map
+    pub fn get_stats(&self) -> PageMapStats {
+
+        let mut pages = 0;
+        let mut tables = 0;
+        for table in self.tables.iter().flatten() {
+            tables += 1;
+            pages += table.pages.iter().filter(|page| page.is_some()).count();
+        }
+
+        let next_index = self.next_index as usize;
+        let items_below = self.occupieds.iter().filter(|index| **index < next_index).count();
+
+        PageMapStats {
+            items: self.size as usize,
+            free_indices: next_index - items_below,
+            pages: pages,
+            tables: tables
+        }
+
+    }
+
     pub fn iterate_items<'a>(&'a self) -> PageIterator<'a, T, F> {
 
         PageIterator {
@@ -305,6 +519,28 @@ fn test_page_map_items() -> Result<(), Error> {
 
 }
 
+#[test]
+fn test_page_map_insert_items() -> Result<(), Error> {
+
+    let mut page_map = PageMap::new(TestPageItemFactory::new());
+
+    page_map.insert_item(2048, Box::new(7))?;
+    assert!(page_map.insert_item(2048, Box::new(8)).is_err());
+    assert_eq!(page_map[2048], 7);
+    assert_eq!(page_map.get_size(), 1);
+
+    assert_eq!(*page_map.replace_item(2048, Box::new(9))?, 7);
+    assert_eq!(page_map[2048], 9);
+    assert!(page_map.replace_item(0, Box::new(9)).is_err());
+
+    assert_eq!(page_map.gain_item()?, 0);
+    page_map.overwrite_next_item_index(2048);
+    assert_eq!(page_map.gain_item()?, 2049);
+
+    Ok(())
+
+}
+
 #[test]
 #[should_panic]
 fn test_page_map_items_inavailable() {
@@ -359,4 +595,83 @@ fn test_page_map_iterator() -> Result<(), Error> {
 
     Ok(())
 
-}
\ No newline at end of file
+}
+#[test]
+fn test_page_map_shrink() -> Result<(), Error> {
+
+    let mut page_map = PageMap::<u32, TestPageItemFactory>::new(TestPageItemFactory {});
+
+    for _ in 0..(MAX_PAGE_ITEMS * 2 + 1) {
+        page_map.gain_item()?;
+    }
+
+    let stats = page_map.get_stats();
+    assert_eq!(stats.get_item_count(), MAX_PAGE_ITEMS * 2 + 1);
+    assert_eq!(stats.get_page_count(), 3);
+    assert_eq!(stats.get_table_count(), 1);
+    assert_eq!(stats.get_free_index_count(), 0);
+
+    for index in MAX_PAGE_ITEMS..(MAX_PAGE_ITEMS * 2 + 1) {
+        page_map.recycle_item(index)?;
+    }
+    page_map.recycle_item(3)?;
+
+    assert_eq!(page_map.iterate_free_indices().take(2).collect::<Vec<usize>>(), vec!(3, MAX_PAGE_ITEMS));
+    assert_eq!(page_map.get_stats().get_free_index_count(), MAX_PAGE_ITEMS + 2);
+
+    assert_eq!(page_map.shrink_to_fit(), 2);
+    let stats = page_map.get_stats();
+    assert_eq!(stats.get_page_count(), 1);
+    assert_eq!(stats.get_item_count(), MAX_PAGE_ITEMS - 1);
+    assert_eq!(page_map.get(MAX_PAGE_ITEMS), None);
+    assert_eq!(page_map[4], 4);
+
+    page_map.insert_item(MAX_PAGE_ITEMS, Box::new(1))?;
+    assert_eq!(page_map.get_stats().get_page_count(), 2);
+
+    Ok(())
+
+}
+
+#[cfg(test)]
+struct LimitedPageItemFactory {
+    limit: usize
+}
+
+#[cfg(test)]
+impl PageItemFactory<u32> for LimitedPageItemFactory {
+
+    fn create_item(&self, id: usize) -> Result<Box<u32>, Error> {
+        if id >= self.limit {
+            return Err(Error::new(OutOfSpace, "Item limit reached"));
+        }
+        Ok(Box::new(id as u32))
+    }
+
+}
+
+#[test]
+fn test_page_map_fallible_factory() -> Result<(), Error> {
+
+    let mut page_map = PageMap::<u32, LimitedPageItemFactory>::new(LimitedPageItemFactory { limit: 2 });
+
+    assert_eq!(page_map.gain_item()?, 0);
+    assert_eq!(page_map.gain_item()?, 1);
+    assert!(page_map.gain_item().is_err());
+    assert_eq!(page_map.peek_next_item_index(), 2);
+    assert_eq!(page_map.get_size(), 2);
+    assert_eq!(page_map.get(2), None);
+
+    assert_eq!(page_map.gain_item_with(|index| Ok(Box::new(index as u32 * 10)))?, 2);
+    assert_eq!(page_map[2], 20);
+
+    assert!(page_map.gain_item_with(|_index| Err(Error::new(FatalError, "Creation failed"))).is_err());
+    assert_eq!(page_map.peek_next_item_index(), 3);
+
+    page_map.recycle_item(0)?;
+    page_map.overwrite_next_item_index(0);
+    assert_eq!(page_map.gain_item()?, 0);
+
+    Ok(())
+
+}
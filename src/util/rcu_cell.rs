@@ -0,0 +1,134 @@
+use alloc::boxed::Box;
+use core::ops::Deref;
+use core::sync::atomic::AtomicPtr;
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
+
+use super::spin_lock::SpinLock;
+
+/// Cell publishing immutable values, read without locks
+///
+/// Readers are counted by epochs, a replaced value is released only after
+/// all readers of both epochs observed before the replacement left, so a
+/// read guard should be dropped quickly, and should never be held while
+/// replacing the value of the same cell
+pub struct RcuCell<T> {
+    spin_lock: SpinLock,
+    pointer: AtomicPtr<T>,
+    epoch: AtomicUsize,
+    readers: [AtomicUsize; 2]
+}
+
+unsafe impl<T: Send + Sync> Send for RcuCell<T> {}
+unsafe impl<T: Send + Sync> Sync for RcuCell<T> {}
+
+pub struct RcuReadGuard<'a, T> {
+    cell: &'a RcuCell<T>,
+    epoch: usize,
+    pointer: *const T
+}
+
+impl<'a, T> Deref for RcuReadGuard<'a, T> {
+
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.pointer }
+    }
+
+}
+
+impl<'a, T> Drop for RcuReadGuard<'a, T> {
+    fn drop(&mut self) {
+        self.cell.readers[self.epoch].fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<T> RcuCell<T> {
+
+    pub fn new(value: T) -> RcuCell<T> {
+        RcuCell {
+            spin_lock: SpinLock::new(),
+            pointer: AtomicPtr::new(Box::into_raw(Box::new(value))),
+            epoch: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)]
+        }
+    }
+
+    #[inline]
+    pub fn read(&self) -> RcuReadGuard<'_, T> {
+
+        let epoch = self.epoch.load(Ordering::SeqCst) & 1;
+
+        self.readers[epoch].fetch_add(1, Ordering::SeqCst);
+
+        RcuReadGuard {
+            cell: self,
+            epoch: epoch,
+            pointer: self.pointer.load(Ordering::SeqCst)
+        }
+
+    }
+
+    /// Publish a new value, the old value is released after all readers
+    /// possibly observing it left
+    pub fn replace(&self, value: T) {
+
+        let _guard = self.spin_lock.lock();
+
+        let old_pointer = self.pointer.swap(Box::into_raw(Box::new(value)), Ordering::SeqCst);
+
+        self.synchronize();
+
+        drop(unsafe { Box::from_raw(old_pointer) });
+
+    }
+
+    /// Wait for readers of both epochs, a reader may increase the counter of
+    /// an epoch observed long before, so flipping once is not enough
+    fn synchronize(&self) {
+        for _ in 0 .. 2 {
+            let epoch = self.epoch.fetch_xor(1, Ordering::SeqCst) & 1;
+            while self.readers[epoch].load(Ordering::SeqCst) != 0 {
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+}
+
+impl<T> Drop for RcuCell<T> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(*self.pointer.get_mut()) });
+    }
+}
+
+#[test]
+fn test_rcu_cell() {
+
+    let cell = RcuCell::new(1);
+
+    {
+        let guard = cell.read();
+        assert_eq!(*guard, 1);
+    }
+
+    cell.replace(2);
+    assert_eq!(*cell.read(), 2);
+
+    let cell = alloc::sync::Arc::new(RcuCell::new(alloc::vec![0usize; 16]));
+    let reader = {
+        let cell = cell.clone();
+        std::thread::spawn(move || {
+            for _ in 0 .. 10000 {
+                let guard = cell.read();
+                assert!(guard.iter().all(|value| *value == guard[0]));
+            }
+        })
+    };
+    for index in 1 .. 1000 {
+        cell.replace(alloc::vec![index; 16]);
+    }
+    reader.join().unwrap();
+
+}
@@ -1,6 +1,21 @@
-use std::sync::Arc;
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering;
+use alloc::sync::Arc;
+use super::sync::AtomicU32;
+use super::sync::Ordering;
+use super::sync::Backoff;
+use super::sync::wake;
+#[cfg(all(feature = "async", not(loom)))] use super::sync::poll_lock;
+#[cfg(all(feature = "async", not(loom)))] use core::future::Future;
+#[cfg(all(feature = "async", not(loom)))] use core::future::poll_fn;
+use super::lock_diagnostics::LockClass;
+#[cfg(feature = "lock-diagnostics")] use super::lock_diagnostics;
+#[cfg(feature = "lock-stats")] use super::lock_stats;
+#[cfg(feature = "lock-diagnostics")] use core::panic::Location;
+
+#[cfg(feature = "fault-injection")] use super::rcu_cell::RcuCell;
+
+/// Hook called before a lock is acquired blocking, with true for writing
+#[cfg(feature = "fault-injection")]
+pub type AcquisitionDelay = Arc<dyn Fn(bool) + Send + Sync>;
 
 pub struct ReentrantToken {
     lock: Arc<ReentrantLock>,
@@ -27,12 +42,14 @@ impl ReentrantToken {
 
     }
 
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn lock_read<'a>(&'a self) -> ReentrantLockReadGuard<'a> {
 
         self.lock.lock_read(self)
 
     }
 
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn try_lock_read<'a>(&'a self) -> ReentrantLockReadGuard<'a> {
 
         self.lock.try_lock_read(self)
@@ -45,12 +62,14 @@ impl ReentrantToken {
 
     }
 
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn lock_write<'a>(&'a self) -> ReentrantLockWriteGuard<'a> {
 
         self.lock.lock_write(self)
 
     }
 
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn try_lock_write<'a>(&'a self) -> ReentrantLockWriteGuard<'a> {
 
         self.lock.try_lock_write(self)
@@ -63,12 +82,22 @@ impl ReentrantToken {
 
     }
 
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
+    pub fn lock_upgradeable_read<'a>(&'a self) -> ReentrantLockUpgradeableReadGuard<'a> {
+
+        self.lock.lock_upgradeable_read(self)
+
+    }
+
 }
 
 pub struct ReentrantLock {
     reading: AtomicU32,
     flag: AtomicU32,
-    next: AtomicU32
+    upgrading: AtomicU32,
+    next: AtomicU32,
+    #[cfg(any(feature = "lock-diagnostics", feature = "lock-stats"))] class: LockClass,
+    #[cfg(feature = "fault-injection")] acquisition_delay: RcuCell<Option<AcquisitionDelay>>
 }
 
 pub struct ReentrantLockReadGuard<'a> {
@@ -117,14 +146,133 @@ impl<'a> Drop for ReentrantLockWriteGuard<'a> {
     }
 }
 
+/// Read guard exclusive with writers and other upgradeable readers, which
+/// could be upgraded into a write guard without releasing the lock between
+///
+/// If the token is already locked for writing, the guard holds the write
+/// lock reentered instead
+pub struct ReentrantLockUpgradeableReadGuard<'a> {
+    token: &'a ReentrantToken,
+    locked: bool,
+    writing: bool
+}
+
+impl<'a> ReentrantLockUpgradeableReadGuard<'a> {
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Upgrade into a write guard, waiting for readers of other tokens to
+    /// leave, reads reentered on the token should be released before
+    pub fn upgrade(mut self) -> ReentrantLockWriteGuard<'a> {
+
+        let token = self.token;
+        let writing = self.writing;
+        self.locked = false;
+
+        if !writing {
+            token.lock.upgrade(token);
+        }
+
+        ReentrantLockWriteGuard {
+            token: token,
+            locked: true
+        }
+
+    }
+
+}
+
+impl<'a> Drop for ReentrantLockUpgradeableReadGuard<'a> {
+    fn drop(&mut self) {
+        if self.locked {
+            self.locked = false;
+            if self.writing {
+                self.token.unlock_write();
+            } else {
+                self.token.lock.unlock_upgradeable_read(self.token);
+            }
+        }
+    }
+}
+
 impl ReentrantLock {
 
     #[inline]
     pub fn new() -> ReentrantLock {
+        ReentrantLock::with_class(LockClass::Unclassified)
+    }
+
+    /// Create a lock checked as the class by lock diagnostics and counted
+    /// by lock stats, the class is ignored without those features
+    #[inline]
+    pub fn with_class(_class: LockClass) -> ReentrantLock {
         ReentrantLock {
             reading: AtomicU32::new(0),
             flag: AtomicU32::new(0),
-            next: AtomicU32::new(1)
+            upgrading: AtomicU32::new(0),
+            next: AtomicU32::new(1),
+            #[cfg(any(feature = "lock-diagnostics", feature = "lock-stats"))] class: _class,
+            #[cfg(feature = "fault-injection")] acquisition_delay: RcuCell::new(None)
+        }
+    }
+
+    /// Get the key threads waiting for the lock are parked on
+    #[inline]
+    fn get_key(&self) -> usize {
+        self as *const ReentrantLock as usize
+    }
+
+    #[cfg(feature = "lock-diagnostics")]
+    #[track_caller]
+    fn diagnose_acquiring(&self) {
+        lock_diagnostics::check_acquisition(self.get_key(), self.class, Location::caller());
+    }
+
+    #[cfg(feature = "lock-diagnostics")]
+    #[track_caller]
+    fn diagnose_acquired(&self) {
+        lock_diagnostics::record_acquired(self.get_key(), self.class, Location::caller());
+    }
+
+    #[cfg(feature = "lock-diagnostics")]
+    fn diagnose_released(&self) {
+        lock_diagnostics::record_released(self.get_key(), self.class);
+    }
+
+    #[cfg(not(feature = "lock-diagnostics"))]
+    #[inline]
+    fn diagnose_acquiring(&self) {}
+
+    #[cfg(not(feature = "lock-diagnostics"))]
+    #[inline]
+    fn diagnose_acquired(&self) {}
+
+    #[cfg(not(feature = "lock-diagnostics"))]
+    #[inline]
+    fn diagnose_released(&self) {}
+
+    #[cfg(feature = "lock-stats")]
+    fn record_wait(&self, backoff: &Backoff) {
+        lock_stats::record_acquisition(self.class, backoff.get_waited());
+    }
+
+    #[cfg(not(feature = "lock-stats"))]
+    #[inline]
+    fn record_wait(&self, _backoff: &Backoff) {}
+
+    /// Set the hook delaying blocking acquisitions not reentered by tokens
+    #[cfg(feature = "fault-injection")]
+    pub fn set_acquisition_delay(&self, acquisition_delay: Option<AcquisitionDelay>) {
+        self.acquisition_delay.replace(acquisition_delay);
+    }
+
+    #[cfg(feature = "fault-injection")]
+    fn delay_acquisition(&self, writing: bool) {
+        let acquisition_delay = self.acquisition_delay.read().clone();
+        if let Some(acquisition_delay) = acquisition_delay {
+            acquisition_delay(writing);
         }
     }
 
@@ -135,6 +283,7 @@ impl ReentrantLock {
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn lock_read<'a>(&self, token: &'a ReentrantToken) -> ReentrantLockReadGuard<'a> {
 
         let flag = token.reading_flag;
@@ -147,10 +296,16 @@ impl ReentrantLock {
             };
         }
 
+        self.diagnose_acquiring();
+
+        #[cfg(feature = "fault-injection")] self.delay_acquisition(false);
+
+        let mut backoff = Backoff::new();
         loop {
             if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
         }
         self.reading.fetch_add(1, Ordering::SeqCst);
         token.reading.fetch_add(1, Ordering::SeqCst);
@@ -158,6 +313,10 @@ impl ReentrantLock {
         if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
             panic!("Invalid reentrant lock read guard to unlock");
         }
+        wake(self.get_key());
+
+        self.diagnose_acquired();
+        self.record_wait(&backoff);
 
         ReentrantLockReadGuard {
             token: token,
@@ -167,6 +326,7 @@ impl ReentrantLock {
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn try_lock_read<'a>(&self, token: &'a ReentrantToken) -> ReentrantLockReadGuard<'a> {
 
         let flag = token.reading_flag;
@@ -187,6 +347,9 @@ impl ReentrantLock {
             if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
                 panic!("Invalid reentrant lock read guard to unlock");
             }
+            wake(self.get_key());
+
+            self.diagnose_acquired();
 
             ReentrantLockReadGuard {
                 token: token,
@@ -207,12 +370,19 @@ impl ReentrantLock {
     #[inline]
     fn unlock_read(&self, token: &ReentrantToken) {
 
-        self.reading.fetch_sub(1, Ordering::SeqCst);
+        if self.reading.fetch_sub(1, Ordering::SeqCst) == 1 {
+            wake(self.get_key());
+        }
         token.reading.fetch_sub(1, Ordering::SeqCst);
 
+        if (token.reading.load(Ordering::SeqCst) == 0) && (token.writing.load(Ordering::SeqCst) == 0) {
+            self.diagnose_released();
+        }
+
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn lock_write<'a>(&self, token: &'a ReentrantToken) -> ReentrantLockWriteGuard<'a> {
 
         let flag = token.writing_flag;
@@ -228,20 +398,33 @@ impl ReentrantLock {
             };
         }
 
+        self.diagnose_acquiring();
+
+        #[cfg(feature = "fault-injection")] self.delay_acquisition(true);
+
+        let mut backoff = Backoff::new();
+
+        self.lock_upgrading(flag, &mut backoff);
+
         loop {
             if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
         }
 
         loop {
             if self.reading.load(Ordering::SeqCst) == 0 {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.reading.load(Ordering::SeqCst) == 0);
         }
 
         token.writing.fetch_add(1, Ordering::SeqCst);
 
+        self.diagnose_acquired();
+        self.record_wait(&backoff);
+
         ReentrantLockWriteGuard {
             token: token,
             locked: true
@@ -250,6 +433,7 @@ impl ReentrantLock {
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn try_lock_write<'a>(&self, token: &'a ReentrantToken) -> ReentrantLockWriteGuard<'a> {
 
         let flag = token.writing_flag;
@@ -268,9 +452,17 @@ impl ReentrantLock {
             };
         }
 
+        if let Err(_) = self.upgrading.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+            return ReentrantLockWriteGuard {
+                token: token,
+                locked: false
+            };
+        }
+
         if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
             if self.reading.load(Ordering::SeqCst) == 0 {
                 token.writing.fetch_add(1, Ordering::SeqCst);
+                self.diagnose_acquired();
                 return ReentrantLockWriteGuard {
                     token: token,
                     locked: true
@@ -279,9 +471,12 @@ impl ReentrantLock {
                 if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
                     panic!("Reentrant lock is locked by violatile token");
                 }
+                wake(self.get_key());
             }
         }
 
+        self.unlock_upgrading(flag);
+
         ReentrantLockWriteGuard {
             token: token,
             locked: false
@@ -301,12 +496,158 @@ impl ReentrantLock {
             if let Err(_) = self.flag.compare_exchange(token.writing_flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
                 panic!("Invalid reentrant lock guard to unlock");
             }
+            wake(self.get_key());
+            self.unlock_upgrading(token.writing_flag);
+            self.diagnose_released();
+        }
+
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
+    pub fn lock_upgradeable_read<'a>(&self, token: &'a ReentrantToken) -> ReentrantLockUpgradeableReadGuard<'a> {
+
+        if token.writing.load(Ordering::SeqCst) > 0 {
+            token.writing.fetch_add(1, Ordering::SeqCst);
+            return ReentrantLockUpgradeableReadGuard {
+                token: token,
+                locked: true,
+                writing: true
+            };
+        }
+
+        if token.reading.load(Ordering::SeqCst) > 0 {
+            panic!("Reentrant lock is locked for reading on the token, but upgradeable reading expected");
+        }
+
+        self.diagnose_acquiring();
+
+        #[cfg(feature = "fault-injection")] self.delay_acquisition(false);
+
+        let mut backoff = Backoff::new();
+
+        self.lock_upgrading(token.writing_flag, &mut backoff);
+
+        let flag = token.reading_flag;
+        loop {
+            if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
+        }
+        self.reading.fetch_add(1, Ordering::SeqCst);
+        token.reading.fetch_add(1, Ordering::SeqCst);
+
+        if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
+            panic!("Invalid reentrant lock read guard to unlock");
+        }
+        wake(self.get_key());
+
+        self.diagnose_acquired();
+        self.record_wait(&backoff);
+
+        ReentrantLockUpgradeableReadGuard {
+            token: token,
+            locked: true,
+            writing: false
+        }
+
+    }
+
+    #[inline]
+    fn lock_upgrading(&self, flag: u32, backoff: &mut Backoff) {
+        loop {
+            if let Ok(_) = self.upgrading.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.upgrading.load(Ordering::SeqCst) == 0);
         }
+    }
+
+    #[inline]
+    fn unlock_upgrading(&self, flag: u32) {
+        if let Err(_) = self.upgrading.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
+            panic!("Invalid reentrant lock upgradeable guard to unlock");
+        }
+        wake(self.get_key());
+    }
+
+    #[inline]
+    fn upgrade(&self, token: &ReentrantToken) {
+
+        if token.reading.load(Ordering::SeqCst) != 1 {
+            panic!("Reentrant lock is upgraded with reading reentered on the token");
+        }
+
+        let flag = token.writing_flag;
+        let mut backoff = Backoff::new();
+        loop {
+            if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
+        }
+
+        if self.reading.fetch_sub(1, Ordering::SeqCst) == 1 {
+            wake(self.get_key());
+        }
+        token.reading.fetch_sub(1, Ordering::SeqCst);
+
+        loop {
+            if self.reading.load(Ordering::SeqCst) == 0 {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.reading.load(Ordering::SeqCst) == 0);
+        }
+
+        token.writing.fetch_add(1, Ordering::SeqCst);
+
+        self.record_wait(&backoff);
+
+    }
+
+    #[inline]
+    fn unlock_upgradeable_read(&self, token: &ReentrantToken) {
+
+        self.unlock_read(token);
+
+        self.unlock_upgrading(token.writing_flag);
 
     }
 
 } 
 
+/// Lock acquisitions for async tasks, tasks waiting for the lock are woken
+/// once it is released instead of blocking executor threads
+///
+/// Reentrance is tracked by tokens instead of threads, so a task could hold
+/// guards of its token across awaits even if it is moved between threads
+#[cfg(all(feature = "async", not(loom)))]
+impl ReentrantToken {
+
+    pub fn lock_read_async(&self) -> impl Future<Output = ReentrantLockReadGuard<'_>> + '_ {
+        poll_fn(move |context| {
+            poll_lock(self.lock.get_key(), context, || {
+                let guard = self.try_lock_read();
+                if guard.is_locked() { Some(guard) } else { None }
+            })
+        })
+    }
+
+    pub fn lock_write_async(&self) -> impl Future<Output = ReentrantLockWriteGuard<'_>> + '_ {
+        if (self.reading.load(Ordering::SeqCst) > 0) && (self.writing.load(Ordering::SeqCst) == 0) {
+            panic!("Reentrant lock is locked for reading on the token, but writing expected");
+        }
+        poll_fn(move |context| {
+            poll_lock(self.lock.get_key(), context, || {
+                let guard = self.try_lock_write();
+                if guard.is_locked() { Some(guard) } else { None }
+            })
+        })
+    }
+
+}
+
 #[test]
 fn test_lock() {
 
@@ -357,4 +698,96 @@ fn test_lock() {
         assert!(!guard_5.is_locked());
     }
 
-}
\ No newline at end of file
+}
+#[test]
+fn test_lock_upgrade() {
+
+    let lock = Arc::new(ReentrantLock::new());
+
+    let token = ReentrantToken::new(lock.clone());
+    let token_2 = ReentrantToken::new(lock);
+
+    {
+        let guard = token.lock_upgradeable_read();
+        let guard_2 = token_2.try_lock_read();
+        let guard_3 = token_2.try_lock_write();
+        assert!(guard.is_locked());
+        assert!(guard_2.is_locked());
+        assert!(!guard_3.is_locked());
+    }
+
+    {
+        let guard = token.lock_upgradeable_read();
+        {
+            let guard_2 = token.lock_read();
+            assert!(guard_2.is_locked());
+        }
+        let guard = guard.upgrade();
+        let guard_2 = token.try_lock_read();
+        let guard_3 = token_2.try_lock_read();
+        let guard_4 = token_2.try_lock_write();
+        assert!(guard.is_locked());
+        assert!(guard_2.is_locked());
+        assert!(!guard_3.is_locked());
+        assert!(!guard_4.is_locked());
+    }
+
+    {
+        let guard = token.lock_write();
+        let guard_2 = token.lock_upgradeable_read();
+        assert!(guard_2.is_locked());
+        let guard_2 = guard_2.upgrade();
+        assert!(guard.is_locked());
+        assert!(guard_2.is_locked());
+    }
+
+    {
+        let guard = token_2.try_lock_write();
+        assert!(guard.is_locked());
+    }
+
+}
+
+#[cfg(all(feature = "async", not(loom)))]
+#[test]
+fn test_lock_async() {
+
+    use std::pin::pin;
+    use std::sync::atomic::AtomicUsize;
+    use std::task::Context;
+    use std::task::Poll;
+    use std::task::Wake;
+    use std::task::Waker;
+
+    struct CountingWaker {
+        wakes: AtomicUsize
+    }
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.wakes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let counting_waker = Arc::new(CountingWaker { wakes: AtomicUsize::new(0) });
+    let waker = Waker::from(counting_waker.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let lock = Arc::new(ReentrantLock::new());
+
+    let token = ReentrantToken::new(lock.clone());
+    let token_2 = ReentrantToken::new(lock);
+
+    let guard = token.lock_write();
+    {
+        let mut future = pin!(token.lock_read_async());
+        assert!(matches!(future.as_mut().poll(&mut context), Poll::Ready(_)));
+    }
+
+    let mut future = pin!(token_2.lock_write_async());
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    drop(guard);
+    assert_eq!(counting_waker.wakes.load(Ordering::SeqCst), 1);
+    assert!(matches!(future.as_mut().poll(&mut context), Poll::Ready(_)));
+
+}
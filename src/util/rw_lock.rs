@@ -1,10 +1,21 @@
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering;
+use super::sync::AtomicU32;
+use super::sync::Ordering;
+use super::sync::Backoff;
+use super::sync::wake;
+#[cfg(all(feature = "async", not(loom)))] use super::sync::poll_lock;
+#[cfg(all(feature = "async", not(loom)))] use core::future::Future;
+#[cfg(all(feature = "async", not(loom)))] use core::future::poll_fn;
+use super::lock_diagnostics::LockClass;
+#[cfg(feature = "lock-diagnostics")] use super::lock_diagnostics;
+#[cfg(feature = "lock-stats")] use super::lock_stats;
+#[cfg(feature = "lock-diagnostics")] use core::panic::Location;
 
 pub struct RwLock {
     reading: AtomicU32,
     flag: AtomicU32,
-    next: AtomicU32
+    upgrading: AtomicU32,
+    next: AtomicU32,
+    #[cfg(any(feature = "lock-diagnostics", feature = "lock-stats"))] class: LockClass
 }
 
 pub struct RwLockReadGuard<'a> {
@@ -56,32 +67,229 @@ impl<'a> Drop for RwLockWriteGuard<'a> {
     }
 }
 
+/// Read guard exclusive with writers and other upgradeable readers, but
+/// shared with plain readers, which could be upgraded into a write guard
+/// without releasing the lock between
+pub struct RwLockUpgradeableReadGuard<'a> {
+    lock: &'a RwLock,
+    flag: u32
+}
+
+impl<'a> RwLockUpgradeableReadGuard<'a> {
+
+    pub fn is_locked(&self) -> bool {
+        self.flag != 0
+    }
+
+    /// Upgrade into a write guard, waiting for plain readers to leave
+    pub fn upgrade(mut self) -> RwLockWriteGuard<'a> {
+
+        let lock = self.lock;
+        let flag = self.flag;
+        self.flag = 0;
+
+        lock.upgrade(flag)
+
+    }
+
+}
+
+impl<'a> Drop for RwLockUpgradeableReadGuard<'a> {
+    fn drop(&mut self) {
+        if self.flag != 0 {
+            self.lock.unlock_upgradeable_read(self.flag);
+            self.flag = 0;
+        }
+    }
+}
+
 impl RwLock {
 
     #[inline]
     pub fn new() -> RwLock {
+        RwLock::with_class(LockClass::Unclassified)
+    }
+
+    /// Create a lock checked as the class by lock diagnostics and counted
+    /// by lock stats, the class is ignored without those features
+    #[inline]
+    pub fn with_class(_class: LockClass) -> RwLock {
         RwLock {
             reading: AtomicU32::new(0),
             flag: AtomicU32::new(0),
-            next: AtomicU32::new(1)
+            upgrading: AtomicU32::new(0),
+            next: AtomicU32::new(1),
+            #[cfg(any(feature = "lock-diagnostics", feature = "lock-stats"))] class: _class
+        }
+    }
+
+    /// Get the key threads waiting for the lock are parked on
+    #[inline]
+    fn get_key(&self) -> usize {
+        self as *const RwLock as usize
+    }
+
+    #[cfg(feature = "lock-diagnostics")]
+    #[track_caller]
+    fn diagnose_acquiring(&self) {
+        lock_diagnostics::check_acquisition(self.get_key(), self.class, Location::caller());
+    }
+
+    #[cfg(feature = "lock-diagnostics")]
+    #[track_caller]
+    fn diagnose_acquired(&self) {
+        lock_diagnostics::record_acquired(self.get_key(), self.class, Location::caller());
+    }
+
+    #[cfg(feature = "lock-diagnostics")]
+    fn diagnose_released(&self) {
+        lock_diagnostics::record_released(self.get_key(), self.class);
+    }
+
+    #[cfg(not(feature = "lock-diagnostics"))]
+    #[inline]
+    fn diagnose_acquiring(&self) {}
+
+    #[cfg(not(feature = "lock-diagnostics"))]
+    #[inline]
+    fn diagnose_acquired(&self) {}
+
+    #[cfg(not(feature = "lock-diagnostics"))]
+    #[inline]
+    fn diagnose_released(&self) {}
+
+    #[cfg(feature = "lock-stats")]
+    fn record_wait(&self, backoff: &Backoff) {
+        lock_stats::record_acquisition(self.class, backoff.get_waited());
+    }
+
+    #[cfg(not(feature = "lock-stats"))]
+    #[inline]
+    fn record_wait(&self, _backoff: &Backoff) {}
+
+    /// Lock for reading, and the guard could be upgraded for writing later,
+    /// so state checked while reading is kept until written
+    #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
+    pub fn lock_upgradeable_read(&self) -> RwLockUpgradeableReadGuard {
+
+        let flag = self.next.fetch_add(1, Ordering::SeqCst);
+
+        self.diagnose_acquiring();
+
+        let mut backoff = Backoff::new();
+
+        self.lock_upgrading(flag, &mut backoff);
+
+        loop {
+            if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
+        }
+        self.reading.fetch_add(1, Ordering::SeqCst);
+
+        if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
+            panic!("Invalid rw lock read guard to unlock");
+        }
+        wake(self.get_key());
+
+        self.diagnose_acquired();
+        self.record_wait(&backoff);
+
+        RwLockUpgradeableReadGuard {
+            lock: self,
+            flag: flag
         }
+
     }
 
     #[inline]
+    fn lock_upgrading(&self, flag: u32, backoff: &mut Backoff) {
+        loop {
+            if let Ok(_) = self.upgrading.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.upgrading.load(Ordering::SeqCst) == 0);
+        }
+    }
+
+    #[inline]
+    fn unlock_upgrading(&self, flag: u32) {
+        if let Err(_) = self.upgrading.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
+            panic!("Invalid rw lock upgradeable guard to unlock");
+        }
+        wake(self.get_key());
+    }
+
+    #[inline]
+    fn upgrade(&self, flag: u32) -> RwLockWriteGuard {
+
+        let mut backoff = Backoff::new();
+        loop {
+            if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
+        }
+
+        if self.reading.fetch_sub(1, Ordering::SeqCst) == 1 {
+            wake(self.get_key());
+        }
+
+        loop {
+            if self.reading.load(Ordering::SeqCst) == 0 {
+                break;
+            }
+            backoff.snooze(self.get_key(), || self.reading.load(Ordering::SeqCst) == 0);
+        }
+
+        self.record_wait(&backoff);
+
+        RwLockWriteGuard {
+            lock: self,
+            flag: flag
+        }
+
+    }
+
+    #[inline]
+    fn unlock_upgradeable_read(&self, flag: u32) {
+
+        self.diagnose_released();
+
+        if self.reading.fetch_sub(1, Ordering::SeqCst) == 1 {
+            wake(self.get_key());
+        }
+
+        self.unlock_upgrading(flag);
+
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn lock_read(&self) -> RwLockReadGuard {
 
         let flag = self.next.fetch_add(1, Ordering::SeqCst);
 
+        self.diagnose_acquiring();
+
+        let mut backoff = Backoff::new();
         loop {
             if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
         }
         self.reading.fetch_add(1, Ordering::SeqCst);
 
         if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
             panic!("Invalid rw lock read guard to unlock");
         }
+        wake(self.get_key());
+
+        self.diagnose_acquired();
+        self.record_wait(&backoff);
 
         RwLockReadGuard {
             lock: self,
@@ -91,6 +299,7 @@ impl RwLock {
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn try_lock_read(&self) -> RwLockReadGuard {
 
         let flag = self.next.fetch_add(1, Ordering::SeqCst);
@@ -102,6 +311,9 @@ impl RwLock {
             if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
                 panic!("Invalid rw lock read guard to unlock");
             }
+            wake(self.get_key());
+
+            self.diagnose_acquired();
 
             RwLockReadGuard {
                 lock: self,
@@ -120,27 +332,43 @@ impl RwLock {
     #[inline]
     fn unlock_read(&self) {
 
-        self.reading.fetch_sub(1, Ordering::SeqCst);
+        self.diagnose_released();
+
+        if self.reading.fetch_sub(1, Ordering::SeqCst) == 1 {
+            wake(self.get_key());
+        }
 
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn lock_write(&self) -> RwLockWriteGuard {
 
         let flag = self.next.fetch_add(1, Ordering::SeqCst);
 
+        self.diagnose_acquiring();
+
+        let mut backoff = Backoff::new();
+
+        self.lock_upgrading(flag, &mut backoff);
+
         loop {
             if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
         }
 
         loop {
             if self.reading.load(Ordering::SeqCst) == 0 {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.reading.load(Ordering::SeqCst) == 0);
         }
 
+        self.diagnose_acquired();
+        self.record_wait(&backoff);
+
         RwLockWriteGuard {
             lock: self,
             flag: flag
@@ -149,13 +377,22 @@ impl RwLock {
     }
 
     #[inline]
+    #[cfg_attr(feature = "lock-diagnostics", track_caller)]
     pub fn try_lock_write(&self) -> RwLockWriteGuard {
 
         let flag = self.next.fetch_add(1, Ordering::SeqCst);
 
+        if let Err(_) = self.upgrading.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
+            return RwLockWriteGuard {
+                lock: self,
+                flag: 0
+            };
+        }
+
         if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
 
             if self.reading.load(Ordering::SeqCst) == 0 {
+                self.diagnose_acquired();
                 RwLockWriteGuard {
                     lock: self,
                     flag: flag
@@ -164,6 +401,8 @@ impl RwLock {
                 if let Err(_) = self.flag.compare_exchange(flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
                     panic!("Invalid rw lock guard to unlock");
                 }
+                wake(self.get_key());
+                self.unlock_upgrading(flag);
                 RwLockWriteGuard {
                     lock: self,
                     flag: 0 
@@ -171,6 +410,7 @@ impl RwLock {
             }
 
         } else {
+            self.unlock_upgrading(flag);
             RwLockWriteGuard {
                 lock: self,
                 flag: 0 
@@ -182,14 +422,44 @@ impl RwLock {
     #[inline]
     fn unlock_write(&self, guard: &RwLockWriteGuard) {
 
+        self.diagnose_released();
+
         if let Err(_) = self.flag.compare_exchange(guard.flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
             panic!("Invalid rw lock guard to unlock");
         }
+        wake(self.get_key());
+
+        self.unlock_upgrading(guard.flag);
 
     }
 
 } 
 
+/// Lock acquisitions for async tasks, tasks waiting for the lock are woken
+/// once it is released instead of blocking executor threads
+#[cfg(all(feature = "async", not(loom)))]
+impl RwLock {
+
+    pub fn lock_read_async(&self) -> impl Future<Output = RwLockReadGuard<'_>> + '_ {
+        poll_fn(move |context| {
+            poll_lock(self.get_key(), context, || {
+                let guard = self.try_lock_read();
+                if guard.is_locked() { Some(guard) } else { None }
+            })
+        })
+    }
+
+    pub fn lock_write_async(&self) -> impl Future<Output = RwLockWriteGuard<'_>> + '_ {
+        poll_fn(move |context| {
+            poll_lock(self.get_key(), context, || {
+                let guard = self.try_lock_write();
+                if guard.is_locked() { Some(guard) } else { None }
+            })
+        })
+    }
+
+}
+
 #[test]
 fn test_lock() {
 
@@ -215,4 +485,79 @@ fn test_lock() {
         assert!(!guard_4.is_locked());
     }
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_lock_upgrade() {
+
+    let lock = RwLock::new();
+
+    {
+        let guard = lock.lock_upgradeable_read();
+        let guard_2 = lock.try_lock_read();
+        assert!(guard.is_locked());
+        assert!(guard_2.is_locked());
+        assert!(!lock.try_lock_write().is_locked());
+        drop(guard_2);
+        let guard = guard.upgrade();
+        assert!(guard.is_locked());
+        assert!(!lock.try_lock_read().is_locked());
+    }
+
+    assert!(lock.try_lock_write().is_locked());
+
+    {
+        let _guard = lock.lock_upgradeable_read();
+    }
+
+    assert!(lock.try_lock_write().is_locked());
+
+}
+
+#[cfg(all(feature = "async", not(loom)))]
+#[test]
+fn test_lock_async() {
+
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::task::Context;
+    use std::task::Poll;
+    use std::task::Wake;
+    use std::task::Waker;
+
+    struct CountingWaker {
+        wakes: AtomicUsize
+    }
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.wakes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let counting_waker = Arc::new(CountingWaker { wakes: AtomicUsize::new(0) });
+    let waker = Waker::from(counting_waker.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let lock = RwLock::new();
+
+    let guard = lock.lock_write();
+    let mut future = pin!(lock.lock_read_async());
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    drop(guard);
+    assert_eq!(counting_waker.wakes.load(Ordering::SeqCst), 1);
+
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(guard) => {
+            assert!(guard.is_locked());
+            let mut future = pin!(lock.lock_write_async());
+            assert!(future.as_mut().poll(&mut context).is_pending());
+        },
+        Poll::Pending => panic!("Lock is not acquired once released")
+    }
+
+    assert!(lock.try_lock_write().is_locked());
+
+}
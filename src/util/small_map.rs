@@ -0,0 +1,220 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::slice;
+
+use super::HashMap;
+
+/// Count of entries kept in the vector of a small map before spilled
+const SMALL_MAP_INLINE_CAPACITY: usize = 8;
+
+/// Map keeping a few entries in a vector scanned linearly, and spilling to a
+/// hash indexed vector once the entries exceed the inline capacity
+///
+/// Most slots have only a few properties, which are looked up faster and
+/// take much less memory in a vector than in a hash map
+///
+/// Entries are always iterated in the order of insertion, replacing the
+/// value of an existing key keeps its position
+pub enum SmallMap<K, V> {
+    Inline(Vec<(K, V)>),
+    Spilled(Box<SpilledMap<K, V>>)
+}
+
+/// Entries of a spilled small map, removed entries are left as holes until
+/// more than half of the entries are removed
+pub struct SpilledMap<K, V> {
+    entries: Vec<Option<(K, V)>>,
+    indices: HashMap<K, usize>
+}
+
+impl<K: Eq + Hash + Clone, V> SpilledMap<K, V> {
+
+    fn compact(&mut self) {
+        self.entries.retain(|entry| entry.is_some());
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Some((key, _)) = entry {
+                self.indices.insert(key.clone(), index);
+            }
+        }
+    }
+
+}
+
+pub enum Iter<'a, K, V> {
+    Inline(slice::Iter<'a, (K, V)>),
+    Spilled(slice::Iter<'a, Option<(K, V)>>)
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Inline(iterator) => iterator.next().map(|(key, value)| (key, value)),
+            Iter::Spilled(iterator) => iterator.flatten().next().map(|(key, value)| (key, value))
+        }
+    }
+
+}
+
+pub struct Keys<'a, K, V> {
+    iterator: Iter<'a, K, V>
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iterator.next().map(|(key, _)| key)
+    }
+
+}
+
+impl<K: Eq + Hash + Clone, V> SmallMap<K, V> {
+
+    pub fn new() -> SmallMap<K, V> {
+        SmallMap::Inline(Vec::new())
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            SmallMap::Inline(entries) => entries.len(),
+            SmallMap::Spilled(map) => map.indices.len()
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match self {
+            SmallMap::Inline(entries) => entries.iter().find(|(entry_key, _)| entry_key == key).map(|(_, value)| value),
+            SmallMap::Spilled(map) => {
+                let index = *map.indices.get(key)?;
+                map.entries[index].as_ref().map(|(_, value)| value)
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+
+        let entries = match self {
+            SmallMap::Inline(entries) => entries,
+            SmallMap::Spilled(map) => {
+                if let Some(index) = map.indices.get(&key) {
+                    if let Some((_, old_value)) = map.entries[*index].as_mut() {
+                        return Some(core::mem::replace(old_value, value));
+                    }
+                }
+                map.indices.insert(key.clone(), map.entries.len());
+                map.entries.push(Some((key, value)));
+                return None;
+            }
+        };
+
+        if let Some((_, old_value)) = entries.iter_mut().find(|(entry_key, _)| *entry_key == key) {
+            return Some(core::mem::replace(old_value, value));
+        }
+
+        if entries.len() < SMALL_MAP_INLINE_CAPACITY {
+            entries.push((key, value));
+            return None;
+        }
+
+        entries.push((key, value));
+        let mut map = SpilledMap {
+            entries: Vec::with_capacity(entries.len()),
+            indices: HashMap::new()
+        };
+        for (index, (key, value)) in entries.drain(..).enumerate() {
+            map.indices.insert(key.clone(), index);
+            map.entries.push(Some((key, value)));
+        }
+        *self = SmallMap::Spilled(Box::new(map));
+
+        None
+
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        match self {
+            SmallMap::Inline(entries) => {
+                let index = entries.iter().position(|(entry_key, _)| entry_key == key)?;
+                Some(entries.remove(index).1)
+            },
+            SmallMap::Spilled(map) => {
+                let index = map.indices.remove(key)?;
+                let (_, value) = map.entries[index].take()?;
+                if map.indices.len() * 2 < map.entries.len() {
+                    map.compact();
+                }
+                Some(value)
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        match self {
+            SmallMap::Inline(entries) => Iter::Inline(entries.iter()),
+            SmallMap::Spilled(map) => Iter::Spilled(map.entries.iter())
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iterator: self.iter()
+        }
+    }
+
+}
+
+#[test]
+fn test_small_map() {
+
+    let mut map = SmallMap::new();
+    for index in 0 .. SMALL_MAP_INLINE_CAPACITY {
+        assert_eq!(map.insert(index, index * 2), None);
+    }
+    assert!(matches!(map, SmallMap::Inline(_)));
+    assert_eq!(map.insert(3, 7), Some(6));
+    assert_eq!(map.get(&3), Some(&7));
+    assert_eq!(map.remove(&3), Some(7));
+    assert_eq!(map.remove(&3), None);
+    assert_eq!(map.len(), SMALL_MAP_INLINE_CAPACITY - 1);
+
+    map.insert(3, 6);
+    map.insert(SMALL_MAP_INLINE_CAPACITY, 0);
+    assert!(matches!(map, SmallMap::Spilled(_)));
+    assert_eq!(map.len(), SMALL_MAP_INLINE_CAPACITY + 1);
+    assert_eq!(map.get(&3), Some(&6));
+    assert_eq!(map.keys().count(), SMALL_MAP_INLINE_CAPACITY + 1);
+    assert_eq!(map.iter().map(|(_, value)| *value).sum::<usize>(), (0 .. SMALL_MAP_INLINE_CAPACITY).map(|index| index * 2).sum());
+
+}
+
+#[test]
+fn test_small_map_order() {
+
+    let count = SMALL_MAP_INLINE_CAPACITY * 4;
+
+    let mut map = SmallMap::new();
+    for index in (0 .. count).rev() {
+        map.insert(index, index);
+    }
+    assert!(matches!(map, SmallMap::Spilled(_)));
+
+    map.insert(1, 0);
+    for index in 2 .. count - 2 {
+        map.remove(&index);
+    }
+
+    assert_eq!(map.keys().cloned().collect::<Vec<usize>>(), vec!(count - 1, count - 2, 1, 0));
+    assert_eq!(map.get(&1), Some(&0));
+    assert_eq!(map.len(), 4);
+
+    map.insert(2, 2);
+    assert_eq!(map.iter().map(|(key, _)| *key).last(), Some(2));
+
+}
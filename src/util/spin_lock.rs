@@ -1,9 +1,17 @@
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering;
+use super::sync::AtomicU32;
+use super::sync::Ordering;
+use super::sync::Backoff;
+use super::sync::wake;
+#[cfg(all(feature = "async", not(loom)))] use super::sync::poll_lock;
+#[cfg(all(feature = "async", not(loom)))] use core::future::Future;
+#[cfg(all(feature = "async", not(loom)))] use core::future::poll_fn;
+use super::lock_diagnostics::LockClass;
+#[cfg(feature = "lock-stats")] use super::lock_stats;
 
 pub struct SpinLock {
     flag: AtomicU32,
-    next: AtomicU32
+    next: AtomicU32,
+    #[cfg(feature = "lock-stats")] class: LockClass
 }
 
 pub struct SpinLockGuard<'a> {
@@ -33,23 +41,50 @@ impl SpinLock {
 
     #[inline]
     pub fn new() -> SpinLock {
+        SpinLock::with_class(LockClass::Unclassified)
+    }
+
+    /// Create a lock counted as the class by lock stats, the class is
+    /// ignored without the `lock-stats` feature
+    #[inline]
+    pub fn with_class(_class: LockClass) -> SpinLock {
         SpinLock {
             flag: AtomicU32::new(0),
-            next: AtomicU32::new(1)
+            next: AtomicU32::new(1),
+            #[cfg(feature = "lock-stats")] class: _class
         }
     }
 
+    /// Get the key threads waiting for the lock are parked on
+    #[inline]
+    fn get_key(&self) -> usize {
+        self as *const SpinLock as usize
+    }
+
+    #[cfg(feature = "lock-stats")]
+    fn record_wait(&self, backoff: &Backoff) {
+        lock_stats::record_acquisition(self.class, backoff.get_waited());
+    }
+
+    #[cfg(not(feature = "lock-stats"))]
+    #[inline]
+    fn record_wait(&self, _backoff: &Backoff) {}
+
     #[inline]
     pub fn lock(&self) -> SpinLockGuard {
 
         let flag = self.next.fetch_add(1, Ordering::SeqCst);
 
+        let mut backoff = Backoff::new();
         loop {
             if let Ok(_) = self.flag.compare_exchange(0, flag, Ordering::SeqCst, Ordering::SeqCst) {
                 break;
             }
+            backoff.snooze(self.get_key(), || self.flag.load(Ordering::SeqCst) == 0);
         }
 
+        self.record_wait(&backoff);
+
         SpinLockGuard {
             lock: self,
             flag: flag
@@ -82,11 +117,28 @@ impl SpinLock {
         if let Err(_) = self.flag.compare_exchange(guard.flag, 0, Ordering::SeqCst, Ordering::SeqCst) {
             panic!("Invalid spin lock guard key to unlock");
         }
+        wake(self.get_key());
 
     }
 
 } 
 
+/// Lock acquisitions for async tasks, tasks waiting for the lock are woken
+/// once it is released instead of blocking executor threads
+#[cfg(all(feature = "async", not(loom)))]
+impl SpinLock {
+
+    pub fn lock_async(&self) -> impl Future<Output = SpinLockGuard<'_>> + '_ {
+        poll_fn(move |context| {
+            poll_lock(self.get_key(), context, || {
+                let guard = self.try_lock();
+                if guard.is_locked() { Some(guard) } else { None }
+            })
+        })
+    }
+
+}
+
 #[test]
 fn test_lock() {
 
@@ -102,4 +154,29 @@ fn test_lock() {
         assert!(lock.try_lock().is_locked());
     }
 
-}
\ No newline at end of file
+}
+#[cfg(all(feature = "parking", not(loom)))]
+#[test]
+fn test_lock_parking() {
+
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    let lock = Arc::new(SpinLock::new());
+
+    let guard = lock.lock();
+
+    let lock_2 = lock.clone();
+    let handle = thread::spawn(move || {
+        let guard = lock_2.lock();
+        guard.is_locked()
+    });
+
+    thread::sleep(Duration::from_millis(20));
+    drop(guard);
+
+    assert!(handle.join().unwrap());
+    assert!(lock.try_lock().is_locked());
+
+}
@@ -0,0 +1,271 @@
+//! Atomics used by the hand-rolled locks, swapped for `loom` types under
+//! `cfg(loom)` so interleavings of the locks could be explored by models
+//!
+//! Waiting threads spin with exponential backoff, and with the `parking`
+//! feature they are parked by the OS once the spinning is exhausted
+
+#[cfg(not(loom))] pub use core::sync::atomic::AtomicU32;
+#[cfg(not(loom))] pub use core::sync::atomic::Ordering;
+
+#[cfg(loom)] pub use loom::sync::atomic::AtomicU32;
+#[cfg(loom)] pub use loom::sync::atomic::Ordering;
+
+#[cfg(all(feature = "async", not(loom)))] use core::task::Context;
+#[cfg(all(feature = "async", not(loom)))] use core::task::Poll;
+
+/// Hint a spinning wait, loom models are told about the spinning so
+/// the spinning thread is deprioritized
+#[cfg(not(loom))]
+#[inline]
+pub fn spin_loop() {
+    core::hint::spin_loop();
+}
+
+#[cfg(loom)]
+#[inline]
+pub fn spin_loop() {
+    loom::hint::spin_loop();
+}
+
+/// Rounds of spinning before a waiting thread parks, each round spins twice
+/// as long as the previous one
+#[cfg(not(loom))] const SPIN_ROUNDS: u32 = 7;
+
+/// Loom models spin once for each retry, or branches of the models explode
+#[cfg(loom)] const SPIN_ROUNDS: u32 = 0;
+
+/// Backoff of a thread waiting for a lock, shared by all waits of an
+/// acquisition
+pub struct Backoff {
+    round: u32,
+    #[cfg(feature = "lock-stats")] waiting_since: Option<std::time::Instant>
+}
+
+impl Backoff {
+
+    #[inline]
+    pub fn new() -> Backoff {
+        Backoff {
+            round: 0,
+            #[cfg(feature = "lock-stats")] waiting_since: None
+        }
+    }
+
+    /// Get time waited since the first snooze, none if the acquisition is
+    /// not contended
+    #[cfg(feature = "lock-stats")]
+    pub fn get_waited(&self) -> Option<std::time::Duration> {
+        self.waiting_since.map(|waiting_since| waiting_since.elapsed())
+    }
+
+    /// Wait before the lock is retried, `ready` tells whether the retrying
+    /// could succeed, and is checked again before the thread parks so
+    /// wakes from `wake` are not lost
+    #[inline]
+    pub fn snooze<F>(&mut self, lock: usize, ready: F) where F: Fn() -> bool {
+
+        #[cfg(feature = "lock-stats")] {
+            if self.waiting_since.is_none() {
+                self.waiting_since = Some(std::time::Instant::now());
+            }
+        }
+
+        if self.round < SPIN_ROUNDS {
+            for _ in 0..(1 << self.round) {
+                spin_loop();
+            }
+            self.round += 1;
+            return;
+        }
+
+        #[cfg(all(feature = "parking", not(loom)))] parking::park(lock, ready);
+
+        #[cfg(not(all(feature = "parking", not(loom))))] {
+            let _ = (lock, ready);
+            spin_loop();
+        }
+
+    }
+
+}
+
+/// Wake threads parked on the lock, should be called once the lock is
+/// released or its readers are all gone
+#[inline]
+pub fn wake(lock: usize) {
+    #[cfg(all(feature = "parking", not(loom)))] parking::wake(lock);
+    #[cfg(all(feature = "async", not(loom)))] wakers::wake(lock);
+    #[cfg(not(all(any(feature = "parking", feature = "async"), not(loom))))] let _ = lock;
+}
+
+/// Poll an acquisition of the lock for async tasks, the waker of the task
+/// is registered before the lock is tried again, so the task is woken once
+/// the lock is released instead of blocking the executor thread
+#[cfg(all(feature = "async", not(loom)))]
+pub fn poll_lock<G, F>(lock: usize, context: &mut Context<'_>, try_lock: F) -> Poll<G> where F: Fn() -> Option<G> {
+
+    if let Some(guard) = try_lock() {
+        return Poll::Ready(guard);
+    }
+
+    wakers::register(lock, context.waker());
+
+    match try_lock() {
+        Some(guard) => Poll::Ready(guard),
+        None => Poll::Pending
+    }
+
+}
+
+#[cfg(all(feature = "parking", not(loom)))]
+mod parking {
+
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::thread::Thread;
+    use std::thread::ThreadId;
+
+    const BUCKET_COUNT: usize = 64;
+
+    /// Threads parked on locks hashed into the bucket, in the order they
+    /// parked
+    struct Bucket {
+        parked: AtomicUsize,
+        waiters: Mutex<VecDeque<(usize, ThreadId, Thread)>>
+    }
+
+    static BUCKETS: [Bucket; BUCKET_COUNT] = [const { Bucket {
+        parked: AtomicUsize::new(0),
+        waiters: Mutex::new(VecDeque::new())
+    } }; BUCKET_COUNT];
+
+    #[inline]
+    fn get_bucket(lock: usize) -> &'static Bucket {
+        &BUCKETS[(lock >> 4) % BUCKET_COUNT]
+    }
+
+    /// Park the current thread until the lock is woken, unless it is ready
+    /// once the thread is queued
+    pub fn park<F>(lock: usize, ready: F) where F: Fn() -> bool {
+
+        let bucket = get_bucket(lock);
+        let thread = thread::current();
+        let id = thread.id();
+
+        {
+            let mut waiters = bucket.waiters.lock().unwrap_or_else(|error| error.into_inner());
+            waiters.push_back((lock, id, thread));
+            bucket.parked.fetch_add(1, Ordering::SeqCst);
+        }
+
+        if !ready() {
+            thread::park();
+        }
+
+        let mut waiters = bucket.waiters.lock().unwrap_or_else(|error| error.into_inner());
+        if let Some(index) = waiters.iter().position(|(_, waiter_id, _)| *waiter_id == id) {
+            waiters.remove(index);
+            bucket.parked.fetch_sub(1, Ordering::SeqCst);
+        }
+
+    }
+
+    /// Wake all threads parked on the lock, in the order they parked so the
+    /// earliest parked thread gets the first chance to retry
+    pub fn wake(lock: usize) {
+
+        let bucket = get_bucket(lock);
+        if bucket.parked.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let mut waiters = bucket.waiters.lock().unwrap_or_else(|error| error.into_inner());
+        let count = waiters.len();
+        waiters.retain(|(waiter_lock, _, thread)| {
+            if *waiter_lock == lock {
+                thread.unpark();
+                false
+            } else {
+                true
+            }
+        });
+        bucket.parked.fetch_sub(count - waiters.len(), Ordering::SeqCst);
+
+    }
+
+}
+
+#[cfg(all(feature = "async", not(loom)))]
+mod wakers {
+
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::task::Waker;
+    use std::vec::Vec;
+
+    const BUCKET_COUNT: usize = 64;
+
+    /// Wakers of tasks waiting for locks hashed into the bucket
+    struct Bucket {
+        registered: AtomicUsize,
+        wakers: Mutex<Vec<(usize, Waker)>>
+    }
+
+    static BUCKETS: [Bucket; BUCKET_COUNT] = [const { Bucket {
+        registered: AtomicUsize::new(0),
+        wakers: Mutex::new(Vec::new())
+    } }; BUCKET_COUNT];
+
+    #[inline]
+    fn get_bucket(lock: usize) -> &'static Bucket {
+        &BUCKETS[(lock >> 4) % BUCKET_COUNT]
+    }
+
+    /// Register the waker of a task waiting for the lock, a task polled
+    /// again is registered once
+    pub fn register(lock: usize, waker: &Waker) {
+
+        let bucket = get_bucket(lock);
+
+        let mut wakers = bucket.wakers.lock().unwrap_or_else(|error| error.into_inner());
+        if wakers.iter().any(|(waker_lock, registered)| (*waker_lock == lock) && registered.will_wake(waker)) {
+            return;
+        }
+        wakers.push((lock, waker.clone()));
+        bucket.registered.fetch_add(1, Ordering::SeqCst);
+
+    }
+
+    /// Wake all tasks waiting for the lock
+    pub fn wake(lock: usize) {
+
+        let bucket = get_bucket(lock);
+        if bucket.registered.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let mut woken = Vec::new();
+        {
+            let mut wakers = bucket.wakers.lock().unwrap_or_else(|error| error.into_inner());
+            let mut index = 0;
+            while index < wakers.len() {
+                if wakers[index].0 == lock {
+                    woken.push(wakers.swap_remove(index).1);
+                } else {
+                    index += 1;
+                }
+            }
+            bucket.registered.fetch_sub(woken.len(), Ordering::SeqCst);
+        }
+
+        for waker in woken {
+            waker.wake();
+        }
+
+    }
+
+}
@@ -0,0 +1,124 @@
+use core::cell::Cell;
+use core::cell::RefCell;
+use alloc::sync::Arc;
+
+use super::base::Error;
+use super::base::ErrorType::*;
+
+/// Listener notified when the overall occupancy of local regions in an
+/// isolate crosses its watermarks
+///
+/// Listeners are notified during slot allocations and sweeps, so they should
+/// schedule collections instead of collecting synchronously
+pub trait RegionWatermarkListener {
+
+    /// Notified once the occupancy rises to the high watermark
+    fn notify_high_watermark_reached(&self, _occupancy: f32) {}
+
+    /// Notified once the occupancy falls to the low watermark after the high
+    /// watermark reached
+    fn notify_low_watermark_reached(&self, _occupancy: f32) {}
+
+}
+
+/// Occupancy of regions tracked with high and low watermarks
+pub struct RegionWatermarks {
+    occupied: Cell<usize>,
+    capacity: Cell<usize>,
+    low: Cell<f32>,
+    high: Cell<f32>,
+    high_reached: Cell<bool>,
+    listener: RefCell<Option<Arc<dyn RegionWatermarkListener>>>
+}
+
+impl RegionWatermarks {
+
+    pub fn new() -> RegionWatermarks {
+        RegionWatermarks {
+            occupied: Cell::new(0),
+            capacity: Cell::new(0),
+            low: Cell::new(0.6),
+            high: Cell::new(0.8),
+            high_reached: Cell::new(false),
+            listener: RefCell::new(None)
+        }
+    }
+
+    /// Duplicate the occupancy and watermarks, the listener is not carried
+    pub fn duplicate(&self) -> RegionWatermarks {
+        RegionWatermarks {
+            occupied: Cell::new(self.occupied.get()),
+            capacity: Cell::new(self.capacity.get()),
+            low: Cell::new(self.low.get()),
+            high: Cell::new(self.high.get()),
+            high_reached: Cell::new(self.high_reached.get()),
+            listener: RefCell::new(None)
+        }
+    }
+
+    pub fn get_occupancy(&self) -> f32 {
+        let capacity = self.capacity.get();
+        if capacity == 0 {
+            0.0
+        } else {
+            self.occupied.get() as f32 / capacity as f32
+        }
+    }
+
+    pub fn get_low_watermark(&self) -> f32 {
+        self.low.get()
+    }
+
+    pub fn get_high_watermark(&self) -> f32 {
+        self.high.get()
+    }
+
+    pub fn set_watermarks(&self, low: f32, high: f32) -> Result<(), Error> {
+
+        if !(0.0..=1.0).contains(&low) || !(0.0..=1.0).contains(&high) || low > high {
+            return Err(Error::new(FatalError, "Invalid watermarks"));
+        }
+
+        self.low.set(low);
+        self.high.set(high);
+
+        Ok(())
+
+    }
+
+    pub fn set_listener(&self, listener: Option<Arc<dyn RegionWatermarkListener>>) {
+        self.listener.replace(listener);
+    }
+
+    /// Apply changes of occupied slots and capacity, the listener will be
+    /// notified if a watermark is crossed
+    pub fn update(&self, occupied_delta: isize, capacity_delta: isize) {
+
+        self.occupied.set((self.occupied.get() as isize + occupied_delta).max(0) as usize);
+        self.capacity.set((self.capacity.get() as isize + capacity_delta).max(0) as usize);
+
+        let occupancy = self.get_occupancy();
+
+        let high_reached = if self.high_reached.get() {
+            occupancy > self.low.get()
+        } else {
+            occupancy >= self.high.get()
+        };
+        if high_reached == self.high_reached.get() {
+            return;
+        }
+
+        self.high_reached.set(high_reached);
+
+        let listener = self.listener.borrow().clone();
+        if let Some(listener) = listener {
+            if high_reached {
+                listener.notify_high_watermark_reached(occupancy);
+            } else {
+                listener.notify_low_watermark_reached(occupancy);
+            }
+        }
+
+    }
+
+}